@@ -5,7 +5,9 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::Key;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
 use meilisearch_types::settings::Unchecked;
-use meilisearch_types::tasks::{Details, IndexSwap, KindWithContent, Status, Task, TaskId};
+use meilisearch_types::tasks::{
+    Details, ExportFormat, IndexSwap, KindWithContent, Status, Task, TaskId,
+};
 use meilisearch_types::InstanceUid;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
@@ -96,6 +98,8 @@ pub enum KindDump {
         method: IndexDocumentsMethod,
         documents_count: u64,
         allow_index_creation: bool,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        skip_invalid_documents: bool,
     },
     DocumentDeletion {
         documents_ids: Vec<String>,
@@ -119,6 +123,15 @@ pub enum KindDump {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexCopy {
+        destination: String,
+    },
+    IndexExport {
+        format: ExportFormat,
+        filter: Option<serde_json::Value>,
+        fields: Option<Vec<String>>,
+        url: Option<String>,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -159,12 +172,14 @@ impl From<KindWithContent> for KindDump {
                 method,
                 documents_count,
                 allow_index_creation,
+                skip_invalid_documents,
                 ..
             } => KindDump::DocumentImport {
                 primary_key,
                 method,
                 documents_count,
                 allow_index_creation,
+                skip_invalid_documents,
             },
             KindWithContent::DocumentDeletion { documents_ids, .. } => {
                 KindDump::DocumentDeletion { documents_ids }
@@ -187,6 +202,10 @@ impl From<KindWithContent> for KindDump {
                 KindDump::IndexUpdate { primary_key }
             }
             KindWithContent::IndexSwap { swaps } => KindDump::IndexSwap { swaps },
+            KindWithContent::IndexCopy { destination, .. } => KindDump::IndexCopy { destination },
+            KindWithContent::IndexExport { format, filter, fields, url, .. } => {
+                KindDump::IndexExport { format, filter, fields, url }
+            }
             KindWithContent::TaskCancelation { query, tasks } => {
                 KindDump::TaskCancelation { query, tasks }
             }
@@ -260,6 +279,13 @@ pub(crate) mod test {
             searchable_attributes: Setting::Set(vec![S("name"), S("race")]),
             filterable_attributes: Setting::Set(btreeset! { S("race"), S("age") }),
             sortable_attributes: Setting::Set(btreeset! { S("age") }),
+            non_flattened_attributes: Setting::NotSet,
+            merge_strategies: Setting::NotSet,
+            type_coercions: Setting::NotSet,
+            ingestion_mapping: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            document_schema: Setting::NotSet,
+            suggestable_attributes: Setting::NotSet,
             ranking_rules: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
@@ -267,6 +293,14 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            static_boost: Setting::NotSet,
+            foreign_keys: Setting::NotSet,
+            query_rules: Setting::NotSet,
+            reranker: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            stemming: Setting::NotSet,
+            romanization: Setting::NotSet,
+            split_identifiers: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::Set(FacetingSettings {
@@ -274,6 +308,7 @@ pub(crate) mod test {
                 sort_facet_values_by: Setting::Set(
                     btreemap! { S("age") => FacetValuesSort::Count },
                 ),
+                hierarchy_separators: Setting::NotSet,
             }),
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
@@ -294,11 +329,15 @@ pub(crate) mod test {
                         allow_index_creation: true,
                         primary_key: Some(S("bone")),
                         documents_count: 12,
+                        skip_invalid_documents: false,
                     },
                     canceled_by: None,
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        embedding_failures: None,
+                        invalid_documents: None,
+                        outdated_documents: None,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
@@ -317,11 +356,15 @@ pub(crate) mod test {
                         allow_index_creation: true,
                         primary_key: None,
                         documents_count: 2,
+                        skip_invalid_documents: false,
                     },
                     canceled_by: None,
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        embedding_failures: None,
+                        invalid_documents: None,
+                        outdated_documents: None,
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
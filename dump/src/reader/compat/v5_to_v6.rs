@@ -103,6 +103,7 @@ impl CompatV5ToV6 {
                                 }
                             },
                             allow_index_creation,
+                            skip_invalid_documents: false,
                         },
                         v5::tasks::TaskContent::DocumentDeletion { deletion, .. } => match deletion
                         {
@@ -132,6 +133,9 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                embedding_failures: None,
+                                invalid_documents: None,
+                                outdated_documents: None,
                             }
                         }
                         v5::Details::Settings { settings } => {
@@ -319,6 +323,13 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             searchable_attributes: settings.searchable_attributes.into(),
             filterable_attributes: settings.filterable_attributes.into(),
             sortable_attributes: settings.sortable_attributes.into(),
+            non_flattened_attributes: v6::Setting::NotSet,
+            merge_strategies: v6::Setting::NotSet,
+            type_coercions: v6::Setting::NotSet,
+            ingestion_mapping: v6::Setting::NotSet,
+            date_attributes: v6::Setting::NotSet,
+            document_schema: v6::Setting::NotSet,
+            suggestable_attributes: v6::Setting::NotSet,
             ranking_rules: {
                 match settings.ranking_rules {
                     v5::settings::Setting::Set(ranking_rules) => {
@@ -345,6 +356,14 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             dictionary: v6::Setting::NotSet,
             synonyms: settings.synonyms.into(),
             distinct_attribute: settings.distinct_attribute.into(),
+            static_boost: v6::Setting::NotSet,
+            foreign_keys: v6::Setting::NotSet,
+            query_rules: v6::Setting::NotSet,
+            reranker: v6::Setting::NotSet,
+            localized_attributes: v6::Setting::NotSet,
+            stemming: v6::Setting::NotSet,
+            romanization: v6::Setting::NotSet,
+            split_identifiers: v6::Setting::NotSet,
             proximity_precision: v6::Setting::NotSet,
             typo_tolerance: match settings.typo_tolerance {
                 v5::Setting::Set(typo) => v6::Setting::Set(v6::TypoTolerance {
@@ -359,6 +378,8 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
                     },
                     disable_on_words: typo.disable_on_words.into(),
                     disable_on_attributes: typo.disable_on_attributes.into(),
+                    disable_on_first_character: v6::Setting::NotSet,
+                    enable_on_numbers: v6::Setting::NotSet,
                 }),
                 v5::Setting::Reset => v6::Setting::Reset,
                 v5::Setting::NotSet => v6::Setting::NotSet,
@@ -367,6 +388,7 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
                 v5::Setting::Set(faceting) => v6::Setting::Set(v6::FacetingSettings {
                     max_values_per_facet: faceting.max_values_per_facet.into(),
                     sort_facet_values_by: v6::Setting::NotSet,
+                    hierarchy_separators: v6::Setting::NotSet,
                 }),
                 v5::Setting::Reset => v6::Setting::Reset,
                 v5::Setting::NotSet => v6::Setting::NotSet,
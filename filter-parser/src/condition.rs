@@ -1,8 +1,10 @@
 //! BNF grammar:
 //!
 //! ```text
-//! condition      = value ("==" | ">" ...) value
-//! to             = value value TO value
+//! condition      = field ("==" | ">" ...) value
+//! contains       = field "CONTAINS" WS+ value
+//! starts_with    = field "STARTS" WS+ "WITH" WS+ value
+//! to             = field value TO value
 //! ```
 
 use nom::branch::alt;
@@ -12,7 +14,7 @@ use nom::combinator::cut;
 use nom::sequence::{terminated, tuple};
 use Condition::*;
 
-use crate::{parse_value, FilterCondition, IResult, Span, Token};
+use crate::{parse_field, parse_value, FilterCondition, IResult, Span, Token};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition<'a> {
@@ -26,12 +28,14 @@ pub enum Condition<'a> {
     LowerThan(Token<'a>),
     LowerThanOrEqual(Token<'a>),
     Between { from: Token<'a>, to: Token<'a> },
+    Contains(Token<'a>),
+    StartsWith(Token<'a>),
 }
 
-/// condition      = value ("==" | ">" ...) value
+/// condition      = field ("==" | ">" ...) value
 pub fn parse_condition(input: Span) -> IResult<FilterCondition> {
     let operator = alt((tag("<="), tag(">="), tag("!="), tag("<"), tag(">"), tag("=")));
-    let (input, (fid, op, value)) = tuple((parse_value, operator, cut(parse_value)))(input)?;
+    let (input, (fid, op, value)) = tuple((parse_field, operator, cut(parse_value)))(input)?;
 
     let condition = match *op.fragment() {
         "<=" => FilterCondition::Condition { fid, op: LowerThanOrEqual(value) },
@@ -46,56 +50,110 @@ pub fn parse_condition(input: Span) -> IResult<FilterCondition> {
     Ok((input, condition))
 }
 
-/// null          = value "IS" WS+ "NULL"
+/// null          = field "IS" WS+ "NULL"
 pub fn parse_is_null(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = parse_value(input)?;
+    let (input, key) = parse_field(input)?;
 
     let (input, _) = tuple((tag("IS"), multispace1, tag("NULL")))(input)?;
     Ok((input, FilterCondition::Condition { fid: key, op: Null }))
 }
 
-/// null          = value "IS" WS+ "NOT" WS+ "NULL"
+/// null          = field "IS" WS+ "NOT" WS+ "NULL"
 pub fn parse_is_not_null(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = parse_value(input)?;
+    let (input, key) = parse_field(input)?;
 
     let (input, _) = tuple((tag("IS"), multispace1, tag("NOT"), multispace1, tag("NULL")))(input)?;
     Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid: key, op: Null }))))
 }
 
-/// empty          = value "IS" WS+ "EMPTY"
+/// empty          = field "IS" WS+ "EMPTY"
 pub fn parse_is_empty(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = parse_value(input)?;
+    let (input, key) = parse_field(input)?;
 
     let (input, _) = tuple((tag("IS"), multispace1, tag("EMPTY")))(input)?;
     Ok((input, FilterCondition::Condition { fid: key, op: Empty }))
 }
 
-/// empty          = value "IS" WS+ "NOT" WS+ "EMPTY"
+/// empty          = field "IS" WS+ "NOT" WS+ "EMPTY"
 pub fn parse_is_not_empty(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = parse_value(input)?;
+    let (input, key) = parse_field(input)?;
 
     let (input, _) = tuple((tag("IS"), multispace1, tag("NOT"), multispace1, tag("EMPTY")))(input)?;
     Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid: key, op: Empty }))))
 }
 
-/// exist          = value "EXISTS"
+/// exist          = field "EXISTS"
 pub fn parse_exists(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = terminated(parse_value, tag("EXISTS"))(input)?;
+    let (input, key) = terminated(parse_field, tag("EXISTS"))(input)?;
 
     Ok((input, FilterCondition::Condition { fid: key, op: Exists }))
 }
-/// exist          = value "NOT" WS+ "EXISTS"
+/// exist          = field "NOT" WS+ "EXISTS"
 pub fn parse_not_exists(input: Span) -> IResult<FilterCondition> {
-    let (input, key) = parse_value(input)?;
+    let (input, key) = parse_field(input)?;
 
     let (input, _) = tuple((tag("NOT"), multispace1, tag("EXISTS")))(input)?;
     Ok((input, FilterCondition::Not(Box::new(FilterCondition::Condition { fid: key, op: Exists }))))
 }
 
-/// to             = value value "TO" WS+ value
+/// contains       = field "CONTAINS" WS+ value
+pub fn parse_contains(input: Span) -> IResult<FilterCondition> {
+    let (input, (key, _, _, value)) =
+        tuple((parse_field, tag("CONTAINS"), multispace1, cut(parse_value)))(input)?;
+
+    Ok((input, FilterCondition::Condition { fid: key, op: Contains(value) }))
+}
+
+/// not_contains   = field "NOT" WS+ "CONTAINS" WS+ value
+pub fn parse_not_contains(input: Span) -> IResult<FilterCondition> {
+    let (input, (key, _, _, _, _, value)) = tuple((
+        parse_field,
+        tag("NOT"),
+        multispace1,
+        tag("CONTAINS"),
+        multispace1,
+        cut(parse_value),
+    ))(input)?;
+
+    let condition = FilterCondition::Condition { fid: key, op: Contains(value) };
+    Ok((input, FilterCondition::Not(Box::new(condition))))
+}
+
+/// starts_with    = field "STARTS" WS+ "WITH" WS+ value
+pub fn parse_starts_with(input: Span) -> IResult<FilterCondition> {
+    let (input, (key, _, _, _, _, value)) = tuple((
+        parse_field,
+        tag("STARTS"),
+        multispace1,
+        tag("WITH"),
+        multispace1,
+        cut(parse_value),
+    ))(input)?;
+
+    Ok((input, FilterCondition::Condition { fid: key, op: StartsWith(value) }))
+}
+
+/// not_starts_with = field "NOT" WS+ "STARTS" WS+ "WITH" WS+ value
+pub fn parse_not_starts_with(input: Span) -> IResult<FilterCondition> {
+    let (input, (key, _, _, _, _, _, _, value)) = tuple((
+        parse_field,
+        tag("NOT"),
+        multispace1,
+        tag("STARTS"),
+        multispace1,
+        tag("WITH"),
+        multispace1,
+        cut(parse_value),
+    ))(input)?;
+
+    let condition = FilterCondition::Condition { fid: key, op: StartsWith(value) };
+    Ok((input, FilterCondition::Not(Box::new(condition))))
+}
+
+/// to             = field value "TO" WS+ value
 pub fn parse_to(input: Span) -> IResult<FilterCondition> {
     let (input, (key, from, _, _, to)) =
-        tuple((parse_value, parse_value, tag("TO"), multispace1, cut(parse_value)))(input)?;
+        tuple((parse_field, parse_value, tag("TO"), multispace1, cut(parse_value)))(input)?;
 
     Ok((input, FilterCondition::Condition { fid: key, op: Between { from, to } }))
 }
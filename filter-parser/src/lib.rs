@@ -6,12 +6,17 @@
 //! or             = and ("OR" WS+ and)*
 //! and            = not ("AND" WS+ not)*
 //! not            = ("NOT" WS+ not) | primary
-//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | in | condition | exists | not_exists | to
-//! in             = value "IN" WS* "[" value_list "]"
-//! condition      = value ("=" | "!=" | ">" | ">=" | "<" | "<=") value
-//! exists         = value "EXISTS"
-//! not_exists     = value "NOT" WS+ "EXISTS"
-//! to             = value value "TO" WS+ value
+//! primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | geoWithin | geoIntersects | nested | in | condition | exists | not_exists | contains | starts_with | to
+//! nested         = value "." "{" WS* expression WS* "}" WS*
+//! in             = field "IN" WS* "[" value_list "]"
+//! condition      = field ("=" | "!=" | ">" | ">=" | "<" | "<=") value
+//! exists         = field "EXISTS"
+//! not_exists     = field "NOT" WS+ "EXISTS"
+//! contains       = field "CONTAINS" WS+ value
+//! starts_with    = field "STARTS" WS+ "WITH" WS+ value
+//! to             = field value "TO" WS+ value
+//! field          = value level?
+//! level          = "LEVEL" WS+ digit+ WS*
 //! value          = WS* ( word | singleQuoted | doubleQuoted) WS+
 //! value_list     = (value ("," value)* ","?)?
 //! singleQuoted   = "'" .* all but quotes "'"
@@ -19,6 +24,11 @@
 //! word           = (alphanumeric | _ | - | .)+
 //! geoRadius      = "_geoRadius(" WS* float WS* "," WS* float WS* "," float WS* ")"
 //! geoBoundingBox = "_geoBoundingBox([" WS * float WS* "," WS* float WS* "], [" WS* float WS* "," WS* float WS* "]")
+//! geoPolygon     = "_geoPolygon(" polygon ("," polygon)* ")"
+//! geoWithin      = "_geoWithin(" polygon ("," polygon)* ")"
+//! geoIntersects  = "_geoIntersects(" polygon ("," polygon)* ")"
+//! polygon        = WS* "[" geoPair ("," geoPair)* "]" WS*
+//! geoPair        = WS* "[" WS* float WS* "," WS* float WS* "]" WS*
 //! ```
 //!
 //! Other BNF grammar used to handle some specific errors:
@@ -48,8 +58,8 @@ use std::fmt::Debug;
 
 pub use condition::{parse_condition, parse_to, Condition};
 use condition::{
-    parse_exists, parse_is_empty, parse_is_not_empty, parse_is_not_null, parse_is_null,
-    parse_not_exists,
+    parse_contains, parse_exists, parse_is_empty, parse_is_not_empty, parse_is_not_null,
+    parse_is_null, parse_not_contains, parse_not_exists, parse_not_starts_with, parse_starts_with,
 };
 use error::{cut_with_err, ExpectedValueKind, NomErrorExt};
 pub use error::{Error, ErrorKind};
@@ -60,10 +70,10 @@ use nom::combinator::{cut, eof, map, opt};
 use nom::multi::{many0, separated_list1};
 use nom::number::complete::recognize_float;
 use nom::sequence::{delimited, preceded, terminated, tuple};
-use nom::Finish;
+use nom::{Finish, Slice};
 use nom_locate::LocatedSpan;
-pub(crate) use value::parse_value;
 use value::word_exact;
+pub(crate) use value::{parse_field, parse_value};
 
 pub type Span<'a> = LocatedSpan<&'a str, &'a str>;
 
@@ -145,6 +155,11 @@ pub enum FilterCondition<'a> {
     And(Vec<Self>),
     GeoLowerThan { point: [Token<'a>; 2], radius: Token<'a> },
     GeoBoundingBox { top_right_point: [Token<'a>; 2], bottom_left_point: [Token<'a>; 2] },
+    GeoPolygon { polygons: Vec<Vec<[Token<'a>; 2]>> },
+    GeoWithin { polygons: Vec<Vec<[Token<'a>; 2]>> },
+    GeoIntersects { polygons: Vec<Vec<[Token<'a>; 2]>> },
+    // the filter must match one of the objects of `fid`, its fields being relative to this object
+    Nested { fid: Token<'a>, filter: Box<Self> },
 }
 
 impl<'a> FilterCondition<'a> {
@@ -238,18 +253,18 @@ fn parse_in_body(input: Span) -> IResult<Vec<Token>> {
     Ok((input, content))
 }
 
-/// in = value "IN" "[" value_list "]"
+/// in = field "IN" "[" value_list "]"
 fn parse_in(input: Span) -> IResult<FilterCondition> {
-    let (input, value) = parse_value(input)?;
+    let (input, value) = parse_field(input)?;
     let (input, content) = parse_in_body(input)?;
 
     let filter = FilterCondition::In { fid: value, els: content };
     Ok((input, filter))
 }
 
-/// in = value "NOT" WS* "IN" "[" value_list "]"
+/// in = field "NOT" WS* "IN" "[" value_list "]"
 fn parse_not_in(input: Span) -> IResult<FilterCondition> {
-    let (input, value) = parse_value(input)?;
+    let (input, value) = parse_field(input)?;
     let (input, _) = word_exact("NOT")(input)?;
     let (input, content) = parse_in_body(input)?;
 
@@ -316,6 +331,30 @@ fn parse_not(input: Span, depth: usize) -> IResult<FilterCondition> {
     ))(input)
 }
 
+/// nested         = value "." "{" WS* expression WS* "}" WS*
+/// If we parse `field.{` we MUST parse the rest of the expression.
+fn parse_nested(input: Span, depth: usize) -> IResult<FilterCondition> {
+    let (rest, (fid, dot)) = terminated(tuple((parse_value, opt(char('.')))), char('{'))(input)?;
+    // the dot following an unquoted field name is parsed as a part of the name
+    let fid = match (dot, fid.value().strip_suffix('.')) {
+        (Some(_), _) => fid,
+        (None, Some(value)) if !value.is_empty() => {
+            let span = fid.span.slice(..fid.span.fragment().len() - 1);
+            Token::new(span, fid.value.as_ref().map(|_| value.to_string()))
+        }
+        (None, _) => {
+            return Err(nom::Err::Error(Error::new_from_kind(input, ErrorKind::InvalidPrimary)))
+        }
+    };
+
+    let (rest, filter) = cut(|input| parse_expression(input, depth + 1))(rest)?;
+    let (rest, _) = cut_with_err(ws(char('}')), |c| {
+        Error::new_from_kind(input, ErrorKind::MissingClosingDelimiter(c.char()))
+    })(rest)?;
+
+    Ok((rest, FilterCondition::Nested { fid, filter: Box::new(filter) }))
+}
+
 /// geoRadius      = WS* "_geoRadius(float WS* "," WS* float WS* "," WS* float)
 /// If we parse `_geoRadius` we MUST parse the rest of the expression.
 fn parse_geo_radius(input: Span) -> IResult<FilterCondition> {
@@ -371,6 +410,77 @@ fn parse_geo_bounding_box(input: Span) -> IResult<FilterCondition> {
     Ok((input, res))
 }
 
+/// geoPolygon     = WS* "_geoPolygon(" polygon ("," polygon)* ")"
+/// If we parse `_geoPolygon` we MUST parse the rest of the expression.
+fn parse_geo_polygon(input: Span) -> IResult<FilterCondition> {
+    let (input, polygons) = parse_polygons("_geoPolygon", input)?;
+    Ok((input, FilterCondition::GeoPolygon { polygons }))
+}
+
+/// geoWithin      = WS* "_geoWithin(" polygon ("," polygon)* ")"
+/// If we parse `_geoWithin` we MUST parse the rest of the expression.
+fn parse_geo_within(input: Span) -> IResult<FilterCondition> {
+    let (input, polygons) = parse_polygons("_geoWithin", input)?;
+    Ok((input, FilterCondition::GeoWithin { polygons }))
+}
+
+/// geoIntersects  = WS* "_geoIntersects(" polygon ("," polygon)* ")"
+/// If we parse `_geoIntersects` we MUST parse the rest of the expression.
+fn parse_geo_intersects(input: Span) -> IResult<FilterCondition> {
+    let (input, polygons) = parse_polygons("_geoIntersects", input)?;
+    Ok((input, FilterCondition::GeoIntersects { polygons }))
+}
+
+/// polygons       = WS* keyword "(" polygon ("," polygon)* ")"
+/// polygon        = WS* "[" geoPair ("," geoPair)* "]" WS*
+/// geoPair        = WS* "[" WS* float WS* "," WS* float WS* "]" WS*
+fn parse_polygons<'a>(
+    keyword: &'static str,
+    input: Span<'a>,
+) -> IResult<'a, Vec<Vec<[Token<'a>; 2]>>> {
+    // we want to allow space BEFORE the keyword but not after
+    let parsed = preceded(
+        tuple((multispace0, word_exact(keyword))),
+        // if we were able to parse the keyword and can't parse the rest of the input we return a failure
+        cut(delimited(
+            char('('),
+            separated_list1(
+                tag(","),
+                ws(delimited(
+                    char('['),
+                    separated_list1(
+                        tag(","),
+                        ws(delimited(
+                            char('['),
+                            separated_list1(tag(","), ws(recognize_float)),
+                            char(']'),
+                        )),
+                    ),
+                    char(']'),
+                )),
+            ),
+            char(')'),
+        )),
+    )(input)
+    .map_err(|e| e.map(|_| Error::new_from_kind(input, ErrorKind::GeoPolygon(keyword))));
+
+    let (input, args) = parsed?;
+
+    // a polygon is made of at least three points
+    if args.iter().any(|polygon| polygon.len() < 3 || polygon.iter().any(|point| point.len() != 2))
+    {
+        return Err(nom::Err::Failure(Error::new_from_kind(input, ErrorKind::GeoPolygon(keyword))));
+    }
+
+    let polygons = args
+        .into_iter()
+        .map(|polygon| {
+            polygon.into_iter().map(|point| [point[0].into(), point[1].into()]).collect()
+        })
+        .collect();
+    Ok((input, polygons))
+}
+
 /// geoPoint      = WS* "_geoPoint(float WS* "," WS* float WS* "," WS* float)
 fn parse_geo_point(input: Span) -> IResult<FilterCondition> {
     // we want to forbid space BEFORE the _geoPoint but not after
@@ -426,7 +536,7 @@ fn parse_error_reserved_keyword(input: Span) -> IResult<FilterCondition> {
     }
 }
 
-/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | condition | exists | not_exists | to
+/// primary        = (WS* "(" WS* expression WS* ")" WS*) | geoRadius | geoBoundingBox | geoPolygon | geoWithin | geoIntersects | nested | condition | exists | not_exists | to
 fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
     if depth > MAX_FILTER_DEPTH {
         return Err(nom::Err::Error(Error::new_from_kind(input, ErrorKind::DepthLimitReached)));
@@ -440,8 +550,14 @@ fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
                 Error::new_from_kind(input, ErrorKind::MissingClosingDelimiter(c.char()))
             }),
         ),
-        parse_geo_radius,
-        parse_geo_bounding_box,
+        alt((
+            parse_geo_radius,
+            parse_geo_bounding_box,
+            parse_geo_polygon,
+            parse_geo_within,
+            parse_geo_intersects,
+        )),
+        |input| parse_nested(input, depth + 1),
         parse_in,
         parse_not_in,
         parse_condition,
@@ -451,6 +567,10 @@ fn parse_primary(input: Span, depth: usize) -> IResult<FilterCondition> {
         parse_is_not_empty,
         parse_exists,
         parse_not_exists,
+        parse_contains,
+        parse_not_contains,
+        parse_starts_with,
+        parse_not_starts_with,
         parse_to,
         // the next lines are only for error handling and are written at the end to have the less possible performance impact
         parse_geo,
@@ -518,9 +638,36 @@ impl<'a> std::fmt::Display for FilterCondition<'a> {
                     bottom_right_point[1]
                 )
             }
+            FilterCondition::GeoPolygon { polygons } => {
+                write!(f, "_geoPolygon(")?;
+                fmt_polygons(f, polygons)
+            }
+            FilterCondition::GeoWithin { polygons } => {
+                write!(f, "_geoWithin(")?;
+                fmt_polygons(f, polygons)
+            }
+            FilterCondition::GeoIntersects { polygons } => {
+                write!(f, "_geoIntersects(")?;
+                fmt_polygons(f, polygons)
+            }
+            FilterCondition::Nested { fid, filter } => {
+                write!(f, "{fid}.{{{filter}}}")
+            }
+        }
+    }
+}
+
+fn fmt_polygons(f: &mut std::fmt::Formatter<'_>, polygons: &[Vec<[Token; 2]>]) -> std::fmt::Result {
+    for polygon in polygons {
+        write!(f, "[")?;
+        for [lat, lng] in polygon {
+            write!(f, "[{lat}, {lng}], ")?;
         }
+        write!(f, "], ")?;
     }
+    write!(f, ")")
 }
+
 impl<'a> std::fmt::Display for Condition<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -534,6 +681,8 @@ impl<'a> std::fmt::Display for Condition<'a> {
             Condition::LowerThan(token) => write!(f, "< {token}"),
             Condition::LowerThanOrEqual(token) => write!(f, "<= {token}"),
             Condition::Between { from, to } => write!(f, "{from} TO {to}"),
+            Condition::Contains(token) => write!(f, "CONTAINS {token}"),
+            Condition::StartsWith(token) => write!(f, "STARTS WITH {token}"),
         }
     }
 }
@@ -585,6 +734,16 @@ pub mod tests {
         insta::assert_display_snapshot!(p("'dog race' = 'Bernese Mountain'"), @"{dog race} = {Bernese Mountain}");
         insta::assert_display_snapshot!(p("\"dog race\" = 'Bernese Mountain'"), @"{dog race} = {Bernese Mountain}");
 
+        // Test LEVEL
+        insta::assert_display_snapshot!(p("category LEVEL 1 = Scifi"), @"{category.lvl1} = {Scifi}");
+        insta::assert_display_snapshot!(p("category LEVEL 0 != 'Science Fiction'"), @"{category.lvl0} != {Science Fiction}");
+        insta::assert_display_snapshot!(p("'product category' LEVEL 12 = \"Space opera\""), @"{product category.lvl12} = {Space opera}");
+        insta::assert_display_snapshot!(p("category LEVEL 1 IN [Scifi, Fantasy]"), @"{category.lvl1} IN[{Scifi}, {Fantasy}, ]");
+        insta::assert_display_snapshot!(p("category LEVEL 1 NOT IN [Scifi]"), @"NOT ({category.lvl1} IN[{Scifi}, ])");
+        insta::assert_display_snapshot!(p("category LEVEL 2 EXISTS"), @"{category.lvl2} EXISTS");
+        insta::assert_display_snapshot!(p("category LEVEL 2 IS EMPTY"), @"{category.lvl2} IS EMPTY");
+        insta::assert_display_snapshot!(p("category LEVEL 0 STARTS WITH Sci"), @"{category.lvl0} STARTS WITH {Sci}");
+
         // Test IN
         insta::assert_display_snapshot!(p("colour IN[]"), @"{colour} IN[]");
         insta::assert_display_snapshot!(p("colour IN[green]"), @"{colour} IN[{green}, ]");
@@ -639,6 +798,18 @@ pub mod tests {
         insta::assert_display_snapshot!(p("NOT subscribers NOT EXISTS"), @"{subscribers} EXISTS");
         insta::assert_display_snapshot!(p("subscribers NOT   EXISTS"), @"NOT ({subscribers} EXISTS)");
 
+        // Test CONTAINS + NOT CONTAINS
+        insta::assert_display_snapshot!(p("colour CONTAINS dark"), @"{colour} CONTAINS {dark}");
+        insta::assert_display_snapshot!(p("colour CONTAINS 'dark blue'"), @"{colour} CONTAINS {dark blue}");
+        insta::assert_display_snapshot!(p("colour NOT CONTAINS dark"), @"NOT ({colour} CONTAINS {dark})");
+        insta::assert_display_snapshot!(p("NOT colour CONTAINS dark"), @"NOT ({colour} CONTAINS {dark})");
+
+        // Test STARTS WITH + NOT STARTS WITH
+        insta::assert_display_snapshot!(p("colour STARTS WITH dark"), @"{colour} STARTS WITH {dark}");
+        insta::assert_display_snapshot!(p("colour STARTS   WITH \"dark blue\""), @"{colour} STARTS WITH {dark blue}");
+        insta::assert_display_snapshot!(p("colour NOT STARTS WITH dark"), @"NOT ({colour} STARTS WITH {dark})");
+        insta::assert_display_snapshot!(p("NOT colour NOT STARTS WITH dark"), @"{colour} STARTS WITH {dark}");
+
         // Test nested NOT
         insta::assert_display_snapshot!(p("NOT NOT NOT NOT x = 5"), @"{x} = {5}");
         insta::assert_display_snapshot!(p("NOT NOT (NOT NOT x = 5)"), @"{x} = {5}");
@@ -653,6 +824,22 @@ pub mod tests {
         insta::assert_display_snapshot!(p("NOT _geoBoundingBox([12, 13], [14, 15])"), @"NOT (_geoBoundingBox([{12}, {13}], [{14}, {15}]))");
         insta::assert_display_snapshot!(p("_geoBoundingBox([12,13],[14,15])"), @"_geoBoundingBox([{12}, {13}], [{14}, {15}])");
 
+        // Test geo polygon
+        insta::assert_display_snapshot!(p("_geoPolygon([[12, 13], [14, 15], [16, 17]])"), @"_geoPolygon([[{12}, {13}], [{14}, {15}], [{16}, {17}], ], )");
+        insta::assert_display_snapshot!(p("NOT _geoPolygon([[12, 13], [14, 15], [16, 17]])"), @"NOT (_geoPolygon([[{12}, {13}], [{14}, {15}], [{16}, {17}], ], ))");
+        insta::assert_display_snapshot!(p("_geoPolygon([[1,2],[3,4],[5,6]],[[7,8],[9,10],[11,12]])"), @"_geoPolygon([[{1}, {2}], [{3}, {4}], [{5}, {6}], ], [[{7}, {8}], [{9}, {10}], [{11}, {12}], ], )");
+
+        // Test geo within and intersects
+        insta::assert_display_snapshot!(p("_geoWithin([[12, 13], [14, 15], [16, 17]])"), @"_geoWithin([[{12}, {13}], [{14}, {15}], [{16}, {17}], ], )");
+        insta::assert_display_snapshot!(p("NOT _geoIntersects([[12, 13], [14, 15], [16, 17]])"), @"NOT (_geoIntersects([[{12}, {13}], [{14}, {15}], [{16}, {17}], ], ))");
+        insta::assert_display_snapshot!(p("_geoIntersects([[1,2],[3,4],[5,6]],[[7,8],[9,10],[11,12]])"), @"_geoIntersects([[{1}, {2}], [{3}, {4}], [{5}, {6}], ], [[{7}, {8}], [{9}, {10}], [{11}, {12}], ], )");
+
+        // Test nested objects
+        insta::assert_display_snapshot!(p("reviews.{author = bob AND rating > 4}"), @"{reviews}.{AND[{author} = {bob}, {rating} > {4}, ]}");
+        insta::assert_display_snapshot!(p(" reviews.{ author = bob } "), @"{reviews}.{{author} = {bob}}");
+        insta::assert_display_snapshot!(p("NOT reviews.{rating > 4 OR NOT comments.{text EXISTS}}"), @"NOT ({reviews}.{OR[{rating} > {4}, NOT ({comments}.{{text} EXISTS}), ]})");
+        insta::assert_display_snapshot!(p("'product reviews'.{rating > 4} AND rating.{x = 1}"), @"AND[{product reviews}.{{rating} > {4}}, {rating}.{{x} = {1}}, ]");
+
         // Test OR + AND
         insta::assert_display_snapshot!(p("channel = ponce AND 'dog race' != 'bernese mountain'"), @"AND[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
         insta::assert_display_snapshot!(p("channel = ponce OR 'dog race' != 'bernese mountain'"), @"OR[{channel} = {ponce}, {dog race} != {bernese mountain}, ]");
@@ -710,7 +897,7 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p("'OR'"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `\'OR\'`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `\'OR\'`.
         1:5 'OR'
         "###);
 
@@ -720,12 +907,12 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p("channel Ponce"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `channel Ponce`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `channel Ponce`.
         1:14 channel Ponce
         "###);
 
         insta::assert_display_snapshot!(p("channel = Ponce OR"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` but instead got nothing.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` but instead got nothing.
         19:19 channel = Ponce OR
         "###);
 
@@ -754,6 +941,26 @@ pub mod tests {
         1:26 _geoBoundingBox(1.0, 1.0)
         "###);
 
+        insta::assert_display_snapshot!(p("_geoPolygon = 12"), @r###"
+        The `_geoPolygon` filter expects one or more polygons of at least three points: `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude]])`.
+        1:17 _geoPolygon = 12
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoPolygon([12, 13], [14, 15])"), @r###"
+        The `_geoPolygon` filter expects one or more polygons of at least three points: `_geoPolygon([[latitude, longitude], [latitude, longitude], [latitude, longitude]])`.
+        1:32 _geoPolygon([12, 13], [14, 15])
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoWithin([[12, 13], [14, 15]])"), @r###"
+        The `_geoWithin` filter expects one or more polygons of at least three points: `_geoWithin([[latitude, longitude], [latitude, longitude], [latitude, longitude]])`.
+        1:33 _geoWithin([[12, 13], [14, 15]])
+        "###);
+
+        insta::assert_display_snapshot!(p("_geoIntersects(12, 13)"), @r###"
+        The `_geoIntersects` filter expects one or more polygons of at least three points: `_geoIntersects([[latitude, longitude], [latitude, longitude], [latitude, longitude]])`.
+        1:23 _geoIntersects(12, 13)
+        "###);
+
         insta::assert_display_snapshot!(p("_geoPoint(12, 13, 14)"), @r###"
         `_geoPoint` is a reserved keyword and thus can't be used as a filter expression. Use the `_geoRadius(latitude, longitude, distance)` or `_geoBoundingBox([latitude, longitude], [latitude, longitude])` built-in rules to filter on `_geo` coordinates.
         1:22 _geoPoint(12, 13, 14)
@@ -789,6 +996,16 @@ pub mod tests {
         13:35 position <= _geoRadius(12, 13, 14)
         "###);
 
+        insta::assert_display_snapshot!(p("position <= _geoPolygon([[1, 2], [3, 4], [5, 6]])"), @r###"
+        The `_geoPolygon` filter is an operation and can't be used as a value.
+        13:50 position <= _geoPolygon([[1, 2], [3, 4], [5, 6]])
+        "###);
+
+        insta::assert_display_snapshot!(p("position = _geoWithin([[1, 2], [3, 4], [5, 6]])"), @r###"
+        The `_geoWithin` filter is an operation and can't be used as a value.
+        12:48 position = _geoWithin([[1, 2], [3, 4], [5, 6]])
+        "###);
+
         insta::assert_display_snapshot!(p("channel = 'ponce"), @r###"
         Expression `\'ponce` is missing the following closing delimiter: `'`.
         11:17 channel = 'ponce
@@ -804,18 +1021,23 @@ pub mod tests {
         17:35 channel = mv OR (followers >= 1000
         "###);
 
+        insta::assert_display_snapshot!(p("reviews.{rating > 4"), @r###"
+        Expression `reviews.{rating > 4` is missing the following closing delimiter: `}`.
+        1:20 reviews.{rating > 4
+        "###);
+
         insta::assert_display_snapshot!(p("channel = mv OR followers >= 1000)"), @r###"
         Found unexpected characters at the end of the filter: `)`. You probably forgot an `OR` or an `AND` rule.
         34:35 channel = mv OR followers >= 1000)
         "###);
 
         insta::assert_display_snapshot!(p("colour NOT EXIST"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `colour NOT EXIST`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `colour NOT EXIST`.
         1:17 colour NOT EXIST
         "###);
 
         insta::assert_display_snapshot!(p("subscribers 100 TO1000"), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `subscribers 100 TO1000`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `subscribers 100 TO1000`.
         1:23 subscribers 100 TO1000
         "###);
 
@@ -878,35 +1100,35 @@ pub mod tests {
         "###);
 
         insta::assert_display_snapshot!(p(r#"value NULL"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value NULL`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value NULL`.
         1:11 value NULL
         "###);
         insta::assert_display_snapshot!(p(r#"value NOT NULL"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value NOT NULL`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value NOT NULL`.
         1:15 value NOT NULL
         "###);
         insta::assert_display_snapshot!(p(r#"value EMPTY"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value EMPTY`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value EMPTY`.
         1:12 value EMPTY
         "###);
         insta::assert_display_snapshot!(p(r#"value NOT EMPTY"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value NOT EMPTY`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value NOT EMPTY`.
         1:16 value NOT EMPTY
         "###);
         insta::assert_display_snapshot!(p(r#"value IS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value IS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value IS`.
         1:9 value IS
         "###);
         insta::assert_display_snapshot!(p(r#"value IS NOT"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value IS NOT`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value IS NOT`.
         1:13 value IS NOT
         "###);
         insta::assert_display_snapshot!(p(r#"value IS EXISTS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value IS EXISTS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value IS EXISTS`.
         1:16 value IS EXISTS
         "###);
         insta::assert_display_snapshot!(p(r#"value IS NOT EXISTS"#), @r###"
-        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `value IS NOT EXISTS`.
+        Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `value IS NOT EXISTS`.
         1:20 value IS NOT EXISTS
         "###);
     }
@@ -1,14 +1,15 @@
 use nom::branch::alt;
-use nom::bytes::complete::{take_till, take_while, take_while1};
-use nom::character::complete::{char, multispace0};
-use nom::combinator::cut;
-use nom::sequence::{delimited, terminated};
+use nom::bytes::complete::{tag, take_till, take_while, take_while1};
+use nom::character::complete::{char, digit1, multispace0, multispace1};
+use nom::combinator::{cut, opt};
+use nom::sequence::{delimited, terminated, tuple};
 use nom::{InputIter, InputLength, InputTake, Slice};
 
 use crate::error::{ExpectedValueKind, NomErrorExt};
 use crate::{
-    parse_geo, parse_geo_bounding_box, parse_geo_distance, parse_geo_point, parse_geo_radius,
-    Error, ErrorKind, IResult, Span, Token,
+    parse_geo, parse_geo_bounding_box, parse_geo_distance, parse_geo_intersects, parse_geo_point,
+    parse_geo_polygon, parse_geo_radius, parse_geo_within, Error, ErrorKind, FilterCondition,
+    IResult, Span, Token,
 };
 
 /// This function goes through all characters in the [Span] if it finds any escaped character (`\`).
@@ -80,6 +81,27 @@ pub fn word_exact<'a, 'b: 'a>(tag: &'b str) -> impl Fn(Span<'a>) -> IResult<'a,
     }
 }
 
+/// field          = value level?
+pub fn parse_field(input: Span) -> IResult<Token> {
+    let (input, (fid, level)) = tuple((parse_value, opt(parse_level)))(input)?;
+
+    // the levels of a hierarchical facet are indexed as the `{field}.lvl{n}` fields
+    let fid = match level {
+        Some(level) => {
+            let name = format!("{}.lvl{}", fid.value(), level.fragment());
+            Token::new(fid.original_span(), Some(name))
+        }
+        None => fid,
+    };
+
+    Ok((input, fid))
+}
+
+/// level          = "LEVEL" WS+ digit+ WS*
+fn parse_level(input: Span) -> IResult<Span> {
+    delimited(tuple((tag("LEVEL"), multispace1)), digit1, multispace0)(input)
+}
+
 /// value          = WS* ( word | singleQuoted | doubleQuoted) WS+
 pub fn parse_value(input: Span) -> IResult<Token> {
     // to get better diagnostic message we are going to strip the left whitespaces from the input right now
@@ -128,6 +150,32 @@ pub fn parse_value(input: Span) -> IResult<Token> {
         _ => (),
     }
 
+    let geo_polygon_parse_functions: [(fn(Span) -> IResult<FilterCondition>, &str); 3] = [
+        (parse_geo_polygon, "_geoPolygon"),
+        (parse_geo_within, "_geoWithin"),
+        (parse_geo_intersects, "_geoIntersects"),
+    ];
+
+    for (parser, name) in geo_polygon_parse_functions {
+        match parser(input) {
+            Ok(_) => {
+                return Err(nom::Err::Failure(Error::new_from_kind(
+                    input,
+                    ErrorKind::MisusedGeoPolygon(name),
+                )))
+            }
+            // if we encountered a failure it means the user badly wrote the filter.
+            // But instead of showing them how to fix his syntax we are going to tell them they should not use this filter as a value.
+            Err(e) if e.is_failure() => {
+                return Err(nom::Err::Failure(Error::new_from_kind(
+                    input,
+                    ErrorKind::MisusedGeoPolygon(name),
+                )))
+            }
+            _ => (),
+        }
+    }
+
     // this parser is only used when an error is encountered and it parse the
     // largest string possible that do not contain any “language” syntax.
     // If we try to parse `name = 🦀 AND language = rust` we want to return an
@@ -213,6 +261,9 @@ fn is_keyword(s: &str) -> bool {
             | "EMPTY"
             | "_geoRadius"
             | "_geoBoundingBox"
+            | "_geoPolygon"
+            | "_geoWithin"
+            | "_geoIntersects"
     )
 }
 
@@ -3,9 +3,18 @@
 use serde_json::{Map, Value};
 
 pub fn flatten(json: &Map<String, Value>) -> Map<String, Value> {
+    flatten_except(json, |_| false)
+}
+
+/// Flattens the object like [`flatten`] but keeps the values of the keys
+/// for which `is_non_flattened` returns `true` verbatim, without exploding them.
+pub fn flatten_except(
+    json: &Map<String, Value>,
+    is_non_flattened: impl Fn(&str) -> bool,
+) -> Map<String, Value> {
     let mut obj = Map::new();
     let mut all_entries = vec![];
-    insert_object(&mut obj, None, json, &mut all_entries);
+    insert_object(&mut obj, None, json, &is_non_flattened, &mut all_entries);
     for (key, old_val) in all_entries {
         obj.entry(key).or_insert(old_val.clone());
     }
@@ -16,15 +25,19 @@ fn insert_object<'a>(
     base_json: &mut Map<String, Value>,
     base_key: Option<&str>,
     object: &'a Map<String, Value>,
+    is_non_flattened: &dyn Fn(&str) -> bool,
     all_entries: &mut Vec<(String, &'a Value)>,
 ) {
     for (key, value) in object {
         let new_key = base_key.map_or_else(|| key.clone(), |base_key| format!("{base_key}.{key}"));
         all_entries.push((new_key.clone(), value));
-        if let Some(array) = value.as_array() {
-            insert_array(base_json, &new_key, array, all_entries);
+        if is_non_flattened(&new_key) {
+            // the original value is kept as is by the `all_entries`
+            continue;
+        } else if let Some(array) = value.as_array() {
+            insert_array(base_json, &new_key, array, is_non_flattened, all_entries);
         } else if let Some(object) = value.as_object() {
-            insert_object(base_json, Some(&new_key), object, all_entries);
+            insert_object(base_json, Some(&new_key), object, is_non_flattened, all_entries);
         } else {
             insert_value(base_json, &new_key, value.clone(), false);
         }
@@ -35,13 +48,14 @@ fn insert_array<'a>(
     base_json: &mut Map<String, Value>,
     base_key: &str,
     array: &'a Vec<Value>,
+    is_non_flattened: &dyn Fn(&str) -> bool,
     all_entries: &mut Vec<(String, &'a Value)>,
 ) {
     for value in array {
         if let Some(object) = value.as_object() {
-            insert_object(base_json, Some(base_key), object, all_entries);
+            insert_object(base_json, Some(base_key), object, is_non_flattened, all_entries);
         } else if let Some(sub_array) = value.as_array() {
-            insert_array(base_json, base_key, sub_array, all_entries);
+            insert_array(base_json, base_key, sub_array, is_non_flattened, all_entries);
         } else {
             insert_value(base_json, base_key, value.clone(), true);
         }
@@ -134,6 +148,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn flatten_object_except_non_flattened_keys() {
+        let mut base: Value = json!({
+          "a": {
+            "b": "c",
+            "d": {
+              "e": "f"
+            }
+          },
+          "metadata": {
+            "g": {
+              "h": "i"
+            }
+          }
+        });
+        let json = std::mem::take(base.as_object_mut().unwrap());
+        let flat = flatten_except(&json, |key| key == "metadata" || key == "a.d");
+
+        assert_eq!(
+            &flat,
+            json!({
+                "a": {
+                  "b": "c",
+                  "d": {
+                    "e": "f"
+                  }
+                },
+                "a.b": "c",
+                "a.d": {
+                  "e": "f"
+                },
+                "metadata": {
+                  "g": {
+                    "h": "i"
+                  }
+                }
+            })
+            .as_object()
+            .unwrap()
+        );
+    }
+
     #[test]
     fn flatten_array() {
         let mut base: Value = json!({
@@ -34,6 +34,8 @@ enum AutobatchKind {
     IndexDeletion,
     IndexUpdate,
     IndexSwap,
+    IndexCopy,
+    IndexExport,
 }
 
 impl AutobatchKind {
@@ -77,6 +79,8 @@ impl From<KindWithContent> for AutobatchKind {
             KindWithContent::IndexCreation { .. } => AutobatchKind::IndexCreation,
             KindWithContent::IndexUpdate { .. } => AutobatchKind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => AutobatchKind::IndexSwap,
+            KindWithContent::IndexCopy { .. } => AutobatchKind::IndexCopy,
+            KindWithContent::IndexExport { .. } => AutobatchKind::IndexExport,
             KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
@@ -132,6 +136,12 @@ pub enum BatchKind {
     IndexSwap {
         id: TaskId,
     },
+    IndexCopy {
+        id: TaskId,
+    },
+    IndexExport {
+        id: TaskId,
+    },
 }
 
 impl BatchKind {
@@ -175,6 +185,8 @@ impl BatchKind {
             K::IndexDeletion => (Break(BatchKind::IndexDeletion { ids: vec![task_id] }), false),
             K::IndexUpdate => (Break(BatchKind::IndexUpdate { id: task_id }), false),
             K::IndexSwap => (Break(BatchKind::IndexSwap { id: task_id }), false),
+            K::IndexCopy => (Break(BatchKind::IndexCopy { id: task_id }), false),
+            K::IndexExport => (Break(BatchKind::IndexExport { id: task_id }), false),
             K::DocumentClear => (Continue(BatchKind::DocumentClear { ids: vec![task_id] }), false),
             K::DocumentImport { method, allow_index_creation, primary_key: pk }
                 if primary_key.is_none() || pk.is_none() || primary_key == pk.as_deref() =>
@@ -222,7 +234,7 @@ impl BatchKind {
 
         match (self, kind) {
             // We don't batch any of these operations
-            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::DocumentDeletionByFilter) => Break(this),
+            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::IndexCopy | K::IndexExport | K::DocumentDeletionByFilter) => Break(this),
             // We must not batch tasks that don't have the same index creation rights if the index doesn't already exists.
             (this, kind) if !index_already_exists && this.allow_index_creation() == Some(false) && kind.allow_index_creation() == Some(true) => {
                 Break(this)
@@ -519,6 +531,8 @@ impl BatchKind {
                 | BatchKind::IndexDeletion { .. }
                 | BatchKind::IndexUpdate { .. }
                 | BatchKind::IndexSwap { .. }
+                | BatchKind::IndexCopy { .. }
+                | BatchKind::IndexExport { .. }
                 | BatchKind::DocumentDeletionByFilter { .. },
                 _,
             ) => {
@@ -599,6 +613,7 @@ mod tests {
             content_file: Uuid::new_v4(),
             documents_count: 0,
             allow_index_creation,
+            skip_invalid_documents: false,
         }
     }
 
@@ -21,12 +21,15 @@ use std::collections::{BTreeSet, HashSet};
 use std::ffi::OsStr;
 use std::fmt;
 use std::fs::{self, File};
-use std::io::BufWriter;
+use std::io::{self, BufWriter, Read, Write};
+use std::sync::mpsc;
+use std::time::Duration;
 
 use dump::IndexMetadata;
 use log::{debug, error, info, trace};
 use meilisearch_types::error::Code;
 use meilisearch_types::heed::{RoTxn, RwTxn};
+use meilisearch_types::milli::change_feed::{ChangeOperation, DocumentChange};
 use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader};
 use meilisearch_types::milli::heed::CompactionOption;
 use meilisearch_types::milli::update::{
@@ -34,7 +37,9 @@ use meilisearch_types::milli::update::{
 };
 use meilisearch_types::milli::{self, Filter};
 use meilisearch_types::settings::{apply_settings_to_builder, Settings, Unchecked};
-use meilisearch_types::tasks::{Details, IndexSwap, Kind, KindWithContent, Status, Task};
+use meilisearch_types::tasks::{
+    Details, ExportFormat, IndexSwap, InvalidDocument, Kind, KindWithContent, Status, Task,
+};
 use meilisearch_types::{compression, Index, VERSION_FILE_NAME};
 use roaring::RoaringBitmap;
 use time::macros::format_description;
@@ -85,11 +90,25 @@ pub(crate) enum Batch {
     IndexSwap {
         task: Task,
     },
+    IndexCopy {
+        index_uid: String,
+        destination: String,
+        task: Task,
+    },
+    IndexExport {
+        index_uid: String,
+        task: Task,
+    },
 }
 
 #[derive(Debug)]
 pub(crate) enum DocumentOperation {
-    Add(Uuid),
+    /// Adds the documents of the content file, skipping the ones that can't be indexed
+    /// instead of rejecting the whole content when `skip_invalid_documents` is set.
+    Add {
+        content_file: Uuid,
+        skip_invalid_documents: bool,
+    },
     Delete(Vec<String>),
 }
 
@@ -149,7 +168,9 @@ impl Batch {
             | Batch::TaskDeletion(task)
             | Batch::Dump(task)
             | Batch::IndexCreation { task, .. }
-            | Batch::IndexUpdate { task, .. } => vec![task.uid],
+            | Batch::IndexUpdate { task, .. }
+            | Batch::IndexCopy { task, .. }
+            | Batch::IndexExport { task, .. } => vec![task.uid],
             Batch::SnapshotCreation(tasks) | Batch::IndexDeletion { tasks, .. } => {
                 tasks.iter().map(|task| task.uid).collect()
             }
@@ -187,7 +208,9 @@ impl Batch {
             IndexOperation { op, .. } => Some(op.index_uid()),
             IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
-            | IndexDeletion { index_uid, .. } => Some(index_uid),
+            | IndexDeletion { index_uid, .. }
+            | IndexCopy { index_uid, .. }
+            | IndexExport { index_uid, .. } => Some(index_uid),
         }
     }
 }
@@ -207,6 +230,8 @@ impl fmt::Display for Batch {
             Batch::IndexUpdate { .. } => f.write_str("IndexUpdate")?,
             Batch::IndexDeletion { .. } => f.write_str("IndexDeletion")?,
             Batch::IndexSwap { .. } => f.write_str("IndexSwap")?,
+            Batch::IndexCopy { .. } => f.write_str("IndexCopy")?,
+            Batch::IndexExport { .. } => f.write_str("IndexExport")?,
         };
         match index_uid {
             Some(name) => f.write_fmt(format_args!(" on {name:?} from tasks: {tasks:?}")),
@@ -226,6 +251,17 @@ impl IndexOperation {
             | IndexOperation::SettingsAndDocumentOperation { index_uid, .. } => index_uid,
         }
     }
+    /// Returns `true` if the operation updates the settings of the index.
+    pub fn updates_settings(&self) -> bool {
+        match self {
+            IndexOperation::DocumentOperation { .. }
+            | IndexOperation::IndexDocumentDeletionByFilter { .. }
+            | IndexOperation::DocumentClear { .. } => false,
+            IndexOperation::Settings { .. }
+            | IndexOperation::DocumentClearAndSetting { .. }
+            | IndexOperation::SettingsAndDocumentOperation { .. } => true,
+        }
+    }
 }
 
 impl fmt::Display for IndexOperation {
@@ -308,10 +344,14 @@ impl IndexScheduler {
                         KindWithContent::DocumentAdditionOrUpdate {
                             content_file,
                             documents_count,
+                            skip_invalid_documents,
                             ..
                         } => {
                             documents_counts.push(documents_count);
-                            operations.push(DocumentOperation::Add(content_file));
+                            operations.push(DocumentOperation::Add {
+                                content_file,
+                                skip_invalid_documents,
+                            });
                         }
                         KindWithContent::DocumentDeletion { ref documents_ids, .. } => {
                             documents_counts.push(documents_ids.len() as u64);
@@ -506,6 +546,18 @@ impl IndexScheduler {
                 let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
                 Ok(Some(Batch::IndexSwap { task }))
             }
+            BatchKind::IndexCopy { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                let destination = match &task.kind {
+                    KindWithContent::IndexCopy { destination, .. } => destination.clone(),
+                    _ => unreachable!(),
+                };
+                Ok(Some(Batch::IndexCopy { index_uid, destination, task }))
+            }
+            BatchKind::IndexExport { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                Ok(Some(Batch::IndexExport { index_uid, task }))
+            }
         }
     }
 
@@ -939,9 +991,45 @@ impl IndexScheduler {
                 *self.currently_updating_index.write().unwrap() =
                     Some((index_uid.clone(), index.clone()));
 
-                let mut index_wtxn = index.write_txn()?;
-                let tasks = self.apply_index_operation(&mut index_wtxn, &index, op)?;
-                index_wtxn.commit()?;
+                let (index, tasks) = if !must_create_index
+                    && op.updates_settings()
+                    && self.features().runtime_features().shadow_settings_update
+                {
+                    // The settings are applied on a hidden copy of the index that is only
+                    // swapped with the index once the reindexing succeeded. The index stays
+                    // available to the search with its previous settings in the meantime.
+                    let rtxn = self.env.read_txn()?;
+                    let (shadow_uuid, shadow) =
+                        self.index_mapper.create_shadow_index(&rtxn, &index_uid)?;
+                    drop(rtxn);
+
+                    let res = || -> Result<Vec<Task>> {
+                        let mut shadow_wtxn = shadow.write_txn()?;
+                        let tasks = self.apply_index_operation(&mut shadow_wtxn, &shadow, op)?;
+                        shadow_wtxn.commit()?;
+                        Ok(tasks)
+                    }();
+
+                    match res {
+                        Ok(tasks) => {
+                            let wtxn = self.env.write_txn()?;
+                            self.index_mapper.swap_shadow_index(wtxn, &index_uid, shadow_uuid)?;
+                            *self.currently_updating_index.write().unwrap() =
+                                Some((index_uid.clone(), shadow.clone()));
+                            (shadow, tasks)
+                        }
+                        Err(e) => {
+                            drop(shadow);
+                            self.index_mapper.delete_shadow_index(shadow_uuid, &index_uid);
+                            return Err(e);
+                        }
+                    }
+                } else {
+                    let mut index_wtxn = index.write_txn()?;
+                    let tasks = self.apply_index_operation(&mut index_wtxn, &index, op)?;
+                    index_wtxn.commit()?;
+                    (index, tasks)
+                };
 
                 // if the update processed successfully, we're going to store the new
                 // stats of the index. Since the tasks have already been processed and
@@ -1019,7 +1107,8 @@ impl IndexScheduler {
                 Ok(vec![task])
             }
             Batch::IndexDeletion { index_uid, index_has_been_created, mut tasks } => {
-                let wtxn = self.env.write_txn()?;
+                let mut wtxn = self.env.write_txn()?;
+                self.search_analytics.delete_index(&index_uid)?;
 
                 // it's possible that the index doesn't exist
                 let number_of_documents = || -> Result<u64> {
@@ -1035,6 +1124,7 @@ impl IndexScheduler {
                     Err(Error::IndexNotFound(_)) if index_has_been_created => (),
                     Err(e) => return Err(e),
                 }
+                self.delete_exports_of(&index_uid, None)?;
 
                 // We set all the tasks details to the default value.
                 for task in &mut tasks {
@@ -1083,9 +1173,106 @@ impl IndexScheduler {
                 task.status = Status::Succeeded;
                 Ok(vec![task])
             }
+            Batch::IndexCopy { index_uid, destination, mut task } => {
+                let wtxn = self.env.write_txn()?;
+                // The write transaction is directly owned and committed inside.
+                let index = self.index_mapper.copy_index(wtxn, &index_uid, &destination)?;
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::IndexCopy { destination: destination.clone() });
+
+                // if the copy processed successfully, we're going to store the stats of the
+                // new index. Since the task has already been processed and this is a
+                // non-critical operation. If it fails, we should not fail the entire batch.
+                let res = || -> Result<()> {
+                    let mut wtxn = self.env.write_txn()?;
+                    let index_rtxn = index.read_txn()?;
+                    let stats = crate::index_mapper::IndexStats::new(&index, &index_rtxn)?;
+                    self.index_mapper.store_stats_of(&mut wtxn, &destination, &stats)?;
+                    wtxn.commit()?;
+                    Ok(())
+                }();
+
+                match res {
+                    Ok(_) => (),
+                    Err(e) => error!("Could not write the stats of the index {}", e),
+                }
+
+                Ok(vec![task])
+            }
+            Batch::IndexExport { index_uid, mut task } => {
+                let (format, filter, fields, url) = match &task.kind {
+                    KindWithContent::IndexExport { format, filter, fields, url, .. } => {
+                        (*format, filter.clone(), fields.clone(), url.clone())
+                    }
+                    _ => unreachable!(),
+                };
+
+                let rtxn = self.env.read_txn()?;
+                let index = self.index_mapper.index(&rtxn, &index_uid)?;
+                drop(rtxn);
+
+                let index_rtxn = index.read_txn()?;
+                let export = |writer: &mut dyn Write| {
+                    export_documents(
+                        &index_rtxn,
+                        &index,
+                        format,
+                        filter.as_ref(),
+                        fields.as_deref(),
+                        writer,
+                        &self.must_stop_processing,
+                    )
+                };
+
+                let (exported_documents, destination) = match url {
+                    // 1. Either stream the documents to the URL
+                    Some(url) => {
+                        self.check_export_url(&url)?;
+                        // the documents are exported twice to know the length of the body
+                        // before uploading it, as e.g. the S3 presigned URLs require it
+                        let mut length = ByteCounter::default();
+                        export(&mut length)?;
+                        let exported_documents =
+                            upload_export(&url, length.0, &self.must_stop_processing, export)?;
+                        (exported_documents, url_without_query(&url).to_string())
+                    }
+                    // 2. Or write them in the exports directory, replacing the previous export
+                    None => {
+                        let mut file = tempfile::NamedTempFile::new_in(&self.exports_path)?;
+                        let exported_documents = export(&mut BufWriter::new(file.as_file_mut()))?;
+                        let file_name = format!("{index_uid}-{}.{}", task.uid, format.extension());
+                        file.persist(self.exports_path.join(&file_name))?;
+                        self.delete_exports_of(&index_uid, Some(&file_name))?;
+                        (exported_documents, file_name)
+                    }
+                };
+                drop(index_rtxn);
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::IndexExport {
+                    original_filter: filter.map(|filter| filter.to_string()),
+                    exported_documents: Some(exported_documents),
+                    destination: Some(destination),
+                });
+                Ok(vec![task])
+            }
         }
     }
 
+    /// Deletes the exports of the index written in the exports directory, except the one named `keep`.
+    fn delete_exports_of(&self, index_uid: &str, keep: Option<&str>) -> Result<()> {
+        for entry in fs::read_dir(&self.exports_path)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some(file_name) = file_name.to_str() else { continue };
+            if Some(file_name) != keep && is_export_of(file_name, index_uid) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
     /// Swap the index `lhs` with the index `rhs`.
     fn apply_index_swap(&self, wtxn: &mut RwTxn, task_id: u32, lhs: &str, rhs: &str) -> Result<()> {
         // 1. Verify that both lhs and rhs are existing indexes
@@ -1130,6 +1317,9 @@ impl IndexScheduler {
 
     /// Process the index operation on the given index.
     ///
+    /// The changes made to the documents are recorded in the change feed of the index, within
+    /// the same transaction, when the change feed is enabled.
+    ///
     /// ## Return
     /// The list of processed tasks.
     fn apply_index_operation<'i>(
@@ -1140,6 +1330,8 @@ impl IndexScheduler {
     ) -> Result<Vec<Task>> {
         puffin::profile_function!();
 
+        let record_changes = self.features().runtime_features().change_feed;
+
         match operation {
             IndexOperation::DocumentClear { mut tasks, .. } => {
                 let count = milli::update::ClearDocuments::new(index_wtxn, index).execute()?;
@@ -1159,6 +1351,19 @@ impl IndexScheduler {
                     };
                 }
 
+                // the documents are all deleted by the first document clear
+                let first_clear = tasks
+                    .iter()
+                    .find(|task| matches!(task.kind, KindWithContent::DocumentClear { .. }));
+                if let Some(task) = first_clear.filter(|_| record_changes && count != 0) {
+                    let change = DocumentChange {
+                        task_uid: task.uid,
+                        document_id: None,
+                        operation: ChangeOperation::Clear,
+                    };
+                    index.append_document_changes(index_wtxn, [change])?;
+                }
+
                 Ok(tasks)
             }
             IndexOperation::DocumentOperation {
@@ -1176,7 +1381,8 @@ impl IndexScheduler {
                 if let Some(primary_key) = primary_key {
                     match index.primary_key(index_wtxn)? {
                         // if a primary key was set AND had already been defined in the index
-                        // but to a different value, we can make the whole batch fail.
+                        // but to a different value, we can make the whole batch fail. Only the
+                        // settings update re-keys the documents of a non-empty index.
                         Some(pk) => {
                             if primary_key != pk {
                                 return Err(milli::Error::from(
@@ -1214,14 +1420,27 @@ impl IndexScheduler {
                     |indexing_step| trace!("update: {:?}", indexing_step),
                     || must_stop_processing.get(),
                 )?;
+                if record_changes {
+                    builder = builder.with_document_changes();
+                }
 
+                // the changes of the failed additions are discarded as they aren't applied
+                let mut document_changes = Vec::new();
                 for (operation, task) in operations.into_iter().zip(tasks.iter_mut()) {
                     match operation {
-                        DocumentOperation::Add(content_uuid) => {
+                        DocumentOperation::Add {
+                            content_file: content_uuid,
+                            skip_invalid_documents,
+                        } => {
                             let content_file = self.file_store.get_update(content_uuid)?;
                             let reader = DocumentsBatchReader::from_reader(content_file)
                                 .map_err(milli::Error::from)?;
-                            let (new_builder, user_result) = builder.add_documents(reader)?;
+                            let (new_builder, user_result) = if skip_invalid_documents {
+                                builder.add_valid_documents(reader)?
+                            } else {
+                                let (new_builder, user_result) = builder.add_documents(reader)?;
+                                (new_builder, user_result.map(|count| (count, Vec::new())))
+                            };
                             builder = new_builder;
 
                             builder = builder.with_embedders(embedders.clone());
@@ -1238,12 +1457,30 @@ impl IndexScheduler {
                                     unreachable!();
                                 };
 
+                            let changes = builder.take_document_changes();
+                            let outdated_documents = builder.take_outdated_documents();
                             match user_result {
-                                Ok(count) => {
+                                Ok((count, invalid_documents)) => {
+                                    document_changes.extend(changes_of_task(task.uid, changes));
+                                    let invalid_documents =
+                                        (!invalid_documents.is_empty()).then(|| {
+                                            invalid_documents
+                                                .into_iter()
+                                                .take(INVALID_DOCUMENTS_REPORT_SIZE)
+                                                .map(|(position, error)| InvalidDocument {
+                                                    position,
+                                                    error: milli::Error::from(error).into(),
+                                                })
+                                                .collect()
+                                        });
                                     task.status = Status::Succeeded;
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(count),
+                                        embedding_failures: None,
+                                        invalid_documents,
+                                        outdated_documents: (outdated_documents > 0)
+                                            .then_some(outdated_documents),
                                     })
                                 }
                                 Err(e) => {
@@ -1251,6 +1488,9 @@ impl IndexScheduler {
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(0),
+                                        embedding_failures: None,
+                                        invalid_documents: None,
+                                        outdated_documents: None,
                                     });
                                     task.error = Some(milli::Error::from(e).into());
                                 }
@@ -1262,6 +1502,8 @@ impl IndexScheduler {
                             builder = new_builder;
                             // Uses Invariant: remove documents actually always returns Ok for the inner result
                             let count = user_result.unwrap();
+                            let changes = builder.take_document_changes();
+                            document_changes.extend(changes_of_task(task.uid, changes));
                             let provided_ids =
                                 if let Some(Details::DocumentDeletion { provided_ids, .. }) =
                                     task.details
@@ -1284,6 +1526,24 @@ impl IndexScheduler {
                 if !tasks.iter().all(|res| res.error.is_some()) {
                     let addition = builder.execute()?;
                     info!("document addition done: {:?}", addition);
+                    if !document_changes.is_empty() {
+                        index.append_document_changes(index_wtxn, document_changes)?;
+                    }
+
+                    // the documents of the batch that couldn't be embedded within the request
+                    // policies are reported by all its succeeded additions
+                    if !addition.embedding_failures.is_empty() {
+                        for task in tasks.iter_mut().filter(|task| task.status == Status::Succeeded)
+                        {
+                            if let Some(Details::DocumentAdditionOrUpdate {
+                                embedding_failures,
+                                ..
+                            }) = &mut task.details
+                            {
+                                *embedding_failures = Some(addition.embedding_failures.clone());
+                            }
+                        }
+                    }
                 } else if primary_key_has_been_set {
                     // Everything failed but we've set a primary key.
                     // We need to remove it.
@@ -1313,24 +1573,25 @@ impl IndexScheduler {
                     self.index_mapper.indexer_config(),
                     self.must_stop_processing.clone(),
                     index,
+                    record_changes.then_some(task.uid),
                 );
-                let original_filter = if let Some(Details::DocumentDeletionByFilter {
-                    original_filter,
-                    deleted_documents: _,
-                }) = task.details
-                {
-                    original_filter
-                } else {
-                    // In the case of a `documentDeleteByFilter` the details MUST be set
-                    unreachable!();
-                };
+                let original_filter =
+                    if let Some(Details::DocumentDeletionByFilter { original_filter, .. }) =
+                        task.details
+                    {
+                        original_filter
+                    } else {
+                        // In the case of a `documentDeleteByFilter` the details MUST be set
+                        unreachable!();
+                    };
 
                 match deleted_documents {
-                    Ok(deleted_documents) => {
+                    Ok((deleted_documents, deleted_documents_sample)) => {
                         task.status = Status::Succeeded;
                         task.details = Some(Details::DocumentDeletionByFilter {
                             original_filter,
                             deleted_documents: Some(deleted_documents),
+                            deleted_documents_sample: Some(deleted_documents_sample),
                         });
                     }
                     Err(e) => {
@@ -1338,6 +1599,7 @@ impl IndexScheduler {
                         task.details = Some(Details::DocumentDeletionByFilter {
                             original_filter,
                             deleted_documents: Some(0),
+                            deleted_documents_sample: None,
                         });
                         task.error = Some(e.into());
                     }
@@ -1546,21 +1808,34 @@ impl IndexScheduler {
     }
 }
 
+/// The maximum number of skipped documents reported in the details of a document addition.
+const INVALID_DOCUMENTS_REPORT_SIZE: usize = 100;
+
+/// The maximum number of external ids kept in the details of a deletion by filter.
+const DELETED_DOCUMENTS_SAMPLE_SIZE: usize = 20;
+
+/// Deletes the documents matching the filter, returning the number of deleted documents
+/// and the external ids of the first ones.
 fn delete_document_by_filter<'a>(
     wtxn: &mut RwTxn<'a>,
     filter: &serde_json::Value,
     indexer_config: &IndexerConfig,
     must_stop_processing: MustStopProcessing,
     index: &'a Index,
-) -> Result<u64> {
+    record_changes_of: Option<TaskId>,
+) -> Result<(u64, Vec<String>)> {
     let filter = Filter::from_json(filter)?;
     Ok(if let Some(filter) = filter {
-        let candidates = filter.evaluate(wtxn, index).map_err(|err| match err {
-            milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
-                Error::from(err).with_custom_error_code(Code::InvalidDocumentFilter)
-            }
-            e => e.into(),
-        })?;
+        // the sample is read before the documents are deleted
+        let candidates = filter.evaluate(wtxn, index).map_err(invalid_document_filter)?;
+        let sample = if candidates.is_empty() {
+            Vec::new()
+        } else {
+            index
+                .external_id_of(wtxn, candidates.into_iter().take(DELETED_DOCUMENTS_SAMPLE_SIZE))?
+                .into_iter()
+                .collect::<milli::Result<Vec<_>>>()?
+        };
 
         let config = IndexDocumentsConfig {
             update_method: IndexDocumentsMethod::ReplaceDocuments,
@@ -1576,12 +1851,276 @@ fn delete_document_by_filter<'a>(
             || must_stop_processing.get(),
         )?;
 
+        if record_changes_of.is_some() {
+            builder = builder.with_document_changes();
+        }
+
+        // the filter was already evaluated, the candidates are directly removed from the database
         let (new_builder, count) = builder.remove_documents_from_db_no_batch(&candidates)?;
         builder = new_builder;
+        let changes = builder.take_document_changes();
 
         let _ = builder.execute()?;
-        count
+        if let Some(task_uid) = record_changes_of.filter(|_| !changes.is_empty()) {
+            index.append_document_changes(wtxn, changes_of_task(task_uid, changes))?;
+        }
+        (count, sample)
     } else {
-        0
+        (0, Vec::new())
+    })
+}
+
+/// Attributes the changes made to the documents to the task that made them.
+fn changes_of_task(
+    task_uid: TaskId,
+    changes: Vec<(String, ChangeOperation)>,
+) -> impl Iterator<Item = DocumentChange> {
+    changes.into_iter().map(move |(document_id, operation)| DocumentChange {
+        task_uid,
+        document_id: Some(document_id),
+        operation,
+    })
+}
+
+/// Reports the invalid filters of the document routes with the `invalid_document_filter` code.
+fn invalid_document_filter(err: milli::Error) -> Error {
+    match err {
+        milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
+            Error::from(err).with_custom_error_code(Code::InvalidDocumentFilter)
+        }
+        e => e.into(),
+    }
+}
+
+/// Writes the documents matching the filter in the given format, keeping only the given
+/// fields, and returns the number of written documents.
+///
+/// The CSV header is made of the given fields or of all the fields known by the index.
+fn export_documents(
+    rtxn: &RoTxn,
+    index: &Index,
+    format: ExportFormat,
+    filter: Option<&serde_json::Value>,
+    fields: Option<&[String]>,
+    mut writer: impl Write,
+    must_stop_processing: &MustStopProcessing,
+) -> Result<u64> {
+    let filter = match filter {
+        Some(filter) => Filter::from_json(filter)?,
+        None => None,
+    };
+    let candidates = match filter {
+        Some(filter) => filter.evaluate(rtxn, index).map_err(invalid_document_filter)?,
+        None => index.documents_ids(rtxn)?,
+    };
+
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
+    let columns: Vec<String> = match fields {
+        Some(fields) => fields.to_vec(),
+        None => fields_ids_map.iter().map(|(_, name)| name.to_string()).collect(),
+    };
+
+    let documents = index.iter_documents(rtxn, candidates)?.map(|ret| -> Result<milli::Object> {
+        if must_stop_processing.get() {
+            return Err(Error::AbortedTask);
+        }
+        let (_id, doc) = ret?;
+        let document = milli::obkv_to_json(&all_fields, &fields_ids_map, doc)?;
+        Ok(match fields {
+            Some(fields) => {
+                permissive_json_pointer::select_values(&document, fields.iter().map(String::as_str))
+            }
+            None => document,
+        })
+    });
+
+    let mut count = 0;
+    match format {
+        ExportFormat::Ndjson => {
+            for document in documents {
+                serde_json::to_writer(&mut writer, &document?).map_err(std::io::Error::from)?;
+                writer.write_all(b"\n")?;
+                count += 1;
+            }
+        }
+        ExportFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(&mut writer);
+            csv_writer.write_record(&columns).map_err(std::io::Error::from)?;
+            for document in documents {
+                let document = document?;
+                let record = columns.iter().map(|column| csv_cell(&document, column));
+                csv_writer.write_record(record).map_err(std::io::Error::from)?;
+                count += 1;
+            }
+            csv_writer.flush()?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Returns the CSV representation of the value of the field, following the dots of the
+/// nested fields. The arrays and objects are written as JSON.
+fn csv_cell(document: &milli::Object, field: &str) -> String {
+    let value = document.get(field).or_else(|| {
+        let mut parts = field.split('.');
+        let first = document.get(parts.next()?)?;
+        parts.try_fold(first, |value, part| value.get(part))
+    });
+
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// The time allowed to connect to the URL an export is uploaded to.
+const EXPORT_UPLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// The time allowed to upload an export, after which its task fails.
+const EXPORT_UPLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// The number of chunks of the export waiting to be uploaded.
+const EXPORT_UPLOAD_QUEUE_SIZE: usize = 64;
+
+/// Uploads the export with a `PUT` request, e.g. to an S3 presigned URL, and returns the
+/// number of exported documents.
+///
+/// The `length` bytes written by `export` are streamed to the URL as they are written, and
+/// the upload stops as soon as the task is canceled.
+fn upload_export(
+    url: &str,
+    length: u64,
+    must_stop_processing: &MustStopProcessing,
+    export: impl FnOnce(&mut dyn Write) -> Result<u64>,
+) -> Result<u64> {
+    let upload_failed = |error: reqwest::Error| Error::IndexExportUploadFailed {
+        url: url_without_query(url).to_string(),
+        // the error must not leak the query string, which may contain credentials
+        error: error.without_url().to_string(),
+    };
+
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(EXPORT_UPLOAD_CONNECT_TIMEOUT)
+        .timeout(EXPORT_UPLOAD_TIMEOUT)
+        .build()
+        .map_err(upload_failed)?;
+
+    let (sender, receiver) = mpsc::sync_channel(EXPORT_UPLOAD_QUEUE_SIZE);
+    let body = reqwest::blocking::Body::sized(ChannelReader::new(receiver), length);
+    let request = client.put(url).body(body);
+
+    // the documents are exported on this thread, as the transaction of the index can't be sent
+    std::thread::scope(|scope| {
+        let upload =
+            scope.spawn(move || request.send().and_then(|response| response.error_for_status()));
+
+        let mut writer = BufWriter::new(ChannelWriter { sender, closed: false });
+        let exported = export(&mut writer).and_then(|count| Ok(writer.flush().map(|()| count)?));
+        let upload_stopped = writer.get_ref().closed;
+        // the end of the body
+        drop(writer);
+
+        let uploaded = upload.join().map_err(|_| Error::ProcessBatchPanicked)?;
+        if must_stop_processing.get() {
+            return Err(Error::AbortedTask);
+        }
+        match exported {
+            // the export failed on its own, not because the upload stopped reading it
+            Err(error) if !upload_stopped => Err(error),
+            exported => {
+                uploaded.map_err(upload_failed)?;
+                exported
+            }
+        }
     })
 }
+
+/// Reads the chunks of bytes sent by a [`ChannelWriter`], until it's dropped.
+struct ChannelReader {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl ChannelReader {
+    fn new(receiver: mpsc::Receiver<Vec<u8>>) -> Self {
+        ChannelReader { receiver, chunk: Vec::new(), position: 0 }
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                Err(mpsc::RecvError) => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len() - self.position);
+        buf[..len].copy_from_slice(&self.chunk[self.position..][..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// Sends the written bytes to a [`ChannelReader`], remembering whether it was dropped.
+struct ChannelWriter {
+    sender: mpsc::SyncSender<Vec<u8>>,
+    closed: bool,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.sender.send(buf.to_vec()).is_err() {
+            self.closed = true;
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "the upload stopped"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Counts the written bytes without keeping them.
+#[derive(Default)]
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Whether the file is an export of the index, named `{index_uid}-{task_uid}.{extension}`.
+fn is_export_of(file_name: &str, index_uid: &str) -> bool {
+    let Some(rest) = file_name.strip_prefix(index_uid).and_then(|rest| rest.strip_prefix('-'))
+    else {
+        return false;
+    };
+    match rest.split_once('.') {
+        Some((task_uid, extension)) => {
+            task_uid.parse::<TaskId>().is_ok()
+                && [ExportFormat::Ndjson, ExportFormat::Csv]
+                    .iter()
+                    .any(|format| format.extension() == extension)
+        }
+        None => false,
+    }
+}
+
+/// Returns the URL without its query string and fragment.
+pub(crate) fn url_without_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
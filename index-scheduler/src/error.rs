@@ -110,6 +110,12 @@ pub enum Error {
     TaskCancelationWithEmptyQuery,
     #[error("Aborted task")]
     AbortedTask,
+    #[error("Failed to upload the export of the index to `{url}`: {error}.")]
+    IndexExportUploadFailed { url: String, error: String },
+    #[error("The export of the index can't be uploaded to `{0}`, it doesn't start with one of the `--experimental-export-allowed-urls`.")]
+    IndexExportUrlNotAllowed(String),
+    #[error("The changes following the cursor `{after}` are no longer retained. The oldest retained change has the cursor `{oldest}`.")]
+    ChangeFeedCursorExpired { after: u64, oldest: u64 },
 
     #[error(transparent)]
     Dump(#[from] dump::Error),
@@ -178,6 +184,9 @@ impl Error {
             | Error::TaskDeletionWithEmptyQuery
             | Error::TaskCancelationWithEmptyQuery
             | Error::AbortedTask
+            | Error::IndexExportUploadFailed { .. }
+            | Error::IndexExportUrlNotAllowed(_)
+            | Error::ChangeFeedCursorExpired { .. }
             | Error::Dump(_)
             | Error::Heed(_)
             | Error::Milli(_)
@@ -220,6 +229,9 @@ impl ErrorCode for Error {
             Error::TaskNotFound(_) => Code::TaskNotFound,
             Error::TaskDeletionWithEmptyQuery => Code::MissingTaskFilters,
             Error::TaskCancelationWithEmptyQuery => Code::MissingTaskFilters,
+            Error::IndexExportUploadFailed { .. } => Code::IndexExportUploadFailed,
+            Error::IndexExportUrlNotAllowed(_) => Code::InvalidIndexExportUrl,
+            Error::ChangeFeedCursorExpired { .. } => Code::InvalidChangeFeedAfter,
             // TODO: not sure of the Code to use
             Error::NoSpaceLeftInTaskQueue => Code::NoSpaceLeftOnDevice,
             Error::Dump(e) => e.error_code(),
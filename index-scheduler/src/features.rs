@@ -69,6 +69,45 @@ impl RoFeatures {
         }
     }
 
+    pub fn check_search_analytics(&self, disabled_action: &'static str) -> Result<()> {
+        if self.runtime.search_analytics {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action,
+                feature: "search analytics",
+                issue_link: "https://github.com/meilisearch/product/discussions/836",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_change_feed(&self, disabled_action: &'static str) -> Result<()> {
+        if self.runtime.change_feed {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action,
+                feature: "change feed",
+                issue_link: "https://github.com/meilisearch/product/discussions/841",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_chat_completions(&self, disabled_action: &'static str) -> Result<()> {
+        if self.runtime.chat_completions {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action,
+                feature: "chat completions",
+                issue_link: "https://github.com/meilisearch/product/discussions/835",
+            }
+            .into())
+        }
+    }
+
     pub fn check_puffin(&self) -> Result<()> {
         if self.runtime.export_puffin_reports {
             Ok(())
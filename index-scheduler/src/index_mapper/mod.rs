@@ -5,7 +5,7 @@ use std::{fs, thread};
 
 use log::error;
 use meilisearch_types::heed::types::{SerdeJson, Str};
-use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use meilisearch_types::heed::{CompactionOption, Database, Env, RoTxn, RwTxn};
 use meilisearch_types::milli::update::IndexerConfig;
 use meilisearch_types::milli::{FieldDistribution, Index};
 use serde::{Deserialize, Serialize};
@@ -192,6 +192,41 @@ impl IndexMapper {
         }
     }
 
+    /// Creates the `destination` index from a compacted copy of the environment of the `source` index.
+    ///
+    /// The copy keeps everything stored in the source index, including its creation and update dates.
+    pub fn copy_index(&self, mut wtxn: RwTxn, source: &str, destination: &str) -> Result<Index> {
+        let source_index = self.index(&wtxn, source)?;
+        if self.exists(&wtxn, destination)? {
+            return Err(Error::IndexAlreadyExists(destination.to_string()));
+        }
+
+        let uuid = Uuid::new_v4();
+        let index_path = self.base_path.join(uuid.to_string());
+        fs::create_dir_all(&index_path)?;
+        // The copy is done from a read transaction of the source index, any write done after
+        // this point will not be part of the destination index.
+        if let Err(e) =
+            source_index.copy_to_file(index_path.join("data.mdb"), CompactionOption::Enabled)
+        {
+            let _ = fs::remove_dir_all(&index_path);
+            return Err(e.into());
+        }
+
+        self.index_mapping.put(&mut wtxn, destination, &uuid)?;
+        let index = self.index_map.write().unwrap().create(
+            &uuid,
+            &index_path,
+            None,
+            self.enable_mdb_writemap,
+            self.index_base_map_size,
+        )?;
+
+        wtxn.commit()?;
+
+        Ok(index)
+    }
+
     /// Removes the index from the mapping table and the in-memory index map
     /// but keeps the associated tasks.
     pub fn delete_index(&self, mut wtxn: RwTxn, name: &str) -> Result<()> {
@@ -208,6 +243,65 @@ impl IndexMapper {
 
         wtxn.commit()?;
 
+        self.delete_index_env(uuid, name);
+
+        Ok(())
+    }
+
+    /// Creates a hidden copy of the environment of the `name` index.
+    ///
+    /// The shadow index is opened but is not part of the mapping table: it can be updated
+    /// without impacting the `name` index and then swapped in with [`Self::swap_shadow_index`].
+    pub fn create_shadow_index(&self, rtxn: &RoTxn, name: &str) -> Result<(Uuid, Index)> {
+        let index = self.index(rtxn, name)?;
+
+        let uuid = Uuid::new_v4();
+        let index_path = self.base_path.join(uuid.to_string());
+        fs::create_dir_all(&index_path)?;
+        if let Err(e) = index.copy_to_file(index_path.join("data.mdb"), CompactionOption::Enabled) {
+            let _ = fs::remove_dir_all(&index_path);
+            return Err(e.into());
+        }
+
+        // The shadow index is opened with the map size of the original index so that it
+        // benefits from the resizes that were done on it.
+        let map_size = index.map_size().max(self.index_base_map_size);
+        let shadow = self.index_map.write().unwrap().create(
+            &uuid,
+            &index_path,
+            None,
+            self.enable_mdb_writemap,
+            map_size,
+        )?;
+
+        Ok((uuid, shadow))
+    }
+
+    /// Atomically replaces the `name` index by the shadow index identified by `shadow_uuid`
+    /// and deletes the previous environment of the index.
+    pub fn swap_shadow_index(&self, mut wtxn: RwTxn, name: &str, shadow_uuid: Uuid) -> Result<()> {
+        let uuid = self
+            .index_mapping
+            .get(&wtxn, name)?
+            .ok_or_else(|| Error::IndexNotFound(name.to_string()))?;
+
+        self.index_stats.delete(&mut wtxn, &uuid)?;
+        self.index_mapping.put(&mut wtxn, name, &shadow_uuid)?;
+
+        wtxn.commit()?;
+
+        self.delete_index_env(uuid, name);
+
+        Ok(())
+    }
+
+    /// Deletes a shadow index that will not be swapped in.
+    pub fn delete_shadow_index(&self, shadow_uuid: Uuid, name: &str) {
+        self.delete_index_env(shadow_uuid, name);
+    }
+
+    /// Closes the environment of the index and removes it from the disk in a separate thread.
+    fn delete_index_env(&self, uuid: Uuid, name: &str) {
         let mut tries = 0;
         // Attempts to remove the index from the in-memory index map in a loop.
         //
@@ -241,7 +335,7 @@ impl IndexMapper {
                     reopen.close(&mut self.index_map.write().unwrap());
                     continue;
                 }
-                Err(None) => return Ok(()),
+                Err(None) => return,
             }
         };
 
@@ -269,8 +363,6 @@ impl IndexMapper {
                 index_map.write().unwrap().end_deletion(&uuid);
             })
             .unwrap();
-
-        Ok(())
     }
 
     pub fn exists(&self, rtxn: &RoTxn, name: &str) -> Result<bool> {
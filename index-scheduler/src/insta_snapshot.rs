@@ -4,7 +4,7 @@ use std::fmt::Write;
 use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str};
 use meilisearch_types::heed::{Database, RoTxn};
 use meilisearch_types::milli::{CboRoaringBitmapCodec, RoaringBitmapCodec, BEU32};
-use meilisearch_types::tasks::{Details, Task};
+use meilisearch_types::tasks::{Details, KindWithContent, Task};
 use roaring::RoaringBitmap;
 
 use crate::index_mapper::IndexMapper;
@@ -28,6 +28,8 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
         started_at,
         finished_at,
         index_mapper,
+        chat_settings: _,
+        search_analytics: _,
         features: _,
         max_number_of_tasks: _,
         max_number_of_batched_tasks: _,
@@ -35,6 +37,8 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
         wake_up: _,
         dumps_path: _,
         snapshots_path: _,
+        exports_path: _,
+        export_allowed_urls: _,
         auth_path: _,
         version_file_path: _,
         test_breakpoint_sdr: _,
@@ -42,6 +46,7 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
         run_loop_iteration: _,
         currently_updating_index: _,
         embedders: _,
+        write_epochs: _,
     } = scheduler;
 
     let rtxn = env.read_txn().unwrap();
@@ -162,19 +167,57 @@ pub fn snapshot_task(task: &Task) -> String {
     if let Some(details) = details {
         snap.push_str(&format!("details: {}, ", &snapshot_details(details)));
     }
-    snap.push_str(&format!("kind: {kind:?}"));
+    snap.push_str(&format!("kind: {}", snapshot_kind_with_content(kind)));
 
     snap.push('}');
     snap
 }
 
+fn snapshot_kind_with_content(kind: &KindWithContent) -> String {
+    match kind {
+        KindWithContent::DocumentAdditionOrUpdate {
+            index_uid,
+            primary_key,
+            method,
+            content_file,
+            documents_count,
+            allow_index_creation,
+            skip_invalid_documents,
+        } => {
+            let mut snap = format!("DocumentAdditionOrUpdate {{ index_uid: {index_uid:?}, primary_key: {primary_key:?}, method: {method:?}, content_file: {content_file}, documents_count: {documents_count}, allow_index_creation: {allow_index_creation}");
+            if *skip_invalid_documents {
+                snap.push_str(", skip_invalid_documents: true");
+            }
+            snap.push_str(" }");
+            snap
+        }
+        kind => format!("{kind:?}"),
+    }
+}
+
 fn snapshot_details(d: &Details) -> String {
     match d {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
+            embedding_failures,
+            invalid_documents,
+            outdated_documents,
         } => {
-            format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
+            let mut snap = format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?}");
+            if let Some(embedding_failures) = embedding_failures {
+                snap.push_str(&format!(", embedding_failures: {embedding_failures:?}"));
+            }
+            if let Some(invalid_documents) = invalid_documents {
+                let invalid_documents: Vec<_> =
+                    invalid_documents.iter().map(|doc| (doc.position, &doc.error.message)).collect();
+                snap.push_str(&format!(", invalid_documents: {invalid_documents:?}"));
+            }
+            if let Some(outdated_documents) = outdated_documents {
+                snap.push_str(&format!(", outdated_documents: {outdated_documents}"));
+            }
+            snap.push_str(" }");
+            snap
         }
         Details::SettingsUpdate { settings } => {
             format!("{{ settings: {settings:?} }}")
@@ -186,8 +229,8 @@ fn snapshot_details(d: &Details) -> String {
             provided_ids: received_document_ids,
             deleted_documents,
         } => format!("{{ received_document_ids: {received_document_ids}, deleted_documents: {deleted_documents:?} }}"),
-        Details::DocumentDeletionByFilter { original_filter, deleted_documents } => format!(
-           "{{ original_filter: {original_filter}, deleted_documents: {deleted_documents:?} }}"
+        Details::DocumentDeletionByFilter { original_filter, deleted_documents, deleted_documents_sample } => format!(
+           "{{ original_filter: {original_filter}, deleted_documents: {deleted_documents:?}, deleted_documents_sample: {deleted_documents_sample:?} }}"
         ),
         Details::ClearAll { deleted_documents } => {
             format!("{{ deleted_documents: {deleted_documents:?} }}")
@@ -212,6 +255,12 @@ fn snapshot_details(d: &Details) -> String {
         Details::IndexSwap { swaps } => {
             format!("{{ swaps: {swaps:?} }}")
         }
+        Details::IndexCopy { destination } => {
+            format!("{{ destination: {destination:?} }}")
+        }
+        Details::IndexExport { original_filter, exported_documents, destination } => {
+            format!("{{ original_filter: {original_filter:?}, exported_documents: {exported_documents:?}, destination: {destination:?} }}")
+        }
     }
 }
 
@@ -26,8 +26,10 @@ mod index_mapper;
 #[cfg(test)]
 mod insta_snapshot;
 mod lru;
+mod search_analytics;
 mod utils;
 pub mod uuid_codec;
+mod write_epochs;
 
 pub type Result<T> = std::result::Result<T, Error>;
 pub type TaskId = u32;
@@ -45,11 +47,13 @@ use dump::{KindDump, TaskDump, UpdateFile};
 pub use error::Error;
 pub use features::RoFeatures;
 use file_store::FileStore;
+use meilisearch_types::chat::ChatCompletionSettings;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::features::{InstanceTogglableFeatures, RuntimeTogglableFeatures};
 use meilisearch_types::heed::byteorder::BE;
 use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str, I128};
 use meilisearch_types::heed::{self, Database, Env, PutFlags, RoTxn, RwTxn};
+use meilisearch_types::milli::change_feed::ChangeFeedEntry;
 use meilisearch_types::milli::documents::DocumentsBatchBuilder;
 use meilisearch_types::milli::update::IndexerConfig;
 use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, EmbeddingConfigs};
@@ -57,6 +61,7 @@ use meilisearch_types::milli::{self, CboRoaringBitmapCodec, Index, RoaringBitmap
 use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
 use puffin::FrameView;
 use roaring::RoaringBitmap;
+pub use search_analytics::QueryAnalytics;
 use synchronoise::SignalEvent;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
@@ -65,6 +70,7 @@ use uuid::Uuid;
 
 use crate::index_mapper::IndexMapper;
 use crate::utils::{check_index_swap_validity, clamp_to_page_size};
+use crate::write_epochs::WriteEpochs;
 
 pub(crate) type BEI128 = I128<BE>;
 
@@ -207,6 +213,7 @@ mod db_name {
     pub const ENQUEUED_AT: &str = "enqueued-at";
     pub const STARTED_AT: &str = "started-at";
     pub const FINISHED_AT: &str = "finished-at";
+    pub const CHAT_SETTINGS: &str = "chat-settings";
 }
 
 #[cfg(test)]
@@ -241,6 +248,10 @@ pub struct IndexSchedulerOptions {
     pub snapshots_path: PathBuf,
     /// The path to the folder containing the dumps.
     pub dumps_path: PathBuf,
+    /// The path to the folder containing the exports of the indexes.
+    pub exports_path: PathBuf,
+    /// The path to the folder containing the search analytics LMDB env.
+    pub search_analytics_path: PathBuf,
     /// The maximum size, in bytes, of the task index.
     pub task_db_size: usize,
     /// The size, in bytes, with which a meilisearch index is opened the first time of each meilisearch index.
@@ -262,6 +273,8 @@ pub struct IndexSchedulerOptions {
     /// If the autobatcher is allowed to automatically batch tasks
     /// it will only batch this defined number of tasks at once.
     pub max_number_of_batched_tasks: usize,
+    /// The prefixes of the URLs the exports of the indexes can be uploaded to.
+    pub export_allowed_urls: Vec<String>,
     /// The experimental features enabled for this instance.
     pub instance_features: InstanceTogglableFeatures,
 }
@@ -310,6 +323,12 @@ pub struct IndexScheduler {
     /// In charge of fetching and setting the status of experimental features.
     features: features::FeatureData,
 
+    /// The settings of the chat completions, stored under a single key.
+    pub(crate) chat_settings: Database<Str, SerdeJson<ChatCompletionSettings>>,
+
+    /// In charge of recording the searches and the clicks on their results.
+    pub(crate) search_analytics: search_analytics::SearchAnalytics,
+
     /// Get a signal when a batch needs to be processed.
     pub(crate) wake_up: Arc<SignalEvent>,
 
@@ -332,6 +351,12 @@ pub struct IndexScheduler {
     /// The path used to create the snapshots.
     pub(crate) snapshots_path: PathBuf,
 
+    /// The path used to write the exports of the indexes.
+    pub(crate) exports_path: PathBuf,
+
+    /// The prefixes of the URLs the exports of the indexes can be uploaded to.
+    pub(crate) export_allowed_urls: Vec<String>,
+
     /// The path to the folder containing the auth LMDB env.
     pub(crate) auth_path: PathBuf,
 
@@ -344,6 +369,9 @@ pub struct IndexScheduler {
 
     embedders: Arc<RwLock<HashMap<EmbedderOptions, Arc<Embedder>>>>,
 
+    /// The number of batches that wrote in each index, to invalidate the cached search results.
+    write_epochs: WriteEpochs,
+
     // ================= test
     // The next entry is dedicated to the tests.
     /// Provide a way to set a breakpoint in multiple part of the scheduler.
@@ -378,6 +406,8 @@ impl IndexScheduler {
             enqueued_at: self.enqueued_at,
             started_at: self.started_at,
             finished_at: self.finished_at,
+            chat_settings: self.chat_settings,
+            search_analytics: self.search_analytics.clone(),
             index_mapper: self.index_mapper.clone(),
             wake_up: self.wake_up.clone(),
             autobatching_enabled: self.autobatching_enabled,
@@ -386,10 +416,13 @@ impl IndexScheduler {
             puffin_frame: self.puffin_frame.clone(),
             snapshots_path: self.snapshots_path.clone(),
             dumps_path: self.dumps_path.clone(),
+            exports_path: self.exports_path.clone(),
+            export_allowed_urls: self.export_allowed_urls.clone(),
             auth_path: self.auth_path.clone(),
             version_file_path: self.version_file_path.clone(),
             currently_updating_index: self.currently_updating_index.clone(),
             embedders: self.embedders.clone(),
+            write_epochs: self.write_epochs.clone(),
             #[cfg(test)]
             test_breakpoint_sdr: self.test_breakpoint_sdr.clone(),
             #[cfg(test)]
@@ -412,6 +445,7 @@ impl IndexScheduler {
         std::fs::create_dir_all(&options.update_file_path)?;
         std::fs::create_dir_all(&options.indexes_path)?;
         std::fs::create_dir_all(&options.dumps_path)?;
+        std::fs::create_dir_all(&options.exports_path)?;
 
         if cfg!(windows) && options.enable_mdb_writemap {
             // programmer error if this happens: in normal use passing the option on Windows is an error in main
@@ -435,11 +469,13 @@ impl IndexScheduler {
         };
 
         let env = heed::EnvOpenOptions::new()
-            .max_dbs(11)
+            .max_dbs(12)
             .map_size(budget.task_db_size)
             .open(options.tasks_path)?;
 
         let features = features::FeatureData::new(&env, options.instance_features)?;
+        let search_analytics =
+            search_analytics::SearchAnalytics::new(&options.search_analytics_path)?;
 
         let file_store = FileStore::new(&options.update_file_path)?;
 
@@ -452,6 +488,7 @@ impl IndexScheduler {
         let enqueued_at = env.create_database(&mut wtxn, Some(db_name::ENQUEUED_AT))?;
         let started_at = env.create_database(&mut wtxn, Some(db_name::STARTED_AT))?;
         let finished_at = env.create_database(&mut wtxn, Some(db_name::FINISHED_AT))?;
+        let chat_settings = env.create_database(&mut wtxn, Some(db_name::CHAT_SETTINGS))?;
         wtxn.commit()?;
 
         // allow unreachable_code to get rids of the warning in the case of a test build.
@@ -467,6 +504,8 @@ impl IndexScheduler {
             enqueued_at,
             started_at,
             finished_at,
+            chat_settings,
+            search_analytics,
             index_mapper: IndexMapper::new(
                 &env,
                 options.indexes_path,
@@ -485,10 +524,13 @@ impl IndexScheduler {
             max_number_of_batched_tasks: options.max_number_of_batched_tasks,
             dumps_path: options.dumps_path,
             snapshots_path: options.snapshots_path,
+            exports_path: options.exports_path,
+            export_allowed_urls: options.export_allowed_urls,
             auth_path: options.auth_path,
             version_file_path: options.version_file_path,
             currently_updating_index: Arc::new(RwLock::new(None)),
             embedders: Default::default(),
+            write_epochs: WriteEpochs::default(),
 
             #[cfg(test)]
             test_breakpoint_sdr,
@@ -1131,6 +1173,7 @@ impl IndexScheduler {
                 None => return Ok(TickOutcome::WaitForSignal),
             };
         let index_uid = batch.index_uid().map(ToOwned::to_owned);
+        let swaps_indexes = matches!(batch, crate::batch::Batch::IndexSwap { .. });
         drop(rtxn);
 
         // 1. store the starting date with the bitmap of processing tasks.
@@ -1157,6 +1200,11 @@ impl IndexScheduler {
             handle.join().unwrap_or(Err(Error::ProcessBatchPanicked))
         };
 
+        // The indexes may have been written even if the batch failed to be entirely processed.
+        if index_uid.is_some() || swaps_indexes {
+            self.write_epochs.increment(index_uid.as_deref());
+        }
+
         // Reset the currently updating index to relinquish the index handle
         *self.currently_updating_index.write().unwrap() = None;
 
@@ -1325,12 +1373,91 @@ impl IndexScheduler {
         self.features.features()
     }
 
+    /// Ensures an export can be uploaded to the URL, i.e. it starts with one of the allowed prefixes.
+    pub fn check_export_url(&self, url: &str) -> Result<()> {
+        if self.export_allowed_urls.iter().any(|prefix| url.starts_with(prefix.as_str())) {
+            Ok(())
+        } else {
+            Err(Error::IndexExportUrlNotAllowed(batch::url_without_query(url).to_string()))
+        }
+    }
+
     pub fn put_runtime_features(&self, features: RuntimeTogglableFeatures) -> Result<()> {
         let wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
         self.features.put_runtime_features(wtxn, features)?;
         Ok(())
     }
 
+    pub fn chat_settings(&self) -> Result<ChatCompletionSettings> {
+        let rtxn = self.read_txn()?;
+        Ok(self.chat_settings.get(&rtxn, db_name::CHAT_SETTINGS)?.unwrap_or_default())
+    }
+
+    pub fn put_chat_settings(&self, settings: &ChatCompletionSettings) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        self.chat_settings.put(&mut wtxn, db_name::CHAT_SETTINGS, settings)?;
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    /// Returns the write epoch of the index, which changes each time a batch writes in the index.
+    pub fn index_write_epoch(&self, index_uid: &str) -> u64 {
+        self.write_epochs.epoch(index_uid)
+    }
+
+    /// Records a search of the query on the index, with its number of hits.
+    pub fn record_search(&self, index_uid: &str, query: &str, hits: usize) -> Result<()> {
+        self.search_analytics.record_search(index_uid, query, hits)
+    }
+
+    /// Records a click on a document returned by a search of the query on the index.
+    pub fn record_search_click(
+        &self,
+        index_uid: &str,
+        query: &str,
+        document_id: &str,
+    ) -> Result<()> {
+        self.search_analytics.record_click(index_uid, query, document_id)
+    }
+
+    /// Returns the most searched queries of the index.
+    pub fn top_queries(&self, index_uid: &str, limit: usize) -> Result<Vec<QueryAnalytics>> {
+        self.search_analytics.queries(index_uid, limit, |stats| stats.searches)
+    }
+
+    /// Returns the queries of the index that were the most often searched without results.
+    pub fn queries_without_results(
+        &self,
+        index_uid: &str,
+        limit: usize,
+    ) -> Result<Vec<QueryAnalytics>> {
+        self.search_analytics.queries(index_uid, limit, |stats| stats.searches_without_results)
+    }
+
+    /// Returns the epoch of the change feed of the index, along with up to `limit` changes
+    /// made to its documents after the `after` cursor, or from the oldest retained change.
+    ///
+    /// The epoch changes when the feed is replaced, e.g. by a swap or the recreation of the
+    /// index, in which case the cursors of the previous feed are meaningless.
+    pub fn document_changes(
+        &self,
+        index_uid: &str,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<(Option<String>, Vec<ChangeFeedEntry>)> {
+        let index = self.index(index_uid)?;
+        let rtxn = index.read_txn()?;
+        if let (Some(after), Some(oldest)) = (after, index.oldest_document_change_cursor(&rtxn)?) {
+            if after.saturating_add(1) < oldest {
+                return Err(Error::ChangeFeedCursorExpired { after, oldest });
+            }
+        }
+
+        let epoch = index.change_feed_epoch(&rtxn)?.map(String::from);
+        let changes = index.document_changes(&rtxn, after, limit)?;
+        Ok((epoch, changes))
+    }
+
     pub(crate) fn delete_persisted_task_data(&self, task: &Task) -> Result<()> {
         match task.content_uuid() {
             Some(content_file) => self.delete_update_file(content_file),
@@ -1345,7 +1472,7 @@ impl IndexScheduler {
     ) -> Result<EmbeddingConfigs> {
         let res: Result<_> = embedding_configs
             .into_iter()
-            .map(|(name, milli::vector::EmbeddingConfig { embedder_options, prompt })| {
+            .map(|(name, milli::vector::EmbeddingConfig { embedder_options, prompt, .. })| {
                 let prompt =
                     Arc::new(prompt.try_into().map_err(meilisearch_types::milli::Error::from)?);
                 // optimistically return existing embedder
@@ -1460,6 +1587,7 @@ impl<'a> Dump<'a> {
                     method,
                     documents_count,
                     allow_index_creation,
+                    skip_invalid_documents,
                 } => KindWithContent::DocumentAdditionOrUpdate {
                     index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
                     primary_key,
@@ -1467,6 +1595,7 @@ impl<'a> Dump<'a> {
                     content_file: content_uuid.ok_or(Error::CorruptedDump)?,
                     documents_count,
                     allow_index_creation,
+                    skip_invalid_documents,
                 },
                 KindDump::DocumentDeletion { documents_ids } => KindWithContent::DocumentDeletion {
                     documents_ids,
@@ -1501,6 +1630,19 @@ impl<'a> Dump<'a> {
                     primary_key,
                 },
                 KindDump::IndexSwap { swaps } => KindWithContent::IndexSwap { swaps },
+                KindDump::IndexCopy { destination } => KindWithContent::IndexCopy {
+                    index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                    destination,
+                },
+                KindDump::IndexExport { format, filter, fields, url } => {
+                    KindWithContent::IndexExport {
+                        index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                        format,
+                        filter,
+                        fields,
+                        url,
+                    }
+                }
                 KindDump::TaskCancelation { query, tasks } => {
                     KindWithContent::TaskCancelation { query, tasks }
                 }
@@ -1677,6 +1819,8 @@ mod tests {
                 indexes_path: tempdir.path().join("indexes"),
                 snapshots_path: tempdir.path().join("snapshots"),
                 dumps_path: tempdir.path().join("dumps"),
+                exports_path: tempdir.path().join("exports"),
+                search_analytics_path: tempdir.path().join("search-analytics"),
                 task_db_size: 1000 * 1000, // 1 MB, we don't use MiB on purpose.
                 index_base_map_size: 1000 * 1000, // 1 MB, we don't use MiB on purpose.
                 enable_mdb_writemap: false,
@@ -1686,6 +1830,7 @@ mod tests {
                 autobatching_enabled: true,
                 max_number_of_tasks: 1_000_000,
                 max_number_of_batched_tasks: usize::MAX,
+                export_allowed_urls: Vec::new(),
                 instance_features: Default::default(),
             };
             configuration(&mut options);
@@ -1753,6 +1898,7 @@ mod tests {
             content_file: Uuid::from_u128(content_file_uuid),
             documents_count,
             allow_index_creation: true,
+            skip_invalid_documents: false,
         }
     }
 
@@ -2187,6 +2333,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "after_register");
@@ -2224,6 +2371,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_second_task");
@@ -2260,6 +2408,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_first_task");
@@ -2314,6 +2463,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_second_task");
@@ -2490,6 +2640,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         index_scheduler
@@ -2648,6 +2799,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -2695,6 +2847,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -2744,6 +2897,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -2794,6 +2948,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -2845,6 +3000,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3343,6 +3499,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_first_task");
@@ -3381,6 +3538,7 @@ mod tests {
                 content_file: uuid,
                 documents_count,
                 allow_index_creation: true,
+                skip_invalid_documents: false,
             })
             .unwrap();
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_first_task");
@@ -3437,6 +3595,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3485,6 +3644,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3539,6 +3699,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3598,6 +3759,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: false,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3662,6 +3824,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3715,6 +3878,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3767,6 +3931,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3828,6 +3993,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3885,6 +4051,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -3966,6 +4133,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -4049,6 +4217,7 @@ mod tests {
                     content_file: uuid,
                     documents_count,
                     allow_index_creation: true,
+                    skip_invalid_documents: false,
                 })
                 .unwrap();
             index_scheduler.assert_internally_consistent();
@@ -4266,6 +4435,8 @@ mod tests {
             "documentAdditionOrUpdate": 0,
             "documentDeletion": 0,
             "dumpCreation": 0,
+            "indexCopy": 0,
+            "indexExport": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
             "indexSwap": 0,
@@ -4297,6 +4468,8 @@ mod tests {
             "documentAdditionOrUpdate": 0,
             "documentDeletion": 0,
             "dumpCreation": 0,
+            "indexCopy": 0,
+            "indexExport": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
             "indexSwap": 0,
@@ -4335,6 +4508,8 @@ mod tests {
             "documentAdditionOrUpdate": 0,
             "documentDeletion": 0,
             "dumpCreation": 0,
+            "indexCopy": 0,
+            "indexExport": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
             "indexSwap": 0,
@@ -4374,6 +4549,8 @@ mod tests {
             "documentAdditionOrUpdate": 0,
             "documentDeletion": 0,
             "dumpCreation": 0,
+            "indexCopy": 0,
+            "indexExport": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
             "indexSwap": 0,
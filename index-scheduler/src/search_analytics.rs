@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use meilisearch_types::heed::types::{SerdeJson, Str};
+use meilisearch_types::heed::{self, Database, Env, RwTxn};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::utils::clamp_to_page_size;
+use crate::{Error, Result};
+
+const SEARCH_ANALYTICS: &str = "search-analytics";
+
+/// What was recorded about the searches of a query on an index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStats {
+    /// The number of times the query was searched.
+    pub searches: u64,
+    /// The number of searches of the query that didn't return any hit.
+    pub searches_without_results: u64,
+    /// The number of clicks on each document returned by the query, by document id.
+    pub clicked_documents: BTreeMap<String, u64>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_searched_at: OffsetDateTime,
+}
+
+/// The statistics of a query, as returned by the aggregation routes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryAnalytics {
+    pub query: String,
+    pub searches: u64,
+    pub searches_without_results: u64,
+    pub clicks: u64,
+    pub clicked_documents: BTreeMap<String, u64>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_searched_at: OffsetDateTime,
+}
+
+/// The maximum size, in bytes, of the search analytics environment.
+const SEARCH_ANALYTICS_DB_SIZE: usize = 1024 * 1024 * 1024; // 1 GiB
+
+/// The interval at which the buffered searches and clicks are written in the database.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Above this number of buffered queries, they are written in the database right away.
+const MAX_BUFFERED_QUERIES: usize = 10_000;
+
+/// The number of queries kept for each index, the least recently searched ones are forgotten.
+const MAX_QUERIES_PER_INDEX: usize = 10_000;
+
+/// The queries that weren't searched for this long are forgotten.
+const RETENTION_PERIOD: time::Duration = time::Duration::days(30);
+
+/// Records the queries searched on the indexes and the documents clicked in their results,
+/// in its own environment keyed by index and normalized query.
+///
+/// The searches and clicks are buffered in memory and regularly written in the database, not
+/// to open a write transaction for each search. The buffered statistics are lost on a crash.
+#[derive(Clone)]
+pub(crate) struct SearchAnalytics {
+    env: Env,
+    queries: Database<Str, SerdeJson<QueryStats>>,
+    buffer: Arc<Mutex<Buffer>>,
+}
+
+/// The searches and clicks recorded since the last flush, by database key.
+struct Buffer {
+    queries: HashMap<String, PendingStats>,
+    last_flush: Instant,
+}
+
+/// The searches and clicks of a query recorded since the last flush.
+#[derive(Default)]
+struct PendingStats {
+    searches: u64,
+    searches_without_results: u64,
+    clicked_documents: BTreeMap<String, u64>,
+    last_searched_at: Option<OffsetDateTime>,
+}
+
+impl SearchAnalytics {
+    pub fn new(path: &Path) -> Result<Self> {
+        std::fs::create_dir_all(path)?;
+        let env = heed::EnvOpenOptions::new()
+            .max_dbs(1)
+            .map_size(clamp_to_page_size(SEARCH_ANALYTICS_DB_SIZE))
+            .open(path)?;
+        let mut wtxn = env.write_txn()?;
+        let queries = env.create_database(&mut wtxn, Some(SEARCH_ANALYTICS))?;
+        wtxn.commit()?;
+
+        let buffer = Buffer { queries: HashMap::new(), last_flush: Instant::now() };
+        Ok(Self { env, queries, buffer: Arc::new(Mutex::new(buffer)) })
+    }
+
+    pub fn record_search(&self, index_uid: &str, query: &str, hits: usize) -> Result<()> {
+        self.record(index_uid, query, |stats| {
+            stats.searches += 1;
+            if hits == 0 {
+                stats.searches_without_results += 1;
+            }
+            stats.last_searched_at = Some(OffsetDateTime::now_utc());
+        })
+    }
+
+    pub fn record_click(&self, index_uid: &str, query: &str, document_id: &str) -> Result<()> {
+        self.record(index_uid, query, |stats| {
+            *stats.clicked_documents.entry(document_id.to_string()).or_default() += 1;
+        })
+    }
+
+    /// Returns the queries of the index, sorted by descending value of the key.
+    pub fn queries(
+        &self,
+        index_uid: &str,
+        limit: usize,
+        key: impl Fn(&QueryStats) -> u64,
+    ) -> Result<Vec<QueryAnalytics>> {
+        self.flush()?;
+
+        let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
+        let prefix = key_prefix(index_uid);
+
+        let mut queries = Vec::new();
+        for result in self.queries.prefix_iter(&rtxn, &prefix)? {
+            let (db_key, stats) = result?;
+            if key(&stats) == 0 {
+                continue;
+            }
+            let query = db_key[prefix.len()..].to_string();
+            queries.push((key(&stats), query, stats));
+        }
+        queries.sort_by(|(left, left_query, _), (right, right_query, _)| {
+            right.cmp(left).then_with(|| left_query.cmp(right_query))
+        });
+
+        Ok(queries
+            .into_iter()
+            .take(limit)
+            .map(|(_, query, stats)| {
+                let QueryStats {
+                    searches,
+                    searches_without_results,
+                    clicked_documents,
+                    last_searched_at,
+                } = stats;
+                QueryAnalytics {
+                    query,
+                    searches,
+                    searches_without_results,
+                    clicks: clicked_documents.values().sum(),
+                    clicked_documents,
+                    last_searched_at,
+                }
+            })
+            .collect())
+    }
+
+    /// Deletes everything recorded about the searches of the index.
+    pub fn delete_index(&self, index_uid: &str) -> Result<()> {
+        let prefix = key_prefix(index_uid);
+        self.buffer.lock().unwrap().queries.retain(|key, _| !key.starts_with(&prefix));
+
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        let mut iter = self.queries.prefix_iter_mut(&mut wtxn, &prefix)?;
+        while iter.next().transpose()?.is_some() {
+            // safety: we don't keep references to the database while deleting the entry.
+            unsafe { iter.del_current()? };
+        }
+        drop(iter);
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    fn record(
+        &self,
+        index_uid: &str,
+        query: &str,
+        f: impl FnOnce(&mut PendingStats),
+    ) -> Result<()> {
+        let query = normalize_query(query);
+        if query.is_empty() {
+            return Ok(());
+        }
+
+        let key = format!("{}{query}", key_prefix(index_uid));
+        let must_flush = {
+            let mut buffer = self.buffer.lock().unwrap();
+            f(buffer.queries.entry(key).or_default());
+            buffer.queries.len() >= MAX_BUFFERED_QUERIES
+                || buffer.last_flush.elapsed() >= FLUSH_INTERVAL
+        };
+
+        if must_flush {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered searches and clicks in the database,
+    /// then forgets the old queries of the indexes they were made on.
+    fn flush(&self) -> Result<()> {
+        let pending = {
+            let mut buffer = self.buffer.lock().unwrap();
+            buffer.last_flush = Instant::now();
+            std::mem::take(&mut buffer.queries)
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        let mut indexes = BTreeSet::new();
+        for (key, pending) in pending {
+            let PendingStats {
+                searches,
+                searches_without_results,
+                clicked_documents,
+                last_searched_at,
+            } = pending;
+            let mut stats = self.queries.get(&wtxn, &key)?.unwrap_or_else(|| QueryStats {
+                searches: 0,
+                searches_without_results: 0,
+                clicked_documents: BTreeMap::new(),
+                last_searched_at: OffsetDateTime::now_utc(),
+            });
+            stats.searches += searches;
+            stats.searches_without_results += searches_without_results;
+            for (document_id, clicks) in clicked_documents {
+                *stats.clicked_documents.entry(document_id).or_default() += clicks;
+            }
+            if let Some(last_searched_at) = last_searched_at {
+                stats.last_searched_at = stats.last_searched_at.max(last_searched_at);
+            }
+            self.queries.put(&mut wtxn, &key, &stats)?;
+
+            if let Some((index_uid, _)) = key.split_once('\0') {
+                indexes.insert(index_uid.to_string());
+            }
+        }
+
+        for index_uid in indexes {
+            self.forget_old_queries(&mut wtxn, &index_uid)?;
+        }
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    /// Forgets the queries of the index that weren't searched during the retention period,
+    /// then the least recently searched ones above the maximum number of queries.
+    fn forget_old_queries(&self, wtxn: &mut RwTxn, index_uid: &str) -> Result<()> {
+        let oldest = OffsetDateTime::now_utc() - RETENTION_PERIOD;
+
+        let mut queries = Vec::new();
+        for result in self.queries.prefix_iter(wtxn, &key_prefix(index_uid))? {
+            let (key, stats) = result?;
+            queries.push((stats.last_searched_at, key.to_string()));
+        }
+        // the most recently searched queries first
+        queries.sort_unstable_by(|left, right| right.cmp(left));
+
+        for (position, (last_searched_at, key)) in queries.into_iter().enumerate() {
+            if position >= MAX_QUERIES_PER_INDEX || last_searched_at < oldest {
+                self.queries.delete(wtxn, &key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The queries only differing by their case or their surrounding spaces are recorded together.
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn key_prefix(index_uid: &str) -> String {
+    format!("{index_uid}\0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffered_searches_are_flushed_and_old_queries_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let analytics = SearchAnalytics::new(dir.path()).unwrap();
+
+        analytics.record_search("movies", "Batman ", 0).unwrap();
+        analytics.record_search("movies", "batman", 3).unwrap();
+        analytics.record_click("movies", "batman", "42").unwrap();
+        // the searches are only buffered until the next flush
+        let rtxn = analytics.env.read_txn().unwrap();
+        assert!(analytics.queries.get(&rtxn, "movies\0batman").unwrap().is_none());
+        drop(rtxn);
+
+        let mut wtxn = analytics.env.write_txn().unwrap();
+        let forgotten = QueryStats {
+            searches: 10,
+            searches_without_results: 0,
+            clicked_documents: BTreeMap::new(),
+            last_searched_at: OffsetDateTime::now_utc() - RETENTION_PERIOD * 2,
+        };
+        analytics.queries.put(&mut wtxn, "movies\0joker", &forgotten).unwrap();
+        wtxn.commit().unwrap();
+
+        let queries = analytics.queries("movies", 10, |stats| stats.searches).unwrap();
+        let queries: Vec<_> = queries
+            .iter()
+            .map(|q| (q.query.as_str(), q.searches, q.searches_without_results, q.clicks))
+            .collect();
+        assert_eq!(queries, [("batman", 2, 1, 1)]);
+
+        analytics.delete_index("movies").unwrap();
+        assert!(analytics.queries("movies", 10, |stats| stats.searches).unwrap().is_empty());
+    }
+}
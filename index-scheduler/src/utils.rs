@@ -88,7 +88,11 @@ impl IndexScheduler {
             }
         }
 
-        self.all_tasks.put(wtxn, &task.uid, task)?;
+        // the finished tasks don't keep the secrets they needed to be processed
+        match task.kind.without_secrets().filter(|_| task.finished_at.is_some()) {
+            Some(kind) => self.all_tasks.put(wtxn, &task.uid, &Task { kind, ..task.clone() })?,
+            None => self.all_tasks.put(wtxn, &task.uid, task)?,
+        }
         Ok(())
     }
 
@@ -245,6 +249,11 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         K::IndexDeletion { index_uid } => index_uids.push(index_uid),
         K::IndexCreation { index_uid, .. } => index_uids.push(index_uid),
         K::IndexUpdate { index_uid, .. } => index_uids.push(index_uid),
+        K::IndexExport { index_uid, .. } => index_uids.push(index_uid),
+        K::IndexCopy { index_uid, destination } => {
+            index_uids.push(index_uid);
+            index_uids.push(destination);
+        }
         K::IndexSwap { swaps } => {
             for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
                 if lhs == swap.0 || lhs == swap.1 {
@@ -270,6 +279,9 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
             }
         }
     }
+    if let Some(Details::IndexCopy { destination }) = &mut task.details {
+        index_uids.push(destination);
+    }
     for index_uid in index_uids {
         if index_uid == swap.0 {
             *index_uid = swap.1.to_owned();
@@ -397,7 +409,27 @@ impl IndexScheduler {
                             assert_eq!(&sw1, sw2);
                         }
                     }
-                    Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+                    Details::IndexCopy { destination: d1 } => {
+                        if let KindWithContent::IndexCopy { destination: d2, .. } = &kind {
+                            assert_eq!(&d1, d2);
+                        }
+                    }
+                    Details::IndexExport { exported_documents, destination, .. } => {
+                        assert_eq!(kind.as_kind(), Kind::IndexExport);
+                        match status {
+                            Status::Enqueued | Status::Processing => (),
+                            Status::Succeeded => assert!(destination.is_some()),
+                            Status::Failed | Status::Canceled => {
+                                assert_eq!(exported_documents, Some(0));
+                                assert!(destination.is_none());
+                            }
+                        }
+                    }
+                    Details::DocumentAdditionOrUpdate {
+                        received_documents,
+                        indexed_documents,
+                        ..
+                    } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentAdditionOrUpdate);
                         match indexed_documents {
                             Some(indexed_documents) => {
@@ -460,7 +492,7 @@ impl IndexScheduler {
                             }
                         }
                     }
-                    Details::DocumentDeletionByFilter { deleted_documents, original_filter: _ } => {
+                    Details::DocumentDeletionByFilter { deleted_documents, .. } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentDeletion);
                         let (index_uid, _) = if let KindWithContent::DocumentDeletionByFilter {
                             ref index_uid,
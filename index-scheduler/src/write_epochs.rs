@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Counts the batches that wrote in each index since the engine started.
+///
+/// The results computed on an index, such as the cached search results, are only
+/// valid as long as the write epoch of the index they were computed on doesn't change.
+#[derive(Debug, Default, Clone)]
+pub struct WriteEpochs {
+    inner: Arc<RwLock<Epochs>>,
+}
+
+#[derive(Debug, Default)]
+struct Epochs {
+    /// Incremented by the batches that may write in several indexes, such as the index swaps.
+    global: u64,
+    indexes: HashMap<String, u64>,
+}
+
+impl WriteEpochs {
+    /// Returns the write epoch of the index, that only ever increases.
+    pub fn epoch(&self, index_uid: &str) -> u64 {
+        let epochs = self.inner.read().unwrap();
+        epochs.global + epochs.indexes.get(index_uid).copied().unwrap_or_default()
+    }
+
+    /// Increments the write epoch of the index written by a batch,
+    /// or of all the indexes when the batch isn't about a single index.
+    pub fn increment(&self, index_uid: Option<&str>) {
+        let mut epochs = self.inner.write().unwrap();
+        match index_uid {
+            Some(index_uid) => *epochs.indexes.entry(index_uid.to_string()).or_default() += 1,
+            None => epochs.global += 1,
+        }
+    }
+}
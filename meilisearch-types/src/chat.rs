@@ -0,0 +1,88 @@
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+/// The placeholder replaced by the documents retrieved by the searches in the system prompt.
+pub const DOCUMENTS_PLACEHOLDER: &str = "{{documents}}";
+
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful assistant answering the questions of \
+the user with the following documents. Cite the documents you rely on with their number between \
+brackets, e.g. [1]. If the documents don't contain the answer, say that you don't know.\n\n\
+{{documents}}";
+
+/// The settings of the chat completions, shared by all the indexes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatCompletionSettings {
+    pub source: ChatCompletionSource,
+    /// The URL of the OpenAI-compatible API, the default URL of the source when `None`.
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    /// The system prompt, where `{{documents}}` is replaced by the documents retrieved by the
+    /// searches, [`DEFAULT_SYSTEM_PROMPT`] when `None`.
+    pub system_prompt: Option<String>,
+}
+
+impl ChatCompletionSettings {
+    /// Masks the API key, so that the settings can be returned without leaking it.
+    pub fn hide_secrets(&mut self) {
+        if let Some(api_key) = &mut self.api_key {
+            hide_secret(api_key);
+        }
+    }
+}
+
+/// Keeps the first characters of the secret, as many as its length allows, to help identify it.
+fn hide_secret(secret: &mut String) {
+    let (kept, mask) = match secret.chars().count() {
+        x if x < 10 => (0, "XXX..."),
+        x if x < 20 => (2, "XXXX..."),
+        x if x < 30 => (3, "XXXXX..."),
+        _ => (5, "XXXXXX..."),
+    };
+    *secret = secret.chars().take(kept).chain(mask.chars()).collect();
+}
+
+/// The providers of chat completions, all exposing an OpenAI-compatible API.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum ChatCompletionSource {
+    #[default]
+    OpenAi,
+    Mistral,
+    Ollama,
+    VLlm,
+}
+
+impl ChatCompletionSource {
+    /// The URL of the API of the source, `None` when the source must be given a URL.
+    pub fn default_base_url(&self) -> Option<&'static str> {
+        match self {
+            ChatCompletionSource::OpenAi => Some("https://api.openai.com/v1"),
+            ChatCompletionSource::Mistral => Some("https://api.mistral.ai/v1"),
+            ChatCompletionSource::Ollama => Some("http://localhost:11434/v1"),
+            ChatCompletionSource::VLlm => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hide_secret_on_char_boundaries() {
+        let mut secret = String::from("sk-1234567890abcdef");
+        hide_secret(&mut secret);
+        assert_eq!(secret, "skXXXX...");
+
+        let mut secret = String::from("é€é€é€é€é€é€");
+        hide_secret(&mut secret);
+        assert_eq!(secret, "é€XXXX...");
+
+        let mut secret = String::from("€€€");
+        hide_secret(&mut secret);
+        assert_eq!(secret, "XXX...");
+    }
+}
@@ -150,11 +150,24 @@ make_missing_field_convenience_builder!(MissingApiKeyActions, missing_api_key_ac
 make_missing_field_convenience_builder!(MissingApiKeyExpiresAt, missing_api_key_expires_at);
 make_missing_field_convenience_builder!(MissingApiKeyIndexes, missing_api_key_indexes);
 make_missing_field_convenience_builder!(MissingSwapIndexes, missing_swap_indexes);
+make_missing_field_convenience_builder!(
+    MissingIndexCopyDestination,
+    missing_index_copy_destination
+);
 make_missing_field_convenience_builder!(MissingDocumentFilter, missing_document_filter);
 make_missing_field_convenience_builder!(
     MissingFacetSearchFacetName,
     missing_facet_search_facet_name
 );
+make_missing_field_convenience_builder!(
+    MissingChatCompletionMessages,
+    missing_chat_completion_messages
+);
+make_missing_field_convenience_builder!(MissingSearchAnalyticsQ, missing_search_analytics_q);
+make_missing_field_convenience_builder!(
+    MissingSearchAnalyticsDocumentId,
+    missing_search_analytics_document_id
+);
 
 // Integrate a sub-error into a [`DeserrError`] by taking its error message but using
 // the default error code (C) from `Self`
@@ -189,3 +202,8 @@ merge_with_error_impl_take_error_message!(ParseTaskKindError);
 merge_with_error_impl_take_error_message!(ParseTaskStatusError);
 merge_with_error_impl_take_error_message!(IndexUidFormatError);
 merge_with_error_impl_take_error_message!(InvalidSearchSemanticRatio);
+merge_with_error_impl_take_error_message!(InvalidSearchGeoReferencePoint);
+merge_with_error_impl_take_error_message!(InvalidSearchAttributeBoosts);
+merge_with_error_impl_take_error_message!(InvalidSearchRankingScoreThreshold);
+merge_with_error_impl_take_error_message!(InvalidSearchSearchAfter);
+merge_with_error_impl_take_error_message!(InvalidSearchAggregations);
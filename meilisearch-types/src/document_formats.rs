@@ -1,10 +1,14 @@
 use std::fmt::{self, Debug, Display};
 use std::fs::File;
-use std::io::{self, Seek, Write};
+use std::io::{self, BufReader, Seek, Write};
 use std::marker::PhantomData;
 
+use encoding_rs::{UTF_16BE, UTF_16LE};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
 use memmap2::MmapOptions;
-use milli::documents::{DocumentsBatchBuilder, Error};
+use milli::documents::{
+    DocumentsBatchBuilder, Error, NdJsonDocumentsStream, DEFAULT_CSV_ARRAY_DELIMITER,
+};
 use milli::Object;
 use serde::de::{SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer};
@@ -18,7 +22,35 @@ type Result<T> = std::result::Result<T, DocumentFormatError>;
 pub enum PayloadType {
     Ndjson,
     Json,
-    Csv { delimiter: u8 },
+    Csv(CsvOptions),
+    Parquet,
+    MessagePack,
+    Avro,
+}
+
+/// The characters used to read the CSV payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The character separating the fields of a record.
+    pub delimiter: u8,
+    /// The character quoting the fields.
+    pub quote: u8,
+    /// The character escaping the quotes in the quoted fields, when they are not escaped
+    /// by doubling them.
+    pub escape: Option<u8>,
+    /// The character separating the values of the array columns, e.g. `genres:string[]`.
+    pub array_delimiter: u8,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            array_delimiter: DEFAULT_CSV_ARRAY_DELIMITER,
+        }
+    }
 }
 
 impl fmt::Display for PayloadType {
@@ -26,7 +58,10 @@ impl fmt::Display for PayloadType {
         match self {
             PayloadType::Ndjson => f.write_str("ndjson"),
             PayloadType::Json => f.write_str("json"),
-            PayloadType::Csv { .. } => f.write_str("csv"),
+            PayloadType::Csv(_) => f.write_str("csv"),
+            PayloadType::Parquet => f.write_str("parquet"),
+            PayloadType::MessagePack => f.write_str("msgpack"),
+            PayloadType::Avro => f.write_str("avro"),
         }
     }
 }
@@ -103,12 +138,68 @@ impl ErrorCode for DocumentFormatError {
     }
 }
 
+/// Returns a reader over the payload as UTF-8 text, skipping the byte order mark and
+/// transcoding the UTF-16 payloads on the fly, like the files exported by Excel and other
+/// Windows tools. The invalid UTF-16 sequences are replaced by the replacement character.
+///
+/// The UTF-16 payloads without a byte order mark are recognized by their first character,
+/// which is expected to be an ASCII one: a `[` or a `{` for JSON or the start of a CSV header.
+fn utf8_payload(bytes: &[u8]) -> BufReader<DecodeReaderBytes<&[u8], Vec<u8>>> {
+    let encoding = match bytes {
+        [first, 0, ..] if *first != 0 => Some(UTF_16LE),
+        [0, second, ..] if *second != 0 => Some(UTF_16BE),
+        _ => None,
+    };
+    BufReader::new(DecodeReaderBytesBuilder::new().encoding(encoding).build(bytes))
+}
+
 /// Reads CSV from input and write an obkv batch to writer.
-pub fn read_csv(file: &File, writer: impl Write + Seek, delimiter: u8) -> Result<u64> {
+pub fn read_csv(file: &File, writer: impl Write + Seek, options: CsvOptions) -> Result<u64> {
+    let mut builder = DocumentsBatchBuilder::new(writer);
+    let mmap = unsafe { MmapOptions::new().map(file)? };
+    let csv = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .escape(options.escape)
+        .from_reader(utf8_payload(&mmap));
+    builder
+        .append_csv_with_array_delimiter(csv, options.array_delimiter)
+        .map_err(|e| (PayloadType::Csv(options), e))?;
+
+    let count = builder.documents_count();
+    let _ = builder.into_inner().map_err(DocumentFormatError::Io)?;
+
+    Ok(count as u64)
+}
+
+/// Reads Parquet from input and write an obkv batch to writer.
+pub fn read_parquet(file: &File, writer: impl Write + Seek) -> Result<u64> {
+    let mut builder = DocumentsBatchBuilder::new(writer);
+    builder.append_parquet(file.try_clone()?).map_err(|e| (PayloadType::Parquet, e))?;
+
+    let count = builder.documents_count();
+    let _ = builder.into_inner().map_err(DocumentFormatError::Io)?;
+
+    Ok(count as u64)
+}
+
+/// Reads MessagePack from input and write an obkv batch to writer.
+pub fn read_msgpack(file: &File, writer: impl Write + Seek) -> Result<u64> {
+    let mut builder = DocumentsBatchBuilder::new(writer);
+    let mmap = unsafe { MmapOptions::new().map(file)? };
+    builder.append_msgpack(mmap.as_ref()).map_err(|e| (PayloadType::MessagePack, e))?;
+
+    let count = builder.documents_count();
+    let _ = builder.into_inner().map_err(DocumentFormatError::Io)?;
+
+    Ok(count as u64)
+}
+
+/// Reads an Avro object container file from input and write an obkv batch to writer.
+pub fn read_avro(file: &File, writer: impl Write + Seek) -> Result<u64> {
     let mut builder = DocumentsBatchBuilder::new(writer);
     let mmap = unsafe { MmapOptions::new().map(file)? };
-    let csv = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(mmap.as_ref());
-    builder.append_csv(csv).map_err(|e| (PayloadType::Csv { delimiter }, e))?;
+    builder.append_avro(mmap.as_ref()).map_err(|e| (PayloadType::Avro, e))?;
 
     let count = builder.documents_count();
     let _ = builder.into_inner().map_err(DocumentFormatError::Io)?;
@@ -120,7 +211,7 @@ pub fn read_csv(file: &File, writer: impl Write + Seek, delimiter: u8) -> Result
 pub fn read_json(file: &File, writer: impl Write + Seek) -> Result<u64> {
     let mut builder = DocumentsBatchBuilder::new(writer);
     let mmap = unsafe { MmapOptions::new().map(file)? };
-    let mut deserializer = serde_json::Deserializer::from_slice(&mmap);
+    let mut deserializer = serde_json::Deserializer::from_reader(utf8_payload(&mmap));
 
     match array_each(&mut deserializer, |obj| builder.append_json_object(&obj)) {
         // The json data has been deserialized and does not need to be processed again.
@@ -137,7 +228,7 @@ pub fn read_json(file: &File, writer: impl Write + Seek) -> Result<u64> {
                 ));
             }
 
-            let content: Object = serde_json::from_slice(&mmap)
+            let content: Object = serde_json::from_reader(utf8_payload(&mmap))
                 .map_err(Error::Json)
                 .map_err(|e| (PayloadType::Json, e))?;
             builder.append_json_object(&content).map_err(DocumentFormatError::Io)?;
@@ -155,10 +246,9 @@ pub fn read_ndjson(file: &File, writer: impl Write + Seek) -> Result<u64> {
     let mut builder = DocumentsBatchBuilder::new(writer);
     let mmap = unsafe { MmapOptions::new().map(file)? };
 
-    for result in serde_json::Deserializer::from_slice(&mmap).into_iter() {
-        let object = result.map_err(Error::Json).map_err(|e| (PayloadType::Ndjson, e))?;
-        builder.append_json_object(&object).map_err(Into::into).map_err(DocumentFormatError::Io)?;
-    }
+    NdJsonDocumentsStream::new(utf8_payload(&mmap))
+        .append_to(&mut builder)
+        .map_err(|e| (PayloadType::Ndjson, e))?;
 
     let count = builder.documents_count();
     let _ = builder.into_inner().map_err(Into::into).map_err(DocumentFormatError::Io)?;
@@ -179,6 +179,7 @@ ApiKeyAlreadyExists                   , InvalidRequest       , CONFLICT ;
 ApiKeyNotFound                        , InvalidRequest       , NOT_FOUND ;
 BadParameter                          , InvalidRequest       , BAD_REQUEST;
 BadRequest                            , InvalidRequest       , BAD_REQUEST;
+ChatCompletionProviderError           , System               , BAD_GATEWAY;
 DatabaseSizeLimitReached              , Internal             , INTERNAL_SERVER_ERROR;
 DocumentNotFound                      , InvalidRequest       , NOT_FOUND;
 DumpAlreadyProcessing                 , InvalidRequest       , CONFLICT;
@@ -197,6 +198,7 @@ ImmutableIndexUid                     , InvalidRequest       , BAD_REQUEST;
 ImmutableIndexUpdatedAt               , InvalidRequest       , BAD_REQUEST;
 IndexAlreadyExists                    , InvalidRequest       , CONFLICT ;
 IndexCreationFailed                   , Internal             , INTERNAL_SERVER_ERROR;
+IndexExportUploadFailed               , System               , BAD_GATEWAY;
 IndexNotFound                         , InvalidRequest       , NOT_FOUND;
 IndexPrimaryKeyAlreadyExists          , InvalidRequest       , BAD_REQUEST ;
 IndexPrimaryKeyMultipleCandidatesFound, InvalidRequest       , BAD_REQUEST;
@@ -211,50 +213,117 @@ InvalidApiKeyLimit                    , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyName                     , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyOffset                   , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyUid                      , InvalidRequest       , BAD_REQUEST ;
+InvalidChangeFeedAfter                , InvalidRequest       , BAD_REQUEST ;
+InvalidChangeFeedLimit                , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionApiKey           , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionBaseUrl          , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionMessages         , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionModel            , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionQueries          , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionSource           , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionStream           , InvalidRequest       , BAD_REQUEST ;
+InvalidChatCompletionSystemPrompt     , InvalidRequest       , BAD_REQUEST ;
 InvalidContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
+InvalidDocumentCsvArrayDelimiter      , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentCsvDelimiter           , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentCsvEscape              , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentCsvQuote               , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFields                 , InvalidRequest       , BAD_REQUEST ;
 MissingDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentGeoField               , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentGeojsonField           , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorDimensions               , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorsType                    , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentId                     , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentIds                    , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentVersion                , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentSchema                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentLimit                  , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentOffset                 , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentSort                   , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentStrict                 , InvalidRequest       , BAD_REQUEST ;
 InvalidEmbedder                       , InvalidRequest       , BAD_REQUEST ;
 InvalidHybridQuery                    , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexCopyDestination           , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexExportFormat              , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexExportUrl                 , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexLimit                     , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexOffset                    , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexPrimaryKey                , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexUid                       , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchAggregations             , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchAnalyticsDocumentId      , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchAnalyticsLimit           , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchAnalyticsQ               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToSearchOn     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchAttributeBoosts          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToCrop         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToHighlight    , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToRetrieve     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropLength               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchCountOnly                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchDisjunctiveFacets        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchDistinct                 , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchEarlyTermination         , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchEmbedForeignDocuments    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchExtendedFacetStats       , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchForeignFilters           , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchGeoReferencePoint        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchGroupBy                  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHiddenDocuments          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSemanticRatio            , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHybridFusion             , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHybridCandidateSelection , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHybridEmbedders          , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRetrieveVectors          , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHighlightEscapeHtml      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPostTag         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPreTag          , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHighlightTags            , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHitsPerPage              , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLimit                    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchMatchesPositionUnit      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchMatchingStrategy         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchOffset                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchPage                     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchPinnedDocuments          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchQ                        , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchQuery               , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchName                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchVector                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowMatchesPosition      , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowMatchedTerms         , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowCorrectedQuery       , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowRankingScore         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowRankingScoreDetails  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRankingScoreThreshold    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSearchAfter              , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchTimeoutMs                , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchVocabulary               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchPhraseSlop               , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSnippet                  , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSort                     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSortFacetValuesBy        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchStructuredQuery          , InvalidRequest       , BAD_REQUEST ;
+InvalidSimilarId                      , InvalidRequest       , BAD_REQUEST ;
+InvalidSuggestLimit                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSuggestQ                       , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDisplayedAttributes    , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDistinctAttribute      , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsStaticBoost            , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsForeignKeys            , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsQueryRules             , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsReranker               , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsLocalizedAttributes    , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsStemming               , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsRomanization           , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsSplitIdentifiers       , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsDocumentSchema         , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsProximityPrecision     , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsFaceting               , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsFilterableAttributes   , InvalidRequest       , BAD_REQUEST ;
@@ -263,6 +332,12 @@ InvalidSettingsEmbedders              , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsRankingRules           , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSearchableAttributes   , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSortableAttributes     , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsNonFlattenedAttributes , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsMergeStrategies        , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsTypeCoercions          , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsIngestionMapping       , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsDateAttributes         , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsSuggestableAttributes  , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsStopWords              , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsNonSeparatorTokens     , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSeparatorTokens        , InvalidRequest       , BAD_REQUEST ;
@@ -293,19 +368,25 @@ MissingApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyExpiresAt                , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
 MissingAuthorizationHeader            , Auth                 , UNAUTHORIZED ;
+MissingChatCompletionMessages         , InvalidRequest       , BAD_REQUEST ;
 MissingContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 MissingDocumentId                     , InvalidRequest       , BAD_REQUEST ;
 MissingFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
+MissingIndexCopyDestination           , InvalidRequest       , BAD_REQUEST ;
 MissingIndexUid                       , InvalidRequest       , BAD_REQUEST ;
 MissingMasterKey                      , Auth                 , UNAUTHORIZED ;
 MissingPayload                        , InvalidRequest       , BAD_REQUEST ;
+MissingSearchAnalyticsDocumentId      , InvalidRequest       , BAD_REQUEST ;
+MissingSearchAnalyticsQ               , InvalidRequest       , BAD_REQUEST ;
 MissingSearchHybrid                   , InvalidRequest       , BAD_REQUEST ;
 MissingSwapIndexes                    , InvalidRequest       , BAD_REQUEST ;
 MissingTaskFilters                    , InvalidRequest       , BAD_REQUEST ;
 NoSpaceLeftOnDevice                   , System               , UNPROCESSABLE_ENTITY;
 PayloadTooLarge                       , InvalidRequest       , PAYLOAD_TOO_LARGE ;
+SearchDeadlineExceeded                , System               , SERVICE_UNAVAILABLE ;
 TaskNotFound                          , InvalidRequest       , NOT_FOUND ;
 TooManyOpenFiles                      , System               , UNPROCESSABLE_ENTITY ;
+TooManySearchRequests                 , System               , SERVICE_UNAVAILABLE ;
 TooManyVectors                        , InvalidRequest       , BAD_REQUEST ;
 UnretrievableDocument                 , Internal             , BAD_REQUEST ;
 UnretrievableErrorCode                , InvalidRequest       , BAD_REQUEST ;
@@ -340,9 +421,11 @@ impl ErrorCode for milli::Error {
                     UserError::InvalidFilter(_) => Code::InvalidSearchFilter,
                     UserError::InvalidFilterExpression(..) => Code::InvalidSearchFilter,
                     UserError::MissingDocumentId { .. } => Code::MissingDocumentId,
-                    UserError::InvalidDocumentId { .. } | UserError::TooManyDocumentIds { .. } => {
-                        Code::InvalidDocumentId
-                    }
+                    UserError::InvalidDocumentVersion { .. } => Code::InvalidDocumentVersion,
+                    UserError::InvalidDocumentSchema { .. } => Code::InvalidDocumentSchema,
+                    UserError::InvalidDocumentId { .. }
+                    | UserError::TooManyDocumentIds { .. }
+                    | UserError::DuplicateDocumentId { .. } => Code::InvalidDocumentId,
                     UserError::MissingDocumentField(_) => Code::InvalidDocumentFields,
                     UserError::InvalidPrompt(_) => Code::InvalidSettingsEmbedders,
                     UserError::TooManyEmbedders(_) => Code::InvalidSettingsEmbedders,
@@ -353,8 +436,14 @@ impl ErrorCode for milli::Error {
                     }
                     UserError::PrimaryKeyCannotBeChanged(_) => Code::IndexPrimaryKeyAlreadyExists,
                     UserError::SortRankingRuleMissing => Code::InvalidSearchSort,
+                    UserError::InvalidSearchCursor => Code::InvalidSearchSearchAfter,
                     UserError::InvalidFacetsDistribution { .. } => Code::InvalidSearchFacets,
                     UserError::InvalidSortableAttribute { .. } => Code::InvalidSearchSort,
+                    UserError::InvalidGroupByAttribute { .. } => Code::InvalidSearchGroupBy,
+                    UserError::InvalidDistinctAttribute { .. } => Code::InvalidSearchDistinct,
+                    UserError::InvalidAggregationAttribute { .. } => {
+                        Code::InvalidSearchAggregations
+                    }
                     UserError::InvalidSearchableAttribute { .. } => {
                         Code::InvalidSearchAttributesToSearchOn
                     }
@@ -363,6 +452,7 @@ impl ErrorCode for milli::Error {
                     }
                     UserError::CriterionError(_) => Code::InvalidSettingsRankingRules,
                     UserError::InvalidGeoField { .. } => Code::InvalidDocumentGeoField,
+                    UserError::InvalidGeoJsonField { .. } => Code::InvalidDocumentGeojsonField,
                     UserError::InvalidVectorDimensions { .. } => Code::InvalidVectorDimensions,
                     UserError::InvalidVectorsMapType { .. } => Code::InvalidVectorsType,
                     UserError::InvalidVectorsType { .. } => Code::InvalidVectorsType,
@@ -371,6 +461,15 @@ impl ErrorCode for milli::Error {
                     UserError::InvalidMinTypoWordLenSetting(_, _) => {
                         Code::InvalidSettingsTypoTolerance
                     }
+                    UserError::InvalidFacetHierarchySeparator => Code::InvalidSettingsFaceting,
+                    UserError::ConflictingSeparatorToken(_) => Code::InvalidSettingsSeparatorTokens,
+                    UserError::UnknownStemmingLocale(_) => Code::InvalidSettingsStemming,
+                    UserError::ConflictingIngestionMapping { .. } => {
+                        Code::InvalidSettingsIngestionMapping
+                    }
+                    UserError::InvalidDocumentSchemaSetting(_) => {
+                        Code::InvalidSettingsDocumentSchema
+                    }
                     UserError::InvalidEmbedder(_) => Code::InvalidEmbedder,
                     UserError::VectorEmbeddingError(_) => Code::VectorEmbeddingError,
                 }
@@ -469,6 +568,51 @@ impl fmt::Display for deserr_codes::InvalidSearchSemanticRatio {
     }
 }
 
+impl fmt::Display for deserr_codes::InvalidSearchRankingScoreThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchSearchAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `searchAfter` is invalid, expected an empty string or the `nextSearchAfter` cursor of a previous search."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchAggregations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `aggregations` is invalid, expected `attribute:aggregation` pairs separated by commas, the aggregation being one of `sum`, `avg`, `min`, `max` or `percentiles`."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchAttributeBoosts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `attributeBoosts` is invalid, expected an object associating attributes to positive numbers."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidSearchGeoReferencePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `geoReferencePoint` is invalid, expected a latitude between `-90` and `90` and a longitude between `-180` and `180`: `[latitude, longitude]`."
+        )
+    }
+}
+
 #[macro_export]
 macro_rules! internal_error {
     ($target:ty : $($other:path), *) => {
@@ -7,6 +7,10 @@ pub struct RuntimeTogglableFeatures {
     pub vector_store: bool,
     pub metrics: bool,
     pub export_puffin_reports: bool,
+    pub shadow_settings_update: bool,
+    pub chat_completions: bool,
+    pub search_analytics: bool,
+    pub change_feed: bool,
 }
 
 #[derive(Default, Debug, Clone, Copy)]
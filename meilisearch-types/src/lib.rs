@@ -1,3 +1,4 @@
+pub mod chat;
 pub mod compression;
 pub mod deserr;
 pub mod document_formats;
@@ -8,8 +8,13 @@ use std::str::FromStr;
 
 use deserr::{DeserializeError, Deserr, ErrorKind, MergeWithError, ValuePointerRef};
 use fst::IntoStreamer;
+use milli::foreign_key::ForeignKey;
+use milli::localized_attributes_rules::{is_known_locale, LocalizedAttributesRule};
 use milli::proximity::ProximityPrecision;
-use milli::update::Setting;
+use milli::query_rules::{Anchoring, QueryRule, QueryRuleConditions, QueryRuleConsequences};
+use milli::static_boost::{BoostCurve, StaticBoost};
+use milli::update::{MergeStrategy, Setting, TypeCoercion};
+use milli::vector::rerank::{RerankerOptions, DEFAULT_RERANKER_TIMEOUT_MS, DEFAULT_RERANKER_TOP_N};
 use milli::{Criterion, CriterionError, Index, DEFAULT_VALUES_PER_FACET};
 use serde::{Deserialize, Serialize, Serializer};
 
@@ -95,6 +100,12 @@ pub struct TypoSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     pub disable_on_attributes: Setting<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub disable_on_first_character: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub enable_on_numbers: Setting<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
@@ -107,6 +118,9 @@ pub struct FacetingSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     pub sort_facet_values_by: Setting<BTreeMap<String, FacetValuesSort>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub hierarchy_separators: Setting<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
@@ -166,6 +180,29 @@ pub struct Settings<T> {
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSortableAttributes>)]
     pub sortable_attributes: Setting<BTreeSet<String>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsNonFlattenedAttributes>)]
+    pub non_flattened_attributes: Setting<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsMergeStrategies>)]
+    pub merge_strategies: Setting<BTreeMap<String, MergeStrategyView>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsTypeCoercions>)]
+    pub type_coercions: Setting<BTreeMap<String, TypeCoercionView>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsIngestionMapping>)]
+    pub ingestion_mapping: Setting<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsDateAttributes>)]
+    pub date_attributes: Setting<BTreeSet<String>>,
+    /// The JSON Schema the documents added to the index must conform to.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsDocumentSchema>)]
+    pub document_schema: Setting<serde_json::Value>,
+    /// The fields whose values complete the queries of the suggest route.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsSuggestableAttributes>)]
+    pub suggestable_attributes: Setting<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsRankingRules>)]
     pub ranking_rules: Setting<Vec<RankingRuleView>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
@@ -187,6 +224,30 @@ pub struct Settings<T> {
     #[deserr(default, error = DeserrJsonError<InvalidSettingsDistinctAttribute>)]
     pub distinct_attribute: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsStaticBoost>)]
+    pub static_boost: Setting<StaticBoostView>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsForeignKeys>)]
+    pub foreign_keys: Setting<Vec<ForeignKeyView>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsQueryRules>)]
+    pub query_rules: Setting<Vec<QueryRuleView>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsReranker>)]
+    pub reranker: Setting<RerankerView>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsLocalizedAttributes>)]
+    pub localized_attributes: Setting<Vec<LocalizedAttributesRuleView>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsStemming>)]
+    pub stemming: Setting<BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsRomanization>)]
+    pub romanization: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsSplitIdentifiers>)]
+    pub split_identifiers: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsProximityPrecision>)]
     pub proximity_precision: Setting<ProximityPrecisionView>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
@@ -215,6 +276,13 @@ impl Settings<Checked> {
             searchable_attributes: Setting::Reset,
             filterable_attributes: Setting::Reset,
             sortable_attributes: Setting::Reset,
+            non_flattened_attributes: Setting::Reset,
+            merge_strategies: Setting::Reset,
+            type_coercions: Setting::Reset,
+            ingestion_mapping: Setting::Reset,
+            date_attributes: Setting::Reset,
+            document_schema: Setting::Reset,
+            suggestable_attributes: Setting::Reset,
             ranking_rules: Setting::Reset,
             stop_words: Setting::Reset,
             synonyms: Setting::Reset,
@@ -222,6 +290,14 @@ impl Settings<Checked> {
             separator_tokens: Setting::Reset,
             dictionary: Setting::Reset,
             distinct_attribute: Setting::Reset,
+            static_boost: Setting::Reset,
+            foreign_keys: Setting::Reset,
+            query_rules: Setting::Reset,
+            reranker: Setting::Reset,
+            localized_attributes: Setting::Reset,
+            stemming: Setting::Reset,
+            romanization: Setting::Reset,
+            split_identifiers: Setting::Reset,
             proximity_precision: Setting::Reset,
             typo_tolerance: Setting::Reset,
             faceting: Setting::Reset,
@@ -237,6 +313,13 @@ impl Settings<Checked> {
             searchable_attributes,
             filterable_attributes,
             sortable_attributes,
+            non_flattened_attributes,
+            merge_strategies,
+            type_coercions,
+            ingestion_mapping,
+            date_attributes,
+            document_schema,
+            suggestable_attributes,
             ranking_rules,
             stop_words,
             non_separator_tokens,
@@ -244,6 +327,14 @@ impl Settings<Checked> {
             dictionary,
             synonyms,
             distinct_attribute,
+            static_boost,
+            foreign_keys,
+            query_rules,
+            reranker,
+            localized_attributes,
+            stemming,
+            romanization,
+            split_identifiers,
             proximity_precision,
             typo_tolerance,
             faceting,
@@ -257,6 +348,13 @@ impl Settings<Checked> {
             searchable_attributes,
             filterable_attributes,
             sortable_attributes,
+            non_flattened_attributes,
+            merge_strategies,
+            type_coercions,
+            ingestion_mapping,
+            date_attributes,
+            document_schema,
+            suggestable_attributes,
             ranking_rules,
             stop_words,
             non_separator_tokens,
@@ -264,6 +362,14 @@ impl Settings<Checked> {
             dictionary,
             synonyms,
             distinct_attribute,
+            static_boost,
+            foreign_keys,
+            query_rules,
+            reranker,
+            localized_attributes,
+            stemming,
+            romanization,
+            split_identifiers,
             proximity_precision,
             typo_tolerance,
             faceting,
@@ -303,6 +409,13 @@ impl Settings<Unchecked> {
             searchable_attributes,
             filterable_attributes: self.filterable_attributes,
             sortable_attributes: self.sortable_attributes,
+            non_flattened_attributes: self.non_flattened_attributes,
+            merge_strategies: self.merge_strategies,
+            type_coercions: self.type_coercions,
+            ingestion_mapping: self.ingestion_mapping,
+            date_attributes: self.date_attributes,
+            document_schema: self.document_schema,
+            suggestable_attributes: self.suggestable_attributes,
             ranking_rules: self.ranking_rules,
             stop_words: self.stop_words,
             synonyms: self.synonyms,
@@ -310,6 +423,14 @@ impl Settings<Unchecked> {
             separator_tokens: self.separator_tokens,
             dictionary: self.dictionary,
             distinct_attribute: self.distinct_attribute,
+            static_boost: self.static_boost,
+            foreign_keys: self.foreign_keys,
+            query_rules: self.query_rules,
+            reranker: self.reranker,
+            localized_attributes: self.localized_attributes,
+            stemming: self.stemming,
+            romanization: self.romanization,
+            split_identifiers: self.split_identifiers,
             proximity_precision: self.proximity_precision,
             typo_tolerance: self.typo_tolerance,
             faceting: self.faceting,
@@ -358,6 +479,59 @@ pub fn apply_settings_to_builder(
         Setting::NotSet => (),
     }
 
+    match settings.non_flattened_attributes {
+        Setting::Set(ref fields) => {
+            builder.set_non_flattened_fields(fields.iter().cloned().collect())
+        }
+        Setting::Reset => builder.reset_non_flattened_fields(),
+        Setting::NotSet => (),
+    }
+
+    match settings.merge_strategies {
+        Setting::Set(ref strategies) => builder.set_merge_strategies(
+            strategies
+                .iter()
+                .map(|(field, strategy)| (field.clone(), (*strategy).into()))
+                .collect(),
+        ),
+        Setting::Reset => builder.reset_merge_strategies(),
+        Setting::NotSet => (),
+    }
+
+    match settings.type_coercions {
+        Setting::Set(ref coercions) => builder.set_type_coercions(
+            coercions.iter().map(|(field, coercion)| (field.clone(), (*coercion).into())).collect(),
+        ),
+        Setting::Reset => builder.reset_type_coercions(),
+        Setting::NotSet => (),
+    }
+
+    match settings.ingestion_mapping {
+        Setting::Set(ref mapping) => builder.set_ingestion_mapping(mapping.clone()),
+        Setting::Reset => builder.reset_ingestion_mapping(),
+        Setting::NotSet => (),
+    }
+
+    match settings.date_attributes {
+        Setting::Set(ref fields) => builder.set_date_fields(fields.iter().cloned().collect()),
+        Setting::Reset => builder.reset_date_fields(),
+        Setting::NotSet => (),
+    }
+
+    match settings.document_schema {
+        Setting::Set(ref schema) => builder.set_document_schema(schema.clone()),
+        Setting::Reset => builder.reset_document_schema(),
+        Setting::NotSet => (),
+    }
+
+    match settings.suggestable_attributes {
+        Setting::Set(ref fields) => {
+            builder.set_suggestable_fields(fields.iter().cloned().collect())
+        }
+        Setting::Reset => builder.reset_suggestable_fields(),
+        Setting::NotSet => (),
+    }
+
     match settings.ranking_rules {
         Setting::Set(ref criteria) => {
             builder.set_criteria(criteria.iter().map(|c| c.clone().into()).collect())
@@ -406,6 +580,60 @@ pub fn apply_settings_to_builder(
         Setting::NotSet => (),
     }
 
+    match settings.static_boost {
+        Setting::Set(ref static_boost) => builder.set_static_boost(static_boost.clone().into()),
+        Setting::Reset => builder.reset_static_boost(),
+        Setting::NotSet => (),
+    }
+
+    match settings.foreign_keys {
+        Setting::Set(ref foreign_keys) => {
+            builder.set_foreign_keys(foreign_keys.iter().cloned().map(ForeignKey::from).collect())
+        }
+        Setting::Reset => builder.reset_foreign_keys(),
+        Setting::NotSet => (),
+    }
+
+    match settings.query_rules {
+        Setting::Set(ref query_rules) => {
+            builder.set_query_rules(query_rules.iter().cloned().map(QueryRule::from).collect())
+        }
+        Setting::Reset => builder.reset_query_rules(),
+        Setting::NotSet => (),
+    }
+
+    match settings.reranker {
+        Setting::Set(ref reranker) => builder.set_reranker(reranker.clone().into()),
+        Setting::Reset => builder.reset_reranker(),
+        Setting::NotSet => (),
+    }
+
+    match settings.localized_attributes {
+        Setting::Set(ref rules) => builder.set_localized_attributes_rules(
+            rules.iter().cloned().map(LocalizedAttributesRule::from).collect(),
+        ),
+        Setting::Reset => builder.reset_localized_attributes_rules(),
+        Setting::NotSet => (),
+    }
+
+    match settings.stemming {
+        Setting::Set(ref locales) => builder.set_stemming(locales.clone()),
+        Setting::Reset => builder.reset_stemming(),
+        Setting::NotSet => (),
+    }
+
+    match settings.romanization {
+        Setting::Set(flag) => builder.set_romanization(flag),
+        Setting::Reset => builder.reset_romanization(),
+        Setting::NotSet => (),
+    }
+
+    match settings.split_identifiers {
+        Setting::Set(flag) => builder.set_split_identifiers(flag),
+        Setting::Reset => builder.reset_split_identifiers(),
+        Setting::NotSet => (),
+    }
+
     match settings.proximity_precision {
         Setting::Set(ref precision) => builder.set_proximity_precision((*precision).into()),
         Setting::Reset => builder.reset_proximity_precision(),
@@ -455,6 +683,18 @@ pub fn apply_settings_to_builder(
                 Setting::Reset => builder.reset_exact_attributes(),
                 Setting::NotSet => (),
             }
+
+            match value.disable_on_first_character {
+                Setting::Set(val) => builder.set_disable_typo_on_first_character(val),
+                Setting::Reset => builder.reset_disable_typo_on_first_character(),
+                Setting::NotSet => (),
+            }
+
+            match value.enable_on_numbers {
+                Setting::Set(val) => builder.set_authorize_typos_on_numbers(val),
+                Setting::Reset => builder.reset_authorize_typos_on_numbers(),
+                Setting::NotSet => (),
+            }
         }
         Setting::Reset => {
             // all typo settings need to be reset here.
@@ -463,12 +703,18 @@ pub fn apply_settings_to_builder(
             builder.reset_min_word_len_two_typos();
             builder.reset_exact_words();
             builder.reset_exact_attributes();
+            builder.reset_disable_typo_on_first_character();
+            builder.reset_authorize_typos_on_numbers();
         }
         Setting::NotSet => (),
     }
 
     match &settings.faceting {
-        Setting::Set(FacetingSettings { max_values_per_facet, sort_facet_values_by }) => {
+        Setting::Set(FacetingSettings {
+            max_values_per_facet,
+            sort_facet_values_by,
+            hierarchy_separators,
+        }) => {
             match max_values_per_facet {
                 Setting::Set(val) => builder.set_max_values_per_facet(*val),
                 Setting::Reset => builder.reset_max_values_per_facet(),
@@ -481,10 +727,16 @@ pub fn apply_settings_to_builder(
                 Setting::Reset => builder.reset_sort_facet_values_by(),
                 Setting::NotSet => (),
             }
+            match hierarchy_separators {
+                Setting::Set(val) => builder.set_facet_hierarchy_separators(val.clone()),
+                Setting::Reset => builder.reset_facet_hierarchy_separators(),
+                Setting::NotSet => (),
+            }
         }
         Setting::Reset => {
             builder.reset_max_values_per_facet();
             builder.reset_sort_facet_values_by();
+            builder.reset_facet_hierarchy_separators();
         }
         Setting::NotSet => (),
     }
@@ -521,6 +773,28 @@ pub fn settings(
 
     let sortable_attributes = index.sortable_fields(rtxn)?.into_iter().collect();
 
+    let non_flattened_attributes = index.non_flattened_fields(rtxn)?.into_iter().collect();
+
+    let merge_strategies = index
+        .merge_strategies(rtxn)?
+        .into_iter()
+        .map(|(field, strategy)| (field, strategy.into()))
+        .collect();
+
+    let type_coercions = index
+        .type_coercions(rtxn)?
+        .into_iter()
+        .map(|(field, coercion)| (field, coercion.into()))
+        .collect();
+
+    let ingestion_mapping = index.ingestion_mapping(rtxn)?;
+
+    let date_attributes = index.date_fields(rtxn)?.into_iter().collect();
+
+    let document_schema = index.document_schema(rtxn)?.map(|schema| schema.as_value().clone());
+
+    let suggestable_attributes = index.suggestable_fields(rtxn)?.into_iter().collect();
+
     let criteria = index.criteria(rtxn)?;
 
     let stop_words = index
@@ -537,6 +811,26 @@ pub fn settings(
 
     let distinct_field = index.distinct_field(rtxn)?.map(String::from);
 
+    let static_boost = index.static_boost(rtxn)?.map(StaticBoostView::from);
+
+    let foreign_keys = index.foreign_keys(rtxn)?.into_iter().map(ForeignKeyView::from).collect();
+
+    let query_rules = index.query_rules(rtxn)?.into_iter().map(QueryRuleView::from).collect();
+
+    let reranker = index.reranker(rtxn)?.map(RerankerView::from);
+
+    let localized_attributes = index
+        .localized_attributes_rules(rtxn)?
+        .into_iter()
+        .map(LocalizedAttributesRuleView::from)
+        .collect();
+
+    let stemming = index.stemming(rtxn)?;
+
+    let romanization = index.romanization(rtxn)?;
+
+    let split_identifiers = index.split_identifiers(rtxn)?;
+
     let proximity_precision = index.proximity_precision(rtxn)?.map(ProximityPrecisionView::from);
 
     let synonyms = index.user_defined_synonyms(rtxn)?;
@@ -558,6 +852,8 @@ pub fn settings(
         min_word_size_for_typos: Setting::Set(min_typo_word_len),
         disable_on_words: Setting::Set(disabled_words),
         disable_on_attributes: Setting::Set(disabled_attributes),
+        disable_on_first_character: Setting::Set(index.disable_typo_on_first_character(rtxn)?),
+        enable_on_numbers: Setting::Set(index.authorize_typos_on_numbers(rtxn)?),
     };
 
     let faceting = FacetingSettings {
@@ -574,6 +870,7 @@ pub fn settings(
                 .map(|(name, sort)| (name, sort.into()))
                 .collect(),
         ),
+        hierarchy_separators: Setting::Set(index.facet_hierarchy_separators(rtxn)?),
     };
 
     let pagination = PaginationSettings {
@@ -602,6 +899,16 @@ pub fn settings(
         },
         filterable_attributes: Setting::Set(filterable_attributes),
         sortable_attributes: Setting::Set(sortable_attributes),
+        non_flattened_attributes: Setting::Set(non_flattened_attributes),
+        merge_strategies: Setting::Set(merge_strategies),
+        type_coercions: Setting::Set(type_coercions),
+        ingestion_mapping: Setting::Set(ingestion_mapping),
+        date_attributes: Setting::Set(date_attributes),
+        document_schema: match document_schema {
+            Some(schema) => Setting::Set(schema),
+            None => Setting::Reset,
+        },
+        suggestable_attributes: Setting::Set(suggestable_attributes),
         ranking_rules: Setting::Set(criteria.iter().map(|c| c.clone().into()).collect()),
         stop_words: Setting::Set(stop_words),
         non_separator_tokens: Setting::Set(non_separator_tokens),
@@ -611,6 +918,20 @@ pub fn settings(
             Some(field) => Setting::Set(field),
             None => Setting::Reset,
         },
+        static_boost: match static_boost {
+            Some(static_boost) => Setting::Set(static_boost),
+            None => Setting::Reset,
+        },
+        foreign_keys: Setting::Set(foreign_keys),
+        query_rules: Setting::Set(query_rules),
+        reranker: match reranker {
+            Some(reranker) => Setting::Set(reranker),
+            None => Setting::Reset,
+        },
+        localized_attributes: Setting::Set(localized_attributes),
+        stemming: Setting::Set(stemming),
+        romanization: Setting::Set(romanization),
+        split_identifiers: Setting::Set(split_identifiers),
         proximity_precision: match proximity_precision {
             Some(precision) => Setting::Set(precision),
             None => Setting::Reset,
@@ -646,6 +967,10 @@ pub enum RankingRuleView {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the increasing value of the expression specified.
+    AscExpression(String),
+    /// Sorted by the decreasing value of the expression specified.
+    DescExpression(String),
 }
 impl Serialize for RankingRuleView {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -702,6 +1027,8 @@ impl From<Criterion> for RankingRuleView {
             Criterion::Exactness => RankingRuleView::Exactness,
             Criterion::Asc(x) => RankingRuleView::Asc(x),
             Criterion::Desc(x) => RankingRuleView::Desc(x),
+            Criterion::AscExpression(x) => RankingRuleView::AscExpression(x),
+            Criterion::DescExpression(x) => RankingRuleView::DescExpression(x),
         }
     }
 }
@@ -716,6 +1043,8 @@ impl From<RankingRuleView> for Criterion {
             RankingRuleView::Exactness => Criterion::Exactness,
             RankingRuleView::Asc(x) => Criterion::Asc(x),
             RankingRuleView::Desc(x) => Criterion::Desc(x),
+            RankingRuleView::AscExpression(x) => Criterion::AscExpression(x),
+            RankingRuleView::DescExpression(x) => Criterion::DescExpression(x),
         }
     }
 }
@@ -745,6 +1074,374 @@ impl From<ProximityPrecisionView> for ProximityPrecision {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsStaticBoost>, rename_all = camelCase, deny_unknown_fields)]
+pub struct StaticBoostView {
+    pub attribute: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub curve: BoostCurveView,
+}
+
+impl From<StaticBoost> for StaticBoostView {
+    fn from(value: StaticBoost) -> Self {
+        StaticBoostView { attribute: value.attribute, curve: value.curve.into() }
+    }
+}
+impl From<StaticBoostView> for StaticBoost {
+    fn from(value: StaticBoostView) -> Self {
+        StaticBoost { attribute: value.attribute, curve: value.curve.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsForeignKeys>, rename_all = camelCase, deny_unknown_fields)]
+pub struct ForeignKeyView {
+    pub attribute: String,
+    pub foreign_index_uid: String,
+}
+
+impl From<ForeignKey> for ForeignKeyView {
+    fn from(value: ForeignKey) -> Self {
+        ForeignKeyView { attribute: value.attribute, foreign_index_uid: value.foreign_index_uid }
+    }
+}
+impl From<ForeignKeyView> for ForeignKey {
+    fn from(value: ForeignKeyView) -> Self {
+        ForeignKey { attribute: value.attribute, foreign_index_uid: value.foreign_index_uid }
+    }
+}
+
+fn validate_query_rule<E: DeserializeError>(
+    rule: QueryRuleView,
+    location: ValuePointerRef,
+) -> Result<QueryRuleView, E> {
+    let filters = [
+        ("conditions.filter", &rule.conditions.filter),
+        ("consequences.filter", &rule.consequences.filter),
+        ("consequences.boost", &rule.consequences.boost),
+    ];
+    for (name, filter) in filters {
+        if let Some(Err(error)) = filter.as_deref().map(milli::Filter::from_str) {
+            let msg = format!("The `{name}` of the `{}` query rule is invalid: {error}", rule.id);
+            return Err(deserr::take_cf_content(E::error::<Infallible>(
+                None,
+                ErrorKind::Unexpected { msg },
+                location,
+            )));
+        }
+    }
+    Ok(rule)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsQueryRules>, rename_all = camelCase, deny_unknown_fields, validate = validate_query_rule -> DeserrJsonError<InvalidSettingsQueryRules>)]
+pub struct QueryRuleView {
+    pub id: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub conditions: QueryRuleConditionsView,
+    #[serde(default)]
+    #[deserr(default)]
+    pub consequences: QueryRuleConsequencesView,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsQueryRules>, rename_all = camelCase, deny_unknown_fields)]
+pub struct QueryRuleConditionsView {
+    #[serde(default)]
+    #[deserr(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub anchoring: AnchoringView,
+    #[serde(default)]
+    #[deserr(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsQueryRules>, rename_all = camelCase, deny_unknown_fields)]
+pub enum AnchoringView {
+    #[default]
+    Is,
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsQueryRules>, rename_all = camelCase, deny_unknown_fields)]
+pub struct QueryRuleConsequencesView {
+    #[serde(default)]
+    #[deserr(default)]
+    pub pin: Vec<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub boost: Option<String>,
+}
+
+impl From<QueryRule> for QueryRuleView {
+    fn from(value: QueryRule) -> Self {
+        let QueryRule { id, conditions, consequences } = value;
+        let QueryRuleConditions { query, anchoring, filter } = conditions;
+        let anchoring = match anchoring {
+            Anchoring::Is => AnchoringView::Is,
+            Anchoring::StartsWith => AnchoringView::StartsWith,
+            Anchoring::EndsWith => AnchoringView::EndsWith,
+            Anchoring::Contains => AnchoringView::Contains,
+        };
+        let QueryRuleConsequences { pin, filter: consequence_filter, boost } = consequences;
+        QueryRuleView {
+            id,
+            conditions: QueryRuleConditionsView { query, anchoring, filter },
+            consequences: QueryRuleConsequencesView { pin, filter: consequence_filter, boost },
+        }
+    }
+}
+impl From<QueryRuleView> for QueryRule {
+    fn from(value: QueryRuleView) -> Self {
+        let QueryRuleView { id, conditions, consequences } = value;
+        let QueryRuleConditionsView { query, anchoring, filter } = conditions;
+        let anchoring = match anchoring {
+            AnchoringView::Is => Anchoring::Is,
+            AnchoringView::StartsWith => Anchoring::StartsWith,
+            AnchoringView::EndsWith => Anchoring::EndsWith,
+            AnchoringView::Contains => Anchoring::Contains,
+        };
+        let QueryRuleConsequencesView { pin, filter: consequence_filter, boost } = consequences;
+        QueryRule {
+            id,
+            conditions: QueryRuleConditions { query, anchoring, filter },
+            consequences: QueryRuleConsequences { pin, filter: consequence_filter, boost },
+        }
+    }
+}
+
+fn validate_localized_attributes_rule<E: DeserializeError>(
+    rule: LocalizedAttributesRuleView,
+    location: ValuePointerRef,
+) -> Result<LocalizedAttributesRuleView, E> {
+    let error = if rule.locales.is_empty() {
+        Some("A localized attributes rule must have at least one locale.".to_string())
+    } else {
+        rule.locales.iter().find(|locale| !is_known_locale(locale)).map(|locale| {
+            format!(
+                "Unknown locale `{locale}`, expected the ISO 639-3 code of a supported language."
+            )
+        })
+    };
+
+    match error {
+        Some(msg) => Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected { msg },
+            location,
+        ))),
+        None => Ok(rule),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsLocalizedAttributes>, rename_all = camelCase, deny_unknown_fields, validate = validate_localized_attributes_rule -> DeserrJsonError<InvalidSettingsLocalizedAttributes>)]
+pub struct LocalizedAttributesRuleView {
+    pub attribute_patterns: Vec<String>,
+    pub locales: Vec<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub stop_words: BTreeSet<String>,
+}
+
+impl From<LocalizedAttributesRule> for LocalizedAttributesRuleView {
+    fn from(value: LocalizedAttributesRule) -> Self {
+        let LocalizedAttributesRule { attribute_patterns, locales, stop_words } = value;
+        LocalizedAttributesRuleView { attribute_patterns, locales, stop_words }
+    }
+}
+
+impl From<LocalizedAttributesRuleView> for LocalizedAttributesRule {
+    fn from(value: LocalizedAttributesRuleView) -> Self {
+        let LocalizedAttributesRuleView { attribute_patterns, locales, stop_words } = value;
+        LocalizedAttributesRule { attribute_patterns, locales, stop_words }
+    }
+}
+
+fn validate_reranker<E: DeserializeError>(
+    reranker: RerankerView,
+    location: ValuePointerRef,
+) -> Result<RerankerView, E> {
+    let error = if reranker.top_n == 0 {
+        Some("The `topN` of the reranker must be greater than 0.".to_string())
+    } else if let Some(Err(error)) =
+        reranker.document_template.clone().map(milli::prompt::Prompt::new)
+    {
+        Some(format!("The `documentTemplate` of the reranker is invalid: {error}"))
+    } else {
+        None
+    };
+    match error {
+        Some(msg) => Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected { msg },
+            location,
+        ))),
+        None => Ok(reranker),
+    }
+}
+
+fn default_reranker_top_n() -> usize {
+    DEFAULT_RERANKER_TOP_N
+}
+
+fn default_reranker_timeout_ms() -> u64 {
+    DEFAULT_RERANKER_TIMEOUT_MS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsReranker>, rename_all = camelCase, deny_unknown_fields, validate = validate_reranker -> DeserrJsonError<InvalidSettingsReranker>)]
+pub struct RerankerView {
+    pub url: String,
+    #[serde(default)]
+    #[deserr(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    #[deserr(default)]
+    pub document_template: Option<String>,
+    #[serde(default = "default_reranker_top_n")]
+    #[deserr(default = default_reranker_top_n())]
+    pub top_n: usize,
+    #[serde(default = "default_reranker_timeout_ms")]
+    #[deserr(default = default_reranker_timeout_ms())]
+    pub timeout_ms: u64,
+}
+
+impl From<RerankerOptions> for RerankerView {
+    fn from(value: RerankerOptions) -> Self {
+        let RerankerOptions { url, api_key, model, document_template, top_n, timeout_ms } = value;
+        RerankerView { url, api_key, model, document_template, top_n, timeout_ms }
+    }
+}
+impl From<RerankerView> for RerankerOptions {
+    fn from(value: RerankerView) -> Self {
+        let RerankerView { url, api_key, model, document_template, top_n, timeout_ms } = value;
+        RerankerOptions { url, api_key, model, document_template, top_n, timeout_ms }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsStaticBoost>, rename_all = camelCase, deny_unknown_fields)]
+pub enum BoostCurveView {
+    Linear,
+    #[default]
+    Log,
+    Sigmoid,
+}
+
+impl From<BoostCurve> for BoostCurveView {
+    fn from(value: BoostCurve) -> Self {
+        match value {
+            BoostCurve::Linear => BoostCurveView::Linear,
+            BoostCurve::Log => BoostCurveView::Log,
+            BoostCurve::Sigmoid => BoostCurveView::Sigmoid,
+        }
+    }
+}
+impl From<BoostCurveView> for BoostCurve {
+    fn from(value: BoostCurveView) -> Self {
+        match value {
+            BoostCurveView::Linear => BoostCurve::Linear,
+            BoostCurveView::Log => BoostCurve::Log,
+            BoostCurveView::Sigmoid => BoostCurve::Sigmoid,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsMergeStrategies>, rename_all = camelCase, deny_unknown_fields)]
+pub enum MergeStrategyView {
+    Append,
+    Increment,
+    Union,
+    KeepMin,
+    KeepMax,
+}
+
+impl From<MergeStrategy> for MergeStrategyView {
+    fn from(value: MergeStrategy) -> Self {
+        match value {
+            MergeStrategy::Append => MergeStrategyView::Append,
+            MergeStrategy::Increment => MergeStrategyView::Increment,
+            MergeStrategy::Union => MergeStrategyView::Union,
+            MergeStrategy::KeepMin => MergeStrategyView::KeepMin,
+            MergeStrategy::KeepMax => MergeStrategyView::KeepMax,
+        }
+    }
+}
+impl From<MergeStrategyView> for MergeStrategy {
+    fn from(value: MergeStrategyView) -> Self {
+        match value {
+            MergeStrategyView::Append => MergeStrategy::Append,
+            MergeStrategyView::Increment => MergeStrategy::Increment,
+            MergeStrategyView::Union => MergeStrategy::Union,
+            MergeStrategyView::KeepMin => MergeStrategy::KeepMin,
+            MergeStrategyView::KeepMax => MergeStrategy::KeepMax,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidSettingsTypeCoercions>, rename_all = camelCase, deny_unknown_fields)]
+pub enum TypeCoercionView {
+    Number,
+    String,
+    Boolean,
+    Array,
+    ArrayOfNumbers,
+    ArrayOfStrings,
+}
+
+impl From<TypeCoercion> for TypeCoercionView {
+    fn from(value: TypeCoercion) -> Self {
+        match value {
+            TypeCoercion::Number => TypeCoercionView::Number,
+            TypeCoercion::String => TypeCoercionView::String,
+            TypeCoercion::Boolean => TypeCoercionView::Boolean,
+            TypeCoercion::Array => TypeCoercionView::Array,
+            TypeCoercion::ArrayOfNumbers => TypeCoercionView::ArrayOfNumbers,
+            TypeCoercion::ArrayOfStrings => TypeCoercionView::ArrayOfStrings,
+        }
+    }
+}
+impl From<TypeCoercionView> for TypeCoercion {
+    fn from(value: TypeCoercionView) -> Self {
+        match value {
+            TypeCoercionView::Number => TypeCoercion::Number,
+            TypeCoercionView::String => TypeCoercion::String,
+            TypeCoercionView::Boolean => TypeCoercion::Boolean,
+            TypeCoercionView::Array => TypeCoercion::Array,
+            TypeCoercionView::ArrayOfNumbers => TypeCoercion::ArrayOfNumbers,
+            TypeCoercionView::ArrayOfStrings => TypeCoercion::ArrayOfStrings,
+        }
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
@@ -757,6 +1454,13 @@ pub(crate) mod test {
             searchable_attributes: Setting::Set(vec![String::from("hello")]),
             filterable_attributes: Setting::NotSet,
             sortable_attributes: Setting::NotSet,
+            non_flattened_attributes: Setting::NotSet,
+            merge_strategies: Setting::NotSet,
+            type_coercions: Setting::NotSet,
+            ingestion_mapping: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            document_schema: Setting::NotSet,
+            suggestable_attributes: Setting::NotSet,
             ranking_rules: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
@@ -764,6 +1468,14 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            static_boost: Setting::NotSet,
+            foreign_keys: Setting::NotSet,
+            query_rules: Setting::NotSet,
+            reranker: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            stemming: Setting::NotSet,
+            romanization: Setting::NotSet,
+            split_identifiers: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::NotSet,
@@ -783,6 +1495,13 @@ pub(crate) mod test {
             searchable_attributes: Setting::Set(vec![String::from("hello"), String::from("*")]),
             filterable_attributes: Setting::NotSet,
             sortable_attributes: Setting::NotSet,
+            non_flattened_attributes: Setting::NotSet,
+            merge_strategies: Setting::NotSet,
+            type_coercions: Setting::NotSet,
+            ingestion_mapping: Setting::NotSet,
+            date_attributes: Setting::NotSet,
+            document_schema: Setting::NotSet,
+            suggestable_attributes: Setting::NotSet,
             ranking_rules: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
@@ -790,6 +1509,14 @@ pub(crate) mod test {
             dictionary: Setting::NotSet,
             synonyms: Setting::NotSet,
             distinct_attribute: Setting::NotSet,
+            static_boost: Setting::NotSet,
+            foreign_keys: Setting::NotSet,
+            query_rules: Setting::NotSet,
+            reranker: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            stemming: Setting::NotSet,
+            romanization: Setting::NotSet,
+            split_identifiers: Setting::NotSet,
             proximity_precision: Setting::NotSet,
             typo_tolerance: Setting::NotSet,
             faceting: Setting::NotSet,
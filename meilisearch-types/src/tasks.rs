@@ -1,15 +1,19 @@
 use core::fmt;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Write};
 use std::str::FromStr;
 
+use deserr::Deserr;
 use enum_iterator::Sequence;
 use milli::update::IndexDocumentsMethod;
+use milli::vector::policy::EmbeddingFailures;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
+use crate::deserr::DeserrJsonError;
+use crate::error::deserr_codes::InvalidIndexExportFormat;
 use crate::error::ResponseError;
 use crate::keys::Key;
 use crate::settings::{Settings, Unchecked};
@@ -54,6 +58,8 @@ impl Task {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
+            | IndexCopy { index_uid, .. }
+            | IndexExport { index_uid, .. }
             | IndexDeletion { index_uid } => Some(index_uid),
         }
     }
@@ -75,6 +81,8 @@ impl Task {
             | KindWithContent::IndexCreation { .. }
             | KindWithContent::IndexUpdate { .. }
             | KindWithContent::IndexSwap { .. }
+            | KindWithContent::IndexCopy { .. }
+            | KindWithContent::IndexExport { .. }
             | KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
@@ -93,6 +101,10 @@ pub enum KindWithContent {
         content_file: Uuid,
         documents_count: u64,
         allow_index_creation: bool,
+        /// Whether the documents that can't be indexed are skipped and reported in the details
+        /// instead of rejecting the whole payload.
+        #[serde(default)]
+        skip_invalid_documents: bool,
     },
     DocumentDeletion {
         index_uid: String,
@@ -125,6 +137,17 @@ pub enum KindWithContent {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexCopy {
+        index_uid: String,
+        destination: String,
+    },
+    IndexExport {
+        index_uid: String,
+        format: ExportFormat,
+        filter: Option<serde_json::Value>,
+        fields: Option<Vec<String>>,
+        url: Option<String>,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -146,6 +169,26 @@ pub struct IndexSwap {
     pub indexes: (String, String),
 }
 
+/// The format of the file written by an index export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(error = DeserrJsonError<InvalidIndexExportFormat>, rename_all = camelCase, deny_unknown_fields)]
+pub enum ExportFormat {
+    /// One JSON document per line.
+    Ndjson,
+    /// One document per record, the arrays and objects being written as JSON.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
 impl KindWithContent {
     pub fn as_kind(&self) -> Kind {
         match self {
@@ -158,6 +201,8 @@ impl KindWithContent {
             KindWithContent::IndexDeletion { .. } => Kind::IndexDeletion,
             KindWithContent::IndexUpdate { .. } => Kind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => Kind::IndexSwap,
+            KindWithContent::IndexCopy { .. } => Kind::IndexCopy,
+            KindWithContent::IndexExport { .. } => Kind::IndexExport,
             KindWithContent::TaskCancelation { .. } => Kind::TaskCancelation,
             KindWithContent::TaskDeletion { .. } => Kind::TaskDeletion,
             KindWithContent::DumpCreation { .. } => Kind::DumpCreation,
@@ -165,6 +210,24 @@ impl KindWithContent {
         }
     }
 
+    /// Returns the content without the secrets the task only needs while it's processed, e.g. the
+    /// credentials in the query string of the URL an index export is uploaded to, if it has any.
+    pub fn without_secrets(&self) -> Option<KindWithContent> {
+        match self {
+            KindWithContent::IndexExport { index_uid, format, filter, fields, url: Some(url) } => {
+                let end = url.find(['?', '#'])?;
+                Some(KindWithContent::IndexExport {
+                    index_uid: index_uid.clone(),
+                    format: *format,
+                    filter: filter.clone(),
+                    fields: fields.clone(),
+                    url: Some(url[..end].to_string()),
+                })
+            }
+            _ => None,
+        }
+    }
+
     pub fn indexes(&self) -> Vec<&str> {
         use KindWithContent::*;
 
@@ -180,7 +243,9 @@ impl KindWithContent {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
+            | IndexExport { index_uid, .. }
             | IndexDeletion { index_uid } => vec![index_uid],
+            IndexCopy { index_uid, destination } => vec![index_uid, destination],
             IndexSwap { swaps } => {
                 let mut indexes = HashSet::<&str>::default();
                 for swap in swaps {
@@ -200,6 +265,9 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_failures: None,
+                    invalid_documents: None,
+                    outdated_documents: None,
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -212,6 +280,7 @@ impl KindWithContent {
                 Some(Details::DocumentDeletionByFilter {
                     original_filter: filter_expr.to_string(),
                     deleted_documents: None,
+                    deleted_documents_sample: None,
                 })
             }
             KindWithContent::DocumentClear { .. } | KindWithContent::IndexDeletion { .. } => {
@@ -227,6 +296,14 @@ impl KindWithContent {
             KindWithContent::IndexSwap { swaps } => {
                 Some(Details::IndexSwap { swaps: swaps.clone() })
             }
+            KindWithContent::IndexCopy { destination, .. } => {
+                Some(Details::IndexCopy { destination: destination.clone() })
+            }
+            KindWithContent::IndexExport { filter, .. } => Some(Details::IndexExport {
+                original_filter: filter.as_ref().map(|filter| filter.to_string()),
+                exported_documents: None,
+                destination: None,
+            }),
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -248,6 +325,9 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    embedding_failures: None,
+                    invalid_documents: None,
+                    outdated_documents: None,
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -260,6 +340,7 @@ impl KindWithContent {
                 Some(Details::DocumentDeletionByFilter {
                     original_filter: filter_expr.to_string(),
                     deleted_documents: Some(0),
+                    deleted_documents_sample: None,
                 })
             }
             KindWithContent::DocumentClear { .. } => {
@@ -276,6 +357,14 @@ impl KindWithContent {
             KindWithContent::IndexSwap { .. } => {
                 todo!()
             }
+            KindWithContent::IndexCopy { destination, .. } => {
+                Some(Details::IndexCopy { destination: destination.clone() })
+            }
+            KindWithContent::IndexExport { filter, .. } => Some(Details::IndexExport {
+                original_filter: filter.as_ref().map(|filter| filter.to_string()),
+                exported_documents: Some(0),
+                destination: None,
+            }),
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: Some(0),
@@ -299,6 +388,9 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    embedding_failures: None,
+                    invalid_documents: None,
+                    outdated_documents: None,
                 })
             }
             KindWithContent::DocumentDeletion { .. } => None,
@@ -315,6 +407,14 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
             }
             KindWithContent::IndexSwap { .. } => None,
+            KindWithContent::IndexCopy { destination, .. } => {
+                Some(Details::IndexCopy { destination: destination.clone() })
+            }
+            KindWithContent::IndexExport { filter, .. } => Some(Details::IndexExport {
+                original_filter: filter.as_ref().map(|filter| filter.to_string()),
+                exported_documents: None,
+                destination: None,
+            }),
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -400,6 +500,8 @@ pub enum Kind {
     IndexDeletion,
     IndexUpdate,
     IndexSwap,
+    IndexCopy,
+    IndexExport,
     TaskCancelation,
     TaskDeletion,
     DumpCreation,
@@ -414,8 +516,10 @@ impl Kind {
             | Kind::SettingsUpdate
             | Kind::IndexCreation
             | Kind::IndexDeletion
-            | Kind::IndexUpdate => true,
+            | Kind::IndexUpdate
+            | Kind::IndexExport => true,
             Kind::IndexSwap
+            | Kind::IndexCopy
             | Kind::TaskCancelation
             | Kind::TaskDeletion
             | Kind::DumpCreation
@@ -433,6 +537,8 @@ impl Display for Kind {
             Kind::IndexDeletion => write!(f, "indexDeletion"),
             Kind::IndexUpdate => write!(f, "indexUpdate"),
             Kind::IndexSwap => write!(f, "indexSwap"),
+            Kind::IndexCopy => write!(f, "indexCopy"),
+            Kind::IndexExport => write!(f, "indexExport"),
             Kind::TaskCancelation => write!(f, "taskCancelation"),
             Kind::TaskDeletion => write!(f, "taskDeletion"),
             Kind::DumpCreation => write!(f, "dumpCreation"),
@@ -450,6 +556,10 @@ impl FromStr for Kind {
             Ok(Kind::IndexUpdate)
         } else if kind.eq_ignore_ascii_case("indexSwap") {
             Ok(Kind::IndexSwap)
+        } else if kind.eq_ignore_ascii_case("indexCopy") {
+            Ok(Kind::IndexCopy)
+        } else if kind.eq_ignore_ascii_case("indexExport") {
+            Ok(Kind::IndexExport)
         } else if kind.eq_ignore_ascii_case("indexDeletion") {
             Ok(Kind::IndexDeletion)
         } else if kind.eq_ignore_ascii_case("documentAdditionOrUpdate") {
@@ -494,37 +604,91 @@ impl fmt::Display for ParseTaskKindError {
 impl std::error::Error for ParseTaskKindError {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+// the variants with documented fields would otherwise make all the variants span several lines
+#[rustfmt::skip]
 pub enum Details {
-    DocumentAdditionOrUpdate { received_documents: u64, indexed_documents: Option<u64> },
+    DocumentAdditionOrUpdate {
+        received_documents: u64,
+        indexed_documents: Option<u64>,
+        /// The documents indexed without the embeddings of an embedder, by embedder name.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        embedding_failures: Option<BTreeMap<String, EmbeddingFailures>>,
+        /// The documents of the payload skipped because they couldn't be indexed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        invalid_documents: Option<Vec<InvalidDocument>>,
+        /// The number of documents of the payload skipped because a previous document with the
+        /// same id has a higher `_version`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        outdated_documents: Option<u64>,
+    },
     SettingsUpdate { settings: Box<Settings<Unchecked>> },
     IndexInfo { primary_key: Option<String> },
     DocumentDeletion { provided_ids: usize, deleted_documents: Option<u64> },
-    DocumentDeletionByFilter { original_filter: String, deleted_documents: Option<u64> },
+    DocumentDeletionByFilter {
+        original_filter: String,
+        deleted_documents: Option<u64>,
+        /// The external ids of the first deleted documents, to audit the deletion.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deleted_documents_sample: Option<Vec<String>>,
+    },
     ClearAll { deleted_documents: Option<u64> },
     TaskCancelation { matched_tasks: u64, canceled_tasks: Option<u64>, original_filter: String },
     TaskDeletion { matched_tasks: u64, deleted_tasks: Option<u64>, original_filter: String },
     Dump { dump_uid: Option<String> },
     IndexSwap { swaps: Vec<IndexSwap> },
+    IndexCopy { destination: String },
+    IndexExport {
+        original_filter: Option<String>,
+        exported_documents: Option<u64>,
+        /// The name of the file written in the exports directory, or the URL
+        /// without its query string it was uploaded to.
+        destination: Option<String>,
+    },
+}
+
+/// A document of a payload that couldn't be indexed, identified by its position in the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidDocument {
+    pub position: u32,
+    pub error: ResponseError,
 }
 
 impl Details {
     pub fn to_failed(&self) -> Self {
         let mut details = self.clone();
         match &mut details {
-            Self::DocumentAdditionOrUpdate { indexed_documents, .. } => {
-                *indexed_documents = Some(0)
+            Self::DocumentAdditionOrUpdate {
+                indexed_documents,
+                embedding_failures,
+                invalid_documents,
+                outdated_documents,
+                ..
+            } => {
+                *indexed_documents = Some(0);
+                *embedding_failures = None;
+                *invalid_documents = None;
+                *outdated_documents = None;
             }
             Self::DocumentDeletion { deleted_documents, .. } => *deleted_documents = Some(0),
-            Self::DocumentDeletionByFilter { deleted_documents, .. } => {
-                *deleted_documents = Some(0)
+            Self::DocumentDeletionByFilter {
+                deleted_documents, deleted_documents_sample, ..
+            } => {
+                *deleted_documents = Some(0);
+                *deleted_documents_sample = None;
             }
             Self::ClearAll { deleted_documents } => *deleted_documents = Some(0),
             Self::TaskCancelation { canceled_tasks, .. } => *canceled_tasks = Some(0),
             Self::TaskDeletion { deleted_tasks, .. } => *deleted_tasks = Some(0),
+            Self::IndexExport { exported_documents, destination, .. } => {
+                *exported_documents = Some(0);
+                *destination = None;
+            }
             Self::SettingsUpdate { .. }
             | Self::IndexInfo { .. }
             | Self::Dump { .. }
-            | Self::IndexSwap { .. } => (),
+            | Self::IndexSwap { .. }
+            | Self::IndexCopy { .. } => (),
         }
 
         details
@@ -11,6 +11,7 @@ use byte_unit::Byte;
 use http::header::CONTENT_TYPE;
 use index_scheduler::IndexScheduler;
 use meilisearch_auth::{AuthController, AuthFilter};
+use meilisearch_types::milli::update::AttributeOverflowPolicy;
 use meilisearch_types::InstanceUid;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -34,9 +35,10 @@ use crate::routes::indexes::facet_search::FacetSearchQuery;
 use crate::routes::tasks::TasksFilterQuery;
 use crate::routes::{create_all_stats, Stats};
 use crate::search::{
-    FacetSearchResult, MatchingStrategy, SearchQuery, SearchQueryWithIndex, SearchResult,
-    DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEMANTIC_RATIO,
+    FacetSearchResult, HybridCandidateSelection, HybridFusion, MatchingStrategy, SearchQuery,
+    SearchQueryWithIndex, SearchResult, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEMANTIC_RATIO,
 };
 use crate::Opt;
 
@@ -252,6 +254,11 @@ struct Infos {
     experimental_enable_metrics: bool,
     experimental_reduce_indexing_memory_usage: bool,
     experimental_max_number_of_batched_tasks: usize,
+    experimental_search_cache_size: usize,
+    experimental_search_max_in_flight: usize,
+    experimental_search_queue_size: usize,
+    experimental_search_deadline_ms: u64,
+    experimental_export_allowed_urls: bool,
     db_path: bool,
     import_dump: bool,
     dump_dir: bool,
@@ -267,6 +274,8 @@ struct Infos {
     log_level: String,
     max_indexing_memory: MaxMemory,
     max_indexing_threads: MaxThreads,
+    attribute_overflow_policy: AttributeOverflowPolicy,
+    unchanged_document_ignored_fields: bool,
     with_configuration_file: bool,
     ssl_auth_path: bool,
     ssl_cert_path: bool,
@@ -287,6 +296,11 @@ impl From<Opt> for Infos {
             experimental_enable_metrics,
             experimental_reduce_indexing_memory_usage,
             experimental_max_number_of_batched_tasks,
+            experimental_search_cache_size,
+            experimental_search_max_in_flight,
+            experimental_search_queue_size,
+            experimental_search_deadline_ms,
+            experimental_export_allowed_urls,
             http_addr,
             master_key: _,
             env,
@@ -321,8 +335,13 @@ impl From<Opt> for Infos {
             ScheduleSnapshot::Enabled(interval) => Some(interval),
         };
 
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } =
-            indexer_options;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            attribute_overflow_policy,
+            unchanged_document_ignored_fields,
+            skip_index_budget: _,
+        } = indexer_options;
 
         // We're going to override every sensible information.
         // We consider information sensible if it contains a path, an address, or a key.
@@ -343,9 +362,16 @@ impl From<Opt> for Infos {
             http_addr: http_addr != default_http_addr(),
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
+            experimental_search_cache_size,
+            experimental_search_max_in_flight,
+            experimental_search_queue_size,
+            experimental_search_deadline_ms,
+            experimental_export_allowed_urls: !experimental_export_allowed_urls.is_empty(),
             log_level: log_level.to_string(),
             max_indexing_memory,
             max_indexing_threads,
+            attribute_overflow_policy,
+            unchanged_document_ignored_fields: !unchanged_document_ignored_fields.is_empty(),
             with_configuration_file: config_file_path.is_some(),
             ssl_auth_path: ssl_auth_path.is_some(),
             ssl_cert_path: ssl_cert_path.is_some(),
@@ -557,6 +583,7 @@ pub struct SearchAggregator {
     // requests
     total_received: usize,
     total_succeeded: usize,
+    total_degraded: usize,
     time_spent: BinaryHeap<usize>,
 
     // sort
@@ -569,6 +596,9 @@ pub struct SearchAggregator {
     // filter
     filter_with_geo_radius: bool,
     filter_with_geo_bounding_box: bool,
+    filter_with_geo_polygon: bool,
+    filter_with_geo_within: bool,
+    filter_with_geo_intersects: bool,
     // every time a request has a filter, this field must be incremented by the number of terms it contains
     filter_sum_of_criteria_terms: usize,
     // every time a request has a filter, this field must be incremented by one
@@ -579,17 +609,33 @@ pub struct SearchAggregator {
     // every time a search is done using attributes_to_search_on
     attributes_to_search_on_total_number_of_uses: usize,
 
+    // attribute_boosts
+    // every time a search is done using attribute_boosts
+    attribute_boosts_total_number_of_uses: usize,
+
     // q
     // The maximum number of terms in a q request
     max_terms_number: usize,
+    // The maximum phrase slop of a request
+    max_phrase_slop: u8,
+    // whether a structured query was used instead of q
+    structured_query: bool,
 
     // vector
     // The maximum number of floats in a vector request
     max_vector_size: usize,
+    // Whether the stored vectors were retrieved in the hits
+    retrieve_vectors: bool,
     // Whether the semantic ratio passed to a hybrid search equals the default ratio.
     semantic_ratio: bool,
     // Whether a non-default embedder was specified
     embedder: bool,
+    // Whether a non-default fusion strategy was specified
+    fusion: bool,
+    // Whether the vectors of several embedders were combined
+    embedders: bool,
+    // Whether a non-default candidate selection of the semantic search was specified
+    candidate_selection: bool,
     hybrid: bool,
 
     // every time a search is done, we increment the counter linked to the used settings
@@ -599,6 +645,11 @@ pub struct SearchAggregator {
     max_limit: usize,
     max_offset: usize,
     finite_pagination: usize,
+    count_only: bool,
+    early_termination: bool,
+    search_after: bool,
+    group_by: bool,
+    distinct: bool,
 
     // formatting
     max_attributes_to_retrieve: usize,
@@ -607,16 +658,38 @@ pub struct SearchAggregator {
     highlight_post_tag: bool,
     max_attributes_to_crop: usize,
     crop_marker: bool,
+    highlight_tags: bool,
+    highlight_escape_html: bool,
+    snippet: bool,
     show_matches_position: bool,
+    matches_position_unit: bool,
+    show_matched_terms: bool,
+    show_corrected_query: bool,
     crop_length: bool,
+    geo_reference_point: bool,
 
     // facets
     facets_sum_of_terms: usize,
     facets_total_number_of_facets: usize,
+    facets_extended_stats: bool,
+    facets_disjunctive: bool,
+    facets_aggregations: bool,
+    facets_sort_facet_values_by: HashMap<String, usize>,
 
     // scoring
     show_ranking_score: bool,
     show_ranking_score_details: bool,
+    ranking_score_threshold: bool,
+    timeout_ms: bool,
+
+    // foreign keys
+    embed_foreign_documents: bool,
+    foreign_filters: bool,
+
+    // overrides
+    pinned_documents: bool,
+    hidden_documents: bool,
+    vocabulary: bool,
 }
 
 impl SearchAggregator {
@@ -624,7 +697,9 @@ impl SearchAggregator {
     pub fn from_query(query: &SearchQuery, request: &HttpRequest) -> Self {
         let SearchQuery {
             q,
+            structured_query,
             vector,
+            retrieve_vectors,
             offset,
             limit,
             page,
@@ -634,17 +709,42 @@ impl SearchAggregator {
             crop_length,
             attributes_to_highlight: _,
             show_matches_position,
+            matches_position_unit,
+            show_matched_terms,
+            show_corrected_query,
             show_ranking_score,
             show_ranking_score_details,
             filter,
             sort,
             facets: _,
+            disjunctive_facets,
+            extended_facet_stats,
+            aggregations,
+            sort_facet_values_by,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            highlight_tags,
+            highlight_escape_html,
+            snippet,
             matching_strategy,
             attributes_to_search_on,
             hybrid,
+            geo_reference_point,
+            attribute_boosts,
+            ranking_score_threshold,
+            timeout_ms,
+            count_only,
+            early_termination,
+            search_after,
+            group_by,
+            distinct,
+            embed_foreign_documents,
+            foreign_filters,
+            pinned_documents,
+            hidden_documents,
+            vocabulary,
+            phrase_slop,
         } = query;
 
         let mut ret = Self::default();
@@ -680,6 +780,9 @@ impl SearchAggregator {
             let stringified_filters = filter.to_string();
             ret.filter_with_geo_radius = stringified_filters.contains("_geoRadius(");
             ret.filter_with_geo_bounding_box = stringified_filters.contains("_geoBoundingBox(");
+            ret.filter_with_geo_polygon = stringified_filters.contains("_geoPolygon(");
+            ret.filter_with_geo_within = stringified_filters.contains("_geoWithin(");
+            ret.filter_with_geo_intersects = stringified_filters.contains("_geoIntersects(");
             ret.filter_sum_of_criteria_terms = RE.split(&stringified_filters).count();
         }
 
@@ -688,13 +791,24 @@ impl SearchAggregator {
             ret.attributes_to_search_on_total_number_of_uses = 1;
         }
 
+        // attribute_boosts
+        if attribute_boosts.is_some() {
+            ret.attribute_boosts_total_number_of_uses = 1;
+        }
+
         if let Some(ref q) = q {
             ret.max_terms_number = q.split_whitespace().count();
         }
+        ret.structured_query = structured_query.is_some();
+
+        if let Some(phrase_slop) = phrase_slop {
+            ret.max_phrase_slop = phrase_slop;
+        }
 
         if let Some(ref vector) = vector {
             ret.max_vector_size = vector.len();
         }
+        ret.retrieve_vectors = *retrieve_vectors;
 
         if query.is_finite_pagination() {
             let limit = hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
@@ -706,21 +820,51 @@ impl SearchAggregator {
             ret.max_offset = *offset;
             ret.finite_pagination = 0;
         }
+        ret.count_only = *count_only;
+        ret.early_termination = *early_termination;
+        ret.search_after = search_after.is_some();
+        ret.group_by = group_by.is_some();
+        ret.distinct = distinct.is_some();
+        ret.embed_foreign_documents = *embed_foreign_documents;
+        ret.foreign_filters = foreign_filters.is_some();
+        ret.pinned_documents = pinned_documents.is_some();
+        ret.hidden_documents = hidden_documents.is_some();
+        ret.vocabulary = vocabulary.is_some();
 
         ret.matching_strategy.insert(format!("{:?}", matching_strategy), 1);
 
         ret.highlight_pre_tag = *highlight_pre_tag != DEFAULT_HIGHLIGHT_PRE_TAG();
         ret.highlight_post_tag = *highlight_post_tag != DEFAULT_HIGHLIGHT_POST_TAG();
         ret.crop_marker = *crop_marker != DEFAULT_CROP_MARKER();
+        ret.highlight_tags = highlight_tags.is_some();
+        ret.highlight_escape_html = *highlight_escape_html;
+        ret.snippet = snippet.is_some();
         ret.crop_length = *crop_length != DEFAULT_CROP_LENGTH();
         ret.show_matches_position = *show_matches_position;
+        ret.matches_position_unit = matches_position_unit.is_some();
+        ret.show_matched_terms = *show_matched_terms;
+        ret.show_corrected_query = *show_corrected_query;
+        ret.geo_reference_point = geo_reference_point.is_some();
+
+        ret.facets_extended_stats = *extended_facet_stats;
+        ret.facets_disjunctive = disjunctive_facets.is_some();
+        ret.facets_aggregations = aggregations.is_some();
+        if let Some(sort_facet_values_by) = sort_facet_values_by {
+            ret.facets_sort_facet_values_by.insert(format!("{:?}", sort_facet_values_by), 1);
+        }
 
         ret.show_ranking_score = *show_ranking_score;
         ret.show_ranking_score_details = *show_ranking_score_details;
+        ret.ranking_score_threshold = ranking_score_threshold.is_some();
+        ret.timeout_ms = timeout_ms.is_some();
 
         if let Some(hybrid) = hybrid {
             ret.semantic_ratio = hybrid.semantic_ratio != DEFAULT_SEMANTIC_RATIO();
             ret.embedder = hybrid.embedder.is_some();
+            ret.fusion = hybrid.fusion != HybridFusion::default();
+            ret.embedders = hybrid.embedders.is_some();
+            ret.candidate_selection =
+                hybrid.candidate_selection != HybridCandidateSelection::default();
             ret.hybrid = true;
         }
 
@@ -736,9 +880,18 @@ impl SearchAggregator {
             hits_info: _,
             facet_distribution: _,
             facet_stats: _,
+            aggregations: _,
+            degraded,
+            next_search_after: _,
+            group_counts: _,
+            reranking: _,
+            corrected_query: _,
         } = result;
 
         self.total_succeeded = self.total_succeeded.saturating_add(1);
+        if *degraded {
+            self.total_degraded = self.total_degraded.saturating_add(1);
+        }
         self.time_spent.push(*processing_time_ms as usize);
     }
 
@@ -749,36 +902,70 @@ impl SearchAggregator {
             user_agents,
             total_received,
             total_succeeded,
+            total_degraded,
             ref mut time_spent,
             sort_with_geo_point,
             sort_sum_of_criteria_terms,
             sort_total_number_of_criteria,
             filter_with_geo_radius,
             filter_with_geo_bounding_box,
+            filter_with_geo_polygon,
+            filter_with_geo_within,
+            filter_with_geo_intersects,
             filter_sum_of_criteria_terms,
             filter_total_number_of_criteria,
             used_syntax,
             attributes_to_search_on_total_number_of_uses,
+            attribute_boosts_total_number_of_uses,
             max_terms_number,
+            max_phrase_slop,
+            structured_query,
             max_vector_size,
+            retrieve_vectors,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            count_only,
+            early_termination,
+            search_after,
+            group_by,
+            distinct,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
             highlight_post_tag,
             max_attributes_to_crop,
             crop_marker,
+            highlight_tags,
+            highlight_escape_html,
+            snippet,
             show_matches_position,
+            matches_position_unit,
+            show_matched_terms,
+            show_corrected_query,
             crop_length,
+            geo_reference_point,
             facets_sum_of_terms,
             facets_total_number_of_facets,
+            facets_extended_stats,
+            facets_disjunctive,
+            facets_aggregations,
+            facets_sort_facet_values_by,
             show_ranking_score,
             show_ranking_score_details,
+            ranking_score_threshold,
+            timeout_ms,
+            embed_foreign_documents,
+            foreign_filters,
+            pinned_documents,
+            hidden_documents,
+            vocabulary,
             semantic_ratio,
             embedder,
+            fusion,
+            embedders,
+            candidate_selection,
             hybrid,
         } = other;
 
@@ -794,6 +981,7 @@ impl SearchAggregator {
         // request
         self.total_received = self.total_received.saturating_add(total_received);
         self.total_succeeded = self.total_succeeded.saturating_add(total_succeeded);
+        self.total_degraded = self.total_degraded.saturating_add(total_degraded);
         self.time_spent.append(time_spent);
 
         // sort
@@ -806,6 +994,9 @@ impl SearchAggregator {
         // filter
         self.filter_with_geo_radius |= filter_with_geo_radius;
         self.filter_with_geo_bounding_box |= filter_with_geo_bounding_box;
+        self.filter_with_geo_polygon |= filter_with_geo_polygon;
+        self.filter_with_geo_within |= filter_with_geo_within;
+        self.filter_with_geo_intersects |= filter_with_geo_intersects;
         self.filter_sum_of_criteria_terms =
             self.filter_sum_of_criteria_terms.saturating_add(filter_sum_of_criteria_terms);
         self.filter_total_number_of_criteria =
@@ -820,19 +1011,35 @@ impl SearchAggregator {
             .attributes_to_search_on_total_number_of_uses
             .saturating_add(attributes_to_search_on_total_number_of_uses);
 
+        // attribute_boosts
+        self.attribute_boosts_total_number_of_uses = self
+            .attribute_boosts_total_number_of_uses
+            .saturating_add(attribute_boosts_total_number_of_uses);
+
         // q
         self.max_terms_number = self.max_terms_number.max(max_terms_number);
+        self.max_phrase_slop = self.max_phrase_slop.max(max_phrase_slop);
+        self.structured_query |= structured_query;
 
         // vector
         self.max_vector_size = self.max_vector_size.max(max_vector_size);
+        self.retrieve_vectors |= retrieve_vectors;
         self.semantic_ratio |= semantic_ratio;
         self.hybrid |= hybrid;
         self.embedder |= embedder;
+        self.fusion |= fusion;
+        self.embedders |= embedders;
+        self.candidate_selection |= candidate_selection;
 
         // pagination
         self.max_limit = self.max_limit.max(max_limit);
         self.max_offset = self.max_offset.max(max_offset);
         self.finite_pagination += finite_pagination;
+        self.count_only |= count_only;
+        self.early_termination |= early_termination;
+        self.search_after |= search_after;
+        self.group_by |= group_by;
+        self.distinct |= distinct;
 
         // formatting
         self.max_attributes_to_retrieve =
@@ -843,13 +1050,27 @@ impl SearchAggregator {
         self.highlight_post_tag |= highlight_post_tag;
         self.max_attributes_to_crop = self.max_attributes_to_crop.max(max_attributes_to_crop);
         self.crop_marker |= crop_marker;
+        self.highlight_tags |= highlight_tags;
+        self.highlight_escape_html |= highlight_escape_html;
+        self.snippet |= snippet;
         self.show_matches_position |= show_matches_position;
+        self.matches_position_unit |= matches_position_unit;
+        self.show_matched_terms |= show_matched_terms;
+        self.show_corrected_query |= show_corrected_query;
         self.crop_length |= crop_length;
+        self.geo_reference_point |= geo_reference_point;
 
         // facets
         self.facets_sum_of_terms = self.facets_sum_of_terms.saturating_add(facets_sum_of_terms);
         self.facets_total_number_of_facets =
             self.facets_total_number_of_facets.saturating_add(facets_total_number_of_facets);
+        self.facets_extended_stats |= facets_extended_stats;
+        self.facets_disjunctive |= facets_disjunctive;
+        self.facets_aggregations |= facets_aggregations;
+        for (key, value) in facets_sort_facet_values_by.into_iter() {
+            let sort_facet_values_by = self.facets_sort_facet_values_by.entry(key).or_insert(0);
+            *sort_facet_values_by = sort_facet_values_by.saturating_add(value);
+        }
 
         // matching strategy
         for (key, value) in matching_strategy.into_iter() {
@@ -860,6 +1081,17 @@ impl SearchAggregator {
         // scoring
         self.show_ranking_score |= show_ranking_score;
         self.show_ranking_score_details |= show_ranking_score_details;
+        self.ranking_score_threshold |= ranking_score_threshold;
+        self.timeout_ms |= timeout_ms;
+
+        // foreign keys
+        self.embed_foreign_documents |= embed_foreign_documents;
+        self.foreign_filters |= foreign_filters;
+
+        // overrides
+        self.pinned_documents |= pinned_documents;
+        self.hidden_documents |= hidden_documents;
+        self.vocabulary |= vocabulary;
     }
 
     pub fn into_event(self, user: &User, event_name: &str) -> Option<Track> {
@@ -868,36 +1100,70 @@ impl SearchAggregator {
             user_agents,
             total_received,
             total_succeeded,
+            total_degraded,
             time_spent,
             sort_with_geo_point,
             sort_sum_of_criteria_terms,
             sort_total_number_of_criteria,
             filter_with_geo_radius,
             filter_with_geo_bounding_box,
+            filter_with_geo_polygon,
+            filter_with_geo_within,
+            filter_with_geo_intersects,
             filter_sum_of_criteria_terms,
             filter_total_number_of_criteria,
             used_syntax,
             attributes_to_search_on_total_number_of_uses,
+            attribute_boosts_total_number_of_uses,
             max_terms_number,
+            max_phrase_slop,
+            structured_query,
             max_vector_size,
+            retrieve_vectors,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            count_only,
+            early_termination,
+            search_after,
+            group_by,
+            distinct,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
             highlight_post_tag,
             max_attributes_to_crop,
             crop_marker,
+            highlight_tags,
+            highlight_escape_html,
+            snippet,
             show_matches_position,
+            matches_position_unit,
+            show_matched_terms,
+            show_corrected_query,
             crop_length,
+            geo_reference_point,
             facets_sum_of_terms,
             facets_total_number_of_facets,
+            facets_extended_stats,
+            facets_disjunctive,
+            facets_aggregations,
+            facets_sort_facet_values_by,
             show_ranking_score,
             show_ranking_score_details,
+            ranking_score_threshold,
+            timeout_ms,
+            embed_foreign_documents,
+            foreign_filters,
+            pinned_documents,
+            hidden_documents,
+            vocabulary,
             semantic_ratio,
             embedder,
+            fusion,
+            embedders,
+            candidate_selection,
             hybrid,
         } = self;
 
@@ -917,6 +1183,7 @@ impl SearchAggregator {
                     "99th_response_time": time_spent.map(|t| format!("{:.2}", t)),
                     "total_succeeded": total_succeeded,
                     "total_failed": total_received.saturating_sub(total_succeeded), // just to be sure we never panics
+                    "total_degraded": total_degraded,
                     "total_received": total_received,
                 },
                 "sort": {
@@ -926,27 +1193,44 @@ impl SearchAggregator {
                 "filter": {
                    "with_geoRadius": filter_with_geo_radius,
                    "with_geoBoundingBox": filter_with_geo_bounding_box,
+                   "with_geoPolygon": filter_with_geo_polygon,
+                   "with_geoWithin": filter_with_geo_within,
+                   "with_geoIntersects": filter_with_geo_intersects,
                    "avg_criteria_number": format!("{:.2}", filter_sum_of_criteria_terms as f64 / filter_total_number_of_criteria as f64),
                    "most_used_syntax": used_syntax.iter().max_by_key(|(_, v)| *v).map(|(k, _)| json!(k)).unwrap_or_else(|| json!(null)),
                 },
                 "attributes_to_search_on": {
                    "total_number_of_uses": attributes_to_search_on_total_number_of_uses,
                 },
+                "attribute_boosts": {
+                   "total_number_of_uses": attribute_boosts_total_number_of_uses,
+                },
                 "q": {
                    "max_terms_number": max_terms_number,
+                   "max_phrase_slop": max_phrase_slop,
+                   "structured_query": structured_query,
                 },
                 "vector": {
                     "max_vector_size": max_vector_size,
+                    "retrieve_vectors": retrieve_vectors,
                 },
                 "hybrid": {
                     "enabled": hybrid,
                     "semantic_ratio": semantic_ratio,
                     "embedder": embedder,
+                    "fusion": fusion,
+                    "embedders": embedders,
+                    "candidate_selection": candidate_selection,
                 },
                 "pagination": {
                    "max_limit": max_limit,
                    "max_offset": max_offset,
                    "most_used_navigation": if finite_pagination > (total_received / 2) { "exhaustive" } else { "estimated" },
+                   "count_only": count_only,
+                   "early_termination": early_termination,
+                   "search_after": search_after,
+                   "group_by": group_by,
+                   "distinct": distinct,
                 },
                 "formatting": {
                     "max_attributes_to_retrieve": max_attributes_to_retrieve,
@@ -955,11 +1239,22 @@ impl SearchAggregator {
                     "highlight_post_tag": highlight_post_tag,
                     "max_attributes_to_crop": max_attributes_to_crop,
                     "crop_marker": crop_marker,
+                    "highlight_tags": highlight_tags,
+                    "highlight_escape_html": highlight_escape_html,
+                    "snippet": snippet,
                     "show_matches_position": show_matches_position,
+                    "matches_position_unit": matches_position_unit,
+                    "show_matched_terms": show_matched_terms,
+                    "show_corrected_query": show_corrected_query,
                     "crop_length": crop_length,
+                    "geo_reference_point": geo_reference_point,
                 },
                 "facets": {
                     "avg_facets_number": format!("{:.2}", facets_sum_of_terms as f64 / facets_total_number_of_facets as f64),
+                    "extended_stats": facets_extended_stats,
+                    "disjunctive": facets_disjunctive,
+                    "aggregations": facets_aggregations,
+                    "most_used_sort_facet_values_by": facets_sort_facet_values_by.iter().max_by_key(|(_, v)| *v).map(|(k, _)| json!(k)).unwrap_or_else(|| json!(null)),
                 },
                 "matching_strategy": {
                     "most_used_strategy": matching_strategy.iter().max_by_key(|(_, v)| *v).map(|(k, _)| json!(k)).unwrap_or_else(|| json!(null)),
@@ -967,6 +1262,17 @@ impl SearchAggregator {
                 "scoring": {
                     "show_ranking_score": show_ranking_score,
                     "show_ranking_score_details": show_ranking_score_details,
+                    "ranking_score_threshold": ranking_score_threshold,
+                    "timeout_ms": timeout_ms,
+                },
+                "foreign_keys": {
+                    "embed_foreign_documents": embed_foreign_documents,
+                    "foreign_filters": foreign_filters,
+                },
+                "overrides": {
+                    "pinned_documents": pinned_documents,
+                    "hidden_documents": hidden_documents,
+                    "vocabulary": vocabulary,
                 },
             });
 
@@ -1018,7 +1324,9 @@ impl MultiSearchAggregator {
                 let SearchQueryWithIndex {
                     index_uid,
                     q: _,
+                    structured_query: _,
                     vector: _,
+                    retrieve_vectors: _,
                     offset: _,
                     limit: _,
                     page: _,
@@ -1030,15 +1338,40 @@ impl MultiSearchAggregator {
                     show_ranking_score: _,
                     show_ranking_score_details: _,
                     show_matches_position: _,
+                    matches_position_unit: _,
+                    show_matched_terms: _,
+                    show_corrected_query: _,
                     filter: _,
                     sort: _,
                     facets: _,
+                    disjunctive_facets: _,
+                    extended_facet_stats: _,
+                    aggregations: _,
+                    sort_facet_values_by: _,
                     highlight_pre_tag: _,
                     highlight_post_tag: _,
                     crop_marker: _,
+                    highlight_tags: _,
+                    highlight_escape_html: _,
+                    snippet: _,
                     matching_strategy: _,
                     attributes_to_search_on: _,
                     hybrid: _,
+                    geo_reference_point: _,
+                    attribute_boosts: _,
+                    ranking_score_threshold: _,
+                    timeout_ms: _,
+                    count_only: _,
+                    early_termination: _,
+                    search_after: _,
+                    group_by: _,
+                    distinct: _,
+                    embed_foreign_documents: _,
+                    foreign_filters: _,
+                    pinned_documents: _,
+                    hidden_documents: _,
+                    vocabulary: _,
+                    phrase_slop: _,
                 } = query;
 
                 index_uid.as_str()
@@ -1313,7 +1646,14 @@ impl DocumentsAggregator {
         index_creation: bool,
         request: &HttpRequest,
     ) -> Self {
-        let UpdateDocumentsQuery { primary_key, csv_delimiter: _ } = documents_query;
+        let UpdateDocumentsQuery {
+            primary_key,
+            csv_delimiter: _,
+            csv_quote: _,
+            csv_escape: _,
+            csv_array_delimiter: _,
+            strict: _,
+        } = documents_query;
 
         let mut primary_keys = HashSet::new();
         if let Some(primary_key) = primary_key.clone() {
@@ -1645,6 +1985,10 @@ pub struct DocumentsFetchAggregator {
     per_document_id: bool,
     // if a filter was used
     per_filter: bool,
+    // if a sort was used
+    per_sort: bool,
+    // if a list of ids was used
+    per_ids: bool,
 
     // pagination
     #[serde(rename = "pagination.max_limit")]
@@ -1665,6 +2009,8 @@ impl DocumentsFetchAggregator {
             total_received: 1,
             per_document_id: matches!(query, DocumentFetchKind::PerDocumentId),
             per_filter: matches!(query, DocumentFetchKind::Normal { with_filter, .. } if *with_filter),
+            per_sort: matches!(query, DocumentFetchKind::Normal { with_sort, .. } if *with_sort),
+            per_ids: matches!(query, DocumentFetchKind::Normal { with_ids, .. } if *with_ids),
             max_limit: limit,
             max_offset: offset,
         }
@@ -1678,6 +2024,8 @@ impl DocumentsFetchAggregator {
             total_received,
             per_document_id,
             per_filter,
+            per_sort,
+            per_ids,
             max_limit,
             max_offset,
         } = other;
@@ -1692,6 +2040,8 @@ impl DocumentsFetchAggregator {
         self.total_received = self.total_received.saturating_add(total_received);
         self.per_document_id |= per_document_id;
         self.per_filter |= per_filter;
+        self.per_sort |= per_sort;
+        self.per_ids |= per_ids;
 
         self.max_limit = self.max_limit.max(max_limit);
         self.max_offset = self.max_offset.max(max_offset);
@@ -0,0 +1,206 @@
+use deserr::Deserr;
+use meilisearch_types::chat::{
+    ChatCompletionSettings, DEFAULT_SYSTEM_PROMPT, DOCUMENTS_PLACEHOLDER,
+};
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::Document;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+
+use crate::error::MeilisearchHttpError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserr, Serialize)]
+#[deserr(error = DeserrJsonError<InvalidChatCompletionMessages>, rename_all = camelCase, deny_unknown_fields)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr, Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+/// A document retrieved by the searches, cited by its position in the sources.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSource {
+    pub index_uid: String,
+    pub document: Document,
+}
+
+/// Answers the conversations with the model of an OpenAI-compatible API.
+pub struct ChatCompleter {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+}
+
+impl ChatCompleter {
+    pub fn new(settings: &ChatCompletionSettings) -> Result<Self, MeilisearchHttpError> {
+        let base_url = settings
+            .base_url
+            .as_deref()
+            .or_else(|| settings.source.default_base_url())
+            .ok_or(MeilisearchHttpError::MissingChatCompletionBaseUrl)?;
+        let model =
+            settings.model.clone().ok_or(MeilisearchHttpError::MissingChatCompletionModel)?;
+
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &settings.api_key {
+            let bearer = HeaderValue::from_str(&format!("Bearer {api_key}"))
+                .map_err(|e| MeilisearchHttpError::InvalidChatCompletionApiKey(e.to_string()))?;
+            headers.insert(AUTHORIZATION, bearer);
+        }
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let client = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| MeilisearchHttpError::ChatCompletionProvider(e.to_string()))?;
+
+        let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+        Ok(Self { client, url, model })
+    }
+
+    /// Returns the whole answer of the model to the messages.
+    pub async fn complete(&self, messages: &[ChatMessage]) -> Result<String, MeilisearchHttpError> {
+        let response = self.send(messages, false).await?;
+        let response: CompletionResponse = response
+            .json()
+            .await
+            .map_err(|e| MeilisearchHttpError::ChatCompletionProvider(e.to_string()))?;
+
+        Ok(response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default())
+    }
+
+    /// Starts streaming the answer of the model to the messages.
+    pub async fn stream(
+        &self,
+        messages: &[ChatMessage],
+    ) -> Result<CompletionStream, MeilisearchHttpError> {
+        let response = self.send(messages, true).await?;
+        Ok(CompletionStream { response, buffer: Vec::new() })
+    }
+
+    async fn send(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<reqwest::Response, MeilisearchHttpError> {
+        let request = CompletionRequest { model: &self.model, messages, stream };
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| MeilisearchHttpError::ChatCompletionProvider(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(MeilisearchHttpError::ChatCompletionProvider(format!(
+                "received unhandled HTTP status code {}",
+                response.status().as_u16()
+            )));
+        }
+        Ok(response)
+    }
+}
+
+/// The pieces of an answer, read from the server-sent events of the provider.
+pub struct CompletionStream {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+}
+
+impl CompletionStream {
+    /// Returns the next piece of the answer, `None` once the answer is complete.
+    pub async fn next_delta(&mut self) -> Result<Option<String>, MeilisearchHttpError> {
+        loop {
+            while let Some(position) = self.buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=position).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:") else { continue };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    return Ok(None);
+                }
+
+                let chunk: CompletionChunk = serde_json::from_str(data)
+                    .map_err(|e| MeilisearchHttpError::ChatCompletionProvider(e.to_string()))?;
+                let content = chunk.choices.into_iter().next().and_then(|c| c.delta.content);
+                if let Some(content) = content.filter(|content| !content.is_empty()) {
+                    return Ok(Some(content));
+                }
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(bytes)) => self.buffer.extend_from_slice(&bytes),
+                Ok(None) => return Ok(None),
+                Err(e) => return Err(MeilisearchHttpError::ChatCompletionProvider(e.to_string())),
+            }
+        }
+    }
+}
+
+/// Renders the system prompt of the settings, listing the sources with their number.
+pub fn system_prompt(settings: &ChatCompletionSettings, sources: &[ChatSource]) -> String {
+    let documents: Vec<_> = sources
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            let document = serde_json::to_string(&source.document).unwrap_or_default();
+            format!("[{}] ({}) {document}", i + 1, source.index_uid)
+        })
+        .collect();
+
+    settings
+        .system_prompt
+        .as_deref()
+        .unwrap_or(DEFAULT_SYSTEM_PROMPT)
+        .replace(DOCUMENTS_PLACEHOLDER, &documents.join("\n"))
+}
+
+// chat completions api structs
+
+#[derive(Debug, Serialize)]
+struct CompletionRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionResponse {
+    choices: Vec<CompletionChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionChoice {
+    message: CompletionMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionChunk {
+    choices: Vec<CompletionChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionChunkChoice {
+    delta: CompletionMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
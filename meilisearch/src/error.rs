@@ -12,8 +12,8 @@ pub enum MeilisearchHttpError {
     #[error("A Content-Type header is missing. Accepted values for the Content-Type header are: {}",
             .0.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", "))]
     MissingContentType(Vec<String>),
-    #[error("The Content-Type `{0}` does not support the use of a csv delimiter. The csv delimiter can only be used with the Content-Type `text/csv`.")]
-    CsvDelimiterWithWrongContentType(String),
+    #[error("The Content-Type `{0}` does not support the use of the csv parameters. The csv parameters can only be used with the Content-Type `text/csv`.")]
+    CsvOptionsWithWrongContentType(String),
     #[error(
         "The Content-Type `{0}` is invalid. Accepted values for the Content-Type header are: {}",
         .1.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", ")
@@ -53,13 +53,61 @@ pub enum MeilisearchHttpError {
     Join(#[from] JoinError),
     #[error("Invalid request: missing `hybrid` parameter when both `q` and `vector` are present.")]
     MissingSearchHybrid,
+    #[error("Invalid request: `searchAfter` cannot be used with a hybrid search mixing `q` and `vector`.")]
+    SearchAfterWithHybrid,
+    #[error(
+        "Invalid request: `groupBy` cannot be used with a hybrid search mixing `q` and `vector`."
+    )]
+    GroupByWithHybrid,
+    #[error("Invalid request: `q` and `structuredQuery` cannot be used together.")]
+    QueryWithStructuredQuery,
+    #[error("Invalid request: invalid `structuredQuery.{0}` clause, {1}.")]
+    InvalidQueryClause(&'static str, String),
+    #[error("Invalid request: `matchingStrategy` cannot be used with `structuredQuery`, the documents must match all the `must` clauses and are ranked by the `should` clauses they match.")]
+    MatchingStrategyWithStructuredQuery,
+    #[error("Invalid request: `hybrid.embedder` and `hybrid.embedders` cannot be used together.")]
+    EmbedderWithEmbedders,
+    #[error("Invalid request: `vector` cannot be used with `hybrid.embedders`, the query is embedded by each embedder.")]
+    VectorWithEmbedders,
+    #[error("Invalid request: `hybrid.embedders` must contain at least one embedder.")]
+    EmptyEmbedders,
+    #[error("Invalid request: the weight of the embedder `{0}` in `hybrid.embedders` must be a positive number, found `{1}`.")]
+    InvalidEmbedderWeight(String, f32),
+    #[error("Document `{0}` has no vector for the embedder `{1}`, similar documents cannot be searched.")]
+    DocumentWithoutVector(String, String),
+    #[error(transparent)]
+    InvalidDisjunctiveFacets(meilisearch_types::milli::UserError),
+    #[error("Invalid request: the attribute `{0}` of `foreignFilters` is not a foreign key of the index.")]
+    UnknownForeignKey(String),
+    #[error("Invalid request: the filter of the foreign key `{0}` in `foreignFilters` matches {1} documents, it must not match more than {} documents.", crate::search::MAX_FOREIGN_FILTER_DOCUMENTS)]
+    TooManyForeignDocuments(String, u64),
+    #[error("The foreign key `{0}` references the index `{1}`, which is not accessible with the provided API key.")]
+    UnauthorizedForeignIndex(String, String),
+    #[error("The suggestions of the index `{0}` cannot be restricted by the filter of the provided tenant token.")]
+    SuggestionsWithTenantFilter(String),
+    #[error(
+        "The chat completions settings must specify a `baseUrl`, the source has no default URL."
+    )]
+    MissingChatCompletionBaseUrl,
+    #[error("The chat completions settings must specify the `model` answering the conversations.")]
+    MissingChatCompletionModel,
+    #[error("The chat completions API key is invalid: {0}.")]
+    InvalidChatCompletionApiKey(String),
+    #[error("Invalid request: the last of the `messages` must be a `user` message, it is the query of the searches.")]
+    ChatCompletionWithoutUserMessage,
+    #[error("The chat completion provider failed: {0}.")]
+    ChatCompletionProvider(String),
+    #[error("Too many search requests running and pending, the limit of {0} pending searches is reached. Try again later.")]
+    TooManySearchRequests(usize),
+    #[error("The search could not be processed within the {0}ms deadline. Try again later.")]
+    SearchDeadlineExceeded(u64),
 }
 
 impl ErrorCode for MeilisearchHttpError {
     fn error_code(&self) -> Code {
         match self {
             MeilisearchHttpError::MissingContentType(_) => Code::MissingContentType,
-            MeilisearchHttpError::CsvDelimiterWithWrongContentType(_) => Code::InvalidContentType,
+            MeilisearchHttpError::CsvOptionsWithWrongContentType(_) => Code::InvalidContentType,
             MeilisearchHttpError::MissingPayload(_) => Code::MissingPayload,
             MeilisearchHttpError::InvalidContentType(_, _) => Code::InvalidContentType,
             MeilisearchHttpError::DocumentNotFound(_) => Code::DocumentNotFound,
@@ -77,6 +125,42 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::DocumentFormat(e) => e.error_code(),
             MeilisearchHttpError::Join(_) => Code::Internal,
             MeilisearchHttpError::MissingSearchHybrid => Code::MissingSearchHybrid,
+            MeilisearchHttpError::SearchAfterWithHybrid => Code::InvalidSearchSearchAfter,
+            MeilisearchHttpError::GroupByWithHybrid => Code::InvalidSearchGroupBy,
+            MeilisearchHttpError::QueryWithStructuredQuery
+            | MeilisearchHttpError::InvalidQueryClause(_, _)
+            | MeilisearchHttpError::MatchingStrategyWithStructuredQuery => {
+                Code::InvalidSearchStructuredQuery
+            }
+            MeilisearchHttpError::EmbedderWithEmbedders
+            | MeilisearchHttpError::VectorWithEmbedders
+            | MeilisearchHttpError::EmptyEmbedders
+            | MeilisearchHttpError::InvalidEmbedderWeight(_, _) => {
+                Code::InvalidSearchHybridEmbedders
+            }
+            MeilisearchHttpError::DocumentWithoutVector(_, _) => Code::InvalidSimilarId,
+            MeilisearchHttpError::InvalidDisjunctiveFacets(_) => {
+                Code::InvalidSearchDisjunctiveFacets
+            }
+            MeilisearchHttpError::UnknownForeignKey(_)
+            | MeilisearchHttpError::TooManyForeignDocuments(_, _) => {
+                Code::InvalidSearchForeignFilters
+            }
+            MeilisearchHttpError::UnauthorizedForeignIndex(_, _) => Code::InvalidApiKey,
+            MeilisearchHttpError::SuggestionsWithTenantFilter(_) => Code::InvalidApiKey,
+            MeilisearchHttpError::MissingChatCompletionBaseUrl => {
+                Code::InvalidChatCompletionBaseUrl
+            }
+            MeilisearchHttpError::MissingChatCompletionModel => Code::InvalidChatCompletionModel,
+            MeilisearchHttpError::InvalidChatCompletionApiKey(_) => {
+                Code::InvalidChatCompletionApiKey
+            }
+            MeilisearchHttpError::ChatCompletionWithoutUserMessage => {
+                Code::InvalidChatCompletionMessages
+            }
+            MeilisearchHttpError::ChatCompletionProvider(_) => Code::ChatCompletionProviderError,
+            MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
+            MeilisearchHttpError::SearchDeadlineExceeded(_) => Code::SearchDeadlineExceeded,
         }
     }
 }
@@ -2,6 +2,7 @@
 #[macro_use]
 pub mod error;
 pub mod analytics;
+pub mod chat;
 #[macro_use]
 pub mod extractors;
 pub mod metrics;
@@ -9,6 +10,8 @@ pub mod middleware;
 pub mod option;
 pub mod routes;
 pub mod search;
+pub mod search_cache;
+pub mod search_queue;
 
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
@@ -39,6 +42,8 @@ use meilisearch_types::versioning::{check_version_file, create_version_file};
 use meilisearch_types::{compression, milli, VERSION_FILE_NAME};
 pub use option::Opt;
 use option::ScheduleSnapshot;
+use search_cache::SearchCache;
+use search_queue::SearchQueue;
 
 use crate::error::MeilisearchHttpError;
 
@@ -91,6 +96,8 @@ pub fn create_app(
     auth_controller: Data<AuthController>,
     opt: Opt,
     analytics: Arc<dyn Analytics>,
+    search_cache: Data<SearchCache>,
+    search_queue: Data<SearchQueue>,
     enable_dashboard: bool,
 ) -> actix_web::App<
     impl ServiceFactory<
@@ -109,6 +116,8 @@ pub fn create_app(
                 auth_controller.clone(),
                 &opt,
                 analytics.clone(),
+                search_cache.clone(),
+                search_queue.clone(),
             )
         })
         .configure(routes::configure)
@@ -228,6 +237,8 @@ fn open_or_create_database_unchecked(
             indexes_path: opt.db_path.join("indexes"),
             snapshots_path: opt.snapshot_dir.clone(),
             dumps_path: opt.dump_dir.clone(),
+            exports_path: opt.db_path.join("exports"),
+            search_analytics_path: opt.db_path.join("search-analytics"),
             task_db_size: opt.max_task_db_size.get_bytes() as usize,
             index_base_map_size: opt.max_index_size.get_bytes() as usize,
             enable_mdb_writemap: opt.experimental_reduce_indexing_memory_usage,
@@ -235,6 +246,7 @@ fn open_or_create_database_unchecked(
             autobatching_enabled: true,
             max_number_of_tasks: 1_000_000,
             max_number_of_batched_tasks: opt.experimental_max_number_of_batched_tasks,
+            export_allowed_urls: opt.experimental_export_allowed_urls.clone(),
             index_growth_amount: byte_unit::Byte::from_str("10GiB").unwrap().get_bytes() as usize,
             index_count: DEFAULT_INDEX_COUNT,
             instance_features,
@@ -390,12 +402,16 @@ pub fn configure_data(
     auth: Data<AuthController>,
     opt: &Opt,
     analytics: Arc<dyn Analytics>,
+    search_cache: Data<SearchCache>,
+    search_queue: Data<SearchQueue>,
 ) {
     let http_payload_size_limit = opt.http_payload_size_limit.get_bytes() as usize;
     config
         .app_data(index_scheduler)
         .app_data(auth)
         .app_data(web::Data::from(analytics))
+        .app_data(search_cache)
+        .app_data(search_queue)
         .app_data(
             web::JsonConfig::default()
                 .limit(http_payload_size_limit)
@@ -2,6 +2,7 @@ use std::env;
 use std::io::{stderr, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use actix_web::http::KeepAlive;
 use actix_web::web::Data;
@@ -9,6 +10,8 @@ use actix_web::HttpServer;
 use index_scheduler::IndexScheduler;
 use is_terminal::IsTerminal;
 use meilisearch::analytics::Analytics;
+use meilisearch::search_cache::SearchCache;
+use meilisearch::search_queue::SearchQueue;
 use meilisearch::{analytics, create_app, prototype_name, setup_meilisearch, Opt};
 use meilisearch_auth::{generate_master_key, AuthController, MASTER_KEY_MIN_SIZE};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
@@ -92,6 +95,14 @@ async fn run_http(
     let opt_clone = opt.clone();
     let index_scheduler = Data::from(index_scheduler);
     let auth_controller = Data::from(auth_controller);
+    // the cache and the queue are shared by all the workers of the server
+    let search_cache = Data::new(SearchCache::new(opt.experimental_search_cache_size));
+    let search_queue = Data::new(SearchQueue::new(
+        opt.experimental_search_max_in_flight,
+        opt.experimental_search_queue_size,
+        (opt.experimental_search_deadline_ms != 0)
+            .then(|| Duration::from_millis(opt.experimental_search_deadline_ms)),
+    ));
 
     let http_server = HttpServer::new(move || {
         create_app(
@@ -99,6 +110,8 @@ async fn run_http(
             auth_controller.clone(),
             opt.clone(),
             analytics.clone(),
+            search_cache.clone(),
+            search_queue.clone(),
             enable_dashboard,
         )
     })
@@ -13,7 +13,7 @@ use std::{env, fmt, fs};
 use byte_unit::{Byte, ByteError};
 use clap::Parser;
 use meilisearch_types::features::InstanceTogglableFeatures;
-use meilisearch_types::milli::update::IndexerConfig;
+use meilisearch_types::milli::update::{AttributeOverflowPolicy, IndexerConfig};
 use rustls::server::{
     AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, ServerSessionMemoryCache,
 };
@@ -53,6 +53,11 @@ const MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE: &str =
     "MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE";
 const MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS: &str =
     "MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS";
+const MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE";
+const MEILI_EXPERIMENTAL_SEARCH_MAX_IN_FLIGHT: &str = "MEILI_EXPERIMENTAL_SEARCH_MAX_IN_FLIGHT";
+const MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE";
+const MEILI_EXPERIMENTAL_SEARCH_DEADLINE_MS: &str = "MEILI_EXPERIMENTAL_SEARCH_DEADLINE_MS";
+const MEILI_EXPERIMENTAL_EXPORT_ALLOWED_URLS: &str = "MEILI_EXPERIMENTAL_EXPORT_ALLOWED_URLS";
 
 const DEFAULT_CONFIG_FILE_PATH: &str = "./config.toml";
 const DEFAULT_DB_PATH: &str = "./data.ms";
@@ -66,6 +71,8 @@ const DEFAULT_DUMP_DIR: &str = "dumps/";
 
 const MEILI_MAX_INDEXING_MEMORY: &str = "MEILI_MAX_INDEXING_MEMORY";
 const MEILI_MAX_INDEXING_THREADS: &str = "MEILI_MAX_INDEXING_THREADS";
+const MEILI_ATTRIBUTE_OVERFLOW_POLICY: &str = "MEILI_ATTRIBUTE_OVERFLOW_POLICY";
+const MEILI_UNCHANGED_DOCUMENT_IGNORED_FIELDS: &str = "MEILI_UNCHANGED_DOCUMENT_IGNORED_FIELDS";
 const DEFAULT_LOG_EVERY_N: usize = 100_000;
 
 // Each environment (index and task-db) is taking space in the virtual address space.
@@ -308,6 +315,44 @@ pub struct Opt {
     #[serde(default = "default_limit_batched_tasks")]
     pub experimental_max_number_of_batched_tasks: usize,
 
+    /// Experimentally caches the results of the most recent searches, up to this number of searches.
+    ///
+    /// A cached result is not used anymore as soon as a task writes in its index. The cache is disabled when set to 0.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE, default_value_t)]
+    #[serde(default)]
+    pub experimental_search_cache_size: usize,
+
+    /// Experimentally limits the number of searches processed at the same time.
+    ///
+    /// The other searches wait for one of them to finish. There is no limit when set to 0.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_MAX_IN_FLIGHT, default_value_t)]
+    #[serde(default)]
+    pub experimental_search_max_in_flight: usize,
+
+    /// Experimentally limits the number of searches waiting to be processed when
+    /// `--experimental-search-max-in-flight` searches are already processed.
+    ///
+    /// The searches received when the queue is full are rejected with a `too_many_search_requests` error.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE, default_value_t = default_search_queue_size())]
+    #[serde(default = "default_search_queue_size")]
+    pub experimental_search_queue_size: usize,
+
+    /// Experimentally limits the time, in milliseconds, a search can wait and be processed.
+    ///
+    /// A search waiting longer is rejected with a `search_deadline_exceeded` error, a search processed
+    /// for the remaining time returns the documents ranked so far. There is no deadline when set to 0.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_DEADLINE_MS, default_value_t)]
+    #[serde(default)]
+    pub experimental_search_deadline_ms: u64,
+
+    /// Experimentally allows the index exports to be uploaded to the URLs starting with one of these
+    /// comma separated prefixes, e.g. `https://my-bucket.s3.amazonaws.com/`.
+    ///
+    /// The prefixes should end with a `/`, the exports can only be written in the exports directory when none is given.
+    #[clap(long, env = MEILI_EXPERIMENTAL_EXPORT_ALLOWED_URLS, value_delimiter = ',')]
+    #[serde(default)]
+    pub experimental_export_allowed_urls: Vec<String>,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -379,6 +424,11 @@ impl Opt {
             max_task_db_size: _,
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
+            experimental_search_cache_size,
+            experimental_search_max_in_flight,
+            experimental_search_queue_size,
+            experimental_search_deadline_ms,
+            experimental_export_allowed_urls,
             ssl_cert_path,
             ssl_key_path,
             ssl_auth_path,
@@ -421,6 +471,28 @@ impl Opt {
             MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS,
             experimental_max_number_of_batched_tasks.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_CACHE_SIZE,
+            experimental_search_cache_size.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_MAX_IN_FLIGHT,
+            experimental_search_max_in_flight.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE,
+            experimental_search_queue_size.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_DEADLINE_MS,
+            experimental_search_deadline_ms.to_string(),
+        );
+        if !experimental_export_allowed_urls.is_empty() {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_EXPORT_ALLOWED_URLS,
+                experimental_export_allowed_urls.join(","),
+            );
+        }
         if let Some(ssl_cert_path) = ssl_cert_path {
             export_to_env_if_not_present(MEILI_SSL_CERT_PATH, ssl_cert_path);
         }
@@ -520,6 +592,21 @@ pub struct IndexerOpts {
     #[serde(default)]
     pub max_indexing_threads: MaxThreads,
 
+    /// Defines what happens when a document adds new attributes to an index that already reached
+    /// its limit of 65,536 attributes. `error` fails the indexation while `ignore` skips the
+    /// exceeding attributes. The nested attributes skipped this way are still stored in their
+    /// parent object.
+    #[clap(long, env = MEILI_ATTRIBUTE_OVERFLOW_POLICY, default_value_t)]
+    #[serde(default)]
+    pub attribute_overflow_policy: AttributeOverflowPolicy,
+
+    /// Lists the top-level attributes, such as `updated_at`, that are not considered when checking
+    /// if an updated document differs from its stored version. A document that only differs by
+    /// these attributes is skipped and keeps its stored values.
+    #[clap(long, env = MEILI_UNCHANGED_DOCUMENT_IGNORED_FIELDS, value_delimiter = ',')]
+    #[serde(default)]
+    pub unchanged_document_ignored_fields: Vec<String>,
+
     /// Whether or not we want to determine the budget of virtual memory address space we have available dynamically
     /// (the default), or statically.
     ///
@@ -533,7 +620,13 @@ pub struct IndexerOpts {
 impl IndexerOpts {
     /// Exports the values to their corresponding env vars if they are not set.
     pub fn export_to_env(self) {
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } = self;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            attribute_overflow_policy,
+            unchanged_document_ignored_fields,
+            skip_index_budget: _,
+        } = self;
         if let Some(max_indexing_memory) = max_indexing_memory.0 {
             export_to_env_if_not_present(
                 MEILI_MAX_INDEXING_MEMORY,
@@ -544,6 +637,16 @@ impl IndexerOpts {
             MEILI_MAX_INDEXING_THREADS,
             max_indexing_threads.0.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_ATTRIBUTE_OVERFLOW_POLICY,
+            attribute_overflow_policy.to_string(),
+        );
+        if !unchanged_document_ignored_fields.is_empty() {
+            export_to_env_if_not_present(
+                MEILI_UNCHANGED_DOCUMENT_IGNORED_FIELDS,
+                unchanged_document_ignored_fields.join(","),
+            );
+        }
     }
 }
 
@@ -562,6 +665,8 @@ impl TryFrom<&IndexerOpts> for IndexerConfig {
             thread_pool: Some(thread_pool),
             max_positions_per_attributes: None,
             skip_index_budget: other.skip_index_budget,
+            attribute_overflow_policy: other.attribute_overflow_policy,
+            unchanged_document_ignored_fields: other.unchanged_document_ignored_fields.clone(),
             ..Default::default()
         })
     }
@@ -739,6 +844,10 @@ fn default_http_payload_size_limit() -> Byte {
     Byte::from_str(DEFAULT_HTTP_PAYLOAD_SIZE_LIMIT).unwrap()
 }
 
+fn default_search_queue_size() -> usize {
+    1000
+}
+
 fn default_limit_batched_tasks() -> usize {
     usize::MAX
 }
@@ -0,0 +1,252 @@
+use actix_http::StatusCode;
+use actix_web::web::{self, Bytes, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use log::debug;
+use meilisearch_types::chat::{ChatCompletionSettings, ChatCompletionSource};
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::keys::actions;
+use meilisearch_types::milli::update::Setting;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::analytics::Analytics;
+use crate::chat::{system_prompt, ChatCompleter, ChatMessage, ChatRole, ChatSource};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::{AuthenticationError, GuardedData};
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::indexes::search::embed;
+use crate::search::{add_search_rules, perform_search, ForeignIndexes, SearchQueryWithIndex};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/settings")
+            .route(web::get().to(SeqHandler(get_chat_settings)))
+            .route(web::patch().to(SeqHandler(patch_chat_settings))),
+    )
+    .service(web::resource("/completions").route(web::post().to(SeqHandler(chat_completions))));
+}
+
+async fn get_chat_settings(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::EXPERIMENTAL_FEATURES_GET }>,
+        Data<IndexScheduler>,
+    >,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_chat_completions("Getting the chat completions settings")?;
+
+    let mut settings = index_scheduler.chat_settings()?;
+    settings.hide_secrets();
+    debug!("returns: {:?}", settings);
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ChatCompletionSettingsUpdate {
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionSource>)]
+    pub source: Setting<ChatCompletionSource>,
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionBaseUrl>)]
+    pub base_url: Setting<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionApiKey>)]
+    pub api_key: Setting<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionModel>)]
+    pub model: Setting<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionSystemPrompt>)]
+    pub system_prompt: Setting<String>,
+}
+
+async fn patch_chat_settings(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::EXPERIMENTAL_FEATURES_UPDATE }>,
+        Data<IndexScheduler>,
+    >,
+    new_settings: AwebJson<ChatCompletionSettingsUpdate, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_chat_completions("Updating the chat completions settings")?;
+
+    let ChatCompletionSettingsUpdate { source, base_url, api_key, model, system_prompt } =
+        new_settings.into_inner();
+    let old_settings = index_scheduler.chat_settings()?;
+    let mut settings = ChatCompletionSettings {
+        source: match source {
+            Setting::Set(source) => source,
+            Setting::Reset => ChatCompletionSource::default(),
+            Setting::NotSet => old_settings.source,
+        },
+        base_url: apply_setting(base_url, old_settings.base_url),
+        api_key: apply_setting(api_key, old_settings.api_key),
+        model: apply_setting(model, old_settings.model),
+        system_prompt: apply_setting(system_prompt, old_settings.system_prompt),
+    };
+
+    analytics.publish(
+        "Chat Completions Settings Updated".to_string(),
+        json!({
+            "source": settings.source,
+            "base_url": settings.base_url.is_some(),
+            "model": settings.model,
+            "system_prompt": settings.system_prompt.is_some(),
+        }),
+        Some(&req),
+    );
+    index_scheduler.put_chat_settings(&settings)?;
+    settings.hide_secrets();
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+fn apply_setting(new: Setting<String>, old: Option<String>) -> Option<String> {
+    match new {
+        Setting::Set(value) => Some(value),
+        Setting::Reset => None,
+        Setting::NotSet => old,
+    }
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ChatCompletionQuery {
+    #[deserr(error = DeserrJsonError<InvalidChatCompletionMessages>, missing_field_error = DeserrJsonError::missing_chat_completion_messages)]
+    pub messages: Vec<ChatMessage>,
+    /// The searches retrieving the documents the answer relies on, searching the last
+    /// user message when they don't specify a `q`.
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionQueries>)]
+    pub queries: Vec<SearchQueryWithIndex>,
+    #[deserr(default, error = DeserrJsonError<InvalidChatCompletionStream>)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatCompletionResult {
+    answer: String,
+    sources: Vec<ChatSource>,
+}
+
+async fn chat_completions(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    params: AwebJson<ChatCompletionQuery, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let ChatCompletionQuery { mut messages, queries, stream } = params.into_inner();
+    debug!("chat completions called with {} messages", messages.len());
+
+    let features = index_scheduler.features();
+    features.check_chat_completions("Using the chat completions route")?;
+
+    let question = match messages.last() {
+        Some(ChatMessage { role: ChatRole::User, content }) => content.clone(),
+        _ => return Err(MeilisearchHttpError::ChatCompletionWithoutUserMessage.into()),
+    };
+
+    let settings = index_scheduler.chat_settings()?;
+    let completer = ChatCompleter::new(&settings)?;
+
+    analytics.publish(
+        "Chat Completions POST".to_string(),
+        json!({
+            "source": settings.source,
+            "messages": messages.len(),
+            "queries": queries.len(),
+            "stream": stream,
+        }),
+        Some(&req),
+    );
+
+    let mut sources = Vec::new();
+    for (query_index, (index_uid, mut query)) in
+        queries.into_iter().map(SearchQueryWithIndex::into_index_query).enumerate()
+    {
+        let with_index = |mut err: ResponseError| {
+            err.message = format!("Inside `.queries[{query_index}]`: {}", err.message);
+            err
+        };
+
+        if !index_scheduler.filters().is_index_authorized(&index_uid) {
+            return Err(with_index(AuthenticationError::InvalidToken.into()));
+        }
+        if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+            add_search_rules(&mut query, search_rules);
+        }
+        if query.q.is_none() {
+            query.q = Some(question.clone());
+        }
+
+        let index = index_scheduler.index(&index_uid).map_err(|err| {
+            let mut err = ResponseError::from(err);
+            // the index is not part of the URL, like in the multi-search
+            err.code = StatusCode::BAD_REQUEST;
+            with_index(err)
+        })?;
+
+        let distribution =
+            embed(&mut query, index_scheduler.get_ref(), &index).await.map_err(with_index)?;
+        let foreign_indexes = ForeignIndexes::new(
+            index_scheduler.get_ref(),
+            index_scheduler.filters(),
+            &index,
+            &query,
+        )
+        .map_err(|err| with_index(err.into()))?;
+
+        let search_result = tokio::task::spawn_blocking(move || {
+            perform_search(&index, query, features, distribution, &foreign_indexes)
+        })
+        .await
+        .map_err(|err| with_index(err.into()))?
+        .map_err(|err| with_index(err.into()))?;
+
+        let index_uid = index_uid.into_inner();
+        sources.extend(
+            search_result
+                .hits
+                .into_iter()
+                .map(|hit| ChatSource { index_uid: index_uid.clone(), document: hit.document }),
+        );
+    }
+
+    messages.insert(
+        0,
+        ChatMessage { role: ChatRole::System, content: system_prompt(&settings, &sources) },
+    );
+
+    if !stream {
+        let answer = completer.complete(&messages).await?;
+        let result = ChatCompletionResult { answer, sources };
+        debug!("returns: {:?}", result);
+        return Ok(HttpResponse::Ok().json(result));
+    }
+
+    // the status code is sent before the answer, the errors of the provider are sent as events
+    let mut completion = completer.stream(&messages).await?;
+    let events = async_stream::stream! {
+        yield Ok::<_, ResponseError>(event("sources", &json!({ "sources": sources })));
+        loop {
+            match completion.next_delta().await {
+                Ok(Some(content)) => yield Ok(event("delta", &json!({ "content": content }))),
+                Ok(None) => break,
+                Err(err) => {
+                    yield Ok(event("error", &ResponseError::from(err)));
+                    return;
+                }
+            }
+        }
+        yield Ok(event("done", &json!({})));
+    };
+
+    Ok(HttpResponse::Ok().content_type("text/event-stream").streaming(events))
+}
+
+/// Formats a server-sent event.
+fn event(name: &str, data: &impl Serialize) -> Bytes {
+    let data = serde_json::to_string(data).unwrap_or_default();
+    Bytes::from(format!("event: {name}\ndata: {data}\n\n"))
+}
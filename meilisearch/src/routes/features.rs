@@ -48,6 +48,14 @@ pub struct RuntimeTogglableFeatures {
     pub metrics: Option<bool>,
     #[deserr(default)]
     pub export_puffin_reports: Option<bool>,
+    #[deserr(default)]
+    pub shadow_settings_update: Option<bool>,
+    #[deserr(default)]
+    pub chat_completions: Option<bool>,
+    #[deserr(default)]
+    pub search_analytics: Option<bool>,
+    #[deserr(default)]
+    pub change_feed: Option<bool>,
 }
 
 async fn patch_features(
@@ -70,6 +78,13 @@ async fn patch_features(
             .0
             .export_puffin_reports
             .unwrap_or(old_features.export_puffin_reports),
+        shadow_settings_update: new_features
+            .0
+            .shadow_settings_update
+            .unwrap_or(old_features.shadow_settings_update),
+        chat_completions: new_features.0.chat_completions.unwrap_or(old_features.chat_completions),
+        search_analytics: new_features.0.search_analytics.unwrap_or(old_features.search_analytics),
+        change_feed: new_features.0.change_feed.unwrap_or(old_features.change_feed),
     };
 
     // explicitly destructure for analytics rather than using the `Serialize` implementation, because
@@ -80,6 +95,10 @@ async fn patch_features(
         vector_store,
         metrics,
         export_puffin_reports,
+        shadow_settings_update,
+        chat_completions,
+        search_analytics,
+        change_feed,
     } = new_features;
 
     analytics.publish(
@@ -89,6 +108,10 @@ async fn patch_features(
             "vector_store": vector_store,
             "metrics": metrics,
             "export_puffin_reports": export_puffin_reports,
+            "shadow_settings_update": shadow_settings_update,
+            "chat_completions": chat_completions,
+            "search_analytics": search_analytics,
+            "change_feed": change_feed,
         }),
         Some(&req),
     );
@@ -0,0 +1,46 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebQueryParameter;
+use index_scheduler::IndexScheduler;
+use log::debug;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use serde_json::json;
+
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+
+/// The number of changes returned when no limit is given.
+const DEFAULT_CHANGES_LIMIT: usize = 20;
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ChangesParams {
+    #[deserr(default, error = DeserrQueryParamError<InvalidChangeFeedAfter>)]
+    after: Option<Param<u64>>,
+    #[deserr(default = Param(DEFAULT_CHANGES_LIMIT), error = DeserrQueryParamError<InvalidChangeFeedLimit>)]
+    limit: Param<usize>,
+}
+
+/// Returns the changes made to the documents of the index after the `after` cursor, along
+/// with the cursor to give to get the next changes and the epoch of the feed, the cursors
+/// of a previous epoch being meaningless.
+pub async fn get_changes(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<ChangesParams, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    index_scheduler.features().check_change_feed("Getting the changes of the documents")?;
+
+    let ChangesParams { after, limit } = params.into_inner();
+    let after = after.map(|after| after.0);
+    let (epoch, results) = index_scheduler.document_changes(&index_uid, after, limit.0)?;
+    let next = results.last().map(|entry| entry.cursor).or(after);
+
+    debug!("returns: {:?}", results);
+    Ok(HttpResponse::Ok().json(json!({ "results": results, "next": next, "epoch": epoch })))
+}
@@ -1,4 +1,5 @@
 use std::io::ErrorKind;
+use std::str::FromStr;
 
 use actix_web::http::header::CONTENT_TYPE;
 use actix_web::web::Data;
@@ -11,18 +12,23 @@ use index_scheduler::IndexScheduler;
 use log::debug;
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
-use meilisearch_types::document_formats::{read_csv, read_json, read_ndjson, PayloadType};
+use meilisearch_types::document_formats::{
+    read_avro, read_csv, read_json, read_msgpack, read_ndjson, read_parquet, CsvOptions,
+    PayloadType,
+};
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::error::{Code, ErrorCode, ResponseError};
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
-use meilisearch_types::milli::DocumentId;
+use meilisearch_types::milli::{AscDesc, DocumentId, FieldId, FieldsIdsMap, Member};
+use meilisearch_types::serde_cs::vec::CS;
 use meilisearch_types::star_or::OptionStarOrList;
 use meilisearch_types::tasks::KindWithContent;
 use meilisearch_types::{milli, Document, Index};
 use mime::Mime;
 use once_cell::sync::Lazy;
+use roaring::RoaringBitmap;
 use serde::Deserialize;
 use serde_json::Value;
 use tempfile::tempfile;
@@ -40,7 +46,14 @@ use crate::routes::{PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT
 use crate::search::parse_filter;
 
 static ACCEPTED_CONTENT_TYPE: Lazy<Vec<String>> = Lazy::new(|| {
-    vec!["application/json".to_string(), "application/x-ndjson".to_string(), "text/csv".to_string()]
+    vec![
+        "application/json".to_string(),
+        "application/x-ndjson".to_string(),
+        "text/csv".to_string(),
+        "application/vnd.apache.parquet".to_string(),
+        "application/msgpack".to_string(),
+        "application/avro".to_string(),
+    ]
 });
 
 /// Extracts the mime type from the content type and return
@@ -146,6 +159,8 @@ pub struct BrowseQueryGet {
     fields: OptionStarOrList<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidDocumentFilter>)]
     filter: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentSort>)]
+    sort: Option<CS<String>>,
 }
 
 #[derive(Debug, Deserr)]
@@ -159,6 +174,10 @@ pub struct BrowseQuery {
     fields: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidDocumentFilter>)]
     filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentSort>)]
+    sort: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentIds>)]
+    ids: Option<Vec<Value>>,
 }
 
 pub async fn documents_by_query_post(
@@ -175,6 +194,8 @@ pub async fn documents_by_query_post(
     analytics.post_fetch_documents(
         &DocumentFetchKind::Normal {
             with_filter: body.filter.is_some(),
+            with_sort: body.sort.is_some(),
+            with_ids: body.ids.is_some(),
             limit: body.limit,
             offset: body.offset,
         },
@@ -193,7 +214,7 @@ pub async fn get_documents(
 ) -> Result<HttpResponse, ResponseError> {
     debug!("called with params: {:?}", params);
 
-    let BrowseQueryGet { limit, offset, fields, filter } = params.into_inner();
+    let BrowseQueryGet { limit, offset, fields, filter, sort } = params.into_inner();
 
     let filter = match filter {
         Some(f) => match serde_json::from_str(&f) {
@@ -208,11 +229,15 @@ pub async fn get_documents(
         limit: limit.0,
         fields: fields.merge_star_and_none(),
         filter,
+        sort: sort.map(|sort| sort.into_iter().collect()),
+        ids: None,
     };
 
     analytics.get_fetch_documents(
         &DocumentFetchKind::Normal {
             with_filter: query.filter.is_some(),
+            with_sort: query.sort.is_some(),
+            with_ids: false,
             limit: query.limit,
             offset: query.offset,
         },
@@ -228,10 +253,22 @@ fn documents_by_query(
     query: BrowseQuery,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
-    let BrowseQuery { offset, limit, fields, filter } = query;
+    let BrowseQuery { offset, limit, fields, filter, sort, ids } = query;
+    // the ids must be strings or integers, like the primary key values of the documents
+    let ids = ids
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| {
+                    milli::documents::validate_document_id_value(id)?.map_err(|err| {
+                        ResponseError::from_msg(err.to_string(), Code::InvalidDocumentIds)
+                    })
+                })
+                .collect::<Result<Vec<_>, ResponseError>>()
+        })
+        .transpose()?;
 
     let index = index_scheduler.index(&index_uid)?;
-    let (total, documents) = retrieve_documents(&index, offset, limit, filter, fields)?;
+    let (total, documents) = retrieve_documents(&index, offset, limit, filter, sort, ids, fields)?;
 
     let ret = PaginationView::new(offset, limit, total as usize, documents);
 
@@ -239,28 +276,92 @@ fn documents_by_query(
     Ok(HttpResponse::Ok().json(ret))
 }
 
-#[derive(Deserialize, Debug, Deserr)]
+#[derive(Debug, Deserr)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 pub struct UpdateDocumentsQuery {
     #[deserr(default, error = DeserrQueryParamError<InvalidIndexPrimaryKey>)]
     pub primary_key: Option<String>,
     #[deserr(default, try_from(char) = from_char_csv_delimiter -> DeserrQueryParamError<InvalidDocumentCsvDelimiter>, error = DeserrQueryParamError<InvalidDocumentCsvDelimiter>)]
     pub csv_delimiter: Option<u8>,
+    #[deserr(default, try_from(char) = from_char_csv_quote -> DeserrQueryParamError<InvalidDocumentCsvQuote>, error = DeserrQueryParamError<InvalidDocumentCsvQuote>)]
+    pub csv_quote: Option<u8>,
+    #[deserr(default, try_from(char) = from_char_csv_escape -> DeserrQueryParamError<InvalidDocumentCsvEscape>, error = DeserrQueryParamError<InvalidDocumentCsvEscape>)]
+    pub csv_escape: Option<u8>,
+    #[deserr(default, try_from(char) = from_char_csv_array_delimiter -> DeserrQueryParamError<InvalidDocumentCsvArrayDelimiter>, error = DeserrQueryParamError<InvalidDocumentCsvArrayDelimiter>)]
+    pub csv_array_delimiter: Option<u8>,
+    /// Whether the whole addition fails on the first invalid document. When disabled, the
+    /// invalid documents are skipped and reported in the task details.
+    #[deserr(default = Param(true), error = DeserrQueryParamError<InvalidDocumentStrict>)]
+    pub strict: Param<bool>,
 }
 
-fn from_char_csv_delimiter(
+impl UpdateDocumentsQuery {
+    /// Returns the options to read the CSV payload with, if any of them was specified.
+    fn csv_options(&self) -> Option<CsvOptions> {
+        let Self {
+            primary_key: _,
+            csv_delimiter,
+            csv_quote,
+            csv_escape,
+            csv_array_delimiter,
+            strict: _,
+        } = *self;
+        if csv_delimiter.is_none()
+            && csv_quote.is_none()
+            && csv_escape.is_none()
+            && csv_array_delimiter.is_none()
+        {
+            return None;
+        }
+
+        let default = CsvOptions::default();
+        Some(CsvOptions {
+            delimiter: csv_delimiter.unwrap_or(default.delimiter),
+            quote: csv_quote.unwrap_or(default.quote),
+            escape: csv_escape.or(default.escape),
+            array_delimiter: csv_array_delimiter.unwrap_or(default.array_delimiter),
+        })
+    }
+}
+
+fn from_char_csv_character<C: Default + ErrorCode>(
+    name: &str,
     c: char,
-) -> Result<Option<u8>, DeserrQueryParamError<InvalidDocumentCsvDelimiter>> {
+) -> Result<Option<u8>, DeserrQueryParamError<C>> {
     if c.is_ascii() {
         Ok(Some(c as u8))
     } else {
         Err(DeserrQueryParamError::new(
-            format!("csv delimiter must be an ascii character. Found: `{}`", c),
-            Code::InvalidDocumentCsvDelimiter,
+            format!("{} must be an ascii character. Found: `{}`", name, c),
+            C::default().error_code(),
         ))
     }
 }
 
+fn from_char_csv_delimiter(
+    c: char,
+) -> Result<Option<u8>, DeserrQueryParamError<InvalidDocumentCsvDelimiter>> {
+    from_char_csv_character("csv delimiter", c)
+}
+
+fn from_char_csv_quote(
+    c: char,
+) -> Result<Option<u8>, DeserrQueryParamError<InvalidDocumentCsvQuote>> {
+    from_char_csv_character("csv quote", c)
+}
+
+fn from_char_csv_escape(
+    c: char,
+) -> Result<Option<u8>, DeserrQueryParamError<InvalidDocumentCsvEscape>> {
+    from_char_csv_character("csv escape", c)
+}
+
+fn from_char_csv_array_delimiter(
+    c: char,
+) -> Result<Option<u8>, DeserrQueryParamError<InvalidDocumentCsvArrayDelimiter>> {
+    from_char_csv_character("csv array delimiter", c)
+}
+
 pub async fn replace_documents(
     index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_ADD }>, Data<IndexScheduler>>,
     index_uid: web::Path<String>,
@@ -281,10 +382,11 @@ pub async fn replace_documents(
         extract_mime_type(&req)?,
         index_scheduler,
         index_uid,
+        params.csv_options(),
         params.primary_key,
-        params.csv_delimiter,
         body,
         IndexDocumentsMethod::ReplaceDocuments,
+        !params.strict.0,
         allow_index_creation,
     )
     .await?;
@@ -312,10 +414,11 @@ pub async fn update_documents(
         extract_mime_type(&req)?,
         index_scheduler,
         index_uid,
+        params.csv_options(),
         params.primary_key,
-        params.csv_delimiter,
         body,
         IndexDocumentsMethod::UpdateDocuments,
+        !params.strict.0,
         allow_index_creation,
     )
     .await?;
@@ -328,41 +431,58 @@ async fn document_addition(
     mime_type: Option<Mime>,
     index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_ADD }>, Data<IndexScheduler>>,
     index_uid: IndexUid,
+    csv_options: Option<CsvOptions>,
     primary_key: Option<String>,
-    csv_delimiter: Option<u8>,
     mut body: Payload,
     method: IndexDocumentsMethod,
+    skip_invalid_documents: bool,
     allow_index_creation: bool,
 ) -> Result<SummarizedTaskView, MeilisearchHttpError> {
-    let format = match (
-        mime_type.as_ref().map(|m| (m.type_().as_str(), m.subtype().as_str())),
-        csv_delimiter,
-    ) {
-        (Some(("application", "json")), None) => PayloadType::Json,
-        (Some(("application", "x-ndjson")), None) => PayloadType::Ndjson,
-        (Some(("text", "csv")), None) => PayloadType::Csv { delimiter: b',' },
-        (Some(("text", "csv")), Some(delimiter)) => PayloadType::Csv { delimiter },
-
-        (Some(("application", "json")), Some(_)) => {
-            return Err(MeilisearchHttpError::CsvDelimiterWithWrongContentType(String::from(
-                "application/json",
-            )))
-        }
-        (Some(("application", "x-ndjson")), Some(_)) => {
-            return Err(MeilisearchHttpError::CsvDelimiterWithWrongContentType(String::from(
-                "application/x-ndjson",
-            )))
-        }
-        (Some((type_, subtype)), _) => {
-            return Err(MeilisearchHttpError::InvalidContentType(
-                format!("{}/{}", type_, subtype),
-                ACCEPTED_CONTENT_TYPE.clone(),
-            ))
-        }
-        (None, _) => {
-            return Err(MeilisearchHttpError::MissingContentType(ACCEPTED_CONTENT_TYPE.clone()))
-        }
-    };
+    let format =
+        match (mime_type.as_ref().map(|m| (m.type_().as_str(), m.subtype().as_str())), csv_options)
+        {
+            (Some(("application", "json")), None) => PayloadType::Json,
+            (Some(("application", "x-ndjson")), None) => PayloadType::Ndjson,
+            (Some(("text", "csv")), options) => PayloadType::Csv(options.unwrap_or_default()),
+            (Some(("application", "vnd.apache.parquet")), None) => PayloadType::Parquet,
+            (Some(("application", "msgpack")), None) => PayloadType::MessagePack,
+            (Some(("application", "avro")), None) => PayloadType::Avro,
+
+            (Some(("application", "json")), Some(_)) => {
+                return Err(MeilisearchHttpError::CsvOptionsWithWrongContentType(String::from(
+                    "application/json",
+                )))
+            }
+            (Some(("application", "x-ndjson")), Some(_)) => {
+                return Err(MeilisearchHttpError::CsvOptionsWithWrongContentType(String::from(
+                    "application/x-ndjson",
+                )))
+            }
+            (Some(("application", "vnd.apache.parquet")), Some(_)) => {
+                return Err(MeilisearchHttpError::CsvOptionsWithWrongContentType(String::from(
+                    "application/vnd.apache.parquet",
+                )))
+            }
+            (Some(("application", "msgpack")), Some(_)) => {
+                return Err(MeilisearchHttpError::CsvOptionsWithWrongContentType(String::from(
+                    "application/msgpack",
+                )))
+            }
+            (Some(("application", "avro")), Some(_)) => {
+                return Err(MeilisearchHttpError::CsvOptionsWithWrongContentType(String::from(
+                    "application/avro",
+                )))
+            }
+            (Some((type_, subtype)), _) => {
+                return Err(MeilisearchHttpError::InvalidContentType(
+                    format!("{}/{}", type_, subtype),
+                    ACCEPTED_CONTENT_TYPE.clone(),
+                ))
+            }
+            (None, _) => {
+                return Err(MeilisearchHttpError::MissingContentType(ACCEPTED_CONTENT_TYPE.clone()))
+            }
+        };
 
     let (uuid, mut update_file) = index_scheduler.create_update_file()?;
 
@@ -404,10 +524,11 @@ async fn document_addition(
     let documents_count = tokio::task::spawn_blocking(move || {
         let documents_count = match format {
             PayloadType::Json => read_json(&read_file, update_file.as_file_mut())?,
-            PayloadType::Csv { delimiter } => {
-                read_csv(&read_file, update_file.as_file_mut(), delimiter)?
-            }
+            PayloadType::Csv(options) => read_csv(&read_file, update_file.as_file_mut(), options)?,
             PayloadType::Ndjson => read_ndjson(&read_file, update_file.as_file_mut())?,
+            PayloadType::Parquet => read_parquet(&read_file, update_file.as_file_mut())?,
+            PayloadType::MessagePack => read_msgpack(&read_file, update_file.as_file_mut())?,
+            PayloadType::Avro => read_avro(&read_file, update_file.as_file_mut())?,
         };
         // we NEED to persist the file here because we moved the `udpate_file` in another task.
         update_file.persist()?;
@@ -441,6 +562,7 @@ async fn document_addition(
         documents_count,
         primary_key,
         allow_index_creation,
+        skip_invalid_documents,
         index_uid: index_uid.to_string(),
     };
 
@@ -536,17 +658,39 @@ pub async fn clear_all_documents(
     Ok(HttpResponse::Accepted().json(task))
 }
 
+/// Returns the ids of the fields to read from the stored documents to retrieve the attributes,
+/// all of them when there is no attributes to retrieve.
+///
+/// A nested attribute like `address.city` only requires reading its top-level `address` field,
+/// the partial `address` object being reconstructed by selecting the attribute in it.
+fn fields_to_read<S: AsRef<str>>(
+    fields_ids_map: &FieldsIdsMap,
+    attributes_to_retrieve: Option<&[S]>,
+) -> Vec<FieldId> {
+    fields_ids_map
+        .iter()
+        .filter(|(_, name)| match attributes_to_retrieve {
+            Some(attributes) => attributes.iter().any(|attribute| {
+                let attribute = attribute.as_ref();
+                milli::is_faceted_by(attribute, name) || milli::is_faceted_by(name, attribute)
+            }),
+            None => true,
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
 fn some_documents<'a, 't: 'a>(
     index: &'a Index,
     rtxn: &'t RoTxn,
     doc_ids: impl IntoIterator<Item = DocumentId> + 'a,
+    fields: Vec<FieldId>,
 ) -> Result<impl Iterator<Item = Result<Document, ResponseError>> + 'a, ResponseError> {
     let fields_ids_map = index.fields_ids_map(rtxn)?;
-    let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
 
     Ok(index.iter_documents(rtxn, doc_ids)?.map(move |ret| {
         ret.map_err(ResponseError::from).and_then(|(_key, document)| -> Result<_, ResponseError> {
-            Ok(milli::obkv_to_json(&all_fields, &fields_ids_map, document)?)
+            Ok(milli::obkv_to_json(&fields, &fields_ids_map, document)?)
         })
     }))
 }
@@ -556,6 +700,8 @@ fn retrieve_documents<S: AsRef<str>>(
     offset: usize,
     limit: usize,
     filter: Option<Value>,
+    sort: Option<Vec<String>>,
+    ids: Option<Vec<String>>,
     attributes_to_retrieve: Option<Vec<S>>,
 ) -> Result<(u64, Vec<Document>), ResponseError> {
     let rtxn = index.read_txn()?;
@@ -567,7 +713,7 @@ fn retrieve_documents<S: AsRef<str>>(
         None
     };
 
-    let candidates = if let Some(filter) = filter {
+    let mut candidates = if let Some(filter) = filter {
         filter.evaluate(&rtxn, index).map_err(|err| match err {
             milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
                 ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter)
@@ -578,14 +724,41 @@ fn retrieve_documents<S: AsRef<str>>(
         index.documents_ids(&rtxn)?
     };
 
-    let (it, number_of_documents) = {
-        let number_of_documents = candidates.len();
-        (
-            some_documents(index, &rtxn, candidates.into_iter().skip(offset).take(limit))?,
-            number_of_documents,
-        )
+    // the requested documents are kept in the order of their ids, the ids of the documents
+    // that don't exist or don't match the filter are ignored and not counted in the total
+    let requested_ids = match ids {
+        Some(ids) => {
+            let external_documents_ids = index.external_documents_ids();
+            let mut requested = RoaringBitmap::new();
+            let mut requested_ids = Vec::new();
+            for id in ids {
+                if let Some(docid) = external_documents_ids.get(&rtxn, id)? {
+                    if candidates.contains(docid) && requested.insert(docid) {
+                        requested_ids.push(docid);
+                    }
+                }
+            }
+            candidates = requested;
+            Some(requested_ids)
+        }
+        None => None,
     };
 
+    let sort = parse_document_sort(index, &rtxn, sort.as_deref().unwrap_or_default())?;
+
+    let number_of_documents = candidates.len();
+    let documents_ids: Vec<_> = match requested_ids {
+        _ if !sort.is_empty() => {
+            let limit = offset.saturating_add(limit);
+            let documents_ids = index.sorted_documents_ids(&rtxn, candidates, &sort, limit)?;
+            documents_ids.into_iter().skip(offset).collect()
+        }
+        Some(requested_ids) => requested_ids.into_iter().skip(offset).take(limit).collect(),
+        None => candidates.into_iter().skip(offset).take(limit).collect(),
+    };
+    let fields = fields_to_read(&index.fields_ids_map(&rtxn)?, attributes_to_retrieve.as_deref());
+    let it = some_documents(index, &rtxn, documents_ids, fields)?;
+
     let documents: Result<Vec<_>, ResponseError> = it
         .map(|document| {
             Ok(match &attributes_to_retrieve {
@@ -601,6 +774,49 @@ fn retrieve_documents<S: AsRef<str>>(
     Ok((number_of_documents, documents?))
 }
 
+/// Parses the sort of the documents into the ids of the sorted fields,
+/// with `true` when a field is sorted in ascending order.
+///
+/// The fields that don't exist in the index are ignored since no document can be sorted by them.
+fn parse_document_sort(
+    index: &Index,
+    rtxn: &RoTxn,
+    sort: &[String],
+) -> Result<Vec<(FieldId, bool)>, ResponseError> {
+    if sort.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let faceted_fields = index.faceted_fields(rtxn)?;
+
+    let mut fields = Vec::new();
+    for text in sort {
+        let asc_desc = AscDesc::from_str(text)
+            .map_err(|err| ResponseError::from_msg(err.to_string(), Code::InvalidDocumentSort))?;
+        let field = match asc_desc.member() {
+            Member::Field(field) => field,
+            _ => {
+                return Err(ResponseError::from_msg(
+                    format!("Invalid sort `{text}`: the documents can only be sorted by fields, in ascending or descending order."),
+                    Code::InvalidDocumentSort,
+                ))
+            }
+        };
+        if !milli::is_faceted(field, &faceted_fields) {
+            return Err(ResponseError::from_msg(
+                format!("Attribute `{field}` is not filterable nor sortable. Add it to the `filterableAttributes` or `sortableAttributes` settings to sort the documents by it."),
+                Code::InvalidDocumentSort,
+            ));
+        }
+        if let Some(field_id) = fields_ids_map.id(field) {
+            fields.push((field_id, matches!(asc_desc, AscDesc::Asc(_))));
+        }
+    }
+
+    Ok(fields)
+}
+
 fn retrieve_document<S: AsRef<str>>(
     index: &Index,
     doc_id: &str,
@@ -609,7 +825,7 @@ fn retrieve_document<S: AsRef<str>>(
     let txn = index.read_txn()?;
 
     let fields_ids_map = index.fields_ids_map(&txn)?;
-    let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
+    let fields = fields_to_read(&fields_ids_map, attributes_to_retrieve.as_deref());
 
     let internal_id = index
         .external_documents_ids()
@@ -623,7 +839,7 @@ fn retrieve_document<S: AsRef<str>>(
         .map(|(_, d)| d)
         .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))?;
 
-    let document = meilisearch_types::milli::obkv_to_json(&all_fields, &fields_ids_map, document)?;
+    let document = meilisearch_types::milli::obkv_to_json(&fields, &fields_ids_map, document)?;
     let document = match &attributes_to_retrieve {
         Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
             &document,
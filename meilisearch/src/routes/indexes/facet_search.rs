@@ -111,18 +111,45 @@ impl From<FacetSearchQuery> for SearchQuery {
             crop_length: DEFAULT_CROP_LENGTH(),
             attributes_to_highlight: None,
             show_matches_position: false,
+            matches_position_unit: None,
+            show_matched_terms: false,
+            show_corrected_query: false,
+            retrieve_vectors: false,
             show_ranking_score: false,
             show_ranking_score_details: false,
             filter,
             sort: None,
             facets: None,
+            disjunctive_facets: None,
+            extended_facet_stats: false,
+            aggregations: None,
+            sort_facet_values_by: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
             crop_marker: DEFAULT_CROP_MARKER(),
+            highlight_tags: None,
+            highlight_escape_html: false,
+            snippet: None,
             matching_strategy,
             vector,
             attributes_to_search_on,
             hybrid,
+            geo_reference_point: None,
+            attribute_boosts: None,
+            ranking_score_threshold: None,
+            timeout_ms: None,
+            count_only: false,
+            early_termination: false,
+            search_after: None,
+            group_by: None,
+            distinct: None,
+            structured_query: None,
+            embed_foreign_documents: false,
+            foreign_filters: None,
+            pinned_documents: None,
+            hidden_documents: None,
+            vocabulary: None,
+            phrase_slop: None,
         }
     }
 }
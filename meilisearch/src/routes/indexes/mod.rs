@@ -12,7 +12,7 @@ use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::{self, FieldDistribution, Index};
-use meilisearch_types::tasks::KindWithContent;
+use meilisearch_types::tasks::{ExportFormat, KindWithContent};
 use serde::Serialize;
 use serde_json::json;
 use time::OffsetDateTime;
@@ -23,10 +23,14 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
 
+pub mod change_feed;
 pub mod documents;
 pub mod facet_search;
 pub mod search;
+pub mod search_analytics;
 pub mod settings;
+pub mod similar;
+pub mod suggest;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -43,9 +47,18 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                     .route(web::delete().to(SeqHandler(delete_index))),
             )
             .service(web::resource("/stats").route(web::get().to(SeqHandler(get_index_stats))))
+            .service(web::resource("/copy").route(web::post().to(SeqHandler(copy_index))))
+            .service(web::resource("/export").route(web::post().to(SeqHandler(export_index))))
+            .service(
+                web::resource("/changes")
+                    .route(web::get().to(SeqHandler(change_feed::get_changes))),
+            )
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
+            .service(web::scope("/similar").configure(similar::configure))
+            .service(web::scope("/suggest").configure(suggest::configure))
+            .service(web::scope("/search-analytics").configure(search_analytics::configure))
             .service(web::scope("/settings").configure(settings::configure)),
     );
 }
@@ -222,6 +235,103 @@ pub async fn delete_index(
     Ok(HttpResponse::Accepted().json(task))
 }
 
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct IndexCopyRequest {
+    #[deserr(error = DeserrJsonError<InvalidIndexCopyDestination>, missing_field_error = DeserrJsonError::missing_index_copy_destination)]
+    destination: IndexUid,
+}
+
+pub async fn copy_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_CREATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<IndexCopyRequest, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let IndexCopyRequest { destination } = body.into_inner();
+
+    if !index_scheduler.filters().allow_index_creation(&destination) {
+        return Err(AuthenticationError::InvalidToken.into());
+    }
+
+    analytics.publish("Index Copied".to_string(), json!({}), Some(&req));
+
+    let task = KindWithContent::IndexCopy {
+        index_uid: index_uid.into_inner(),
+        destination: destination.into_inner(),
+    };
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task)).await??.into();
+
+    debug!("returns: {:?}", task);
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct IndexExportRequest {
+    #[deserr(default = ExportFormat::Ndjson, error = DeserrJsonError<InvalidIndexExportFormat>)]
+    format: ExportFormat,
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentFilter>)]
+    filter: Option<serde_json::Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidDocumentFields>)]
+    fields: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidIndexExportUrl>)]
+    url: Option<String>,
+}
+
+pub async fn export_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<IndexExportRequest, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let IndexExportRequest { format, filter, fields, url } = body.into_inner();
+
+    // we ensure the filter is well formed before enqueuing it
+    if let Some(filter) = &filter {
+        crate::search::parse_filter(filter)
+            .map_err(|err| ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter))?;
+    }
+    if let Some(url) = &url {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(ResponseError::from_msg(
+                format!("The export URL `{url}` is invalid. It must start with `http://` or `https://`."),
+                Code::InvalidIndexExportUrl,
+            ));
+        }
+        index_scheduler.check_export_url(url)?;
+    }
+
+    analytics.publish(
+        "Index Exported".to_string(),
+        json!({
+            "format": format.extension(),
+            "filter": filter.is_some(),
+            "fields": fields.is_some(),
+            "upload": url.is_some(),
+        }),
+        Some(&req),
+    );
+
+    let task = KindWithContent::IndexExport {
+        index_uid: index_uid.into_inner(),
+        format,
+        filter,
+        fields,
+        url,
+    };
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task)).await??.into();
+
+    debug!("returns: {:?}", task);
+    Ok(HttpResponse::Accepted().json(task))
+}
+
 /// Stats of an `Index`, as known to the `stats` route.
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -1,12 +1,16 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
 use index_scheduler::IndexScheduler;
-use log::{debug, warn};
+use log::{debug, error, warn};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::ResponseError;
+use meilisearch_types::facet_values_sort::FacetValuesSort;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli;
 use meilisearch_types::milli::vector::DistributionShift;
@@ -18,10 +22,15 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::search::{
-    add_search_rules, perform_search, HybridQuery, MatchingStrategy, SearchQuery, SemanticRatio,
-    DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
+    add_search_rules, perform_search, Aggregation, AttributeBoosts, ForeignIndexes,
+    GeoReferencePoint, GroupByQuery, HybridCandidateSelection, HybridFusion, HybridQuery,
+    MatchesPositionUnit, MatchingStrategy, RankingScoreThreshold, SearchAfter, SearchQuery,
+    SearchResult, SemanticRatio, SnippetQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_GROUP_BY_LIMIT, DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG,
+    DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO, DEFAULT_SNIPPET_LENGTH,
 };
+use crate::search_cache::SearchCache;
+use crate::search_queue::SearchQueue;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -38,6 +47,8 @@ pub struct SearchQueryGet {
     q: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchVector>)]
     vector: Option<CS<f32>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRetrieveVectors>)]
+    retrieve_vectors: Param<bool>,
     #[deserr(default = Param(DEFAULT_SEARCH_OFFSET()), error = DeserrQueryParamError<InvalidSearchOffset>)]
     offset: Param<usize>,
     #[deserr(default = Param(DEFAULT_SEARCH_LIMIT()), error = DeserrQueryParamError<InvalidSearchLimit>)]
@@ -60,18 +71,38 @@ pub struct SearchQueryGet {
     sort: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowMatchesPosition>)]
     show_matches_position: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchMatchesPositionUnit>)]
+    matches_position_unit: Option<MatchesPositionUnit>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowMatchedTerms>)]
+    show_matched_terms: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowCorrectedQuery>)]
+    show_corrected_query: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScore>)]
     show_ranking_score: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScoreDetails>)]
     show_ranking_score_details: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacets>)]
     facets: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchDisjunctiveFacets>)]
+    disjunctive_facets: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchExtendedFacetStats>)]
+    extended_facet_stats: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchAggregations>)]
+    aggregations: Option<AggregationsGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSortFacetValuesBy>)]
+    sort_facet_values_by: Option<FacetValuesSort>,
     #[deserr( default = DEFAULT_HIGHLIGHT_PRE_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPreTag>)]
     highlight_pre_tag: String,
     #[deserr( default = DEFAULT_HIGHLIGHT_POST_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPostTag>)]
     highlight_post_tag: String,
     #[deserr(default = DEFAULT_CROP_MARKER(), error = DeserrQueryParamError<InvalidSearchCropMarker>)]
     crop_marker: String,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHighlightEscapeHtml>)]
+    highlight_escape_html: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSnippet>)]
+    snippet_attributes: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSnippet>)]
+    snippet_length: Option<Param<usize>>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchMatchingStrategy>)]
     matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToSearchOn>)]
@@ -80,6 +111,38 @@ pub struct SearchQueryGet {
     pub hybrid_embedder: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchSemanticRatio>)]
     pub hybrid_semantic_ratio: Option<SemanticRatioGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHybridFusion>)]
+    pub hybrid_fusion: Option<HybridFusion>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHybridCandidateSelection>)]
+    pub hybrid_candidate_selection: Option<HybridCandidateSelection>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchGeoReferencePoint>)]
+    pub geo_reference_point: Option<GeoReferencePointGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributeBoosts>)]
+    pub attribute_boosts: Option<AttributeBoostsGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRankingScoreThreshold>)]
+    pub ranking_score_threshold: Option<RankingScoreThresholdGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchTimeoutMs>)]
+    pub timeout_ms: Option<Param<u64>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchCountOnly>)]
+    pub count_only: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchEarlyTermination>)]
+    pub early_termination: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<SearchAfter>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchGroupBy>)]
+    pub group_by_attribute: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchGroupBy>)]
+    pub group_by_limit: Option<Param<usize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchEmbedForeignDocuments>)]
+    pub embed_foreign_documents: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchPinnedDocuments>)]
+    pub pinned_documents: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHiddenDocuments>)]
+    pub hidden_documents: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchPhraseSlop>)]
+    pub phrase_slop: Option<Param<u8>>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, deserr::Deserr)]
@@ -103,6 +166,85 @@ impl std::ops::Deref for SemanticRatioGet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchGeoReferencePoint)]
+pub struct GeoReferencePointGet(GeoReferencePoint);
+
+impl std::convert::TryFrom<String> for GeoReferencePointGet {
+    type Error = InvalidSearchGeoReferencePoint;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let point = s
+            .split(',')
+            .map(|coordinate| coordinate.trim().parse())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| InvalidSearchGeoReferencePoint)?;
+        Ok(GeoReferencePointGet(GeoReferencePoint::try_from(point)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchRankingScoreThreshold)]
+pub struct RankingScoreThresholdGet(pub RankingScoreThreshold);
+
+impl std::convert::TryFrom<String> for RankingScoreThresholdGet {
+    type Error = InvalidSearchRankingScoreThreshold;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let f: f64 = s.parse().map_err(|_| InvalidSearchRankingScoreThreshold)?;
+        Ok(RankingScoreThresholdGet(RankingScoreThreshold::try_from(f)?))
+    }
+}
+
+/// The aggregations written as `attribute:aggregation` pairs separated by commas.
+#[derive(Debug, Clone, PartialEq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchAggregations)]
+pub struct AggregationsGet(BTreeMap<String, Vec<Aggregation>>);
+
+impl std::convert::TryFrom<String> for AggregationsGet {
+    type Error = InvalidSearchAggregations;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let mut aggregations: BTreeMap<String, Vec<Aggregation>> = BTreeMap::new();
+        for pair in s.split(',') {
+            let (attribute, aggregation) =
+                pair.rsplit_once(':').ok_or(InvalidSearchAggregations)?;
+            let aggregation = match aggregation.trim() {
+                "sum" => Aggregation::Sum,
+                "avg" => Aggregation::Avg,
+                "min" => Aggregation::Min,
+                "max" => Aggregation::Max,
+                "percentiles" => Aggregation::Percentiles,
+                _ => return Err(InvalidSearchAggregations),
+            };
+            aggregations.entry(attribute.trim().to_string()).or_default().push(aggregation);
+        }
+        Ok(AggregationsGet(aggregations))
+    }
+}
+
+/// The attribute boosts written as `attribute:boost` pairs separated by commas.
+#[derive(Debug, Clone, PartialEq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchAttributeBoosts)]
+pub struct AttributeBoostsGet(AttributeBoosts);
+
+impl std::convert::TryFrom<String> for AttributeBoostsGet {
+    type Error = InvalidSearchAttributeBoosts;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let boosts = s
+            .split(',')
+            .map(|pair| {
+                let (attribute, boost) =
+                    pair.rsplit_once(':').ok_or(InvalidSearchAttributeBoosts)?;
+                let boost = boost.trim().parse().map_err(|_| InvalidSearchAttributeBoosts)?;
+                Ok((attribute.trim().to_string(), boost))
+            })
+            .collect::<Result<BTreeMap<String, f64>, _>>()?;
+        Ok(AttributeBoostsGet(AttributeBoosts::try_from(boosts)?))
+    }
+}
+
 impl From<SearchQueryGet> for SearchQuery {
     fn from(other: SearchQueryGet) -> Self {
         let filter = match other.filter {
@@ -113,23 +255,38 @@ impl From<SearchQueryGet> for SearchQuery {
             None => None,
         };
 
-        let hybrid = match (other.hybrid_embedder, other.hybrid_semantic_ratio) {
-            (None, None) => None,
-            (None, Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: None })
-            }
-            (Some(embedder), None) => Some(HybridQuery {
-                semantic_ratio: DEFAULT_SEMANTIC_RATIO(),
-                embedder: Some(embedder),
+        let hybrid = match (
+            other.hybrid_embedder,
+            other.hybrid_semantic_ratio,
+            other.hybrid_fusion,
+            other.hybrid_candidate_selection,
+        ) {
+            (None, None, None, None) => None,
+            (embedder, semantic_ratio, fusion, candidate_selection) => Some(HybridQuery {
+                semantic_ratio: semantic_ratio.map_or_else(DEFAULT_SEMANTIC_RATIO, |ratio| *ratio),
+                embedder,
+                fusion: fusion.unwrap_or_default(),
+                candidate_selection: candidate_selection.unwrap_or_default(),
+                embedders: None,
+                embedded_queries: BTreeMap::new(),
             }),
-            (Some(embedder), Some(semantic_ratio)) => {
-                Some(HybridQuery { semantic_ratio: *semantic_ratio, embedder: Some(embedder) })
-            }
         };
 
+        let group_by = other.group_by_attribute.map(|attribute| GroupByQuery {
+            attribute,
+            limit: other.group_by_limit.map_or_else(DEFAULT_GROUP_BY_LIMIT, |limit| limit.0),
+        });
+
+        let snippet = other.snippet_attributes.map(|attributes| SnippetQuery {
+            attributes: attributes.into_iter().collect(),
+            length: other.snippet_length.map_or_else(DEFAULT_SNIPPET_LENGTH, |length| length.0),
+        });
+
         Self {
             q: other.q,
+            structured_query: None,
             vector: other.vector.map(CS::into_inner),
+            retrieve_vectors: other.retrieve_vectors.0,
             offset: other.offset.0,
             limit: other.limit.0,
             page: other.page.as_deref().copied(),
@@ -141,15 +298,40 @@ impl From<SearchQueryGet> for SearchQuery {
             filter,
             sort: other.sort.map(|attr| fix_sort_query_parameters(&attr)),
             show_matches_position: other.show_matches_position.0,
+            matches_position_unit: other.matches_position_unit,
+            show_matched_terms: other.show_matched_terms.0,
+            show_corrected_query: other.show_corrected_query.0,
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
             facets: other.facets.map(|o| o.into_iter().collect()),
+            disjunctive_facets: other.disjunctive_facets.map(|o| o.into_iter().collect()),
+            extended_facet_stats: other.extended_facet_stats.0,
+            aggregations: other.aggregations.map(|aggregations| aggregations.0),
+            sort_facet_values_by: other.sort_facet_values_by,
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
             crop_marker: other.crop_marker,
+            highlight_tags: None,
+            highlight_escape_html: other.highlight_escape_html.0,
+            snippet,
             matching_strategy: other.matching_strategy,
             attributes_to_search_on: other.attributes_to_search_on.map(|o| o.into_iter().collect()),
             hybrid,
+            geo_reference_point: other.geo_reference_point.map(|point| point.0),
+            attribute_boosts: other.attribute_boosts.map(|boosts| boosts.0),
+            ranking_score_threshold: other.ranking_score_threshold.map(|threshold| threshold.0),
+            timeout_ms: other.timeout_ms.map(|timeout| timeout.0),
+            count_only: other.count_only.0,
+            early_termination: other.early_termination.0,
+            search_after: other.search_after,
+            group_by,
+            distinct: other.distinct,
+            embed_foreign_documents: other.embed_foreign_documents.0,
+            foreign_filters: None,
+            pinned_documents: other.pinned_documents.map(|o| o.into_iter().collect()),
+            hidden_documents: other.hidden_documents.map(|o| o.into_iter().collect()),
+            vocabulary: None,
+            phrase_slop: other.phrase_slop.map(|slop| slop.0),
         }
     }
 }
@@ -185,6 +367,8 @@ pub async fn search_with_url_query(
     params: AwebQueryParameter<SearchQueryGet, DeserrQueryParamError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
+    search_cache: web::Data<SearchCache>,
+    search_queue: web::Data<SearchQueue>,
 ) -> Result<HttpResponse, ResponseError> {
     debug!("called with params: {:?}", params);
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
@@ -201,13 +385,43 @@ pub async fn search_with_url_query(
     let index = index_scheduler.index(&index_uid)?;
     let features = index_scheduler.features();
 
-    let distribution = embed(&mut query, index_scheduler.get_ref(), &index).await?;
-
-    let search_result =
-        tokio::task::spawn_blocking(move || perform_search(&index, query, features, distribution))
-            .await?;
+    // the key is computed before the query is embedded, the cached searches are not embedded again
+    let before_search = Instant::now();
+    let cache_key =
+        search_cache.key(&index_uid, index_scheduler.index_write_epoch(&index_uid), &query);
+    let cached_result = cache_key.as_ref().and_then(|key| search_cache.get(key));
+
+    let q = query.q.clone();
+    let search_result = match cached_result {
+        Some(mut search_result) => {
+            search_result.processing_time_ms = before_search.elapsed().as_millis();
+            Ok(search_result)
+        }
+        None => {
+            let permit = search_queue.try_get_search_permit().await?;
+            let distribution = embed(&mut query, index_scheduler.get_ref(), &index).await?;
+            let foreign_indexes = ForeignIndexes::new(
+                index_scheduler.get_ref(),
+                index_scheduler.filters(),
+                &index,
+                &query,
+            )?;
+            permit.apply_deadline(&mut query);
+            tokio::task::spawn_blocking(move || {
+                // the search is still in flight when the request is cancelled
+                let _permit = permit;
+                perform_search(&index, query, features, distribution, &foreign_indexes)
+            })
+            .await?
+        }
+    };
     if let Ok(ref search_result) = search_result {
+        // a degraded search may have the time to rank all the hits the next time
+        if let Some(cache_key) = cache_key.filter(|_| !search_result.degraded) {
+            search_cache.insert(cache_key, search_result.clone());
+        }
         aggregate.succeed(search_result);
+        record_search(&index_scheduler, &index_uid, q, search_result);
     }
     analytics.get_search(aggregate);
 
@@ -223,6 +437,8 @@ pub async fn search_with_post(
     params: AwebJson<SearchQuery, DeserrJsonError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
+    search_cache: web::Data<SearchCache>,
+    search_queue: web::Data<SearchQueue>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
 
@@ -240,13 +456,43 @@ pub async fn search_with_post(
 
     let features = index_scheduler.features();
 
-    let distribution = embed(&mut query, index_scheduler.get_ref(), &index).await?;
-
-    let search_result =
-        tokio::task::spawn_blocking(move || perform_search(&index, query, features, distribution))
-            .await?;
+    // the key is computed before the query is embedded, the cached searches are not embedded again
+    let before_search = Instant::now();
+    let cache_key =
+        search_cache.key(&index_uid, index_scheduler.index_write_epoch(&index_uid), &query);
+    let cached_result = cache_key.as_ref().and_then(|key| search_cache.get(key));
+
+    let q = query.q.clone();
+    let search_result = match cached_result {
+        Some(mut search_result) => {
+            search_result.processing_time_ms = before_search.elapsed().as_millis();
+            Ok(search_result)
+        }
+        None => {
+            let permit = search_queue.try_get_search_permit().await?;
+            let distribution = embed(&mut query, index_scheduler.get_ref(), &index).await?;
+            let foreign_indexes = ForeignIndexes::new(
+                index_scheduler.get_ref(),
+                index_scheduler.filters(),
+                &index,
+                &query,
+            )?;
+            permit.apply_deadline(&mut query);
+            tokio::task::spawn_blocking(move || {
+                // the search is still in flight when the request is cancelled
+                let _permit = permit;
+                perform_search(&index, query, features, distribution, &foreign_indexes)
+            })
+            .await?
+        }
+    };
     if let Ok(ref search_result) = search_result {
+        // a degraded search may have the time to rank all the hits the next time
+        if let Some(cache_key) = cache_key.filter(|_| !search_result.degraded) {
+            search_cache.insert(cache_key, search_result.clone());
+        }
         aggregate.succeed(search_result);
+        record_search(&index_scheduler, &index_uid, q, search_result);
     }
     analytics.post_search(aggregate);
 
@@ -256,15 +502,81 @@ pub async fn search_with_post(
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+/// Records the query and its number of hits when the search analytics are enabled,
+/// in a background task not to delay the response.
+pub fn record_search(
+    index_scheduler: &Data<IndexScheduler>,
+    index_uid: &IndexUid,
+    q: Option<String>,
+    search_result: &SearchResult,
+) {
+    let Some(q) = q else { return };
+    if !index_scheduler.features().runtime_features().search_analytics {
+        return;
+    }
+
+    let index_scheduler = index_scheduler.clone();
+    let index_uid = index_uid.to_string();
+    let hits = search_result.hits_info.total_hits();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = index_scheduler.record_search(&index_uid, &q, hits) {
+            error!("Could not record the search of `{index_uid}`: {e}");
+        }
+    });
+}
+
 pub async fn embed(
     query: &mut SearchQuery,
     index_scheduler: &IndexScheduler,
     index: &milli::Index,
 ) -> Result<Option<DistributionShift>, ResponseError> {
-    match (&query.hybrid, &query.vector, &query.q) {
-        (Some(HybridQuery { semantic_ratio: _, embedder }), None, Some(q))
-            if !q.trim().is_empty() =>
-        {
+    // only the positive part of the query is embedded, its negative words exclude documents
+    let positive_query = query.q.as_deref().map(|q| milli::split_negative_query(q).0);
+    match (&query.hybrid, &query.vector, &positive_query) {
+        (Some(hybrid), vector, q) if hybrid.embedders.is_some() => {
+            hybrid.validate_embedders(vector.is_some())?;
+            let Some(q) = q.as_deref().filter(|q| !q.trim().is_empty()) else { return Ok(None) };
+
+            let embedder_configs = index.embedding_configs(&index.read_txn()?)?;
+            let embedders = index_scheduler.embedders(embedder_configs)?;
+
+            let mut requested_embedders = Vec::new();
+            for embedder_name in hybrid.embedders.iter().flat_map(|embedders| embedders.keys()) {
+                let embedder = embedders
+                    .get(embedder_name)
+                    .ok_or_else(|| milli::UserError::InvalidEmbedder(embedder_name.to_owned()))
+                    .map_err(milli::Error::from)?
+                    .0;
+                requested_embedders.push((embedder_name.clone(), embedder));
+            }
+
+            // the embedders are queried concurrently
+            let all_embeddings = futures::future::try_join_all(
+                requested_embedders.iter().map(|(_, embedder)| embedder.embed(vec![q.to_owned()])),
+            )
+            .await
+            .map_err(milli::vector::Error::from)
+            .map_err(milli::Error::from)?;
+
+            let mut embedded_queries = BTreeMap::new();
+            for ((embedder_name, _), mut embeddings) in
+                requested_embedders.into_iter().zip(all_embeddings)
+            {
+                let embeddings = embeddings.pop().expect("No vector returned from embedding");
+
+                if embeddings.iter().nth(1).is_some() {
+                    warn!("Ignoring embeddings past the first one in long search query");
+                }
+                embedded_queries.insert(embedder_name, embeddings.iter().next().unwrap().to_vec());
+            }
+
+            if let Some(hybrid) = &mut query.hybrid {
+                hybrid.embedded_queries = embedded_queries;
+            }
+            // the similarities of each embedder are normalized before being combined
+            Ok(None)
+        }
+        (Some(HybridQuery { embedder, .. }), None, Some(q)) if !q.trim().is_empty() => {
             let embedder_configs = index.embedding_configs(&index.read_txn()?)?;
             let embedders = index_scheduler.embedders(embedder_configs)?;
 
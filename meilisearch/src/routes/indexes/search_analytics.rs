@@ -0,0 +1,94 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::{AwebJson, AwebQueryParameter};
+use index_scheduler::IndexScheduler;
+use log::debug;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use serde_json::json;
+
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+/// The number of queries returned by the aggregation routes when no limit is given.
+const DEFAULT_QUERIES_LIMIT: usize = 20;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/clicks").route(web::post().to(SeqHandler(record_click))))
+        .service(web::resource("/top-queries").route(web::get().to(SeqHandler(top_queries))))
+        .service(
+            web::resource("/queries-without-results")
+                .route(web::get().to(SeqHandler(queries_without_results))),
+        );
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchClick {
+    #[deserr(error = DeserrJsonError<InvalidSearchAnalyticsQ>, missing_field_error = DeserrJsonError::missing_search_analytics_q)]
+    q: String,
+    #[deserr(error = DeserrJsonError<InvalidSearchAnalyticsDocumentId>, missing_field_error = DeserrJsonError::missing_search_analytics_document_id)]
+    document_id: String,
+}
+
+/// Records that a document returned by a search of the query was clicked.
+pub async fn record_click(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SearchClick, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    index_scheduler.features().check_search_analytics("Recording the clicks on the hits")?;
+
+    let SearchClick { q, document_id } = params.into_inner();
+    debug!("click recorded on document `{document_id}` for query `{q}`");
+
+    // the index must exist, but its documents are not checked
+    index_scheduler.index(&index_uid)?;
+    let index_scheduler = index_scheduler.into_inner();
+    tokio::task::spawn_blocking(move || {
+        index_scheduler.record_search_click(&index_uid, &q, &document_id)
+    })
+    .await??;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct QueriesParams {
+    #[deserr(default = Param(DEFAULT_QUERIES_LIMIT), error = DeserrQueryParamError<InvalidSearchAnalyticsLimit>)]
+    limit: Param<usize>,
+}
+
+/// Returns the most searched queries of the index.
+pub async fn top_queries(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<QueriesParams, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    index_scheduler.features().check_search_analytics("Getting the top queries")?;
+
+    let results = index_scheduler.top_queries(&index_uid, params.limit.0)?;
+    debug!("returns: {:?}", results);
+    Ok(HttpResponse::Ok().json(json!({ "results": results })))
+}
+
+/// Returns the queries of the index that were the most often searched without results.
+pub async fn queries_without_results(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<QueriesParams, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    index_scheduler.features().check_search_analytics("Getting the queries without results")?;
+
+    let results = index_scheduler.queries_without_results(&index_uid, params.limit.0)?;
+    debug!("returns: {:?}", results);
+    Ok(HttpResponse::Ok().json(json!({ "results": results })))
+}
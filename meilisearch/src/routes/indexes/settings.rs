@@ -190,6 +190,181 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/non-flattened-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsNonFlattenedAttributes,
+    >,
+    non_flattened_attributes,
+    "nonFlattenedAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "NonFlattenedAttributes Updated".to_string(),
+            json!({
+                "non_flattened_attributes": {
+                    "total": setting.as_ref().map(|fields| fields.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/merge-strategies",
+    put,
+    std::collections::BTreeMap<String, meilisearch_types::settings::MergeStrategyView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsMergeStrategies,
+    >,
+    merge_strategies,
+    "mergeStrategies",
+    analytics,
+    |setting: &Option<std::collections::BTreeMap<String, meilisearch_types::settings::MergeStrategyView>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "MergeStrategies Updated".to_string(),
+            json!({
+                "merge_strategies": {
+                    "total": setting.as_ref().map(|strategies| strategies.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/type-coercions",
+    put,
+    std::collections::BTreeMap<String, meilisearch_types::settings::TypeCoercionView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsTypeCoercions,
+    >,
+    type_coercions,
+    "typeCoercions",
+    analytics,
+    |setting: &Option<std::collections::BTreeMap<String, meilisearch_types::settings::TypeCoercionView>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "TypeCoercions Updated".to_string(),
+            json!({
+                "type_coercions": {
+                    "total": setting.as_ref().map(|coercions| coercions.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/ingestion-mapping",
+    put,
+    std::collections::BTreeMap<String, String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsIngestionMapping,
+    >,
+    ingestion_mapping,
+    "ingestionMapping",
+    analytics,
+    |setting: &Option<std::collections::BTreeMap<String, String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "IngestionMapping Updated".to_string(),
+            json!({
+                "ingestion_mapping": {
+                    "total": setting.as_ref().map(|mapping| mapping.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/date-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsDateAttributes,
+    >,
+    date_attributes,
+    "dateAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "DateAttributes Updated".to_string(),
+            json!({
+                "date_attributes": {
+                    "total": setting.as_ref().map(|fields| fields.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/document-schema",
+    put,
+    serde_json::Value,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsDocumentSchema,
+    >,
+    document_schema,
+    "documentSchema",
+    analytics,
+    |setting: &Option<serde_json::Value>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "DocumentSchema Updated".to_string(),
+            json!({
+                "document_schema": {
+                    "set": setting.is_some(),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/suggestable-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsSuggestableAttributes,
+    >,
+    suggestable_attributes,
+    "suggestableAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+
+        analytics.publish(
+            "SuggestableAttributes Updated".to_string(),
+            json!({
+                "suggestable_attributes": {
+                    "total": setting.as_ref().map(|fields| fields.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
 make_setting_route!(
     "/displayed-attributes",
     put,
@@ -240,6 +415,12 @@ make_setting_route!(
                     "disable_on_words": setting
                         .as_ref()
                         .and_then(|s| s.disable_on_words.as_ref().set().map(|m| !m.is_empty())),
+                    "disable_on_first_character": setting
+                        .as_ref()
+                        .and_then(|s| s.disable_on_first_character.as_ref().set().copied()),
+                    "enable_on_numbers": setting
+                        .as_ref()
+                        .and_then(|s| s.enable_on_numbers.as_ref().set().copied()),
                     "min_word_size_for_one_typo": setting
                         .as_ref()
                         .and_then(|s| s.min_word_size_for_typos
@@ -436,6 +617,201 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/static-boost",
+    put,
+    meilisearch_types::settings::StaticBoostView,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsStaticBoost,
+    >,
+    static_boost,
+    "staticBoost",
+    analytics,
+    |static_boost: &Option<meilisearch_types::settings::StaticBoostView>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "StaticBoost Updated".to_string(),
+            json!({
+                "static_boost": {
+                    "set": static_boost.is_some(),
+                    "curve": static_boost.as_ref().map(|static_boost| static_boost.curve),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/foreign-keys",
+    put,
+    Vec<meilisearch_types::settings::ForeignKeyView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsForeignKeys,
+    >,
+    foreign_keys,
+    "foreignKeys",
+    analytics,
+    |foreign_keys: &Option<Vec<meilisearch_types::settings::ForeignKeyView>>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "ForeignKeys Updated".to_string(),
+            json!({
+                "foreign_keys": {
+                    "total": foreign_keys.as_ref().map(|foreign_keys| foreign_keys.len()),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/query-rules",
+    put,
+    Vec<meilisearch_types::settings::QueryRuleView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsQueryRules,
+    >,
+    query_rules,
+    "queryRules",
+    analytics,
+    |query_rules: &Option<Vec<meilisearch_types::settings::QueryRuleView>>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "QueryRules Updated".to_string(),
+            json!({
+                "query_rules": {
+                    "total": query_rules.as_ref().map(|query_rules| query_rules.len()),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/reranker",
+    put,
+    meilisearch_types::settings::RerankerView,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsReranker,
+    >,
+    reranker,
+    "reranker",
+    analytics,
+    |reranker: &Option<meilisearch_types::settings::RerankerView>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "Reranker Updated".to_string(),
+            json!({
+                "reranker": {
+                    "set": reranker.is_some(),
+                    "top_n": reranker.as_ref().map(|reranker| reranker.top_n),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/localized-attributes",
+    put,
+    Vec<meilisearch_types::settings::LocalizedAttributesRuleView>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsLocalizedAttributes,
+    >,
+    localized_attributes,
+    "localizedAttributes",
+    analytics,
+    |rules: &Option<Vec<meilisearch_types::settings::LocalizedAttributesRuleView>>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "LocalizedAttributes Updated".to_string(),
+            json!({
+                "localized_attributes": {
+                    "total": rules.as_ref().map(|rules| rules.len()),
+                    "locales": rules.as_ref().map(|rules| rules.iter().flat_map(|rule| rule.locales.iter()).collect::<std::collections::BTreeSet<_>>()),
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/stemming",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsStemming,
+    >,
+    stemming,
+    "stemming",
+    analytics,
+    |locales: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "Stemming Updated".to_string(),
+            json!({
+                "stemming": {
+                    "locales": locales,
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/romanization",
+    put,
+    bool,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsRomanization,
+    >,
+    romanization,
+    "romanization",
+    analytics,
+    |romanization: &Option<bool>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "Romanization Updated".to_string(),
+            json!({
+                "romanization": {
+                    "enabled": romanization,
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/split-identifiers",
+    put,
+    bool,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsSplitIdentifiers,
+    >,
+    split_identifiers,
+    "splitIdentifiers",
+    analytics,
+    |split_identifiers: &Option<bool>, req: &HttpRequest| {
+        use serde_json::json;
+        analytics.publish(
+            "SplitIdentifiers Updated".to_string(),
+            json!({
+                "split_identifiers": {
+                    "enabled": split_identifiers,
+                }
+            }),
+            Some(req),
+        );
+    }
+);
+
 make_setting_route!(
     "/proximity-precision",
     put,
@@ -484,7 +860,7 @@ make_setting_route!(
                     "attribute_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Attribute))),
                     "sort_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Sort))),
                     "exactness_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Exactness))),
-                    "values": setting.as_ref().map(|rr| rr.iter().filter(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Asc(_) | meilisearch_types::settings::RankingRuleView::Desc(_)) ).map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
+                    "values": setting.as_ref().map(|rr| rr.iter().filter(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Asc(_) | meilisearch_types::settings::RankingRuleView::Desc(_) | meilisearch_types::settings::RankingRuleView::AscExpression(_) | meilisearch_types::settings::RankingRuleView::DescExpression(_)) ).map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
                 }
             }),
             Some(req),
@@ -515,6 +891,7 @@ make_setting_route!(
                         s.sort_facet_values_by.as_ref().set().map(|s| s.iter().any(|(k, v)| k == "*" && v == &FacetValuesSort::Count))
                     }),
                     "sort_facet_values_by_total": setting.as_ref().and_then(|s| s.sort_facet_values_by.as_ref().set().map(|s| s.len())),
+                    "hierarchy_separators_total": setting.as_ref().and_then(|s| s.hierarchy_separators.as_ref().set().map(|s| s.len())),
                 },
             }),
             Some(req),
@@ -587,6 +964,8 @@ fn embedder_analytics(
             use meilisearch_types::milli::vector::settings::EmbedderSettings;
             match source {
                 EmbedderSettings::OpenAi(_) => sources.insert("openAi"),
+                EmbedderSettings::Ollama(_) => sources.insert("ollama"),
+                EmbedderSettings::Rest(_) => sources.insert("rest"),
                 EmbedderSettings::HuggingFace(_) => sources.insert("huggingFace"),
                 EmbedderSettings::UserProvided(_) => sources.insert("userProvided"),
             };
@@ -599,11 +978,42 @@ fn embedder_analytics(
             .any(|config| config.document_template.set().is_some())
     });
 
+    let mut distances = std::collections::HashSet::new();
+    if let Some(s) = &setting {
+        for distance in s
+            .values()
+            .filter_map(|config| config.clone().set())
+            .filter_map(|config| config.distance.set())
+        {
+            use meilisearch_types::milli::vector::Distance;
+            match distance {
+                Distance::Cosine => distances.insert("cosine"),
+                Distance::DotProduct => distances.insert("dotProduct"),
+                Distance::Euclidean => distances.insert("euclidean"),
+            };
+        }
+    };
+
+    let binary_quantized_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.binary_quantized.set().unwrap_or_default())
+    });
+
+    let request_policy_used = setting.as_ref().map(|map| {
+        map.values()
+            .filter_map(|config| config.clone().set())
+            .any(|config| config.request_policy.set().is_some())
+    });
+
     json!(
         {
             "total": setting.as_ref().map(|s| s.len()),
             "sources": sources,
             "document_template_used": document_template_used,
+            "binary_quantized_used": binary_quantized_used,
+            "request_policy_used": request_policy_used,
+            "distances": distances,
         }
     )
 }
@@ -625,9 +1035,24 @@ macro_rules! generate_configure {
 generate_configure!(
     filterable_attributes,
     sortable_attributes,
+    non_flattened_attributes,
+    merge_strategies,
+    type_coercions,
+    ingestion_mapping,
+    date_attributes,
+    document_schema,
+    suggestable_attributes,
     displayed_attributes,
     searchable_attributes,
     distinct_attribute,
+    static_boost,
+    foreign_keys,
+    query_rules,
+    reranker,
+    localized_attributes,
+    stemming,
+    romanization,
+    split_identifiers,
     proximity_precision,
     stop_words,
     separator_tokens,
@@ -680,9 +1105,57 @@ pub async fn update_all(
                 "total": new_settings.filterable_attributes.as_ref().set().map(|filter| filter.len()),
                 "has_geo": new_settings.filterable_attributes.as_ref().set().map(|filter| filter.iter().any(|s| s == "_geo")),
             },
+            "non_flattened_attributes": {
+                "total": new_settings.non_flattened_attributes.as_ref().set().map(|fields| fields.len()),
+            },
+            "merge_strategies": {
+                "total": new_settings.merge_strategies.as_ref().set().map(|strategies| strategies.len()),
+            },
+            "type_coercions": {
+                "total": new_settings.type_coercions.as_ref().set().map(|coercions| coercions.len()),
+            },
+            "ingestion_mapping": {
+                "total": new_settings.ingestion_mapping.as_ref().set().map(|mapping| mapping.len()),
+            },
+            "date_attributes": {
+                "total": new_settings.date_attributes.as_ref().set().map(|fields| fields.len()),
+            },
+            "document_schema": {
+                "set": new_settings.document_schema.as_ref().set().is_some(),
+            },
+            "suggestable_attributes": {
+                "total": new_settings.suggestable_attributes.as_ref().set().map(|fields| fields.len()),
+            },
             "distinct_attribute": {
                 "set": new_settings.distinct_attribute.as_ref().set().is_some()
             },
+            "static_boost": {
+                "set": new_settings.static_boost.as_ref().set().is_some(),
+                "curve": new_settings.static_boost.as_ref().set().map(|static_boost| static_boost.curve),
+            },
+            "foreign_keys": {
+                "total": new_settings.foreign_keys.as_ref().set().map(|foreign_keys| foreign_keys.len()),
+            },
+            "query_rules": {
+                "total": new_settings.query_rules.as_ref().set().map(|query_rules| query_rules.len()),
+            },
+            "reranker": {
+                "set": new_settings.reranker.as_ref().set().is_some(),
+                "top_n": new_settings.reranker.as_ref().set().map(|reranker| reranker.top_n),
+            },
+            "localized_attributes": {
+                "total": new_settings.localized_attributes.as_ref().set().map(|rules| rules.len()),
+                "locales": new_settings.localized_attributes.as_ref().set().map(|rules| rules.iter().flat_map(|rule| rule.locales.iter()).collect::<std::collections::BTreeSet<_>>()),
+            },
+            "stemming": {
+                "locales": new_settings.stemming.as_ref().set(),
+            },
+            "romanization": {
+                "enabled": new_settings.romanization.as_ref().set(),
+            },
+            "split_identifiers": {
+                "enabled": new_settings.split_identifiers.as_ref().set(),
+            },
             "proximity_precision": {
                 "set": new_settings.proximity_precision.as_ref().set().is_some()
             },
@@ -700,6 +1173,16 @@ pub async fn update_all(
                     .as_ref()
                     .set()
                     .and_then(|s| s.disable_on_words.as_ref().set().map(|m| !m.is_empty())),
+                "disable_on_first_character": new_settings.typo_tolerance
+                    .as_ref()
+                    .set()
+                    .and_then(|s| s.disable_on_first_character.as_ref().set())
+                    .copied(),
+                "enable_on_numbers": new_settings.typo_tolerance
+                    .as_ref()
+                    .set()
+                    .and_then(|s| s.enable_on_numbers.as_ref().set())
+                    .copied(),
                 "min_word_size_for_one_typo": new_settings.typo_tolerance
                     .as_ref()
                     .set()
@@ -732,6 +1215,10 @@ pub async fn update_all(
                     .as_ref()
                     .set()
                     .and_then(|s| s.sort_facet_values_by.as_ref().set().map(|s| s.len())),
+                "hierarchy_separators_total": new_settings.faceting
+                    .as_ref()
+                    .set()
+                    .and_then(|s| s.hierarchy_separators.as_ref().set().map(|s| s.len())),
             },
             "pagination": {
                 "max_total_hits": new_settings.pagination
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebQueryParameter;
+use index_scheduler::IndexScheduler;
+use log::debug;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::serde_cs::vec::CS;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::search::{embed, RankingScoreThresholdGet};
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::search::{
+    add_search_rules, parse_filter, perform_search, ForeignIndexes, HybridQuery, MatchingStrategy,
+    SearchQuery, SemanticRatio, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET,
+};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/{document_id}").route(web::get().to(SeqHandler(similar))));
+}
+
+#[derive(Deserialize)]
+pub struct SimilarParam {
+    index_uid: String,
+    document_id: String,
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SimilarQueryGet {
+    #[deserr(default, error = DeserrQueryParamError<InvalidEmbedder>)]
+    embedder: Option<String>,
+    #[deserr(default = Param(DEFAULT_SEARCH_OFFSET()), error = DeserrQueryParamError<InvalidSearchOffset>)]
+    offset: Param<usize>,
+    #[deserr(default = Param(DEFAULT_SEARCH_LIMIT()), error = DeserrQueryParamError<InvalidSearchLimit>)]
+    limit: Param<usize>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToRetrieve>)]
+    attributes_to_retrieve: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFilter>)]
+    filter: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScore>)]
+    show_ranking_score: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScoreDetails>)]
+    show_ranking_score_details: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRankingScoreThreshold>)]
+    ranking_score_threshold: Option<RankingScoreThresholdGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRetrieveVectors>)]
+    retrieve_vectors: Param<bool>,
+}
+
+/// Returns the nearest neighbors of a document in the vector space of an embedder,
+/// the document itself being excluded from the results.
+pub async fn similar(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    path: web::Path<SimilarParam>,
+    params: AwebQueryParameter<SimilarQueryGet, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let SimilarParam { index_uid, document_id } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+    debug!("similar called with params: {:?}", params);
+
+    let mut query = params.into_inner().into_search_query(document_id.clone());
+
+    // Tenant token search_rules.
+    let search_rules = index_scheduler.filters().get_index_search_rules(&index_uid);
+    let tenant_filter = search_rules.as_ref().and_then(|rules| rules.filter.clone());
+    if let Some(search_rules) = search_rules {
+        add_search_rules(&mut query, search_rules);
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    index_scheduler.features().check_vector("Using the similar documents route")?;
+
+    // the stored embedding of the document is the vector of the search
+    let rtxn = index.read_txn()?;
+    let docid = index
+        .external_documents_ids()
+        .get(&rtxn, &document_id)?
+        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(document_id.clone()))?;
+    // the reference document must be visible to the tenant token, like the returned ones
+    if let Some(filter) = tenant_filter.as_ref().map(parse_filter).transpose()?.flatten() {
+        if !filter.evaluate(&rtxn, &index)?.contains(docid) {
+            return Err(MeilisearchHttpError::DocumentNotFound(document_id).into());
+        }
+    }
+    let embedder_name = match query.hybrid.as_ref().and_then(|hybrid| hybrid.embedder.clone()) {
+        Some(embedder_name) => embedder_name,
+        None => index.default_embedding_name(&rtxn)?,
+    };
+    query.vector = index
+        .embeddings(&rtxn, docid)?
+        .remove(&embedder_name)
+        .and_then(|embeddings| embeddings.into_iter().next());
+    drop(rtxn);
+    if query.vector.is_none() {
+        return Err(MeilisearchHttpError::DocumentWithoutVector(document_id, embedder_name).into());
+    }
+
+    let features = index_scheduler.features();
+    let distribution = embed(&mut query, index_scheduler.get_ref(), &index).await?;
+    let foreign_indexes =
+        ForeignIndexes::new(index_scheduler.get_ref(), index_scheduler.filters(), &index, &query)?;
+
+    let search_result = tokio::task::spawn_blocking(move || {
+        perform_search(&index, query, features, distribution, &foreign_indexes)
+    })
+    .await??;
+
+    debug!("returns: {:?}", search_result);
+    Ok(HttpResponse::Ok().json(search_result))
+}
+
+impl SimilarQueryGet {
+    /// A purely semantic search that hides the reference document.
+    fn into_search_query(self, document_id: String) -> SearchQuery {
+        let SimilarQueryGet {
+            embedder,
+            offset,
+            limit,
+            attributes_to_retrieve,
+            filter,
+            show_ranking_score,
+            show_ranking_score_details,
+            ranking_score_threshold,
+            retrieve_vectors,
+        } = self;
+
+        let filter = filter.map(|f| match serde_json::from_str(&f) {
+            Ok(v) => v,
+            _ => Value::String(f),
+        });
+
+        SearchQuery {
+            q: None,
+            vector: None,
+            retrieve_vectors: retrieve_vectors.0,
+            hybrid: Some(HybridQuery {
+                semantic_ratio: SemanticRatio::semantic_only(),
+                embedder,
+                fusion: Default::default(),
+                candidate_selection: Default::default(),
+                embedders: None,
+                embedded_queries: BTreeMap::new(),
+            }),
+            offset: offset.0,
+            limit: limit.0,
+            page: None,
+            hits_per_page: None,
+            attributes_to_retrieve: attributes_to_retrieve.map(|o| o.into_iter().collect()),
+            attributes_to_crop: None,
+            crop_length: DEFAULT_CROP_LENGTH(),
+            attributes_to_highlight: None,
+            show_matches_position: false,
+            matches_position_unit: None,
+            show_matched_terms: false,
+            show_corrected_query: false,
+            show_ranking_score: show_ranking_score.0,
+            show_ranking_score_details: show_ranking_score_details.0,
+            filter,
+            sort: None,
+            facets: None,
+            disjunctive_facets: None,
+            extended_facet_stats: false,
+            aggregations: None,
+            sort_facet_values_by: None,
+            highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
+            highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
+            crop_marker: DEFAULT_CROP_MARKER(),
+            highlight_tags: None,
+            highlight_escape_html: false,
+            snippet: None,
+            matching_strategy: MatchingStrategy::default(),
+            attributes_to_search_on: None,
+            geo_reference_point: None,
+            attribute_boosts: None,
+            ranking_score_threshold: ranking_score_threshold.map(|threshold| threshold.0),
+            timeout_ms: None,
+            count_only: false,
+            early_termination: false,
+            search_after: None,
+            group_by: None,
+            distinct: None,
+            structured_query: None,
+            embed_foreign_documents: false,
+            foreign_filters: None,
+            pinned_documents: None,
+            hidden_documents: Some(vec![document_id]),
+            vocabulary: None,
+            phrase_slop: None,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Instant;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::{AwebJson, AwebQueryParameter};
+use index_scheduler::IndexScheduler;
+use log::debug;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::suggestions::{Suggest, Suggestion, DEFAULT_SUGGESTIONS_LIMIT};
+use serde::Serialize;
+
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(SeqHandler(suggest_with_url_query)))
+            .route(web::post().to(SeqHandler(suggest_with_post))),
+    );
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SuggestQueryGet {
+    #[deserr(default, error = DeserrQueryParamError<InvalidSuggestQ>)]
+    q: Option<String>,
+    #[deserr(default = Param(DEFAULT_SUGGESTIONS_LIMIT), error = DeserrQueryParamError<InvalidSuggestLimit>)]
+    limit: Param<usize>,
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SuggestQuery {
+    #[deserr(default, error = DeserrJsonError<InvalidSuggestQ>)]
+    q: Option<String>,
+    #[deserr(default = DEFAULT_SUGGESTIONS_LIMIT, error = DeserrJsonError<InvalidSuggestLimit>)]
+    limit: usize,
+}
+
+impl From<SuggestQueryGet> for SuggestQuery {
+    fn from(SuggestQueryGet { q, limit }: SuggestQueryGet) -> Self {
+        SuggestQuery { q, limit: limit.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestResult {
+    suggestions: Vec<Suggestion>,
+    query: String,
+    processing_time_ms: u128,
+}
+
+pub async fn suggest_with_url_query(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<SuggestQueryGet, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!("suggest called with params: {:?}", params);
+    let query = params.into_inner().into();
+    let result = suggest(&index_scheduler, index_uid.into_inner(), query)?;
+
+    debug!("returns: {:?}", result);
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub async fn suggest_with_post(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SuggestQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!("suggest called with params: {:?}", params);
+    let result = suggest(&index_scheduler, index_uid.into_inner(), params.into_inner())?;
+
+    debug!("returns: {:?}", result);
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Completes the query with the suggestions of the index.
+///
+/// Only the suggestions FST of the index is read, in the current task: the suggestions don't
+/// wait for a search permit and are not slowed down by the indexing, which writes in its own
+/// transaction.
+fn suggest(
+    index_scheduler: &GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: String,
+    query: SuggestQuery,
+) -> Result<SuggestResult, ResponseError> {
+    let before_suggest = Instant::now();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    // the suggestions are not computed from the documents at query time and can't be filtered
+    let search_rules = index_scheduler.filters().get_index_search_rules(&index_uid);
+    if search_rules.map_or(false, |rules| rules.filter.is_some()) {
+        return Err(
+            MeilisearchHttpError::SuggestionsWithTenantFilter(index_uid.into_inner()).into()
+        );
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let q = query.q.unwrap_or_default();
+    let suggestions = Suggest::new(&index, &rtxn, q.as_str()).limit(query.limit).execute()?;
+
+    Ok(SuggestResult {
+        suggestions,
+        query: q,
+        processing_time_ms: before_suggest.elapsed().as_millis(),
+    })
+}
@@ -19,6 +19,7 @@ use crate::extractors::authentication::GuardedData;
 const PAGINATION_DEFAULT_LIMIT: usize = 20;
 
 mod api_key;
+mod chat;
 mod dump;
 pub mod features;
 pub mod indexes;
@@ -40,7 +41,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(web::scope("/multi-search").configure(multi_search::configure))
         .service(web::scope("/swap-indexes").configure(swap_indexes::configure))
         .service(web::scope("/metrics").configure(metrics::configure))
-        .service(web::scope("/experimental-features").configure(features::configure));
+        .service(web::scope("/experimental-features").configure(features::configure))
+        .service(web::scope("/chat").configure(chat::configure));
 }
 
 #[derive(Debug, Serialize)]
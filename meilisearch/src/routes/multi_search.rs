@@ -15,8 +15,9 @@ use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::routes::indexes::search::embed;
 use crate::search::{
-    add_search_rules, perform_search, SearchQueryWithIndex, SearchResultWithIndex,
+    add_search_rules, perform_search, ForeignIndexes, SearchQueryWithIndex, SearchResultWithIndex,
 };
+use crate::search_queue::SearchQueue;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("").route(web::post().to(SeqHandler(multi_search_with_post))));
@@ -38,12 +39,16 @@ pub async fn multi_search_with_post(
     params: AwebJson<SearchQueries, DeserrJsonError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
+    search_queue: web::Data<SearchQueue>,
 ) -> Result<HttpResponse, ResponseError> {
     let queries = params.into_inner().queries;
 
     let mut multi_aggregate = MultiSearchAggregator::from_queries(&queries, &req);
     let features = index_scheduler.features();
 
+    // the queries of a multi-search are processed one after the other with a single permit
+    let permit = search_queue.try_get_search_permit().await?;
+
     // Explicitly expect a `(ResponseError, usize)` for the error type rather than `ResponseError` only,
     // so that `?` doesn't work if it doesn't use `with_index`, ensuring that it is not forgotten in case of code
     // changes.
@@ -79,8 +84,17 @@ pub async fn multi_search_with_post(
                 .await
                 .with_index(query_index)?;
 
+            let foreign_indexes = ForeignIndexes::new(
+                index_scheduler.get_ref(),
+                index_scheduler.filters(),
+                &index,
+                &query,
+            )
+            .with_index(query_index)?;
+
+            permit.apply_deadline(&mut query);
             let search_result = tokio::task::spawn_blocking(move || {
-                perform_search(&index, query, features, distribution)
+                perform_search(&index, query, features, distribution, &foreign_indexes)
             })
             .await
             .with_index(query_index)?;
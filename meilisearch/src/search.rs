@@ -1,34 +1,42 @@
-use std::cmp::min;
+use std::cmp::{max, min, Reverse};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use deserr::Deserr;
 use either::Either;
-use index_scheduler::RoFeatures;
+use index_scheduler::{IndexScheduler, RoFeatures};
 use indexmap::IndexMap;
-use meilisearch_auth::IndexSearchRules;
+use meilisearch_auth::{AuthFilter, IndexSearchRules};
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::facet_values_sort::FacetValuesSort;
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::foreign_key::ForeignKey;
+use meilisearch_types::milli::geojson::is_geojson_field;
 use meilisearch_types::milli::score_details::{self, ScoreDetails, ScoringStrategy};
+use meilisearch_types::milli::vector::rerank::{Reranker, RerankerOptions};
 use meilisearch_types::milli::vector::DistributionShift;
 use meilisearch_types::milli::{FacetValueHit, OrderBy, SearchForFacetValues};
 use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
 use meilisearch_types::{milli, Document};
 use milli::tokenizer::TokenizerBuilder;
 use milli::{
-    AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, MatchBounds, MatcherBuilder,
-    SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    AscDesc, CandidateSelection, DocumentId, FieldId, FieldsIdsMap, Filter, FormatOptions,
+    FusionStrategy, Index, MatchBounds, MatcherBuilder, OffsetUnit, QueryVocabulary, SearchCursor,
+    SortError, TermsMatchingStrategy, TimeBudget, VectorTarget, DEFAULT_VALUES_PER_FACET,
 };
 use regex::Regex;
+use roaring::RoaringBitmap;
 use serde::Serialize;
 use serde_json::{json, Value};
 
 use crate::error::MeilisearchHttpError;
 
 type MatchesPosition = BTreeMap<String, Vec<MatchBounds>>;
+/// The matched words of the documents associated with the attributes they matched in.
+type MatchedTerms = BTreeMap<String, BTreeSet<String>>;
 
 pub const DEFAULT_SEARCH_OFFSET: fn() -> usize = || 0;
 pub const DEFAULT_SEARCH_LIMIT: fn() -> usize = || 20;
@@ -37,14 +45,24 @@ pub const DEFAULT_CROP_MARKER: fn() -> String = || "…".to_string();
 pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
+pub const DEFAULT_GROUP_BY_LIMIT: fn() -> usize = || 1;
+pub const DEFAULT_SNIPPET_LENGTH: fn() -> usize = || 200;
+
+/// The maximum number of foreign documents a foreign filter can match, their ids being
+/// turned into an `IN` filter on the foreign key.
+pub const MAX_FOREIGN_FILTER_DOCUMENTS: u64 = 10_000;
 
 #[derive(Debug, Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SearchQuery {
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub q: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub structured_query: Option<StructuredQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchVector>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRetrieveVectors>)]
+    pub retrieve_vectors: bool,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -65,6 +83,12 @@ pub struct SearchQuery {
     pub attributes_to_highlight: Option<HashSet<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchesPosition>, default)]
     pub show_matches_position: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMatchesPositionUnit>)]
+    pub matches_position_unit: Option<MatchesPositionUnit>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchedTerms>, default)]
+    pub show_matched_terms: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowCorrectedQuery>, default)]
+    pub show_corrected_query: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingScore>, default)]
     pub show_ranking_score: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingScoreDetails>, default)]
@@ -75,16 +99,60 @@ pub struct SearchQuery {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDisjunctiveFacets>)]
+    pub disjunctive_facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchExtendedFacetStats>, default)]
+    pub extended_facet_stats: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAggregations>)]
+    pub aggregations: Option<BTreeMap<String, Vec<Aggregation>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSortFacetValuesBy>)]
+    pub sort_facet_values_by: Option<FacetValuesSort>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
     pub highlight_post_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     pub crop_marker: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTags>)]
+    pub highlight_tags: Option<BTreeMap<String, HighlightTags>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightEscapeHtml>, default)]
+    pub highlight_escape_html: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippet>)]
+    pub snippet: Option<SnippetQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGeoReferencePoint>)]
+    pub geo_reference_point: Option<GeoReferencePoint>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributeBoosts>)]
+    pub attribute_boosts: Option<AttributeBoosts>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>)]
+    pub ranking_score_threshold: Option<RankingScoreThreshold>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchTimeoutMs>)]
+    pub timeout_ms: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCountOnly>, default)]
+    pub count_only: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchEarlyTermination>, default)]
+    pub early_termination: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<SearchAfter>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGroupBy>)]
+    pub group_by: Option<GroupByQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchEmbedForeignDocuments>, default)]
+    pub embed_foreign_documents: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchForeignFilters>)]
+    pub foreign_filters: Option<BTreeMap<String, Value>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPinnedDocuments>)]
+    pub pinned_documents: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHiddenDocuments>)]
+    pub hidden_documents: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub vocabulary: Option<SearchVocabulary>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPhraseSlop>)]
+    pub phrase_slop: Option<u8>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Deserr)]
@@ -95,6 +163,178 @@ pub struct HybridQuery {
     pub semantic_ratio: SemanticRatio,
     #[deserr(default, error = DeserrJsonError<InvalidEmbedder>, default)]
     pub embedder: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHybridFusion>)]
+    pub fusion: HybridFusion,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHybridCandidateSelection>)]
+    pub candidate_selection: HybridCandidateSelection,
+    /// The weight of each embedder whose vector space is searched, combining their similarities.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHybridEmbedders>)]
+    pub embedders: Option<BTreeMap<String, f32>>,
+    /// The query embedded by each embedder of `embedders`.
+    #[deserr(skip)]
+    pub embedded_queries: BTreeMap<String, Vec<f32>>,
+}
+
+impl HybridQuery {
+    /// Checks that `embedders` can replace the embedder and the vector of the query.
+    pub fn validate_embedders(&self, has_vector: bool) -> Result<(), MeilisearchHttpError> {
+        let Some(embedders) = &self.embedders else { return Ok(()) };
+        if self.embedder.is_some() {
+            return Err(MeilisearchHttpError::EmbedderWithEmbedders);
+        }
+        if has_vector {
+            return Err(MeilisearchHttpError::VectorWithEmbedders);
+        }
+        if embedders.is_empty() {
+            return Err(MeilisearchHttpError::EmptyEmbedders);
+        }
+        match embedders.iter().find(|(_, weight)| !weight.is_finite() || **weight <= 0.0) {
+            Some((name, weight)) => {
+                Err(MeilisearchHttpError::InvalidEmbedderWeight(name.clone(), *weight))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// The highlight tags of the attributes matching a pattern, replacing the ones of the query.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchHighlightTags>, rename_all = camelCase, deny_unknown_fields)]
+pub struct HighlightTags {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTags>)]
+    pub pre_tag: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTags>)]
+    pub post_tag: Option<String>,
+}
+
+/// Builds a single snippet out of the best matching fragments of the attributes,
+/// holding at most `length` characters.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchSnippet>, rename_all = camelCase, deny_unknown_fields)]
+pub struct SnippetQuery {
+    #[deserr(error = DeserrJsonError<InvalidSearchSnippet>)]
+    pub attributes: Vec<String>,
+    #[deserr(default = DEFAULT_SNIPPET_LENGTH(), error = DeserrJsonError<InvalidSearchSnippet>)]
+    pub length: usize,
+}
+
+/// Returns the `limit` best hits of each group of hits sharing the same value for the attribute.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchGroupBy>, rename_all = camelCase, deny_unknown_fields)]
+pub struct GroupByQuery {
+    #[deserr(error = DeserrJsonError<InvalidSearchGroupBy>)]
+    pub attribute: String,
+    #[deserr(default = DEFAULT_GROUP_BY_LIMIT(), error = DeserrJsonError<InvalidSearchGroupBy>)]
+    pub limit: usize,
+}
+
+/// A query made of clauses, replacing `q` for the queries built by applications.
+#[derive(Debug, Clone, Default, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchStructuredQuery>, rename_all = camelCase, deny_unknown_fields)]
+pub struct StructuredQuery {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub must: Vec<QueryClause>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub should: Vec<QueryClause>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub must_not: Vec<QueryClause>,
+}
+
+/// A clause of a structured query, made of exactly one of `term`, `phrase` or `filter`.
+///
+/// The `vector` and nested `bool` clauses are recognized to be rejected with an explicit error.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchStructuredQuery>, rename_all = camelCase, deny_unknown_fields)]
+pub struct QueryClause {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub term: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub phrase: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub filter: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub vector: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub bool: Option<Value>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub boost: Option<f64>,
+}
+
+impl StructuredQuery {
+    fn to_milli(&self) -> Result<milli::StructuredQuery<'_>, MeilisearchHttpError> {
+        Ok(milli::StructuredQuery {
+            must: clauses_to_milli(&self.must, "must")?,
+            should: clauses_to_milli(&self.should, "should")?,
+            must_not: clauses_to_milli(&self.must_not, "mustNot")?,
+        })
+    }
+}
+
+fn clauses_to_milli<'a>(
+    clauses: &'a [QueryClause],
+    name: &'static str,
+) -> Result<Vec<milli::QueryClause<'a>>, MeilisearchHttpError> {
+    let mut converted = Vec::new();
+    for clause in clauses {
+        if let Some(clause) = clause.to_milli(name)? {
+            converted.push(clause);
+        }
+    }
+    Ok(converted)
+}
+
+impl QueryClause {
+    /// Returns `None` for the filters matching all the documents, such as an empty array.
+    fn to_milli(
+        &self,
+        name: &'static str,
+    ) -> Result<Option<milli::QueryClause<'_>>, MeilisearchHttpError> {
+        if self.vector.is_some() {
+            return Err(MeilisearchHttpError::InvalidQueryClause(
+                name,
+                "`vector` clauses are not supported, use the `vector` or `hybrid` parameters to combine the query with a vector search".to_string(),
+            ));
+        }
+        if self.bool.is_some() {
+            return Err(MeilisearchHttpError::InvalidQueryClause(
+                name,
+                "nested `bool` clauses are not supported".to_string(),
+            ));
+        }
+
+        let boost = match self.boost {
+            Some(_) if name != "should" => {
+                return Err(MeilisearchHttpError::InvalidQueryClause(
+                    name,
+                    "a `boost` can only be used in the `should` clauses".to_string(),
+                ))
+            }
+            Some(boost) if !boost.is_finite() || boost <= 0.0 => {
+                return Err(MeilisearchHttpError::InvalidQueryClause(
+                    name,
+                    format!("the `boost` must be a positive number, found `{boost}`"),
+                ))
+            }
+            Some(boost) => boost,
+            None => 1.0,
+        };
+
+        match (&self.term, &self.phrase, &self.filter) {
+            (Some(text), None, None) => Ok(Some(milli::QueryClause::Term { text, boost })),
+            (None, Some(text), None) => Ok(Some(milli::QueryClause::Phrase { text, boost })),
+            (None, None, Some(_)) if name == "should" => {
+                Err(MeilisearchHttpError::InvalidQueryClause(
+                    name,
+                    "a `filter` can only be used in the `must` and `mustNot` clauses".to_string(),
+                ))
+            }
+            (None, None, Some(filter)) => Ok(parse_filter(filter)?.map(milli::QueryClause::Filter)),
+            _ => Err(MeilisearchHttpError::InvalidQueryClause(
+                name,
+                "a clause must contain exactly one of `term`, `phrase` or `filter`".to_string(),
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserr)]
@@ -107,6 +347,13 @@ impl Default for SemanticRatio {
     }
 }
 
+impl SemanticRatio {
+    /// Ranks the documents only by the similarity of their vectors.
+    pub fn semantic_only() -> Self {
+        SemanticRatio(1.0)
+    }
+}
+
 impl std::convert::TryFrom<f32> for SemanticRatio {
     type Error = InvalidSearchSemanticRatio;
 
@@ -129,6 +376,127 @@ impl std::ops::Deref for SemanticRatio {
     }
 }
 
+/// The `[latitude, longitude]` point from which the `_geoDistance` of the hits is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(try_from(Vec<f64>) = TryFrom::try_from -> InvalidSearchGeoReferencePoint)]
+pub struct GeoReferencePoint([f64; 2]);
+
+impl std::convert::TryFrom<Vec<f64>> for GeoReferencePoint {
+    type Error = InvalidSearchGeoReferencePoint;
+
+    fn try_from(point: Vec<f64>) -> Result<Self, Self::Error> {
+        match point.as_slice() {
+            [lat, lng] if (-90.0..=90.0).contains(lat) && (-180.0..=180.0).contains(lng) => {
+                Ok(GeoReferencePoint([*lat, *lng]))
+            }
+            _ => Err(InvalidSearchGeoReferencePoint),
+        }
+    }
+}
+
+impl std::ops::Deref for GeoReferencePoint {
+    type Target = [f64; 2];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The minimum ranking score of the hits, between `0.0` and `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(try_from(f64) = TryFrom::try_from -> InvalidSearchRankingScoreThreshold)]
+pub struct RankingScoreThreshold(f64);
+
+impl std::convert::TryFrom<f64> for RankingScoreThreshold {
+    type Error = InvalidSearchRankingScoreThreshold;
+
+    fn try_from(f: f64) -> Result<Self, Self::Error> {
+        if (0.0..=1.0).contains(&f) {
+            Ok(RankingScoreThreshold(f))
+        } else {
+            Err(InvalidSearchRankingScoreThreshold)
+        }
+    }
+}
+
+impl std::ops::Deref for RankingScoreThreshold {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The opaque cursor from which the hits are returned, an empty string starting from the first hit.
+///
+/// The cursor of the hit following the last returned one is given as `nextSearchAfter`
+/// in the search response.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchSearchAfter)]
+pub struct SearchAfter(Option<SearchCursor>);
+
+impl SearchAfter {
+    pub fn encode(cursor: &SearchCursor) -> String {
+        let bytes = serde_json::to_vec(cursor).expect("a search cursor is always serializable");
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+impl std::convert::TryFrom<String> for SearchAfter {
+    type Error = InvalidSearchSearchAfter;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            return Ok(SearchAfter(None));
+        }
+        let bytes: Option<Vec<u8>> = (0..s.len())
+            .step_by(2)
+            .map(|i| s.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+            .collect();
+        let cursor = bytes.and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        cursor.map(|cursor| SearchAfter(Some(cursor))).ok_or(InvalidSearchSearchAfter)
+    }
+}
+
+/// The boosts scaling the weight of the attributes in the `attribute` ranking rule.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(try_from(BTreeMap<String, f64>) = TryFrom::try_from -> InvalidSearchAttributeBoosts)]
+pub struct AttributeBoosts(BTreeMap<String, f64>);
+
+impl std::convert::TryFrom<BTreeMap<String, f64>> for AttributeBoosts {
+    type Error = InvalidSearchAttributeBoosts;
+
+    fn try_from(boosts: BTreeMap<String, f64>) -> Result<Self, Self::Error> {
+        if boosts.values().all(|boost| boost.is_finite() && *boost > 0.0) {
+            Ok(AttributeBoosts(boosts))
+        } else {
+            Err(InvalidSearchAttributeBoosts)
+        }
+    }
+}
+
+impl std::ops::Deref for AttributeBoosts {
+    type Target = BTreeMap<String, f64>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The synonyms and stop words used by a search in addition to, or instead of, the ones of the index.
+#[derive(Debug, Clone, Default, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchVocabulary>, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchVocabulary {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub synonyms: BTreeMap<String, Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub stop_words: BTreeSet<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub disable_index_synonyms: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub disable_index_stop_words: bool,
+}
+
 impl SearchQuery {
     pub fn is_finite_pagination(&self) -> bool {
         self.page.or(self.hits_per_page).is_some()
@@ -146,8 +514,12 @@ pub struct SearchQueryWithIndex {
     pub index_uid: IndexUid,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub q: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchStructuredQuery>)]
+    pub structured_query: Option<StructuredQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchQ>)]
     pub vector: Option<Vec<f32>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRetrieveVectors>)]
+    pub retrieve_vectors: bool,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
     #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
@@ -172,22 +544,72 @@ pub struct SearchQueryWithIndex {
     pub show_ranking_score_details: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchesPosition>, default)]
     pub show_matches_position: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchMatchesPositionUnit>)]
+    pub matches_position_unit: Option<MatchesPositionUnit>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchedTerms>, default)]
+    pub show_matched_terms: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowCorrectedQuery>, default)]
+    pub show_corrected_query: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDisjunctiveFacets>)]
+    pub disjunctive_facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchExtendedFacetStats>, default)]
+    pub extended_facet_stats: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAggregations>)]
+    pub aggregations: Option<BTreeMap<String, Vec<Aggregation>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSortFacetValuesBy>)]
+    pub sort_facet_values_by: Option<FacetValuesSort>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
     pub highlight_post_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     pub crop_marker: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTags>)]
+    pub highlight_tags: Option<BTreeMap<String, HighlightTags>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightEscapeHtml>, default)]
+    pub highlight_escape_html: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSnippet>)]
+    pub snippet: Option<SnippetQuery>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGeoReferencePoint>)]
+    pub geo_reference_point: Option<GeoReferencePoint>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchAttributeBoosts>)]
+    pub attribute_boosts: Option<AttributeBoosts>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>)]
+    pub ranking_score_threshold: Option<RankingScoreThreshold>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchTimeoutMs>)]
+    pub timeout_ms: Option<u64>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchCountOnly>, default)]
+    pub count_only: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchEarlyTermination>, default)]
+    pub early_termination: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<SearchAfter>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchGroupBy>)]
+    pub group_by: Option<GroupByQuery>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchEmbedForeignDocuments>, default)]
+    pub embed_foreign_documents: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchForeignFilters>)]
+    pub foreign_filters: Option<BTreeMap<String, Value>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPinnedDocuments>)]
+    pub pinned_documents: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHiddenDocuments>)]
+    pub hidden_documents: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchVocabulary>)]
+    pub vocabulary: Option<SearchVocabulary>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPhraseSlop>)]
+    pub phrase_slop: Option<u8>,
 }
 
 impl SearchQueryWithIndex {
@@ -195,7 +617,9 @@ impl SearchQueryWithIndex {
         let SearchQueryWithIndex {
             index_uid,
             q,
+            structured_query,
             vector,
+            retrieve_vectors,
             offset,
             limit,
             page,
@@ -207,21 +631,48 @@ impl SearchQueryWithIndex {
             show_ranking_score,
             show_ranking_score_details,
             show_matches_position,
+            matches_position_unit,
+            show_matched_terms,
+            show_corrected_query,
             filter,
             sort,
             facets,
+            disjunctive_facets,
+            extended_facet_stats,
+            aggregations,
+            sort_facet_values_by,
             highlight_pre_tag,
             highlight_post_tag,
             crop_marker,
+            highlight_tags,
+            highlight_escape_html,
+            snippet,
             matching_strategy,
             attributes_to_search_on,
             hybrid,
+            geo_reference_point,
+            attribute_boosts,
+            ranking_score_threshold,
+            timeout_ms,
+            count_only,
+            early_termination,
+            search_after,
+            group_by,
+            distinct,
+            embed_foreign_documents,
+            foreign_filters,
+            pinned_documents,
+            hidden_documents,
+            vocabulary,
+            phrase_slop,
         } = self;
         (
             index_uid,
             SearchQuery {
                 q,
+                structured_query,
                 vector,
+                retrieve_vectors,
                 offset,
                 limit,
                 page,
@@ -233,15 +684,40 @@ impl SearchQueryWithIndex {
                 show_ranking_score,
                 show_ranking_score_details,
                 show_matches_position,
+                matches_position_unit,
+                show_matched_terms,
+                show_corrected_query,
                 filter,
                 sort,
                 facets,
+                disjunctive_facets,
+                extended_facet_stats,
+                aggregations,
+                sort_facet_values_by,
                 highlight_pre_tag,
                 highlight_post_tag,
                 crop_marker,
+                highlight_tags,
+                highlight_escape_html,
+                snippet,
                 matching_strategy,
                 attributes_to_search_on,
                 hybrid,
+                geo_reference_point,
+                attribute_boosts,
+                ranking_score_threshold,
+                timeout_ms,
+                count_only,
+                early_termination,
+                search_after,
+                group_by,
+                distinct,
+                embed_foreign_documents,
+                foreign_filters,
+                pinned_documents,
+                hidden_documents,
+                vocabulary,
+                phrase_slop,
                 // do not use ..Default::default() here,
                 // rather add any missing field from `SearchQuery` to `SearchQueryWithIndex`
             },
@@ -256,6 +732,8 @@ pub enum MatchingStrategy {
     Last,
     /// All query words are mandatory
     All,
+    /// Remove query words from the most frequent to the least frequent
+    Frequency,
 }
 
 impl Default for MatchingStrategy {
@@ -269,6 +747,71 @@ impl From<MatchingStrategy> for TermsMatchingStrategy {
         match other {
             MatchingStrategy::Last => Self::Last,
             MatchingStrategy::All => Self::All,
+            MatchingStrategy::Frequency => Self::Frequency,
+        }
+    }
+}
+
+/// The unit of the bounds of the matches returned in `_matchesPosition`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum MatchesPositionUnit {
+    Bytes,
+    Chars,
+    Graphemes,
+}
+
+impl From<MatchesPositionUnit> for OffsetUnit {
+    fn from(other: MatchesPositionUnit) -> Self {
+        match other {
+            MatchesPositionUnit::Bytes => Self::Bytes,
+            MatchesPositionUnit::Chars => Self::Chars,
+            MatchesPositionUnit::Graphemes => Self::Graphemes,
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum HybridFusion {
+    /// Compare the scores of the keyword and semantic hits weighted by the semantic ratio
+    #[default]
+    Linear,
+    /// Sum the weighted scores of the hits once normalized in each search
+    Normalized,
+    /// Sum the weighted reciprocal ranks of the hits in each search
+    ReciprocalRank,
+}
+
+impl From<HybridFusion> for FusionStrategy {
+    fn from(other: HybridFusion) -> Self {
+        match other {
+            HybridFusion::Linear => Self::Linear,
+            HybridFusion::Normalized => Self::Normalized,
+            HybridFusion::ReciprocalRank => Self::ReciprocalRank,
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum HybridCandidateSelection {
+    /// Compare the query with the vectors of the filtered documents when the filter is selective,
+    /// retrieve the approximate nearest neighbors from the vector store otherwise
+    Auto,
+    /// Retrieve the approximate nearest neighbors from the vector store
+    #[default]
+    Approximate,
+    /// Compare the query with the vectors of all the filtered documents
+    Exhaustive,
+}
+
+impl From<HybridCandidateSelection> for CandidateSelection {
+    fn from(other: HybridCandidateSelection) -> Self {
+        match other {
+            HybridCandidateSelection::Auto => Self::Auto,
+            HybridCandidateSelection::Approximate => Self::Approximate,
+            HybridCandidateSelection::Exhaustive => Self::Exhaustive,
         }
     }
 }
@@ -284,6 +827,22 @@ pub enum FacetValuesSort {
     Count,
 }
 
+/// An aggregation computed over the numeric values of a filterable attribute
+/// for all the documents matching the search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum Aggregation {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    /// The values at the [`AGGREGATION_PERCENTILES`].
+    Percentiles,
+}
+
+/// The percentiles returned by the `percentiles` aggregation.
+pub const AGGREGATION_PERCENTILES: [f64; 7] = [1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0];
+
 impl From<FacetValuesSort> for OrderBy {
     fn from(val: FacetValuesSort) -> Self {
         match val {
@@ -301,12 +860,18 @@ pub struct SearchHit {
     pub formatted: Document,
     #[serde(rename = "_matchesPosition", skip_serializing_if = "Option::is_none")]
     pub matches_position: Option<MatchesPosition>,
+    #[serde(rename = "_matchedTerms", skip_serializing_if = "Option::is_none")]
+    pub matched_terms: Option<MatchedTerms>,
+    #[serde(rename = "_snippet", skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
     #[serde(rename = "_rankingScore", skip_serializing_if = "Option::is_none")]
     pub ranking_score: Option<f64>,
     #[serde(rename = "_rankingScoreDetails", skip_serializing_if = "Option::is_none")]
     pub ranking_score_details: Option<serde_json::Map<String, serde_json::Value>>,
     #[serde(rename = "_semanticScore", skip_serializing_if = "Option::is_none")]
     pub semantic_score: Option<f32>,
+    #[serde(rename = "_appliedRules", skip_serializing_if = "Vec::is_empty")]
+    pub applied_rules: Vec<String>,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -323,6 +888,34 @@ pub struct SearchResult {
     pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregations: Option<BTreeMap<String, AggregationResult>>,
+    /// Whether the search ran out of time and the hits are only partially ranked.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+    /// The cursor of the last hit, to fetch the next hits with `searchAfter`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_search_after: Option<String>,
+    /// The number of documents in the group of each hit, when the hits are grouped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_counts: Option<BTreeMap<String, u64>>,
+    /// How the best hits were reordered by the reranker of the index, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reranking: Option<Reranking>,
+    /// The query with the typos of its words corrected, when some words were unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corrected_query: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reranking {
+    pub processing_time_ms: u128,
+    /// The number of best hits reordered by the reranker.
+    pub reranked_hits: usize,
+    /// Why the hits kept the order of the search, when the reranker failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -340,12 +933,44 @@ pub enum HitsInfo {
     Pagination { hits_per_page: usize, page: usize, total_pages: usize, total_hits: usize },
     #[serde(rename_all = "camelCase")]
     OffsetLimit { limit: usize, offset: usize, estimated_total_hits: usize },
+    #[serde(rename_all = "camelCase")]
+    Count { total_hits: usize },
+}
+
+impl HitsInfo {
+    /// The number of documents matching the search, whatever the pagination.
+    pub fn total_hits(&self) -> usize {
+        match *self {
+            HitsInfo::Pagination { total_hits, .. } | HitsInfo::Count { total_hits } => total_hits,
+            HitsInfo::OffsetLimit { estimated_total_hits, .. } => estimated_total_hits,
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct FacetStats {
     pub min: f64,
     pub max: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg: Option<f64>,
+}
+
+/// The requested aggregations of an attribute, `null` when no matching document
+/// has a numeric value for the attribute.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct AggregationResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentiles: Option<Option<IndexMap<String, f64>>>,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -358,20 +983,231 @@ pub struct FacetSearchResult {
 
 /// Incorporate search rules in search query
 pub fn add_search_rules(query: &mut SearchQuery, rules: IndexSearchRules) {
-    query.filter = match (query.filter.take(), rules.filter) {
-        (None, rules_filter) => rules_filter,
-        (filter, None) => filter,
-        (Some(filter), Some(rules_filter)) => {
-            let filter = match filter {
-                Value::Array(filter) => filter,
-                filter => vec![filter],
+    query.filter = merge_filters(query.filter.take(), rules.filter);
+}
+
+/// Returns the filter matching the documents matching both filters.
+fn merge_filters(left: Option<Value>, right: Option<Value>) -> Option<Value> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(left), Some(right)) => {
+            let left = match left {
+                Value::Array(left) => left,
+                left => vec![left],
             };
-            let rules_filter = match rules_filter {
-                Value::Array(rules_filter) => rules_filter,
-                rules_filter => vec![rules_filter],
+            let right = match right {
+                Value::Array(right) => right,
+                right => vec![right],
             };
 
-            Some(Value::Array([filter, rules_filter].concat()))
+            Some(Value::Array([left, right].concat()))
+        }
+    }
+}
+
+/// An index referenced by a foreign key of the searched index.
+pub struct ForeignIndex {
+    index: Index,
+    /// The filter of the tenant token on the foreign index, if any.
+    rules_filter: Option<Value>,
+}
+
+impl ForeignIndex {
+    /// Returns the documents of the foreign index matching the filter and the tenant token.
+    fn filtered_documents(
+        &self,
+        rtxn: &RoTxn,
+        filter: Option<&Value>,
+    ) -> Result<RoaringBitmap, MeilisearchHttpError> {
+        let mut docids = self.index.documents_ids(rtxn)?;
+        for filter in [filter, self.rules_filter.as_ref()].into_iter().flatten() {
+            if let Some(filter) = parse_filter(filter)? {
+                docids &= filter.evaluate(rtxn, &self.index)?;
+            }
+        }
+        Ok(docids)
+    }
+}
+
+/// The indexes referenced by the foreign keys used by a search, by foreign key attribute.
+#[derive(Default)]
+pub struct ForeignIndexes(BTreeMap<String, ForeignIndex>);
+
+impl ForeignIndexes {
+    /// Opens the foreign indexes needed to filter the hits of the query by their foreign documents
+    /// or to embed their foreign documents.
+    pub fn new(
+        index_scheduler: &IndexScheduler,
+        auth_filter: &AuthFilter,
+        index: &Index,
+        query: &SearchQuery,
+    ) -> Result<Self, MeilisearchHttpError> {
+        if !query.embed_foreign_documents && query.foreign_filters.is_none() {
+            return Ok(Self::default());
+        }
+
+        let rtxn = index.read_txn()?;
+        let foreign_keys = index.foreign_keys(&rtxn)?;
+        let is_filtered = |attribute: &str| {
+            query.foreign_filters.as_ref().map_or(false, |filters| filters.contains_key(attribute))
+        };
+
+        if let Some(filters) = &query.foreign_filters {
+            if let Some(attribute) = filters
+                .keys()
+                .find(|attribute| foreign_keys.iter().all(|key| &key.attribute != *attribute))
+            {
+                return Err(MeilisearchHttpError::UnknownForeignKey(attribute.clone()));
+            }
+        }
+
+        let mut foreign_indexes = BTreeMap::new();
+        for ForeignKey { attribute, foreign_index_uid } in foreign_keys {
+            if !query.embed_foreign_documents && !is_filtered(&attribute) {
+                continue;
+            }
+            let Some(rules) = auth_filter.get_index_search_rules(&foreign_index_uid) else {
+                return Err(MeilisearchHttpError::UnauthorizedForeignIndex(
+                    attribute,
+                    foreign_index_uid,
+                ));
+            };
+            let index = index_scheduler.index(&foreign_index_uid)?;
+            foreign_indexes.insert(attribute, ForeignIndex { index, rules_filter: rules.filter });
+        }
+
+        Ok(Self(foreign_indexes))
+    }
+
+    /// Replaces the foreign filters of the query by a filter on the foreign keys
+    /// holding the primary key of the foreign documents matching these filters.
+    fn resolve_foreign_filters(&self, query: &mut SearchQuery) -> Result<(), MeilisearchHttpError> {
+        let Some(foreign_filters) = query.foreign_filters.take() else { return Ok(()) };
+
+        for (attribute, filter) in foreign_filters {
+            let Some(foreign) = self.0.get(&attribute) else { continue };
+            let rtxn = foreign.index.read_txn()?;
+            let docids = foreign.filtered_documents(&rtxn, Some(&filter))?;
+            if docids.len() > MAX_FOREIGN_FILTER_DOCUMENTS {
+                return Err(MeilisearchHttpError::TooManyForeignDocuments(attribute, docids.len()));
+            }
+            let external_ids: Vec<String> = if docids.is_empty() {
+                Vec::new()
+            } else {
+                foreign
+                    .index
+                    .external_id_of(&rtxn, docids)?
+                    .into_iter()
+                    .collect::<Result<_, _>>()?
+            };
+
+            // the primary keys only contain alphanumeric characters, hyphens and underscores
+            let external_ids: Vec<_> = external_ids.iter().map(|id| format!("\"{id}\"")).collect();
+            let filter = format!("\"{attribute}\" IN [{}]", external_ids.join(", "));
+            query.filter = merge_filters(query.filter.take(), Some(Value::String(filter)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the foreign documents of the hits of a search.
+struct ForeignDocumentsReader<'a> {
+    foreign_keys: Vec<ForeignKeyReader<'a>>,
+}
+
+struct ForeignKeyReader<'a> {
+    attribute: &'a str,
+    /// The id of the foreign key in the searched index.
+    field_id: Option<FieldId>,
+    index: &'a Index,
+    rtxn: RoTxn<'a>,
+    fields_ids_map: FieldsIdsMap,
+    displayed_ids: BTreeSet<FieldId>,
+    /// The foreign documents accessible with the tenant token.
+    accessible: RoaringBitmap,
+}
+
+impl<'a> ForeignDocumentsReader<'a> {
+    fn new(
+        foreign_indexes: &'a ForeignIndexes,
+        fields_ids_map: &FieldsIdsMap,
+    ) -> Result<Self, MeilisearchHttpError> {
+        let mut foreign_keys = Vec::new();
+        for (attribute, foreign) in &foreign_indexes.0 {
+            let rtxn = foreign.index.read_txn()?;
+            let foreign_fields_ids_map = foreign.index.fields_ids_map(&rtxn)?;
+            let displayed_ids = foreign
+                .index
+                .displayed_fields_ids(&rtxn)?
+                .map(|fields| fields.into_iter().collect::<BTreeSet<_>>())
+                .unwrap_or_else(|| foreign_fields_ids_map.iter().map(|(id, _)| id).collect());
+            let accessible = foreign.filtered_documents(&rtxn, None)?;
+            foreign_keys.push(ForeignKeyReader {
+                attribute,
+                field_id: fields_ids_map.id(attribute),
+                index: &foreign.index,
+                rtxn,
+                fields_ids_map: foreign_fields_ids_map,
+                displayed_ids,
+                accessible,
+            });
+        }
+        Ok(Self { foreign_keys })
+    }
+
+    /// Inserts in the `_foreign` field of the hit the foreign documents referenced by its
+    /// foreign keys, a foreign key holding an array of primary keys is replaced by the array
+    /// of the existing foreign documents.
+    fn embed(
+        &self,
+        obkv: obkv::KvReaderU16,
+        document: &mut Document,
+    ) -> Result<(), MeilisearchHttpError> {
+        let mut foreign_documents = serde_json::Map::new();
+        for foreign_key in &self.foreign_keys {
+            let value = match foreign_key.field_id.and_then(|field_id| obkv.get(field_id)) {
+                Some(value) => serde_json::from_slice(value)?,
+                None => Value::Null,
+            };
+            let foreign_document = match value {
+                Value::Array(values) => {
+                    let mut foreign_documents = Vec::new();
+                    for value in values {
+                        if let Some(document) = foreign_key.document(&value)? {
+                            foreign_documents.push(Value::Object(document));
+                        }
+                    }
+                    Value::Array(foreign_documents)
+                }
+                value => foreign_key.document(&value)?.map_or(Value::Null, Value::Object),
+            };
+            foreign_documents.insert(foreign_key.attribute.to_string(), foreign_document);
+        }
+        document.insert("_foreign".to_string(), Value::Object(foreign_documents));
+        Ok(())
+    }
+}
+
+impl ForeignKeyReader<'_> {
+    /// Returns the displayed fields of the foreign document with the given primary key.
+    fn document(&self, primary_key: &Value) -> Result<Option<Document>, MeilisearchHttpError> {
+        let external_id = match primary_key {
+            Value::String(external_id) => external_id.clone(),
+            Value::Number(external_id) => external_id.to_string(),
+            _ => return Ok(None),
+        };
+        let external_documents_ids = self.index.external_documents_ids();
+        let docid = match external_documents_ids.get(&self.rtxn, external_id)? {
+            Some(docid) if self.accessible.contains(docid) => docid,
+            _ => return Ok(None),
+        };
+        match self.index.documents(&self.rtxn, Some(docid))?.into_iter().next() {
+            Some((_, obkv)) => {
+                Ok(Some(make_document(&self.displayed_ids, &self.fields_ids_map, obkv)?))
+            }
+            None => Ok(None),
         }
     }
 }
@@ -382,7 +1218,7 @@ fn prepare_search<'t>(
     query: &'t SearchQuery,
     features: RoFeatures,
     distribution: Option<DistributionShift>,
-) -> Result<(milli::Search<'t>, bool, usize, usize), MeilisearchHttpError> {
+) -> Result<(milli::Search<'t>, bool, usize, usize, usize), MeilisearchHttpError> {
     let mut search = index.search(rtxn);
 
     if query.vector.is_some() {
@@ -393,10 +1229,35 @@ fn prepare_search<'t>(
         features.check_vector("Passing `hybrid` as a query parameter")?;
     }
 
+    if query.retrieve_vectors {
+        features.check_vector("Passing `retrieveVectors` as a query parameter")?;
+    }
+
     if query.hybrid.is_none() && query.q.is_some() && query.vector.is_some() {
         return Err(MeilisearchHttpError::MissingSearchHybrid);
     }
 
+    if query.q.is_some() && query.structured_query.is_some() {
+        return Err(MeilisearchHttpError::QueryWithStructuredQuery);
+    }
+
+    // the documents match all the `must` clauses whatever the matching strategy
+    if query.structured_query.is_some() && query.matching_strategy != MatchingStrategy::Last {
+        return Err(MeilisearchHttpError::MatchingStrategyWithStructuredQuery);
+    }
+
+    // the hits of a hybrid search are not ranked by buckets but by their global score
+    if let Some(hybrid) = &query.hybrid {
+        if *hybrid.semantic_ratio > 0.0 && *hybrid.semantic_ratio < 1.0 {
+            if query.search_after.is_some() {
+                return Err(MeilisearchHttpError::SearchAfterWithHybrid);
+            }
+            if query.group_by.is_some() {
+                return Err(MeilisearchHttpError::GroupByWithHybrid);
+            }
+        }
+    }
+
     search.distribution_shift(distribution);
 
     if let Some(ref vector) = query.vector {
@@ -410,21 +1271,65 @@ fn prepare_search<'t>(
         }
     }
 
+    // If semantic ratio is 1.0, only the vector search will impact the search results,
+    // the query is still given to exclude the documents containing its negative words
     if let Some(ref q) = query.q {
-        match &query.hybrid {
-            // If semantic ratio is 1.0, only the vector search will impact the search results,
-            // skip the query
-            Some(hybrid) if *hybrid.semantic_ratio == 1.0 => (),
-            _otherwise => {
-                search.query(q);
-            }
-        }
+        search.query(q);
+    }
+
+    if let Some(ref structured_query) = query.structured_query {
+        search.structured_query(structured_query.to_milli()?);
     }
 
     if let Some(ref searchable) = query.attributes_to_search_on {
         search.searchable_attributes(searchable);
     }
 
+    if let Some(ref attribute_boosts) = query.attribute_boosts {
+        search.attribute_boosts(attribute_boosts);
+    }
+
+    if let Some(vocabulary) = &query.vocabulary {
+        search.vocabulary(QueryVocabulary {
+            synonyms: &vocabulary.synonyms,
+            stop_words: &vocabulary.stop_words,
+            disable_index_synonyms: vocabulary.disable_index_synonyms,
+            disable_index_stop_words: vocabulary.disable_index_stop_words,
+        });
+    }
+
+    if let Some(phrase_slop) = query.phrase_slop {
+        search.phrase_slop(phrase_slop);
+    }
+
+    if let Some(ranking_score_threshold) = query.ranking_score_threshold {
+        search.ranking_score_threshold(*ranking_score_threshold);
+    }
+
+    if let Some(timeout_ms) = query.timeout_ms {
+        search.time_budget(TimeBudget::new(Duration::from_millis(timeout_ms)));
+    }
+
+    if let Some(SearchAfter(Some(cursor))) = &query.search_after {
+        search.search_after(cursor.clone());
+    }
+
+    if let Some(group_by) = &query.group_by {
+        search.group_by(&group_by.attribute, group_by.limit);
+    }
+
+    if let Some(distinct) = &query.distinct {
+        search.distinct(distinct);
+    }
+
+    if let Some(pinned_documents) = &query.pinned_documents {
+        search.pinned_documents(pinned_documents);
+    }
+
+    if let Some(hidden_documents) = &query.hidden_documents {
+        search.hidden_documents(hidden_documents);
+    }
+
     let is_finite_pagination = query.is_finite_pagination();
     search.terms_matching_strategy(query.matching_strategy.into());
 
@@ -434,12 +1339,20 @@ fn prepare_search<'t>(
         .map(|x| x as usize)
         .unwrap_or(DEFAULT_PAGINATION_MAX_TOTAL_HITS);
 
-    search.exhaustive_number_hits(is_finite_pagination);
-    search.scoring_strategy(if query.show_ranking_score || query.show_ranking_score_details {
-        ScoringStrategy::Detailed
-    } else {
-        ScoringStrategy::Skip
-    });
+    // Only counting the hits must give the exact number of hits.
+    search.exhaustive_number_hits(is_finite_pagination || query.count_only);
+    search.corrected_query(query.show_corrected_query);
+    // the next cursor is built from the scores of the last hit
+    search.scoring_strategy(
+        if query.show_ranking_score
+            || query.show_ranking_score_details
+            || query.search_after.is_some()
+        {
+            ScoringStrategy::Detailed
+        } else {
+            ScoringStrategy::Skip
+        },
+    );
 
     if query.show_ranking_score_details {
         features.check_score_details()?;
@@ -449,8 +1362,33 @@ fn prepare_search<'t>(
         search.embedder_name(embedder);
     }
 
+    if let Some(hybrid) = &query.hybrid {
+        search.candidate_selection(hybrid.candidate_selection.into());
+    }
+
+    // If semantic ratio is 0.0, only the query search will impact the search results,
+    // skip the vectors
+    if let Some(HybridQuery {
+        embedders: Some(embedders), embedded_queries, semantic_ratio, ..
+    }) = &query.hybrid
+    {
+        if **semantic_ratio > 0.0 {
+            let targets = embedded_queries
+                .iter()
+                .map(|(embedder_name, vector)| VectorTarget {
+                    embedder_name: embedder_name.clone(),
+                    vector: vector.clone(),
+                    weight: embedders.get(embedder_name).copied().unwrap_or_default(),
+                })
+                .collect();
+            search.vector_targets(targets);
+        }
+    }
+
     // compute the offset on the limit depending on the pagination mode.
-    let (offset, limit) = if is_finite_pagination {
+    let (offset, limit) = if query.count_only {
+        (0, 0)
+    } else if is_finite_pagination {
         let limit = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
         let page = query.page.unwrap_or(1);
 
@@ -485,29 +1423,85 @@ fn prepare_search<'t>(
         search.sort_criteria(sort);
     }
 
-    Ok((search, is_finite_pagination, max_total_hits, offset))
+    Ok((search, is_finite_pagination, max_total_hits, offset, limit))
 }
 
 pub fn perform_search(
     index: &Index,
-    query: SearchQuery,
+    mut query: SearchQuery,
     features: RoFeatures,
     distribution: Option<DistributionShift>,
+    foreign_indexes: &ForeignIndexes,
 ) -> Result<SearchResult, MeilisearchHttpError> {
     let before_search = Instant::now();
     let rtxn = index.read_txn()?;
 
-    let (search, is_finite_pagination, max_total_hits, offset) =
+    foreign_indexes.resolve_foreign_filters(&mut query)?;
+
+    let (mut search, is_finite_pagination, max_total_hits, offset, limit) =
         prepare_search(index, &rtxn, &query, features, distribution)?;
 
-    let milli::SearchResult { documents_ids, matching_words, candidates, document_scores, .. } =
-        match &query.hybrid {
-            Some(hybrid) => match *hybrid.semantic_ratio {
-                ratio if ratio == 0.0 || ratio == 1.0 => search.execute()?,
-                ratio => search.execute_hybrid(ratio)?,
-            },
-            None => search.execute()?,
-        };
+    // the facets and aggregations are computed on the candidates, they must then be exhaustive
+    search.early_termination(
+        query.early_termination
+            && !is_finite_pagination
+            && !query.count_only
+            && query.facets.is_none()
+            && query.aggregations.is_none(),
+    );
+
+    // the reranker doesn't apply when the hits must keep the order requested by the search
+    let reranker = match (index.reranker(&rtxn)?, query.q.as_deref()) {
+        (Some(reranker), Some(q))
+            if !q.trim().is_empty()
+                && limit > 0
+                && query.sort.is_none()
+                && query.search_after.is_none()
+                && query.group_by.is_none()
+                && query.pinned_documents.is_none() =>
+        {
+            // the best hits are reranked before the page is selected
+            search.offset(0);
+            search.limit(max(reranker.top_n, offset + limit));
+            Some((reranker, q))
+        }
+        _ => None,
+    };
+
+    let milli::SearchResult {
+        documents_ids,
+        matching_words,
+        candidates,
+        document_scores,
+        degraded,
+        group_counts,
+        mut applied_query_rules,
+        corrected_query,
+    } = match &query.hybrid {
+        Some(hybrid) => match *hybrid.semantic_ratio {
+            ratio if ratio == 0.0 || ratio == 1.0 => search.execute()?,
+            ratio => search.execute_hybrid(ratio, hybrid.fusion.into())?,
+        },
+        None => search.execute()?,
+    };
+
+    let (documents_ids, document_scores, reranking) = match reranker {
+        Some((reranker, q)) => {
+            let (documents_ids, document_scores, reranking) =
+                rerank_hits(index, &rtxn, reranker, q, documents_ids, document_scores)?;
+            let documents_ids = documents_ids.into_iter().skip(offset).take(limit).collect();
+            let document_scores = document_scores.into_iter().skip(offset).take(limit).collect();
+            (documents_ids, document_scores, Some(reranking))
+        }
+        None => (documents_ids, document_scores, None),
+    };
+
+    let next_search_after = match (&query.search_after, documents_ids.last()) {
+        (Some(_), Some(&docid)) => document_scores
+            .last()
+            .map(|scores| SearchAfter::encode(&SearchCursor::new(scores, docid))),
+        _ => None,
+    };
 
     let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
 
@@ -544,10 +1538,32 @@ pub fn perform_search(
         .cloned()
         .collect();
 
+    // The nested attributes to retrieve are selected by their path in the displayed document,
+    // whether or not they have their own field id, e.g. `address.city` when `address` is displayed
+    let attributes_to_retrieve: Vec<_> = match &query.attributes_to_retrieve {
+        Some(attrs) if !attrs.contains("*") => attrs.iter().map(String::as_str).collect(),
+        _ => to_retrieve_ids
+            .iter()
+            .map(|&fid| fields_ids_map.name(fid).expect("Missing field name"))
+            .collect(),
+    };
+
     let attr_to_highlight = query.attributes_to_highlight.unwrap_or_default();
 
     let attr_to_crop = query.attributes_to_crop.unwrap_or_default();
 
+    // The attributes without their own highlight tags use the ones of the query
+    let highlight_tags: BTreeMap<_, _> = query
+        .highlight_tags
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(pattern, HighlightTags { pre_tag, post_tag })| {
+            let pre_tag = pre_tag.unwrap_or_else(|| query.highlight_pre_tag.clone());
+            let post_tag = post_tag.unwrap_or_else(|| query.highlight_post_tag.clone());
+            (pattern, (pre_tag, post_tag))
+        })
+        .collect();
+
     // Attributes in `formatted_options` correspond to the attributes that will be in `_formatted`
     // These attributes are:
     // - the attributes asked to be highlighted or cropped (with `attributesToCrop` or `attributesToHighlight`)
@@ -588,34 +1604,64 @@ pub fn perform_search(
     formatter_builder.crop_marker(query.crop_marker);
     formatter_builder.highlight_prefix(query.highlight_pre_tag);
     formatter_builder.highlight_suffix(query.highlight_post_tag);
+    formatter_builder.split_identifiers(index.split_identifiers(&rtxn)?);
+    formatter_builder.escape_html(query.highlight_escape_html);
+
+    let foreign_documents = if query.embed_foreign_documents {
+        Some(ForeignDocumentsReader::new(foreign_indexes, &fields_ids_map)?)
+    } else {
+        None
+    };
+
+    // the stored embeddings are only retrieved when `_vectors` is a displayed attribute
+    let retrieve_vectors = query.retrieve_vectors
+        && index.displayed_fields(&rtxn)?.map_or(true, |fields| fields.contains(&"_vectors"));
 
     let mut documents = Vec::new();
     let documents_iter = index.documents(&rtxn, documents_ids)?;
 
-    for ((_id, obkv), score) in documents_iter.into_iter().zip(document_scores.into_iter()) {
+    for ((id, obkv), score) in documents_iter.into_iter().zip(document_scores.into_iter()) {
         // First generate a document with all the displayed fields
         let displayed_document = make_document(&displayed_ids, &fields_ids_map, obkv)?;
 
         // select the attributes to retrieve
-        let attributes_to_retrieve = to_retrieve_ids
-            .iter()
-            .map(|&fid| fields_ids_map.name(fid).expect("Missing field name"));
-        let mut document =
-            permissive_json_pointer::select_values(&displayed_document, attributes_to_retrieve);
+        let mut document = permissive_json_pointer::select_values(
+            &displayed_document,
+            attributes_to_retrieve.iter().copied(),
+        );
 
-        let (matches_position, formatted) = format_fields(
+        let (matches_position, matched_terms, formatted) = format_fields(
             &displayed_document,
             &fields_ids_map,
             &formatter_builder,
             &formatted_options,
+            &highlight_tags,
             query.show_matches_position,
+            query.matches_position_unit.map(OffsetUnit::from),
+            query.show_matched_terms,
             &displayed_ids,
         )?;
 
-        if let Some(sort) = query.sort.as_ref() {
+        let snippet = query.snippet.as_ref().and_then(|snippet| {
+            compute_snippet(&displayed_document, snippet, &formatter_builder, &highlight_tags)
+        });
+
+        if let Some(geo_reference_point) = query.geo_reference_point {
+            insert_geo_distance_to_point(&geo_reference_point, &mut document);
+        } else if let Some(sort) = query.sort.as_ref() {
             insert_geo_distance(sort, &mut document);
         }
 
+        if let Some(foreign_documents) = &foreign_documents {
+            foreign_documents.embed(obkv, &mut document)?;
+        }
+
+        // the stored embeddings replace the vectors provided in the document
+        if retrieve_vectors {
+            let embeddings = index.embeddings(&rtxn, id)?;
+            document.insert("_vectors".to_string(), json!(embeddings));
+        }
+
         let mut semantic_score = None;
         for details in &score {
             if let ScoreDetails::Vector(score_details::Vector {
@@ -637,15 +1683,20 @@ pub fn perform_search(
             document,
             formatted,
             matches_position,
+            matched_terms,
+            snippet,
             ranking_score_details,
             ranking_score,
             semantic_score,
+            applied_rules: applied_query_rules.remove(&id).unwrap_or_default(),
         };
         documents.push(hit);
     }
 
     let number_of_hits = min(candidates.len() as usize, max_total_hits);
-    let hits_info = if is_finite_pagination {
+    let hits_info = if query.count_only {
+        HitsInfo::Count { total_hits: number_of_hits }
+    } else if is_finite_pagination {
         let hits_per_page = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
         // If hit_per_page is 0, then pages can't be computed and so we respond 0.
         let total_pages = (number_of_hits + hits_per_page.saturating_sub(1))
@@ -662,50 +1713,46 @@ pub fn perform_search(
         HitsInfo::OffsetLimit { limit: query.limit, offset, estimated_total_hits: number_of_hits }
     };
 
-    let (facet_distribution, facet_stats) = match query.facets {
+    let aggregations = match query.aggregations {
+        Some(ref aggregations) => {
+            Some(compute_aggregations(index, &rtxn, aggregations, &candidates)?)
+        }
+        None => None,
+    };
+
+    let disjunctive_candidates = match query.disjunctive_facets {
+        Some(ref disjunctive_facets) => {
+            let has_vector_search = query.hybrid.is_some() || query.vector.is_some();
+            Some(search.execute_for_disjunctive_facets(
+                disjunctive_facets,
+                has_vector_search,
+                &candidates,
+            )?)
+        }
+        None => None,
+    };
+
+    let (mut facet_distribution, mut facet_stats) = match query.facets {
         Some(ref fields) => {
-            let mut facet_distribution = index.facets_distribution(&rtxn);
-
-            let max_values_by_facet = index
-                .max_values_per_facet(&rtxn)
-                .map_err(milli::Error::from)?
-                .map(|x| x as usize)
-                .unwrap_or(DEFAULT_VALUES_PER_FACET);
-            facet_distribution.max_values_per_facet(max_values_by_facet);
-
-            let sort_facet_values_by =
-                index.sort_facet_values_by(&rtxn).map_err(milli::Error::from)?;
-            let default_sort_facet_values_by =
-                sort_facet_values_by.get("*").copied().unwrap_or_default();
-
-            if fields.iter().all(|f| f != "*") {
-                let fields: Vec<_> = fields
-                    .iter()
-                    .map(|n| {
-                        (
-                            n,
-                            sort_facet_values_by
-                                .get(n)
-                                .copied()
-                                .unwrap_or(default_sort_facet_values_by),
-                        )
-                    })
-                    .collect();
-                facet_distribution.facets(fields);
-            }
-            let distribution = facet_distribution
-                .candidates(candidates)
-                .default_order_by(default_sort_facet_values_by)
-                .execute()?;
-            let stats = facet_distribution.compute_stats()?;
+            let (distribution, stats) =
+                compute_facet_distribution_and_stats(index, &rtxn, &query, fields, candidates)?;
             (Some(distribution), Some(stats))
         }
         None => (None, None),
     };
 
-    let facet_stats = facet_stats.map(|stats| {
-        stats.into_iter().map(|(k, (min, max))| (k, FacetStats { min, max })).collect()
-    });
+    for (facet, candidates) in disjunctive_candidates.into_iter().flatten() {
+        let (distribution, stats) =
+            compute_facet_distribution_and_stats(index, &rtxn, &query, &[facet], candidates)
+                .map_err(|error| match error {
+                    MeilisearchHttpError::Milli(milli::Error::UserError(
+                        error @ milli::UserError::InvalidFacetsDistribution { .. },
+                    )) => MeilisearchHttpError::InvalidDisjunctiveFacets(error),
+                    error => error,
+                })?;
+        facet_distribution.get_or_insert_with(BTreeMap::new).extend(distribution);
+        facet_stats.get_or_insert_with(BTreeMap::new).extend(stats);
+    }
 
     let result = SearchResult {
         hits: documents,
@@ -715,10 +1762,170 @@ pub fn perform_search(
         processing_time_ms: before_search.elapsed().as_millis(),
         facet_distribution,
         facet_stats,
+        aggregations,
+        degraded,
+        next_search_after,
+        group_counts: query.group_by.is_some().then_some(group_counts),
+        reranking,
+        corrected_query,
     };
     Ok(result)
 }
 
+/// Reorders the best hits with the reranker of the index.
+///
+/// The hits keep the order of the search when the reranker is down or returns an invalid
+/// response, the reason being returned as the fallback of the reranking.
+fn rerank_hits(
+    index: &Index,
+    rtxn: &RoTxn,
+    options: RerankerOptions,
+    query: &str,
+    mut documents_ids: Vec<DocumentId>,
+    mut document_scores: Vec<Vec<ScoreDetails>>,
+) -> Result<(Vec<DocumentId>, Vec<Vec<ScoreDetails>>, Reranking), MeilisearchHttpError> {
+    let before_reranking = Instant::now();
+    let top_n = min(options.top_n, documents_ids.len());
+    let fields_ids_map = index.fields_ids_map(rtxn)?;
+    let documents = index.documents(rtxn, documents_ids[..top_n].iter().copied())?;
+
+    let ranking = Reranker::new(options).and_then(|reranker| {
+        let texts = documents
+            .into_iter()
+            .map(|(_id, obkv)| reranker.render(obkv, &fields_ids_map))
+            .collect::<Result<Vec<_>, _>>()?;
+        tokio::runtime::Handle::current().block_on(reranker.rerank(query, &texts))
+    });
+
+    let (reranked_hits, fallback) = match ranking {
+        Ok(ranking) => {
+            // the hits missing from the response keep their order after the reranked ones
+            let mut seen = vec![false; top_n];
+            let mut order: Vec<_> = ranking
+                .into_iter()
+                .map(|(position, _score)| position)
+                .filter(|&position| !std::mem::replace(&mut seen[position], true))
+                .collect();
+            order.extend((0..top_n).filter(|&position| !seen[position]));
+
+            let ids: Vec<_> = order.iter().map(|&position| documents_ids[position]).collect();
+            documents_ids.splice(..top_n, ids);
+            let mut scores: Vec<_> = document_scores.drain(..top_n).map(Some).collect();
+            let scores: Vec<_> =
+                order.iter().map(|&position| scores[position].take().unwrap()).collect();
+            document_scores.splice(..0, scores);
+            (top_n, None)
+        }
+        Err(error) => {
+            log::warn!("The reranker failed, falling back on the ranking of the search: {error}");
+            (0, Some(error.to_string()))
+        }
+    };
+
+    let reranking = Reranking {
+        processing_time_ms: before_reranking.elapsed().as_millis(),
+        reranked_hits,
+        fallback,
+    };
+    Ok((documents_ids, document_scores, reranking))
+}
+
+fn compute_facet_distribution_and_stats(
+    index: &Index,
+    rtxn: &RoTxn,
+    query: &SearchQuery,
+    fields: &[String],
+    candidates: RoaringBitmap,
+) -> Result<
+    (BTreeMap<String, IndexMap<String, u64>>, BTreeMap<String, FacetStats>),
+    MeilisearchHttpError,
+> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+
+    let max_values_by_facet = index
+        .max_values_per_facet(rtxn)
+        .map_err(milli::Error::from)?
+        .map(|x| x as usize)
+        .unwrap_or(DEFAULT_VALUES_PER_FACET);
+    facet_distribution.max_values_per_facet(max_values_by_facet);
+
+    let mut sort_facet_values_by = index.sort_facet_values_by(rtxn).map_err(milli::Error::from)?;
+    // the order requested by the query applies to all the facets
+    if let Some(order_by) = query.sort_facet_values_by {
+        sort_facet_values_by.clear();
+        sort_facet_values_by.insert("*".to_string(), order_by.into());
+    }
+    let default_sort_facet_values_by = sort_facet_values_by.get("*").copied().unwrap_or_default();
+
+    if fields.iter().all(|f| f != "*") {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|n| {
+                (n, sort_facet_values_by.get(n).copied().unwrap_or(default_sort_facet_values_by))
+            })
+            .collect();
+        facet_distribution.facets(fields);
+    }
+    let distribution = facet_distribution
+        .candidates(candidates)
+        .default_order_by(default_sort_facet_values_by)
+        .execute()?;
+    let stats = facet_distribution.compute_stats()?;
+    let mut sums = if query.extended_facet_stats {
+        facet_distribution.compute_sum_and_average()?
+    } else {
+        BTreeMap::new()
+    };
+
+    let stats = stats
+        .into_iter()
+        .map(|(k, (min, max))| {
+            let (sum, avg) = sums.remove(&k).unzip();
+            (k, FacetStats { min, max, sum, avg })
+        })
+        .collect();
+
+    Ok((distribution, stats))
+}
+
+fn compute_aggregations(
+    index: &Index,
+    rtxn: &RoTxn,
+    aggregations: &BTreeMap<String, Vec<Aggregation>>,
+    candidates: &RoaringBitmap,
+) -> Result<BTreeMap<String, AggregationResult>, MeilisearchHttpError> {
+    let mut results = BTreeMap::new();
+    for (field, requested) in aggregations {
+        let percentiles: &[f64] = if requested.contains(&Aggregation::Percentiles) {
+            &AGGREGATION_PERCENTILES
+        } else {
+            &[]
+        };
+        let aggregation = milli::aggregate_numbers(index, rtxn, field, candidates, percentiles)?;
+
+        let mut result = AggregationResult::default();
+        for requested in requested {
+            let aggregation = aggregation.as_ref();
+            match requested {
+                Aggregation::Sum => result.sum = Some(aggregation.map(|a| a.sum)),
+                Aggregation::Avg => result.avg = Some(aggregation.map(|a| a.avg())),
+                Aggregation::Min => result.min = Some(aggregation.map(|a| a.min)),
+                Aggregation::Max => result.max = Some(aggregation.map(|a| a.max)),
+                Aggregation::Percentiles => {
+                    result.percentiles = Some(aggregation.map(|a| {
+                        a.percentiles
+                            .iter()
+                            .map(|(percentile, value)| (percentile.to_string(), *value))
+                            .collect()
+                    }))
+                }
+            }
+        }
+        results.insert(field.clone(), result);
+    }
+    Ok(results)
+}
+
 pub fn perform_facet_search(
     index: &Index,
     search_query: SearchQuery,
@@ -729,7 +1936,7 @@ pub fn perform_facet_search(
     let before_search = Instant::now();
     let rtxn = index.read_txn()?;
 
-    let (search, _, _, _) = prepare_search(index, &rtxn, &search_query, features, None)?;
+    let (search, _, _, _, _) = prepare_search(index, &rtxn, &search_query, features, None)?;
     let mut facet_search =
         SearchForFacetValues::new(facet_name, search, search_query.hybrid.is_some());
     if let Some(facet_query) = &facet_query {
@@ -751,11 +1958,18 @@ fn insert_geo_distance(sorts: &[String], document: &mut Document) {
     if let Some(capture_group) = sorts.iter().find_map(|sort| GEO_REGEX.captures(sort)) {
         // TODO: TAMO: milli encountered an internal error, what do we want to do?
         let base = [capture_group[1].parse().unwrap(), capture_group[2].parse().unwrap()];
-        let geo_point = &document.get("_geo").unwrap_or(&json!(null));
-        if let Some((lat, lng)) = geo_point["lat"].as_f64().zip(geo_point["lng"].as_f64()) {
-            let distance = milli::distance_between_two_points(&base, &[lat, lng]);
-            document.insert("_geoDistance".to_string(), json!(distance.round() as usize));
-        }
+        insert_geo_distance_to_point(&base, document);
+    }
+}
+
+/// Inserts the distance in meters between the point and the `_geo` field of the document.
+fn insert_geo_distance_to_point(base: &[f64; 2], document: &mut Document) {
+    // the coordinates can also be stored as strings
+    let as_f64 = |value: &Value| value.as_f64().or_else(|| value.as_str()?.parse().ok());
+    let geo_point = &document.get("_geo").unwrap_or(&json!(null));
+    if let Some((lat, lng)) = as_f64(&geo_point["lat"]).zip(as_f64(&geo_point["lng"])) {
+        let distance = milli::distance_between_two_points(base, &[lat, lng]);
+        document.insert("_geoDistance".to_string(), json!(distance.round() as usize));
     }
 }
 
@@ -769,11 +1983,18 @@ fn compute_formatted_options(
 ) -> BTreeMap<FieldId, FormatOptions> {
     let mut formatted_options = BTreeMap::new();
 
+    // the coordinates of the `_geojson` field are neither highlighted nor cropped
+    let displayed_ids: BTreeSet<FieldId> = displayed_ids
+        .iter()
+        .copied()
+        .filter(|id| fields_ids_map.name(*id).map_or(true, |name| !is_geojson_field(name)))
+        .collect();
+
     add_highlight_to_formatted_options(
         &mut formatted_options,
         attr_to_highlight,
         fields_ids_map,
-        displayed_ids,
+        &displayed_ids,
     );
 
     add_crop_to_formatted_options(
@@ -781,7 +2002,7 @@ fn compute_formatted_options(
         attr_to_crop,
         query_crop_length,
         fields_ids_map,
-        displayed_ids,
+        &displayed_ids,
     );
 
     // Should not return `_formatted` if no valid attributes to highlight/crop
@@ -886,15 +2107,20 @@ fn make_document(
     Ok(document)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn format_fields<'a>(
     document: &Document,
     field_ids_map: &FieldsIdsMap,
     builder: &'a MatcherBuilder<'a>,
     formatted_options: &BTreeMap<FieldId, FormatOptions>,
+    highlight_tags: &'a BTreeMap<String, (String, String)>,
     compute_matches: bool,
+    matches_unit: Option<OffsetUnit>,
+    compute_terms: bool,
     displayable_ids: &BTreeSet<FieldId>,
-) -> Result<(Option<MatchesPosition>, Document), MeilisearchHttpError> {
+) -> Result<(Option<MatchesPosition>, Option<MatchedTerms>, Document), MeilisearchHttpError> {
     let mut matches_position = compute_matches.then(BTreeMap::new);
+    let mut matched_terms = compute_terms.then(MatchedTerms::new);
     let mut document = document.clone();
 
     // select the attributes to retrieve
@@ -913,15 +2139,33 @@ fn format_fields<'a>(
             })
             .map(|(_, option)| *option)
             .reduce(|acc, option| acc.merge(option));
+        let tags = attribute_highlight_tags(highlight_tags, key);
         let mut infos = Vec::new();
-
-        *value = format_value(std::mem::take(value), builder, format, &mut infos, compute_matches);
+        let mut terms = Vec::new();
+
+        *value = format_value(
+            std::mem::take(value),
+            builder,
+            format,
+            tags,
+            &mut infos,
+            compute_matches,
+            matches_unit,
+            &mut terms,
+            compute_terms,
+        );
 
         if let Some(matches) = matches_position.as_mut() {
             if !infos.is_empty() {
                 matches.insert(key.to_owned(), infos);
             }
         }
+
+        if let Some(matched_terms) = matched_terms.as_mut() {
+            for term in terms {
+                matched_terms.entry(term).or_default().insert(key.to_owned());
+            }
+        }
     });
 
     let selectors = formatted_options
@@ -931,23 +2175,108 @@ fn format_fields<'a>(
         .map(|&fid| field_ids_map.name(fid).unwrap());
     let document = permissive_json_pointer::select_values(&document, selectors);
 
-    Ok((matches_position, document))
+    Ok((matches_position, matched_terms, document))
+}
+
+/// Returns the highlight tags of the attribute, given by the most specific pattern matching it.
+fn attribute_highlight_tags<'a>(
+    highlight_tags: &'a BTreeMap<String, (String, String)>,
+    attribute: &str,
+) -> Option<(&'a str, &'a str)> {
+    highlight_tags
+        .iter()
+        .filter(|(pattern, _tags)| milli::match_attribute_pattern(pattern, attribute))
+        .max_by_key(|(pattern, _tags)| pattern.len())
+        .map(|(_, (pre_tag, post_tag))| (pre_tag.as_str(), post_tag.as_str()))
 }
 
+/// Builds a single snippet out of the best matching texts of the snippet attributes.
+///
+/// The texts matching the most words are picked first, each one being cropped to the
+/// characters left in the budget, and the picked texts are joined in the order of the attributes.
+fn compute_snippet<'a>(
+    document: &Document,
+    snippet: &SnippetQuery,
+    builder: &'a MatcherBuilder<'a>,
+    highlight_tags: &'a BTreeMap<String, (String, String)>,
+) -> Option<String> {
+    fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+        match value {
+            Value::String(string) => strings.push(string.clone()),
+            Value::Array(values) => values.iter().for_each(|value| collect_strings(value, strings)),
+            Value::Object(object) => {
+                object.values().for_each(|value| collect_strings(value, strings))
+            }
+            _ => (),
+        }
+    }
+
+    let mut texts = Vec::new();
+    for attribute in &snippet.attributes {
+        let tags = attribute_highlight_tags(highlight_tags, attribute);
+        let values = permissive_json_pointer::select_values(document, [attribute.as_str()]);
+        let mut strings = Vec::new();
+        values.values().for_each(|value| collect_strings(value, &mut strings));
+        texts.extend(strings.into_iter().map(|string| (string, tags)));
+    }
+
+    let mut matchers: Vec<_> = texts
+        .iter()
+        .map(|(text, tags)| {
+            let mut matcher = builder.build(text);
+            if let Some((pre_tag, post_tag)) = *tags {
+                matcher.highlight_tags(pre_tag, post_tag);
+            }
+            let matched_words = matcher.matched_words().len();
+            (matcher, matched_words)
+        })
+        .collect();
+
+    // the sort is stable, the first attributes are picked first among the texts matching as many words.
+    let mut order: Vec<_> = (0..matchers.len()).filter(|&i| matchers[i].1 > 0).collect();
+    order.sort_by_key(|&i| Reverse(matchers[i].1));
+
+    let mut remaining_chars = snippet.length;
+    let mut fragments = BTreeMap::new();
+    for i in order {
+        let (matcher, _) = &mut matchers[i];
+        let Some((crop_size, chars)) = matcher.crop_size_within(remaining_chars) else { continue };
+        remaining_chars -= chars;
+        let fragment = matcher.format(FormatOptions { highlight: true, crop: Some(crop_size) });
+        fragments.insert(i, fragment.into_owned());
+    }
+
+    (!fragments.is_empty()).then(|| fragments.into_values().collect::<Vec<_>>().join(" "))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn format_value<'a>(
     value: Value,
     builder: &'a MatcherBuilder<'a>,
     format_options: Option<FormatOptions>,
+    tags: Option<(&'a str, &'a str)>,
     infos: &mut Vec<MatchBounds>,
     compute_matches: bool,
+    matches_unit: Option<OffsetUnit>,
+    terms: &mut Vec<String>,
+    compute_terms: bool,
 ) -> Value {
     match value {
         Value::String(old_string) => {
             let mut matcher = builder.build(&old_string);
+            if let Some((pre_tag, post_tag)) = tags {
+                matcher.highlight_tags(pre_tag, post_tag);
+            }
             if compute_matches {
-                let matches = matcher.matches();
+                let matches = match matches_unit {
+                    Some(unit) => matcher.matches_in(unit),
+                    None => matcher.matches(),
+                };
                 infos.extend_from_slice(&matches[..]);
             }
+            if compute_terms {
+                terms.extend(matcher.matched_words());
+            }
 
             match format_options {
                 Some(format_options) => {
@@ -968,8 +2297,12 @@ fn format_value<'a>(
                             highlight: format_options.highlight,
                             crop: None,
                         }),
+                        tags,
                         infos,
                         compute_matches,
+                        matches_unit,
+                        terms,
+                        compute_terms,
                     )
                 })
                 .collect(),
@@ -987,8 +2320,12 @@ fn format_value<'a>(
                                 highlight: format_options.highlight,
                                 crop: None,
                             }),
+                            tags,
                             infos,
                             compute_matches,
+                            matches_unit,
+                            terms,
+                            compute_terms,
                         ),
                     )
                 })
@@ -998,10 +2335,19 @@ fn format_value<'a>(
             let s = number.to_string();
 
             let mut matcher = builder.build(&s);
+            if let Some((pre_tag, post_tag)) = tags {
+                matcher.highlight_tags(pre_tag, post_tag);
+            }
             if compute_matches {
-                let matches = matcher.matches();
+                let matches = match matches_unit {
+                    Some(unit) => matcher.matches_in(unit),
+                    None => matcher.matches(),
+                };
                 infos.extend_from_slice(&matches[..]);
             }
+            if compute_terms {
+                terms.extend(matcher.matched_words());
+            }
 
             match format_options {
                 Some(format_options) => {
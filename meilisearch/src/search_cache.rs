@@ -0,0 +1,74 @@
+//! An in-process cache of the results of the most recent searches, to answer the hot
+//! repeated searches, such as the ones of the home pages, without searching again.
+
+use std::sync::Mutex;
+
+use linked_hash_map::LinkedHashMap;
+
+use crate::search::{SearchQuery, SearchResult};
+
+/// Identifies the results of a search on a version of an index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchCacheKey {
+    index_uid: String,
+    /// The write epoch of the index, changing each time a task writes in the index.
+    write_epoch: u64,
+    /// The parameters of the search, including the filter of the tenant token.
+    query: String,
+}
+
+/// Keeps the results of the most recently used searches.
+pub struct SearchCache {
+    capacity: usize,
+    /// The results ordered from the least to the most recently used.
+    results: Mutex<LinkedHashMap<SearchCacheKey, SearchResult>>,
+}
+
+impl SearchCache {
+    /// Creates a cache of up to `capacity` search results, disabled when the capacity is 0.
+    pub fn new(capacity: usize) -> Self {
+        SearchCache { capacity, results: Mutex::new(LinkedHashMap::new()) }
+    }
+
+    /// Returns the key of the results of the search on the index,
+    /// or `None` if the cache is disabled or the results can't be cached.
+    pub fn key(
+        &self,
+        index_uid: &str,
+        write_epoch: u64,
+        query: &SearchQuery,
+    ) -> Option<SearchCacheKey> {
+        // the foreign filters and documents depend on the versions of the other indexes
+        if self.capacity == 0 || query.foreign_filters.is_some() || query.embed_foreign_documents {
+            return None;
+        }
+
+        // the order of the iteration of a hash set depends on its random state
+        let mut attributes_to_highlight: Vec<_> =
+            query.attributes_to_highlight.iter().flatten().collect();
+        attributes_to_highlight.sort_unstable();
+        let query = SearchQuery { attributes_to_highlight: None, ..query.clone() };
+
+        Some(SearchCacheKey {
+            index_uid: index_uid.to_string(),
+            write_epoch,
+            query: format!("{query:?} {attributes_to_highlight:?}"),
+        })
+    }
+
+    pub fn get(&self, key: &SearchCacheKey) -> Option<SearchResult> {
+        self.results.lock().unwrap().get_refresh(key).cloned()
+    }
+
+    /// Caches the results of a search, evicting the least recently used results if the cache is full.
+    ///
+    /// The results of the previous write epochs of an index are never used again,
+    /// they end up being evicted.
+    pub fn insert(&self, key: SearchCacheKey, result: SearchResult) {
+        let mut results = self.results.lock().unwrap();
+        if results.len() >= self.capacity && !results.contains_key(&key) {
+            results.pop_front();
+        }
+        results.insert(key, result);
+    }
+}
@@ -0,0 +1,122 @@
+//! Limits the number of searches processed at the same time and the number of searches
+//! waiting to be processed, so that a burst of expensive searches is rejected early
+//! instead of exhausting the memory and the file descriptors of the instance.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::error::MeilisearchHttpError;
+use crate::search::SearchQuery;
+
+pub struct SearchQueue {
+    permits: Arc<Semaphore>,
+    /// The number of searches waiting for a permit.
+    pending: AtomicUsize,
+    capacity: usize,
+    deadline: Option<Duration>,
+}
+
+/// Allows a search to be processed, the next pending search is processed when it is dropped.
+pub struct SearchPermit {
+    _permit: OwnedSemaphorePermit,
+    deadline: Option<Instant>,
+}
+
+impl SearchPermit {
+    /// Limits the time budget of the search to the time left before the deadline, if any.
+    ///
+    /// Once the time budget is exceeded, the search returns the documents ranked so far.
+    pub fn apply_deadline(&self, query: &mut SearchQuery) {
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now()).as_millis() as u64;
+            query.timeout_ms = Some(query.timeout_ms.map_or(remaining, |ms| ms.min(remaining)));
+        }
+    }
+}
+
+/// Decrements the number of pending searches, even when the request is cancelled while waiting.
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl SearchQueue {
+    /// Creates a queue processing up to `parallelism` searches at the same time, without limit
+    /// when 0, and keeping up to `capacity` searches waiting for them.
+    ///
+    /// The searches can't wait and be processed for longer than the `deadline`, if any.
+    pub fn new(parallelism: usize, capacity: usize, deadline: Option<Duration>) -> Self {
+        let parallelism = if parallelism == 0 { Semaphore::MAX_PERMITS } else { parallelism };
+        SearchQueue {
+            permits: Arc::new(Semaphore::new(parallelism)),
+            pending: AtomicUsize::new(0),
+            capacity,
+            deadline,
+        }
+    }
+
+    /// Waits for a search to be allowed to be processed.
+    ///
+    /// Returns an error if too many searches are already waiting,
+    /// or if the deadline is exceeded before a search finishes.
+    pub async fn try_get_search_permit(&self) -> Result<SearchPermit, MeilisearchHttpError> {
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            return Ok(SearchPermit { _permit: permit, deadline });
+        }
+
+        if self.pending.fetch_add(1, Ordering::Relaxed) >= self.capacity {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+            return Err(MeilisearchHttpError::TooManySearchRequests(self.capacity));
+        }
+        let _guard = PendingGuard(&self.pending);
+
+        let permit = self.permits.clone().acquire_owned();
+        let permit = match self.deadline {
+            Some(timeout) => tokio::time::timeout(timeout, permit).await.map_err(|_| {
+                MeilisearchHttpError::SearchDeadlineExceeded(timeout.as_millis() as u64)
+            })?,
+            None => permit.await,
+        };
+
+        // the semaphore is never closed
+        let permit = permit.expect("the search queue semaphore was closed");
+        Ok(SearchPermit { _permit: permit, deadline })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::SearchQueue;
+    use crate::error::MeilisearchHttpError;
+
+    #[actix_rt::test]
+    async fn full_queue_rejects_the_searches() {
+        let queue = SearchQueue::new(1, 0, None);
+
+        let permit = queue.try_get_search_permit().await.unwrap();
+        let error = queue.try_get_search_permit().await.err().unwrap();
+        assert!(matches!(error, MeilisearchHttpError::TooManySearchRequests(0)));
+
+        drop(permit);
+        assert!(queue.try_get_search_permit().await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn pending_search_exceeds_its_deadline() {
+        let queue = SearchQueue::new(1, 10, Some(Duration::from_millis(10)));
+
+        let _permit = queue.try_get_search_permit().await.unwrap();
+        let error = queue.try_get_search_permit().await.err().unwrap();
+        assert!(matches!(error, MeilisearchHttpError::SearchDeadlineExceeded(10)));
+    }
+}
@@ -589,3 +589,54 @@ async fn error_access_modified_token() {
     assert_eq!(response, INVALID_RESPONSE.clone());
     assert_eq!(code, 403);
 }
+
+#[actix_rt::test]
+async fn similar_documents_of_a_filtered_out_document() {
+    let mut server = Server::new_auth().await;
+    server.use_admin_key("MASTER_KEY").await;
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    assert_eq!(code, 200);
+    let index = server.index("sales");
+    let (response, _) = index
+        .update_settings(json!({
+            "filterableAttributes": ["color"],
+            "embedders": {"default": {"source": {"userProvided": {"dimensions": 2}}}},
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+    let documents = json!([
+        { "id": "1", "color": "blue", "_vectors": {"default": [1.0, 3.0]} },
+        { "id": "2", "color": "blue", "_vectors": {"default": [1.0, 2.0]} },
+        { "id": "3", "color": "red", "_vectors": {"default": [2.0, 3.0]} },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+    drop(index);
+
+    let (response, code) = server
+        .add_api_key(json!({
+            "indexes": ["sales"],
+            "actions": ["search"],
+            "expiresAt": (OffsetDateTime::now_utc() + Duration::days(1)).format(&Rfc3339).unwrap()
+        }))
+        .await;
+    assert_eq!(code, 201);
+    let key = response["key"].as_str().unwrap();
+    let uid = response["uid"].as_str().unwrap();
+    let tenant_token = hashmap! {
+        "searchRules" => json!({"sales": {"filter": "color = blue"}}),
+        "exp" => json!((OffsetDateTime::now_utc() + Duration::hours(1)).unix_timestamp())
+    };
+    let web_token = generate_tenant_token(uid, key, tenant_token);
+    server.use_api_key(&web_token);
+    let index = server.index("sales");
+
+    let (response, code) = index.similar_get("1", "attributesToRetrieve=id").await;
+    assert_eq!(code, 200, "{response}");
+    assert_eq!(response["hits"], json!([{ "id": "2" }]));
+
+    // the reference document must be visible to the tenant token too
+    let (response, code) = index.similar_get("3", "").await;
+    assert_eq!(code, 404, "{response}");
+    assert_eq!(response["code"], json!("document_not_found"));
+}
@@ -0,0 +1,171 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn chat_completions_feature_not_enabled() {
+    let server = Server::new().await;
+
+    let (response, code) = server.service.get("/chat/settings").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Getting the chat completions settings requires enabling the `chat completions` experimental feature. See https://github.com/meilisearch/product/discussions/835",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
+    let (response, code) = server
+        .service
+        .post("/chat/completions", json!({"messages": [{"role": "user", "content": "hello"}]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the chat completions route requires enabling the `chat completions` experimental feature. See https://github.com/meilisearch/product/discussions/835",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn chat_settings() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"chatCompletions": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server.service.get("/chat/settings").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "source": "openAi",
+      "baseUrl": null,
+      "apiKey": null,
+      "model": null,
+      "systemPrompt": null
+    }
+    "###);
+
+    let (response, code) = server
+        .service
+        .patch(
+            "/chat/settings",
+            json!({"source": "ollama", "model": "llama3", "systemPrompt": "Answer with {{documents}}"}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "source": "ollama",
+      "baseUrl": null,
+      "apiKey": null,
+      "model": "llama3",
+      "systemPrompt": "Answer with {{documents}}"
+    }
+    "###);
+
+    // the settings that are not sent are kept, `null` resets them
+    let (response, code) =
+        server.service.patch("/chat/settings", json!({"systemPrompt": null})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "source": "ollama",
+      "baseUrl": null,
+      "apiKey": null,
+      "model": "llama3",
+      "systemPrompt": null
+    }
+    "###);
+
+    // the API key is masked
+    let (response, code) = server
+        .service
+        .patch("/chat/settings", json!({"apiKey": "sk-0123456789abcdefghijklm"}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["apiKey"], @r###""sk-XXXXX...""###);
+    let (response, code) = server.service.get("/chat/settings").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["apiKey"], @r###""sk-XXXXX...""###);
+
+    let (response, code) =
+        server.service.patch("/chat/settings", json!({"source": "gemini"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `gemini` at `.source`: expected one of `openAi`, `mistral`, `ollama`, `vLlm`",
+      "code": "invalid_chat_completion_source",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_chat_completion_source"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn invalid_chat_completions() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({"chatCompletions": true})).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server.service.post("/chat/completions", json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `messages`",
+      "code": "missing_chat_completion_messages",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_chat_completion_messages"
+    }
+    "###);
+
+    let (response, code) = server
+        .service
+        .post("/chat/completions", json!({"messages": [{"role": "assistant", "content": "hello"}]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: the last of the `messages` must be a `user` message, it is the query of the searches.",
+      "code": "invalid_chat_completion_messages",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_chat_completion_messages"
+    }
+    "###);
+
+    let (response, code) = server
+        .service
+        .post("/chat/completions", json!({"messages": [{"role": "user", "content": "hello"}]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The chat completions settings must specify the `model` answering the conversations.",
+      "code": "invalid_chat_completion_model",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_chat_completion_model"
+    }
+    "###);
+
+    let (_, code) =
+        server.service.patch("/chat/settings", json!({"source": "vLlm", "model": "llama3"})).await;
+    snapshot!(code, @"200 OK");
+    let (response, code) = server
+        .service
+        .post("/chat/completions", json!({"messages": [{"role": "user", "content": "hello"}]}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The chat completions settings must specify a `baseUrl`, the source has no default URL.",
+      "code": "invalid_chat_completion_base_url",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_chat_completion_base_url"
+    }
+    "###);
+}
@@ -12,6 +12,7 @@ pub enum Encoder {
     Gzip,
     Deflate,
     Brotli,
+    Zstd,
 }
 
 impl Encoder {
@@ -34,6 +35,9 @@ impl Encoder {
                 encoder.flush().expect("Failed to encode request body");
                 encoder.into_inner()
             }
+            Self::Zstd => {
+                zstd::encode_all(body.into().as_ref(), 3).expect("Failed to encode request body")
+            }
         }
     }
 
@@ -59,6 +63,9 @@ impl Encoder {
                     .read_to_end(&mut buffer)
                     .expect("Invalid brotli stream");
             }
+            Self::Zstd => {
+                buffer = zstd::decode_all(input.as_ref()).expect("Invalid zstd stream");
+            }
         };
         buffer
     }
@@ -69,10 +76,11 @@ impl Encoder {
             Self::Gzip => Some(("Content-Encoding", "gzip")),
             Self::Deflate => Some(("Content-Encoding", "deflate")),
             Self::Brotli => Some(("Content-Encoding", "br")),
+            Self::Zstd => Some(("Content-Encoding", "zstd")),
         }
     }
 
     pub fn iterator() -> impl Iterator<Item = Self> {
-        [Self::Plain, Self::Gzip, Self::Deflate, Self::Brotli].iter().copied()
+        [Self::Plain, Self::Gzip, Self::Deflate, Self::Brotli, Self::Zstd].iter().copied()
     }
 }
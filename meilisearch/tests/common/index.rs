@@ -83,6 +83,22 @@ impl Index<'_> {
         self.service.delete(url).await
     }
 
+    pub async fn copy(&self, destination: &str) -> (Value, StatusCode) {
+        let body = json!({ "destination": destination });
+        let url = format!("/indexes/{}/copy", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, body, self.encoder).await
+    }
+
+    pub async fn export(&self, body: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/export", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, body, self.encoder).await
+    }
+
+    pub async fn changes(&self, query: &str) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/changes?{}", urlencode(self.uid.as_ref()), query);
+        self.service.get(url).await
+    }
+
     pub async fn add_documents(
         &self,
         documents: Value,
@@ -313,6 +329,11 @@ impl Index<'_> {
         self.service.put_encoded(url, settings, self.encoder).await
     }
 
+    pub async fn update_settings_stemming(&self, settings: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/stemming", urlencode(self.uid.as_ref()));
+        self.service.put_encoded(url, settings, self.encoder).await
+    }
+
     pub async fn update_settings_distinct_attribute(&self, settings: Value) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/settings/distinct-attribute", urlencode(self.uid.as_ref()));
         self.service.put_encoded(url, settings, self.encoder).await
@@ -380,6 +401,49 @@ impl Index<'_> {
         self.service.get(url).await
     }
 
+    pub async fn similar_get(&self, id: &str, query: &str) -> (Value, StatusCode) {
+        let url = format!(
+            "/indexes/{}/similar/{}?{}",
+            urlencode(self.uid.as_ref()),
+            urlencode(id),
+            query
+        );
+        self.service.get(url).await
+    }
+
+    pub async fn suggest(&self, query: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/suggest", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, query, self.encoder).await
+    }
+
+    pub async fn suggest_get(&self, query: &str) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/suggest?{}", urlencode(self.uid.as_ref()), query);
+        self.service.get(url).await
+    }
+
+    pub async fn search_click(&self, click: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/search-analytics/clicks", urlencode(self.uid.as_ref()));
+        self.service.post_encoded(url, click, self.encoder).await
+    }
+
+    pub async fn top_queries(&self, query: &str) -> (Value, StatusCode) {
+        let url = format!(
+            "/indexes/{}/search-analytics/top-queries?{}",
+            urlencode(self.uid.as_ref()),
+            query
+        );
+        self.service.get(url).await
+    }
+
+    pub async fn queries_without_results(&self, query: &str) -> (Value, StatusCode) {
+        let url = format!(
+            "/indexes/{}/search-analytics/queries-without-results?{}",
+            urlencode(self.uid.as_ref()),
+            query
+        );
+        self.service.get(url).await
+    }
+
     pub async fn facet_search(&self, query: Value) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/facet-search", urlencode(self.uid.as_ref()));
         self.service.post_encoded(url, query, self.encoder).await
@@ -41,7 +41,7 @@ impl Server {
         let options = default_settings(dir.path());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Server { service, _dir: Some(dir) }
     }
@@ -56,7 +56,7 @@ impl Server {
         options.master_key = Some("MASTER_KEY".to_string());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Server { service, _dir: Some(dir) }
     }
@@ -69,7 +69,7 @@ impl Server {
 
     pub async fn new_with_options(options: Opt) -> Result<Self, anyhow::Error> {
         let (index_scheduler, auth) = setup_meilisearch(&options)?;
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service::new(index_scheduler, auth, options);
 
         Ok(Server { service, _dir: None })
     }
@@ -86,6 +86,8 @@ impl Server {
             self.service.auth.clone().into(),
             self.service.options.clone(),
             analytics::MockAnalytics::new(&self.service.options),
+            self.service.search_cache.clone().into(),
+            self.service.search_queue.clone().into(),
             true,
         ))
         .await
@@ -1,10 +1,13 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use actix_web::http::header::ContentType;
 use actix_web::http::StatusCode;
 use actix_web::test;
 use actix_web::test::TestRequest;
 use index_scheduler::IndexScheduler;
+use meilisearch::search_cache::SearchCache;
+use meilisearch::search_queue::SearchQueue;
 use meilisearch::{analytics, create_app, Opt};
 use meilisearch_auth::AuthController;
 
@@ -15,10 +18,27 @@ pub struct Service {
     pub index_scheduler: Arc<IndexScheduler>,
     pub auth: Arc<AuthController>,
     pub options: Opt,
+    pub search_cache: Arc<SearchCache>,
+    pub search_queue: Arc<SearchQueue>,
     pub api_key: Option<String>,
 }
 
 impl Service {
+    pub fn new(
+        index_scheduler: Arc<IndexScheduler>,
+        auth: Arc<AuthController>,
+        options: Opt,
+    ) -> Self {
+        let search_cache = Arc::new(SearchCache::new(options.experimental_search_cache_size));
+        let search_queue = Arc::new(SearchQueue::new(
+            options.experimental_search_max_in_flight,
+            options.experimental_search_queue_size,
+            (options.experimental_search_deadline_ms != 0)
+                .then(|| Duration::from_millis(options.experimental_search_deadline_ms)),
+        ));
+        Service { index_scheduler, auth, options, search_cache, search_queue, api_key: None }
+    }
+
     pub async fn post(&self, url: impl AsRef<str>, body: Value) -> (Value, StatusCode) {
         self.post_encoded(url, body, Encoder::Plain).await
     }
@@ -110,6 +130,8 @@ impl Service {
             self.auth.clone().into(),
             self.options.clone(),
             analytics::MockAnalytics::new(&self.options),
+            self.search_cache.clone().into(),
+            self.search_queue.clone().into(),
             true,
         ))
         .await;
@@ -183,6 +183,28 @@ async fn add_single_document_gzip_encoded() {
     "###);
 }
 
+#[actix_rt::test]
+async fn add_documents_zstd_encoded() {
+    let server = Server::new().await;
+    let index = server.index_with_encoder("dog", Encoder::Zstd);
+
+    let documents = json!([
+        { "id": 1, "content": "Bouvier Bernois" },
+        { "id": 2, "content": "Berger Blanc Suisse" },
+    ]);
+
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202, "response: {}", response);
+
+    let response = index.wait_task(0).await;
+    assert_eq!(response["status"], "succeeded", "response: {}", response);
+    assert_eq!(response["details"]["indexedDocuments"], 2);
+
+    let (response, code) = index.get_document(2, None).await;
+    assert_eq!(code, 200);
+    assert_eq!(response, json!({ "id": 2, "content": "Berger Blanc Suisse" }));
+}
+
 /// Here we try document request with every encoding
 #[actix_rt::test]
 async fn add_single_document_with_every_encoding() {
@@ -418,6 +440,59 @@ async fn add_csv_document_with_custom_delimiter() {
     "###);
 }
 
+#[actix_rt::test]
+async fn add_csv_document_with_custom_quote_and_array_delimiter() {
+    let server = Server::new().await;
+    let index = server.index("pets");
+
+    let document = "#id;name;genres:string[];ages:number[]
+0;'jean; the dog';bernese, mountain;1, 4
+1;'jorts ''the cat''';;7";
+
+    let (response, code) = index
+        .raw_update_documents(
+            document,
+            Some("text/csv"),
+            "?csvDelimiter=;&csvQuote='&csvArrayDelimiter=,",
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents), @r###"
+    {
+      "results": [
+        {
+          "#id": "0",
+          "name": "jean; the dog",
+          "genres": [
+            "bernese",
+            "mountain"
+          ],
+          "ages": [
+            1,
+            4
+          ]
+        },
+        {
+          "#id": "1",
+          "name": "jorts 'the cat'",
+          "genres": null,
+          "ages": [
+            7
+          ]
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 2
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn add_csv_document_with_types_error() {
     let server = Server::new().await;
@@ -479,7 +554,7 @@ async fn error_add_documents_test_bad_content_types() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/msgpack`, `application/avro`",
       "code": "invalid_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_content_type"
@@ -500,7 +575,7 @@ async fn error_add_documents_test_bad_content_types() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "The Content-Type `text/plain` is invalid. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/msgpack`, `application/avro`",
       "code": "invalid_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_content_type"
@@ -534,7 +609,7 @@ async fn error_add_documents_test_no_content_type() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/msgpack`, `application/avro`",
       "code": "missing_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#missing_content_type"
@@ -554,7 +629,7 @@ async fn error_add_documents_test_no_content_type() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`",
+      "message": "A Content-Type header is missing. Accepted values for the Content-Type header are: `application/json`, `application/x-ndjson`, `text/csv`, `application/vnd.apache.parquet`, `application/msgpack`, `application/avro`",
       "code": "missing_content_type",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#missing_content_type"
@@ -562,6 +637,80 @@ async fn error_add_documents_test_no_content_type() {
     "###);
 }
 
+#[actix_rt::test]
+async fn add_documents_with_bom_or_in_utf16() {
+    let server = Server::new().await;
+    let app = server.init_web_app().await;
+
+    let json = r#"[{"id": 1, "content": "Bouvier Bernois"}]"#;
+    let utf16_le_with_bom: Vec<u8> =
+        [0xFF, 0xFE].into_iter().chain(json.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+    let ndjson = r#"{"id": 2, "content": "Crème brûlée"}"#;
+    let utf16_be: Vec<u8> = ndjson.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let csv = "\u{feff}id,content\n3,Jorts";
+    // the lone surrogate is replaced by the replacement character, which isn't valid JSON
+    let invalid_utf16: Vec<u8> = [0xFF, 0xFE].into_iter().chain([0x00, 0xD8, 0x5B, 0x00]).collect();
+
+    for (payload, content_type) in [
+        (utf16_le_with_bom, "application/json"),
+        (utf16_be, "application/x-ndjson"),
+        (csv.as_bytes().to_vec(), "text/csv"),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/indexes/dog/documents")
+            .set_payload(payload)
+            .insert_header(("content-type", content_type))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        snapshot!(res.status(), @"202 Accepted");
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/indexes/dog/documents")
+        .set_payload(invalid_utf16)
+        .insert_header(("content-type", "application/json"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    let status_code = res.status();
+    let body = test::read_body(res).await;
+    let response: Value = serde_json::from_slice(&body).unwrap_or_default();
+    snapshot!(status_code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The `json` payload provided is malformed. `Couldn't serialize document value: expected value at line 1 column 1`.",
+      "code": "malformed_payload",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#malformed_payload"
+    }
+    "###);
+
+    let index = server.index("dog");
+    index.wait_task(2).await;
+    let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents), @r###"
+    {
+      "results": [
+        {
+          "id": 1,
+          "content": "Bouvier Bernois"
+        },
+        {
+          "id": 2,
+          "content": "Crème brûlée"
+        },
+        {
+          "id": "3",
+          "content": "Jorts"
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 3
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn error_add_malformed_csv_documents() {
     let document = "id, content\n1234, hello, world\n12, hello world";
@@ -1235,6 +1384,363 @@ async fn error_add_documents_missing_document_id() {
     "###);
 }
 
+#[actix_rt::test]
+async fn add_documents_skipping_the_invalid_ones() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(Some("id")).await;
+    index.update_settings(json!({"filterableAttributes": ["_geo"]})).await;
+    let documents = json!([
+        { "id": "1", "_geo": { "lat": 12, "lng": 13 } },
+        { "id": "foo & bar" },
+        { "id": "3", "_geo": { "lat": 12 } },
+        { "content": "foobar" },
+        { "id": "5" },
+    ]);
+    index
+        .raw_add_documents(&documents.to_string(), Some("application/json"), "?strict=false")
+        .await;
+    index.wait_task(2).await;
+    let (response, code) = index.get_task(2).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".duration" => "[duration]", ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]" }),
+        @r###"
+    {
+      "uid": 2,
+      "indexUid": "test",
+      "status": "succeeded",
+      "type": "documentAdditionOrUpdate",
+      "canceledBy": null,
+      "details": {
+        "receivedDocuments": 5,
+        "indexedDocuments": 2,
+        "invalidDocuments": [
+          {
+            "position": 1,
+            "error": {
+              "message": "Document identifier `\"foo & bar\"` is invalid. A document identifier can be of type integer or string, only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and underscores (_).",
+              "code": "invalid_document_id",
+              "type": "invalid_request",
+              "link": "https://docs.meilisearch.com/errors#invalid_document_id"
+            }
+          },
+          {
+            "position": 2,
+            "error": {
+              "message": "Could not find longitude in the document with the id: `3`. Was expecting a `_geo.lng` field.",
+              "code": "invalid_document_geo_field",
+              "type": "invalid_request",
+              "link": "https://docs.meilisearch.com/errors#invalid_document_geo_field"
+            }
+          },
+          {
+            "position": 3,
+            "error": {
+              "message": "Document doesn't have a `id` attribute: `{\"content\":\"foobar\"}`.",
+              "code": "missing_document_id",
+              "type": "invalid_request",
+              "link": "https://docs.meilisearch.com/errors#missing_document_id"
+            }
+          }
+        ]
+      },
+      "error": null,
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    let (response, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "id": "1",
+        "_geo": {
+          "lat": 12,
+          "lng": 13
+        }
+      },
+      {
+        "id": "5"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_strict_add_documents_with_an_invalid_one() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(Some("id")).await;
+    let documents = json!([
+        { "id": "1" },
+        { "id": "foo & bar" },
+        { "id": "3" },
+    ]);
+    let (response, code) = index
+        .raw_add_documents(&documents.to_string(), Some("application/json"), "?strict=true")
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.get_task(response.uid()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".duration" => "[duration]", ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]" }),
+        @r###"
+    {
+      "uid": 1,
+      "indexUid": "test",
+      "status": "failed",
+      "type": "documentAdditionOrUpdate",
+      "canceledBy": null,
+      "details": {
+        "receivedDocuments": 3,
+        "indexedDocuments": 0
+      },
+      "error": {
+        "message": "Document identifier `\"foo & bar\"` is invalid. A document identifier can be of type integer or string, only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and underscores (_).",
+        "code": "invalid_document_id",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_document_id"
+      },
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    let (response, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @"[]");
+}
+
+#[actix_rt::test]
+async fn error_add_documents_not_matching_the_document_schema() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(Some("id")).await;
+
+    let schema = json!({
+        "type": "object",
+        "required": ["title"],
+        "properties": {
+            "title": { "type": "string" },
+            "price": { "type": ["number", "null"], "minimum": 0 }
+        }
+    });
+    let (response, _) = index.update_settings(json!({ "documentSchema": schema })).await;
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["documentSchema"], schema);
+
+    let documents = json!([
+        { "id": 1, "title": "Carol", "price": 12 },
+        { "id": 2, "title": "Gravity", "price": "12" },
+    ]);
+    let (response, _) = index
+        .raw_add_documents(&documents.to_string(), Some("application/json"), "?strict=true")
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "The document with the id: `2` does not match the document schema of the index: `price` must be of type `number` or `null`, but found a string: `\"12\"`.",
+      "code": "invalid_document_schema",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_schema"
+    }
+    "###);
+
+    // the documents not matching the schema are skipped when the addition isn't strict
+    let (response, _) = index
+        .raw_add_documents(&documents.to_string(), Some("application/json"), "?strict=false")
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 2,
+      "indexedDocuments": 1,
+      "invalidDocuments": [
+        {
+          "position": 1,
+          "error": {
+            "message": "The document with the id: `2` does not match the document schema of the index: `price` must be of type `number` or `null`, but found a string: `\"12\"`.",
+            "code": "invalid_document_schema",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#invalid_document_schema"
+          }
+        }
+      ]
+    }
+    "###);
+
+    // the partial updates are validated once merged with the stored documents
+    let (response, _) = index.update_documents(json!([{ "id": 1, "price": 10 }]), None).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    let (response, _) = index
+        .raw_update_documents(
+            r#"[{ "id": 3, "price": 10 }]"#,
+            Some("application/json"),
+            "?strict=true",
+        )
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["error"]["message"]), @r###""The document with the id: `3` does not match the document schema of the index: `title` is required.""###);
+
+    // the schema only supports a subset of the JSON Schema keywords
+    let (response, _) = index
+        .update_settings(json!({ "documentSchema": { "type": "object", "patternProperties": {} } }))
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`documentSchema` setting is invalid at `#/patternProperties`: the `patternProperties` keyword is not supported.",
+      "code": "invalid_settings_document_schema",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_document_schema"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn add_documents_with_type_coercions() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(Some("id")).await;
+
+    let (response, _) = index
+        .update_settings(json!({
+            "filterableAttributes": ["price", "tags"],
+            "typeCoercions": { "price": "number", "tags": "arrayOfStrings" }
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["typeCoercions"]), @r###"
+    {
+      "price": "number",
+      "tags": "arrayOfStrings"
+    }
+    "###);
+
+    let documents = json!([
+        { "id": 1, "price": "42", "tags": "drama" },
+        { "id": 2, "price": 12, "tags": ["comedy", 2024] },
+        { "id": 3, "price": "unknown" },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents), @r###"
+    {
+      "results": [
+        {
+          "id": 1,
+          "price": 42,
+          "tags": [
+            "drama"
+          ]
+        },
+        {
+          "id": 2,
+          "price": 12,
+          "tags": [
+            "comedy",
+            "2024"
+          ]
+        },
+        {
+          "id": 3,
+          "price": "unknown"
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 3
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "price > 20 AND tags = drama" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["estimatedTotalHits"], @"1");
+}
+
+#[actix_rt::test]
+async fn add_documents_with_ingestion_mapping() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, _) = index
+        .update_settings(json!({
+            "ingestionMapping": { "legacy_id": "id", "properties.title": "title" }
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+
+    let documents = json!([
+        { "legacy_id": 1, "properties": { "title": "Carol", "year": 2015 } },
+        { "legacy_id": 2, "properties": { "title": "Gravity" } },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // the primary key is inferred from the renamed fields
+    let (response, code) = index.get().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["primaryKey"], @r###""id""###);
+
+    let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents), @r###"
+    {
+      "results": [
+        {
+          "id": 1,
+          "properties": {
+            "year": 2015
+          },
+          "title": "Carol"
+        },
+        {
+          "id": 2,
+          "properties": {},
+          "title": "Gravity"
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 2
+    }
+    "###);
+
+    let (response, _) = index
+        .update_settings(json!({
+            "ingestionMapping": { "name": "title", "properties.title": "title" }
+        }))
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`ingestionMapping` setting is invalid. The `name` and `properties.title` fields can't both be renamed into `title`.",
+      "code": "invalid_settings_ingestion_mapping",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ingestion_mapping"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 #[ignore] // // TODO: Fix in an other PR: this does not provoke any error.
 async fn error_document_field_limit_reached() {
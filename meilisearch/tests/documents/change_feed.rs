@@ -0,0 +1,271 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn change_feed_feature_not_enabled() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.changes("").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Getting the changes of the documents requires enabling the `change feed` experimental feature. See https://github.com/meilisearch/product/discussions/841",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn record_document_changes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = server.set_features(json!({ "changeFeed": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "Carol" }, { "id": 2, "title": "Wonder Woman" }]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 2, "title": "Wonder Woman 1984" }, { "id": 3, "title": "Shazam" }]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.delete_document(1).await;
+    index.wait_task(task.uid()).await;
+    // the document 1 is already deleted and doesn't appear in the feed
+    let (task, _code) = index.delete_batch(vec![1, 3]).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.clear_all_documents().await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.changes("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".epoch" => "[epoch]" }), @r###"
+    {
+      "results": [
+        {
+          "cursor": 0,
+          "taskUid": 0,
+          "documentId": "1",
+          "operation": "addition"
+        },
+        {
+          "cursor": 1,
+          "taskUid": 0,
+          "documentId": "2",
+          "operation": "addition"
+        },
+        {
+          "cursor": 2,
+          "taskUid": 1,
+          "documentId": "2",
+          "operation": "update"
+        },
+        {
+          "cursor": 3,
+          "taskUid": 1,
+          "documentId": "3",
+          "operation": "addition"
+        },
+        {
+          "cursor": 4,
+          "taskUid": 2,
+          "documentId": "1",
+          "operation": "deletion"
+        },
+        {
+          "cursor": 5,
+          "taskUid": 3,
+          "documentId": "3",
+          "operation": "deletion"
+        },
+        {
+          "cursor": 6,
+          "taskUid": 4,
+          "operation": "clear"
+        }
+      ],
+      "next": 6,
+      "epoch": "[epoch]"
+    }
+    "###);
+
+    let (response, code) = index.changes("after=3&limit=2").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".epoch" => "[epoch]" }), @r###"
+    {
+      "results": [
+        {
+          "cursor": 4,
+          "taskUid": 2,
+          "documentId": "1",
+          "operation": "deletion"
+        },
+        {
+          "cursor": 5,
+          "taskUid": 3,
+          "documentId": "3",
+          "operation": "deletion"
+        }
+      ],
+      "next": 5,
+      "epoch": "[epoch]"
+    }
+    "###);
+
+    let (response, code) = index.changes("after=6").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".epoch" => "[epoch]" }), @r###"
+    {
+      "results": [],
+      "next": 6,
+      "epoch": "[epoch]"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn record_deletions_by_filter_and_failed_additions() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = server.set_features(json!({ "changeFeed": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "genre": "romance" }, { "id": 2, "genre": "action" }]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["genre"])).await;
+    index.wait_task(task.uid()).await;
+    // the whole addition fails because of the invalid document id
+    let (task, _code) = index.add_documents(json!([{ "id": 3 }, { "id": "bad id!" }]), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    let (task, _code) =
+        index.delete_document_by_filter(json!({ "filter": "genre = action" })).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.changes("after=1").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".epoch" => "[epoch]" }), @r###"
+    {
+      "results": [
+        {
+          "cursor": 2,
+          "taskUid": 3,
+          "documentId": "2",
+          "operation": "deletion"
+        }
+      ],
+      "next": 2,
+      "epoch": "[epoch]"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn the_feeds_follow_their_index() {
+    let server = Server::new().await;
+    let (_, code) = server.set_features(json!({ "changeFeed": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1 }]), Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let other = server.index("other");
+    let (task, _code) = other.add_documents(json!([{ "id": 2 }, { "id": 3 }]), Some("id")).await;
+    other.wait_task(task.uid()).await;
+
+    let (response, _code) = index.changes("").await;
+    let test_epoch = response["epoch"].clone();
+    let (response, _code) = other.changes("").await;
+    let other_epoch = response["epoch"].clone();
+    assert_ne!(test_epoch, other_epoch);
+
+    // the feeds are swapped along with the indexes
+    let (task, _code) = server.index_swap(json!([{ "indexes": ["test", "other"] }])).await;
+    server.wait_task(task.uid()).await;
+    let (response, code) = index.changes("").await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["epoch"], other_epoch);
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "cursor": 0,
+        "taskUid": 1,
+        "documentId": "2",
+        "operation": "addition"
+      },
+      {
+        "cursor": 1,
+        "taskUid": 1,
+        "documentId": "3",
+        "operation": "addition"
+      }
+    ]
+    "###);
+
+    // a recreated index gets a new feed
+    let (task, _code) = index.delete().await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(json!([{ "id": 4 }]), Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (response, code) = index.changes("").await;
+    snapshot!(code, @"200 OK");
+    assert_ne!(response["epoch"], other_epoch);
+    assert_ne!(response["epoch"], test_epoch);
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "cursor": 0,
+        "taskUid": 4,
+        "documentId": "4",
+        "operation": "addition"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_get_changes_bad_params() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.changes("after=abc").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `after`: could not parse `abc` as a positive integer",
+      "code": "invalid_change_feed_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_change_feed_after"
+    }
+    "###);
+
+    let (response, code) = index.changes("limit=-1").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `limit`: could not parse `-1` as a positive integer",
+      "code": "invalid_change_feed_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_change_feed_limit"
+    }
+    "###);
+}
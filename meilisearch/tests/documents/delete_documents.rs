@@ -191,6 +191,10 @@ async fn delete_document_by_filter() {
       "details": {
         "providedIds": 0,
         "deletedDocuments": 2,
+        "deletedDocumentsSample": [
+          "1",
+          "2"
+        ],
         "originalFilter": "\"color = blue\""
       },
       "error": null,
@@ -256,6 +260,9 @@ async fn delete_document_by_filter() {
       "details": {
         "providedIds": 0,
         "deletedDocuments": 1,
+        "deletedDocumentsSample": [
+          "3"
+        ],
         "originalFilter": "\"color NOT EXISTS\""
       },
       "error": null,
@@ -340,6 +347,10 @@ async fn delete_document_by_complex_filter() {
       "details": {
         "providedIds": 0,
         "deletedDocuments": 2,
+        "deletedDocumentsSample": [
+          "1",
+          "2"
+        ],
         "originalFilter": "[\"color != red\",\"color != green\",\"color EXISTS\"]"
       },
       "error": null,
@@ -398,6 +409,10 @@ async fn delete_document_by_complex_filter() {
       "details": {
         "providedIds": 0,
         "deletedDocuments": 2,
+        "deletedDocumentsSample": [
+          "3",
+          "4"
+        ],
         "originalFilter": "[[\"color = green\",\"color NOT EXISTS\"]]"
       },
       "error": null,
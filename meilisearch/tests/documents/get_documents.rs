@@ -357,6 +357,49 @@ async fn get_document_s_nested_attributes_to_retrieve() {
     );
 }
 
+#[actix_rt::test]
+async fn get_documents_nested_fields() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let documents = json!([
+        {
+            "id": 0,
+            "title": "Le Procope",
+            "address": { "city": "Paris", "street": "rue de l'Ancienne Comédie" },
+            "reviews": [{ "rating": 4, "author": "kero" }, { "rating": 5, "author": "tamo" }],
+        },
+    ]);
+    let (_, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(0).await;
+
+    let (response, code) = index.get_all_documents_raw("?fields=address.city,reviews.rating").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "address": {
+          "city": "Paris"
+        },
+        "reviews": [
+          {
+            "rating": 4
+          },
+          {
+            "rating": 5
+          }
+        ]
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .get_document(0, Some(GetDocumentOptions { fields: Some(vec!["address.city", "id"]) }))
+        .await;
+    assert_eq!(code, 200);
+    assert_eq!(response, json!({ "id": 0, "address": { "city": "Paris" } }));
+}
+
 #[actix_rt::test]
 async fn get_documents_displayed_attributes_is_ignored() {
     let server = Server::new().await;
@@ -540,3 +583,144 @@ async fn get_document_by_filter() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn get_documents_by_ids() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    index.update_settings_filterable_attributes(json!(["color"])).await;
+    index
+        .add_documents(
+            json!([
+                { "id": 0, "color": "red" },
+                { "id": 1, "color": "blue" },
+                { "id": 2, "color": "blue" },
+                { "id": 3 },
+            ]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.get_document_by_filter(json!({ "ids": [2, "0", 42, 3, 2] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "id": 2,
+          "color": "blue"
+        },
+        {
+          "id": 0,
+          "color": "red"
+        },
+        {
+          "id": 3
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 3
+    }
+    "###);
+
+    let (response, code) = index
+        .get_document_by_filter(json!({ "ids": [3, 2, 1], "filter": "color = blue", "limit": 1 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "id": 2,
+          "color": "blue"
+        }
+      ],
+      "offset": 0,
+      "limit": 1,
+      "total": 2
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn get_document_by_filter_and_sort() {
+    let server = Server::new().await;
+    let index = server.index("doggo");
+    index.update_settings_filterable_attributes(json!(["color", "age"])).await;
+    index
+        .add_documents(
+            json!([
+                { "id": 0, "color": "red", "age": 4 },
+                { "id": 1, "color": "blue", "age": 2 },
+                { "id": 2, "color": "blue", "age": 7 },
+                { "id": 3, "color": "blue" },
+                { "id": 4, "color": "red", "age": 2 },
+            ]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .get_document_by_filter(json!({ "filter": "color = blue", "sort": ["age:desc"] }))
+        .await;
+    let (response2, code2) = index.get_all_documents_raw("?filter=color=blue&sort=age:desc").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "id": 2,
+          "color": "blue",
+          "age": 7
+        },
+        {
+          "id": 1,
+          "color": "blue",
+          "age": 2
+        },
+        {
+          "id": 3,
+          "color": "blue"
+        }
+      ],
+      "offset": 0,
+      "limit": 20,
+      "total": 3
+    }
+    "###);
+    assert_eq!(code, code2);
+    assert_eq!(response, response2);
+
+    let (response, code) = index
+        .get_document_by_filter(
+            json!({ "offset": 1, "limit": 2, "sort": ["age:asc", "color:desc"] }),
+        )
+        .await;
+    let (response2, code2) =
+        index.get_all_documents_raw("?offset=1&limit=2&sort=age:asc,color:desc").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "id": 1,
+          "color": "blue",
+          "age": 2
+        },
+        {
+          "id": 0,
+          "color": "red",
+          "age": 4
+        }
+      ],
+      "offset": 1,
+      "limit": 2,
+      "total": 5
+    }
+    "###);
+    assert_eq!(code, code2);
+    assert_eq!(response, response2);
+}
@@ -1,4 +1,5 @@
 mod add_documents;
+mod change_feed;
 mod delete_documents;
 mod errors;
 mod get_documents;
@@ -46,6 +46,13 @@ async fn import_dump_v1_movie_raw() {
       ],
       "filterableAttributes": [],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -59,6 +66,14 @@ async fn import_dump_v1_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -67,13 +82,16 @@ async fn import_dump_v1_movie_raw() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -205,6 +223,13 @@ async fn import_dump_v1_movie_with_settings() {
       "sortableAttributes": [
         "genres"
       ],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -221,6 +246,14 @@ async fn import_dump_v1_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -229,13 +262,16 @@ async fn import_dump_v1_movie_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -354,6 +390,13 @@ async fn import_dump_v1_rubygems_with_settings() {
       "sortableAttributes": [
         "version"
       ],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -369,6 +412,14 @@ async fn import_dump_v1_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -377,13 +428,16 @@ async fn import_dump_v1_rubygems_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -490,6 +544,13 @@ async fn import_dump_v2_movie_raw() {
       ],
       "filterableAttributes": [],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -503,6 +564,14 @@ async fn import_dump_v2_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -511,13 +580,16 @@ async fn import_dump_v2_movie_raw() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -633,6 +705,13 @@ async fn import_dump_v2_movie_with_settings() {
         "genres"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -649,6 +728,14 @@ async fn import_dump_v2_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -657,13 +744,16 @@ async fn import_dump_v2_movie_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -779,6 +869,13 @@ async fn import_dump_v2_rubygems_with_settings() {
         "version"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -794,6 +891,14 @@ async fn import_dump_v2_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -802,13 +907,16 @@ async fn import_dump_v2_rubygems_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -915,6 +1023,13 @@ async fn import_dump_v3_movie_raw() {
       ],
       "filterableAttributes": [],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -928,6 +1043,14 @@ async fn import_dump_v3_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -936,13 +1059,16 @@ async fn import_dump_v3_movie_raw() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1058,6 +1184,13 @@ async fn import_dump_v3_movie_with_settings() {
         "genres"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -1074,6 +1207,14 @@ async fn import_dump_v3_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -1082,13 +1223,16 @@ async fn import_dump_v3_movie_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1204,6 +1348,13 @@ async fn import_dump_v3_rubygems_with_settings() {
         "version"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -1219,6 +1370,14 @@ async fn import_dump_v3_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -1227,13 +1386,16 @@ async fn import_dump_v3_rubygems_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1340,6 +1502,13 @@ async fn import_dump_v4_movie_raw() {
       ],
       "filterableAttributes": [],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -1353,6 +1522,14 @@ async fn import_dump_v4_movie_raw() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -1361,13 +1538,16 @@ async fn import_dump_v4_movie_raw() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1483,6 +1663,13 @@ async fn import_dump_v4_movie_with_settings() {
         "genres"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -1499,6 +1686,14 @@ async fn import_dump_v4_movie_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -1507,13 +1702,16 @@ async fn import_dump_v4_movie_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1629,6 +1827,13 @@ async fn import_dump_v4_rubygems_with_settings() {
         "version"
       ],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "typo",
         "words",
@@ -1644,6 +1849,14 @@ async fn import_dump_v4_rubygems_with_settings() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": null,
       "typoTolerance": {
         "enabled": true,
@@ -1652,13 +1865,16 @@ async fn import_dump_v4_rubygems_with_settings() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
@@ -1860,7 +2076,11 @@ async fn import_dump_v6_containing_experimental_features() {
       "scoreDetails": false,
       "vectorStore": false,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -1878,6 +2098,13 @@ async fn import_dump_v6_containing_experimental_features() {
       ],
       "filterableAttributes": [],
       "sortableAttributes": [],
+      "nonFlattenedAttributes": [],
+      "mergeStrategies": {},
+      "typeCoercions": {},
+      "ingestionMapping": {},
+      "dateAttributes": [],
+      "documentSchema": null,
+      "suggestableAttributes": [],
       "rankingRules": [
         "words",
         "typo",
@@ -1889,6 +2116,14 @@ async fn import_dump_v6_containing_experimental_features() {
       "dictionary": [],
       "synonyms": {},
       "distinctAttribute": null,
+      "staticBoost": null,
+      "foreignKeys": [],
+      "queryRules": [],
+      "reranker": null,
+      "localizedAttributes": [],
+      "stemming": [],
+      "romanization": false,
+      "splitIdentifiers": false,
       "proximityPrecision": "byAttribute",
       "typoTolerance": {
         "enabled": true,
@@ -1897,13 +2132,16 @@ async fn import_dump_v6_containing_experimental_features() {
           "twoTypos": 9
         },
         "disableOnWords": [],
-        "disableOnAttributes": []
+        "disableOnAttributes": [],
+        "disableOnFirstCharacter": false,
+        "enableOnNumbers": true
       },
       "faceting": {
         "maxValuesPerFacet": 100,
         "sortFacetValuesBy": {
           "*": "alpha"
-        }
+        },
+        "hierarchySeparators": {}
       },
       "pagination": {
         "maxTotalHits": 1000
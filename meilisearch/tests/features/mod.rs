@@ -21,7 +21,11 @@ async fn experimental_features() {
       "scoreDetails": false,
       "vectorStore": false,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -33,7 +37,11 @@ async fn experimental_features() {
       "scoreDetails": false,
       "vectorStore": true,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -45,7 +53,11 @@ async fn experimental_features() {
       "scoreDetails": false,
       "vectorStore": true,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -58,7 +70,11 @@ async fn experimental_features() {
       "scoreDetails": false,
       "vectorStore": true,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -71,7 +87,11 @@ async fn experimental_features() {
       "scoreDetails": false,
       "vectorStore": true,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 }
@@ -91,7 +111,11 @@ async fn experimental_feature_metrics() {
       "scoreDetails": false,
       "vectorStore": false,
       "metrics": true,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -146,7 +170,7 @@ async fn errors() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "Unknown field `NotAFeature`: expected one of `scoreDetails`, `vectorStore`, `metrics`, `exportPuffinReports`",
+      "message": "Unknown field `NotAFeature`: expected one of `scoreDetails`, `vectorStore`, `metrics`, `exportPuffinReports`, `shadowSettingsUpdate`, `chatCompletions`, `searchAnalytics`, `changeFeed`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
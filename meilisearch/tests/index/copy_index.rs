@@ -0,0 +1,120 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{GetAllDocumentsOptions, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn copy_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "title": "Carol", "genre": "romance" },
+        { "id": 2, "title": "Wonder Woman", "genre": "action" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["genre"])).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) = index.copy("copy").await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["type"], @r###""indexCopy""###);
+    snapshot!(response["indexUid"], @r###""test""###);
+    snapshot!(response["details"], @r###"{"destination":"copy"}"###);
+
+    let copy = server.index("copy");
+    let (response, code) = copy.get().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["primaryKey"], @r###""id""###);
+
+    let (response, _code) = copy.settings().await;
+    snapshot!(response["filterableAttributes"], @r###"["genre"]"###);
+
+    let (response, _code) = copy.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(json_string!(response["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Carol",
+        "genre": "romance"
+      },
+      {
+        "id": 2,
+        "title": "Wonder Woman",
+        "genre": "action"
+      }
+    ]
+    "###);
+
+    // the copy is independent from the source index
+    let (task, _code) = copy.delete_document(1).await;
+    copy.wait_task(task.uid()).await;
+    let (response, _code) = index.stats().await;
+    snapshot!(response["numberOfDocuments"], @"2");
+    let (response, _code) = copy.stats().await;
+    snapshot!(response["numberOfDocuments"], @"1");
+}
+
+#[actix_rt::test]
+async fn error_copy_to_existing_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = server.index("copy").create(None).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) = index.copy("copy").await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "Index `copy` already exists.",
+      "code": "index_already_exists",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_already_exists"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_copy_unexisting_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, code) = index.copy("copy").await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "Index `test` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+
+    let (_response, code) = server.index("copy").get().await;
+    snapshot!(code, @"404 Not Found");
+}
+
+#[actix_rt::test]
+async fn error_copy_bad_destination() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.copy("doggo & catto").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.destination`: `doggo & catto` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "code": "invalid_index_copy_destination",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_index_copy_destination"
+    }
+    "###);
+}
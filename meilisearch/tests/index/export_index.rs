@@ -0,0 +1,236 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn export_index_as_ndjson() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(default_settings(temp.path())).await.unwrap();
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "title": "Carol", "genre": "romance", "tags": ["drama", "period"] },
+        { "id": 2, "title": "Wonder Woman", "genre": "action" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_settings_filterable_attributes(json!(["genre"])).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) = index.export(json!({ "filter": "genre = action" })).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["type"], @r###""indexExport""###);
+    snapshot!(response["indexUid"], @r###""test""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "originalFilter": "\"genre = action\"",
+      "destination": "test-2.ndjson",
+      "exportedDocuments": 1
+    }
+    "###);
+
+    let export = std::fs::read_to_string(temp.path().join("db/exports/test-2.ndjson")).unwrap();
+    snapshot!(export, @r###"
+    {"id":2,"title":"Wonder Woman","genre":"action"}
+    "###);
+}
+
+#[actix_rt::test]
+async fn export_index_as_csv() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(default_settings(temp.path())).await.unwrap();
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "title": "Carol", "genre": "romance", "tags": ["drama", "period"] },
+        { "id": 2, "title": "Wonder Woman", "genre": "action" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) =
+        index.export(json!({ "format": "csv", "fields": ["id", "title", "tags"] })).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "originalFilter": null,
+      "destination": "test-1.csv",
+      "exportedDocuments": 2
+    }
+    "###);
+
+    let export = std::fs::read_to_string(temp.path().join("db/exports/test-1.csv")).unwrap();
+    snapshot!(export, @r###"
+    id,title,tags
+    1,Carol,"[""drama"",""period""]"
+    2,Wonder Woman,
+    "###);
+}
+
+#[actix_rt::test]
+async fn a_new_export_replaces_the_previous_one() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(default_settings(temp.path())).await.unwrap();
+    let exports = temp.path().join("db/exports");
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1 }]), None).await;
+    index.wait_task(task.uid()).await;
+    let other = server.index("test-2");
+    let (task, _code) = other.add_documents(json!([{ "id": 1 }]), None).await;
+    other.wait_task(task.uid()).await;
+
+    let (task, _code) = other.export(json!({})).await;
+    other.wait_task(task.uid()).await;
+    let (task, _code) = index.export(json!({})).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.export(json!({ "format": "csv" })).await;
+    index.wait_task(task.uid()).await;
+
+    let mut files: Vec<_> =
+        std::fs::read_dir(&exports).unwrap().map(|e| e.unwrap().file_name()).collect();
+    files.sort();
+    snapshot!(format!("{files:?}"), @r###"["test-2-2.ndjson", "test-4.csv"]"###);
+
+    let (task, _code) = index.delete().await;
+    index.wait_task(task.uid()).await;
+    let files: Vec<_> =
+        std::fs::read_dir(&exports).unwrap().map(|e| e.unwrap().file_name()).collect();
+    snapshot!(format!("{files:?}"), @r###"["test-2-2.ndjson"]"###);
+}
+
+#[actix_rt::test]
+async fn upload_export_to_an_allowed_url() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    // answers a single PUT request, returning its request line and body
+    let upload = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").unwrap();
+        (request_line, String::from_utf8(body).unwrap())
+    });
+
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt {
+        experimental_export_allowed_urls: vec![format!("http://{address}/exports/")],
+        ..default_settings(temp.path())
+    };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    let documents = json!([{ "id": 1, "title": "Carol" }, { "id": 2, "title": "Wonder Woman" }]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    let url = format!("http://{address}/exports/test.ndjson?X-Amz-Signature=secret");
+    let (task, code) = index.export(json!({ "url": url })).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["details"]["exportedDocuments"], @"2");
+    assert_eq!(
+        response["details"]["destination"],
+        json!(format!("http://{address}/exports/test.ndjson"))
+    );
+
+    let (request_line, body) = upload.join().unwrap();
+    snapshot!(request_line.trim(), @"PUT /exports/test.ndjson?X-Amz-Signature=secret HTTP/1.1");
+    snapshot!(body, @r###"
+    {"id":1,"title":"Carol"}
+    {"id":2,"title":"Wonder Woman"}
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_export_with_a_non_filterable_attribute() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "genre": "action" }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) = index.export(json!({ "filter": "genre = action" })).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["code"], @r###""invalid_document_filter""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "originalFilter": "\"genre = action\"",
+      "exportedDocuments": 0
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_export_bad_request() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.export(json!({ "format": "xml" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `xml` at `.format`: expected one of `ndjson`, `csv`",
+      "code": "invalid_index_export_format",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_index_export_format"
+    }
+    "###);
+
+    let (response, code) = index.export(json!({ "filter": true })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid syntax for the filter parameter: `expected String, Array, found: true`.",
+      "code": "invalid_document_filter",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_filter"
+    }
+    "###);
+
+    let (response, code) = index.export(json!({ "url": "ftp://example.com/test.ndjson" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The export URL `ftp://example.com/test.ndjson` is invalid. It must start with `http://` or `https://`.",
+      "code": "invalid_index_export_url",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_index_export_url"
+    }
+    "###);
+
+    let (response, code) =
+        index.export(json!({ "url": "https://example.com/test.ndjson?signature=secret" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The export of the index can't be uploaded to `https://example.com/test.ndjson`, it doesn't start with one of the `--experimental-export-allowed-urls`.",
+      "code": "invalid_index_export_url",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_index_export_url"
+    }
+    "###);
+}
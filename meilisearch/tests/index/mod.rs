@@ -1,6 +1,8 @@
+mod copy_index;
 mod create_index;
 mod delete_index;
 mod errors;
+mod export_index;
 mod get_index;
 mod stats;
 mod update_index;
@@ -73,7 +73,40 @@ async fn update_nothing() {
 }
 
 #[actix_rt::test]
-async fn error_update_existing_primary_key() {
+async fn update_existing_primary_key() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_response, code) = index.create(Some("id")).await;
+
+    assert_eq!(code, 202);
+
+    let documents = json!([
+        {
+            "id": "11",
+            "uid": 12,
+            "content": "foobar"
+        }
+    ]);
+    index.add_documents(documents, None).await;
+
+    let (_, code) = index.update(Some("uid")).await;
+
+    assert_eq!(code, 202);
+
+    let response = index.wait_task(2).await;
+
+    assert_eq!(response["status"], "succeeded");
+
+    let (response, code) = index.get().await;
+    assert_eq!(code, 200);
+    assert_eq!(response["primaryKey"], "uid");
+
+    let (_, code) = index.get_document(12, None).await;
+    assert_eq!(code, 200);
+}
+
+#[actix_rt::test]
+async fn error_update_existing_primary_key_missing_in_documents() {
     let server = Server::new().await;
     let index = server.index("test");
     let (_response, code) = index.create(Some("id")).await;
@@ -95,10 +128,10 @@ async fn error_update_existing_primary_key() {
     let response = index.wait_task(2).await;
 
     let expected_response = json!({
-        "message": "Index already has a primary key: `id`.",
-        "code": "index_primary_key_already_exists",
+        "message": "Document doesn't have a `primary` attribute: `{\"id\":\"11\",\"content\":\"foobar\"}`.",
+        "code": "missing_document_id",
         "type": "invalid_request",
-        "link": "https://docs.meilisearch.com/errors#index_primary_key_already_exists"
+        "link": "https://docs.meilisearch.com/errors#missing_document_id"
     });
 
     assert_eq!(response["error"], expected_response);
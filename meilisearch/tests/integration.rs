@@ -1,4 +1,5 @@
 mod auth;
+mod chat;
 mod common;
 mod dashboard;
 mod documents;
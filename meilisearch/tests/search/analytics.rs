@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+use tokio::time::sleep;
+
+use crate::common::index::Index;
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        {
+            "title": "Shazam!",
+            "id": "287947",
+        },
+        {
+            "title": "Captain Marvel",
+            "id": "299537",
+        },
+        {
+            "title": "Escape Room",
+            "id": "522681",
+        },
+    ])
+});
+
+/// The searches are recorded in the background, waits for all of them to be visible.
+async fn wait_for_searches(index: &Index<'_>, searches: u64) {
+    for _ in 0..100 {
+        let (response, code) = index.top_queries("").await;
+        assert_eq!(200, code, "{:?}", response);
+        let recorded: u64 = response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|query| query["searches"].as_u64().unwrap())
+            .sum();
+        if recorded >= searches {
+            return;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Timeout waiting for the searches to be recorded");
+}
+
+#[actix_rt::test]
+async fn search_analytics_feature_not_enabled() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Getting the top queries requires enabling the `search analytics` experimental feature. See https://github.com/meilisearch/product/discussions/836",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
+    let (response, code) = index.search_click(json!({ "q": "captain", "documentId": "1" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Recording the clicks on the hits requires enabling the `search analytics` experimental feature. See https://github.com/meilisearch/product/discussions/836",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn record_searches_and_clicks() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = server.set_features(json!({ "searchAnalytics": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, _) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(response.uid()).await;
+
+    for q in ["captain", " Captain  ", "shazam", "batman", ""] {
+        let (response, code) = index.search_post(json!({ "q": q })).await;
+        assert_eq!(200, code, "{:?}", response);
+    }
+    wait_for_searches(&index, 4).await;
+
+    let (_, code) = index.search_click(json!({ "q": "Captain", "documentId": "299537" })).await;
+    snapshot!(code, @"204 No Content");
+
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].lastSearchedAt" => "[date]" }), @r###"
+    {
+      "results": [
+        {
+          "query": "captain",
+          "searches": 2,
+          "searchesWithoutResults": 0,
+          "clicks": 1,
+          "clickedDocuments": {
+            "299537": 1
+          },
+          "lastSearchedAt": "[date]"
+        },
+        {
+          "query": "batman",
+          "searches": 1,
+          "searchesWithoutResults": 1,
+          "clicks": 0,
+          "clickedDocuments": {},
+          "lastSearchedAt": "[date]"
+        },
+        {
+          "query": "shazam",
+          "searches": 1,
+          "searchesWithoutResults": 0,
+          "clicks": 0,
+          "clickedDocuments": {},
+          "lastSearchedAt": "[date]"
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index.top_queries("limit=1").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["results"].as_array().unwrap().len(), @"1");
+
+    let (response, code) = index.queries_without_results("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].lastSearchedAt" => "[date]" }), @r###"
+    {
+      "results": [
+        {
+          "query": "batman",
+          "searches": 1,
+          "searchesWithoutResults": 1,
+          "clicks": 0,
+          "clickedDocuments": {},
+          "lastSearchedAt": "[date]"
+        }
+      ]
+    }
+    "###);
+
+    // the analytics of an index are deleted along with it
+    let (response, _) = index.delete().await;
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.top_queries("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": []
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_click_errors() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = server.set_features(json!({ "searchAnalytics": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = index.search_click(json!({ "q": "captain", "documentId": "1" })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `test` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+
+    let (response, _) = index.create(None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.search_click(json!({ "q": "captain" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `documentId`",
+      "code": "missing_search_analytics_document_id",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_search_analytics_document_id"
+    }
+    "###);
+
+    let (response, code) = index.search_click(json!({ "q": 1, "documentId": "1" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.q`: expected a string, but found a positive integer: `1`",
+      "code": "invalid_search_analytics_q",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_analytics_q"
+    }
+    "###);
+
+    let (response, code) = index.top_queries("limit=abc").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `limit`: could not parse `abc` as a positive integer",
+      "code": "invalid_search_analytics_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_analytics_limit"
+    }
+    "###);
+}
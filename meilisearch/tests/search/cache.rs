@@ -0,0 +1,54 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+use tempfile::TempDir;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn cached_search_is_invalidated_by_a_write() {
+    let dir = TempDir::new().unwrap();
+    let options = Opt { experimental_search_cache_size: 10, ..default_settings(dir.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+
+    index
+        .add_documents(
+            json!([
+                { "id": 1, "title": "Captain Marvel" },
+                { "id": 2, "title": "Escape Room" },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(0).await;
+
+    let query = json!({ "q": "captain", "attributesToRetrieve": ["id"] });
+    for _ in 0..2 {
+        let (response, code) = index.search_post(query.clone()).await;
+        snapshot!(code, @"200 OK");
+        snapshot!(json_string!(response["hits"]), @r###"
+        [
+          {
+            "id": 1
+          }
+        ]
+        "###);
+    }
+
+    index.add_documents(json!([{ "id": 3, "title": "Captain America" }]), None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.search_post(query).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 3
+      }
+    ]
+    "###);
+}
@@ -1,4 +1,4 @@
-use meili_snap::snapshot;
+use meili_snap::{json_string, snapshot};
 use once_cell::sync::Lazy;
 
 use crate::common::{Server, Value};
@@ -239,3 +239,49 @@ async fn distinct_search_with_pagination_no_ranking() {
     snapshot!(response["totalPages"], @"2");
     snapshot!(response["totalHits"], @"6");
 }
+
+#[actix_rt::test]
+async fn distinct_of_the_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, Some(DOCUMENT_PRIMARY_KEY)).await;
+    index
+        .update_settings(json!({
+            "distinctAttribute": DOCUMENT_DISTINCT_KEY,
+            "filterableAttributes": ["color"],
+        }))
+        .await;
+    index.wait_task(1).await;
+
+    fn get_ids(response: &Value) -> Vec<u64> {
+        let hits_array = response["hits"].as_array().unwrap();
+        hits_array.iter().map(|h| h["id"].as_u64().unwrap()).collect::<Vec<_>>()
+    }
+
+    let (response, code) = index.search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_ids(&response)), @"[1, 4, 6, 8, 9, 12, 14]");
+
+    // the distinct attribute of the query replaces the one of the index
+    let (response, code) = index.search_post(json!({ "distinct": "color" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_ids(&response)), @"[1, 2, 3, 4, 7, 8, 9, 12, 14]");
+
+    let (response, code) = index.search_get("distinct=color").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_ids(&response)), @"[1, 2, 3, 4, 7, 8, 9, 12, 14]");
+
+    // the distinct attribute of the query must be filterable
+    let (response, code) = index.search_post(json!({ "distinct": "brand" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Attribute `brand` is not filterable and cannot be used as a distinct attribute. Available filterable attributes are: `color`.",
+      "code": "invalid_search_distinct",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_distinct"
+    }
+    "###);
+}
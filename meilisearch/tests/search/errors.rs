@@ -303,6 +303,90 @@ async fn search_bad_show_matches_position() {
     "###);
 }
 
+#[actix_rt::test]
+async fn search_bad_matches_position_unit() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"matchesPositionUnit": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.matchesPositionUnit`: expected one of `bytes`, `chars`, `graphemes`",
+      "code": "invalid_search_matches_position_unit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_matches_position_unit"
+    }
+    "###);
+
+    let (response, code) = index.search_get("matchesPositionUnit=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` for parameter `matchesPositionUnit`: expected one of `bytes`, `chars`, `graphemes`",
+      "code": "invalid_search_matches_position_unit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_matches_position_unit"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_show_matched_terms() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"showMatchedTerms": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.showMatchedTerms`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_show_matched_terms",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_show_matched_terms"
+    }
+    "###);
+
+    let (response, code) = index.search_get("showMatchedTerms=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `showMatchedTerms`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_show_matched_terms",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_show_matched_terms"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_show_corrected_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"showCorrectedQuery": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.showCorrectedQuery`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_show_corrected_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_show_corrected_query"
+    }
+    "###);
+
+    let (response, code) = index.search_get("showCorrectedQuery=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `showCorrectedQuery`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_show_corrected_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_show_corrected_query"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn search_bad_facets() {
     let server = Server::new().await;
@@ -478,6 +562,225 @@ async fn search_bad_highlight_post_tag() {
     // Can't make the `highlight_post_tag` fail with a get search since it'll accept anything as a strings.
 }
 
+#[actix_rt::test]
+async fn search_bad_highlight_tags() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"highlightTags": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.highlightTags`: expected an object, but found a string: `\"doggo\"`",
+      "code": "invalid_search_highlight_tags",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_highlight_tags"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"highlightTags": { "title": { "preTag": 1 } }})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.highlightTags.title.preTag`: expected a string, but found a positive integer: `1`",
+      "code": "invalid_search_highlight_tags",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_highlight_tags"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_highlight_escape_html() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"highlightEscapeHtml": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.highlightEscapeHtml`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_highlight_escape_html",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_highlight_escape_html"
+    }
+    "###);
+
+    let (response, code) = index.search_get("highlightEscapeHtml=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `highlightEscapeHtml`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_highlight_escape_html",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_highlight_escape_html"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_snippet() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"snippet": { "length": 10 }})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `attributes` inside `.snippet`",
+      "code": "invalid_search_snippet",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippet"
+    }
+    "###);
+
+    let (response, code) = index.search_get("snippetAttributes=title&snippetLength=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `snippetLength`: could not parse `doggo` as a positive integer",
+      "code": "invalid_search_snippet",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_snippet"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_distinct() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"distinct": ["doggo"]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.distinct`: expected a string, but found an array: `[\"doggo\"]`",
+      "code": "invalid_search_distinct",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_distinct"
+    }
+    "###);
+    // Can't make the `distinct` fail with a get search since it'll accept anything as a string.
+}
+
+#[actix_rt::test]
+async fn search_bad_structured_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = index.create(None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({"structuredQuery": ["doggo"]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.structuredQuery`: expected an object, but found an array: `[\"doggo\"]`",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({"q": "doggo", "structuredQuery": {"must": [{"term": "doggo"}]}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: `q` and `structuredQuery` cannot be used together.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"structuredQuery": {"must": [{"term": "doggo", "phrase": "good doggo"}]}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: invalid `structuredQuery.must` clause, a clause must contain exactly one of `term`, `phrase` or `filter`.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"structuredQuery": {"should": [{"filter": "age > 2"}]}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: invalid `structuredQuery.should` clause, a `filter` can only be used in the `must` and `mustNot` clauses.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({"structuredQuery": {"must": [{"term": "doggo", "boost": 2.0}]}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: invalid `structuredQuery.must` clause, a `boost` can only be used in the `should` clauses.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"structuredQuery": {"should": [{"vector": [0.1, 0.2]}]}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: invalid `structuredQuery.should` clause, `vector` clauses are not supported, use the `vector` or `hybrid` parameters to combine the query with a vector search.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"structuredQuery": {"must": [{"bool": {"should": [{"term": "doggo"}]}}]}}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: invalid `structuredQuery.must` clause, nested `bool` clauses are not supported.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(
+            json!({"structuredQuery": {"should": [{"term": "doggo"}]}, "matchingStrategy": "all"}),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: `matchingStrategy` cannot be used with `structuredQuery`, the documents must match all the `must` clauses and are ranked by the `should` clauses they match.",
+      "code": "invalid_search_structured_query",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_structured_query"
+    }
+    "###);
+    // Can't make the `structuredQuery` fail with a get search since it can't be passed as a query parameter.
+}
+
 #[actix_rt::test]
 async fn search_bad_crop_marker() {
     let server = Server::new().await;
@@ -505,32 +808,215 @@ async fn search_bad_matching_strategy() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown value `doggo` at `.matchingStrategy`: expected one of `last`, `all`",
+      "message": "Unknown value `doggo` at `.matchingStrategy`: expected one of `last`, `all`, `frequency`",
       "code": "invalid_search_matching_strategy",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_search_matching_strategy"
     }
     "###);
 
-    let (response, code) = index.search_post(json!({"matchingStrategy": {"doggo": "doggo"}})).await;
+    let (response, code) = index.search_post(json!({"matchingStrategy": {"doggo": "doggo"}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.matchingStrategy`: expected a string, but found an object: `{\"doggo\":\"doggo\"}`",
+      "code": "invalid_search_matching_strategy",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_matching_strategy"
+    }
+    "###);
+
+    let (response, code) = index.search_get("matchingStrategy=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` for parameter `matchingStrategy`: expected one of `last`, `all`, `frequency`",
+      "code": "invalid_search_matching_strategy",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_matching_strategy"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_ranking_score_threshold() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"rankingScoreThreshold": 1.5})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingScoreThreshold`: the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`.",
+      "code": "invalid_search_ranking_score_threshold",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_score_threshold"
+    }
+    "###);
+
+    let (response, code) = index.search_get("rankingScoreThreshold=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `rankingScoreThreshold`: the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`.",
+      "code": "invalid_search_ranking_score_threshold",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_score_threshold"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_group_by() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = index.create(None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({"groupBy": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.groupBy`: expected an object, but found a string: `\"doggo\"`",
+      "code": "invalid_search_group_by",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_group_by"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"groupBy": { "attribute": "doggo", "limit": 2 }})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Attribute `doggo` is not filterable and cannot be used to group the hits. This index does not have configured filterable attributes.",
+      "code": "invalid_search_group_by",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_group_by"
+    }
+    "###);
+
+    let (response, code) = index.search_get("groupByAttribute=doggo&groupByLimit=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `groupByLimit`: could not parse `doggo` as a positive integer",
+      "code": "invalid_search_group_by",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_group_by"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_timeout_ms() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"timeoutMs": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.timeoutMs`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_timeout_ms",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_timeout_ms"
+    }
+    "###);
+
+    let (response, code) = index.search_get("timeoutMs=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `timeoutMs`: could not parse `doggo` as a positive integer",
+      "code": "invalid_search_timeout_ms",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_timeout_ms"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_search_after() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"searchAfter": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.searchAfter`: the value of `searchAfter` is invalid, expected an empty string or the `nextSearchAfter` cursor of a previous search.",
+      "code": "invalid_search_search_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_search_after"
+    }
+    "###);
+
+    let (response, code) = index.search_get("searchAfter=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `searchAfter`: the value of `searchAfter` is invalid, expected an empty string or the `nextSearchAfter` cursor of a previous search.",
+      "code": "invalid_search_search_after",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_search_after"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_count_only() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"countOnly": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.countOnly`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_count_only",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_count_only"
+    }
+    "###);
+
+    let (response, code) = index.search_get("countOnly=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `countOnly`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_count_only",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_count_only"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_early_termination() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"earlyTermination": "doggo"})).await;
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Invalid value type at `.matchingStrategy`: expected a string, but found an object: `{\"doggo\":\"doggo\"}`",
-      "code": "invalid_search_matching_strategy",
+      "message": "Invalid value type at `.earlyTermination`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_early_termination",
       "type": "invalid_request",
-      "link": "https://docs.meilisearch.com/errors#invalid_search_matching_strategy"
+      "link": "https://docs.meilisearch.com/errors#invalid_search_early_termination"
     }
     "###);
 
-    let (response, code) = index.search_get("matchingStrategy=doggo").await;
+    let (response, code) = index.search_get("earlyTermination=doggo").await;
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown value `doggo` for parameter `matchingStrategy`: expected one of `last`, `all`",
-      "code": "invalid_search_matching_strategy",
+      "message": "Invalid value in parameter `earlyTermination`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_early_termination",
       "type": "invalid_request",
-      "link": "https://docs.meilisearch.com/errors#invalid_search_matching_strategy"
+      "link": "https://docs.meilisearch.com/errors#invalid_search_early_termination"
     }
     "###);
 }
@@ -547,7 +1033,7 @@ async fn filter_invalid_syntax_object() {
     index.wait_task(1).await;
 
     let expected_response = json!({
-        "message": "Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `title & Glass`.\n1:14 title & Glass",
+        "message": "Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `title & Glass`.\n1:14 title & Glass",
         "code": "invalid_search_filter",
         "type": "invalid_request",
         "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
@@ -572,7 +1058,7 @@ async fn filter_invalid_syntax_array() {
     index.wait_task(1).await;
 
     let expected_response = json!({
-        "message": "Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `_geoRadius`, or `_geoBoundingBox` at `title & Glass`.\n1:14 title & Glass",
+        "message": "Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `title & Glass`.\n1:14 title & Glass",
         "code": "invalid_search_filter",
         "type": "invalid_request",
         "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
@@ -870,6 +1356,66 @@ async fn sort_reserved_attribute() {
         .await;
 }
 
+#[actix_rt::test]
+async fn sort_invalid_modifiers() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"sortableAttributes": ["id"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let expected_response = json!({
+        "message": "Invalid sort modifiers in `id:asc:min:max`: expected at most one of `:nullsFirst` or `:nullsLast` and one of `:min`, `:max` or `:avg` after the `:asc` or `:desc` of a field.",
+        "code": "invalid_search_sort",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_search_sort"
+    });
+    index
+        .search(
+            json!({
+                "sort": ["id:asc:min:max"]
+            }),
+            |response, code| {
+                assert_eq!(response, expected_response);
+                assert_eq!(code, 400);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn sort_bad_random() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"sortableAttributes": ["id"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let expected_response = json!({
+        "message": "Invalid syntax for the random sort parameter: expected expression formatted like `_random(seed)` with an unsigned integer seed, found `_random(doggo)`.",
+        "code": "invalid_search_sort",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_search_sort"
+    });
+    index
+        .search(
+            json!({
+                "sort": ["_random(doggo)"]
+            }),
+            |response, code| {
+                assert_eq!(response, expected_response);
+                assert_eq!(code, 400);
+            },
+        )
+        .await;
+}
+
 #[actix_rt::test]
 async fn sort_unsortable_attribute() {
     let server = Server::new().await;
@@ -1038,3 +1584,278 @@ async fn search_on_unknown_field_plus_joker() {
         )
         .await;
 }
+
+#[actix_rt::test]
+async fn search_bad_extended_facet_stats() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"extendedFacetStats": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.extendedFacetStats`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_extended_facet_stats",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_extended_facet_stats"
+    }
+    "###);
+
+    let (response, code) = index.search_get("extendedFacetStats=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `extendedFacetStats`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_extended_facet_stats",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_extended_facet_stats"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_aggregations() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = index.create(None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({"aggregations": {"price": ["doggo"]}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.aggregations.price[0]`: expected one of `sum`, `avg`, `min`, `max`, `percentiles`",
+      "code": "invalid_search_aggregations",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_aggregations"
+    }
+    "###);
+
+    let (response, code) = index.search_post(json!({"aggregations": {"price": ["sum"]}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Attribute `price` is not filterable and cannot be aggregated. This index does not have configured filterable attributes.",
+      "code": "invalid_search_aggregations",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_aggregations"
+    }
+    "###);
+
+    let (response, code) = index.search_get("aggregations=price:doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `aggregations`: the value of `aggregations` is invalid, expected `attribute:aggregation` pairs separated by commas, the aggregation being one of `sum`, `avg`, `min`, `max` or `percentiles`.",
+      "code": "invalid_search_aggregations",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_aggregations"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_embed_foreign_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"embedForeignDocuments": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.embedForeignDocuments`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_search_embed_foreign_documents",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_embed_foreign_documents"
+    }
+    "###);
+
+    let (response, code) = index.search_get("embedForeignDocuments=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `embedForeignDocuments`: could not parse `doggo` as a boolean, expected either `true` or `false`",
+      "code": "invalid_search_embed_foreign_documents",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_embed_foreign_documents"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_foreign_filters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = index.create(None).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({"foreignFilters": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.foreignFilters`: expected an object, but found a string: `\"doggo\"`",
+      "code": "invalid_search_foreign_filters",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_foreign_filters"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"foreignFilters": {"authorId": "country = FR"}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid request: the attribute `authorId` of `foreignFilters` is not a foreign key of the index.",
+      "code": "invalid_search_foreign_filters",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_foreign_filters"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_pinned_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"pinnedDocuments": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.pinnedDocuments`: expected an array, but found a string: `\"doggo\"`",
+      "code": "invalid_search_pinned_documents",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_pinned_documents"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_hidden_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"hiddenDocuments": [1]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.hiddenDocuments[0]`: expected a string, but found a positive integer: `1`",
+      "code": "invalid_search_hidden_documents",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hidden_documents"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_vocabulary() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"vocabulary": {"stopWords": "the"}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.vocabulary.stopWords`: expected an array, but found a string: `\"the\"`",
+      "code": "invalid_search_vocabulary",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_vocabulary"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_phrase_slop() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"phraseSlop": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.phraseSlop`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_phrase_slop",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_phrase_slop"
+    }
+    "###);
+
+    let (response, code) = index.search_get("phraseSlop=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `phraseSlop`: could not parse `doggo` as a positive integer",
+      "code": "invalid_search_phrase_slop",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_phrase_slop"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_sort_facet_values_by() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"sortFacetValuesBy": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` at `.sortFacetValuesBy`: expected one of `alpha`, `count`",
+      "code": "invalid_search_sort_facet_values_by",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sort_facet_values_by"
+    }
+    "###);
+
+    let (response, code) = index.search_get("sortFacetValuesBy=doggo").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `doggo` for parameter `sortFacetValuesBy`: expected one of `alpha`, `count`",
+      "code": "invalid_search_sort_facet_values_by",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_sort_facet_values_by"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_bad_disjunctive_facets() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({"disjunctiveFacets": "doggo"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.disjunctiveFacets`: expected an array, but found a string: `\"doggo\"`",
+      "code": "invalid_search_disjunctive_facets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_disjunctive_facets"
+    }
+    "###);
+    // Can't make the `disjunctive_facets` fail with a get search since it'll accept anything as an array of strings.
+}
+
+#[actix_rt::test]
+async fn search_non_filterable_disjunctive_facets() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+    index.wait_task(0).await;
+
+    let (response, code) =
+        index.search_post(json!({"filter": "title = Kefir", "disjunctiveFacets": ["doggo"]})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid facet distribution, attribute `doggo` is not filterable. The available filterable attribute is `title`.",
+      "code": "invalid_search_disjunctive_facets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_disjunctive_facets"
+    }
+    "###);
+}
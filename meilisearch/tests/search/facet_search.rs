@@ -139,3 +139,38 @@ async fn facet_search_dont_support_words() {
     assert_eq!(code, 200, "{}", response);
     assert_eq!(response["facetHits"].as_array().unwrap().len(), 0);
 }
+
+#[actix_rt::test]
+async fn facet_search_with_typos_and_filter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "acton"})).await;
+
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":3}]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "acton", "filter": "genres = Comedy"}),
+        )
+        .await;
+
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @r###"[{"value":"Action","count":1}]"###);
+
+    let (response, code) = index
+        .facet_search(
+            json!({"facetName": "genres", "facetQuery": "adventre", "filter": "genres = Comedy"}),
+        )
+        .await;
+
+    snapshot!(code, @"200 OK");
+    snapshot!(response["facetHits"], @"[]");
+}
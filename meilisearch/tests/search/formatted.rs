@@ -568,3 +568,247 @@ async fn test_cjk_highlight() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn matched_terms() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "synonyms": { "film": ["movie"] } })).await;
+    let documents = json!([
+        { "id": 0, "title": "Captain Marvel", "desc": "A captain movie about Marvel" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "q": "captian film marvel", "attributesToRetrieve": ["id"], "showMatchedTerms": true }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"][0]), @r###"
+                {
+                  "id": 0,
+                  "_matchedTerms": {
+                    "captain": [
+                      "desc",
+                      "title"
+                    ],
+                    "marvel": [
+                      "desc",
+                      "title"
+                    ],
+                    "movie": [
+                      "desc"
+                    ]
+                  }
+                }
+                "###);
+            },
+        )
+        .await;
+
+    // the matched terms are not returned by default
+    index
+        .search(json!({ "q": "marvel", "attributesToRetrieve": ["id"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["hits"][0], json!({ "id": 0 }));
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn highlight_tags_and_escape_html() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "title": "<b>Captain</b> Marvel", "desc": "A captain & Marvel movie" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // the stored markup is escaped but not the highlight tags
+    index
+        .search(
+            json!({
+                "q": "captain",
+                "attributesToRetrieve": ["id"],
+                "attributesToHighlight": ["title", "desc"],
+                "highlightTags": { "desc": { "preTag": "<mark>", "postTag": "</mark>" } },
+                "highlightEscapeHtml": true,
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"][0]["_formatted"]), @r###"
+                {
+                  "id": "0",
+                  "title": "&lt;b&gt;<em>Captain</em>&lt;/b&gt; Marvel",
+                  "desc": "A <mark>captain</mark> &amp; Marvel movie"
+                }
+                "###);
+            },
+        )
+        .await;
+
+    // the missing tags of an attribute are the ones of the query
+    index
+        .search(
+            json!({
+                "q": "captain",
+                "attributesToRetrieve": ["id"],
+                "attributesToHighlight": ["*"],
+                "highlightPreTag": "[",
+                "highlightPostTag": "]",
+                "highlightTags": { "t*": { "preTag": "<" } },
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"][0]["_formatted"]), @r###"
+                {
+                  "id": "0",
+                  "title": "<b><Captain]</b> Marvel",
+                  "desc": "A [captain] & Marvel movie"
+                }
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn unified_snippet() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        {
+            "id": 0,
+            "title": "Captain Marvel",
+            "body": "Carol Danvers is caught in a galactic war between two alien races.",
+            "comments": ["A great Marvel movie", "Boring"],
+        },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // the attributes are cropped to the characters left in the budget
+    index
+        .search(
+            json!({
+                "q": "marvel war",
+                "attributesToRetrieve": ["id"],
+                "snippet": { "attributes": ["title", "body", "comments"], "length": 60 },
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"][0]), @r###"
+                {
+                  "id": 0,
+                  "_snippet": "Captain <em>Marvel</em> …caught in a galactic <em>war</em> between two alien…"
+                }
+                "###);
+            },
+        )
+        .await;
+
+    // the texts matching the most words are picked first but are kept in the order of the attributes
+    index
+        .search(
+            json!({
+                "q": "marvel movie",
+                "attributesToRetrieve": ["id"],
+                "snippet": { "attributes": ["title", "body", "comments"], "length": 30 },
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"][0]), @r###"
+                {
+                  "id": 0,
+                  "_snippet": "…<em>Marvel</em> A great <em>Marvel</em> <em>movie</em>"
+                }
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn matches_position_unit() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    // the family emoji is a single grapheme made of 5 chars and 18 bytes
+    let documents = json!([{ "id": 0, "title": "👨‍👩‍👦 café" }]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // by default the start is in bytes and the length in chars
+    for (unit, start, length) in [
+        (json!(null), 19, 4),
+        (json!("bytes"), 19, 5),
+        (json!("chars"), 6, 4),
+        (json!("graphemes"), 2, 4),
+    ] {
+        index
+            .search(
+                json!({
+                    "q": "cafe",
+                    "attributesToRetrieve": ["id"],
+                    "showMatchesPosition": true,
+                    "matchesPositionUnit": unit,
+                }),
+                |response, code| {
+                    assert_eq!(code, 200, "{}", response);
+                    assert_eq!(
+                        response["hits"][0]["_matchesPosition"],
+                        json!({ "title": [{ "start": start, "length": length }] }),
+                        "{unit}"
+                    );
+                },
+            )
+            .await;
+    }
+}
+
+#[actix_rt::test]
+async fn retrieve_nested_attributes_of_displayed_attribute() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([{
+        "id": 0,
+        "title": "Le Procope",
+        "address": { "city": "Paris", "street": "rue de l'Ancienne Comédie" },
+        "reviews": [{ "rating": 4, "author": "kero" }, { "rating": 5, "author": "tamo" }],
+    }]);
+    index.update_settings_displayed_attributes(json!(["id", "address", "reviews"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "q": "procope", "attributesToRetrieve": ["address.city", "reviews.rating", "title"] }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                    assert_json_snapshot!(response["hits"][0], @r###"
+                    {
+                      "address": {
+                        "city": "Paris"
+                      },
+                      "reviews": [
+                        {
+                          "rating": 4
+                        },
+                        {
+                          "rating": 5
+                        }
+                      ]
+                    }
+                    "###)
+                }
+            },
+        )
+        .await;
+}
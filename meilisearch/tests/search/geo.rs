@@ -117,3 +117,188 @@ async fn geo_bounding_box_with_string_and_number() {
         )
         .await;
 }
+
+#[actix_rt::test]
+async fn geo_polygon_with_string_and_number() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoPolygon([[45, 9], [46, 9], [46, 10], [45, 10]])",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 2
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    // a multi-polygon matches the documents inside any of its polygons
+    index
+        .search(
+            json!({
+                "filter": "_geoPolygon([[45, 9], [46, 9], [46, 10], [45, 10]], [[34, -119], [35, -118], [33, -118]])",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  },
+                  {
+                    "id": 2
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn geojson_within_and_intersects() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "name": "Park", "_geojson": { "type": "Polygon", "coordinates": [[[9.1, 45.1], [9.3, 45.1], [9.3, 45.3], [9.1, 45.3], [9.1, 45.1]]] } },
+        { "id": 2, "name": "Highway", "_geojson": { "type": "LineString", "coordinates": [[8.5, 45.2], [10.5, 45.2]] } },
+        { "id": 3, "name": "Beach", "_geojson": { "type": "Point", "coordinates": [-118.5, 34.0] } },
+    ]);
+    index.update_settings_filterable_attributes(json!(["_geojson"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoWithin([[45, 9], [46, 9], [46, 10], [45, 10]])",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "_geoIntersects([[45, 9], [46, 9], [46, 10], [45, 10]])",
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  },
+                  {
+                    "id": 2
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    let (task, _code) = index
+        .add_documents(json!([{ "id": 4, "_geojson": { "type": "Circle", "radius": 12 } }]), None)
+        .await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["code"], @r###""invalid_document_geojson_field""###);
+}
+
+#[actix_rt::test]
+async fn geo_distance_from_reference_point() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["_geo"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // the distance is computed without any `_geoPoint` sort or `_geoRadius` filter
+    index
+        .search(
+            json!({
+                "filter": "_geoBoundingBox([46, 10], [45, 9])",
+                "attributesToRetrieve": ["id", "_geo"],
+                "geoReferencePoint": [45.4777599, 9.1967508],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 2,
+                    "_geo": {
+                      "lat": "45.4777599",
+                      "lng": "9.1967508"
+                    },
+                    "_geoDistance": 0
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    // the documents without `_geo` field don't have any distance
+    index
+        .search(
+            json!({
+                "q": "Billig",
+                "attributesToRetrieve": ["id", "_geo"],
+                "geoReferencePoint": [45.4777599, 9.1967508],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 3
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    let (response, code) = index.search_post(json!({ "geoReferencePoint": [100, 9] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.geoReferencePoint`: the value of `geoReferencePoint` is invalid, expected a latitude between `-90` and `90` and a longitude between `-180` and `180`: `[latitude, longitude]`.",
+      "code": "invalid_search_geo_reference_point",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_geo_reference_point"
+    }
+    "###);
+}
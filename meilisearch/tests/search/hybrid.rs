@@ -1,7 +1,7 @@
-use meili_snap::snapshot;
+use meili_snap::{json_string, snapshot};
 use once_cell::sync::Lazy;
 
-use crate::common::index::Index;
+use crate::common::index::{GetAllDocumentsOptions, Index};
 use crate::common::{Server, Value};
 use crate::json;
 
@@ -16,7 +16,11 @@ async fn index_with_documents<'a>(server: &'a Server, documents: &Value) -> Inde
       "scoreDetails": false,
       "vectorStore": true,
       "metrics": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "shadowSettingsUpdate": false,
+      "chatCompletions": false,
+      "searchAnalytics": false,
+      "changeFeed": false
     }
     "###);
 
@@ -78,6 +82,249 @@ async fn simple_search() {
     snapshot!(response["hits"], @r###"[{"title":"Captain Marvel","desc":"a Shazam ersatz","id":"3","_vectors":{"default":[2.0,3.0]},"_semanticScore":0.99029034},{"title":"Captain Planet","desc":"He's not part of the Marvel Cinematic Universe","id":"2","_vectors":{"default":[1.0,2.0]},"_semanticScore":0.97434163},{"title":"Shazam!","desc":"a Captain Marvel ersatz","id":"1","_vectors":{"default":[1.0,3.0]},"_semanticScore":0.9472136}]"###);
 }
 
+#[actix_rt::test]
+async fn retrieve_vectors() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [2.0, 3.0],
+            "hybrid": {"semanticRatio": 1.0},
+            "attributesToRetrieve": ["id"],
+            "retrieveVectors": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits = response["hits"].as_array().unwrap();
+    assert_eq!(hits[0]["id"], json!("3"));
+    assert_eq!(hits[0]["_vectors"], json!({"default": [[2.0, 3.0]]}));
+    assert_eq!(hits[1]["_vectors"]["default"].as_array().unwrap().len(), 1);
+    assert!(hits.iter().all(|hit| hit["_semanticScore"].is_number()));
+
+    // the embeddings are not retrieved when `_vectors` is not a displayed attribute
+    let (response, code) = index.update_settings(json!({"displayedAttributes": ["id"]})).await;
+    assert_eq!(202, code, "{:?}", response);
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [2.0, 3.0],
+            "hybrid": {"semanticRatio": 1.0},
+            "retrieveVectors": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    let hits = response["hits"].as_array().unwrap();
+    assert_eq!(hits[0]["id"], json!("3"));
+    assert!(hits.iter().all(|hit| hit.get("_vectors").is_none()));
+
+    let (response, code) = index
+        .search_post(json!({"q": "Captain", "attributesToRetrieve": ["id"], "retrieveVectors": 1}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value type at `.retrieveVectors`: expected a boolean, but found a positive integer: `1`",
+      "code": "invalid_search_retrieve_vectors",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_retrieve_vectors"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn similar_documents() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index.similar_get("3", "attributesToRetrieve=id").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "2"
+      },
+      {
+        "id": "1"
+      }
+    ]
+    "###);
+
+    let (response, code) = index.similar_get("3", "attributesToRetrieve=id&limit=1").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "2"
+      }
+    ]
+    "###);
+
+    let (response, code) = index.similar_get("4", "").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response, @r###"
+    {
+      "message": "Document `4` not found.",
+      "code": "document_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#document_not_found"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn fusion_strategies() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let searches = [
+        (json!({"semanticRatio": 0.2, "fusion": "reciprocalRank"}), ["2", "3", "1"]),
+        (json!({"semanticRatio": 0.8, "fusion": "reciprocalRank"}), ["3", "2", "1"]),
+        // the keyword scores of "Captain Planet" and "Captain Marvel" are equal
+        (json!({"semanticRatio": 0.5, "fusion": "normalized"}), ["3", "2", "1"]),
+        (json!({"semanticRatio": 0.2, "fusion": "linear"}), ["2", "3", "1"]),
+    ];
+
+    for (hybrid, expected) in searches {
+        let (response, code) = index
+            .search_post(json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": hybrid.clone()}))
+            .await;
+        assert_eq!(code, 200, "{}", response);
+        let ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        assert_eq!(json!(ids), json!(expected), "{hybrid}");
+    }
+}
+
+#[actix_rt::test]
+async fn invalid_fusion() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .search_post(json!({"q": "Captain", "vector": [1.0, 1.0], "hybrid": {"fusion": "rrf"}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Unknown value `rrf` at `.hybrid.fusion`: expected one of `linear`, `normalized`, `reciprocalRank`",
+      "code": "invalid_search_hybrid_fusion",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_fusion"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn semantic_only_search() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    // the keywords don't retrieve any candidate, "Captain Planet" doesn't contain "Shazam"
+    for candidate_selection in ["auto", "approximate", "exhaustive"] {
+        let (response, code) = index
+            .search_post(json!({
+                "q": "Shazam",
+                "vector": [2.0, 3.0],
+                "hybrid": {"semanticRatio": 1.0, "candidateSelection": candidate_selection},
+                "attributesToRetrieve": ["id"],
+            }))
+            .await;
+        snapshot!(code, @"200 OK");
+        snapshot!(response["hits"], @r###"[{"id":"3"},{"id":"2"},{"id":"1"}]"###);
+    }
+
+    let (response, code) = index
+        .search_post(json!({
+            "vector": [2.0, 3.0],
+            "hybrid": {"semanticRatio": 1.0, "candidateSelection": "exhaustive"},
+            "attributesToRetrieve": ["id"],
+            "hiddenDocuments": ["3"],
+            "limit": 1,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"id":"2"}]"###);
+
+    let (response, code) = index
+        .search_post(json!({"vector": [2.0, 3.0], "hybrid": {"candidateSelection": "exact"}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Unknown value `exact` at `.hybrid.candidateSelection`: expected one of `auto`, `approximate`, `exhaustive`",
+      "code": "invalid_search_hybrid_candidate_selection",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_candidate_selection"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn semantic_search_negative_words() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    // "Captain Planet" is the second most similar document but contains a negative word
+    for semantic_ratio in [1.0, 0.8] {
+        let (response, code) = index
+            .search_post(json!({
+                "q": "Captain -Planet",
+                "vector": [1.0, 1.0],
+                "hybrid": {"semanticRatio": semantic_ratio},
+                "attributesToRetrieve": ["id"],
+            }))
+            .await;
+        snapshot!(code, @"200 OK");
+        snapshot!(response["hits"], @r###"[{"id":"3"},{"id":"1"}]"###);
+    }
+}
+
+#[actix_rt::test]
+async fn invalid_embedders() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) = index
+        .search_post(json!({"q": "Captain", "hybrid": {"embedder": "default", "embedders": {"default": 1.0}}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid request: `hybrid.embedder` and `hybrid.embedders` cannot be used together.",
+      "code": "invalid_search_hybrid_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_embedders"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({"q": "Captain", "hybrid": {"embedders": {"default": -1.0}}}))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid request: the weight of the embedder `default` in `hybrid.embedders` must be a positive number, found `-1`.",
+      "code": "invalid_search_hybrid_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_embedders"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"q": "Captain", "hybrid": {"embedders": {"default": 0.0}}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid request: the weight of the embedder `default` in `hybrid.embedders` must be a positive number, found `0`.",
+      "code": "invalid_search_hybrid_embedders",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_hybrid_embedders"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn invalid_semantic_ratio() {
     let server = Server::new().await;
@@ -149,3 +396,53 @@ async fn invalid_semantic_ratio() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn embedder_request_policy() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = server.set_features(json!({"vectorStore": true})).await;
+    snapshot!(code, @"200 OK");
+
+    // nothing listens on this port, every request to the embedder fails at runtime
+    let (response, code) = index
+        .update_settings(json!({ "embedders": { "default": {
+            "source": { "rest": { "url": "http://127.0.0.1:1", "dimensions": 2 } },
+            "requestPolicy": { "maxConcurrentRequests": 1, "maxRetries": 1, "initialBackoffMs": 1, "maxFailedDocuments": 2 },
+        }}}))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["embedders"]["default"]["requestPolicy"]), @r###"
+    {
+      "maxConcurrentRequests": 1,
+      "maxRequestsPerSecond": null,
+      "maxRetries": 1,
+      "initialBackoffMs": 1,
+      "maxFailedDocuments": 2
+    }
+    "###);
+
+    // the documents are indexed without their embeddings
+    let (response, code) =
+        index.add_documents(json!([{ "id": 1, "title": "Shazam!" }, { "id": 2 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(response["details"]["indexedDocuments"], @"2");
+    snapshot!(response["details"]["embeddingFailures"]["default"]["failedDocuments"], @"2");
+    assert!(response["details"]["embeddingFailures"]["default"]["lastError"].is_string());
+
+    // past the tolerated failures the whole batch fails
+    let (response, code) =
+        index.add_documents(json!([{ "id": 3 }, { "id": 4 }, { "id": 5 }]), None).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    let (response, _) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(response["total"], @"2");
+}
@@ -1,6 +1,8 @@
 // This modules contains all the test concerning search. Each particular feature of the search
 // should be tested in its own module to isolate tests and keep the tests readable.
 
+mod analytics;
+mod cache;
 mod distinct;
 mod errors;
 mod facet_search;
@@ -10,6 +12,7 @@ mod hybrid;
 mod multi;
 mod pagination;
 mod restrict_searchable;
+mod suggest;
 
 use once_cell::sync::Lazy;
 
@@ -163,6 +166,44 @@ async fn simple_search() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_corrected_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(
+            json!({"q": "escepe room", "attributesToRetrieve": ["id"], "showCorrectedQuery": true}),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                meili_snap::snapshot!(meili_snap::json_string!(response["correctedQuery"]), @r###""escape room""###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({"q": "escape room", "attributesToRetrieve": ["id"], "showCorrectedQuery": true}),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                meili_snap::snapshot!(meili_snap::json_string!(response["correctedQuery"]), @"null");
+            },
+        )
+        .await;
+
+    // the corrected query is only computed when requested
+    index
+        .search(json!({"q": "escepe room", "attributesToRetrieve": ["id"]}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            meili_snap::snapshot!(meili_snap::json_string!(response["correctedQuery"]), @"null");
+        })
+        .await;
+}
+
 #[actix_rt::test]
 async fn phrase_search_with_stop_word() {
     // related to https://github.com/meilisearch/meilisearch/issues/3521
@@ -184,6 +225,110 @@ async fn phrase_search_with_stop_word() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_with_negative_terms() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(
+            json!({"q": "-\"hidden world\" -room", "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "287947"
+                  },
+                  {
+                    "id": "299537"
+                  },
+                  {
+                    "id": "450465"
+                  }
+                ]
+                "###);
+                meili_snap::snapshot!(response["estimatedTotalHits"], @"3");
+            },
+        )
+        .await;
+
+    // the dash of a negative term must follow a whitespace
+    index
+        .search(json!({"q": "escape-room", "attributesToRetrieve": ["id"] }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": "522681"
+              }
+            ]
+            "###);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_structured_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(
+            json!({
+                "structuredQuery": { "mustNot": [{ "phrase": "hidden world" }, { "term": "room" }] },
+                "attributesToRetrieve": ["id"]
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "287947"
+                  },
+                  {
+                    "id": "299537"
+                  },
+                  {
+                    "id": "450465"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "structuredQuery": {
+                    "must": [{ "term": "captain" }],
+                    "should": [{ "term": "marvel", "boost": 2.0 }]
+                },
+                "attributesToRetrieve": ["id"]
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "299537"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
 #[cfg(feature = "default")]
 #[actix_rt::test]
 async fn test_kanji_language_detection() {
@@ -372,6 +517,323 @@ async fn search_with_filter_array_notation() {
     assert_eq!(response["hits"].as_array().unwrap().len(), 3);
 }
 
+#[actix_rt::test]
+async fn search_with_exists_null_and_empty_filters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["author"]})).await;
+
+    let documents = json!([
+        { "id": 1, "author": null },
+        { "id": 2, "author": "" },
+        { "id": 3, "author": [] },
+        { "id": 4, "author": { "name": null } },
+        { "id": 5, "author": { "name": "Ursula" } },
+        { "id": 6 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let filters = [
+        ("author EXISTS", vec![1, 2, 3, 4, 5]),
+        ("author NOT EXISTS", vec![6]),
+        ("author IS NULL", vec![1]),
+        ("author IS NOT NULL", vec![2, 3, 4, 5, 6]),
+        ("author IS EMPTY", vec![2, 3]),
+        ("author.name EXISTS", vec![4, 5]),
+        ("author.name IS NULL", vec![4]),
+        ("author.name IS NOT NULL AND author.name EXISTS", vec![5]),
+    ];
+
+    for (filter, expected) in filters {
+        let (response, code) = index.search_post(json!({ "filter": filter })).await;
+        assert_eq!(code, 200, "{}", response);
+        let ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        assert_eq!(json!(ids), json!(expected), "{filter}");
+    }
+}
+
+#[actix_rt::test]
+async fn search_with_nested_object_filters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["reviews"]})).await;
+
+    let documents = json!([
+        { "id": 1, "reviews": [{ "author": "bob", "rating": 5 }, { "author": "alice", "rating": 2 }] },
+        { "id": 2, "reviews": [{ "author": "bob", "rating": 2 }, { "author": "alice", "rating": 5 }] },
+        { "id": 3, "reviews": [{ "author": "alice", "rating": 4 }] },
+        { "id": 4 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let filters = [
+        ("reviews.author = bob AND reviews.rating > 4", vec![1, 2]),
+        ("reviews.{author = bob AND rating > 4}", vec![1]),
+        ("reviews.{author = alice AND rating >= 4}", vec![2, 3]),
+        ("reviews.{author = bob} AND NOT reviews.{rating < 3}", vec![]),
+        ("NOT reviews.{author = bob}", vec![3, 4]),
+    ];
+
+    for (filter, expected) in filters {
+        let (response, code) = index.search_post(json!({ "filter": filter })).await;
+        assert_eq!(code, 200, "{}", response);
+        let ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        assert_eq!(json!(ids), json!(expected), "{filter}");
+    }
+}
+
+#[actix_rt::test]
+async fn search_with_query_rules() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["brand", "onSale"],
+            "queryRules": [
+                {
+                    "id": "sneakers",
+                    "conditions": { "query": "shoes", "anchoring": "contains" },
+                    "consequences": { "pin": ["4", "99"] },
+                },
+                {
+                    "id": "sale",
+                    "conditions": { "filter": "brand = nike" },
+                    "consequences": { "boost": "onSale = true" },
+                },
+                {
+                    "id": "adidas",
+                    "conditions": { "query": "socks" },
+                    "consequences": { "filter": "brand = adidas" },
+                },
+            ],
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "title": "red shoes", "brand": "adidas", "onSale": false },
+        { "id": 2, "title": "blue shoes", "brand": "nike", "onSale": false },
+        { "id": 3, "title": "green shoes", "brand": "nike", "onSale": true },
+        { "id": 4, "title": "running shoes", "brand": "puma", "onSale": false },
+        { "id": 5, "title": "socks", "brand": "nike", "onSale": true },
+        { "id": 6, "title": "socks", "brand": "adidas", "onSale": false },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let searches = [
+        (json!({ "q": "shoes" }), json!([[4, ["sneakers"]], [1, null], [2, null], [3, null]])),
+        (json!({ "q": "shoes", "filter": "brand = nike" }), json!([[3, ["sale"]], [2, null]])),
+        (json!({ "filter": ["brand = nike", "onSale = false"] }), json!([[2, null]])),
+        (json!({ "filter": "brand = nike" }), json!([[3, ["sale"]], [5, ["sale"]], [2, null]])),
+        (json!({ "q": "socks" }), json!([[6, ["adidas"]]])),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let hits: Vec<_> = response["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| json!([hit["id"], hit["_appliedRules"]]))
+            .collect();
+        assert_eq!(json!(hits), expected, "{search}");
+    }
+}
+
+#[actix_rt::test]
+async fn search_with_pinned_and_hidden_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["color"]})).await;
+
+    let documents = json!([
+        { "id": 1, "title": "red shoes", "color": "red" },
+        { "id": 2, "title": "blue shoes", "color": "blue" },
+        { "id": 3, "title": "green shoes", "color": "green" },
+        { "id": 4, "title": "red hat", "color": "red" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let searches = [
+        (json!({ "q": "shoes", "pinnedDocuments": ["3", "unknown", "2"] }), vec![3, 2, 1]),
+        // the pinned documents must match the search
+        (json!({ "q": "shoes", "pinnedDocuments": ["4", "3"] }), vec![3, 1, 2]),
+        (json!({ "filter": "color = red", "pinnedDocuments": ["4"] }), vec![4, 1]),
+        (json!({ "q": "shoes", "hiddenDocuments": ["1", "unknown"] }), vec![2, 3]),
+        (json!({ "pinnedDocuments": ["2"], "hiddenDocuments": ["2", "4"] }), vec![1, 3]),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+
+    let (response, code) = index.search_get("q=shoes&pinnedDocuments=2&hiddenDocuments=1").await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"][0]["id"], json!(2));
+    assert_eq!(response["estimatedTotalHits"], json!(2));
+}
+
+#[actix_rt::test]
+async fn search_with_vocabulary() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({ "synonyms": { "tv": ["television"] }, "stopWords": ["the"] }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "title": "the television set" },
+        { "id": 2, "title": "a new smartphone" },
+        { "id": 3, "title": "the cell phone" },
+        { "id": 4, "title": "laptop computer" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let mobile = json!({ "synonyms": { "mobile": ["smartphone", "cell phone"] } });
+    let searches = [
+        (json!({ "q": "mobile" }), vec![]),
+        (json!({ "q": "mobile", "vocabulary": mobile }), vec![2, 3]),
+        // the synonyms of the index are kept unless disabled
+        (json!({ "q": "tv", "vocabulary": mobile }), vec![1]),
+        (json!({ "q": "tv", "vocabulary": { "disableIndexSynonyms": true } }), vec![]),
+        (json!({ "q": "new laptop", "matchingStrategy": "all" }), vec![]),
+        (
+            json!({ "q": "new laptop", "matchingStrategy": "all", "vocabulary": { "stopWords": ["new"] } }),
+            vec![4],
+        ),
+        // the stop words of the index are not indexed
+        (
+            json!({ "q": "the television", "matchingStrategy": "all", "vocabulary": { "disableIndexStopWords": true } }),
+            vec![],
+        ),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let mut ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64()).collect();
+        ids.sort_unstable();
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+
+    // the vocabulary of a search doesn't change the settings of the index
+    let (response, _) = index.settings().await;
+    assert_eq!(response["synonyms"], json!({ "tv": ["television"] }));
+    assert_eq!(response["stopWords"], json!(["the"]));
+}
+
+#[actix_rt::test]
+async fn search_with_frequency_matching_strategy() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "the cat" },
+        { "id": 2, "title": "the dog" },
+        { "id": 3, "title": "a cat sleeps" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let searches = [
+        (json!({ "q": "the cat", "matchingStrategy": "last" }), vec![1, 2]),
+        // the most frequent word, `the`, is removed first
+        (json!({ "q": "the cat", "matchingStrategy": "frequency" }), vec![1, 3]),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let mut ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64()).collect();
+        ids.sort_unstable();
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+}
+
+#[actix_rt::test]
+async fn search_with_phrase_slop() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "the quick fox" },
+        { "id": 2, "title": "the quick brown fox" },
+        { "id": 3, "title": "the quick and very agile brown fox" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let searches = [
+        (json!({ "q": "\"quick fox\"" }), vec![1]),
+        (json!({ "q": "\"quick fox\"", "phraseSlop": 1 }), vec![1, 2]),
+        (json!({ "q": "\"quick fox\"", "phraseSlop": 5 }), vec![1, 2, 3]),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let mut ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64()).collect();
+        ids.sort_unstable();
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+}
+
+#[actix_rt::test]
+async fn search_with_unreachable_reranker() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "red shoes" },
+        { "id": 2, "title": "blue shoes" },
+        { "id": 3, "title": "green shoes" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({ "q": "shoes", "limit": 2 })).await;
+    assert_eq!(code, 200, "{}", response);
+    let expected = response["hits"].clone();
+    assert!(response.get("reranking").is_none(), "{}", response);
+
+    let (_, code) = index
+        .update_settings(json!({ "reranker": { "url": "http://127.0.0.1:1/rerank", "topN": 3 } }))
+        .await;
+    assert_eq!(code, 202);
+    index.wait_task(1).await;
+
+    // the hits keep the ranking of the search when the reranker is down
+    let (response, code) = index.search_post(json!({ "q": "shoes", "limit": 2 })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"], expected);
+    assert_eq!(response["reranking"]["rerankedHits"], json!(0));
+    assert!(response["reranking"]["fallback"].is_string(), "{}", response);
+
+    // the placeholder searches are not reranked
+    let (response, code) = index.search_post(json!({ "limit": 2 })).await;
+    assert_eq!(code, 200, "{}", response);
+    assert!(response.get("reranking").is_none(), "{}", response);
+}
+
 #[actix_rt::test]
 async fn search_with_sort_on_numbers() {
     let server = Server::new().await;
@@ -458,102 +920,790 @@ async fn search_with_sort_on_strings() {
             },
         )
         .await;
-}
-
-#[actix_rt::test]
-async fn search_with_multiple_sort() {
-    let server = Server::new().await;
-    let index = server.index("test");
+}
+
+#[actix_rt::test]
+async fn search_with_sort_and_filter_on_dates() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["publishedAt"],
+            "sortableAttributes": ["publishedAt"],
+            "dateAttributes": ["publishedAt"],
+        }))
+        .await;
+
+    // lexicographically sorted, the dates with time offsets are not in chronological order
+    let documents = json!([
+        { "id": 1, "publishedAt": "2024-01-01T09:00:00+10:00" },
+        { "id": 2, "publishedAt": "2024-01-01T01:00:00Z" },
+        { "id": 3, "publishedAt": "2023-12-31T23:30:00-05:00" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "sort": ["publishedAt:asc"], "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1
+              },
+              {
+                "id": 2
+              },
+              {
+                "id": 3
+              }
+            ]
+            "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "publishedAt > \"2024-01-01T00:00:00Z\"",
+                "sort": ["publishedAt:desc"],
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 3
+                  },
+                  {
+                    "id": 2
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_static_boost() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = server.set_features(json!({"scoreDetails": true})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(response["scoreDetails"], @"true");
+
+    index
+        .update_settings(json!({
+            "staticBoost": { "attribute": "popularity", "curve": "log" },
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "title": "the quick fox", "popularity": 5 },
+        { "id": 2, "title": "the quick fox", "popularity": 5000 },
+        { "id": 3, "title": "the quick", "popularity": 100000 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // the most popular document doesn't match all the query words
+    index
+        .search(
+            json!({
+                "q": "quick fox",
+                "attributesToRetrieve": ["id"],
+                "showRankingScoreDetails": true,
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                let ids: Vec<_> = response["hits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|hit| hit["id"].as_u64().unwrap())
+                    .collect();
+                assert_eq!(ids, vec![2, 1, 3]);
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"][0]["_rankingScoreDetails"]["staticBoost"]), @r###"
+                {
+                  "order": 4,
+                  "score": 0.7272727272727273
+                }
+                "###);
+            },
+        )
+        .await;
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["staticBoost"], json!({ "attribute": "popularity", "curve": "log" }));
+}
+
+#[actix_rt::test]
+async fn search_with_ranking_score_threshold() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "rankingRules": ["words"] })).await;
+
+    let documents = json!([
+        { "id": 1, "title": "the quick brown fox" },
+        { "id": 2, "title": "the quick brown dog" },
+        { "id": 3, "title": "the quick cat" },
+        { "id": 4, "title": "the lazy fox" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // the documents matching less than 3 of the 4 query words are dropped
+    index
+        .search(
+            json!({
+                "q": "the quick brown fox",
+                "rankingScoreThreshold": 0.7,
+                "limit": 1,
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+                meili_snap::snapshot!(response["estimatedTotalHits"], @"2");
+            },
+        )
+        .await;
+
+    index
+        .search(json!({ "q": "the quick brown fox", "limit": 1 }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(response["estimatedTotalHits"], @"4");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_ranking_expression() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "rankingRules": ["words", "desc(log(popularity) * 2 + rating)"],
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "title": "the quick fox", "popularity": 100, "rating": 1 },
+        { "id": 2, "title": "the quick fox", "popularity": 10, "rating": 6 },
+        { "id": 3, "title": "the quick fox", "rating": 10 },
+        { "id": 4, "title": "the quick", "popularity": 100000, "rating": 5 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // the document without popularity comes last among the documents matching all the words
+    index
+        .search(json!({ "q": "quick fox", "attributesToRetrieve": ["id"] }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            let ids: Vec<_> = response["hits"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|hit| hit["id"].as_u64().unwrap())
+                .collect();
+            assert_eq!(ids, vec![2, 1, 3, 4]);
+        })
+        .await;
+
+    let (response, code) = index.settings().await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["rankingRules"], json!(["words", "desc(log(popularity) * 2 + rating)"]));
+}
+
+#[actix_rt::test]
+async fn search_with_sort_filter_and_facets_on_big_integers() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["snowflake"],
+            "sortableAttributes": ["snowflake"],
+        }))
+        .await;
+
+    // those snowflake ids all round to the same float
+    let documents = json!([
+        { "id": 1, "snowflake": 1152921504606846977_u64 },
+        { "id": 2, "snowflake": 1152921504606846978_u64 },
+        { "id": 3, "snowflake": 1152921504606846976_u64 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "sort": ["snowflake:asc"], "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 3
+              },
+              {
+                "id": 1
+              },
+              {
+                "id": 2
+              }
+            ]
+            "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "filter": "snowflake = 1152921504606846977",
+                "facets": ["snowflake"],
+                "attributesToRetrieve": ["id"],
+            }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+                {
+                  "snowflake": {
+                    "1152921504606846977": 1
+                  }
+                }
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_multiple_sort() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"sortableAttributes": ["id", "title"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "sort": ["id:asc", "title:desc"]
+        }))
+        .await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["hits"].as_array().unwrap().len(), 5);
+}
+
+#[actix_rt::test]
+async fn search_facet_distribution() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({
+                "facets": ["title"]
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let dist = response["facetDistribution"].as_object().unwrap();
+                assert_eq!(dist.len(), 1);
+                assert!(dist.get("title").is_some());
+            },
+        )
+        .await;
+
+    let index = server.index("nested");
+
+    index.update_settings(json!({"filterableAttributes": ["father", "doggos.name"]})).await;
+
+    let documents = NESTED_DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(3).await;
+
+    // TODO: TAMO: fix the test
+    index
+        .search(
+            json!({
+                // "facets": ["father", "doggos.name"]
+                "facets": ["father"]
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let dist = response["facetDistribution"].as_object().unwrap();
+                assert_eq!(dist.len(), 1);
+                assert_eq!(
+                    dist["father"],
+                    json!({ "jean": 1, "pierre": 1, "romain": 1, "jean-baptiste": 1})
+                );
+                /*
+                assert_eq!(
+                    dist["doggos.name"],
+                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
+                );
+                */
+            },
+        )
+        .await;
+
+    index.update_settings(json!({"filterableAttributes": ["doggos"]})).await;
+    index.wait_task(4).await;
+
+    index
+        .search(
+            json!({
+                "facets": ["doggos.name"]
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let dist = response["facetDistribution"].as_object().unwrap();
+                assert_eq!(dist.len(), 1);
+                assert_eq!(
+                    dist["doggos.name"],
+                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
+                );
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "facets": ["doggos"]
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let dist = response["facetDistribution"].as_object().unwrap();
+                assert_eq!(dist.len(), 3);
+                assert_eq!(
+                    dist["doggos.name"],
+                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
+                );
+                assert_eq!(dist["doggos.age"], json!({ "2": 1, "4": 1, "5": 1, "6": 1, "8": 1}));
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_extended_facet_stats() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["price", "title"]})).await;
+
+    let documents = json!([
+        { "id": 1, "title": "shirt", "price": 10 },
+        { "id": 2, "title": "shoes", "price": [20, 40] },
+        { "id": 3, "title": "hat", "price": 30 },
+        { "id": 4, "title": "socks" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(json!({ "facets": ["price", "title"] }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetStats"]), @r###"
+            {
+              "price": {
+                "min": 10.0,
+                "max": 40.0
+              }
+            }
+            "###);
+        })
+        .await;
+
+    index
+        .search(
+            json!({ "facets": ["price", "title"], "extendedFacetStats": true }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetStats"]), @r###"
+                {
+                  "price": {
+                    "min": 10.0,
+                    "max": 40.0,
+                    "sum": 100.0,
+                    "avg": 25.0
+                  }
+                }
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({ "filter": "price < 25", "facets": ["price"], "extendedFacetStats": true }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetStats"]), @r###"
+                {
+                  "price": {
+                    "min": 10.0,
+                    "max": 40.0,
+                    "sum": 70.0,
+                    "avg": 23.333333333333332
+                  }
+                }
+                "###);
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_aggregations() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["price", "title"]})).await;
+
+    let documents = json!([
+        { "id": 1, "title": "shirt", "price": 10 },
+        { "id": 2, "title": "shoes", "price": [20, 40] },
+        { "id": 3, "title": "hat", "price": 30 },
+        { "id": 4, "title": "socks" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "aggregations": {
+                "price": ["sum", "avg", "min", "max", "percentiles"],
+                "title": ["sum"],
+            },
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["aggregations"]), @r###"
+    {
+      "price": {
+        "sum": 100.0,
+        "avg": 25.0,
+        "min": 10.0,
+        "max": 40.0,
+        "percentiles": {
+          "1": 10.0,
+          "5": 10.0,
+          "25": 10.0,
+          "50": 20.0,
+          "75": 30.0,
+          "95": 40.0,
+          "99": 40.0
+        }
+      },
+      "title": {
+        "sum": null
+      }
+    }
+    "###);
+
+    let (response, code) =
+        index.search_get("filter=price%20%3C%2025&aggregations=price:sum,price:avg").await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["aggregations"]), @r###"
+    {
+      "price": {
+        "sum": 70.0,
+        "avg": 23.333333333333332
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_with_foreign_keys() {
+    let server = Server::new().await;
+
+    let authors = server.index("authors");
+    authors.update_settings(json!({"filterableAttributes": ["country"]})).await;
+    let documents = json!([
+        { "id": 1, "name": "Victor Hugo", "country": "FR" },
+        { "id": 2, "name": "Jane Austen", "country": "UK" },
+    ]);
+    authors.add_documents(documents, None).await;
+    authors.wait_task(1).await;
+
+    let books = server.index("books");
+    books
+        .update_settings(json!({
+            "filterableAttributes": ["authorId"],
+            "foreignKeys": [{ "attribute": "authorId", "foreignIndexUid": "authors" }],
+        }))
+        .await;
+    let documents = json!([
+        { "id": 1, "title": "Les Misérables", "authorId": 1 },
+        { "id": 2, "title": "Pride and Prejudice", "authorId": 2 },
+        { "id": 3, "title": "Good Omens", "authorId": [3, 2] },
+    ]);
+    books.add_documents(documents, None).await;
+    books.wait_task(3).await;
+
+    let (response, code) = books.search_post(json!({ "embedForeignDocuments": true })).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Les Misérables",
+        "authorId": 1,
+        "_foreign": {
+          "authorId": {
+            "id": 1,
+            "name": "Victor Hugo",
+            "country": "FR"
+          }
+        }
+      },
+      {
+        "id": 2,
+        "title": "Pride and Prejudice",
+        "authorId": 2,
+        "_foreign": {
+          "authorId": {
+            "id": 2,
+            "name": "Jane Austen",
+            "country": "UK"
+          }
+        }
+      },
+      {
+        "id": 3,
+        "title": "Good Omens",
+        "authorId": [
+          3,
+          2
+        ],
+        "_foreign": {
+          "authorId": [
+            {
+              "id": 2,
+              "name": "Jane Austen",
+              "country": "UK"
+            }
+          ]
+        }
+      }
+    ]
+    "###);
+
+    let (response, code) =
+        books.search_post(json!({ "foreignFilters": { "authorId": "country = UK" } })).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 2,
+        "title": "Pride and Prejudice",
+        "authorId": 2
+      },
+      {
+        "id": 3,
+        "title": "Good Omens",
+        "authorId": [
+          3,
+          2
+        ]
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_sort_facet_values_by_count() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["genre"]})).await;
+
+    let documents = json!([
+        { "id": 1, "genre": "action" },
+        { "id": 2, "genre": "comedy" },
+        { "id": 3, "genre": "comedy" },
+        { "id": 4, "genre": "drama" },
+        { "id": 5, "genre": "drama" },
+        { "id": 6, "genre": "drama" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(json!({ "facets": ["genre"] }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+            {
+              "genre": {
+                "action": 1,
+                "comedy": 2,
+                "drama": 3
+              }
+            }
+            "###);
+        })
+        .await;
 
-    index.update_settings(json!({"sortableAttributes": ["id", "title"]})).await;
+    index
+        .search(json!({ "facets": ["genre"], "sortFacetValuesBy": "count" }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+            {
+              "genre": {
+                "drama": 3,
+                "comedy": 2,
+                "action": 1
+              }
+            }
+            "###);
+        })
+        .await;
 
-    let documents = DOCUMENTS.clone();
-    index.add_documents(documents, None).await;
-    index.wait_task(1).await;
+    // the order of the query overrides the one of the settings
+    index.update_settings(json!({"faceting": { "sortFacetValuesBy": { "*": "count" } }})).await;
+    index.wait_task(2).await;
 
-    let (response, code) = index
-        .search_post(json!({
-            "sort": ["id:asc", "title:desc"]
-        }))
+    index
+        .search(json!({ "facets": ["genre"], "sortFacetValuesBy": "alpha" }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+            {
+              "genre": {
+                "action": 1,
+                "comedy": 2,
+                "drama": 3
+              }
+            }
+            "###);
+        })
         .await;
-    assert_eq!(code, 200, "{}", response);
-    assert_eq!(response["hits"].as_array().unwrap().len(), 5);
 }
 
 #[actix_rt::test]
-async fn search_facet_distribution() {
+async fn search_hierarchical_facets() {
     let server = Server::new().await;
     let index = server.index("test");
 
-    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["category"],
+            "faceting": { "hierarchySeparators": { "category": " > " } },
+        }))
+        .await;
 
-    let documents = DOCUMENTS.clone();
+    let documents = json!([
+        { "id": 1, "category": "Books > Scifi > Space opera" },
+        { "id": 2, "category": "Books > Scifi > Cyberpunk" },
+        { "id": 3, "category": ["Books > Fantasy", "Movies > Scifi"] },
+    ]);
     index.add_documents(documents, None).await;
     index.wait_task(1).await;
 
     index
         .search(
             json!({
-                "facets": ["title"]
+                "filter": "category LEVEL 0 = Books AND category LEVEL 1 = Scifi",
+                "facets": ["category.lvl0", "category.lvl1", "category.lvl2"],
             }),
             |response, code| {
-                assert_eq!(code, 200, "{}", response);
-                let dist = response["facetDistribution"].as_object().unwrap();
-                assert_eq!(dist.len(), 1);
-                assert!(dist.get("title").is_some());
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+                {
+                  "category.lvl0": {
+                    "Books": 3,
+                    "Movies": 1
+                  },
+                  "category.lvl1": {
+                    "Fantasy": 1,
+                    "Scifi": 3
+                  },
+                  "category.lvl2": {
+                    "Cyberpunk": 1,
+                    "Space opera": 1
+                  }
+                }
+                "###);
             },
         )
         .await;
+}
 
-    let index = server.index("nested");
+#[actix_rt::test]
+async fn search_with_disjunctive_facets() {
+    let server = Server::new().await;
+    let index = server.index("test");
 
-    index.update_settings(json!({"filterableAttributes": ["father", "doggos.name"]})).await;
+    index.update_settings(json!({"filterableAttributes": ["genre", "color"]})).await;
 
-    let documents = NESTED_DOCUMENTS.clone();
+    let documents = json!([
+        { "id": 1, "genre": "action", "color": "red" },
+        { "id": 2, "genre": "comedy", "color": "red" },
+        { "id": 3, "genre": "comedy", "color": "blue" },
+        { "id": 4, "genre": "drama", "color": "blue" },
+        { "id": 5, "genre": "drama", "color": "green" },
+    ]);
     index.add_documents(documents, None).await;
-    index.wait_task(3).await;
-
-    // TODO: TAMO: fix the test
-    index
-        .search(
-            json!({
-                // "facets": ["father", "doggos.name"]
-                "facets": ["father"]
-            }),
-            |response, code| {
-                assert_eq!(code, 200, "{}", response);
-                let dist = response["facetDistribution"].as_object().unwrap();
-                assert_eq!(dist.len(), 1);
-                assert_eq!(
-                    dist["father"],
-                    json!({ "jean": 1, "pierre": 1, "romain": 1, "jean-baptiste": 1})
-                );
-                /*
-                assert_eq!(
-                    dist["doggos.name"],
-                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
-                );
-                */
-            },
-        )
-        .await;
-
-    index.update_settings(json!({"filterableAttributes": ["doggos"]})).await;
-    index.wait_task(4).await;
+    index.wait_task(1).await;
 
+    // the distribution of the genres ignores the filter on the genres
+    // while the distribution of the colors is computed on the filtered documents
     index
         .search(
             json!({
-                "facets": ["doggos.name"]
+                "filter": "(genre = comedy OR genre = drama) AND color = blue",
+                "facets": ["color"],
+                "disjunctiveFacets": ["genre"],
             }),
             |response, code| {
-                assert_eq!(code, 200, "{}", response);
-                let dist = response["facetDistribution"].as_object().unwrap();
-                assert_eq!(dist.len(), 1);
-                assert_eq!(
-                    dist["doggos.name"],
-                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
-                );
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(response["estimatedTotalHits"], @"2");
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+                {
+                  "color": {
+                    "blue": 2
+                  },
+                  "genre": {
+                    "comedy": 1,
+                    "drama": 1
+                  }
+                }
+                "###);
             },
         )
         .await;
@@ -561,17 +1711,24 @@ async fn search_facet_distribution() {
     index
         .search(
             json!({
-                "facets": ["doggos"]
+                "filter": "(genre = comedy OR genre = drama) AND color = blue",
+                "disjunctiveFacets": ["genre", "color"],
             }),
             |response, code| {
-                assert_eq!(code, 200, "{}", response);
-                let dist = response["facetDistribution"].as_object().unwrap();
-                assert_eq!(dist.len(), 3);
-                assert_eq!(
-                    dist["doggos.name"],
-                    json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
-                );
-                assert_eq!(dist["doggos.age"], json!({ "2": 1, "4": 1, "5": 1, "6": 1, "8": 1}));
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+                {
+                  "color": {
+                    "blue": 2,
+                    "green": 1,
+                    "red": 1
+                  },
+                  "genre": {
+                    "comedy": 1,
+                    "drama": 1
+                  }
+                }
+                "###);
             },
         )
         .await;
@@ -1278,3 +2435,239 @@ async fn simple_search_with_strange_synonyms() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn search_with_timeout() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "rankingRules": ["words"] })).await;
+
+    let documents = json!([
+        { "id": 1, "title": "the lazy fox" },
+        { "id": 2, "title": "the quick cat" },
+        { "id": 3, "title": "the quick brown fox" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "q": "the quick brown fox", "timeoutMs": 1000, "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 3
+                  },
+                  {
+                    "id": 2
+                  },
+                  {
+                    "id": 1
+                  }
+                ]
+                "###);
+                meili_snap::snapshot!(response["degraded"], @"null");
+            },
+        )
+        .await;
+
+    // the time budget is exceeded right away, the hits are returned in the order of their ids
+    index
+        .search(
+            json!({ "q": "the quick brown fox", "timeoutMs": 0, "attributesToRetrieve": ["id"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1
+                  },
+                  {
+                    "id": 2
+                  },
+                  {
+                    "id": 3
+                  }
+                ]
+                "###);
+                meili_snap::snapshot!(response["degraded"], @"true");
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_count_only() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({ "filterableAttributes": ["color"], "distinctAttribute": "name" }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "name": "shirt", "color": "blue" },
+        { "id": 2, "name": "shirt", "color": "red" },
+        { "id": 3, "name": "pants", "color": "blue" },
+        { "id": 4, "name": "socks", "color": "blue" },
+        { "id": 5, "name": "socks", "color": "green" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // the count is exhaustive and takes the distinct attribute into account
+    index
+        .search(json!({ "countOnly": true, "filter": "color = blue" }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @"[]");
+            meili_snap::snapshot!(response["totalHits"], @"3");
+            meili_snap::snapshot!(response["estimatedTotalHits"], @"null");
+            meili_snap::snapshot!(response["limit"], @"null");
+        })
+        .await;
+
+    index
+        .search(json!({ "q": "socks", "countOnly": true, "limit": 1 }), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @"[]");
+            meili_snap::snapshot!(response["totalHits"], @"1");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_search_after() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "rankingRules": ["words", "desc(rank)"] })).await;
+
+    let documents = json!([
+        { "id": 1, "title": "the quick fox", "rank": 1 },
+        { "id": 2, "title": "the quick fox", "rank": 3 },
+        { "id": 3, "title": "the quick", "rank": 5 },
+        { "id": 4, "title": "the", "rank": 2 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let mut search_after = String::new();
+    let mut pages = Vec::new();
+    loop {
+        let (response, code) = index
+            .search_post(json!({
+                "q": "the quick fox",
+                "searchAfter": search_after,
+                "limit": 3,
+                "attributesToRetrieve": ["id"],
+            }))
+            .await;
+        meili_snap::snapshot!(code, @"200 OK");
+        pages.push(response["hits"].clone());
+        match response["nextSearchAfter"].as_str() {
+            Some(next) => search_after = next.to_string(),
+            None => break,
+        }
+    }
+
+    meili_snap::snapshot!(meili_snap::json_string!(pages), @r###"
+    [
+      [
+        {
+          "id": 2
+        },
+        {
+          "id": 1
+        },
+        {
+          "id": 3
+        }
+      ],
+      [
+        {
+          "id": 4
+        }
+      ],
+      []
+    ]
+    "###);
+
+    // the cursor is only returned when requested
+    let (response, code) = index.search_post(json!({ "q": "the quick fox" })).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(response["nextSearchAfter"], @"null");
+}
+
+#[actix_rt::test]
+async fn search_with_group_by() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index
+        .update_settings(json!({
+            "filterableAttributes": ["brand"],
+            "rankingRules": ["words", "desc(rank)"],
+        }))
+        .await;
+
+    let documents = json!([
+        { "id": 1, "title": "red shirt", "brand": "Apex", "rank": 5 },
+        { "id": 2, "title": "blue shirt", "brand": "Apex", "rank": 4 },
+        { "id": 3, "title": "green shirt", "brand": "Apex", "rank": 3 },
+        { "id": 4, "title": "red shirt", "brand": "Bolt", "rank": 2 },
+        { "id": 5, "title": "white shirt", "rank": 1 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "shirt",
+            "groupBy": { "attribute": "brand", "limit": 2 },
+            "attributesToRetrieve": ["id"],
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 2
+      },
+      {
+        "id": 4
+      },
+      {
+        "id": 5
+      }
+    ]
+    "###);
+    meili_snap::snapshot!(meili_snap::json_string!(response["groupCounts"]), @r###"
+    {
+      "Apex": 3,
+      "Bolt": 1
+    }
+    "###);
+
+    let (response, code) =
+        index.search_get("q=shirt&groupByAttribute=brand&attributesToRetrieve=id").await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 4
+      },
+      {
+        "id": 5
+      }
+    ]
+    "###);
+}
@@ -163,3 +163,38 @@ async fn ensure_placeholder_search_hit_count_valid() {
             .await;
     }
 }
+
+#[actix_rt::test]
+async fn early_termination_only_estimates_the_first_buckets() {
+    let server = Server::new().await;
+    let index = server.index("basic");
+
+    let documents = json!([
+        { "id": 0, "title": "red running shoes" },
+        { "id": 1, "title": "red shoes" },
+        { "id": 2, "title": "blue shoes" },
+        { "id": 3, "title": "red socks" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    // the documents matching fewer words are counted by default
+    index
+        .search(json!({ "q": "red shoes", "limit": 1 }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["hits"][0]["id"], json!(1));
+            assert_eq!(response["estimatedTotalHits"], json!(4));
+        })
+        .await;
+
+    index
+        .search(
+            json!({ "q": "red shoes", "limit": 1, "earlyTermination": true }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(response["hits"][0]["id"], json!(1));
+                assert_eq!(response["estimatedTotalHits"], json!(2));
+            },
+        )
+        .await;
+}
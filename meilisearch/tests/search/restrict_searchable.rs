@@ -367,3 +367,113 @@ async fn search_on_exact_field() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn search_with_attribute_boosts() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server, &SIMPLE_SEARCH_DOCUMENTS).await;
+
+    let (response, code) =
+        index.search_post(json!({"q": "Shazam", "attributesToRetrieve": ["id"]})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "1"
+      },
+      {
+        "id": "3"
+      }
+    ]
+    "###);
+
+    // the `desc` attribute is second, boosting it more than twice makes it win over the `title`
+    let (response, code) = index
+        .search_post(
+            json!({"q": "Shazam", "attributesToRetrieve": ["id"], "attributeBoosts": {"desc": 3}}),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "3"
+      },
+      {
+        "id": "1"
+      }
+    ]
+    "###);
+
+    let (response, code) =
+        index.search_get("q=Shazam&attributesToRetrieve=id&attributeBoosts=desc:3").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": "3"
+      },
+      {
+        "id": "1"
+      }
+    ]
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({"q": "Shazam", "attributeBoosts": {"desc": -1}})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.attributeBoosts`: the value of `attributeBoosts` is invalid, expected an object associating attributes to positive numbers.",
+      "code": "invalid_search_attribute_boosts",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_attribute_boosts"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_on_attribute_patterns_and_nested_fields() {
+    let server = Server::new().await;
+    let documents = json!([
+        { "id": "1", "title_en": "Captain Marvel", "metadata": { "tags": ["hero"], "studio": "Marvel" } },
+        { "id": "2", "title_fr": "Capitaine Marvel", "metadata": { "tags": ["marvel"], "studio": "Disney" } },
+        { "id": "3", "desc": "Marvel", "metadata": { "tags": ["villain"], "studio": "Marvel" } },
+    ]);
+    let index = index_with_documents(&server, &documents).await;
+
+    let searches = [
+        (json!({ "q": "marvel", "attributesToSearchOn": ["title*"] }), vec!["1", "2"]),
+        (json!({ "q": "marvel", "attributesToSearchOn": ["*_fr"] }), vec!["2"]),
+        (json!({ "q": "marvel", "attributesToSearchOn": ["metadata.tags"] }), vec!["2"]),
+        (json!({ "q": "marvel", "attributesToSearchOn": ["metadata"] }), vec!["1", "2", "3"]),
+        (json!({ "q": "marvel", "attributesToSearchOn": ["unknown*"] }), vec![]),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let mut ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        ids.sort_by_key(|id| id.to_string());
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+
+    // the patterns only match the searchable attributes
+    index.update_settings_searchable_attributes(json!(["title_en", "metadata.tags"])).await;
+    index.wait_task(1).await;
+
+    let searches = [
+        (json!({ "q": "marvel", "attributesToSearchOn": ["title*"] }), vec!["1"]),
+        (json!({ "q": "marvel", "attributesToSearchOn": ["metadata"] }), vec!["2"]),
+    ];
+
+    for (search, expected) in searches {
+        let (response, code) = index.search_post(search.clone()).await;
+        assert_eq!(code, 200, "{}", response);
+        let mut ids: Vec<_> =
+            response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+        ids.sort_by_key(|id| id.to_string());
+        assert_eq!(json!(ids), json!(expected), "{search}");
+    }
+}
@@ -0,0 +1,151 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        {
+            "title": "Shazam!",
+            "genres": ["Action", "Adventure"],
+            "id": "287947",
+        },
+        {
+            "title": "Captain Marvel",
+            "genres": ["Action", "Adventure"],
+            "id": "299537",
+        },
+        {
+            "title": "Escape Room",
+            "genres": ["Horror", "Thriller", "Multiple Words"],
+            "id": "522681",
+        },
+        {
+            "title": "How to Train Your Dragon: The Hidden World",
+            "genres": ["Action", "Comedy"],
+            "id": "166428",
+        },
+        {
+            "title": "Gläss",
+            "genres": ["Thriller"],
+            "id": "450465",
+        }
+    ])
+});
+
+#[actix_rt::test]
+async fn suggest_values_of_suggestable_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) =
+        index.update_settings(json!({ "suggestableAttributes": ["title", "genres"] })).await;
+    snapshot!(code, @"202 Accepted");
+    index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.suggest(json!({ "q": "a" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[time]" }), @r###"
+    {
+      "suggestions": [
+        {
+          "value": "Action",
+          "count": 3
+        },
+        {
+          "value": "Adventure",
+          "count": 2
+        }
+      ],
+      "query": "a",
+      "processingTimeMs": "[time]"
+    }
+    "###);
+
+    let (response, code) = index.suggest_get("q=THR&limit=1").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "value": "Thriller",
+        "count": 2
+      }
+    ]
+    "###);
+
+    // the suggestions follow the deletions of the documents
+    index.delete_document(450465).await;
+    index.wait_task(2).await;
+    let (response, code) = index.suggest(json!({ "q": "thr" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "value": "Thriller",
+        "count": 1
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn suggest_without_suggestable_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(0).await;
+
+    let (response, code) = index.suggest(json!({ "q": "a" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @"[]");
+
+    // the suggestions are built from the existing documents when the setting is updated
+    let (_, code) = index.update_settings(json!({ "suggestableAttributes": ["genres"] })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(1).await;
+
+    let (response, code) = index.suggest(json!({ "q": "mult" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "value": "Multiple Words",
+        "count": 1
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn suggest_bad_parameters() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.create(None).await;
+    index.wait_task(0).await;
+
+    let (response, code) = index.suggest(json!({ "q": 12 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.q`: expected a string, but found a positive integer: `12`",
+      "code": "invalid_suggest_q",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_suggest_q"
+    }
+    "###);
+
+    let (response, code) = index.suggest_get("limit=many").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `limit`: could not parse `many` as a positive integer",
+      "code": "invalid_suggest_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_suggest_limit"
+    }
+    "###);
+}
@@ -141,6 +141,18 @@ async fn settings_bad_ranking_rules() {
       "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
     }
     "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "rankingRules": ["desc(log(popularity) *)"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[0]`: `desc(log(popularity) *)` ranking rule is invalid. The expression is invalid: unexpected end of the expression.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -274,7 +286,7 @@ async fn settings_bad_typo_tolerance() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Unknown field `typoTolerance`: expected one of `enabled`, `minWordSizeForTypos`, `disableOnWords`, `disableOnAttributes`",
+      "message": "Unknown field `typoTolerance`: expected one of `enabled`, `minWordSizeForTypos`, `disableOnWords`, `disableOnAttributes`, `disableOnFirstCharacter`, `enableOnNumbers`",
       "code": "invalid_settings_typo_tolerance",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_settings_typo_tolerance"
@@ -337,3 +349,235 @@ async fn settings_bad_pagination() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn settings_bad_static_boost() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "staticBoost": "popularity" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.staticBoost`: expected an object, but found a string: `\"popularity\"`",
+      "code": "invalid_settings_static_boost",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_static_boost"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_foreign_keys() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "foreignKeys": "authors" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.foreignKeys`: expected an array, but found a string: `\"authors\"`",
+      "code": "invalid_settings_foreign_keys",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_foreign_keys"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "foreignKeys": [{ "attribute": "authorId" }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `foreignIndexUid` inside `.foreignKeys[0]`",
+      "code": "invalid_settings_foreign_keys",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_foreign_keys"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_query_rules() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "queryRules": { "id": "sale" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.queryRules`: expected an array, but found an object: `{\"id\":\"sale\"}`",
+      "code": "invalid_settings_query_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_query_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "queryRules": [{ "id": "sale", "conditions": { "anchoring": "startWith" } }] }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `startWith` at `.queryRules[0].conditions.anchoring`: expected one of `is`, `startsWith`, `endsWith`, `contains`",
+      "code": "invalid_settings_query_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_query_rules"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "queryRules": [{ "id": "sale", "consequences": { "boost": "title & Glass" } }] }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.queryRules[0]`: The `consequences.boost` of the `sale` query rule is invalid: Was expecting an operation `=`, `!=`, `>=`, `>`, `<=`, `<`, `IN`, `NOT IN`, `TO`, `EXISTS`, `NOT EXISTS`, `IS NULL`, `IS NOT NULL`, `IS EMPTY`, `IS NOT EMPTY`, `CONTAINS`, `NOT CONTAINS`, `STARTS WITH`, `NOT STARTS WITH`, `_geoRadius`, `_geoBoundingBox`, `_geoPolygon`, `_geoWithin`, or `_geoIntersects` at `title & Glass`.\n1:14 title & Glass",
+      "code": "invalid_settings_query_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_query_rules"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_reranker() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "reranker": { "topN": 10 } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `url` inside `.reranker`",
+      "code": "invalid_settings_reranker",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_reranker"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(
+            json!({ "reranker": { "url": "http://localhost:8080/rerank", "topN": 0 } }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.reranker`: The `topN` of the reranker must be greater than 0.",
+      "code": "invalid_settings_reranker",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_reranker"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_localized_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({ "localizedAttributes": [{ "attributePatterns": ["title_ja"] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `locales` inside `.localizedAttributes[0]`",
+      "code": "invalid_settings_localized_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_localized_attributes"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "localizedAttributes": [{ "attributePatterns": ["title_ja"], "locales": ["japanese"] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.localizedAttributes[0]`: Unknown locale `japanese`, expected the ISO 639-3 code of a supported language.",
+      "code": "invalid_settings_localized_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_localized_attributes"
+    }
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "localizedAttributes": [{ "attributePatterns": ["title_ja"], "locales": [] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.localizedAttributes[0]`: A localized attributes rule must have at least one locale.",
+      "code": "invalid_settings_localized_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_localized_attributes"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_stemming() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "stemming": "eng" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.stemming`: expected an array, but found a string: `\"eng\"`",
+      "code": "invalid_settings_stemming",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_stemming"
+    }
+    "###);
+
+    let (response, code) = index.update_settings_stemming(json!("eng")).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type: expected an array, but found a string: `\"eng\"`",
+      "code": "invalid_settings_stemming",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_stemming"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_romanization() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "romanization": "pinyin" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.romanization`: expected a boolean, but found a string: `\"pinyin\"`",
+      "code": "invalid_settings_romanization",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_romanization"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_split_identifiers() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "splitIdentifiers": "camelCase" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.splitIdentifiers`: expected a boolean, but found a string: `\"camelCase\"`",
+      "code": "invalid_settings_split_identifiers",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_split_identifiers"
+    }
+    "###);
+}
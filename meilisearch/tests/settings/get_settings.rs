@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use once_cell::sync::Lazy;
 
+use crate::common::encoder::Encoder;
 use crate::common::{Server, Value};
 use crate::json;
 
@@ -10,7 +11,22 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
     map.insert("displayed_attributes", json!(["*"]));
     map.insert("searchable_attributes", json!(["*"]));
     map.insert("filterable_attributes", json!([]));
+    map.insert("non_flattened_attributes", json!([]));
+    map.insert("merge_strategies", json!({}));
+    map.insert("type_coercions", json!({}));
+    map.insert("ingestion_mapping", json!({}));
+    map.insert("date_attributes", json!([]));
+    map.insert("document_schema", json!(null));
+    map.insert("suggestable_attributes", json!([]));
     map.insert("distinct_attribute", json!(null));
+    map.insert("static_boost", json!(null));
+    map.insert("foreign_keys", json!([]));
+    map.insert("query_rules", json!([]));
+    map.insert("reranker", json!(null));
+    map.insert("localized_attributes", json!([]));
+    map.insert("stemming", json!([]));
+    map.insert("romanization", json!(false));
+    map.insert("split_identifiers", json!(false));
     map.insert(
         "ranking_rules",
         json!(["words", "typo", "proximity", "attribute", "sort", "exactness"]),
@@ -26,7 +42,8 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
             "maxValuesPerFacet": json!(100),
             "sortFacetValuesBy": {
                 "*": "alpha"
-            }
+            },
+            "hierarchySeparators": {}
         }),
     );
     map.insert(
@@ -54,12 +71,27 @@ async fn get_settings() {
     let (response, code) = index.settings().await;
     assert_eq!(code, 200);
     let settings = response.as_object().unwrap();
-    assert_eq!(settings.keys().len(), 16);
+    assert_eq!(settings.keys().len(), 31);
     assert_eq!(settings["displayedAttributes"], json!(["*"]));
     assert_eq!(settings["searchableAttributes"], json!(["*"]));
     assert_eq!(settings["filterableAttributes"], json!([]));
     assert_eq!(settings["sortableAttributes"], json!([]));
+    assert_eq!(settings["nonFlattenedAttributes"], json!([]));
+    assert_eq!(settings["mergeStrategies"], json!({}));
+    assert_eq!(settings["typeCoercions"], json!({}));
+    assert_eq!(settings["ingestionMapping"], json!({}));
+    assert_eq!(settings["dateAttributes"], json!([]));
+    assert_eq!(settings["documentSchema"], json!(null));
+    assert_eq!(settings["suggestableAttributes"], json!([]));
     assert_eq!(settings["distinctAttribute"], json!(null));
+    assert_eq!(settings["staticBoost"], json!(null));
+    assert_eq!(settings["foreignKeys"], json!([]));
+    assert_eq!(settings["queryRules"], json!([]));
+    assert_eq!(settings["reranker"], json!(null));
+    assert_eq!(settings["localizedAttributes"], json!([]));
+    assert_eq!(settings["stemming"], json!([]));
+    assert_eq!(settings["romanization"], json!(false));
+    assert_eq!(settings["splitIdentifiers"], json!(false));
     assert_eq!(
         settings["rankingRules"],
         json!(["words", "typo", "proximity", "attribute", "sort", "exactness"])
@@ -74,7 +106,8 @@ async fn get_settings() {
             "maxValuesPerFacet": 100,
             "sortFacetValuesBy": {
                 "*": "alpha"
-            }
+            },
+            "hierarchySeparators": {}
         })
     );
     assert_eq!(
@@ -114,6 +147,25 @@ async fn test_partial_update() {
     assert_eq!(response["searchableAttributes"], json!(["bar"]));
 }
 
+#[actix_rt::test]
+async fn update_settings_with_every_encoding() {
+    let server = Server::new().await;
+
+    for (task_uid, encoder) in Encoder::iterator().enumerate() {
+        let index = server.index_with_encoder("test", encoder);
+        let attribute = format!("attribute_{task_uid}");
+        let (response, code) =
+            index.update_settings(json!({ "displayedAttributes": [attribute] })).await;
+        assert_eq!(code, 202, "{response}");
+        let response = index.wait_task(task_uid as u64).await;
+        assert_eq!(response["status"], "succeeded", "{response}");
+
+        let (response, code) = index.settings().await;
+        assert_eq!(code, 200);
+        assert_eq!(response["displayedAttributes"], json!([attribute]));
+    }
+}
+
 #[actix_rt::test]
 async fn error_delete_settings_unexisting_index() {
     let server = Server::new().await;
@@ -275,9 +327,24 @@ macro_rules! test_setting_routes {
 
 test_setting_routes!(
     filterable_attributes put,
+    non_flattened_attributes put,
+    merge_strategies put,
+    type_coercions put,
+    ingestion_mapping put,
+    date_attributes put,
+    document_schema put,
+    suggestable_attributes put,
     displayed_attributes put,
     searchable_attributes put,
     distinct_attribute put,
+    static_boost put,
+    foreign_keys put,
+    query_rules put,
+    reranker put,
+    localized_attributes put,
+    stemming put,
+    romanization put,
+    split_identifiers put,
     stop_words put,
     separator_tokens put,
     non_separator_tokens put,
@@ -2,4 +2,5 @@ mod distinct;
 mod errors;
 mod get_settings;
 mod proximity_settings;
+mod shadow_settings_update;
 mod tokenizer_customization;
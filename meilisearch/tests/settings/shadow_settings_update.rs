@@ -0,0 +1,84 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn update_settings_through_a_shadow_index() {
+    let server = Server::new().await;
+    let (_response, code) = server.set_features(json!({ "shadowSettingsUpdate": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "title": "Carol", "genre": "romance" },
+        { "id": 2, "title": "Wonder Woman", "genre": "action" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    let (task, _code) = index
+        .update_settings(json!({ "filterableAttributes": ["genre"], "stopWords": ["woman"] }))
+        .await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, _code) = index.settings().await;
+    snapshot!(response["filterableAttributes"], @r###"["genre"]"###);
+    snapshot!(response["stopWords"], @r###"["woman"]"###);
+
+    let (response, _code) = index.stats().await;
+    snapshot!(response["numberOfDocuments"], @"2");
+
+    index
+        .search(json!({ "q": "wonder woman", "filter": "genre = action" }), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "title": "Wonder Woman",
+                "genre": "action"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // the index keeps being updated normally afterward
+    let (task, _code) = index.add_documents(json!([{ "id": 3, "title": "Shazam!" }]), None).await;
+    index.wait_task(task.uid()).await;
+    let (response, _code) = index.stats().await;
+    snapshot!(response["numberOfDocuments"], @"3");
+}
+
+#[actix_rt::test]
+async fn failed_shadow_settings_update_keeps_the_index() {
+    let server = Server::new().await;
+    let (_response, code) = server.set_features(json!({ "shadowSettingsUpdate": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "title": "Carol", "genre": "romance" },
+        { "id": 2, "title": "Wonder Woman", "genre": "action" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    // the embedders can't be set while the vector store is disabled
+    let (task, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["genre"],
+            "embedders": { "default": { "source": "userProvided", "dimensions": 2 } },
+        }))
+        .await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["code"], @r###""feature_not_enabled""###);
+
+    let (response, _code) = index.stats().await;
+    snapshot!(response["numberOfDocuments"], @"2");
+    let (response, _code) = index.settings().await;
+    snapshot!(response["filterableAttributes"], @"[]");
+}
@@ -465,3 +465,313 @@ async fn advanced_synergies() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn dictionary_compounds() {
+    let documents = json!([
+        {
+            "id": 1,
+            "content": "C++ for beginners",
+        },
+        {
+            "id": 2,
+            "content": "C for beginners",
+        },
+    ]);
+
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index.update_settings(json!({ "dictionary": ["  C++ ", ""] })).await;
+    index.wait_task(1).await;
+
+    // the words of the dictionary are trimmed and the empty ones ignored
+    let (response, _) = index.settings().await;
+    snapshot!(json_string!(response["dictionary"]), @r###"
+    [
+      "C++"
+    ]
+    "###);
+
+    // the compound is never split into the `c` word
+    index
+        .search(json!({"q": "C++", "attributesToHighlight": ["content"]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1,
+                "content": "C++ for beginners",
+                "_formatted": {
+                  "id": "1",
+                  "content": "<em>C++</em> for beginners"
+                }
+              }
+            ]
+            "###);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn hashtags_and_handles() {
+    let documents = json!([
+        {
+            "id": 1,
+            "content": "#rustlang is great",
+        },
+        {
+            "id": 2,
+            "content": "rustlang by @ferris",
+        },
+    ]);
+
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .update_settings(json!({
+            "nonSeparatorTokens": ["#", "@"],
+            "typoTolerance": { "enabled": false },
+        }))
+        .await;
+    index.wait_task(1).await;
+
+    index
+        .search(json!({"q": "#rustlang", "matchingStrategy": "all"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1,
+                "content": "#rustlang is great"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "@ferris", "matchingStrategy": "all"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "content": "rustlang by @ferris"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // a token can't be both a separator and a non separator token
+    let (response, _) = index.update_settings(json!({ "separatorTokens": ["@"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`separatorTokens` and `nonSeparatorTokens` settings are invalid. A token can't be both a separator and a non separator token but found `@` in both.",
+      "code": "invalid_settings_separator_tokens",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_separator_tokens"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn stemming() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "content": "the dogs are running" },
+        { "id": 2, "content": "a dog runs" },
+        { "id": 3, "content": "a runner" },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, _) = index.update_settings(json!({ "stemming": ["eng"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["stemming"]), @r###"
+    [
+      "eng"
+    ]
+    "###);
+
+    // the words sharing the stem of the query words match them
+    index
+        .search(json!({"q": "dog run ", "matchingStrategy": "all"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "content": "a dog runs"
+              },
+              {
+                "id": 1,
+                "content": "the dogs are running"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // only the languages having a stemmer can be stemmed
+    let (response, _) = index.update_settings(json!({ "stemming": ["jpn"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "`stemming` setting is invalid. Unknown locale `jpn`, expected the ISO 639-3 code of a language having a stemmer.",
+      "code": "invalid_settings_stemming",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_stemming"
+    }
+    "###);
+
+    let (response, _) = index.update_settings(json!({ "stemming": [] })).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({"q": "dog run ", "matchingStrategy": "all"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @"[]");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn romanization() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "content": "すし" },
+        { "id": 2, "content": "sushi" },
+        { "id": 3, "content": "北京" },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, _) = index.update_settings(json!({ "romanization": true })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // the latin transcriptions match the words written in kana or in chinese characters,
+    // the words written as in the query ranking first
+    index
+        .search(json!({"q": "sushi "}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2,
+                "content": "sushi"
+              },
+              {
+                "id": 1,
+                "content": "すし"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "beijing "}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 3,
+                "content": "北京"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    let (response, _) = index.update_settings(json!({ "romanization": false })).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({"q": "beijing "}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @"[]");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn split_identifiers() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "content": "let name = getUserName(user);" },
+        { "id": 2, "content": "def get_user_name(user):" },
+        { "id": 3, "content": "items = fetchAllItems()" },
+    ]);
+    let (response, _) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, _) = index.update_settings(json!({ "splitIdentifiers": true })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // the identifiers of the documents and of the query are split into their sub-words
+    index
+        .search(
+            json!({"q": "userName ", "matchingStrategy": "all", "attributesToHighlight": ["content"]}),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": 1,
+                    "content": "let name = getUserName(user);",
+                    "_formatted": {
+                      "id": "1",
+                      "content": "let <em>name</em> = get<em>User</em><em>Name</em>(<em>user</em>);"
+                    }
+                  },
+                  {
+                    "id": 2,
+                    "content": "def get_user_name(user):",
+                    "_formatted": {
+                      "id": "2",
+                      "content": "def get_<em>user</em>_<em>name</em>(<em>user</em>):"
+                    }
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    // the identifiers are also indexed as a whole
+    index
+        .search(json!({"q": "fetchallitems", "matchingStrategy": "all"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 3,
+                "content": "items = fetchAllItems()"
+              }
+            ]
+            "###);
+        })
+        .await;
+}
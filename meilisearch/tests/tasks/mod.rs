@@ -555,6 +555,7 @@ async fn test_summarized_delete_documents_by_filter() {
       "details": {
         "providedIds": 0,
         "deletedDocuments": 0,
+        "deletedDocumentsSample": [],
         "originalFilter": "\"doggo = bernese\""
       },
       "error": null,
@@ -6,7 +6,7 @@ use std::time::Instant;
 use heed::EnvOpenOptions;
 use milli::{
     execute_search, filtered_universe, DefaultSearchLogger, GeoSortStrategy, Index, SearchContext,
-    SearchLogger, TermsMatchingStrategy,
+    SearchLogger, TermsMatchingStrategy, TimeBudget,
 };
 
 #[global_allocator]
@@ -54,8 +54,14 @@ fn main() -> Result<(), Box<dyn Error>> {
             let docs = execute_search(
                 &mut ctx,
                 (!query.trim().is_empty()).then(|| query.trim()),
+                None,
                 TermsMatchingStrategy::Last,
                 milli::score_details::ScoringStrategy::Skip,
+                None,
+                TimeBudget::max(),
+                None,
+                None,
+                false,
                 false,
                 universe,
                 &None,
@@ -20,6 +20,8 @@ pub enum AscDescError {
     InvalidSyntax { name: String },
     #[error("`{name}` is a reserved keyword and thus can't be used as a asc/desc rule.")]
     ReservedKeyword { name: String },
+    #[error("Invalid sort modifiers in `{name}`: expected at most one of `:nullsFirst` or `:nullsLast` and one of `:min`, `:max` or `:avg` after the `:asc` or `:desc` of a field.")]
+    InvalidModifiers { name: String },
 }
 
 impl From<BadGeoError> for AscDescError {
@@ -44,21 +46,64 @@ impl From<AscDescError> for CriterionError {
             AscDescError::ReservedKeyword { name } if name.starts_with("_geoBoundingBox") => {
                 CriterionError::ReservedNameForFilter { name: "_geoBoundingBox".to_string() }
             }
+            AscDescError::ReservedKeyword { name } if name.starts_with("_random") => {
+                CriterionError::ReservedNameForSort { name: "_random".to_string() }
+            }
             AscDescError::ReservedKeyword { name } => CriterionError::ReservedName { name },
+            AscDescError::InvalidModifiers { name } => CriterionError::InvalidName { name },
         }
     }
 }
 
+/// Where the documents without a value for the sorted field are placed.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    First,
+    #[default]
+    Last,
+}
+
+/// The value of an array a document is sorted by.
+///
+/// Without a strategy, a document is placed at the first of its values met by the sort,
+/// i.e. its minimum in ascending order and its maximum in descending order.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MultiValue {
+    Min,
+    Max,
+    Avg,
+}
+
+/// The modifiers written after the `:asc` or `:desc` of a sorted field,
+/// e.g. `price:asc:nullsFirst` or `ratings:desc:avg`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SortModifiers {
+    pub nulls: NullsOrder,
+    pub multi_value: Option<MultiValue>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Member {
     Field(String),
+    /// A field sorted with some modifiers.
+    ModifiedField(String, SortModifiers),
     Geo([f64; 2]),
+    /// A pseudo-random order derived from a seed, written `_random(seed)`.
+    Random(u64),
 }
 
 impl FromStr for Member {
     type Err = AscDescError;
 
     fn from_str(text: &str) -> Result<Member, Self::Err> {
+        if let Some(seed) = text.strip_prefix("_random(").and_then(|text| text.strip_suffix(')')) {
+            return seed
+                .trim()
+                .parse()
+                .map(Member::Random)
+                .map_err(|_| AscDescError::ReservedKeyword { name: text.to_string() });
+        }
+
         match text.strip_prefix("_geoPoint(").and_then(|text| text.strip_suffix(')')) {
             Some(point) => {
                 let (lat, lng) = point
@@ -95,8 +140,9 @@ impl FromStr for Member {
 impl fmt::Display for Member {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Member::Field(name) => f.write_str(name),
+            Member::Field(name) | Member::ModifiedField(name, _) => f.write_str(name),
             Member::Geo([lat, lng]) => write!(f, "_geoPoint({}, {})", lat, lng),
+            Member::Random(seed) => write!(f, "_random({})", seed),
         }
     }
 }
@@ -104,15 +150,22 @@ impl fmt::Display for Member {
 impl Member {
     pub fn field(&self) -> Option<&str> {
         match self {
-            Member::Field(field) => Some(field),
-            Member::Geo(_) => None,
+            Member::Field(field) | Member::ModifiedField(field, _) => Some(field),
+            Member::Geo(_) | Member::Random(_) => None,
+        }
+    }
+
+    pub fn modifiers(&self) -> SortModifiers {
+        match self {
+            Member::ModifiedField(_, modifiers) => *modifiers,
+            Member::Field(_) | Member::Geo(_) | Member::Random(_) => SortModifiers::default(),
         }
     }
 
     pub fn geo_point(&self) -> Option<&[f64; 2]> {
         match self {
             Member::Geo(point) => Some(point),
-            Member::Field(_) => None,
+            Member::Field(_) | Member::ModifiedField(..) | Member::Random(_) => None,
         }
     }
 }
@@ -140,10 +193,49 @@ impl FromStr for AscDesc {
     type Err = AscDescError;
 
     fn from_str(text: &str) -> Result<AscDesc, Self::Err> {
-        match text.rsplit_once(':') {
-            Some((left, "asc")) => Ok(AscDesc::Asc(left.parse()?)),
-            Some((left, "desc")) => Ok(AscDesc::Desc(left.parse()?)),
-            _ => Err(AscDescError::InvalidSyntax { name: text.to_string() }),
+        // the random order doesn't need a direction
+        if text.starts_with("_random(") && text.ends_with(')') {
+            return Ok(AscDesc::Asc(text.parse()?));
+        }
+
+        let invalid_modifiers = || AscDescError::InvalidModifiers { name: text.to_string() };
+
+        // the modifiers are peeled off the end of the text until the `:asc` or `:desc`
+        let mut nulls = None;
+        let mut multi_value = None;
+        let mut rest = text;
+        let (left, is_ascending) = loop {
+            let Some((left, last)) = rest.rsplit_once(':') else {
+                return Err(AscDescError::InvalidSyntax { name: text.to_string() });
+            };
+            match last {
+                "asc" => break (left, true),
+                "desc" => break (left, false),
+                "nullsFirst" | "nullsLast" if nulls.is_some() => return Err(invalid_modifiers()),
+                "nullsFirst" => nulls = Some(NullsOrder::First),
+                "nullsLast" => nulls = Some(NullsOrder::Last),
+                "min" | "max" | "avg" if multi_value.is_some() => return Err(invalid_modifiers()),
+                "min" => multi_value = Some(MultiValue::Min),
+                "max" => multi_value = Some(MultiValue::Max),
+                "avg" => multi_value = Some(MultiValue::Avg),
+                _ => return Err(AscDescError::InvalidSyntax { name: text.to_string() }),
+            }
+            rest = left;
+        };
+
+        let member = match left.parse::<Member>()? {
+            member if rest == text => member,
+            Member::Field(field) => {
+                let nulls = nulls.unwrap_or_default();
+                Member::ModifiedField(field, SortModifiers { nulls, multi_value })
+            }
+            _ => return Err(invalid_modifiers()),
+        };
+
+        if is_ascending {
+            Ok(AscDesc::Asc(member))
+        } else {
+            Ok(AscDesc::Desc(member))
         }
     }
 }
@@ -155,6 +247,11 @@ pub enum SortError {
     #[error("Invalid syntax for the geo parameter: expected expression formated like \
                     `_geoPoint(latitude, longitude)` and ending by `:asc` or `:desc`, found `{name}`.")]
     BadGeoPointUsage { name: String },
+    #[error(
+        "Invalid syntax for the random sort parameter: expected expression formatted like \
+                    `_random(seed)` with an unsigned integer seed, found `{name}`."
+    )]
+    BadRandomUsage { name: String },
     #[error("Invalid syntax for the sort parameter: expected expression ending by `:asc` or `:desc`, found `{name}`.")]
     InvalidName { name: String },
     #[error("`{name}` is a reserved keyword and thus can't be used as a sort expression.")]
@@ -165,6 +262,8 @@ pub enum SortError {
     #[error("`{name}` is a reserved keyword and thus can't be used as a sort expression. \
                     Use the _geoPoint(latitude, longitude) built-in rule to sort on _geo field coordinates.")]
     ReservedNameForFilter { name: String },
+    #[error("Invalid sort modifiers in `{name}`: expected at most one of `:nullsFirst` or `:nullsLast` and one of `:min`, `:max` or `:avg` after the `:asc` or `:desc` of a field.")]
+    InvalidModifiers { name: String },
 }
 
 impl From<AscDescError> for SortError {
@@ -175,6 +274,9 @@ impl From<AscDescError> for SortError {
             AscDescError::ReservedKeyword { name } if name.starts_with("_geoPoint") => {
                 SortError::BadGeoPointUsage { name }
             }
+            AscDescError::ReservedKeyword { name } if name.starts_with("_random") => {
+                SortError::BadRandomUsage { name }
+            }
             AscDescError::ReservedKeyword { name } if &name == "_geo" => {
                 SortError::ReservedNameForSettings { name }
             }
@@ -185,6 +287,7 @@ impl From<AscDescError> for SortError {
                 SortError::ReservedNameForFilter { name: String::from("_geoBoundingBox") }
             }
             AscDescError::ReservedKeyword { name } => SortError::ReservedName { name },
+            AscDescError::InvalidModifiers { name } => SortError::InvalidModifiers { name },
         }
     }
 }
@@ -222,6 +325,31 @@ mod tests {
             ("_geoPoint(42.0002, 59.895):desc", Desc(Geo([42.0002, 59.895]))),
             ("_geoPoint(42., 59.):desc", Desc(Geo([42., 59.]))),
             ("truc(12, 13):desc", Desc(Field(S("truc(12, 13)")))),
+            (
+                "price:asc:nullsFirst",
+                Asc(ModifiedField(
+                    S("price"),
+                    SortModifiers { nulls: NullsOrder::First, multi_value: None },
+                )),
+            ),
+            (
+                "ratings:desc:avg",
+                Desc(ModifiedField(
+                    S("ratings"),
+                    SortModifiers { nulls: NullsOrder::Last, multi_value: Some(MultiValue::Avg) },
+                )),
+            ),
+            (
+                "a:b:asc:min:nullsLast",
+                Asc(ModifiedField(
+                    S("a:b"),
+                    SortModifiers { nulls: NullsOrder::Last, multi_value: Some(MultiValue::Min) },
+                )),
+            ),
+            ("max:desc", Desc(Field(S("max")))),
+            ("_random(42)", Asc(Random(42))),
+            ("_random( 0 )", Asc(Random(0))),
+            ("_random(42):desc", Desc(Random(42))),
         ];
 
         for (req, expected) in valid_req {
@@ -240,6 +368,16 @@ mod tests {
             ("truc:machin", InvalidSyntax { name: S("truc:machin") }),
             ("truc:deesc", InvalidSyntax { name: S("truc:deesc") }),
             ("truc:asc:deesc", InvalidSyntax { name: S("truc:asc:deesc") }),
+            ("truc:nullsFirst", InvalidSyntax { name: S("truc:nullsFirst") }),
+            ("truc:asc:min:max", InvalidModifiers { name: S("truc:asc:min:max") }),
+            (
+                "truc:asc:nullsFirst:nullsLast",
+                InvalidModifiers { name: S("truc:asc:nullsFirst:nullsLast") },
+            ),
+            (
+                "_geoPoint(42, 59):asc:nullsFirst",
+                InvalidModifiers { name: S("_geoPoint(42, 59):asc:nullsFirst") },
+            ),
             ("42desc", InvalidSyntax { name: S("42desc") }),
             ("_geoPoint:asc", ReservedKeyword { name: S("_geoPoint") }),
             ("_geoDistance:asc", ReservedKeyword { name: S("_geoDistance") }),
@@ -262,6 +400,10 @@ mod tests {
             ),
             ("_geoPoint(35, 85, 75):asc", ReservedKeyword { name: S("_geoPoint(35, 85, 75)") }),
             ("_geoPoint(18):asc", ReservedKeyword { name: S("_geoPoint(18)") }),
+            ("_random(doggo)", ReservedKeyword { name: S("_random(doggo)") }),
+            ("_random(-1):asc", ReservedKeyword { name: S("_random(-1)") }),
+            ("_random:asc", ReservedKeyword { name: S("_random") }),
+            ("_random(42):asc:min", InvalidModifiers { name: S("_random(42):asc:min") }),
             ("_geoPoint(200, 200):asc", GeoError(BadGeoError::Lat(200.))),
             ("_geoPoint(90.000001, 0):asc", GeoError(BadGeoError::Lat(90.000001))),
             ("_geoPoint(0, -180.000001):desc", GeoError(BadGeoError::Lng(-180.000001))),
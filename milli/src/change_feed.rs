@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// The number of changes kept in the change feed of an index, the oldest ones being
+/// forgotten when new changes are recorded.
+pub const MAX_RETAINED_DOCUMENT_CHANGES: u64 = 1_000_000;
+
+/// How the documents were changed by a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOperation {
+    Addition,
+    Update,
+    Deletion,
+    /// All the documents of the index were deleted.
+    Clear,
+}
+
+/// A change of the documents, as recorded in the change feed of the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentChange {
+    pub task_uid: u32,
+    /// The external id of the changed document, absent when all the documents are cleared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    pub operation: ChangeOperation,
+}
+
+/// A change of the feed along with its cursor, the changes being returned after a cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeFeedEntry {
+    pub cursor: u64,
+    #[serde(flatten)]
+    pub change: DocumentChange,
+}
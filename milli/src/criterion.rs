@@ -4,6 +4,7 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::ranking_expression::{ExpressionError, RankingExpression};
 use crate::{AscDesc, Member};
 
 #[derive(Error, Debug)]
@@ -22,6 +23,8 @@ pub enum CriterionError {
 `{name}` can only be used for filtering at search time"
     )]
     ReservedNameForFilter { name: String },
+    #[error("`{name}` ranking rule is invalid. The expression is invalid: {error}.")]
+    InvalidExpression { name: String, error: ExpressionError },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -45,6 +48,10 @@ pub enum Criterion {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Sorted by the increasing value of the expression specified.
+    AscExpression(String),
+    /// Sorted by the decreasing value of the expression specified.
+    DescExpression(String),
 }
 
 impl Criterion {
@@ -55,6 +62,20 @@ impl Criterion {
             _otherwise => None,
         }
     }
+
+    /// Returns the names of the fields used by this criterion, to sort the documents by.
+    pub fn sort_fields(&self) -> Vec<String> {
+        match self {
+            Criterion::Asc(name) | Criterion::Desc(name) => vec![name.clone()],
+            Criterion::AscExpression(expression) | Criterion::DescExpression(expression) => {
+                match RankingExpression::from_str(expression) {
+                    Ok(expression) => expression.fields().into_iter().map(String::from).collect(),
+                    Err(_) => Vec::new(),
+                }
+            }
+            _otherwise => Vec::new(),
+        }
+    }
 }
 
 impl FromStr for Criterion {
@@ -68,17 +89,40 @@ impl FromStr for Criterion {
             "attribute" => Ok(Criterion::Attribute),
             "sort" => Ok(Criterion::Sort),
             "exactness" => Ok(Criterion::Exactness),
+            text if text.ends_with(')') && text.starts_with("asc(") => {
+                let expression = parse_expression(text, &text[4..text.len() - 1])?;
+                Ok(Criterion::AscExpression(expression))
+            }
+            text if text.ends_with(')') && text.starts_with("desc(") => {
+                let expression = parse_expression(text, &text[5..text.len() - 1])?;
+                Ok(Criterion::DescExpression(expression))
+            }
             text => match AscDesc::from_str(text)? {
                 AscDesc::Asc(Member::Field(field)) => Ok(Criterion::Asc(field)),
                 AscDesc::Desc(Member::Field(field)) => Ok(Criterion::Desc(field)),
+                AscDesc::Asc(Member::ModifiedField(..))
+                | AscDesc::Desc(Member::ModifiedField(..)) => {
+                    Err(CriterionError::InvalidName { name: text.to_string() })
+                }
                 AscDesc::Asc(Member::Geo(_)) | AscDesc::Desc(Member::Geo(_)) => {
                     Err(CriterionError::ReservedNameForSort { name: "_geoPoint".to_string() })?
                 }
+                AscDesc::Asc(Member::Random(_)) | AscDesc::Desc(Member::Random(_)) => {
+                    Err(CriterionError::ReservedNameForSort { name: "_random".to_string() })?
+                }
             },
         }
     }
 }
 
+/// Checks that the expression of the `name` ranking rule is valid and returns it trimmed.
+fn parse_expression(name: &str, expression: &str) -> Result<String, CriterionError> {
+    match RankingExpression::from_str(expression) {
+        Ok(_) => Ok(expression.trim().to_string()),
+        Err(error) => Err(CriterionError::InvalidExpression { name: name.to_string(), error }),
+    }
+}
+
 pub fn default_criteria() -> Vec<Criterion> {
     vec![
         Criterion::Words,
@@ -103,6 +147,8 @@ impl fmt::Display for Criterion {
             Exactness => f.write_str("exactness"),
             Asc(attr) => write!(f, "{}:asc", attr),
             Desc(attr) => write!(f, "{}:desc", attr),
+            AscExpression(expression) => write!(f, "asc({})", expression),
+            DescExpression(expression) => write!(f, "desc({})", expression),
         }
     }
 }
@@ -129,6 +175,14 @@ mod tests {
             ("truc:machin:desc", Criterion::Desc(S("truc:machin"))),
             ("hello-world!:desc", Criterion::Desc(S("hello-world!"))),
             ("it's spacy over there:asc", Criterion::Asc(S("it's spacy over there"))),
+            ("asc(price * 2)", Criterion::AscExpression(S("price * 2"))),
+            (
+                "desc( log(popularity) * freshness_decay(published_at, 30d) )",
+                Criterion::DescExpression(S(
+                    "log(popularity) * freshness_decay(published_at, 30d)",
+                )),
+            ),
+            ("desc(price):asc", Criterion::Asc(S("desc(price)"))),
         ];
 
         for (input, expected) in valid_criteria {
@@ -157,6 +211,8 @@ mod tests {
             ("_geoDistance:asc", ReservedName { name: S("_geoDistance") }),
             ("_geoPoint:asc", ReservedNameForSort { name: S("_geoPoint") }),
             ("_geoPoint(42, 75):asc", ReservedNameForSort { name: S("_geoPoint") }),
+            ("_random(42)", ReservedNameForSort { name: S("_random") }),
+            ("_random:asc", ReservedNameForSort { name: S("_random") }),
             ("_geoRadius:asc", ReservedNameForFilter { name: S("_geoRadius") }),
             ("_geoRadius(42, 75, 59):asc", ReservedNameForFilter { name: S("_geoRadius") }),
             ("_geoBoundingBox:asc", ReservedNameForFilter { name: S("_geoBoundingBox") }),
@@ -164,6 +220,20 @@ mod tests {
                 "_geoBoundingBox([42, 75], [75, 59]):asc",
                 ReservedNameForFilter { name: S("_geoBoundingBox") },
             ),
+            (
+                "desc(price *)",
+                InvalidExpression {
+                    name: S("desc(price *)"),
+                    error: ExpressionError::UnexpectedEnd,
+                },
+            ),
+            (
+                "asc(square(price))",
+                InvalidExpression {
+                    name: S("asc(square(price))"),
+                    error: ExpressionError::UnknownFunction { name: S("square") },
+                },
+            ),
         ];
 
         for (input, expected) in invalid_criteria {
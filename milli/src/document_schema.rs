@@ -0,0 +1,462 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A JSON Schema the documents added to an index must conform to.
+///
+/// Only a subset of the specification is supported: the `type`, `enum`, `const`, `properties`,
+/// `required`, `additionalProperties`, `items`, `minimum`, `maximum`, `minLength` and `maxLength`
+/// keywords. The annotations, such as `title` or `description`, are ignored and the other
+/// keywords are rejected rather than silently not enforced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Value", into = "Value")]
+pub struct DocumentSchema {
+    raw: Value,
+    root: Node,
+}
+
+impl DocumentSchema {
+    pub fn new(schema: Value) -> Result<Self, DocumentSchemaError> {
+        let root = Node::parse(&schema, "#")?;
+        Ok(DocumentSchema { raw: schema, root })
+    }
+
+    /// The schema as it was defined.
+    pub fn as_value(&self) -> &Value {
+        &self.raw
+    }
+
+    /// Returns the first value of the document that doesn't conform to the schema, if any.
+    pub fn validate(&self, document: &Value) -> Result<(), SchemaViolation> {
+        self.root.validate(document, "")
+    }
+}
+
+impl TryFrom<Value> for DocumentSchema {
+    type Error = DocumentSchemaError;
+
+    fn try_from(schema: Value) -> Result<Self, Self::Error> {
+        DocumentSchema::new(schema)
+    }
+}
+
+impl From<DocumentSchema> for Value {
+    fn from(schema: DocumentSchema) -> Self {
+        schema.raw
+    }
+}
+
+/// A keyword of the schema that can't be enforced, located by its JSON pointer in the schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("`documentSchema` setting is invalid at `{path}`: {message}.")]
+pub struct DocumentSchemaError {
+    pub path: String,
+    pub message: String,
+}
+
+/// A value of a document that doesn't conform to the schema, located by its path in the document,
+/// e.g. `author.name` or `tags[1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "the document {}", self.message)
+        } else {
+            write!(f, "`{}` {}", self.path, self.message)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonType {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    Integer,
+    String,
+}
+
+impl JsonType {
+    const ALL: [JsonType; 7] = [
+        JsonType::Null,
+        JsonType::Boolean,
+        JsonType::Object,
+        JsonType::Array,
+        JsonType::Number,
+        JsonType::Integer,
+        JsonType::String,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            JsonType::Null => "null",
+            JsonType::Boolean => "boolean",
+            JsonType::Object => "object",
+            JsonType::Array => "array",
+            JsonType::Number => "number",
+            JsonType::Integer => "integer",
+            JsonType::String => "string",
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (JsonType::Null, Value::Null)
+            | (JsonType::Boolean, Value::Bool(_))
+            | (JsonType::Object, Value::Object(_))
+            | (JsonType::Array, Value::Array(_))
+            | (JsonType::Number, Value::Number(_))
+            | (JsonType::String, Value::String(_)) => true,
+            (JsonType::Integer, Value::Number(number)) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().map_or(false, |number| number.fract() == 0.0)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A schema, either a boolean accepting or rejecting any value, or a set of keywords.
+#[derive(Debug, Clone)]
+enum Node {
+    Boolean(bool),
+    Keywords(Box<Keywords>),
+}
+
+#[derive(Debug, Clone)]
+struct Keywords {
+    types: Option<Vec<JsonType>>,
+    enum_values: Option<Vec<Value>>,
+    const_value: Option<Value>,
+    properties: BTreeMap<String, Node>,
+    required: Vec<String>,
+    additional_properties: Node,
+    items: Option<Node>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl Default for Keywords {
+    fn default() -> Self {
+        Keywords {
+            types: None,
+            enum_values: None,
+            const_value: None,
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+            additional_properties: Node::Boolean(true),
+            items: None,
+            minimum: None,
+            maximum: None,
+            min_length: None,
+            max_length: None,
+        }
+    }
+}
+
+fn schema_error(path: &str, message: impl Into<String>) -> DocumentSchemaError {
+    DocumentSchemaError { path: path.to_string(), message: message.into() }
+}
+
+impl Node {
+    fn parse(schema: &Value, path: &str) -> Result<Node, DocumentSchemaError> {
+        let object = match schema {
+            Value::Bool(accept) => return Ok(Node::Boolean(*accept)),
+            Value::Object(object) => object,
+            _ => return Err(schema_error(path, "a schema must be an object or a boolean")),
+        };
+
+        let mut keywords = Keywords::default();
+        for (keyword, value) in object {
+            let path = format!("{path}/{keyword}");
+            match keyword.as_str() {
+                "type" => keywords.types = Some(parse_types(value, &path)?),
+                "enum" => match value {
+                    Value::Array(values) => keywords.enum_values = Some(values.clone()),
+                    _ => return Err(schema_error(&path, "`enum` must be an array")),
+                },
+                "const" => keywords.const_value = Some(value.clone()),
+                "properties" => match value {
+                    Value::Object(properties) => {
+                        for (name, schema) in properties {
+                            let node = Node::parse(schema, &format!("{path}/{name}"))?;
+                            keywords.properties.insert(name.clone(), node);
+                        }
+                    }
+                    _ => return Err(schema_error(&path, "`properties` must be an object")),
+                },
+                "required" => {
+                    keywords.required = match value {
+                        Value::Array(names) => names
+                            .iter()
+                            .map(|name| name.as_str().map(String::from))
+                            .collect::<Option<_>>(),
+                        _ => None,
+                    }
+                    .ok_or_else(|| schema_error(&path, "`required` must be an array of strings"))?
+                }
+                "additionalProperties" => {
+                    keywords.additional_properties = Node::parse(value, &path)?
+                }
+                "items" => keywords.items = Some(Node::parse(value, &path)?),
+                "minimum" => keywords.minimum = Some(parse_number(keyword, value, &path)?),
+                "maximum" => keywords.maximum = Some(parse_number(keyword, value, &path)?),
+                "minLength" => keywords.min_length = Some(parse_length(keyword, value, &path)?),
+                "maxLength" => keywords.max_length = Some(parse_length(keyword, value, &path)?),
+                "$schema" | "$id" | "$comment" | "title" | "description" | "default"
+                | "examples" => (),
+                _ => {
+                    return Err(schema_error(
+                        &path,
+                        format!("the `{keyword}` keyword is not supported"),
+                    ))
+                }
+            }
+        }
+
+        Ok(Node::Keywords(Box::new(keywords)))
+    }
+
+    fn validate(&self, value: &Value, path: &str) -> Result<(), SchemaViolation> {
+        let violation = |message: String| SchemaViolation { path: path.to_string(), message };
+        let keywords = match self {
+            Node::Boolean(true) => return Ok(()),
+            Node::Boolean(false) => return Err(violation("is not allowed".to_string())),
+            Node::Keywords(keywords) => keywords,
+        };
+
+        if let Some(types) = &keywords.types {
+            if !types.iter().any(|json_type| json_type.matches(value)) {
+                let types: Vec<_> =
+                    types.iter().map(|json_type| format!("`{}`", json_type.name())).collect();
+                return Err(violation(format!(
+                    "must be of type {}, but found {}",
+                    types.join(" or "),
+                    describe(value)
+                )));
+            }
+        }
+
+        if let Some(values) = &keywords.enum_values {
+            if !values.contains(value) {
+                let values: Vec<_> = values.iter().map(|value| format!("`{value}`")).collect();
+                return Err(violation(format!(
+                    "must be one of {}, but found {}",
+                    values.join(", "),
+                    describe(value)
+                )));
+            }
+        }
+
+        if let Some(constant) = &keywords.const_value {
+            if constant != value {
+                return Err(violation(format!(
+                    "must be `{constant}`, but found {}",
+                    describe(value)
+                )));
+            }
+        }
+
+        match value {
+            Value::Object(object) => {
+                if let Some(name) =
+                    keywords.required.iter().find(|name| !object.contains_key(*name))
+                {
+                    return Err(SchemaViolation {
+                        path: child_path(path, name),
+                        message: "is required".to_string(),
+                    });
+                }
+                for (name, value) in object {
+                    let node =
+                        keywords.properties.get(name).unwrap_or(&keywords.additional_properties);
+                    node.validate(value, &child_path(path, name))?;
+                }
+            }
+            Value::Array(values) => {
+                if let Some(items) = &keywords.items {
+                    for (position, value) in values.iter().enumerate() {
+                        items.validate(value, &format!("{path}[{position}]"))?;
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = keywords.minimum.filter(|minimum| number < *minimum) {
+                    return Err(violation(format!(
+                        "must be greater than or equal to `{minimum}`, but found `{number}`"
+                    )));
+                }
+                if let Some(maximum) = keywords.maximum.filter(|maximum| number > *maximum) {
+                    return Err(violation(format!(
+                        "must be less than or equal to `{maximum}`, but found `{number}`"
+                    )));
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count();
+                if let Some(min_length) = keywords.min_length.filter(|min| length < *min) {
+                    return Err(violation(format!(
+                        "must be at least {min_length} characters long, but found {length} characters"
+                    )));
+                }
+                if let Some(max_length) = keywords.max_length.filter(|max| length > *max) {
+                    return Err(violation(format!(
+                        "must be at most {max_length} characters long, but found {length} characters"
+                    )));
+                }
+            }
+            Value::Null | Value::Bool(_) => (),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_types(value: &Value, path: &str) -> Result<Vec<JsonType>, DocumentSchemaError> {
+    let parse_type = |value: &Value| {
+        let name = value.as_str().unwrap_or_default();
+        JsonType::ALL.into_iter().find(|json_type| json_type.name() == name).ok_or_else(|| {
+            let types: Vec<_> =
+                JsonType::ALL.iter().map(|json_type| format!("`{}`", json_type.name())).collect();
+            schema_error(
+                path,
+                format!("unknown type `{value}`, expected one of {}", types.join(", ")),
+            )
+        })
+    };
+
+    match value {
+        Value::Array(values) => values.iter().map(parse_type).collect(),
+        value => parse_type(value).map(|json_type| vec![json_type]),
+    }
+}
+
+fn parse_number(keyword: &str, value: &Value, path: &str) -> Result<f64, DocumentSchemaError> {
+    value.as_f64().ok_or_else(|| schema_error(path, format!("`{keyword}` must be a number")))
+}
+
+fn parse_length(keyword: &str, value: &Value, path: &str) -> Result<usize, DocumentSchemaError> {
+    value
+        .as_u64()
+        .map(|length| length as usize)
+        .ok_or_else(|| schema_error(path, format!("`{keyword}` must be a positive integer")))
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "`null`".to_string(),
+        Value::Bool(_) => format!("a boolean: `{value}`"),
+        Value::Number(_) => format!("a number: `{value}`"),
+        Value::String(_) => format!("a string: `{value}`"),
+        Value::Array(_) => "an array".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn violation(schema: &DocumentSchema, document: Value) -> Option<String> {
+        schema.validate(&document).err().map(|violation| violation.to_string())
+    }
+
+    #[test]
+    fn validate_documents() {
+        let schema = DocumentSchema::new(json!({
+            "title": "movies",
+            "type": "object",
+            "required": ["id", "title"],
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "title": { "type": "string", "minLength": 1 },
+                "price": { "type": ["number", "null"] },
+                "genres": { "type": "array", "items": { "enum": ["drama", "comedy"] } },
+                "director": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "additionalProperties": false
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(violation(&schema, json!({ "id": 1, "title": "Carol", "price": null })), None);
+        assert_eq!(violation(&schema, json!({ "id": 1, "title": "Carol", "extra": true })), None);
+        assert_eq!(
+            violation(&schema, json!({ "id": 1, "title": "Carol", "price": "12" })).unwrap(),
+            "`price` must be of type `number` or `null`, but found a string: `\"12\"`"
+        );
+        assert_eq!(
+            violation(&schema, json!({ "id": 1.5, "title": "Carol" })).unwrap(),
+            "`id` must be of type `integer`, but found a number: `1.5`"
+        );
+        assert_eq!(
+            violation(&schema, json!({ "id": -1, "title": "Carol" })).unwrap(),
+            "`id` must be greater than or equal to `0`, but found `-1`"
+        );
+        assert_eq!(violation(&schema, json!({ "id": 1 })).unwrap(), "`title` is required");
+        assert_eq!(
+            violation(&schema, json!({ "id": 1, "title": "" })).unwrap(),
+            "`title` must be at least 1 characters long, but found 0 characters"
+        );
+        assert_eq!(
+            violation(&schema, json!({ "id": 1, "title": "Carol", "genres": ["drama", "horror"] }))
+                .unwrap(),
+            "`genres[1]` must be one of `\"drama\"`, `\"comedy\"`, but found a string: `\"horror\"`"
+        );
+        assert_eq!(
+            violation(
+                &schema,
+                json!({ "id": 1, "title": "Carol", "director": { "name": "Todd", "age": 62 } })
+            )
+            .unwrap(),
+            "`director.age` is not allowed"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_schemas() {
+        let error = |schema: Value| DocumentSchema::new(schema).unwrap_err().to_string();
+
+        assert_eq!(
+            error(json!({ "properties": { "price": { "type": "float" } } })),
+            "`documentSchema` setting is invalid at `#/properties/price/type`: unknown type `\"float\"`, expected one of `null`, `boolean`, `object`, `array`, `number`, `integer`, `string`."
+        );
+        assert_eq!(
+            error(json!({ "properties": { "tags": { "uniqueItems": true } } })),
+            "`documentSchema` setting is invalid at `#/properties/tags/uniqueItems`: the `uniqueItems` keyword is not supported."
+        );
+        assert_eq!(
+            error(json!({ "required": "id" })),
+            "`documentSchema` setting is invalid at `#/required`: `required` must be an array of strings."
+        );
+        assert_eq!(
+            error(json!(12)),
+            "`documentSchema` setting is invalid at `#`: a schema must be an object or a boolean."
+        );
+    }
+}
@@ -1,8 +1,14 @@
-use std::io::{self, Write};
+use std::io::{self, BufRead, Write};
 
+use apache_avro::types::Value as AvroValue;
 use grenad::{CompressionType, WriterBuilder};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::file::reader::ChunkReader;
 use serde::de::Deserializer;
 use serde_json::{to_writer, Value};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::OffsetDateTime;
 
 use super::{DocumentsBatchIndex, Error, DOCUMENTS_BATCH_INDEX_KEY};
 use crate::documents::serde_impl::DocumentVisitor;
@@ -88,7 +94,22 @@ impl<W: Write> DocumentsBatchBuilder<W> {
     }
 
     /// Appends a new CSV file into the batch and updates the `DocumentsBatchIndex` accordingly.
-    pub fn append_csv<R: io::Read>(&mut self, mut reader: csv::Reader<R>) -> Result<(), Error> {
+    ///
+    /// The values of the array columns are separated by the [`DEFAULT_CSV_ARRAY_DELIMITER`].
+    pub fn append_csv<R: io::Read>(&mut self, reader: csv::Reader<R>) -> Result<(), Error> {
+        self.append_csv_with_array_delimiter(reader, DEFAULT_CSV_ARRAY_DELIMITER)
+    }
+
+    /// Appends a new CSV file into the batch and updates the `DocumentsBatchIndex` accordingly.
+    ///
+    /// The columns are typed by suffixing their header with `:string`, `:boolean` or `:number`,
+    /// or with `:string[]`, `:boolean[]` or `:number[]` for the arrays whose values are separated
+    /// by the `array_delimiter`, e.g. `drama|comedy` in a `genres:string[]` column.
+    pub fn append_csv_with_array_delimiter<R: io::Read>(
+        &mut self,
+        mut reader: csv::Reader<R>,
+        array_delimiter: u8,
+    ) -> Result<(), Error> {
         // Make sure that we insert the fields ids in order as the obkv writer has this requirement.
         let mut typed_fields_ids: Vec<_> = reader
             .headers()?
@@ -110,56 +131,31 @@ impl<W: Write> DocumentsBatchBuilder<W> {
             self.obkv_buffer.clear();
             let mut writer = obkv::KvWriter::new(&mut self.obkv_buffer);
 
-            for (i, (field_id, type_)) in typed_fields_ids.iter() {
+            for (i, (field_id, column)) in typed_fields_ids.iter() {
                 self.value_buffer.clear();
 
                 let value = &record[*i];
-                let trimmed_value = value.trim();
-                match type_ {
-                    AllowedType::Number => {
-                        if trimmed_value.is_empty() {
-                            to_writer(&mut self.value_buffer, &Value::Null)?;
-                        } else if let Ok(integer) = trimmed_value.parse::<i64>() {
-                            to_writer(&mut self.value_buffer, &integer)?;
-                        } else {
-                            match trimmed_value.parse::<f64>() {
-                                Ok(float) => {
-                                    to_writer(&mut self.value_buffer, &float)?;
-                                }
-                                Err(error) => {
-                                    return Err(Error::ParseFloat {
-                                        error,
-                                        line,
-                                        value: value.to_string(),
-                                    });
-                                }
-                            }
-                        }
+                match column {
+                    CsvColumn::Scalar(type_) => {
+                        write_csv_value(&mut self.value_buffer, *type_, value, line)?
                     }
-                    AllowedType::Boolean => {
-                        if trimmed_value.is_empty() {
-                            to_writer(&mut self.value_buffer, &Value::Null)?;
-                        } else {
-                            match trimmed_value.parse::<bool>() {
-                                Ok(bool) => {
-                                    to_writer(&mut self.value_buffer, &bool)?;
-                                }
-                                Err(error) => {
-                                    return Err(Error::ParseBool {
-                                        error,
-                                        line,
-                                        value: value.to_string(),
-                                    });
-                                }
-                            }
-                        }
+                    // The empty cells are null values, like the empty cells of the other columns.
+                    CsvColumn::Array(_) if value.trim().is_empty() => {
+                        to_writer(&mut self.value_buffer, &Value::Null)?
                     }
-                    AllowedType::String => {
-                        if value.is_empty() {
-                            to_writer(&mut self.value_buffer, &Value::Null)?;
-                        } else {
-                            to_writer(&mut self.value_buffer, value)?;
+                    CsvColumn::Array(type_) => {
+                        self.value_buffer.push(b'[');
+                        let values = value
+                            .split(char::from(array_delimiter))
+                            .map(str::trim)
+                            .filter(|value| !value.is_empty());
+                        for (n, value) in values.enumerate() {
+                            if n != 0 {
+                                self.value_buffer.push(b',');
+                            }
+                            write_csv_value(&mut self.value_buffer, *type_, value, line)?;
                         }
+                        self.value_buffer.push(b']');
                     }
                 }
 
@@ -176,6 +172,68 @@ impl<W: Write> DocumentsBatchBuilder<W> {
         Ok(())
     }
 
+    /// Appends a new Parquet file into the batch and updates the `DocumentsBatchIndex` accordingly.
+    ///
+    /// The file is streamed one record batch at a time and the Arrow values are converted
+    /// into their JSON representation, the null values are skipped.
+    pub fn append_parquet<R: ChunkReader + 'static>(&mut self, reader: R) -> Result<(), Error> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(reader)?.build()?;
+
+        let mut json_buffer = Vec::new();
+        for batch in reader {
+            let batch = batch?;
+
+            json_buffer.clear();
+            let mut writer = arrow_json::LineDelimitedWriter::new(&mut json_buffer);
+            writer.write(&batch)?;
+            writer.finish()?;
+            drop(writer);
+
+            for object in serde_json::Deserializer::from_slice(&json_buffer).into_iter::<Object>() {
+                self.append_json_object(&object?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends the MessagePack maps, or arrays of maps, into the batch and updates the
+    /// `DocumentsBatchIndex` accordingly.
+    ///
+    /// The values are read one after the other until the end of the reader, like the lines
+    /// of an NDJSON payload.
+    pub fn append_msgpack<R: io::Read>(&mut self, reader: R) -> Result<(), Error> {
+        let mut reader = io::BufReader::new(reader);
+        while !reader.fill_buf()?.is_empty() {
+            let mut de = rmp_serde::Deserializer::new(&mut reader);
+            let mut visitor = DocumentVisitor::new(self);
+            de.deserialize_any(&mut visitor)??;
+        }
+
+        Ok(())
+    }
+
+    /// Appends a new Avro object container file into the batch and updates the
+    /// `DocumentsBatchIndex` accordingly.
+    ///
+    /// The records are read using the schema embedded in the file and converted with
+    /// [`avro_to_json`].
+    pub fn append_avro<R: io::Read>(&mut self, reader: R) -> Result<(), Error> {
+        let reader = apache_avro::Reader::new(reader)?;
+        for value in reader {
+            match avro_to_json(value?)? {
+                Value::Object(object) => self.append_json_object(&object)?,
+                value => {
+                    return Err(Error::InvalidAvroValue(format!(
+                        "expected a record but found `{value}`"
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Flushes the content on disk and stores the final version of the `DocumentsBatchIndex`.
     pub fn into_inner(mut self) -> io::Result<W> {
         let DocumentsBatchBuilder { mut writer, fields_index, .. } = self;
@@ -189,27 +247,156 @@ impl<W: Write> DocumentsBatchBuilder<W> {
     }
 }
 
-#[derive(Debug)]
+/// The character separating the values of the array columns of a CSV, e.g. `genres:string[]`.
+pub const DEFAULT_CSV_ARRAY_DELIMITER: u8 = b'|';
+
+#[derive(Debug, Clone, Copy)]
 enum AllowedType {
     String,
     Boolean,
     Number,
 }
 
-fn parse_csv_header(header: &str) -> (&str, AllowedType) {
+#[derive(Debug, Clone, Copy)]
+enum CsvColumn {
+    Scalar(AllowedType),
+    Array(AllowedType),
+}
+
+/// Converts an Avro value into its JSON representation.
+///
+/// The bytes and fixed values must be UTF-8 strings, the dates are written as `YYYY-MM-DD`
+/// and the timestamps, local ones included, as RFC 3339 UTC dates. The times of the day are
+/// kept as numbers. The decimals and durations are rejected as their value depends on the
+/// schema.
+fn avro_to_json(value: AvroValue) -> Result<Value, Error> {
+    Ok(match value {
+        AvroValue::Null => Value::Null,
+        AvroValue::Boolean(boolean) => Value::Bool(boolean),
+        AvroValue::Int(number) | AvroValue::TimeMillis(number) => Value::from(number),
+        AvroValue::Long(number) | AvroValue::TimeMicros(number) => Value::from(number),
+        AvroValue::Float(number) => Value::from(number),
+        AvroValue::Double(number) => Value::from(number),
+        AvroValue::String(string) | AvroValue::Enum(_, string) => Value::String(string),
+        AvroValue::Bytes(bytes) | AvroValue::Fixed(_, bytes) => {
+            Value::String(String::from_utf8(bytes).map_err(|e| e.utf8_error())?)
+        }
+        AvroValue::Uuid(uuid) => Value::String(uuid.to_string()),
+        AvroValue::Date(days) => {
+            let date = avro_timestamp(days as i128 * 86_400 * 1_000_000_000)?.date();
+            let date = date.format(format_description!("[year]-[month]-[day]"));
+            Value::String(date.map_err(|e| Error::InvalidAvroValue(e.to_string()))?)
+        }
+        AvroValue::TimestampMillis(millis) | AvroValue::LocalTimestampMillis(millis) => {
+            let datetime = avro_timestamp(millis as i128 * 1_000_000)?.format(&Rfc3339);
+            Value::String(datetime.map_err(|e| Error::InvalidAvroValue(e.to_string()))?)
+        }
+        AvroValue::TimestampMicros(micros) | AvroValue::LocalTimestampMicros(micros) => {
+            let datetime = avro_timestamp(micros as i128 * 1_000)?.format(&Rfc3339);
+            Value::String(datetime.map_err(|e| Error::InvalidAvroValue(e.to_string()))?)
+        }
+        AvroValue::Union(_, value) => avro_to_json(*value)?,
+        AvroValue::Array(values) => {
+            Value::Array(values.into_iter().map(avro_to_json).collect::<Result<_, _>>()?)
+        }
+        AvroValue::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((key, avro_to_json(value)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        AvroValue::Record(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| Ok((key, avro_to_json(value)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        value => return Err(Error::InvalidAvroValue(format!("unsupported value `{value:?}`"))),
+    })
+}
+
+fn avro_timestamp(nanos: i128) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::from_unix_timestamp_nanos(nanos)
+        .map_err(|e| Error::InvalidAvroValue(e.to_string()))
+}
+
+fn parse_csv_header(header: &str) -> (&str, CsvColumn) {
     // if there are several separators we only split on the last one.
     match header.rsplit_once(':') {
-        Some((field_name, field_type)) => match field_type {
-            "string" => (field_name, AllowedType::String),
-            "boolean" => (field_name, AllowedType::Boolean),
-            "number" => (field_name, AllowedType::Number),
-            // if the pattern isn't reconized, we keep the whole field.
-            _otherwise => (header, AllowedType::String),
-        },
-        None => (header, AllowedType::String),
+        Some((field_name, field_type)) => {
+            let (field_type, is_array) = match field_type.strip_suffix("[]") {
+                Some(field_type) => (field_type, true),
+                None => (field_type, false),
+            };
+            let allowed_type = match field_type {
+                "string" => AllowedType::String,
+                "boolean" => AllowedType::Boolean,
+                "number" => AllowedType::Number,
+                // if the pattern isn't reconized, we keep the whole field.
+                _otherwise => return (header, CsvColumn::Scalar(AllowedType::String)),
+            };
+            if is_array {
+                (field_name, CsvColumn::Array(allowed_type))
+            } else {
+                (field_name, CsvColumn::Scalar(allowed_type))
+            }
+        }
+        None => (header, CsvColumn::Scalar(AllowedType::String)),
     }
 }
 
+/// Writes the JSON representation of the CSV value according to the type of its column,
+/// the empty values being written as `null`.
+fn write_csv_value(
+    buffer: &mut Vec<u8>,
+    type_: AllowedType,
+    value: &str,
+    line: usize,
+) -> Result<(), Error> {
+    let trimmed_value = value.trim();
+    match type_ {
+        AllowedType::Number => {
+            if trimmed_value.is_empty() {
+                to_writer(buffer, &Value::Null)?;
+            } else if let Ok(integer) = trimmed_value.parse::<i64>() {
+                to_writer(buffer, &integer)?;
+            } else {
+                match trimmed_value.parse::<f64>() {
+                    Ok(float) => {
+                        to_writer(buffer, &float)?;
+                    }
+                    Err(error) => {
+                        return Err(Error::ParseFloat { error, line, value: value.to_string() });
+                    }
+                }
+            }
+        }
+        AllowedType::Boolean => {
+            if trimmed_value.is_empty() {
+                to_writer(buffer, &Value::Null)?;
+            } else {
+                match trimmed_value.parse::<bool>() {
+                    Ok(bool) => {
+                        to_writer(buffer, &bool)?;
+                    }
+                    Err(error) => {
+                        return Err(Error::ParseBool { error, line, value: value.to_string() });
+                    }
+                }
+            }
+        }
+        AllowedType::String => {
+            if value.is_empty() {
+                to_writer(buffer, &Value::Null)?;
+            } else {
+                to_writer(buffer, value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use std::io::Cursor;
@@ -278,6 +465,203 @@ mod test {
         assert!(cursor.next_document().unwrap().is_none());
     }
 
+    #[test]
+    fn add_documents_parquet() {
+        use std::sync::Arc;
+
+        use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+        use parquet::arrow::ArrowWriter;
+
+        let ids: ArrayRef = Arc::new(Int64Array::from(vec![1, 2]));
+        let names: ArrayRef = Arc::new(StringArray::from(vec![Some("hello!"), None]));
+        let prices: ArrayRef = Arc::new(Float64Array::from(vec![12.5, 3.0]));
+        let batch =
+            RecordBatch::try_from_iter([("id", ids), ("field", names), ("price", prices)]).unwrap();
+
+        let file = tempfile::tempfile().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(file.try_clone().unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_parquet(file).unwrap();
+        assert_eq!(builder.documents_count(), 2);
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 1, "field": "hello!", "price": 12.5 }));
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 2, "price": 3.0 }));
+
+        assert!(cursor.next_document().unwrap().is_none());
+    }
+
+    #[test]
+    fn add_documents_msgpack() {
+        let documents = json!([
+            { "id": 1, "field": "hello!", "tags": ["a", "b"] },
+            { "id": 2, "price": 3.5 },
+        ]);
+        let msgpack = rmp_serde::to_vec(&documents).unwrap();
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_msgpack(msgpack.as_slice()).unwrap();
+        assert_eq!(builder.documents_count(), 2);
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 1, "field": "hello!", "tags": ["a", "b"] }));
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 2, "price": 3.5 }));
+
+        assert!(cursor.next_document().unwrap().is_none());
+    }
+
+    #[test]
+    fn add_documents_msgpack_stream() {
+        let mut msgpack = rmp_serde::to_vec(&json!({ "id": 1 })).unwrap();
+        msgpack.extend(rmp_serde::to_vec(&json!([{ "id": 2 }, { "id": 3 }])).unwrap());
+        msgpack.extend(rmp_serde::to_vec(&json!({ "id": 4 })).unwrap());
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_msgpack(msgpack.as_slice()).unwrap();
+        assert_eq!(builder.documents_count(), 4);
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        for id in 1..=4 {
+            let doc = cursor.next_document().unwrap().unwrap();
+            let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+            assert_eq!(val, json!({ "id": id }));
+        }
+
+        assert!(cursor.next_document().unwrap().is_none());
+    }
+
+    #[test]
+    fn add_documents_avro() {
+        use apache_avro::types::Record;
+        use apache_avro::{Schema, Writer};
+
+        let schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "document",
+                "fields": [
+                    { "name": "id", "type": "long" },
+                    { "name": "field", "type": ["null", "string"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut writer = Writer::new(&schema, Vec::new());
+        for (id, field) in [(1, Some("hello!")), (2, None)] {
+            let mut record = Record::new(writer.schema()).unwrap();
+            record.put("id", id as i64);
+            record.put("field", field);
+            writer.append(record).unwrap();
+        }
+        let avro = writer.into_inner().unwrap();
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_avro(avro.as_slice()).unwrap();
+        assert_eq!(builder.documents_count(), 2);
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 1, "field": "hello!" }));
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "id": 2, "field": null }));
+
+        assert!(cursor.next_document().unwrap().is_none());
+    }
+
+    #[test]
+    fn add_documents_avro_logical_types() {
+        use apache_avro::types::Record;
+        use apache_avro::{Schema, Writer};
+
+        let schema = Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "document",
+                "fields": [
+                    { "name": "id", "type": "long" },
+                    { "name": "name", "type": "bytes" },
+                    { "name": "birthday", "type": { "type": "int", "logicalType": "date" } },
+                    {
+                        "name": "created_at",
+                        "type": { "type": "long", "logicalType": "timestamp-millis" }
+                    },
+                    {
+                        "name": "kind",
+                        "type": { "type": "enum", "name": "kind", "symbols": ["dog", "cat"] }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut writer = Writer::new(&schema, Vec::new());
+        let mut record = Record::new(writer.schema()).unwrap();
+        record.put("id", 1i64);
+        record.put("name", AvroValue::Bytes(b"kefir".to_vec()));
+        record.put("birthday", AvroValue::Date(19000));
+        record.put("created_at", AvroValue::TimestampMillis(1_700_000_000_000));
+        record.put("kind", AvroValue::Enum(0, "dog".to_string()));
+        writer.append(record).unwrap();
+        let avro = writer.into_inner().unwrap();
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_avro(avro.as_slice()).unwrap();
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(
+            val,
+            json!({
+                "id": 1,
+                "name": "kefir",
+                "birthday": "2022-01-08",
+                "created_at": "2023-11-14T22:13:20Z",
+                "kind": "dog",
+            })
+        );
+
+        assert!(cursor.next_document().unwrap().is_none());
+    }
+
     #[test]
     fn simple_csv_document() {
         let csv_content = r#"city,country,pop
@@ -597,4 +981,72 @@ mod test {
         let mut builder = DocumentsBatchBuilder::new(Vec::new());
         assert!(builder.append_csv(csv).is_err());
     }
+
+    #[test]
+    fn array_types_in_header() {
+        let csv_content = r#"city,tags:string[],sizes:number[],flags:boolean[],empty:number[]
+"Boston","harbor| history |","1|2.5","true|false","""#;
+        let csv = csv::Reader::from_reader(Cursor::new(csv_content));
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_csv(csv).unwrap();
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+
+        assert_eq!(
+            val,
+            json!({
+                "city": "Boston",
+                "tags": ["harbor", "history"],
+                "sizes": [1, 2.5],
+                "flags": [true, false],
+                "empty": null,
+            })
+        );
+    }
+
+    #[test]
+    fn custom_csv_dialect() {
+        let csv_content = "city;tags:string[];pop:number\n'Boston';'harbor,history';'4628910'\n'O\\'Fallon';'';'29000'";
+        let csv = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .quote(b'\'')
+            .escape(Some(b'\\'))
+            .from_reader(Cursor::new(csv_content));
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        builder.append_csv_with_array_delimiter(csv, b',').unwrap();
+        let vector = builder.into_inner().unwrap();
+
+        let (mut cursor, index) = DocumentsBatchReader::from_reader(Cursor::new(vector))
+            .unwrap()
+            .into_cursor_and_fields_index();
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "city": "Boston", "tags": ["harbor", "history"], "pop": 4628910 }));
+
+        let doc = cursor.next_document().unwrap().unwrap();
+        let val = obkv_to_object(&doc, &index).map(Value::from).unwrap();
+        assert_eq!(val, json!({ "city": "O'Fallon", "tags": null, "pop": 29000 }));
+    }
+
+    #[test]
+    fn bad_type_in_array() {
+        let csv_content = r#"city,sizes:number[]
+"Boston","1|big""#;
+        let csv = csv::Reader::from_reader(Cursor::new(csv_content));
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        assert!(matches!(
+            builder.append_csv(csv),
+            Err(Error::ParseFloat { line: 1, ref value, .. }) if value == "big"
+        ));
+    }
 }
@@ -17,6 +17,9 @@ use crate::FieldId;
 ///
 /// The documents are returned in the form of `obkv::Reader` where each field is identified with a
 /// `FieldId`. The mapping between the field ids and the field names is done thanks to the index.
+///
+/// The external ids are stored under the position of their document in the batch, the documents
+/// without an external id are skipped.
 pub struct EnrichedDocumentsBatchReader<R> {
     documents: DocumentsBatchReader<R>,
     primary_key: String,
@@ -29,7 +32,7 @@ impl<R: io::Read + io::Seek> EnrichedDocumentsBatchReader<R> {
         primary_key: String,
         external_ids: grenad::Reader<BufReader<File>>,
     ) -> Result<Self, Error> {
-        if documents.documents_count() as u64 == external_ids.len() {
+        if documents.documents_count() as u64 >= external_ids.len() {
             Ok(EnrichedDocumentsBatchReader {
                 documents,
                 primary_key,
@@ -63,7 +66,9 @@ impl<R: io::Read + io::Seek> EnrichedDocumentsBatchReader<R> {
         let EnrichedDocumentsBatchReader { documents, primary_key, mut external_ids } = self;
         let (documents, fields_index) = documents.into_cursor_and_fields_index();
         external_ids.reset();
-        (EnrichedDocumentsBatchCursor { documents, primary_key, external_ids }, fields_index)
+        let cursor =
+            EnrichedDocumentsBatchCursor { documents, primary_key, external_ids, position: 0 };
+        (cursor, fields_index)
     }
 }
 
@@ -71,12 +76,16 @@ impl<R: io::Read + io::Seek> EnrichedDocumentsBatchReader<R> {
 pub struct EnrichedDocument<'a> {
     pub document: KvReader<'a, FieldId>,
     pub document_id: DocumentId,
+    /// The position of the document in the batch, counting the skipped documents.
+    pub position: u32,
 }
 
 pub struct EnrichedDocumentsBatchCursor<R> {
     documents: DocumentsBatchCursor<R>,
     primary_key: String,
     external_ids: grenad::ReaderCursor<BufReader<File>>,
+    /// The position of the next document of the documents cursor.
+    position: u32,
 }
 
 impl<R> EnrichedDocumentsBatchCursor<R> {
@@ -87,6 +96,7 @@ impl<R> EnrichedDocumentsBatchCursor<R> {
     pub fn reset(&mut self) {
         self.documents.reset();
         self.external_ids.reset();
+        self.position = 0;
     }
 }
 
@@ -96,15 +106,24 @@ impl<R: io::Read + io::Seek> EnrichedDocumentsBatchCursor<R> {
     pub fn next_enriched_document(
         &mut self,
     ) -> Result<Option<EnrichedDocument>, DocumentsBatchCursorError> {
-        let document = self.documents.next_document()?;
-        let document_id = match self.external_ids.move_on_next()? {
-            Some((_, bytes)) => serde_json::from_slice(bytes).map(Some)?,
-            None => None,
+        let (position, document_id) = match self.external_ids.move_on_next()? {
+            Some((key, bytes)) => {
+                let position = key.try_into().map(u32::from_be_bytes).unwrap_or(u32::MAX);
+                (position, serde_json::from_slice(bytes)?)
+            }
+            None => return Ok(None),
         };
 
-        match document.zip(document_id) {
-            Some((document, document_id)) => Ok(Some(EnrichedDocument { document, document_id })),
-            None => Ok(None),
+        // the documents without an external id are skipped
+        while self.position < position {
+            if self.documents.next_document()?.is_none() {
+                return Ok(None);
+            }
+            self.position += 1;
         }
+
+        let document = self.documents.next_document()?;
+        self.position += 1;
+        Ok(document.map(|document| EnrichedDocument { document, document_id, position }))
     }
 }
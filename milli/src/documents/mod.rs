@@ -3,18 +3,23 @@ mod enriched;
 mod primary_key;
 mod reader;
 mod serde_impl;
+mod stream;
 
 use std::fmt::Debug;
 use std::io;
 use std::str::Utf8Error;
 
 use bimap::BiHashMap;
-pub use builder::DocumentsBatchBuilder;
+pub use builder::{DocumentsBatchBuilder, DEFAULT_CSV_ARRAY_DELIMITER};
 pub use enriched::{EnrichedDocument, EnrichedDocumentsBatchCursor, EnrichedDocumentsBatchReader};
 use obkv::KvReader;
-pub use primary_key::{DocumentIdExtractionError, FieldIdMapper, PrimaryKey, DEFAULT_PRIMARY_KEY};
+pub use primary_key::{
+    validate_document_id_value, DocumentIdExtractionError, FieldIdMapper, PrimaryKey,
+    DEFAULT_PRIMARY_KEY,
+};
 pub use reader::{DocumentsBatchCursor, DocumentsBatchCursorError, DocumentsBatchReader};
 use serde::{Deserialize, Serialize};
+pub use stream::NdJsonDocumentsStream;
 
 use crate::error::{FieldIdMapMissingEntry, InternalError};
 use crate::{FieldId, Object, Result};
@@ -110,6 +115,16 @@ pub enum Error {
     #[error(transparent)]
     Csv(#[from] csv::Error),
     #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+    #[error(transparent)]
+    MessagePack(#[from] rmp_serde::decode::Error),
+    #[error(transparent)]
+    Avro(#[from] apache_avro::Error),
+    #[error("Invalid Avro value: {0}")]
+    InvalidAvroValue(String),
+    #[error(transparent)]
     Json(#[from] serde_json::Error),
     #[error(transparent)]
     Serialize(serde_json::Error),
@@ -0,0 +1,64 @@
+use std::io;
+
+use serde_json::de::IoRead;
+use serde_json::StreamDeserializer;
+
+use super::{DocumentsBatchBuilder, Error};
+use crate::Object;
+
+/// The `NdJsonDocumentsStream` parses a stream of NDJSON documents, e.g. a network body,
+/// and writes each document to a documents batch as soon as it is parsed.
+///
+/// The reader is not buffered, wrapping it in a `BufReader` is recommended.
+pub struct NdJsonDocumentsStream<R: io::Read> {
+    documents: StreamDeserializer<'static, IoRead<R>, Object>,
+}
+
+impl<R: io::Read> NdJsonDocumentsStream<R> {
+    pub fn new(reader: R) -> Self {
+        let documents = serde_json::Deserializer::from_reader(reader).into_iter();
+        NdJsonDocumentsStream { documents }
+    }
+
+    /// Appends all the remaining documents of the stream to a single documents batch,
+    /// each document being written as soon as it is parsed instead of being buffered.
+    pub fn append_to<W: io::Write>(
+        &mut self,
+        builder: &mut DocumentsBatchBuilder<W>,
+    ) -> Result<(), Error> {
+        for object in self.documents.by_ref() {
+            builder.append_json_object(&object?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::documents::DocumentsBatchReader;
+
+    #[test]
+    fn ndjson_stream_is_appended_to_the_batch() {
+        let ndjson = r#"{ "id": 1 }
+        { "id": 2, "name": "kevin" }
+        { "id": 3 }"#;
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        NdJsonDocumentsStream::new(ndjson.as_bytes()).append_to(&mut builder).unwrap();
+        let vector = builder.into_inner().unwrap();
+        let batch = DocumentsBatchReader::from_reader(Cursor::new(vector)).unwrap();
+        assert_eq!(batch.documents_count(), 3);
+    }
+
+    #[test]
+    fn ndjson_stream_malformed_document() {
+        let ndjson = r#"{ "id": 1 } { "id": "#;
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        let mut stream = NdJsonDocumentsStream::new(ndjson.as_bytes());
+        assert!(matches!(stream.append_to(&mut builder), Err(Error::Json(_))));
+    }
+}
@@ -8,11 +8,13 @@ use rayon::ThreadPoolBuildError;
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::document_schema::{DocumentSchemaError, SchemaViolation};
 use crate::documents::{self, DocumentsBatchCursorError};
 use crate::{CriterionError, DocumentId, FieldId, Object, SortError};
 
 pub fn is_reserved_keyword(keyword: &str) -> bool {
-    ["_geo", "_geoDistance", "_geoPoint", "_geoRadius", "_geoBoundingBox"].contains(&keyword)
+    ["_geo", "_geoDistance", "_geoPoint", "_geoRadius", "_geoBoundingBox", "_random"]
+        .contains(&keyword)
 }
 
 #[derive(Error, Debug)]
@@ -112,6 +114,8 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     },
     #[error(transparent)]
     InvalidGeoField(#[from] GeoError),
+    #[error("The `_geojson` field in the document with the id: `{document_id}` is not a valid GeoJSON geometry. Was expecting a `Point`, `MultiPoint`, `LineString`, `MultiLineString`, `Polygon` or `MultiPolygon` with longitudes and latitudes in range but instead got `{value}`.")]
+    InvalidGeoJsonField { document_id: Value, value: Value },
     #[error("Invalid vector dimensions: expected: `{}`, found: `{}`.", .expected, .found)]
     InvalidVectorDimensions { expected: usize, found: usize },
     #[error("The `_vectors.{subfield}` field in the document with id: `{document_id}` is not an array. Was expecting an array of floats or an array of arrays of floats but instead got `{value}`.")]
@@ -148,6 +152,43 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         valid_fields: BTreeSet<String>,
         hidden_fields: bool,
     },
+    #[error("Attribute `{}` is not filterable and cannot be used to group the hits. {}",
+        .field,
+        match .valid_fields.is_empty() {
+            true => "This index does not have configured filterable attributes.".to_string(),
+            false => format!("Available filterable attributes are: `{}{}`.",
+                    valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+                    .hidden_fields.then_some(", <..hidden-attributes>").unwrap_or(""),
+                ),
+        }
+    )]
+    InvalidGroupByAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
+    #[error("Attribute `{}` is not filterable and cannot be used as a distinct attribute. {}",
+        .field,
+        match .valid_fields.is_empty() {
+            true => "This index does not have configured filterable attributes.".to_string(),
+            false => format!("Available filterable attributes are: `{}{}`.",
+                    valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+                    .hidden_fields.then_some(", <..hidden-attributes>").unwrap_or(""),
+                ),
+        }
+    )]
+    InvalidDistinctAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
+    #[error("Attribute `{}` is not filterable and cannot be aggregated. {}",
+        .field,
+        match .valid_fields.is_empty() {
+            true => "This index does not have configured filterable attributes.".to_string(),
+            false => format!("Available filterable attributes are: `{}{}`.",
+                    valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+                    .hidden_fields.then_some(", <..hidden-attributes>").unwrap_or(""),
+                ),
+        }
+    )]
+    InvalidAggregationAttribute {
+        field: String,
+        valid_fields: BTreeSet<String>,
+        hidden_fields: bool,
+    },
     #[error("Attribute `{}` is not searchable. Available searchable attributes are: `{}{}`.",
         .field,
         .valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
@@ -162,10 +203,16 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     InvalidLmdbOpenOptions,
     #[error("You must specify where `sort` is listed in the rankingRules setting to use the sort parameter at search time.")]
     SortRankingRuleMissing,
+    #[error("The search cursor does not match the ranking rules of the search. A cursor must be used with the search that returned it.")]
+    InvalidSearchCursor,
     #[error("The database file is in an invalid state.")]
     InvalidStoreFile,
     #[error("Maximum database size has been reached.")]
     MaxDatabaseSizeReached,
+    #[error("The `_version` field in the document with the id: `{document_id}` must be a positive integer, but received: `{version}`.")]
+    InvalidDocumentVersion { document_id: Value, version: Value },
+    #[error("The document with the id: `{document_id}` does not match the document schema of the index: {violation}.")]
+    InvalidDocumentSchema { document_id: Value, violation: SchemaViolation },
     #[error("Document doesn't have a `{}` attribute: `{}`.", .primary_key, serde_json::to_string(.document).unwrap())]
     MissingDocumentId { primary_key: String, document: Object },
     #[error("Document have too many matching `{}` attribute: `{}`.", .primary_key, serde_json::to_string(.document).unwrap())]
@@ -178,6 +225,8 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     NoSpaceLeftOnDevice,
     #[error("Index already has a primary key: `{0}`.")]
     PrimaryKeyCannotBeChanged(String),
+    #[error("The primary key cannot be changed to `{primary_key}` as multiple documents share the `{document_id}` document id.")]
+    DuplicateDocumentId { primary_key: String, document_id: String },
     #[error(transparent)]
     SerdeJson(serde_json::Error),
     #[error(transparent)]
@@ -186,6 +235,16 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     UnknownInternalDocumentId { document_id: DocumentId },
     #[error("`minWordSizeForTypos` setting is invalid. `oneTypo` and `twoTypos` fields should be between `0` and `255`, and `twoTypos` should be greater or equals to `oneTypo` but found `oneTypo: {0}` and twoTypos: {1}`.")]
     InvalidMinTypoWordLenSetting(u8, u8),
+    #[error("`faceting.hierarchySeparators` setting is invalid. The separators of the hierarchical facets must not be empty or only made of whitespaces.")]
+    InvalidFacetHierarchySeparator,
+    #[error("`separatorTokens` and `nonSeparatorTokens` settings are invalid. A token can't be both a separator and a non separator token but found `{0}` in both.")]
+    ConflictingSeparatorToken(String),
+    #[error("`stemming` setting is invalid. Unknown locale `{0}`, expected the ISO 639-3 code of a language having a stemmer.")]
+    UnknownStemmingLocale(String),
+    #[error(transparent)]
+    InvalidDocumentSchemaSetting(#[from] DocumentSchemaError),
+    #[error("`ingestionMapping` setting is invalid. The `{first}` and `{second}` fields can't both be renamed into `{target}`.")]
+    ConflictingIngestionMapping { first: String, second: String, target: String },
     #[error(transparent)]
     VectorEmbeddingError(#[from] crate::vector::Error),
     #[error(transparent)]
@@ -385,7 +444,7 @@ impl From<HeedError> for Error {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FaultSource {
     User,
     Runtime,
@@ -13,6 +13,43 @@ pub fn f64_into_bytes(float: f64) -> Option<[u8; 8]> {
     None
 }
 
+/// Returns the greatest float lower than or equal to the integer
+/// and whether this float is exactly equal to the integer.
+#[inline]
+pub fn i128_into_floor_f64(integer: i128) -> (f64, bool) {
+    // the conversion rounds to the nearest float, which can be greater than the integer,
+    // the integers near `i128::MAX` even round to 2^127 which can't be converted back.
+    let float = integer as f64;
+    if float >= i128::MAX as f64 || float as i128 > integer {
+        (next_down(float), false)
+    } else {
+        (float, float as i128 == integer)
+    }
+}
+
+/// Returns the greatest float lower than a non-zero finite float.
+#[inline]
+fn next_down(float: f64) -> f64 {
+    if float.is_sign_positive() {
+        f64::from_bits(float.to_bits() - 1)
+    } else {
+        f64::from_bits(float.to_bits() + 1)
+    }
+}
+
+#[inline]
+pub fn i128_into_bytes(integer: i128) -> [u8; 16] {
+    let mut x = integer.to_be_bytes();
+    x[0] ^= 0x80;
+    x
+}
+
+#[inline]
+pub fn i128_from_bytes(mut x: [u8; 16]) -> i128 {
+    x[0] ^= 0x80;
+    i128::from_be_bytes(x)
+}
+
 #[inline]
 fn xor_first_bit(mut x: [u8; 8]) -> [u8; 8] {
     x[0] ^= 0x80;
@@ -46,4 +83,33 @@ mod tests {
         let vec: Vec<_> = [a, b, c, d, e].iter().cloned().map(f64_into_bytes).collect();
         assert!(is_sorted(&vec), "{:?}", vec);
     }
+
+    #[test]
+    fn ordered_i128_bytes() {
+        let a = i128::MIN;
+        let b = -(u64::MAX as i128);
+        let c = -1;
+        let d = 0;
+        let e = 9_007_199_254_740_993;
+        let f = i128::MAX;
+
+        let vec: Vec<_> = [a, b, c, d, e, f].iter().cloned().map(i128_into_bytes).collect();
+        assert!(is_sorted(&vec), "{:?}", vec);
+        assert!(vec.into_iter().map(i128_from_bytes).eq([a, b, c, d, e, f]));
+    }
+
+    #[test]
+    fn i128_floor_f64() {
+        assert_eq!(i128_into_floor_f64(42), (42.0, true));
+        assert_eq!(i128_into_floor_f64(-42), (-42.0, true));
+        assert_eq!(i128_into_floor_f64(9_007_199_254_740_993), (9_007_199_254_740_992.0, false));
+        assert_eq!(i128_into_floor_f64(9_007_199_254_740_994), (9_007_199_254_740_994.0, true));
+        // rounds up to the nearest float
+        assert_eq!(i128_into_floor_f64(9_007_199_254_740_995), (9_007_199_254_740_994.0, false));
+        assert_eq!(i128_into_floor_f64(-9_007_199_254_740_995), (-9_007_199_254_740_996.0, false));
+        assert_eq!(i128_into_floor_f64(10_000_000_000_000_000_000), (1e19, true));
+        assert_eq!(i128_into_floor_f64(i128::MIN), (i128::MIN as f64, true));
+        let (float, exact) = i128_into_floor_f64(i128::MAX);
+        assert!(!exact && float < i128::MAX as f64 && float as i128 <= i128::MAX);
+    }
 }
@@ -26,6 +26,11 @@ impl FieldsIdsMap {
         self.names_ids.is_empty()
     }
 
+    /// Returns the number of fields ids that can still be inserted in the map.
+    pub fn remaining_capacity(&self) -> usize {
+        self.next_id.map_or(0, |id| (FieldId::MAX - id) as usize + 1)
+    }
+
     /// Returns the field id related to a field name, it will create a new field id if the
     /// name is not already known. Returns `None` if the maximum field id as been reached.
     pub fn insert(&mut self, name: &str) -> Option<FieldId> {
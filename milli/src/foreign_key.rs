@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// An attribute of the documents referencing a document of another index by its primary key,
+/// for example the `authorId` of books referencing the documents of an `authors` index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignKey {
+    /// The attribute holding the primary key of the foreign document.
+    pub attribute: String,
+    /// The uid of the index containing the foreign documents.
+    pub foreign_index_uid: String,
+}
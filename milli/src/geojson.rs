@@ -0,0 +1,244 @@
+//! The shapes stored in the `_geojson` field of the documents.
+//!
+//! The `_geojson` field contains a [GeoJSON](https://datatracker.ietf.org/doc/html/rfc7946)
+//! geometry, or a feature wrapping one. Its bounding box is indexed in the
+//! `_geojson._minLat`, `_geojson._minLng`, `_geojson._maxLat` and `_geojson._maxLng` facets,
+//! which are used to quickly narrow down the candidates of the `_geoWithin` and
+//! `_geoIntersects` filters before the exact shapes are compared.
+
+use serde_json::{Map, Value};
+
+use crate::is_point_in_polygon;
+
+/// The reserved field containing the shape of the documents. It is neither flattened nor
+/// searchable, its bounding box facets being the only fields derived from it.
+pub const GEOJSON_FIELD: &str = "_geojson";
+
+/// Returns `true` if the field is the `_geojson` field or one of its bounding box facets.
+pub fn is_geojson_field(field: &str) -> bool {
+    field.strip_prefix(GEOJSON_FIELD).map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// The facets containing the bounding box of the `_geojson` field of the documents,
+/// in the order returned by [`GeoShape::bounding_box`].
+pub const GEOJSON_BOUNDING_BOX_FIELDS: [&str; 4] =
+    ["_geojson._minLat", "_geojson._minLng", "_geojson._maxLat", "_geojson._maxLng"];
+
+/// A shape made of points, lines and polygons expressed in terms of latitude and longitude.
+///
+/// Only the exterior ring of the polygons is kept, their holes are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeoShape {
+    points: Vec<[f64; 2]>,
+    lines: Vec<Vec<[f64; 2]>>,
+    polygons: Vec<Vec<[f64; 2]>>,
+}
+
+impl GeoShape {
+    /// Parses a GeoJSON geometry or feature, returns `None` if it is invalid or empty.
+    pub fn from_geojson(value: &Value) -> Option<GeoShape> {
+        let mut shape = GeoShape::default();
+        shape.push_geojson(value.as_object()?)?;
+        if shape.vertices().next().is_none() {
+            None
+        } else {
+            Some(shape)
+        }
+    }
+
+    fn push_geojson(&mut self, object: &Map<String, Value>) -> Option<()> {
+        let coordinates = || object.get("coordinates");
+        match object.get("type")?.as_str()? {
+            "Feature" => self.push_geojson(object.get("geometry")?.as_object()?)?,
+            "GeometryCollection" => {
+                for geometry in object.get("geometries")?.as_array()? {
+                    self.push_geojson(geometry.as_object()?)?;
+                }
+            }
+            "Point" => self.points.push(parse_position(coordinates()?)?),
+            "MultiPoint" => self.points.extend(parse_positions(coordinates()?, 0)?),
+            "LineString" => self.lines.push(parse_positions(coordinates()?, 2)?),
+            "MultiLineString" => {
+                for line in coordinates()?.as_array()? {
+                    self.lines.push(parse_positions(line, 2)?);
+                }
+            }
+            "Polygon" => self.polygons.push(parse_polygon(coordinates()?)?),
+            "MultiPolygon" => {
+                for polygon in coordinates()?.as_array()? {
+                    self.polygons.push(parse_polygon(polygon)?);
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Returns the `[min_lat, min_lng, max_lat, max_lng]` bounding box of the shape.
+    pub fn bounding_box(&self) -> [f64; 4] {
+        let mut bbox = [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY];
+        for [lat, lng] in self.vertices() {
+            bbox[0] = bbox[0].min(*lat);
+            bbox[1] = bbox[1].min(*lng);
+            bbox[2] = bbox[2].max(*lat);
+            bbox[3] = bbox[3].max(*lng);
+        }
+        bbox
+    }
+
+    /// Returns `true` if the whole shape is inside the polygon.
+    pub fn is_within(&self, polygon: &[[f64; 2]]) -> bool {
+        self.vertices().all(|point| is_point_in_polygon(point, polygon))
+            && !self
+                .edges()
+                .any(|edge| polygon_edges(polygon).any(|other| edges_cross(edge, other)))
+    }
+
+    /// Returns `true` if the shape and the polygon have at least one point in common.
+    pub fn intersects(&self, polygon: &[[f64; 2]]) -> bool {
+        self.vertices().any(|point| is_point_in_polygon(point, polygon))
+            || polygon.iter().any(|point| {
+                self.polygons.iter().any(|shape_polygon| is_point_in_polygon(point, shape_polygon))
+            })
+            || self.edges().any(|edge| polygon_edges(polygon).any(|other| edges_cross(edge, other)))
+    }
+
+    fn vertices(&self) -> impl Iterator<Item = &[f64; 2]> {
+        self.points.iter().chain(self.lines.iter().flatten()).chain(self.polygons.iter().flatten())
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&[f64; 2], &[f64; 2])> {
+        let lines = self.lines.iter().flat_map(|line| line.iter().zip(line.iter().skip(1)));
+        lines.chain(self.polygons.iter().flat_map(|polygon| polygon_edges(polygon)))
+    }
+}
+
+/// Returns the edges of the polygon, which is automatically closed.
+fn polygon_edges(polygon: &[[f64; 2]]) -> impl Iterator<Item = (&[f64; 2], &[f64; 2])> {
+    polygon.iter().zip(polygon.iter().cycle().skip(1))
+}
+
+/// Returns `true` if the two edges cross each other, touching is not considered as crossing.
+fn edges_cross((a, b): (&[f64; 2], &[f64; 2]), (c, d): (&[f64; 2], &[f64; 2])) -> bool {
+    fn orientation(a: &[f64; 2], b: &[f64; 2], c: &[f64; 2]) -> f64 {
+        (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+    }
+    orientation(a, b, c) * orientation(a, b, d) < 0.0
+        && orientation(c, d, a) * orientation(c, d, b) < 0.0
+}
+
+/// Parses a GeoJSON `[longitude, latitude]` position into a `[latitude, longitude]` point.
+fn parse_position(value: &Value) -> Option<[f64; 2]> {
+    match value.as_array()?.as_slice() {
+        [lng, lat, ..] => {
+            let (lat, lng) = (lat.as_f64()?, lng.as_f64()?);
+            if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) {
+                Some([lat, lng])
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_positions(value: &Value, min_len: usize) -> Option<Vec<[f64; 2]>> {
+    let positions: Vec<_> = value.as_array()?.iter().map(parse_position).collect::<Option<_>>()?;
+    (positions.len() >= min_len).then_some(positions)
+}
+
+/// Parses the exterior ring of a GeoJSON polygon, its last position closing the ring is removed.
+fn parse_polygon(value: &Value) -> Option<Vec<[f64; 2]>> {
+    let mut ring = parse_positions(value.as_array()?.first()?, 4)?;
+    if ring.first() == ring.last() {
+        ring.pop();
+    }
+    Some(ring)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_geojson() {
+        let shape = GeoShape::from_geojson(&json!({
+            "type": "LineString",
+            "coordinates": [[1.0, 2.0], [3.0, 4.0]],
+        }))
+        .unwrap();
+        assert_eq!(shape.bounding_box(), [2.0, 1.0, 4.0, 3.0]);
+
+        let shape = GeoShape::from_geojson(&json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0, 0], [10, 0], [10, 5], [0, 5], [0, 0]]],
+            },
+        }))
+        .unwrap();
+        assert_eq!(shape.bounding_box(), [0.0, 0.0, 5.0, 10.0]);
+
+        // not closed
+        assert!(GeoShape::from_geojson(
+            &json!({ "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 5]]] })
+        )
+        .is_none());
+        // out of range
+        assert!(
+            GeoShape::from_geojson(&json!({ "type": "Point", "coordinates": [0, 91] })).is_none()
+        );
+        assert!(
+            GeoShape::from_geojson(&json!({ "type": "Circle", "coordinates": [0, 0] })).is_none()
+        );
+        assert!(
+            GeoShape::from_geojson(&json!({ "type": "MultiPoint", "coordinates": [] })).is_none()
+        );
+        assert!(GeoShape::from_geojson(&json!([0, 0])).is_none());
+    }
+
+    #[test]
+    fn within_and_intersects() {
+        let square = [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0]];
+
+        let inner_line =
+            GeoShape { lines: vec![vec![[1.0, 1.0], [9.0, 9.0]]], ..Default::default() };
+        assert!(inner_line.is_within(&square));
+        assert!(inner_line.intersects(&square));
+
+        let crossing_line =
+            GeoShape { lines: vec![vec![[5.0, -5.0], [5.0, 15.0]]], ..Default::default() };
+        assert!(!crossing_line.is_within(&square));
+        assert!(crossing_line.intersects(&square));
+
+        let outer_line =
+            GeoShape { lines: vec![vec![[11.0, 0.0], [11.0, 10.0]]], ..Default::default() };
+        assert!(!outer_line.is_within(&square));
+        assert!(!outer_line.intersects(&square));
+
+        let containing_polygon = GeoShape {
+            polygons: vec![vec![[-1.0, -1.0], [-1.0, 11.0], [11.0, 11.0], [11.0, -1.0]]],
+            ..Default::default()
+        };
+        assert!(!containing_polygon.is_within(&square));
+        assert!(containing_polygon.intersects(&square));
+
+        // all the points of the shape are in the notched polygon but one of its edges is not
+        let notched = [
+            [0.0, 0.0],
+            [0.0, 10.0],
+            [4.0, 10.0],
+            [5.0, 5.0],
+            [6.0, 10.0],
+            [10.0, 10.0],
+            [10.0, 0.0],
+        ];
+        let line = GeoShape { lines: vec![vec![[3.0, 8.0], [7.0, 8.0]]], ..Default::default() };
+        assert!(line.vertices().all(|point| is_point_in_polygon(point, &notched)));
+        assert!(!line.is_within(&notched));
+        assert!(line.intersects(&notched));
+    }
+}
@@ -0,0 +1,28 @@
+use std::borrow::Cow;
+use std::convert::TryInto;
+
+use heed::BoxedError;
+
+pub struct BEU16BEU32Codec;
+
+impl<'a> heed::BytesDecode<'a> for BEU16BEU32Codec {
+    type DItem = (u16, u32);
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        let (left, right) = bytes.split_at(2);
+        let left = left.try_into().map(u16::from_be_bytes)?;
+        let right = right.try_into().map(u32::from_be_bytes)?;
+        Ok((left, right))
+    }
+}
+
+impl<'a> heed::BytesEncode<'a> for BEU16BEU32Codec {
+    type EItem = (u16, u32);
+
+    fn bytes_encode((left, right): &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+        let mut bytes = Vec::with_capacity(2 + 4);
+        bytes.extend_from_slice(&left.to_be_bytes());
+        bytes.extend_from_slice(&right.to_be_bytes());
+        Ok(Cow::Owned(bytes))
+    }
+}
@@ -1,5 +1,6 @@
 mod field_doc_id_facet_codec;
 mod ordered_f64_codec;
+mod ordered_i128_codec;
 
 use std::borrow::Cow;
 use std::convert::TryFrom;
@@ -11,6 +12,7 @@ use roaring::RoaringBitmap;
 
 pub use self::field_doc_id_facet_codec::FieldDocIdFacetCodec;
 pub use self::ordered_f64_codec::OrderedF64Codec;
+pub use self::ordered_i128_codec::OrderedI128Codec;
 use super::StrRefCodec;
 use crate::{CboRoaringBitmapCodec, BEU16};
 
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+use std::convert::TryInto;
+
+use heed::{BoxedError, BytesDecode};
+
+use super::OrderedF64Codec;
+use crate::facet::value_encoding::{i128_from_bytes, i128_into_bytes, i128_into_floor_f64};
+
+/// Encodes an integer in the facet numbers databases without losing its precision.
+///
+/// The integers that an `f64` represents exactly are encoded like the [`OrderedF64Codec`] does.
+/// The others are encoded like the greatest float lower than them, followed by their globally
+/// ordered representation, that way they are sorted between this float and the next one and
+/// the integers that round to the same float are distinct. The encoding of the floats is
+/// left untouched, so are the facet numbers already stored in the databases.
+pub struct OrderedI128Codec;
+
+impl<'a> BytesDecode<'a> for OrderedI128Codec {
+    type DItem = i128;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        match bytes.get(16..32) {
+            Some(bytes) => bytes.try_into().map(i128_from_bytes).map_err(Into::into),
+            None => OrderedF64Codec::bytes_decode(bytes).map(|float| float as i128),
+        }
+    }
+}
+
+impl heed::BytesEncode<'_> for OrderedI128Codec {
+    type EItem = i128;
+
+    fn bytes_encode(integer: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+        let (float, exact) = i128_into_floor_f64(*integer);
+        let mut buffer = OrderedF64Codec::bytes_encode(&float)?.into_owned();
+        if !exact {
+            buffer.extend_from_slice(&i128_into_bytes(*integer));
+        }
+
+        Ok(Cow::Owned(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heed::{BytesDecode, BytesEncode};
+
+    use super::*;
+
+    #[test]
+    fn big_integers_are_ordered_and_distinct() {
+        // the first three integers round to the same float
+        let integers = [
+            9_007_199_254_740_992_i128,
+            9_007_199_254_740_993,
+            9_007_199_254_740_994,
+            9_007_199_254_740_995,
+            9_007_199_254_740_996,
+        ];
+        let encoded: Vec<_> = integers
+            .iter()
+            .map(|i| OrderedI128Codec::bytes_encode(i).unwrap().into_owned())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        let lengths: Vec<_> = encoded.iter().map(Vec::len).collect();
+        assert_eq!(lengths, [16, 32, 16, 32, 16]);
+
+        let decoded: Vec<_> =
+            encoded.iter().map(|bytes| OrderedI128Codec::bytes_decode(bytes).unwrap()).collect();
+        assert_eq!(decoded, integers);
+        // the inexact integers are read as the greatest float lower than them
+        let floats: Vec<_> =
+            encoded.iter().map(|bytes| OrderedF64Codec::bytes_decode(bytes).unwrap()).collect();
+        assert_eq!(
+            floats,
+            [
+                9_007_199_254_740_992.0,
+                9_007_199_254_740_992.0,
+                9_007_199_254_740_994.0,
+                9_007_199_254_740_994.0,
+                9_007_199_254_740_996.0
+            ]
+        );
+    }
+
+    #[test]
+    fn big_integers_are_sorted_with_floats() {
+        let float = |f: f64| OrderedF64Codec::bytes_encode(&f).unwrap().into_owned();
+        let integer = |i: i128| OrderedI128Codec::bytes_encode(&i).unwrap().into_owned();
+
+        assert!(float(9_007_199_254_740_992.0) < integer(9_007_199_254_740_993));
+        assert!(integer(9_007_199_254_740_993) < float(9_007_199_254_740_994.0));
+        assert!(float(-9_007_199_254_740_996.0) < integer(-9_007_199_254_740_995));
+        assert!(integer(-9_007_199_254_740_995) < float(-9_007_199_254_740_994.0));
+        assert!(float(1e38) < integer(i128::MAX));
+        assert!(integer(i128::MAX) < float(1e39));
+    }
+
+    #[test]
+    fn encoding_is_consistent_with_floats() {
+        for float in [-1e300, -1e19, -1.5, 0.0, 42.0, 1e19, 1e300] {
+            let integer = float as i128;
+            if integer as f64 == float {
+                assert_eq!(
+                    OrderedI128Codec::bytes_encode(&integer).unwrap(),
+                    OrderedF64Codec::bytes_encode(&float).unwrap(),
+                );
+            }
+        }
+        let small = OrderedI128Codec::bytes_encode(&42).unwrap();
+        assert_eq!(small.len(), 16);
+    }
+}
@@ -1,3 +1,4 @@
+mod beu16_beu32_codec;
 mod beu16_str_codec;
 mod beu32_str_codec;
 mod byte_slice_ref;
@@ -17,6 +18,7 @@ use heed::BoxedError;
 pub use str_ref::StrRefCodec;
 use thiserror::Error;
 
+pub use self::beu16_beu32_codec::BEU16BEU32Codec;
 pub use self::beu16_str_codec::BEU16StrCodec;
 pub use self::beu32_str_codec::BEU32StrCodec;
 pub use self::field_id_word_count_codec::FieldIdWordCountCodec;
@@ -0,0 +1,140 @@
+use charabia::{Normalize, Token};
+use smallvec::{smallvec, SmallVec};
+
+/// Splits the words written in camelCase or in snake_case into one token by sub-word,
+/// `getUserName` and `get_user_name` both becoming `get`, `user` and `name`.
+///
+/// The tokens must come from the tokenization of the text, their offsets being used to
+/// retrieve the original case of the words.
+pub fn split_identifiers<'o>(
+    text: &'o str,
+    tokens: impl Iterator<Item = Token<'o>>,
+    enabled: bool,
+) -> impl Iterator<Item = Token<'o>> {
+    split_tokens(text, tokens, enabled, false)
+}
+
+/// Splits the identifiers like [`split_identifiers`] but keeps each split word in front of
+/// its sub-words, the documents being found by the whole word as well as by its sub-words.
+pub fn split_identifiers_keeping_words<'o>(
+    text: &'o str,
+    tokens: impl Iterator<Item = Token<'o>>,
+    enabled: bool,
+) -> impl Iterator<Item = Token<'o>> {
+    split_tokens(text, tokens, enabled, true)
+}
+
+fn split_tokens<'o>(
+    text: &'o str,
+    tokens: impl Iterator<Item = Token<'o>>,
+    enabled: bool,
+    keep_word: bool,
+) -> impl Iterator<Item = Token<'o>> {
+    tokens.flat_map(move |token| {
+        if enabled && token.is_word() {
+            split_identifier(text, token, keep_word)
+        } else {
+            smallvec![token]
+        }
+    })
+}
+
+fn split_identifier<'o>(
+    text: &'o str,
+    token: Token<'o>,
+    keep_word: bool,
+) -> SmallVec<[Token<'o>; 1]> {
+    let Some(original) = text.get(token.byte_start..token.byte_end) else {
+        return smallvec![token];
+    };
+
+    let bounds = sub_word_bounds(original);
+    if bounds.len() <= 1 {
+        return smallvec![token];
+    }
+
+    let word = keep_word.then(|| token.clone());
+    let sub_words = bounds.into_iter().map(|(start, end)| {
+        let sub_word = &original[start..end];
+        let char_start = token.char_start + original[..start].chars().count();
+
+        // the characters are normalized one by one to keep the char map of the token
+        let mut lemma = String::new();
+        let mut char_map = Vec::new();
+        for c in sub_word.chars() {
+            let mut buffer = [0; 4];
+            let normalized = (&*c.encode_utf8(&mut buffer)).normalize(&Default::default());
+            lemma.push_str(&normalized);
+            char_map.push((c.len_utf8() as u8, normalized.len() as u8));
+        }
+
+        Token {
+            lemma: lemma.into(),
+            char_start,
+            char_end: char_start + sub_word.chars().count(),
+            byte_start: token.byte_start + start,
+            byte_end: token.byte_start + end,
+            char_map: token.char_map.as_ref().map(|_| char_map),
+            ..token.clone()
+        }
+    });
+
+    word.into_iter().chain(sub_words).collect()
+}
+
+/// Returns the byte ranges of the sub-words of the word, split on the underscores
+/// and on the lowercase to uppercase transitions, the acronyms being kept together.
+fn sub_word_bounds(word: &str) -> Vec<(usize, usize)> {
+    let mut bounds = Vec::new();
+    let mut start = 0;
+    let mut chars = word.char_indices().peekable();
+    let mut previous: Option<char> = None;
+
+    while let Some((i, c)) = chars.next() {
+        if c == '_' {
+            if start < i {
+                bounds.push((start, i));
+            }
+            start = i + c.len_utf8();
+            previous = None;
+            continue;
+        }
+
+        if let Some(p) = previous {
+            let next_is_lowercase = chars.peek().map_or(false, |(_, n)| n.is_lowercase());
+            let camel_case = (p.is_lowercase() || p.is_numeric()) && c.is_uppercase();
+            let acronym_end = p.is_uppercase() && c.is_uppercase() && next_is_lowercase;
+            if (camel_case || acronym_end) && start < i {
+                bounds.push((start, i));
+                start = i;
+            }
+        }
+        previous = Some(c);
+    }
+
+    if start < word.len() {
+        bounds.push((start, word.len()));
+    }
+
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sub_word_bounds;
+
+    fn split(word: &str) -> Vec<&str> {
+        sub_word_bounds(word).into_iter().map(|(start, end)| &word[start..end]).collect()
+    }
+
+    #[test]
+    fn sub_words() {
+        assert_eq!(split("getUserName"), vec!["get", "User", "Name"]);
+        assert_eq!(split("get_user_name"), vec!["get", "user", "name"]);
+        assert_eq!(split("__init__"), vec!["init"]);
+        assert_eq!(split("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(split("parseJSON"), vec!["parse", "JSON"]);
+        assert_eq!(split("utf8Decode"), vec!["utf8", "Decode"]);
+        assert_eq!(split("Meilisearch"), vec!["Meilisearch"]);
+    }
+}
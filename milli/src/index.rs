@@ -10,18 +10,28 @@ use roaring::RoaringBitmap;
 use rstar::RTree;
 use time::OffsetDateTime;
 
+use crate::change_feed::{ChangeFeedEntry, DocumentChange, MAX_RETAINED_DOCUMENT_CHANGES};
+use crate::document_schema::DocumentSchema;
 use crate::documents::PrimaryKey;
 use crate::error::{InternalError, UserError};
 use crate::fields_ids_map::FieldsIdsMap;
+use crate::foreign_key::ForeignKey;
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
     FieldIdCodec, OrderedF64Codec,
 };
 use crate::heed_codec::{
-    BEU16StrCodec, FstSetCodec, ScriptLanguageCodec, StrBEU16Codec, StrRefCodec,
+    BEU16BEU32Codec, BEU16StrCodec, BytesRefCodec, FstSetCodec, ScriptLanguageCodec, StrBEU16Codec,
+    StrRefCodec,
 };
+use crate::localized_attributes_rules::LocalizedAttributesRule;
 use crate::proximity::ProximityPrecision;
-use crate::vector::EmbeddingConfig;
+use crate::query_rules::QueryRule;
+use crate::search::facet::{ascending_facet_sort, descending_facet_sort, CachedFacetDistribution};
+use crate::static_boost::StaticBoost;
+use crate::update::{MergeStrategy, TypeCoercion};
+use crate::vector::rerank::RerankerOptions;
+use crate::vector::{Distance, Embedding, EmbeddingConfig};
 use crate::{
     default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
     FacetDistribution, FieldDistribution, FieldId, FieldIdWordCountCodec, GeoPoint, ObkvCodec,
@@ -40,6 +50,23 @@ pub mod main_key {
     pub const HIDDEN_FACETED_FIELDS_KEY: &str = "hidden-faceted-fields";
     pub const FILTERABLE_FIELDS_KEY: &str = "filterable-fields";
     pub const SORTABLE_FIELDS_KEY: &str = "sortable-fields";
+    pub const NON_FLATTENED_FIELDS_KEY: &str = "non-flattened-fields";
+    pub const MERGE_STRATEGIES_KEY: &str = "merge-strategies";
+    pub const TYPE_COERCIONS_KEY: &str = "type-coercions";
+    pub const INGESTION_MAPPING_KEY: &str = "ingestion-mapping";
+    pub const FACET_HIERARCHY_SEPARATORS_KEY: &str = "facet-hierarchy-separators";
+    pub const DATE_FIELDS_KEY: &str = "date-fields";
+    pub const SUGGESTABLE_FIELDS_KEY: &str = "suggestable-fields";
+    pub const SUGGESTIONS_FST_KEY: &str = "suggestions-fst";
+    pub const STATIC_BOOST_KEY: &str = "static-boost";
+    pub const FOREIGN_KEYS_KEY: &str = "foreign-keys";
+    pub const QUERY_RULES_KEY: &str = "query-rules";
+    pub const LOCALIZED_ATTRIBUTES_RULES_KEY: &str = "localized-attributes-rules";
+    pub const STEMMING_KEY: &str = "stemming";
+    pub const ROMANIZATION_KEY: &str = "romanization";
+    pub const SPLIT_IDENTIFIERS_KEY: &str = "split-identifiers";
+    pub const DOCUMENT_SCHEMA_KEY: &str = "document-schema";
+    pub const RERANKER_KEY: &str = "reranker";
     pub const FIELD_DISTRIBUTION_KEY: &str = "fields-distribution";
     pub const FIELDS_IDS_MAP_KEY: &str = "fields-ids-map";
     pub const GEO_FACETED_DOCUMENTS_IDS_KEY: &str = "geo-faceted-documents-ids";
@@ -53,11 +80,16 @@ pub mod main_key {
     pub const DICTIONARY_KEY: &str = "dictionary";
     pub const SYNONYMS_KEY: &str = "synonyms";
     pub const USER_DEFINED_SYNONYMS_KEY: &str = "user-defined-synonyms";
+    pub const MAX_SYNONYM_WORDS_KEY: &str = "max-synonym-words";
     pub const WORDS_FST_KEY: &str = "words-fst";
     pub const WORDS_PREFIXES_FST_KEY: &str = "words-prefixes-fst";
+    pub const WORD_STEMS_FST_KEY: &str = "word-stems-fst";
+    pub const WORD_ROMANIZATIONS_FST_KEY: &str = "word-romanizations-fst";
     pub const CREATED_AT_KEY: &str = "created-at";
     pub const UPDATED_AT_KEY: &str = "updated-at";
     pub const AUTHORIZE_TYPOS: &str = "authorize-typos";
+    pub const DISABLE_TYPO_ON_FIRST_CHARACTER: &str = "disable-typo-on-first-character";
+    pub const AUTHORIZE_TYPOS_ON_NUMBERS: &str = "authorize-typos-on-numbers";
     pub const ONE_TYPO_WORD_LEN: &str = "one-typo-word-len";
     pub const TWO_TYPOS_WORD_LEN: &str = "two-typos-word-len";
     pub const EXACT_WORDS: &str = "exact-words";
@@ -67,6 +99,7 @@ pub mod main_key {
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
+    pub const CHANGE_FEED_EPOCH_KEY: &str = "change-feed-epoch";
 }
 
 pub mod db_name {
@@ -90,12 +123,19 @@ pub mod db_name {
     pub const FACET_ID_STRING_DOCIDS: &str = "facet-id-string-docids";
     pub const FACET_ID_NORMALIZED_STRING_STRINGS: &str = "facet-id-normalized-string-strings";
     pub const FACET_ID_STRING_FST: &str = "facet-id-string-fst";
+    pub const FACET_ID_DISTRIBUTION: &str = "facet-id-distribution";
     pub const FIELD_ID_DOCID_FACET_F64S: &str = "field-id-docid-facet-f64s";
     pub const FIELD_ID_DOCID_FACET_STRINGS: &str = "field-id-docid-facet-strings";
     pub const VECTOR_EMBEDDER_CATEGORY_ID: &str = "vector-embedder-category-id";
     pub const VECTOR_ARROY: &str = "vector-arroy";
+    pub const VECTOR_ARROY_DOT_PRODUCT: &str = "vector-arroy-dot-product";
+    pub const VECTOR_ARROY_EUCLIDEAN: &str = "vector-arroy-euclidean";
+    pub const VECTOR_BINARY_QUANTIZED: &str = "vector-binary-quantized";
+    pub const VECTOR_PROMPT_HASHES: &str = "vector-prompt-hashes";
     pub const DOCUMENTS: &str = "documents";
+    pub const PHRASE_SYNONYMS: &str = "phrase-synonyms";
     pub const SCRIPT_LANGUAGE_DOCIDS: &str = "script_language_docids";
+    pub const CHANGE_FEED: &str = "change-feed";
 }
 
 #[derive(Clone)]
@@ -154,6 +194,8 @@ pub struct Index {
     pub facet_id_normalized_string_strings: Database<BEU16StrCodec, SerdeJson<BTreeSet<String>>>,
     /// Maps the facet field id of the string facets with an FST containing all the facets values.
     pub facet_id_string_fst: Database<BEU16, FstSetCodec>,
+    /// Maps the facet field id with the number of documents of each of its values.
+    pub facet_id_distribution: Database<BEU16, SerdeBincode<CachedFacetDistribution>>,
 
     /// Maps the document id, the facet field id and the numbers.
     pub field_id_docid_facet_f64s: Database<FieldDocIdFacetF64Codec, Unit>,
@@ -162,13 +204,33 @@ pub struct Index {
 
     /// Maps an embedder name to its id in the arroy store.
     pub embedder_category_id: Database<Str, U8>,
-    /// Vector store based on arroy™.
+    /// Vector store based on arroy™, for the embedders using the cosine distance.
     pub vector_arroy: arroy::Database<arroy::distances::Angular>,
+    /// Vector store for the embedders using the dot product distance.
+    pub vector_arroy_dot_product: arroy::Database<arroy::distances::DotProduct>,
+    /// Vector store for the embedders using the euclidean distance.
+    pub vector_arroy_euclidean: arroy::Database<arroy::distances::Euclidean>,
+    /// Maps the arroy store index and the document id with the 1-bit quantized vector
+    /// of the embedders configured with binary quantization.
+    pub vector_binary_quantized: Database<BEU16BEU32Codec, Bytes>,
+    /// Maps the embedder id and the document id with the hash of the embedder options and
+    /// of the prompt its embeddings were generated from.
+    pub vector_prompt_hashes: Database<BEU16BEU32Codec, BEU64>,
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
+
+    /// Maps a normalized phrase, its words separated by [`SYNONYM_WORDS_SEPARATOR`],
+    /// with the phrases it is a synonym of.
+    pub phrase_synonyms: Database<Str, SerdeBincode<Vec<Vec<String>>>>,
+
+    /// Maps a cursor, increasing with each change, with a change made to the documents.
+    pub change_feed: Database<BEU64, SerdeJson<DocumentChange>>,
 }
 
+/// Separates the words of the phrases in the keys of the synonyms database.
+pub const SYNONYM_WORDS_SEPARATOR: char = '\0';
+
 impl Index {
     pub fn new_with_creation_dates<P: AsRef<Path>>(
         mut options: heed::EnvOpenOptions,
@@ -178,7 +240,7 @@ impl Index {
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(32);
 
         let env = options.open(path)?;
         let mut wtxn = env.write_txn()?;
@@ -208,6 +270,7 @@ impl Index {
         let facet_id_normalized_string_strings =
             env.create_database(&mut wtxn, Some(FACET_ID_NORMALIZED_STRING_STRINGS))?;
         let facet_id_string_fst = env.create_database(&mut wtxn, Some(FACET_ID_STRING_FST))?;
+        let facet_id_distribution = env.create_database(&mut wtxn, Some(FACET_ID_DISTRIBUTION))?;
         let facet_id_exists_docids =
             env.create_database(&mut wtxn, Some(FACET_ID_EXISTS_DOCIDS))?;
         let facet_id_is_null_docids =
@@ -222,8 +285,17 @@ impl Index {
         let embedder_category_id =
             env.create_database(&mut wtxn, Some(VECTOR_EMBEDDER_CATEGORY_ID))?;
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
+        let vector_arroy_dot_product =
+            env.create_database(&mut wtxn, Some(VECTOR_ARROY_DOT_PRODUCT))?;
+        let vector_arroy_euclidean =
+            env.create_database(&mut wtxn, Some(VECTOR_ARROY_EUCLIDEAN))?;
+        let vector_binary_quantized =
+            env.create_database(&mut wtxn, Some(VECTOR_BINARY_QUANTIZED))?;
+        let vector_prompt_hashes = env.create_database(&mut wtxn, Some(VECTOR_PROMPT_HASHES))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
+        let phrase_synonyms = env.create_database(&mut wtxn, Some(PHRASE_SYNONYMS))?;
+        let change_feed = env.create_database(&mut wtxn, Some(CHANGE_FEED))?;
         wtxn.commit()?;
 
         Index::set_creation_dates(&env, main, created_at, updated_at)?;
@@ -247,14 +319,21 @@ impl Index {
             facet_id_string_docids,
             facet_id_normalized_string_strings,
             facet_id_string_fst,
+            facet_id_distribution,
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_arroy_dot_product,
+            vector_arroy_euclidean,
+            vector_binary_quantized,
+            vector_prompt_hashes,
             embedder_category_id,
             documents,
+            phrase_synonyms,
+            change_feed,
         })
     }
 
@@ -757,6 +836,323 @@ impl Index {
         Ok(fields.into_iter().filter_map(|name| fields_ids_map.id(&name)).collect())
     }
 
+    /* non flattened fields */
+
+    /// Writes the names of the fields that must not be flattened in the database.
+    pub(crate) fn put_non_flattened_fields(
+        &self,
+        wtxn: &mut RwTxn,
+        fields: &HashSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::NON_FLATTENED_FIELDS_KEY,
+            fields,
+        )
+    }
+
+    /// Deletes the names of the fields that must not be flattened in the database.
+    pub(crate) fn delete_non_flattened_fields(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::NON_FLATTENED_FIELDS_KEY)
+    }
+
+    /// Returns the names of the fields whose nested objects are stored
+    /// verbatim and never flattened into `a.b.c` fields.
+    pub fn non_flattened_fields(&self, rtxn: &RoTxn) -> heed::Result<HashSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::NON_FLATTENED_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* merge strategies */
+
+    /// Writes the merge strategies of the fields in the database.
+    pub(crate) fn put_merge_strategies(
+        &self,
+        wtxn: &mut RwTxn,
+        strategies: &BTreeMap<String, MergeStrategy>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::MERGE_STRATEGIES_KEY,
+            strategies,
+        )
+    }
+
+    /// Deletes the merge strategies of the fields in the database.
+    pub(crate) fn delete_merge_strategies(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::MERGE_STRATEGIES_KEY)
+    }
+
+    /// Returns the strategies used to combine the stored and new values of the
+    /// fields when documents are updated, indexed by field name.
+    pub fn merge_strategies(&self, rtxn: &RoTxn) -> heed::Result<BTreeMap<String, MergeStrategy>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::MERGE_STRATEGIES_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* type coercions */
+
+    /// Writes the types the values of the fields are converted to in the database.
+    pub(crate) fn put_type_coercions(
+        &self,
+        wtxn: &mut RwTxn,
+        coercions: &BTreeMap<String, TypeCoercion>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::TYPE_COERCIONS_KEY,
+            coercions,
+        )
+    }
+
+    /// Deletes the type coercions of the fields in the database.
+    pub(crate) fn delete_type_coercions(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::TYPE_COERCIONS_KEY)
+    }
+
+    /// Returns the types the values of the fields are converted to when
+    /// documents are added, indexed by field name.
+    pub fn type_coercions(&self, rtxn: &RoTxn) -> heed::Result<BTreeMap<String, TypeCoercion>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::TYPE_COERCIONS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* ingestion mapping */
+
+    /// Writes the new names of the fields of the added documents in the database.
+    pub(crate) fn put_ingestion_mapping(
+        &self,
+        wtxn: &mut RwTxn,
+        mapping: &BTreeMap<String, String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::INGESTION_MAPPING_KEY,
+            mapping,
+        )
+    }
+
+    /// Deletes the ingestion mapping of the fields in the database.
+    pub(crate) fn delete_ingestion_mapping(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::INGESTION_MAPPING_KEY)
+    }
+
+    /// Returns the new names of the fields of the documents when they are added,
+    /// indexed by the path of the fields in the documents.
+    pub fn ingestion_mapping(&self, rtxn: &RoTxn) -> heed::Result<BTreeMap<String, String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::INGESTION_MAPPING_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* date fields */
+
+    /// Writes the names of the fields whose RFC 3339 dates are indexed as timestamps.
+    pub(crate) fn put_date_fields(
+        &self,
+        wtxn: &mut RwTxn,
+        fields: &HashSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(wtxn, main_key::DATE_FIELDS_KEY, fields)
+    }
+
+    /// Deletes the names of the date fields in the database.
+    pub(crate) fn delete_date_fields(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::DATE_FIELDS_KEY)
+    }
+
+    /// Returns the names of the fields whose RFC 3339 dates are indexed as timestamps
+    /// in the facet numbers database, to be filtered and sorted chronologically.
+    pub fn date_fields(&self, rtxn: &RoTxn) -> heed::Result<HashSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::DATE_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /// Identical to `date_fields`, but returns the ids instead, along with
+    /// the ids of the fields nested in the date fields.
+    pub fn date_fields_ids(&self, rtxn: &RoTxn) -> Result<HashSet<FieldId>> {
+        let fields = self.date_fields(rtxn)?;
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+
+        Ok(fields_ids_map
+            .iter()
+            .filter(|(_, name)| fields.iter().any(|field| crate::is_faceted_by(name, field)))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /* suggestable fields */
+
+    /// Writes the names of the fields whose values are suggested by the suggestions.
+    pub(crate) fn put_suggestable_fields(
+        &self,
+        wtxn: &mut RwTxn,
+        fields: &HashSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::SUGGESTABLE_FIELDS_KEY,
+            fields,
+        )
+    }
+
+    /// Deletes the names of the suggestable fields in the database.
+    pub(crate) fn delete_suggestable_fields(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SUGGESTABLE_FIELDS_KEY)
+    }
+
+    /// Returns the names of the fields whose values are suggested by the suggestions.
+    pub fn suggestable_fields(&self, rtxn: &RoTxn) -> heed::Result<HashSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::SUGGESTABLE_FIELDS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* suggestions fst */
+
+    /// Writes the FST of the suggestions, mapping their keys to their number of documents.
+    pub(crate) fn put_suggestions_fst<A: AsRef<[u8]>>(
+        &self,
+        wtxn: &mut RwTxn,
+        fst: &fst::Map<A>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, Bytes>().put(
+            wtxn,
+            main_key::SUGGESTIONS_FST_KEY,
+            fst.as_fst().as_bytes(),
+        )
+    }
+
+    /// Deletes the FST of the suggestions.
+    pub(crate) fn delete_suggestions_fst(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SUGGESTIONS_FST_KEY)
+    }
+
+    /// Returns the FST of the suggestions, mapping their keys to their number of documents.
+    pub fn suggestions_fst<'t>(&self, rtxn: &'t RoTxn) -> Result<fst::Map<Cow<'t, [u8]>>> {
+        match self.main.remap_types::<Str, Bytes>().get(rtxn, main_key::SUGGESTIONS_FST_KEY)? {
+            Some(bytes) => Ok(fst::Map::new(bytes)?.map_data(Cow::Borrowed)?),
+            None => Ok(fst::Map::default().map_data(Cow::Owned)?),
+        }
+    }
+
+    /* change feed */
+
+    /// Appends the changes to the change feed, the first one getting the cursor following the
+    /// one of the last recorded change, and forgets the changes exceeding the retention limit.
+    ///
+    /// The feed gets a new epoch when its first change is recorded, so that the cursors of
+    /// different feeds, e.g. of a deleted and recreated index, can't be mistaken for each other.
+    pub fn append_document_changes(
+        &self,
+        wtxn: &mut RwTxn,
+        changes: impl IntoIterator<Item = DocumentChange>,
+    ) -> Result<()> {
+        let mut cursor = match self.change_feed.last(wtxn)? {
+            Some((cursor, _)) => cursor + 1,
+            None => 0,
+        };
+        if self.change_feed_epoch(wtxn)?.is_none() {
+            let epoch = uuid::Uuid::new_v4().to_string();
+            self.main.remap_types::<Str, Str>().put(
+                wtxn,
+                main_key::CHANGE_FEED_EPOCH_KEY,
+                &epoch,
+            )?;
+        }
+
+        for change in changes {
+            self.change_feed.put(wtxn, &cursor, &change)?;
+            cursor += 1;
+        }
+
+        let first_retained = cursor.saturating_sub(MAX_RETAINED_DOCUMENT_CHANGES);
+        if first_retained > 0 {
+            self.change_feed.delete_range(wtxn, &(..first_retained))?;
+        }
+        Ok(())
+    }
+
+    /// Returns up to `limit` changes following the `after` cursor, or from the oldest
+    /// retained change.
+    pub fn document_changes(
+        &self,
+        rtxn: &RoTxn,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<ChangeFeedEntry>> {
+        let start = after.map_or(0, |after| after.saturating_add(1));
+        self.change_feed
+            .range(rtxn, &(start..))?
+            .take(limit)
+            .map(|result| -> Result<ChangeFeedEntry> {
+                let (cursor, change) = result?;
+                Ok(ChangeFeedEntry { cursor, change })
+            })
+            .collect()
+    }
+
+    /// Returns the cursor of the oldest change retained in the change feed.
+    pub fn oldest_document_change_cursor(&self, rtxn: &RoTxn) -> heed::Result<Option<u64>> {
+        Ok(self.change_feed.first(rtxn)?.map(|(cursor, _)| cursor))
+    }
+
+    /// Returns the epoch of the change feed, absent until its first change is recorded.
+    pub fn change_feed_epoch<'t>(&self, rtxn: &'t RoTxn) -> heed::Result<Option<&'t str>> {
+        self.main.remap_types::<Str, Str>().get(rtxn, main_key::CHANGE_FEED_EPOCH_KEY)
+    }
+
+    /* facet hierarchy separators */
+
+    /// Writes the separators of the hierarchical facets in the database.
+    pub(crate) fn put_facet_hierarchy_separators(
+        &self,
+        wtxn: &mut RwTxn,
+        separators: &BTreeMap<String, String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::FACET_HIERARCHY_SEPARATORS_KEY,
+            separators,
+        )
+    }
+
+    /// Deletes the separators of the hierarchical facets in the database.
+    pub(crate) fn delete_facet_hierarchy_separators(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::FACET_HIERARCHY_SEPARATORS_KEY)
+    }
+
+    /// Returns the separators splitting the values of the hierarchical facets
+    /// into levels, indexed by field name.
+    ///
+    /// The `n`th level of such a field is indexed as the `{field}.lvl{n}` field.
+    pub fn facet_hierarchy_separators(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<BTreeMap<String, String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::FACET_HIERARCHY_SEPARATORS_KEY)?
+            .unwrap_or_default())
+    }
+
     /* faceted fields */
 
     /// Writes the faceted fields in the database.
@@ -800,16 +1196,15 @@ impl Index {
 
     /// Returns the user defined faceted fields names.
     ///
-    /// The user faceted fields are the union of all the filterable, sortable, distinct, and Asc/Desc fields.
+    /// The user faceted fields are the union of all the filterable, sortable, distinct,
+    /// static boost, and Asc/Desc fields, including the fields of the ranking expressions.
     pub fn user_defined_faceted_fields(&self, rtxn: &RoTxn) -> Result<HashSet<String>> {
         let filterable_fields = self.filterable_fields(rtxn)?;
         let sortable_fields = self.sortable_fields(rtxn)?;
         let distinct_field = self.distinct_field(rtxn)?;
+        let static_boost = self.static_boost(rtxn)?;
         let asc_desc_fields =
-            self.criteria(rtxn)?.into_iter().filter_map(|criterion| match criterion {
-                Criterion::Asc(field) | Criterion::Desc(field) => Some(field),
-                _otherwise => None,
-            });
+            self.criteria(rtxn)?.into_iter().flat_map(|criterion| criterion.sort_fields());
 
         let mut faceted_fields = filterable_fields;
         faceted_fields.extend(sortable_fields);
@@ -817,6 +1212,9 @@ impl Index {
         if let Some(field) = distinct_field {
             faceted_fields.insert(field.to_owned());
         }
+        if let Some(StaticBoost { attribute, .. }) = static_boost {
+            faceted_fields.insert(attribute);
+        }
 
         Ok(faceted_fields)
     }
@@ -892,6 +1290,223 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(wtxn, main_key::DISTINCT_FIELD_KEY)
     }
 
+    /* static boost */
+
+    /// Writes the numeric field blended into the relevancy of the search results.
+    pub(crate) fn put_static_boost(
+        &self,
+        wtxn: &mut RwTxn,
+        static_boost: &StaticBoost,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::STATIC_BOOST_KEY,
+            static_boost,
+        )
+    }
+
+    /// Deletes the static boost of the index.
+    pub(crate) fn delete_static_boost(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::STATIC_BOOST_KEY)
+    }
+
+    /// Returns the numeric field, and the curve applied to its values, that is blended
+    /// into the relevancy of the search results right before the custom sort rules.
+    pub fn static_boost(&self, rtxn: &RoTxn) -> heed::Result<Option<StaticBoost>> {
+        self.main.remap_types::<Str, SerdeJson<_>>().get(rtxn, main_key::STATIC_BOOST_KEY)
+    }
+
+    /* foreign keys */
+
+    /// Writes the attributes referencing the documents of other indexes.
+    pub(crate) fn put_foreign_keys(
+        &self,
+        wtxn: &mut RwTxn,
+        foreign_keys: &[ForeignKey],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::FOREIGN_KEYS_KEY,
+            &foreign_keys,
+        )
+    }
+
+    /// Deletes the foreign keys of the index.
+    pub(crate) fn delete_foreign_keys(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::FOREIGN_KEYS_KEY)
+    }
+
+    /// Returns the attributes referencing, by their primary key, the documents of other indexes.
+    pub fn foreign_keys(&self, rtxn: &RoTxn) -> heed::Result<Vec<ForeignKey>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::FOREIGN_KEYS_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* query rules */
+
+    /// Writes the merchandising rules applied to the searches.
+    pub(crate) fn put_query_rules(
+        &self,
+        wtxn: &mut RwTxn,
+        query_rules: &[QueryRule],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::QUERY_RULES_KEY,
+            &query_rules,
+        )
+    }
+
+    /// Deletes the query rules of the index.
+    pub(crate) fn delete_query_rules(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::QUERY_RULES_KEY)
+    }
+
+    /// Returns the merchandising rules applied to the searches, in the order they were defined.
+    pub fn query_rules(&self, rtxn: &RoTxn) -> heed::Result<Vec<QueryRule>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::QUERY_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* localized attributes rules */
+
+    /// Writes the languages of the attributes used by the tokenizer.
+    pub(crate) fn put_localized_attributes_rules(
+        &self,
+        wtxn: &mut RwTxn,
+        rules: &[LocalizedAttributesRule],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::LOCALIZED_ATTRIBUTES_RULES_KEY,
+            &rules,
+        )
+    }
+
+    /// Deletes the localized attributes rules of the index.
+    pub(crate) fn delete_localized_attributes_rules(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::LOCALIZED_ATTRIBUTES_RULES_KEY)
+    }
+
+    /// Returns the languages of the attributes, the first rule matching an attribute applying to it.
+    pub fn localized_attributes_rules(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<Vec<LocalizedAttributesRule>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::LOCALIZED_ATTRIBUTES_RULES_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* stemming */
+
+    /// Writes the locales of the languages whose words are matched by their stem.
+    pub(crate) fn put_stemming(
+        &self,
+        wtxn: &mut RwTxn,
+        locales: &BTreeSet<String>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(wtxn, main_key::STEMMING_KEY, locales)
+    }
+
+    /// Deletes the stemmed languages of the index.
+    pub(crate) fn delete_stemming(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::STEMMING_KEY)
+    }
+
+    /// Returns the locales of the languages whose words are matched by their stem.
+    pub fn stemming(&self, rtxn: &RoTxn) -> heed::Result<BTreeSet<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<_>>()
+            .get(rtxn, main_key::STEMMING_KEY)?
+            .unwrap_or_default())
+    }
+
+    /* romanization */
+
+    /// Whether the words written in kana or in chinese characters are matched by their latin transcription.
+    pub fn romanization(&self, rtxn: &RoTxn) -> heed::Result<bool> {
+        // The absence of a value is false, because by default, the words are only matched as written.
+        match self.main.remap_types::<Str, U8>().get(rtxn, main_key::ROMANIZATION_KEY)? {
+            Some(0) | None => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_romanization(&self, wtxn: &mut RwTxn, flag: bool) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(wtxn, main_key::ROMANIZATION_KEY, &(flag as u8))
+    }
+
+    /* split identifiers */
+
+    /// Whether the words written in camelCase or in snake_case are split into their sub-words.
+    pub fn split_identifiers(&self, rtxn: &RoTxn) -> heed::Result<bool> {
+        // The absence of a value is false, because by default, the words are kept whole.
+        match self.main.remap_types::<Str, U8>().get(rtxn, main_key::SPLIT_IDENTIFIERS_KEY)? {
+            Some(0) | None => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_split_identifiers(&self, wtxn: &mut RwTxn, flag: bool) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(wtxn, main_key::SPLIT_IDENTIFIERS_KEY, &(flag as u8))
+    }
+
+    /* document schema */
+
+    /// Writes the JSON Schema the documents added to the index must conform to.
+    pub(crate) fn put_document_schema(
+        &self,
+        wtxn: &mut RwTxn,
+        schema: &DocumentSchema,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::DOCUMENT_SCHEMA_KEY,
+            schema,
+        )
+    }
+
+    /// Deletes the document schema of the index.
+    pub(crate) fn delete_document_schema(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::DOCUMENT_SCHEMA_KEY)
+    }
+
+    /// Returns the JSON Schema the documents added to the index must conform to.
+    pub fn document_schema(&self, rtxn: &RoTxn) -> heed::Result<Option<DocumentSchema>> {
+        self.main.remap_types::<Str, SerdeJson<_>>().get(rtxn, main_key::DOCUMENT_SCHEMA_KEY)
+    }
+
+    /* reranker */
+
+    /// Writes the second-stage reranker reordering the best hits of the searches.
+    pub(crate) fn put_reranker(
+        &self,
+        wtxn: &mut RwTxn,
+        reranker: &RerankerOptions,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(wtxn, main_key::RERANKER_KEY, reranker)
+    }
+
+    /// Deletes the reranker of the index.
+    pub(crate) fn delete_reranker(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::RERANKER_KEY)
+    }
+
+    /// Returns the second-stage reranker reordering the best hits of the searches, if any.
+    pub fn reranker(&self, rtxn: &RoTxn) -> heed::Result<Option<RerankerOptions>> {
+        self.main.remap_types::<Str, SerdeJson<_>>().get(rtxn, main_key::RERANKER_KEY)
+    }
+
     /* criteria */
 
     pub(crate) fn put_criteria(
@@ -1062,16 +1677,28 @@ impl Index {
 
     /* synonyms */
 
+    /// Writes the normalized synonyms in the synonyms database, along with the user defined
+    /// synonyms they were normalized from.
+    ///
+    /// The synonyms of the indexes created before the synonyms database, stored in the main
+    /// database, are moved to the synonyms database.
     pub(crate) fn put_synonyms(
         &self,
         wtxn: &mut RwTxn,
         synonyms: &HashMap<Vec<String>, Vec<Vec<String>>>,
         user_defined_synonyms: &BTreeMap<String, Vec<String>>,
     ) -> heed::Result<()> {
-        self.main.remap_types::<Str, SerdeBincode<_>>().put(
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SYNONYMS_KEY)?;
+        self.phrase_synonyms.clear(wtxn)?;
+        for (words, synonyms) in synonyms {
+            self.phrase_synonyms.put(wtxn, &synonym_key(words), synonyms)?;
+        }
+
+        let max_words = synonyms.keys().map(Vec::len).max().unwrap_or_default();
+        self.main.remap_types::<Str, BEU64>().put(
             wtxn,
-            main_key::SYNONYMS_KEY,
-            synonyms,
+            main_key::MAX_SYNONYM_WORDS_KEY,
+            &(max_words as u64),
         )?;
         self.main.remap_types::<Str, SerdeBincode<_>>().put(
             wtxn,
@@ -1081,8 +1708,13 @@ impl Index {
     }
 
     pub(crate) fn delete_synonyms(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
-        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SYNONYMS_KEY)?;
-        self.main.remap_key_type::<Str>().delete(wtxn, main_key::USER_DEFINED_SYNONYMS_KEY)
+        let legacy_deleted =
+            self.main.remap_key_type::<Str>().delete(wtxn, main_key::SYNONYMS_KEY)?;
+        let deleted = !self.phrase_synonyms.is_empty(wtxn)?;
+        self.phrase_synonyms.clear(wtxn)?;
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::MAX_SYNONYM_WORDS_KEY)?;
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::USER_DEFINED_SYNONYMS_KEY)?;
+        Ok(legacy_deleted || deleted)
     }
 
     pub fn user_defined_synonyms(
@@ -1096,21 +1728,55 @@ impl Index {
             .unwrap_or_default())
     }
 
+    /// The synonyms of the indexes created before the synonyms database, until they are updated.
+    fn legacy_synonyms(
+        &self,
+        rtxn: &RoTxn,
+    ) -> heed::Result<Option<HashMap<Vec<String>, Vec<Vec<String>>>>> {
+        self.main.remap_types::<Str, SerdeBincode<_>>().get(rtxn, main_key::SYNONYMS_KEY)
+    }
+
     pub fn synonyms(&self, rtxn: &RoTxn) -> heed::Result<HashMap<Vec<String>, Vec<Vec<String>>>> {
-        Ok(self
-            .main
-            .remap_types::<Str, SerdeBincode<_>>()
-            .get(rtxn, main_key::SYNONYMS_KEY)?
-            .unwrap_or_default())
+        if let Some(synonyms) = self.legacy_synonyms(rtxn)? {
+            return Ok(synonyms);
+        }
+
+        let mut synonyms = HashMap::new();
+        for result in self.phrase_synonyms.iter(rtxn)? {
+            let (key, phrases) = result?;
+            let words = key.split(SYNONYM_WORDS_SEPARATOR).map(String::from).collect();
+            synonyms.insert(words, phrases);
+        }
+        Ok(synonyms)
     }
 
+    /// Returns the synonyms of the phrase made of the normalized words.
+    ///
+    /// The synonyms are directional: the phrases are returned for the words they were defined
+    /// for, not the other way around.
     pub fn words_synonyms<S: AsRef<str>>(
         &self,
         rtxn: &RoTxn,
         words: &[S],
     ) -> heed::Result<Option<Vec<Vec<String>>>> {
         let words: Vec<_> = words.iter().map(|s| s.as_ref().to_owned()).collect();
-        Ok(self.synonyms(rtxn)?.remove(&words))
+        match self.legacy_synonyms(rtxn)? {
+            Some(mut synonyms) => Ok(synonyms.remove(&words)),
+            None => self.phrase_synonyms.get(rtxn, &synonym_key(&words)),
+        }
+    }
+
+    /// Returns the greatest number of words of the phrases having synonyms.
+    pub fn max_synonym_words(&self, rtxn: &RoTxn) -> heed::Result<usize> {
+        if let Some(synonyms) = self.legacy_synonyms(rtxn)? {
+            return Ok(synonyms.keys().map(Vec::len).max().unwrap_or_default());
+        }
+
+        Ok(self
+            .main
+            .remap_types::<Str, BEU64>()
+            .get(rtxn, main_key::MAX_SYNONYM_WORDS_KEY)?
+            .unwrap_or_default() as usize)
     }
 
     /* words prefixes fst */
@@ -1136,6 +1802,56 @@ impl Index {
         }
     }
 
+    /* word stems fst */
+
+    /// Writes the FST associating the stems of the stemmed languages to the words having them.
+    pub(crate) fn put_word_stems_fst<A: AsRef<[u8]>>(
+        &self,
+        wtxn: &mut RwTxn,
+        fst: &fst::Set<A>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, Bytes>().put(
+            wtxn,
+            main_key::WORD_STEMS_FST_KEY,
+            fst.as_fst().as_bytes(),
+        )
+    }
+
+    /// Returns the FST associating the stems of the stemmed languages to the words having them.
+    pub fn word_stems_fst<'t>(&self, rtxn: &'t RoTxn) -> Result<fst::Set<Cow<'t, [u8]>>> {
+        match self.main.remap_types::<Str, Bytes>().get(rtxn, main_key::WORD_STEMS_FST_KEY)? {
+            Some(bytes) => Ok(fst::Set::new(bytes)?.map_data(Cow::Borrowed)?),
+            None => Ok(fst::Set::default().map_data(Cow::Owned)?),
+        }
+    }
+
+    /* word romanizations fst */
+
+    /// Writes the FST associating the latin transcriptions of the words to these words.
+    pub(crate) fn put_word_romanizations_fst<A: AsRef<[u8]>>(
+        &self,
+        wtxn: &mut RwTxn,
+        fst: &fst::Set<A>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, Bytes>().put(
+            wtxn,
+            main_key::WORD_ROMANIZATIONS_FST_KEY,
+            fst.as_fst().as_bytes(),
+        )
+    }
+
+    /// Returns the FST associating the latin transcriptions of the words to these words.
+    pub fn word_romanizations_fst<'t>(&self, rtxn: &'t RoTxn) -> Result<fst::Set<Cow<'t, [u8]>>> {
+        match self
+            .main
+            .remap_types::<Str, Bytes>()
+            .get(rtxn, main_key::WORD_ROMANIZATIONS_FST_KEY)?
+        {
+            Some(bytes) => Ok(fst::Set::new(bytes)?.map_data(Cow::Borrowed)?),
+            None => Ok(fst::Set::default().map_data(Cow::Owned)?),
+        }
+    }
+
     /* word documents count */
 
     /// Returns the number of documents ids associated with the given word,
@@ -1178,6 +1894,69 @@ impl Index {
         self.iter_documents(rtxn, self.documents_ids(rtxn)?)
     }
 
+    /// Returns up to `limit` ids of the candidates, sorted by the values of the given faceted
+    /// fields, in ascending order when their boolean is `true`.
+    ///
+    /// The documents having the same value for a field are sorted by the next fields, the documents
+    /// without a value for a field come after the others and the remaining ties are sorted by id.
+    pub fn sorted_documents_ids(
+        &self,
+        rtxn: &RoTxn,
+        candidates: RoaringBitmap,
+        sort: &[(FieldId, bool)],
+        limit: usize,
+    ) -> Result<Vec<DocumentId>> {
+        let mut documents_ids = Vec::new();
+        self.sort_documents_ids_into(rtxn, candidates, sort, limit, &mut documents_ids)?;
+        Ok(documents_ids)
+    }
+
+    fn sort_documents_ids_into(
+        &self,
+        rtxn: &RoTxn,
+        candidates: RoaringBitmap,
+        sort: &[(FieldId, bool)],
+        limit: usize,
+        documents_ids: &mut Vec<DocumentId>,
+    ) -> Result<()> {
+        let Some((&(field_id, ascending), next_sort)) = sort.split_first() else {
+            let remaining = limit.saturating_sub(documents_ids.len());
+            documents_ids.extend(candidates.into_iter().take(remaining));
+            return Ok(());
+        };
+
+        let number_db =
+            self.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let string_db =
+            self.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let (number_iter, string_iter) = if ascending {
+            let number_iter = ascending_facet_sort(rtxn, number_db, field_id, candidates.clone())?;
+            let string_iter = ascending_facet_sort(rtxn, string_db, field_id, candidates.clone())?;
+            (itertools::Either::Left(number_iter), itertools::Either::Left(string_iter))
+        } else {
+            let number_iter = descending_facet_sort(rtxn, number_db, field_id, candidates.clone())?;
+            let string_iter = descending_facet_sort(rtxn, string_db, field_id, candidates.clone())?;
+            (itertools::Either::Right(number_iter), itertools::Either::Right(string_iter))
+        };
+
+        // a document having several values is sorted by the first one
+        let mut remaining = candidates;
+        for result in number_iter.chain(string_iter) {
+            if documents_ids.len() >= limit || remaining.is_empty() {
+                return Ok(());
+            }
+            let (docids, _) = result?;
+            let bucket = docids & &remaining;
+            if bucket.is_empty() {
+                continue;
+            }
+            remaining -= &bucket;
+            self.sort_documents_ids_into(rtxn, bucket, next_sort, limit, documents_ids)?;
+        }
+
+        self.sort_documents_ids_into(rtxn, remaining, next_sort, limit, documents_ids)
+    }
+
     pub fn external_id_of<'a, 't: 'a>(
         &'a self,
         rtxn: &'t RoTxn,
@@ -1271,6 +2050,57 @@ impl Index {
         Ok(())
     }
 
+    /// Whether the words of the queries must be matched with their first character, the typos
+    /// being only allowed on the following ones.
+    pub fn disable_typo_on_first_character(&self, txn: &RoTxn) -> heed::Result<bool> {
+        // The absence of a value is false, because by default, we authorize the typos
+        // on the first character.
+        match self
+            .main
+            .remap_types::<Str, U8>()
+            .get(txn, main_key::DISABLE_TYPO_ON_FIRST_CHARACTER)?
+        {
+            Some(0) | None => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_disable_typo_on_first_character(
+        &self,
+        txn: &mut RwTxn,
+        flag: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(
+            txn,
+            main_key::DISABLE_TYPO_ON_FIRST_CHARACTER,
+            &(flag as u8),
+        )?;
+        Ok(())
+    }
+
+    /// Whether the words containing digits, like model numbers, can be matched with typos.
+    pub fn authorize_typos_on_numbers(&self, txn: &RoTxn) -> heed::Result<bool> {
+        // The absence of a value is true, because by default, the words containing
+        // digits are typo tolerant like the other words.
+        match self.main.remap_types::<Str, U8>().get(txn, main_key::AUTHORIZE_TYPOS_ON_NUMBERS)? {
+            Some(0) => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_authorize_typos_on_numbers(
+        &self,
+        txn: &mut RwTxn,
+        flag: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(
+            txn,
+            main_key::AUTHORIZE_TYPOS_ON_NUMBERS,
+            &(flag as u8),
+        )?;
+        Ok(())
+    }
+
     pub fn min_word_len_one_typo(&self, txn: &RoTxn) -> heed::Result<u8> {
         // It is not possible to put a bool in heed with OwnedType, so we put a u8 instead. We
         // identify 0 as being false, and anything else as true. The absence of a value is true,
@@ -1507,6 +2337,68 @@ impl Index {
             _ => "default".to_owned(),
         })
     }
+
+    /// Returns the embeddings of the document stored for each embedder.
+    pub fn embeddings(
+        &self,
+        rtxn: &RoTxn<'_>,
+        docid: DocumentId,
+    ) -> Result<BTreeMap<String, Vec<Embedding>>> {
+        let mut embeddings = BTreeMap::new();
+        for (embedder_name, config) in self.embedding_configs(rtxn)? {
+            let embedder_index = self.embedder_category_id.get(rtxn, &embedder_name)?.ok_or(
+                InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
+            )?;
+            let document_embeddings = match config.distance {
+                Distance::Cosine => {
+                    stored_embeddings(rtxn, self.vector_arroy, embedder_index, docid)?
+                }
+                Distance::DotProduct => {
+                    stored_embeddings(rtxn, self.vector_arroy_dot_product, embedder_index, docid)?
+                }
+                Distance::Euclidean => {
+                    stored_embeddings(rtxn, self.vector_arroy_euclidean, embedder_index, docid)?
+                }
+            };
+            embeddings.insert(embedder_name, document_embeddings);
+        }
+        Ok(embeddings)
+    }
+}
+
+fn synonym_key<S: AsRef<str>>(words: &[S]) -> String {
+    let mut key = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i != 0 {
+            key.push(SYNONYM_WORDS_SEPARATOR);
+        }
+        key.push_str(word.as_ref());
+    }
+    key
+}
+
+/// Returns the embeddings of the document stored in the arroy database of an embedder.
+pub(crate) fn stored_embeddings<D: arroy::Distance>(
+    rtxn: &RoTxn<'_>,
+    database: arroy::Database<D>,
+    embedder_index: u8,
+    docid: DocumentId,
+) -> Result<Vec<Embedding>> {
+    let writer_index = (embedder_index as u16) << 8;
+    let mut embeddings = Vec::new();
+    for k in 0..=u8::MAX {
+        let reader = match arroy::Reader::open(rtxn, writer_index | (k as u16), database) {
+            Ok(reader) => reader,
+            Err(arroy::Error::MissingMetadata) => break,
+            Err(e) => return Err(e.into()),
+        };
+        match reader.item_vector(rtxn, docid)? {
+            Some(embedding) => embeddings.push(embedding),
+            // uses invariant: vectors are packed in the first writers.
+            None => break,
+        }
+    }
+    Ok(embeddings)
 }
 
 #[cfg(test)]
@@ -1575,7 +2467,7 @@ pub(crate) mod tests {
                 || false,
             )
             .unwrap();
-            let (builder, user_error) = builder.add_documents(documents).unwrap();
+            let (builder, user_error) = builder.add_documents(documents)?;
             user_error?;
             builder.execute()?;
             Ok(())
@@ -1749,6 +2641,52 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn field_distribution_of_replaced_documents() {
+        let mut index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevin", "age": 20 },
+                { "id": 2, "name": "bob" },
+            ]))
+            .unwrap();
+
+        // the values change but not the fields, then a document gains a field and loses it
+        // again in the same batch.
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevina", "age": 21 },
+                { "id": 2, "name": "bobby" },
+                { "id": 1, "name": "kevina", "age": 21, "has_dog": true },
+                { "id": 1, "name": "kevina" },
+            ]))
+            .unwrap();
+
+        db_snap!(index, field_distribution,
+            @r###"
+        id               2      |
+        name             2      |
+        "###
+        );
+
+        // updating a document with a subset of its fields doesn't change it either.
+        index.index_documents_config.update_method = IndexDocumentsMethod::UpdateDocuments;
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevin" },
+                { "id": 2, "age": 20 },
+            ]))
+            .unwrap();
+
+        db_snap!(index, field_distribution,
+            @r###"
+        age              1      |
+        id               2      |
+        name             2      |
+        "###
+        );
+    }
+
     #[test]
     fn put_and_retrieve_disable_typo() {
         let index = TempIndex::new();
@@ -1807,6 +2745,45 @@ pub(crate) mod tests {
         assert_eq!(user_defined, &["doggo", "name"]);
     }
 
+    #[test]
+    fn sort_documents_ids_by_faceted_fields() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("age"), S("name") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "name": "kevin", "age": 20 },
+                { "id": 1, "name": "bob", "age": [25, 12] },
+                { "id": 2, "name": "alice", "age": 20 },
+                { "id": 3, "name": "jean" },
+                { "id": 4, "name": "bob", "age": "unknown" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let age = fields_ids_map.id("age").unwrap();
+        let name = fields_ids_map.id("name").unwrap();
+        let candidates = index.documents_ids(&rtxn).unwrap();
+
+        // the numbers come before the strings and the documents without a value come last
+        let sorted = index
+            .sorted_documents_ids(&rtxn, candidates.clone(), &[(age, true), (name, true)], 10)
+            .unwrap();
+        assert_eq!(sorted, vec![1, 2, 0, 4, 3]);
+
+        // the ties are sorted by id
+        let sorted =
+            index.sorted_documents_ids(&rtxn, candidates.clone(), &[(age, false)], 3).unwrap();
+        assert_eq!(sorted, vec![1, 0, 2]);
+
+        let sorted = index.sorted_documents_ids(&rtxn, candidates, &[(name, false)], 2).unwrap();
+        assert_eq!(sorted, vec![0, 3]);
+    }
+
     #[test]
     fn set_searchable_fields_and_add_documents() {
         let index = TempIndex::new();
@@ -2423,6 +3400,10 @@ pub(crate) mod tests {
             candidates: _,
             document_scores: _,
             mut documents_ids,
+            degraded: _,
+            group_counts: _,
+            applied_query_rules: _,
+            corrected_query: _,
         } = search.execute().unwrap();
         let primary_key_id = index.fields_ids_map(&rtxn).unwrap().id("primary_key").unwrap();
         documents_ids.sort_unstable();
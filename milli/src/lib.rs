@@ -9,17 +9,29 @@ pub static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 pub mod documents;
 
 mod asc_desc;
+pub mod change_feed;
 mod criterion;
+pub mod document_schema;
 mod error;
 mod external_documents_ids;
 pub mod facet;
 mod fields_ids_map;
+pub mod foreign_key;
+pub mod geojson;
 pub mod heed_codec;
+pub mod identifiers;
 pub mod index;
+pub mod localized_attributes_rules;
 pub mod prompt;
 pub mod proximity;
+pub mod query_rules;
+pub mod ranking_expression;
+pub mod romanization;
 pub mod score_details;
 mod search;
+pub mod static_boost;
+pub mod stemming;
+pub mod suggestions;
 pub mod update;
 pub mod vector;
 
@@ -40,9 +52,12 @@ pub use search::new::{
     SearchLogger, VisualSearchLogger,
 };
 use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
 pub use {charabia as tokenizer, heed};
 
-pub use self::asc_desc::{AscDesc, AscDescError, Member, SortError};
+pub use self::asc_desc::{
+    AscDesc, AscDescError, Member, MultiValue, NullsOrder, SortError, SortModifiers,
+};
 pub use self::criterion::{default_criteria, Criterion, CriterionError};
 pub use self::error::{
     Error, FieldIdMapMissingEntry, InternalError, SerializationError, UserError,
@@ -57,9 +72,11 @@ pub use self::heed_codec::{
 };
 pub use self::index::Index;
 pub use self::search::{
-    FacetDistribution, FacetValueHit, Filter, FormatOptions, MatchBounds, MatcherBuilder,
-    MatchingWords, OrderBy, Search, SearchForFacetValues, SearchResult, TermsMatchingStrategy,
-    DEFAULT_VALUES_PER_FACET,
+    aggregate_numbers, split_negative_query, BucketCoordinate, CandidateSelection,
+    FacetDistribution, FacetValueHit, Filter, FormatOptions, FusionStrategy, MatchBounds,
+    MatcherBuilder, MatchingWords, NumberAggregation, OffsetUnit, OrderBy, QueryClause,
+    QueryVocabulary, Search, SearchCursor, SearchForFacetValues, SearchResult, StructuredQuery,
+    TermsMatchingStrategy, TimeBudget, VectorTarget, DEFAULT_VALUES_PER_FACET,
 };
 
 pub type Result<T> = std::result::Result<T, error::Error>;
@@ -247,6 +264,30 @@ pub fn lat_lng_to_xyz(coord: &[f64; 2]) -> [f64; 3] {
     [x, y, z]
 }
 
+/// Returns `true` if the point is inside the polygon, both expressed in terms of latitude and
+/// longitude. The edges of the polygon are straight lines between the latitudes and longitudes
+/// of its points, which are automatically closed.
+pub fn is_point_in_polygon(point: &[f64; 2], polygon: &[[f64; 2]]) -> bool {
+    let [lat, lng] = *point;
+    let mut inside = false;
+    let mut previous = match polygon.last() {
+        Some(previous) => previous,
+        None => return false,
+    };
+    // we cast a ray toward the east of the point and count the edges it crosses
+    for current in polygon {
+        let ([lat_a, lng_a], [lat_b, lng_b]) = (*previous, *current);
+        if (lat_a > lat) != (lat_b > lat) {
+            let crossing_lng = lng_a + (lat - lat_a) * (lng_b - lng_a) / (lat_b - lat_a);
+            if lng < crossing_lng {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
 /// Returns `true` if the field match one of the faceted fields.
 /// See the function [`is_faceted_by`] below to see what “matching” means.
 pub fn is_faceted(field: &str, faceted_fields: impl IntoIterator<Item = impl AsRef<str>>) -> bool {
@@ -284,10 +325,34 @@ pub fn is_faceted_by(field: &str, facet: &str) -> bool {
         && field[facet.len()..].chars().next().map(|c| c == '.').unwrap_or(true)
 }
 
+/// Returns `true` if the attribute matches the pattern.
+///
+/// A pattern matches the attribute itself and its nested fields, a `*` prefix or suffix
+/// matching any characters before or after the rest of the pattern.
+pub fn match_attribute_pattern(pattern: &str, attribute: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        (Some(_), Some(_)) => attribute.contains(&pattern[1..pattern.len() - 1]),
+        (Some(suffix), None) => attribute.ends_with(suffix),
+        (None, Some(prefix)) => attribute.starts_with(prefix),
+        (None, None) => is_faceted_by(attribute, pattern),
+    }
+}
+
 pub fn normalize_facet(original: &str) -> String {
     CompatibilityDecompositionNormalizer.normalize_str(original.trim()).to_lowercase()
 }
 
+/// Parses an RFC 3339 date into the timestamp, in seconds, under which
+/// it is stored in the facet numbers database when it belongs to a date field.
+pub fn date_to_timestamp(date: &str) -> Option<f64> {
+    let date = time::OffsetDateTime::parse(date.trim(), &Rfc3339).ok()?;
+    Some(date.unix_timestamp_nanos() as f64 / 1_000_000_000.0)
+}
+
 /// Represents either a vector or an array of multiple vectors.
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 #[serde(transparent)]
@@ -394,4 +459,51 @@ mod tests {
 
         assert_eq!(&actual, expected);
     }
+
+    #[test]
+    fn test_date_to_timestamp() {
+        assert_eq!(date_to_timestamp("1970-01-01T00:00:00Z"), Some(0.0));
+        assert_eq!(date_to_timestamp("2024-01-01T00:00:00Z"), Some(1704067200.0));
+        assert_eq!(date_to_timestamp("2024-01-01T01:00:00+01:00"), Some(1704067200.0));
+        assert_eq!(date_to_timestamp("1969-12-31T23:59:59.5Z"), Some(-0.5));
+        assert_eq!(date_to_timestamp("2024-01-01"), None);
+        assert_eq!(date_to_timestamp("12"), None);
+    }
+
+    #[test]
+    fn test_is_point_in_polygon() {
+        // a square with a notch on its east side
+        let polygon = [
+            [0.0, 0.0],
+            [0.0, 10.0],
+            [4.0, 10.0],
+            [5.0, 5.0],
+            [6.0, 10.0],
+            [10.0, 10.0],
+            [10.0, 0.0],
+        ];
+        assert!(is_point_in_polygon(&[1.0, 1.0], &polygon));
+        assert!(is_point_in_polygon(&[5.0, 4.0], &polygon));
+        assert!(!is_point_in_polygon(&[5.0, 8.0], &polygon));
+        assert!(!is_point_in_polygon(&[11.0, 1.0], &polygon));
+        assert!(!is_point_in_polygon(&[-1.0, 5.0], &polygon));
+        assert!(!is_point_in_polygon(&[1.0, 1.0], &[]));
+    }
+
+    #[test]
+    fn test_match_attribute_pattern() {
+        assert!(match_attribute_pattern("*", "title"));
+        assert!(match_attribute_pattern("title", "title"));
+        assert!(match_attribute_pattern("title", "title.ja"));
+        assert!(!match_attribute_pattern("title", "subtitle"));
+
+        assert!(match_attribute_pattern("*_ja", "title_ja"));
+        assert!(!match_attribute_pattern("*_ja", "title_jap"));
+
+        assert!(match_attribute_pattern("title_*", "title_ja"));
+        assert!(!match_attribute_pattern("title_*", "subtitle_ja"));
+
+        assert!(match_attribute_pattern("*_ja_*", "title_ja_long"));
+        assert!(!match_attribute_pattern("*_ja_*", "title_ja"));
+    }
 }
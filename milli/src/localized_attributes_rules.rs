@@ -0,0 +1,106 @@
+use std::collections::{BTreeSet, HashMap};
+
+use charabia::{Language, Script};
+use serde::{Deserialize, Serialize};
+
+use crate::{match_attribute_pattern, FieldId, FieldsIdsMap, Result};
+
+/// The languages of the attributes matching one of the patterns of the rule,
+/// the tokenizer only detecting these languages in the text of the attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedAttributesRule {
+    /// The attribute names, a `*` at the start or the end of a pattern matching any characters.
+    pub attribute_patterns: Vec<String>,
+    /// The ISO 639-3 codes of the languages of the attributes.
+    pub locales: Vec<String>,
+    /// The stop words of these languages, ignored in the attributes in addition to the ones of the index.
+    #[serde(default)]
+    pub stop_words: BTreeSet<String>,
+}
+
+impl LocalizedAttributesRule {
+    /// Returns `true` if the attribute matches one of the patterns of the rule.
+    pub fn matches(&self, attribute: &str) -> bool {
+        self.attribute_patterns.iter().any(|pattern| match_attribute_pattern(pattern, attribute))
+    }
+
+    /// Returns the languages of the locales of the rule.
+    pub fn languages(&self) -> Vec<Language> {
+        self.locales.iter().map(|locale| Language::from_name(locale)).collect()
+    }
+}
+
+/// The scripts shared by several languages, in which the tokenizer detects the language.
+const MULTILINGUAL_SCRIPTS: [Script; 6] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Arabic,
+    Script::Devanagari,
+    Script::Hebrew,
+    Script::Cj,
+];
+
+/// Returns `true` if the locale is the ISO 639-3 code of a language known by the tokenizer.
+pub fn is_known_locale(locale: &str) -> bool {
+    Language::from_name(locale).name() == locale
+}
+
+/// The localized attributes rules of an index resolved to the fields they apply to.
+#[derive(Debug, Default)]
+pub struct LocalizedFieldIds {
+    /// The index of the first rule matching each field.
+    field_rules: HashMap<FieldId, usize>,
+    /// The languages of each rule, allowed in all the scripts the tokenizer detects languages in.
+    script_languages: Vec<HashMap<Script, Vec<Language>>>,
+    /// The stop words of the index merged with the ones of each rule,
+    /// `None` when the rule has no stop words.
+    stop_words: Vec<Option<fst::Set<Vec<u8>>>>,
+}
+
+impl LocalizedFieldIds {
+    pub fn new(
+        rules: &[LocalizedAttributesRule],
+        fields_ids_map: &FieldsIdsMap,
+        index_stop_words: Option<&fst::Set<&[u8]>>,
+    ) -> Result<Self> {
+        let field_rules = fields_ids_map
+            .iter()
+            .filter_map(|(field_id, name)| {
+                rules.iter().position(|rule| rule.matches(name)).map(|rule| (field_id, rule))
+            })
+            .collect();
+
+        let mut script_languages = Vec::with_capacity(rules.len());
+        let mut stop_words = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let languages = rule.languages();
+            script_languages.push(
+                MULTILINGUAL_SCRIPTS.iter().map(|script| (*script, languages.clone())).collect(),
+            );
+            if rule.stop_words.is_empty() {
+                stop_words.push(None);
+            } else {
+                let mut rule_stop_words = rule.stop_words.clone();
+                if let Some(index_stop_words) = index_stop_words {
+                    rule_stop_words.extend(index_stop_words.stream().into_strs()?);
+                }
+                stop_words.push(Some(fst::Set::from_iter(rule_stop_words)?));
+            }
+        }
+
+        Ok(LocalizedFieldIds { field_rules, script_languages, stop_words })
+    }
+
+    /// Returns the index of the rule the field matches, `None` if it doesn't match any rule.
+    pub fn rule(&self, field_id: FieldId) -> Option<usize> {
+        self.field_rules.get(&field_id).copied()
+    }
+
+    /// Returns the languages allowed in each script and the stop words of every rule, in order.
+    pub fn rules(
+        &self,
+    ) -> impl Iterator<Item = (&HashMap<Script, Vec<Language>>, Option<&fst::Set<Vec<u8>>>)> {
+        self.script_languages.iter().zip(self.stop_words.iter().map(Option::as_ref))
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// A merchandising rule of an index: when a search matches its conditions,
+/// its consequences are applied to the search before the documents are ranked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRule {
+    /// The identifier of the rule, reported in the hits it was applied to.
+    pub id: String,
+    #[serde(default)]
+    pub conditions: QueryRuleConditions,
+    #[serde(default)]
+    pub consequences: QueryRuleConsequences,
+}
+
+/// The conditions a search must match to trigger a rule, a rule without
+/// conditions being triggered by every search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRuleConditions {
+    /// The words the query must match, according to the anchoring.
+    pub query: Option<String>,
+    #[serde(default)]
+    pub anchoring: Anchoring,
+    /// A filter condition the filter of the search must contain, either as its whole
+    /// filter or as one of the conditions of its root `AND`.
+    pub filter: Option<String>,
+}
+
+/// How the words of the query are compared to the words of the pattern of a rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Anchoring {
+    /// The query is made of the words of the pattern.
+    #[default]
+    Is,
+    /// The query starts with the words of the pattern.
+    StartsWith,
+    /// The query ends with the words of the pattern.
+    EndsWith,
+    /// The query contains the words of the pattern, next to each other.
+    Contains,
+}
+
+/// What a triggered rule applies to the search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRuleConsequences {
+    /// The primary keys of the documents ranked first, in this order, when they match the search.
+    #[serde(default)]
+    pub pin: Vec<String>,
+    /// A filter restricting the documents of the search.
+    pub filter: Option<String>,
+    /// A filter selecting the documents ranked before the other ones, after the pinned documents.
+    pub boost: Option<String>,
+}
+
+impl Anchoring {
+    /// Returns `true` if the query matches the pattern, their words being
+    /// compared case insensitively.
+    pub fn matches(&self, query: &str, pattern: &str) -> bool {
+        let query = crate::normalize_facet(query);
+        let pattern = crate::normalize_facet(pattern);
+        let query: Vec<_> = query.split_whitespace().collect();
+        let pattern: Vec<_> = pattern.split_whitespace().collect();
+
+        match self {
+            Anchoring::Is => query == pattern,
+            Anchoring::StartsWith => query.starts_with(&pattern),
+            Anchoring::EndsWith => query.ends_with(&pattern),
+            Anchoring::Contains => {
+                pattern.is_empty() || query.windows(pattern.len()).any(|words| words == pattern)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Anchoring;
+
+    #[test]
+    fn anchoring() {
+        assert!(Anchoring::Is.matches("Red  Shoes", "red shoes"));
+        assert!(!Anchoring::Is.matches("red shoes for kids", "red shoes"));
+        assert!(Anchoring::Is.matches("", ""));
+
+        assert!(Anchoring::StartsWith.matches("red shoes for kids", "red shoes"));
+        assert!(!Anchoring::StartsWith.matches("kids red shoes", "red shoes"));
+
+        assert!(Anchoring::EndsWith.matches("kids red shoes", "red shoes"));
+        assert!(!Anchoring::EndsWith.matches("red shoes for kids", "red shoes"));
+
+        assert!(Anchoring::Contains.matches("cheap red shoes for kids", "red shoes"));
+        assert!(!Anchoring::Contains.matches("red cheap shoes", "red shoes"));
+        assert!(!Anchoring::Contains.matches("red", "red shoes"));
+    }
+}
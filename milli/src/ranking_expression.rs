@@ -0,0 +1,486 @@
+//! This module provides the arithmetic expressions of the custom ranking rules,
+//! such as `desc(log(popularity) * freshness_decay(published_at, 30d))`.
+//!
+//! An expression is made of numbers, durations, numeric fields of the documents,
+//! the `+`, `-`, `*` and `/` operators, parentheses and the following functions:
+//! - `log(x)`, `log10(x)`, `sqrt(x)`, `exp(x)` and `abs(x)`,
+//! - `min(x, y)`, `max(x, y)` and `pow(x, y)`,
+//! - `now()`, the current timestamp in seconds,
+//! - `freshness_decay(timestamp, half_life)`, which is `1` for a timestamp equal to now
+//!   and halves every `half_life`.
+//!
+//! The durations are numbers of seconds followed by a unit: `30d`, `12h`, `15m` or `10s`.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::error::is_reserved_keyword;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExpressionError {
+    #[error("unexpected end of the expression")]
+    UnexpectedEnd,
+    #[error("unexpected character `{character}` at position {position}")]
+    UnexpectedCharacter { character: char, position: usize },
+    #[error("`{name}` is not a valid function. Valid functions are {}", Function::NAMES.join(", "))]
+    UnknownFunction { name: String },
+    #[error("the `{name}` function takes {expected} argument(s) but {found} were given")]
+    InvalidArity { name: String, expected: usize, found: usize },
+    #[error("`{name}` is a reserved keyword and thus can't be used in an expression")]
+    ReservedKeyword { name: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    fn apply(&self, left: f64, right: f64) -> f64 {
+        match self {
+            Operator::Add => left + right,
+            Operator::Sub => left - right,
+            Operator::Mul => left * right,
+            Operator::Div => left / right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Log,
+    Log10,
+    Sqrt,
+    Exp,
+    Abs,
+    Min,
+    Max,
+    Pow,
+    Now,
+    FreshnessDecay,
+}
+
+impl Function {
+    const NAMES: [&'static str; 10] =
+        ["log", "log10", "sqrt", "exp", "abs", "min", "max", "pow", "now", "freshness_decay"];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "log" => Some(Function::Log),
+            "log10" => Some(Function::Log10),
+            "sqrt" => Some(Function::Sqrt),
+            "exp" => Some(Function::Exp),
+            "abs" => Some(Function::Abs),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "pow" => Some(Function::Pow),
+            "now" => Some(Function::Now),
+            "freshness_decay" => Some(Function::FreshnessDecay),
+            _ => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Function::Now => 0,
+            Function::Log | Function::Log10 | Function::Sqrt | Function::Exp | Function::Abs => 1,
+            Function::Min | Function::Max | Function::Pow | Function::FreshnessDecay => 2,
+        }
+    }
+
+    fn apply(&self, args: &[f64], now: f64) -> f64 {
+        match (self, args) {
+            (Function::Log, [x]) => x.ln(),
+            (Function::Log10, [x]) => x.log10(),
+            (Function::Sqrt, [x]) => x.sqrt(),
+            (Function::Exp, [x]) => x.exp(),
+            (Function::Abs, [x]) => x.abs(),
+            (Function::Min, [x, y]) => x.min(*y),
+            (Function::Max, [x, y]) => x.max(*y),
+            (Function::Pow, [x, y]) => x.powf(*y),
+            (Function::Now, []) => now,
+            (Function::FreshnessDecay, [timestamp, half_life]) => {
+                let age = (now - timestamp).max(0.0);
+                0.5f64.powf(age / half_life)
+            }
+            _ => f64::NAN,
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Function::Log => "log",
+            Function::Log10 => "log10",
+            Function::Sqrt => "sqrt",
+            Function::Exp => "exp",
+            Function::Abs => "abs",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Pow => "pow",
+            Function::Now => "now",
+            Function::FreshnessDecay => "freshness_decay",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A parsed ranking expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankingExpression {
+    Number(f64),
+    Field(String),
+    Neg(Box<RankingExpression>),
+    Binary(Operator, Box<RankingExpression>, Box<RankingExpression>),
+    Call(Function, Vec<RankingExpression>),
+}
+
+impl RankingExpression {
+    /// Returns the names of the fields used by the expression.
+    pub fn fields(&self) -> BTreeSet<&str> {
+        let mut fields = BTreeSet::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut BTreeSet<&'a str>) {
+        match self {
+            RankingExpression::Number(_) => (),
+            RankingExpression::Field(name) => {
+                fields.insert(name);
+            }
+            RankingExpression::Neg(expr) => expr.collect_fields(fields),
+            RankingExpression::Binary(_, left, right) => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            RankingExpression::Call(_, args) => {
+                args.iter().for_each(|arg| arg.collect_fields(fields))
+            }
+        }
+    }
+
+    /// Evaluates the expression with the numeric values of the fields of a document
+    /// and the current timestamp in seconds.
+    ///
+    /// Returns `None` if a field has no value or if the result is not a finite number.
+    pub fn evaluate(&self, field_value: &impl Fn(&str) -> Option<f64>, now: f64) -> Option<f64> {
+        let value = match self {
+            RankingExpression::Number(number) => *number,
+            RankingExpression::Field(name) => field_value(name)?,
+            RankingExpression::Neg(expr) => -expr.evaluate(field_value, now)?,
+            RankingExpression::Binary(operator, left, right) => {
+                let left = left.evaluate(field_value, now)?;
+                let right = right.evaluate(field_value, now)?;
+                operator.apply(left, right)
+            }
+            RankingExpression::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.evaluate(field_value, now))
+                    .collect::<Option<Vec<_>>>()?;
+                function.apply(&args, now)
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+impl FromStr for RankingExpression {
+    type Err = ExpressionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { text, position: 0 };
+        let expression = parser.expression()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(expression),
+            Some(character) => {
+                Err(ExpressionError::UnexpectedCharacter { character, position: parser.position })
+            }
+        }
+    }
+}
+
+impl fmt::Display for RankingExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RankingExpression::Number(number) => write!(f, "{number}"),
+            RankingExpression::Field(name) => f.write_str(name),
+            RankingExpression::Neg(expr) => write!(f, "-({expr})"),
+            RankingExpression::Binary(operator, left, right) => {
+                let operator = match operator {
+                    Operator::Add => '+',
+                    Operator::Sub => '-',
+                    Operator::Mul => '*',
+                    Operator::Div => '/',
+                };
+                write!(f, "({left} {operator} {right})")
+            }
+            RankingExpression::Call(function, args) => {
+                write!(f, "{function}(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// A recursive descent parser over the expression grammar:
+///
+/// ```text
+/// expression = term (("+" | "-") term)*
+/// term       = unary (("*" | "/") unary)*
+/// unary      = "-" unary | primary
+/// primary    = number | identifier | identifier "(" (expression ("," expression)*)? ")"
+///            | "(" expression ")"
+/// ```
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ExpressionError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(character) if character == expected => Ok(()),
+            Some(character) => Err(ExpressionError::UnexpectedCharacter {
+                character,
+                position: self.position - character.len_utf8(),
+            }),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self) -> Result<RankingExpression, ExpressionError> {
+        let mut left = self.term()?;
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some('+') => Operator::Add,
+                Some('-') => Operator::Sub,
+                _ => return Ok(left),
+            };
+            self.bump();
+            let right = self.term()?;
+            left = RankingExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn term(&mut self) -> Result<RankingExpression, ExpressionError> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            let operator = match self.peek() {
+                Some('*') => Operator::Mul,
+                Some('/') => Operator::Div,
+                _ => return Ok(left),
+            };
+            self.bump();
+            let right = self.unary()?;
+            left = RankingExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<RankingExpression, ExpressionError> {
+        self.skip_whitespace();
+        if self.peek() == Some('-') {
+            self.bump();
+            let expr = self.unary()?;
+            return Ok(RankingExpression::Neg(Box::new(expr)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<RankingExpression, ExpressionError> {
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek() {
+            None => Err(ExpressionError::UnexpectedEnd),
+            Some('(') => {
+                self.bump();
+                let expr = self.expression()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(start),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                while self.peek().map_or(false, |c| c.is_alphanumeric() || c == '_' || c == '.') {
+                    self.bump();
+                }
+                let name = &self.text[start..self.position];
+                self.skip_whitespace();
+                if self.peek() == Some('(') {
+                    self.bump();
+                    self.call(name)
+                } else if is_reserved_keyword(name) {
+                    Err(ExpressionError::ReservedKeyword { name: name.to_string() })
+                } else {
+                    Ok(RankingExpression::Field(name.to_string()))
+                }
+            }
+            Some(character) => {
+                Err(ExpressionError::UnexpectedCharacter { character, position: start })
+            }
+        }
+    }
+
+    fn number(&mut self, start: usize) -> Result<RankingExpression, ExpressionError> {
+        while self.peek().map_or(false, |c| c.is_ascii_digit() || c == '.') {
+            self.bump();
+        }
+        let number: f64 = self.text[start..self.position].parse().map_err(|_| {
+            ExpressionError::UnexpectedCharacter { character: '.', position: start }
+        })?;
+        // a number directly followed by a unit is a duration in seconds
+        let unit = match self.peek() {
+            Some('s') => 1.0,
+            Some('m') => 60.0,
+            Some('h') => 60.0 * 60.0,
+            Some('d') => 24.0 * 60.0 * 60.0,
+            _ => return Ok(RankingExpression::Number(number)),
+        };
+        self.bump();
+        Ok(RankingExpression::Number(number * unit))
+    }
+
+    fn call(&mut self, name: &str) -> Result<RankingExpression, ExpressionError> {
+        let function = Function::from_name(name)
+            .ok_or_else(|| ExpressionError::UnknownFunction { name: name.to_string() })?;
+
+        let mut args = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(')') {
+            self.bump();
+        } else {
+            loop {
+                args.push(self.expression()?);
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(',') => continue,
+                    Some(')') => break,
+                    Some(character) => {
+                        return Err(ExpressionError::UnexpectedCharacter {
+                            character,
+                            position: self.position - character.len_utf8(),
+                        })
+                    }
+                    None => return Err(ExpressionError::UnexpectedEnd),
+                }
+            }
+        }
+
+        if args.len() != function.arity() {
+            return Err(ExpressionError::InvalidArity {
+                name: name.to_string(),
+                expected: function.arity(),
+                found: args.len(),
+            });
+        }
+        Ok(RankingExpression::Call(function, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(expression: &str, fields: &[(&str, f64)], now: f64) -> Option<f64> {
+        let expression: RankingExpression = expression.parse().unwrap();
+        let field_value =
+            |name: &str| fields.iter().find(|(field, _)| *field == name).map(|(_, value)| *value);
+        expression.evaluate(&field_value, now)
+    }
+
+    #[test]
+    fn parse_expressions() {
+        let expression: RankingExpression =
+            "log(popularity) * freshness_decay(published_at, 30d)".parse().unwrap();
+        assert_eq!(
+            expression.to_string(),
+            "(log(popularity) * freshness_decay(published_at, 2592000))"
+        );
+        assert_eq!(
+            expression.fields().into_iter().collect::<Vec<_>>(),
+            ["popularity", "published_at"]
+        );
+
+        let expression: RankingExpression = "1 + 2 * -price / (stock.count - 1)".parse().unwrap();
+        assert_eq!(expression.to_string(), "(1 + ((2 * -(price)) / (stock.count - 1)))");
+
+        let expression: RankingExpression = " now ( ) ".parse().unwrap();
+        assert_eq!(expression.to_string(), "now()");
+    }
+
+    #[test]
+    fn parse_invalid_expressions() {
+        let invalid = [
+            ("", ExpressionError::UnexpectedEnd),
+            ("1 +", ExpressionError::UnexpectedEnd),
+            ("(1 + 2", ExpressionError::UnexpectedEnd),
+            ("1 2", ExpressionError::UnexpectedCharacter { character: '2', position: 2 }),
+            ("price % 2", ExpressionError::UnexpectedCharacter { character: '%', position: 6 }),
+            ("square(price)", ExpressionError::UnknownFunction { name: "square".to_string() }),
+            (
+                "log(price, 2)",
+                ExpressionError::InvalidArity { name: "log".to_string(), expected: 1, found: 2 },
+            ),
+            ("_geo * 2", ExpressionError::ReservedKeyword { name: "_geo".to_string() }),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(input.parse::<RankingExpression>().unwrap_err(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        assert_eq!(evaluate("price * 2 + 1", &[("price", 10.0)], 0.0), Some(21.0));
+        assert_eq!(evaluate("max(price, 15) - min(price, 15)", &[("price", 10.0)], 0.0), Some(5.0));
+        assert_eq!(evaluate("pow(2, 10)", &[], 0.0), Some(1024.0));
+        assert_eq!(evaluate("now() - 1d", &[], 100_000.0), Some(13_600.0));
+
+        // the document doesn't have the field
+        assert_eq!(evaluate("price * 2", &[], 0.0), None);
+        // the result is not a finite number
+        assert_eq!(evaluate("log(price)", &[("price", 0.0)], 0.0), None);
+        assert_eq!(evaluate("1 / price", &[("price", 0.0)], 0.0), None);
+
+        let day = 24.0 * 60.0 * 60.0;
+        let decay = "freshness_decay(published_at, 30d)";
+        assert_eq!(evaluate(decay, &[("published_at", 100.0 * day)], 100.0 * day), Some(1.0));
+        assert_eq!(evaluate(decay, &[("published_at", 70.0 * day)], 100.0 * day), Some(0.5));
+        assert_eq!(evaluate(decay, &[("published_at", 40.0 * day)], 100.0 * day), Some(0.25));
+        // the documents published in the future are as fresh as possible
+        assert_eq!(evaluate(decay, &[("published_at", 200.0 * day)], 100.0 * day), Some(1.0));
+    }
+}
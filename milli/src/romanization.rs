@@ -0,0 +1,93 @@
+use pinyin::ToPinyin;
+use wana_kana::ConvertJapanese;
+
+/// The scripts of the words that have a latin transcription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RomanizableScript {
+    /// Hiragana and katakana, transcribed into romaji.
+    Kana,
+    /// Chinese characters, transcribed into pinyin when the word is chinese.
+    Han,
+}
+
+/// Returns the script a word is entirely written in, if it has a latin transcription.
+///
+/// The words written in chinese characters can be japanese words written in kanji,
+/// whose reading is unknown, only the chinese ones must be transcribed into pinyin.
+pub fn romanizable_script(word: &str) -> Option<RomanizableScript> {
+    if word.is_empty() {
+        None
+    } else if word.chars().all(is_kana) {
+        Some(RomanizableScript::Kana)
+    } else if word.chars().all(is_han) {
+        Some(RomanizableScript::Han)
+    } else {
+        None
+    }
+}
+
+/// Returns the latin transcription of a word written in the given script,
+/// the romaji of the kana and the pinyin, without tones, of the chinese characters.
+pub fn romanize(word: &str, script: RomanizableScript) -> Option<String> {
+    match script {
+        RomanizableScript::Kana => Some(word.to_romaji()),
+        RomanizableScript::Han => {
+            word.to_pinyin().map(|pinyin| pinyin.map(|pinyin| pinyin.plain())).collect()
+        }
+    }
+}
+
+/// Returns `true` if the word could be the transcription of a word of the index.
+pub fn is_romanized(word: &str) -> bool {
+    word.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}')
+}
+
+fn is_han(c: char) -> bool {
+    matches!(
+        c,
+        '\u{4e00}'..='\u{9fff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{20000}'..='\u{2a6df}'
+    )
+}
+
+/// Returns the prefix of the keys of the word romanizations FST of the words
+/// having this latin transcription.
+///
+/// The keys of the FST are made of the transcription and the word, separated by a `\0`.
+pub(crate) fn word_romanization_prefix(romanized: &str) -> String {
+    format!("{romanized}\0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{romanizable_script, romanize, RomanizableScript};
+
+    fn romanize_word(word: &str) -> Option<String> {
+        romanizable_script(word).and_then(|script| romanize(word, script))
+    }
+
+    #[test]
+    fn romanizations() {
+        assert_eq!(romanize_word("中国").as_deref(), Some("zhongguo"));
+        assert_eq!(romanize_word("すし").as_deref(), Some("sushi"));
+        assert_eq!(romanize_word("カメラ").as_deref(), Some("kamera"));
+        // the words mixing scripts are not romanized
+        assert_eq!(romanize_word("東京タワー"), None);
+        assert_eq!(romanize_word("hello"), None);
+        assert_eq!(romanize_word(""), None);
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(romanizable_script("すし"), Some(RomanizableScript::Kana));
+        assert_eq!(romanizable_script("東京"), Some(RomanizableScript::Han));
+        assert_eq!(romanizable_script("東京タワー"), None);
+        assert_eq!(romanizable_script("tokyo"), None);
+    }
+}
@@ -14,6 +14,9 @@ pub enum ScoreDetails {
     Position(Rank),
     ExactAttribute(ExactAttribute),
     ExactWords(ExactWords),
+    StaticBoost(Rank),
+    QueryRules(Rank),
+    ShouldClauses(Rank),
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
@@ -22,12 +25,14 @@ pub enum ScoreDetails {
 #[derive(Clone, Copy)]
 pub enum ScoreValue<'a> {
     Score(f64),
+    QueryRules(Rank),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
 }
 
 enum RankOrValue<'a> {
     Rank(Rank),
+    QueryRules(Rank),
     Sort(&'a Sort),
     GeoSort(&'a GeoSort),
     Score(f64),
@@ -47,6 +52,9 @@ impl ScoreDetails {
             ScoreDetails::Position(details) => Some(*details),
             ScoreDetails::ExactAttribute(details) => Some(details.rank()),
             ScoreDetails::ExactWords(details) => Some(details.rank()),
+            ScoreDetails::StaticBoost(details) => Some(*details),
+            ScoreDetails::QueryRules(details) => Some(*details),
+            ScoreDetails::ShouldClauses(details) => Some(*details),
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
             ScoreDetails::Vector(_) => None,
@@ -77,6 +85,7 @@ impl ScoreDetails {
             })
             .map(|rank_or_value| match rank_or_value {
                 RankOrValue::Rank(r) => ScoreValue::Score(r.local_score()),
+                RankOrValue::QueryRules(r) => ScoreValue::QueryRules(r),
                 RankOrValue::Sort(s) => ScoreValue::Sort(s),
                 RankOrValue::GeoSort(g) => ScoreValue::GeoSort(g),
                 RankOrValue::Score(s) => ScoreValue::Score(s),
@@ -92,6 +101,10 @@ impl ScoreDetails {
             ScoreDetails::Position(p) => RankOrValue::Rank(*p),
             ScoreDetails::ExactAttribute(e) => RankOrValue::Rank(e.rank()),
             ScoreDetails::ExactWords(e) => RankOrValue::Rank(e.rank()),
+            ScoreDetails::StaticBoost(b) => RankOrValue::Rank(*b),
+            // the query rules only reorder the documents, like a sort, they don't lower their score
+            ScoreDetails::QueryRules(r) => RankOrValue::QueryRules(*r),
+            ScoreDetails::ShouldClauses(s) => RankOrValue::Rank(*s),
             ScoreDetails::Sort(sort) => RankOrValue::Sort(sort),
             ScoreDetails::GeoSort(geosort) => RankOrValue::GeoSort(geosort),
             ScoreDetails::Vector(vector) => RankOrValue::Score(
@@ -204,6 +217,31 @@ impl ScoreDetails {
                     }
                     // do not update the order since this was already done by exactAttribute
                 }
+                ScoreDetails::StaticBoost(static_boost) => {
+                    let static_boost_details = serde_json::json!({
+                        "order": order,
+                        "score": static_boost.local_score(),
+                    });
+                    details_map.insert("staticBoost".into(), static_boost_details);
+                    order += 1;
+                }
+                ScoreDetails::QueryRules(query_rules) => {
+                    let query_rules_details = serde_json::json!({
+                        "order": order,
+                        "pinned": query_rules.rank > 2,
+                        "boosted": query_rules.rank == 2,
+                    });
+                    details_map.insert("queryRules".into(), query_rules_details);
+                    order += 1;
+                }
+                ScoreDetails::ShouldClauses(should_clauses) => {
+                    let should_clauses_details = serde_json::json!({
+                        "order": order,
+                        "score": should_clauses.local_score(),
+                    });
+                    details_map.insert("shouldClauses".into(), should_clauses_details);
+                    order += 1;
+                }
                 ScoreDetails::Sort(details) => {
                     let sort = if details.redacted {
                         format!("<hidden-rule-{order}>")
@@ -0,0 +1,177 @@
+use std::ops::ControlFlow;
+
+use heed::BytesDecode;
+use roaring::RoaringBitmap;
+
+use crate::error::UserError;
+use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::BytesRefCodec;
+use crate::search::facet::facet_distribution_iter::lexicographically_iterate_over_facet_distribution;
+use crate::{FieldId, Index, Result};
+
+/// The aggregations of the numeric values of a faceted field over a set of candidates.
+///
+/// Every numeric value of a candidate is taken into account, a document containing
+/// an array of numbers contributes once per distinct number it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberAggregation {
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    /// The requested percentiles along with their value, by increasing percentile.
+    pub percentiles: Vec<(f64, f64)>,
+}
+
+impl NumberAggregation {
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// Aggregates the numeric values of the field over the candidates.
+///
+/// The percentiles, between 0 and 100, are computed with the nearest-rank method.
+/// Returns `None` if none of the candidates have a numeric value for the field.
+pub fn aggregate_numbers(
+    index: &Index,
+    rtxn: &heed::RoTxn,
+    field: &str,
+    candidates: &RoaringBitmap,
+    percentiles: &[f64],
+) -> Result<Option<NumberAggregation>> {
+    let filterable_fields = index.filterable_fields(rtxn)?;
+    if !crate::is_faceted(field, &filterable_fields) {
+        let (valid_fields, hidden_fields) = index.remove_hidden_fields(rtxn, filterable_fields)?;
+        return Err(UserError::InvalidAggregationAttribute {
+            field: field.to_string(),
+            valid_fields,
+            hidden_fields,
+        }
+        .into());
+    }
+
+    let Some(field_id) = index.fields_ids_map(rtxn)?.id(field) else { return Ok(None) };
+
+    let mut aggregation =
+        NumberAggregation { count: 0, min: f64::MAX, max: f64::MIN, sum: 0.0, percentiles: vec![] };
+    iterate_over_numbers(index, rtxn, field_id, candidates, |value, count| {
+        aggregation.count += count;
+        aggregation.min = aggregation.min.min(value);
+        aggregation.max = aggregation.max.max(value);
+        aggregation.sum += value * count as f64;
+        ControlFlow::Continue(())
+    })?;
+
+    if aggregation.count == 0 {
+        return Ok(None);
+    } else if percentiles.is_empty() {
+        return Ok(Some(aggregation));
+    }
+
+    let mut percentiles = percentiles.to_vec();
+    percentiles.sort_unstable_by(f64::total_cmp);
+    let mut ranks = percentiles
+        .into_iter()
+        .map(|percentile| {
+            let rank = (percentile / 100.0 * aggregation.count as f64).ceil() as u64;
+            (percentile, rank.clamp(1, aggregation.count))
+        })
+        .peekable();
+
+    // the values are iterated in ascending order, we assign to each percentile
+    // the first value whose cumulated count reaches its rank
+    let mut cumulated = 0;
+    iterate_over_numbers(index, rtxn, field_id, candidates, |value, count| {
+        cumulated += count;
+        while let Some((percentile, _)) = ranks.next_if(|&(_, rank)| rank <= cumulated) {
+            aggregation.percentiles.push((percentile, value));
+        }
+        match ranks.peek() {
+            Some(_) => ControlFlow::Continue(()),
+            None => ControlFlow::Break(()),
+        }
+    })?;
+
+    Ok(Some(aggregation))
+}
+
+/// Calls the callback with the numeric values of the field in ascending order,
+/// along with the number of candidates having each value.
+fn iterate_over_numbers(
+    index: &Index,
+    rtxn: &heed::RoTxn,
+    field_id: FieldId,
+    candidates: &RoaringBitmap,
+    mut callback: impl FnMut(f64, u64) -> ControlFlow<()>,
+) -> Result<()> {
+    lexicographically_iterate_over_facet_distribution(
+        rtxn,
+        index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+        field_id,
+        candidates,
+        |facet_key, nbr_docids, _| {
+            let value = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+            Ok(callback(value, nbr_docids))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+
+    #[test]
+    fn aggregate() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("price"), S("title") })
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10 },
+                { "id": 1, "price": [20, 40] },
+                { "id": 2, "price": 30 },
+                { "id": 3, "price": 30 },
+                { "id": 4, "title": "socks" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let aggregation =
+            aggregate_numbers(&index, &txn, "price", &(0..5).collect(), &[99.0, 0.0, 50.0])
+                .unwrap()
+                .unwrap();
+        assert_eq!(aggregation.count, 5);
+        assert_eq!(aggregation.min, 10.0);
+        assert_eq!(aggregation.max, 40.0);
+        assert_eq!(aggregation.sum, 130.0);
+        assert_eq!(aggregation.avg(), 26.0);
+        assert_eq!(aggregation.percentiles, vec![(0.0, 10.0), (50.0, 30.0), (99.0, 40.0)]);
+
+        let aggregation =
+            aggregate_numbers(&index, &txn, "price", &(2..4).collect(), &[]).unwrap().unwrap();
+        assert_eq!(aggregation.count, 2);
+        assert_eq!(aggregation.sum, 60.0);
+        assert_eq!(aggregation.percentiles, vec![]);
+
+        let aggregation =
+            aggregate_numbers(&index, &txn, "price", &(4..5).collect(), &[50.0]).unwrap();
+        assert_eq!(aggregation, None);
+
+        let aggregation = aggregate_numbers(&index, &txn, "id", &(0..5).collect(), &[]);
+        assert!(matches!(
+            aggregation,
+            Err(crate::Error::UserError(UserError::InvalidAggregationAttribute { .. }))
+        ));
+    }
+}
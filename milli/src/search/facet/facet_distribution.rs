@@ -3,7 +3,7 @@ use std::ops::ControlFlow;
 use std::{fmt, mem};
 
 use heed::types::Bytes;
-use heed::BytesDecode;
+use heed::{BytesDecode, BytesEncode};
 use indexmap::IndexMap;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
@@ -11,7 +11,8 @@ use serde::{Deserialize, Serialize};
 use crate::error::UserError;
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{
-    FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec, FieldDocIdFacetCodec,
+    FieldDocIdFacetStringCodec, OrderedF64Codec, OrderedI128Codec,
 };
 use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
@@ -27,6 +28,10 @@ pub const DEFAULT_VALUES_PER_FACET: usize = 100;
 /// the system to choose between one algorithm or another.
 const CANDIDATES_THRESHOLD: u64 = 3000;
 
+/// The maximum number of values of a given type a faceted field can have
+/// for its distribution over all the documents to be cached.
+pub const CACHED_FACET_VALUES_LIMIT: usize = 1000;
+
 /// How should we fetch the facets?
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderBy {
@@ -37,6 +42,132 @@ pub enum OrderBy {
     Count,
 }
 
+/// The distribution of a faceted field over all the documents, maintained by the indexing
+/// to answer the searches without query nor filter without going through the facet levels.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CachedFacetDistribution {
+    /// The number values, `None` if they are not computed yet or too many to be cached.
+    pub numbers: Option<CachedFacetValues>,
+    /// The string values, `None` if they are not computed yet or too many to be cached.
+    pub strings: Option<CachedFacetValues>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CachedFacetValues {
+    /// The values and their number of documents, by their level 0 key in the facet database.
+    values: BTreeMap<Vec<u8>, (String, u64)>,
+}
+
+impl CachedFacetValues {
+    /// Computes the values of the given type of a faceted field over all the documents,
+    /// returns `None` if there are more than [`CACHED_FACET_VALUES_LIMIT`] of them.
+    pub(crate) fn compute(
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        field_id: FieldId,
+        facet_type: FacetType,
+    ) -> heed::Result<Option<Self>> {
+        let db = facet_values_database(index, facet_type);
+        let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+        let prefix = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&prefix)
+            .map_err(heed::Error::Encoding)?;
+
+        let mut values = CachedFacetValues::default();
+        for result in db.remap_key_type::<Bytes>().prefix_iter(rtxn, &prefix)? {
+            if values.values.len() == CACHED_FACET_VALUES_LIMIT {
+                return Ok(None);
+            }
+            let (key, group) = result?;
+            let left_bound = &key[prefix.len()..];
+            let value =
+                facet_value_to_string(rtxn, index, field_id, facet_type, left_bound, &group)?;
+            values.values.insert(left_bound.to_vec(), (value, group.bitmap.len()));
+        }
+
+        Ok(Some(values))
+    }
+
+    /// Updates the number of documents of the values whose documents changed,
+    /// returns `false` if there are now more than [`CACHED_FACET_VALUES_LIMIT`] values.
+    pub(crate) fn update<'a>(
+        &mut self,
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        field_id: FieldId,
+        facet_type: FacetType,
+        touched_values: impl IntoIterator<Item = &'a [u8]>,
+    ) -> heed::Result<bool> {
+        let db = facet_values_database(index, facet_type);
+        for left_bound in touched_values {
+            let key = FacetGroupKey { field_id, level: 0, left_bound };
+            match db.get(rtxn, &key)? {
+                Some(group) => {
+                    let value = facet_value_to_string(
+                        rtxn, index, field_id, facet_type, left_bound, &group,
+                    )?;
+                    self.values.insert(left_bound.to_vec(), (value, group.bitmap.len()));
+                }
+                None => {
+                    self.values.remove(left_bound);
+                }
+            }
+        }
+
+        Ok(self.values.len() <= CACHED_FACET_VALUES_LIMIT)
+    }
+
+    /// Returns the values and their number of documents in the given order.
+    fn ordered_by(&self, order_by: OrderBy) -> Vec<(&str, u64)> {
+        let mut values: Vec<_> =
+            self.values.values().map(|(value, count)| (value.as_str(), *count)).collect();
+        if order_by == OrderBy::Count {
+            // the sort is stable, the values of the same count stay in lexicographic order
+            values.sort_by(|(_, left), (_, right)| right.cmp(left));
+        }
+        values
+    }
+}
+
+fn facet_values_database(
+    index: &Index,
+    facet_type: FacetType,
+) -> heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec> {
+    match facet_type {
+        FacetType::Number => {
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        }
+        FacetType::String => {
+            index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+        }
+    }
+}
+
+/// Returns the value displayed for a level 0 key of the facet database, the original string
+/// of any of its documents for the strings, like the facet levels do.
+fn facet_value_to_string(
+    rtxn: &heed::RoTxn,
+    index: &Index,
+    field_id: FieldId,
+    facet_type: FacetType,
+    left_bound: &[u8],
+    group: &FacetGroupValue,
+) -> heed::Result<String> {
+    match facet_type {
+        FacetType::Number => Ok(facet_number_to_string(left_bound)),
+        FacetType::String => {
+            let normalized =
+                StrRefCodec::bytes_decode(left_bound).map_err(heed::Error::Decoding)?;
+            let original = match group.bitmap.min() {
+                Some(docid) => {
+                    index.field_id_docid_facet_strings.get(rtxn, &(field_id, docid, normalized))?
+                }
+                None => None,
+            };
+            Ok(original.unwrap_or(normalized).to_owned())
+        }
+    }
+}
+
 pub struct FacetDistribution<'a> {
     facets: Option<HashMap<String, OrderBy>>,
     candidates: Option<RoaringBitmap>,
@@ -107,11 +238,11 @@ impl<'a> FacetDistribution<'a> {
                     let iter = db
                         .remap_key_type::<Bytes>()
                         .prefix_iter(self.rtxn, &key_buffer)?
-                        .remap_key_type::<FieldDocIdFacetF64Codec>();
+                        .remap_key_type::<FieldDocIdFacetCodec<BytesRefCodec>>();
 
                     for result in iter {
                         let ((_, _, value), ()) = result?;
-                        *distribution.entry(value.to_string()).or_insert(0) += 1;
+                        *distribution.entry(facet_number_to_string(value)).or_insert(0) += 1;
 
                         if distribution.len() - distribution_prelength == self.max_values_per_facet
                         {
@@ -176,8 +307,7 @@ impl<'a> FacetDistribution<'a> {
             field_id,
             candidates,
             |facet_key, nbr_docids, _| {
-                let facet_key = OrderedF64Codec::bytes_decode(facet_key).unwrap();
-                distribution.insert(facet_key.to_string(), nbr_docids);
+                distribution.insert(facet_number_to_string(facet_key), nbr_docids);
                 if distribution.len() == self.max_values_per_facet {
                     Ok(ControlFlow::Break(()))
                 } else {
@@ -225,11 +355,20 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
-    fn facet_values(
-        &self,
-        field_id: FieldId,
-        order_by: OrderBy,
-    ) -> heed::Result<IndexMap<String, u64>> {
+    /// Returns the distribution of the field maintained by the indexing,
+    /// if the candidates are all the documents and it is cached.
+    fn cached_distribution(&self, field_id: FieldId) -> Result<Option<CachedFacetDistribution>> {
+        if let Some(candidates) = &self.candidates {
+            // the candidates are a subset of the documents
+            if candidates.len() != self.index.number_of_documents(self.rtxn)? {
+                return Ok(None);
+            }
+        }
+
+        Ok(self.index.facet_id_distribution.get(self.rtxn, &field_id)?)
+    }
+
+    fn facet_values(&self, field_id: FieldId, order_by: OrderBy) -> Result<IndexMap<String, u64>> {
         use FacetType::{Number, String};
 
         let mut distribution = IndexMap::new();
@@ -241,6 +380,25 @@ impl<'a> FacetDistribution<'a> {
                 self.facet_distribution_from_documents(field_id, String, cnd, &mut distribution)?;
             }
             _ => {
+                if let Some(CachedFacetDistribution {
+                    numbers: Some(numbers),
+                    strings: Some(strings),
+                }) = self.cached_distribution(field_id)?
+                {
+                    // the values are inserted like the facet levels would have
+                    'values: for values in
+                        [numbers.ordered_by(order_by), strings.ordered_by(order_by)]
+                    {
+                        for (value, count) in values {
+                            distribution.insert(value.to_owned(), count);
+                            if distribution.len() == self.max_values_per_facet {
+                                break 'values;
+                            }
+                        }
+                    }
+                    return Ok(distribution);
+                }
+
                 let universe;
                 let candidates = match &self.candidates {
                     Some(cnd) => cnd,
@@ -268,16 +426,10 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
-    pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
-        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+    /// Returns the faceted fields on which the stats must be computed.
+    fn stats_fields(&self) -> Result<HashSet<String>> {
         let filterable_fields = self.index.filterable_fields(self.rtxn)?;
-        let candidates = if let Some(candidates) = self.candidates.clone() {
-            candidates
-        } else {
-            return Ok(Default::default());
-        };
-
-        let fields = match &self.facets {
+        match &self.facets {
             Some(facets) => {
                 let invalid_fields: HashSet<_> = facets
                     .iter()
@@ -285,18 +437,29 @@ impl<'a> FacetDistribution<'a> {
                     .filter(|facet| !crate::is_faceted(facet, &filterable_fields))
                     .collect();
                 if !invalid_fields.is_empty() {
-                    return Err(UserError::InvalidFacetsDistribution {
+                    Err(UserError::InvalidFacetsDistribution {
                         invalid_facets_name: invalid_fields.into_iter().cloned().collect(),
                         valid_facets_name: filterable_fields.into_iter().collect(),
                     }
-                    .into());
+                    .into())
                 } else {
-                    facets.iter().map(|(name, _)| name).cloned().collect()
+                    Ok(facets.iter().map(|(name, _)| name).cloned().collect())
                 }
             }
-            None => filterable_fields,
+            None => Ok(filterable_fields),
+        }
+    }
+
+    pub fn compute_stats(&self) -> Result<BTreeMap<String, (f64, f64)>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = if let Some(candidates) = self.candidates.clone() {
+            candidates
+        } else {
+            return Ok(Default::default());
         };
 
+        let fields = self.stats_fields()?;
+
         let mut distribution = BTreeMap::new();
         for (fid, name) in fields_ids_map.iter() {
             if crate::is_faceted(name, &fields) {
@@ -328,6 +491,49 @@ impl<'a> FacetDistribution<'a> {
         Ok(distribution)
     }
 
+    /// Computes the sum and the average of the numeric values of the faceted fields.
+    ///
+    /// Every numeric value of a candidate is taken into account, a document containing
+    /// an array of numbers contributes once per distinct number it contains.
+    pub fn compute_sum_and_average(&self) -> Result<BTreeMap<String, (f64, f64)>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let candidates = if let Some(candidates) = self.candidates.clone() {
+            candidates
+        } else {
+            return Ok(Default::default());
+        };
+
+        let fields = self.stats_fields()?;
+
+        let mut distribution = BTreeMap::new();
+        for (fid, name) in fields_ids_map.iter() {
+            if crate::is_faceted(name, &fields) {
+                let mut sum = 0.0;
+                let mut count = 0;
+                lexicographically_iterate_over_facet_distribution(
+                    self.rtxn,
+                    self.index
+                        .facet_id_f64_docids
+                        .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+                    fid,
+                    &candidates,
+                    |facet_key, nbr_docids, _| {
+                        let value = OrderedF64Codec::bytes_decode(facet_key).unwrap();
+                        sum += value * nbr_docids as f64;
+                        count += nbr_docids;
+                        Ok(ControlFlow::Continue(()))
+                    },
+                )?;
+
+                if count != 0 {
+                    distribution.insert(name.to_string(), (sum, sum / count as f64));
+                }
+            }
+        }
+
+        Ok(distribution)
+    }
+
     pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
         let filterable_fields = self.index.filterable_fields(self.rtxn)?;
@@ -389,6 +595,15 @@ impl fmt::Debug for FacetDistribution<'_> {
     }
 }
 
+/// Formats an encoded facet number, the big integers are formatted with their exact value.
+fn facet_number_to_string(bytes: &[u8]) -> String {
+    if bytes.len() > 16 {
+        OrderedI128Codec::bytes_decode(bytes).unwrap().to_string()
+    } else {
+        OrderedF64Codec::bytes_decode(bytes).unwrap().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
@@ -673,6 +888,55 @@ mod tests {
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (217.0, 776.0)}"###);
     }
 
+    #[test]
+    fn facet_sum_and_average() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        let mut documents = vec![];
+        for i in 0..1000 {
+            let document = if i % 2 == 0 {
+                serde_json::json!({ "colour": [i, i + 1000] })
+            } else {
+                serde_json::json!({ "colour": format!("{}", i) })
+            };
+            documents.push(document.as_object().unwrap().clone());
+        }
+
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .compute_sum_and_average()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "no_candidates", @"{}");
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((0..1000).collect())
+            .compute_sum_and_average()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "candidates_0_1000", @r###"{"colour": (999000.0, 999.0)}"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((1..1000).step_by(2).collect())
+            .compute_sum_and_average()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), "odd_candidates", @"{}");
+    }
+
     #[test]
     fn facet_stats_array() {
         let mut index = TempIndex::new_with_map_size(4096 * 10_000);
@@ -832,4 +1096,68 @@ mod tests {
 
         milli_snap!(format!("{map:?}"), "candidates_217_777", @r###"{"colour": (218.0, 1776.0)}"###);
     }
+
+    #[test]
+    fn cached_distribution_is_maintained_by_the_indexing() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "colour": "Red" },
+                { "id": 1, "colour": "red" },
+                { "id": 2, "colour": "Blue" },
+                { "id": 3, "colour": 3 },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let fid = index.fields_ids_map(&txn).unwrap().id("colour").unwrap();
+        let cached = index.facet_id_distribution.get(&txn, &fid).unwrap().unwrap();
+        let (numbers, strings) = (cached.numbers.clone().unwrap(), cached.strings.clone().unwrap());
+        let (numbers, strings) =
+            (numbers.ordered_by(OrderBy::Count), strings.ordered_by(OrderBy::Count));
+        milli_snap!(format!("{numbers:?} {strings:?}"), @r###"[("3", 1)] [("Red", 2), ("Blue", 1)]"###);
+        let strings = cached.strings.unwrap();
+        milli_snap!(format!("{:?}", strings.ordered_by(OrderBy::Lexicographic)), @r###"[("Blue", 1), ("Red", 2)]"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::Count)))
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"3": 1, "Red": 2, "Blue": 1}}"###);
+        drop(txn);
+
+        index.delete_documents(vec![S("0"), S("1")]);
+
+        let txn = index.read_txn().unwrap();
+        let cached = index.facet_id_distribution.get(&txn, &fid).unwrap().unwrap();
+        let strings = cached.strings.unwrap();
+        milli_snap!(format!("{:?}", strings.ordered_by(OrderBy::Count)), @r###"[("Blue", 1)]"###);
+
+        let map = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::Count)))
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{map:?}"), @r###"{"colour": {"3": 1, "Blue": 1}}"###);
+        drop(txn);
+
+        // only the values of the new documents are updated
+        index
+            .add_documents(documents!([
+                { "id": 4, "colour": "Green" },
+                { "id": 5, "colour": "blue" },
+            ]))
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        let cached = index.facet_id_distribution.get(&txn, &fid).unwrap().unwrap();
+        let strings = cached.strings.unwrap();
+        milli_snap!(format!("{:?}", strings.ordered_by(OrderBy::Count)), @r###"[("Blue", 2), ("Green", 1)]"###);
+    }
 }
@@ -1,18 +1,25 @@
 use std::collections::HashSet;
 use std::fmt::{Debug, Display};
-use std::ops::Bound::{self, Excluded, Included};
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
 
 use either::Either;
 pub use filter_parser::{Condition, Error as FPError, FilterCondition, Token};
+use heed::BytesEncode;
 use roaring::RoaringBitmap;
-use serde_json::Value;
+use rstar::AABB;
+use serde_json::{Map, Value};
 
 use super::facet_range_search;
-use crate::error::{Error, UserError};
+use crate::error::{Error, InternalError, UserError};
+use crate::geojson::{GeoShape, GEOJSON_BOUNDING_BOX_FIELDS};
 use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec,
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec, OrderedF64Codec,
+    OrderedI128Codec,
+};
+use crate::heed_codec::BytesRefCodec;
+use crate::{
+    distance_between_two_points, is_point_in_polygon, lat_lng_to_xyz, FieldId, Index, Result,
 };
-use crate::{distance_between_two_points, lat_lng_to_xyz, FieldId, Index, Result};
 
 /// The maximum number of filters the filter AST can process.
 const MAX_FILTER_DEPTH: usize = 2000;
@@ -56,6 +63,7 @@ enum FilterError<'a> {
     AttributeNotFilterable { attribute: &'a str, filterable_fields: HashSet<String> },
     ParseGeoError(BadGeoError),
     TooDeep,
+    NestedGeo { attribute: String },
 }
 impl<'a> std::error::Error for FilterError<'a> {}
 
@@ -96,6 +104,11 @@ impl<'a> Display for FilterError<'a> {
                 MAX_FILTER_DEPTH
             ),
             Self::ParseGeoError(error) => write!(f, "{}", error),
+            Self::NestedGeo { attribute } => write!(
+                f,
+                "The geo filters cannot be used inside the nested filter on `{}`.",
+                attribute
+            ),
         }
     }
 }
@@ -221,6 +234,60 @@ impl<'a> Filter<'a> {
 }
 
 impl<'a> Filter<'a> {
+    /// Returns the filter without the conditions on the `field` facet, `None` if nothing remains.
+    ///
+    /// Only the conditions that constrain the whole filter are removed, i.e. the root condition
+    /// or the conditions of the root `AND`, when all of their sub-conditions are on the facet.
+    pub fn without_facet(&self, field: &str) -> Option<Filter<'a>> {
+        fn only_on_facet(condition: &FilterCondition, field: &str) -> bool {
+            match condition {
+                FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+                    crate::is_faceted_by(fid.value(), field)
+                }
+                FilterCondition::Not(condition) => only_on_facet(condition, field),
+                FilterCondition::Nested { fid, .. } => crate::is_faceted_by(fid.value(), field),
+                FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+                    !conditions.is_empty()
+                        && conditions.iter().all(|condition| only_on_facet(condition, field))
+                }
+                FilterCondition::GeoLowerThan { .. }
+                | FilterCondition::GeoBoundingBox { .. }
+                | FilterCondition::GeoPolygon { .. }
+                | FilterCondition::GeoWithin { .. }
+                | FilterCondition::GeoIntersects { .. } => false,
+            }
+        }
+
+        match &self.condition {
+            condition if only_on_facet(condition, field) => None,
+            FilterCondition::And(conditions) => {
+                let mut conditions: Vec<_> = conditions
+                    .iter()
+                    .filter(|condition| !only_on_facet(condition, field))
+                    .cloned()
+                    .collect();
+                let condition = match conditions.len() {
+                    0 => return None,
+                    1 => conditions.pop().unwrap(),
+                    _ => FilterCondition::And(conditions),
+                };
+                Some(Filter { condition })
+            }
+            _ => Some(self.clone()),
+        }
+    }
+
+    /// Returns `true` if the `other` filter is the whole filter or one of the conditions of its root `AND`.
+    pub fn contains_condition(&self, other: &Filter) -> bool {
+        match &self.condition {
+            condition if *condition == other.condition => true,
+            FilterCondition::And(conditions) => {
+                conditions.iter().any(|condition| *condition == other.condition)
+            }
+            _ => false,
+        }
+    }
+
     pub fn evaluate(&self, rtxn: &heed::RoTxn, index: &Index) -> Result<RoaringBitmap> {
         // to avoid doing this for each recursive call we're going to do it ONCE ahead of time
         let filterable_fields = index.filterable_fields(rtxn)?;
@@ -242,18 +309,14 @@ impl<'a> Filter<'a> {
         // field id and the level.
 
         let (left, right) = match operator {
-            Condition::GreaterThan(val) => {
-                (Excluded(val.parse_finite_float()?), Included(f64::MAX))
-            }
+            Condition::GreaterThan(val) => (Excluded(encode_number_or_date(val)?), Unbounded),
             Condition::GreaterThanOrEqual(val) => {
-                (Included(val.parse_finite_float()?), Included(f64::MAX))
-            }
-            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(val.parse_finite_float()?)),
-            Condition::LowerThanOrEqual(val) => {
-                (Included(f64::MIN), Included(val.parse_finite_float()?))
+                (Included(encode_number_or_date(val)?), Unbounded)
             }
+            Condition::LowerThan(val) => (Unbounded, Excluded(encode_number_or_date(val)?)),
+            Condition::LowerThanOrEqual(val) => (Unbounded, Included(encode_number_or_date(val)?)),
             Condition::Between { from, to } => {
-                (Included(from.parse_finite_float()?), Included(to.parse_finite_float()?))
+                (Included(encode_number_or_date(from)?), Included(encode_number_or_date(to)?))
             }
             Condition::Null => {
                 let is_null = index.null_faceted_documents_ids(rtxn, field_id)?;
@@ -279,10 +342,11 @@ impl<'a> Filter<'a> {
                     )?
                     .map(|v| v.bitmap)
                     .unwrap_or_default();
-                let number = val.parse_finite_float().ok();
+                let number = encode_number_or_date(val).ok();
                 let number_docids = match number {
                     Some(n) => numbers_db
-                        .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n })?
+                        .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>()
+                        .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n.as_slice() })?
                         .map(|v| v.bitmap)
                         .unwrap_or_default(),
                     None => RoaringBitmap::new(),
@@ -295,6 +359,31 @@ impl<'a> Filter<'a> {
                 let all_ids = index.documents_ids(rtxn)?;
                 return Ok(all_ids - docids);
             }
+            Condition::StartsWith(val) => {
+                // the normalized strings sharing a prefix are contiguous in the database
+                let prefix = crate::normalize_facet(val.value());
+                let key = FacetGroupKey { field_id, level: 0, left_bound: prefix.as_str() };
+                let mut docids = RoaringBitmap::new();
+                for result in strings_db.prefix_iter(rtxn, &key)? {
+                    let (_, FacetGroupValue { bitmap, .. }) = result?;
+                    docids |= bitmap;
+                }
+                return Ok(docids);
+            }
+            Condition::Contains(val) => {
+                // there is no index of the substrings, we scan all the strings of the field
+                let substring = crate::normalize_facet(val.value());
+                let key = FacetGroupKey { field_id, level: 0, left_bound: "" };
+                let mut docids = RoaringBitmap::new();
+                for result in strings_db.prefix_iter(rtxn, &key)? {
+                    let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) =
+                        result?;
+                    if left_bound.contains(substring.as_str()) {
+                        docids |= bitmap;
+                    }
+                }
+                return Ok(docids);
+            }
         };
 
         let mut output = RoaringBitmap::new();
@@ -304,15 +393,17 @@ impl<'a> Filter<'a> {
 
     /// Aggregates the documents ids that are part of the specified range automatically
     /// going deeper through the levels.
+    ///
+    /// The bounds are encoded like the facet numbers, whose encoding preserves their order.
     fn explore_facet_number_levels(
         rtxn: &heed::RoTxn,
         db: heed::Database<FacetGroupKeyCodec<OrderedF64Codec>, FacetGroupValueCodec>,
         field_id: FieldId,
-        left: Bound<f64>,
-        right: Bound<f64>,
+        left: Bound<Vec<u8>>,
+        right: Bound<Vec<u8>>,
         output: &mut RoaringBitmap,
     ) -> Result<()> {
-        match (left, right) {
+        match (&left, &right) {
             // lower TO upper when lower > upper must return no result
             (Included(l), Included(r)) if l > r => return Ok(()),
             (Included(l), Excluded(r)) if l >= r => return Ok(()),
@@ -320,8 +411,21 @@ impl<'a> Filter<'a> {
             (Excluded(l), Included(r)) if l >= r => return Ok(()),
             (_, _) => (),
         }
-        facet_range_search::find_docids_of_facet_within_bounds::<OrderedF64Codec>(
-            rtxn, db, field_id, &left, &right, output,
+        fn as_slice(bound: &Bound<Vec<u8>>) -> Bound<&[u8]> {
+            match bound {
+                Included(bytes) => Included(bytes.as_slice()),
+                Excluded(bytes) => Excluded(bytes.as_slice()),
+                Unbounded => Unbounded,
+            }
+        }
+        let (left, right) = (as_slice(&left), as_slice(&right));
+        facet_range_search::find_docids_of_facet_within_bounds::<BytesRefCodec>(
+            rtxn,
+            db.remap_key_type(),
+            field_id,
+            &left,
+            &right,
+            output,
         )?;
 
         Ok(())
@@ -580,6 +684,245 @@ impl<'a> Filter<'a> {
                     ))?
                 }
             }
+            FilterCondition::GeoPolygon { polygons } => {
+                if filterable_fields.contains("_geo") {
+                    let parsed_polygons = parse_polygons(polygons)?;
+
+                    let rtree = match index.geo_rtree(rtxn)? {
+                        Some(rtree) => rtree,
+                        None => return Ok(RoaringBitmap::new()),
+                    };
+
+                    // the rtree is indexed by cartesian coordinates, we only go through the
+                    // points within the envelope of the bounding box of each polygon
+                    let mut result = RoaringBitmap::new();
+                    for polygon in &parsed_polygons {
+                        let envelope = xyz_envelope(polygon_bounding_box(polygon));
+                        for point in rtree.locate_in_envelope(&envelope) {
+                            if is_point_in_polygon(&point.data.1, polygon) {
+                                result.insert(point.data.0);
+                            }
+                        }
+                    }
+
+                    Ok(result)
+                } else {
+                    Err(polygons[0][0][0].as_external_error(FilterError::AttributeNotFilterable {
+                        attribute: "_geo",
+                        filterable_fields: filterable_fields.clone(),
+                    }))?
+                }
+            }
+            FilterCondition::GeoWithin { polygons }
+            | FilterCondition::GeoIntersects { polygons } => {
+                if filterable_fields.contains("_geojson") {
+                    let within = matches!(self.condition, FilterCondition::GeoWithin { .. });
+                    let parsed_polygons = parse_polygons(polygons)?;
+                    let span = polygons[0][0][0].original_span();
+
+                    // We first select the documents whose shape bounding box is within or
+                    // intersects the bounding box of one of the polygons by re-using the range
+                    // filters on the `_geojson._minLat`, `_geojson._minLng`, `_geojson._maxLat`
+                    // and `_geojson._maxLng` facets.
+                    let mut candidates = RoaringBitmap::new();
+                    for polygon in &parsed_polygons {
+                        let [min_lat, min_lng, max_lat, max_lng] = polygon_bounding_box(polygon);
+                        let token = |value: f64| Token::new(span, Some(value.to_string()));
+                        let conditions = if within {
+                            [
+                                Condition::GreaterThanOrEqual(token(min_lat)),
+                                Condition::GreaterThanOrEqual(token(min_lng)),
+                                Condition::LowerThanOrEqual(token(max_lat)),
+                                Condition::LowerThanOrEqual(token(max_lng)),
+                            ]
+                        } else {
+                            [
+                                Condition::LowerThanOrEqual(token(max_lat)),
+                                Condition::LowerThanOrEqual(token(max_lng)),
+                                Condition::GreaterThanOrEqual(token(min_lat)),
+                                Condition::GreaterThanOrEqual(token(min_lng)),
+                            ]
+                        };
+
+                        let mut polygon_candidates: Option<RoaringBitmap> = None;
+                        for (field, op) in GEOJSON_BOUNDING_BOX_FIELDS.iter().zip(conditions) {
+                            let fid = Token::new(span, Some(field.to_string()));
+                            let selected =
+                                Filter { condition: FilterCondition::Condition { fid, op } }
+                                    .inner_evaluate(rtxn, index, filterable_fields)?;
+                            polygon_candidates = Some(match polygon_candidates {
+                                Some(polygon_candidates) => polygon_candidates & selected,
+                                None => selected,
+                            });
+                        }
+                        candidates |= polygon_candidates.unwrap_or_default();
+                    }
+
+                    let geojson_fid = match index.fields_ids_map(rtxn)?.id("_geojson") {
+                        Some(fid) => fid,
+                        None => return Ok(RoaringBitmap::new()),
+                    };
+
+                    // Then we compare the exact shapes of the candidates with the polygons.
+                    let mut result = RoaringBitmap::new();
+                    for document in index.iter_documents(rtxn, candidates)? {
+                        let (docid, obkv) = document?;
+                        let shape = obkv
+                            .get(geojson_fid)
+                            .and_then(|bytes| serde_json::from_slice(bytes).ok())
+                            .and_then(|value: Value| GeoShape::from_geojson(&value));
+                        let matches = shape.map_or(false, |shape| {
+                            parsed_polygons.iter().any(|polygon| {
+                                if within {
+                                    shape.is_within(polygon)
+                                } else {
+                                    shape.intersects(polygon)
+                                }
+                            })
+                        });
+                        if matches {
+                            result.insert(docid);
+                        }
+                    }
+
+                    Ok(result)
+                } else {
+                    Err(polygons[0][0][0].as_external_error(FilterError::AttributeNotFilterable {
+                        attribute: "_geojson",
+                        filterable_fields: filterable_fields.clone(),
+                    }))?
+                }
+            }
+            FilterCondition::Nested { fid, filter } => {
+                // Flattening the objects of an array loses the boundaries between them, we first
+                // select the documents whose flattened fields match the filter, ignoring its
+                // negations, and then check the objects of these candidates one by one.
+                let candidates = match Self::nested_candidates(
+                    rtxn,
+                    index,
+                    filterable_fields,
+                    fid.value(),
+                    filter,
+                    false,
+                )? {
+                    Some(candidates) => candidates,
+                    None => index.documents_ids(rtxn)?,
+                };
+
+                let fields_ids_map = index.fields_ids_map(rtxn)?;
+                let date_fields = index.date_fields(rtxn)?;
+                let mut result = RoaringBitmap::new();
+                for document in index.iter_documents(rtxn, candidates)? {
+                    let (docid, obkv) = document?;
+                    let mut fields = Map::new();
+                    for (field_id, bytes) in obkv.iter() {
+                        match fields_ids_map.name(field_id) {
+                            Some(name) if crate::is_faceted_by(fid.value(), name) => {
+                                let value = serde_json::from_slice(bytes)
+                                    .map_err(InternalError::SerdeJson)?;
+                                fields.insert(name.to_string(), value);
+                            }
+                            _ => (),
+                        }
+                    }
+
+                    let mut objects = Vec::new();
+                    nested_objects(&fields, fid.value(), &mut objects);
+                    if objects
+                        .into_iter()
+                        .any(|object| object_matches(filter, object, fid.value(), &date_fields))
+                    {
+                        result.insert(docid);
+                    }
+                }
+
+                Ok(result)
+            }
+        }
+    }
+
+    /// Returns a superset of the documents matching the nested filter on the objects of `prefix`,
+    /// `None` if the filter can't restrict them, by evaluating its conditions on the flattened
+    /// fields of the objects.
+    ///
+    /// The negated conditions are still evaluated to check that their fields are filterable.
+    fn nested_candidates(
+        rtxn: &heed::RoTxn,
+        index: &Index,
+        filterable_fields: &HashSet<String>,
+        prefix: &str,
+        condition: &FilterCondition<'a>,
+        negated: bool,
+    ) -> Result<Option<RoaringBitmap>> {
+        let prefixed = |fid: &Token<'a>| {
+            Token::new(fid.original_span(), Some(format!("{prefix}.{}", fid.value())))
+        };
+
+        match condition {
+            FilterCondition::Not(condition) => {
+                Self::nested_candidates(rtxn, index, filterable_fields, prefix, condition, true)?;
+                Ok(None)
+            }
+            FilterCondition::Condition { fid, op } => {
+                let condition = FilterCondition::Condition { fid: prefixed(fid), op: op.clone() };
+                let docids = Filter { condition }.inner_evaluate(rtxn, index, filterable_fields)?;
+                // the objects not equal to a value can belong to a document having an equal one
+                Ok((!negated && !matches!(op, Condition::NotEqual(_))).then_some(docids))
+            }
+            FilterCondition::In { fid, els } => {
+                let condition = FilterCondition::In { fid: prefixed(fid), els: els.clone() };
+                let docids = Filter { condition }.inner_evaluate(rtxn, index, filterable_fields)?;
+                Ok((!negated).then_some(docids))
+            }
+            FilterCondition::Or(subfilters) => {
+                let mut union = Some(RoaringBitmap::new());
+                for f in subfilters {
+                    let docids = Self::nested_candidates(
+                        rtxn,
+                        index,
+                        filterable_fields,
+                        prefix,
+                        f,
+                        negated,
+                    )?;
+                    union = match (union, docids) {
+                        (Some(union), Some(docids)) => Some(union | docids),
+                        _ => None,
+                    };
+                }
+                Ok(union)
+            }
+            FilterCondition::And(subfilters) => {
+                let mut intersection: Option<RoaringBitmap> = None;
+                for f in subfilters {
+                    let docids = Self::nested_candidates(
+                        rtxn,
+                        index,
+                        filterable_fields,
+                        prefix,
+                        f,
+                        negated,
+                    )?;
+                    intersection = match (intersection, docids) {
+                        (Some(intersection), Some(docids)) => Some(intersection & docids),
+                        (intersection, docids) => intersection.or(docids),
+                    };
+                }
+                Ok(intersection)
+            }
+            FilterCondition::Nested { fid, filter } => {
+                let prefix = format!("{prefix}.{}", fid.value());
+                Self::nested_candidates(rtxn, index, filterable_fields, &prefix, filter, negated)
+            }
+            FilterCondition::GeoLowerThan { point: [token, _], .. }
+            | FilterCondition::GeoBoundingBox { top_right_point: [token, _], .. } => {
+                Err(token
+                    .as_external_error(FilterError::NestedGeo { attribute: prefix.to_string() }))?
+            }
+            FilterCondition::GeoPolygon { polygons }
+            | FilterCondition::GeoWithin { polygons }
+            | FilterCondition::GeoIntersects { polygons } => Err(polygons[0][0][0]
+                .as_external_error(FilterError::NestedGeo { attribute: prefix.to_string() }))?,
         }
     }
 }
@@ -590,6 +933,237 @@ impl<'a> From<FilterCondition<'a>> for Filter<'a> {
     }
 }
 
+/// Parses the points of the polygons of a geo filter, checking that they are valid coordinates.
+fn parse_polygons(polygons: &[Vec<[Token; 2]>]) -> Result<Vec<Vec<[f64; 2]>>> {
+    let mut parsed_polygons = Vec::with_capacity(polygons.len());
+    for polygon in polygons {
+        let mut parsed_polygon = Vec::with_capacity(polygon.len());
+        for [lat_token, lng_token] in polygon {
+            let point = [lat_token.parse_finite_float()?, lng_token.parse_finite_float()?];
+            if !(-90.0..=90.0).contains(&point[0]) {
+                return Err(lat_token.as_external_error(BadGeoError::Lat(point[0])))?;
+            }
+            if !(-180.0..=180.0).contains(&point[1]) {
+                return Err(lng_token.as_external_error(BadGeoError::Lng(point[1])))?;
+            }
+            parsed_polygon.push(point);
+        }
+        parsed_polygons.push(parsed_polygon);
+    }
+    Ok(parsed_polygons)
+}
+
+/// Returns the `[min_lat, min_lng, max_lat, max_lng]` bounding box of a polygon.
+fn polygon_bounding_box(polygon: &[[f64; 2]]) -> [f64; 4] {
+    polygon.iter().fold(
+        [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY],
+        |[min_lat, min_lng, max_lat, max_lng], [lat, lng]| {
+            [min_lat.min(*lat), min_lng.min(*lng), max_lat.max(*lat), max_lng.max(*lng)]
+        },
+    )
+}
+
+/// Returns the cartesian envelope containing all the points of a
+/// `[min_lat, min_lng, max_lat, max_lng]` bounding box once converted with [`lat_lng_to_xyz`].
+fn xyz_envelope([min_lat, min_lng, max_lat, max_lng]: [f64; 4]) -> AABB<[f64; 3]> {
+    // the range of a trigonometric function over an interval is reached at its bounds
+    // or at the extremums the interval contains
+    fn range(f: fn(f64) -> f64, min: f64, max: f64, extremums: &[f64]) -> [f64; 2] {
+        let values = [min, max].into_iter().chain(extremums.iter().copied());
+        values
+            .filter(|x| (min..=max).contains(x))
+            .map(|x| f(x.to_radians()))
+            .fold([f64::INFINITY, f64::NEG_INFINITY], |[low, high], value| {
+                [low.min(value), high.max(value)]
+            })
+    }
+
+    // the product of a range by a positive range
+    fn product([low, high]: [f64; 2], [min, max]: [f64; 2]) -> [f64; 2] {
+        let low = if low < 0.0 { low * max } else { low * min };
+        let high = if high > 0.0 { high * max } else { high * min };
+        [low, high]
+    }
+
+    let lat_cos = range(f64::cos, min_lat, max_lat, &[0.0]);
+    let [min_x, max_x] = product(range(f64::cos, min_lng, max_lng, &[0.0]), lat_cos);
+    let [min_y, max_y] = product(range(f64::sin, min_lng, max_lng, &[-90.0, 90.0]), lat_cos);
+    let [min_z, max_z] = range(f64::sin, min_lat, max_lat, &[]);
+
+    // we leave some room for the rounding errors of the conversion
+    let epsilon = 1e-9;
+    AABB::from_corners(
+        [min_x - epsilon, min_y - epsilon, min_z - epsilon],
+        [max_x + epsilon, max_y + epsilon, max_z + epsilon],
+    )
+}
+
+/// Collects the objects found under the dotted `path` of the fields, going through the arrays.
+fn nested_objects<'v>(
+    fields: &'v Map<String, Value>,
+    path: &str,
+    objects: &mut Vec<&'v Map<String, Value>>,
+) {
+    fn collect<'v>(
+        value: &'v Value,
+        path: Option<&str>,
+        objects: &mut Vec<&'v Map<String, Value>>,
+    ) {
+        match (value, path) {
+            (Value::Object(object), None) => objects.push(object),
+            (Value::Object(object), Some(path)) => nested_objects(object, path, objects),
+            (Value::Array(values), path) => {
+                values.iter().for_each(|value| collect(value, path, objects))
+            }
+            _ => (),
+        }
+    }
+
+    for (key, value) in fields {
+        if key == path {
+            collect(value, None, objects);
+        } else if let Some(rest) = path.strip_prefix(key.as_str()).and_then(|p| p.strip_prefix('.'))
+        {
+            collect(value, Some(rest), objects);
+        }
+    }
+}
+
+/// Returns `true` if the object matches the nested filter, whose fields are relative to it.
+///
+/// The object is found under the `prefix` path of the documents, only the strings of the date
+/// fields under it are compared as dates.
+fn object_matches(
+    condition: &FilterCondition,
+    object: &Map<String, Value>,
+    prefix: &str,
+    date_fields: &HashSet<String>,
+) -> bool {
+    let is_date_field = |field: &str| {
+        let path = format!("{prefix}.{field}");
+        date_fields.iter().any(|date_field| crate::is_faceted_by(&path, date_field))
+    };
+
+    match condition {
+        FilterCondition::Not(condition) => !object_matches(condition, object, prefix, date_fields),
+        FilterCondition::Condition { fid, op } => {
+            let flattened = flatten_serde_json::flatten(object);
+            value_matches(op, flattened.get(fid.value()), is_date_field(fid.value()))
+        }
+        FilterCondition::In { fid, els } => {
+            let flattened = flatten_serde_json::flatten(object);
+            let value = flattened.get(fid.value());
+            let date_field = is_date_field(fid.value());
+            els.iter().any(|el| value_matches(&Condition::Equal(el.clone()), value, date_field))
+        }
+        FilterCondition::Or(subfilters) => {
+            subfilters.iter().any(|f| object_matches(f, object, prefix, date_fields))
+        }
+        FilterCondition::And(subfilters) => {
+            subfilters.iter().all(|f| object_matches(f, object, prefix, date_fields))
+        }
+        FilterCondition::Nested { fid, filter } => {
+            let prefix = format!("{prefix}.{}", fid.value());
+            let mut objects = Vec::new();
+            nested_objects(object, fid.value(), &mut objects);
+            objects.into_iter().any(|object| object_matches(filter, object, &prefix, date_fields))
+        }
+        // the geo filters are rejected while selecting the candidates
+        FilterCondition::GeoLowerThan { .. }
+        | FilterCondition::GeoBoundingBox { .. }
+        | FilterCondition::GeoPolygon { .. }
+        | FilterCondition::GeoWithin { .. }
+        | FilterCondition::GeoIntersects { .. } => false,
+    }
+}
+
+/// Returns `true` if the value of a field of a nested object matches the condition,
+/// like the facet databases would, an array matching if one of its values matches.
+///
+/// The strings are only compared as dates when the field is a date field.
+fn value_matches(op: &Condition, value: Option<&Value>, date_field: bool) -> bool {
+    let value = match (op, value) {
+        (Condition::NotEqual(val), value) => {
+            return !value_matches(&Condition::Equal(val.clone()), value, date_field)
+        }
+        (_, None) => return false,
+        (Condition::Exists, Some(_)) => return true,
+        (Condition::Null, Some(value)) => return value.is_null(),
+        (Condition::Empty, Some(value)) => {
+            return match value {
+                Value::String(string) => string.is_empty(),
+                Value::Array(values) => values.is_empty(),
+                Value::Object(object) => object.is_empty(),
+                _ => false,
+            }
+        }
+        (_, Some(value)) => value,
+    };
+
+    if let Value::Array(values) = value {
+        return values.iter().any(|value| value_matches(op, Some(value), date_field));
+    }
+
+    let number = match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) if date_field => crate::date_to_timestamp(string),
+        _ => None,
+    };
+    let string = match value {
+        Value::String(string) => Some(crate::normalize_facet(string)),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        _ => None,
+    };
+    let compare = |val: &Token, cmp: fn(f64, f64) -> bool| match (number, parse_number_or_date(val))
+    {
+        (Some(number), Ok(val)) => cmp(number, val),
+        _ => false,
+    };
+
+    match op {
+        Condition::Equal(val) => {
+            string.map_or(false, |string| string == crate::normalize_facet(val.value()))
+                || compare(val, |number, val| number == val)
+        }
+        Condition::GreaterThan(val) => compare(val, |number, val| number > val),
+        Condition::GreaterThanOrEqual(val) => compare(val, |number, val| number >= val),
+        Condition::LowerThan(val) => compare(val, |number, val| number < val),
+        Condition::LowerThanOrEqual(val) => compare(val, |number, val| number <= val),
+        Condition::Between { from, to } => {
+            compare(from, |number, from| number >= from) && compare(to, |number, to| number <= to)
+        }
+        Condition::Contains(val) => {
+            string.map_or(false, |string| string.contains(&crate::normalize_facet(val.value())))
+        }
+        Condition::StartsWith(val) => {
+            string.map_or(false, |string| string.starts_with(&crate::normalize_facet(val.value())))
+        }
+        Condition::NotEqual(_) | Condition::Exists | Condition::Null | Condition::Empty => {
+            unreachable!()
+        }
+    }
+}
+
+/// Parses the value of a numeric condition, the RFC 3339 dates being converted
+/// into the timestamps under which the values of the date fields are stored.
+fn parse_number_or_date<'t>(token: &'t Token) -> std::result::Result<f64, FPError<'t>> {
+    match token.parse_finite_float() {
+        Ok(number) => Ok(number),
+        Err(error) => crate::date_to_timestamp(token.value()).ok_or(error),
+    }
+}
+
+/// Encodes a number or a date like in the facet numbers database,
+/// the integers are parsed as such to not lose their precision.
+fn encode_number_or_date<'t>(token: &'t Token) -> std::result::Result<Vec<u8>, FPError<'t>> {
+    let bytes = match token.value().parse::<i128>() {
+        Ok(integer) => OrderedI128Codec::bytes_encode(&integer),
+        Err(_) => OrderedF64Codec::bytes_encode(&parse_number_or_date(token)?),
+    };
+    // the floats are finite, they can always be encoded
+    Ok(bytes.unwrap().into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Write;
@@ -686,6 +1260,34 @@ mod tests {
         assert_eq!(condition, expected);
     }
 
+    #[test]
+    fn without_facet() {
+        let filter = Filter::from_str("genre = action AND price > 10").unwrap().unwrap();
+        let expected = Filter::from_str("price > 10").unwrap().unwrap();
+        assert_eq!(filter.without_facet("genre"), Some(expected));
+
+        let filter = Filter::from_str(
+            "(genre = action OR genre = comedy) AND price > 10 AND NOT genre.sub IN [a, b]",
+        )
+        .unwrap()
+        .unwrap();
+        let expected = Filter::from_str("price > 10").unwrap().unwrap();
+        assert_eq!(filter.without_facet("genre"), Some(expected));
+
+        let filter =
+            Filter::from_str("genre = action AND genres = comedy AND price > 10").unwrap().unwrap();
+        let expected = Filter::from_str("genres = comedy AND price > 10").unwrap().unwrap();
+        assert_eq!(filter.without_facet("genre"), Some(expected));
+
+        // the conditions that don't constrain the whole filter are kept
+        let filter = Filter::from_str("genre = action OR price > 10").unwrap().unwrap();
+        assert_eq!(filter.without_facet("genre"), Some(filter.clone()));
+
+        let filter = Filter::from_str("genre = action OR genre = comedy").unwrap().unwrap();
+        assert_eq!(filter.without_facet("genre"), None);
+        assert_eq!(filter.without_facet("price"), Some(filter.clone()));
+    }
+
     #[test]
     fn not_filterable() {
         let index = TempIndex::new();
@@ -1128,4 +1730,303 @@ mod tests {
         let result = filter.evaluate(&rtxn, &index).unwrap();
         assert_eq!(result, RoaringBitmap::from_iter((0..100).filter(|x| x % 10 != 0)));
     }
+
+    #[test]
+    fn filter_string_contains_and_starts_with() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("colour") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "colour": "Dark blue" },
+                { "id": 1, "colour": ["light blue", "dark green"] },
+                { "id": 2, "colour": "Red" },
+                { "id": 3, "colour": 12 },
+                { "id": 4 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        assert_eq!(evaluate("colour STARTS WITH dark"), RoaringBitmap::from_iter([0, 1]));
+        assert_eq!(evaluate("colour STARTS WITH 'DARK B'"), RoaringBitmap::from_iter([0]));
+        assert_eq!(evaluate("colour STARTS WITH blue"), RoaringBitmap::new());
+        assert_eq!(evaluate("colour NOT STARTS WITH dark"), RoaringBitmap::from_iter([2, 3, 4]));
+
+        assert_eq!(evaluate("colour CONTAINS blue"), RoaringBitmap::from_iter([0, 1]));
+        assert_eq!(evaluate("colour CONTAINS 'e'"), RoaringBitmap::from_iter([0, 1, 2]));
+        assert_eq!(evaluate("colour CONTAINS yellow"), RoaringBitmap::new());
+        assert_eq!(evaluate("colour NOT CONTAINS green"), RoaringBitmap::from_iter([0, 2, 3, 4]));
+    }
+
+    #[test]
+    fn filter_nested_objects() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("reviews") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "reviews": [{ "author": "Bob", "rating": 5 }, { "author": "alice", "rating": 2 }] },
+                { "id": 1, "reviews": [{ "author": "bob", "rating": 3 }, { "author": "alice", "rating": 5 }] },
+                { "id": 2, "reviews": { "author": "bob", "rating": 4.5 } },
+                { "id": 3, "reviews": [{ "author": "carol", "rating": 1, "comments": [{ "text": "Nice" }] }] },
+                { "id": 4 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        // the flattened fields mix the objects of the array
+        assert_eq!(
+            evaluate("reviews.author = bob AND reviews.rating > 4"),
+            RoaringBitmap::from_iter([0, 1, 2])
+        );
+        assert_eq!(
+            evaluate("reviews.{author = bob AND rating > 4}"),
+            RoaringBitmap::from_iter([0, 2])
+        );
+        assert_eq!(
+            evaluate("reviews.{author = bob AND NOT rating > 4}"),
+            RoaringBitmap::from_iter([1])
+        );
+        assert_eq!(evaluate("reviews.{author != bob}"), RoaringBitmap::from_iter([0, 1, 3]));
+        assert_eq!(evaluate("NOT reviews.{author = bob}"), RoaringBitmap::from_iter([3, 4]));
+        assert_eq!(evaluate("reviews.{rating 2 TO 3}"), RoaringBitmap::from_iter([0, 1]));
+        assert_eq!(
+            evaluate("reviews.{author IN [carol, dave] AND comments.{text = nice}}"),
+            RoaringBitmap::from_iter([3])
+        );
+
+        let filter = Filter::from_str("reviews.{_geoRadius(12, 13, 14)}").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("The geo filters cannot be used inside the nested filter on `reviews`."));
+
+        let filter = Filter::from_str("id.{author = bob}").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Attribute `id.author` is not filterable. Available filterable attributes are: `reviews`."
+        ));
+    }
+
+    #[test]
+    fn filter_nested_objects_dates() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("reviews") });
+                settings.set_date_fields(hashset! { S("reviews.date") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "reviews": [{ "author": "bob", "date": "2024-01-01T00:00:00Z", "code": "2024-01-01T00:00:00Z" }] },
+                { "id": 1, "reviews": [{ "author": "bob", "date": "2022-01-01T00:00:00Z", "code": "2022-01-01T00:00:00Z" }] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        assert_eq!(
+            evaluate("reviews.{author = bob AND NOT date > 2023-01-01T00:00:00Z}"),
+            RoaringBitmap::from_iter([1])
+        );
+        // the strings of the other fields are never compared as dates
+        assert_eq!(
+            evaluate("reviews.{author = bob AND NOT code > 2023-01-01T00:00:00Z}"),
+            RoaringBitmap::from_iter([0, 1])
+        );
+    }
+
+    #[test]
+    fn filter_big_integers() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("snowflake") });
+            })
+            .unwrap();
+
+        // the first three integers round to the same float
+        index
+            .add_documents(documents!([
+                { "id": 0, "snowflake": 9007199254740992_u64 },
+                { "id": 1, "snowflake": 9007199254740993_u64 },
+                { "id": 2, "snowflake": 9007199254740994_u64 },
+                { "id": 3, "snowflake": 18446744073709551615_u64 },
+                { "id": 4, "snowflake": 18446744073709551614_u64 },
+                { "id": 5, "snowflake": -9223372036854775807_i64 },
+                { "id": 6, "snowflake": 1e19 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        assert_eq!(evaluate("snowflake = 9007199254740993"), RoaringBitmap::from_iter([1]));
+        assert_eq!(
+            evaluate("snowflake != 9007199254740993"),
+            RoaringBitmap::from_iter([0, 2, 3, 4, 5, 6])
+        );
+        assert_eq!(evaluate("snowflake = 18446744073709551614"), RoaringBitmap::from_iter([4]));
+        assert_eq!(evaluate("snowflake = -9223372036854775807"), RoaringBitmap::from_iter([5]));
+        assert_eq!(evaluate("snowflake = 10000000000000000000"), RoaringBitmap::from_iter([6]));
+        assert_eq!(
+            evaluate("snowflake > 9007199254740992"),
+            RoaringBitmap::from_iter([1, 2, 3, 4, 6])
+        );
+        assert_eq!(
+            evaluate("snowflake < 18446744073709551615"),
+            RoaringBitmap::from_iter([0, 1, 2, 4, 5, 6])
+        );
+        assert_eq!(
+            evaluate("snowflake 9007199254740993 TO 9007199254740994"),
+            RoaringBitmap::from_iter([1, 2])
+        );
+        assert_eq!(
+            evaluate("snowflake 1e19 TO 18446744073709551614"),
+            RoaringBitmap::from_iter([4, 6])
+        );
+        assert_eq!(evaluate("snowflake < 0"), RoaringBitmap::from_iter([5]));
+    }
+
+    #[test]
+    fn geo_polygon() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_geo": { "lat": 1.0, "lng": 1.0 } },
+                { "id": 1, "_geo": { "lat": 5.0, "lng": 8.0 } },
+                { "id": 2, "_geo": { "lat": 5.0, "lng": 4.0 } },
+                { "id": 3, "_geo": { "lat": 21.0, "lng": 21.5 } },
+                { "id": 4, "_geo": { "lat": -40.0, "lng": 100.0 } },
+                { "id": 5 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        // a square with a notch on its east side, the document 1 is in the notch
+        let notched = "[[0, 0], [0, 10], [4, 10], [5, 5], [6, 10], [10, 10], [10, 0]]";
+        assert_eq!(evaluate(&format!("_geoPolygon({notched})")), RoaringBitmap::from_iter([0, 2]));
+        assert_eq!(
+            evaluate(&format!("NOT _geoPolygon({notched})")),
+            RoaringBitmap::from_iter([1, 3, 4, 5])
+        );
+        assert_eq!(
+            evaluate(&format!("_geoPolygon({notched}, [[20, 20], [20, 22], [22, 22]])")),
+            RoaringBitmap::from_iter([0, 2, 3])
+        );
+        assert_eq!(
+            evaluate("_geoPolygon([[-50, 90], [-30, 90], [-40, 110]])"),
+            RoaringBitmap::from_iter([4])
+        );
+
+        let filter =
+            Filter::from_str("_geoPolygon([[0, 0], [0, 10], [100, 10]])").unwrap().unwrap();
+        let error = filter.evaluate(&rtxn, &index).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Bad latitude `100`. Latitude must be contained between -90 and 90 degrees."
+        ));
+    }
+
+    #[test]
+    fn geo_within_and_intersects() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geojson") });
+            })
+            .unwrap();
+
+        // the GeoJSON positions are expressed as `[longitude, latitude]`
+        index
+            .add_documents(documents!([
+                { "id": 0, "_geojson": { "type": "LineString", "coordinates": [[1, 1], [9, 9]] } },
+                { "id": 1, "_geojson": { "type": "Polygon", "coordinates": [[[5, 5], [15, 5], [15, 15], [5, 15], [5, 5]]] } },
+                { "id": 2, "_geojson": { "type": "Point", "coordinates": [100, 40] } },
+                { "id": 3, "_geojson": { "type": "LineString", "coordinates": [[-5, 5], [15, 5]] } },
+                { "id": 4, "_geojson": { "type": "Polygon", "coordinates": [[[-20, -20], [20, -20], [20, 20], [-20, 20], [-20, -20]]] } },
+                { "id": 5 },
+                { "id": 6, "_geojson": null },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let evaluate = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            filter.evaluate(&rtxn, &index).unwrap()
+        };
+
+        let square = "[[0, 0], [0, 10], [10, 10], [10, 0]]";
+        assert_eq!(evaluate(&format!("_geoWithin({square})")), RoaringBitmap::from_iter([0]));
+        assert_eq!(
+            evaluate(&format!("NOT _geoWithin({square})")),
+            RoaringBitmap::from_iter([1, 2, 3, 4, 5, 6])
+        );
+        assert_eq!(
+            evaluate(&format!("_geoIntersects({square})")),
+            RoaringBitmap::from_iter([0, 1, 3, 4])
+        );
+        assert_eq!(
+            evaluate(&format!("_geoWithin({square}, [[30, 90], [50, 90], [50, 110], [30, 110]])")),
+            RoaringBitmap::from_iter([0, 2])
+        );
+        assert_eq!(
+            evaluate("_geoIntersects([[-30, -30], [-30, -25], [-25, -25]])"),
+            RoaringBitmap::new()
+        );
+        drop(rtxn);
+
+        let error = index
+            .add_documents(
+                documents!({ "id": 7, "_geojson": { "type": "Point", "coordinates": [0, 100] } }),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The `_geojson` field in the document with the id: `7` is not a valid GeoJSON geometry. Was expecting a `Point`, `MultiPoint`, `LineString`, `MultiLineString`, `Polygon` or `MultiPolygon` with longitudes and latitudes in range but instead got `{\"type\":\"Point\",\"coordinates\":[0,100]}`."
+        );
+    }
 }
@@ -4,11 +4,16 @@ use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 use roaring::RoaringBitmap;
 
-pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_aggregation::{aggregate_numbers, NumberAggregation};
+pub use self::facet_distribution::{
+    CachedFacetDistribution, CachedFacetValues, FacetDistribution, OrderBy,
+    CACHED_FACET_VALUES_LIMIT, DEFAULT_VALUES_PER_FACET,
+};
 pub use self::filter::{BadGeoError, Filter};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec};
 use crate::heed_codec::BytesRefCodec;
 use crate::{Index, Result};
+mod facet_aggregation;
 mod facet_distribution;
 mod facet_distribution_iter;
 mod facet_range_search;
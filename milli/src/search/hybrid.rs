@@ -1,15 +1,47 @@
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
+use super::new::{group_counts, GroupBy};
 use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
-use crate::{MatchingWords, Result, Search, SearchResult};
+use crate::{DocumentId, MatchingWords, Result, Search, SearchResult};
+
+/// The constant dampening the weight of the best ranked hits in the reciprocal rank fusion.
+const RECIPROCAL_RANK_CONSTANT: f64 = 60.0;
+
+/// How the hits of the keyword and semantic searches are fused into the hits of a hybrid search.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FusionStrategy {
+    /// Compares the scores of the hits weighted by the semantic ratio.
+    #[default]
+    Linear,
+    /// Sums the scores of the hits weighted by the semantic ratio, once normalized between
+    /// the worst and the best score of each search.
+    Normalized,
+    /// Sums the reciprocal of the rank of the hits in each search, weighted by the semantic
+    /// ratio, ignoring their scores.
+    ReciprocalRank,
+}
+
+/// A vector to search in the vector space of an embedder, weighting its similarities.
+#[derive(Clone)]
+pub struct VectorTarget {
+    pub embedder_name: String,
+    pub vector: Vec<f32>,
+    pub weight: f32,
+}
 
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
     candidates: RoaringBitmap,
     document_scores: Vec<(u32, ScoreWithRatio)>,
+    degraded: bool,
+    group_counts: BTreeMap<String, u64>,
+    applied_query_rules: BTreeMap<DocumentId, Vec<String>>,
+    corrected_query: Option<String>,
 }
 
 type ScoreWithRatio = (Vec<ScoreDetails>, f32);
@@ -49,6 +81,14 @@ fn compare_scores(
                     order => return order,
                 }
             }
+            (Some(ScoreValue::QueryRules(left)), Some(ScoreValue::QueryRules(right))) => {
+                match left.rank.cmp(&right.rank) {
+                    Ordering::Equal => continue,
+                    order => return order,
+                }
+            }
+            (Some(ScoreValue::QueryRules(_)), Some(_)) => return Ordering::Greater,
+            (Some(_), Some(ScoreValue::QueryRules(_))) => return Ordering::Less,
             (Some(ScoreValue::Score(_)), Some(_)) => return Ordering::Greater,
             (Some(_), Some(ScoreValue::Score(_))) => return Ordering::Less,
             // if we have this, we're bad
@@ -72,82 +112,199 @@ impl ScoreWithRatioResult {
             matching_words: results.matching_words,
             candidates: results.candidates,
             document_scores,
+            degraded: results.degraded,
+            group_counts: results.group_counts,
+            applied_query_rules: results.applied_query_rules,
+            corrected_query: results.corrected_query,
         }
     }
 
-    fn merge(left: Self, right: Self, from: usize, length: usize) -> SearchResult {
-        let mut documents_ids =
-            Vec::with_capacity(left.document_scores.len() + right.document_scores.len());
-        let mut document_scores =
-            Vec::with_capacity(left.document_scores.len() + right.document_scores.len());
+    fn merge(
+        mut left: Self,
+        mut right: Self,
+        fusion: FusionStrategy,
+        from: usize,
+        length: usize,
+    ) -> SearchResult {
+        let left_scores = std::mem::take(&mut left.document_scores);
+        let right_scores = std::mem::take(&mut right.document_scores);
+        let mut documents_ids = Vec::with_capacity(left_scores.len() + right_scores.len());
+        let mut document_scores = Vec::with_capacity(left_scores.len() + right_scores.len());
 
-        let mut documents_seen = RoaringBitmap::new();
-        for (docid, (main_score, _sub_score)) in left
-            .document_scores
-            .into_iter()
-            .merge_by(right.document_scores.into_iter(), |(_, left), (_, right)| {
-                // the first value is the one with the greatest score
-                compare_scores(left, right).is_ge()
-            })
-            // remove documents we already saw
-            .filter(|(docid, _)| documents_seen.insert(*docid))
-            // start skipping **after** the filter
-            .skip(from)
-            // take **after** skipping
-            .take(length)
-        {
+        let hits: Vec<_> = match fusion {
+            FusionStrategy::Linear => {
+                let mut documents_seen = RoaringBitmap::new();
+                left_scores
+                    .into_iter()
+                    .merge_by(right_scores, |(_, left), (_, right)| {
+                        // the first value is the one with the greatest score
+                        compare_scores(left, right).is_ge()
+                    })
+                    // remove documents we already saw
+                    .filter(|(docid, _)| documents_seen.insert(*docid))
+                    // TODO: pass both scores to documents_score in some way?
+                    .map(|(docid, (main_score, _sub_score))| (docid, main_score))
+                    .collect()
+            }
+            FusionStrategy::Normalized | FusionStrategy::ReciprocalRank => {
+                fuse([left_scores, right_scores], fusion)
+            }
+        };
+
+        // start skipping **after** the fusion, take **after** skipping
+        for (docid, main_score) in hits.into_iter().skip(from).take(length) {
             documents_ids.push(docid);
-            // TODO: pass both scores to documents_score in some way?
             document_scores.push(main_score);
         }
 
+        let mut group_counts = right.group_counts;
+        group_counts.extend(left.group_counts);
+        let mut applied_query_rules = right.applied_query_rules;
+        applied_query_rules.extend(left.applied_query_rules);
+        applied_query_rules.retain(|docid, _| documents_ids.contains(docid));
+
         SearchResult {
             matching_words: left.matching_words,
             candidates: left.candidates | right.candidates,
             documents_ids,
             document_scores,
+            degraded: left.degraded | right.degraded,
+            group_counts,
+            applied_query_rules,
+            corrected_query: left.corrected_query.or(right.corrected_query),
         }
     }
 }
 
+/// Sums the weighted contributions of each search to the hits, ranking the hits from the greatest
+/// sum to the lowest, each hit keeping the scores of the search contributing the most to it.
+fn fuse(
+    searches: impl IntoIterator<Item = Vec<(DocumentId, ScoreWithRatio)>>,
+    fusion: FusionStrategy,
+) -> Vec<(DocumentId, Vec<ScoreDetails>)> {
+    let mut fused: HashMap<DocumentId, (f64, f64, Vec<ScoreDetails>)> = HashMap::new();
+    for document_scores in searches {
+        let global_scores: Vec<_> = document_scores
+            .iter()
+            .map(|(_, (scores, _))| ScoreDetails::global_score(scores.iter()))
+            .collect();
+        let (min, max) = global_scores
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &s| (min.min(s), max.max(s)));
+
+        for (rank, ((docid, (scores, ratio)), global_score)) in
+            document_scores.into_iter().zip(global_scores).enumerate()
+        {
+            let contribution = match fusion {
+                FusionStrategy::ReciprocalRank => {
+                    1.0 / (RECIPROCAL_RANK_CONSTANT + rank as f64 + 1.0)
+                }
+                // all the hits are equally good when the search can't tell them apart
+                FusionStrategy::Linear | FusionStrategy::Normalized if max > min => {
+                    (global_score - min) / (max - min)
+                }
+                FusionStrategy::Linear | FusionStrategy::Normalized => 1.0,
+            } * ratio as f64;
+
+            match fused.entry(docid) {
+                Entry::Occupied(mut entry) => {
+                    let (sum, best, best_scores) = entry.get_mut();
+                    *sum += contribution;
+                    if contribution > *best {
+                        *best = contribution;
+                        *best_scores = scores;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((contribution, contribution, scores));
+                }
+            }
+        }
+    }
+
+    let mut fused: Vec<_> = fused.into_iter().collect();
+    // the documents pinned or boosted by the query rules stay first,
+    // the ties are broken by document id to keep the pagination stable
+    fused.sort_by(
+        |(left_docid, (left, _, left_scores)), (right_docid, (right, _, right_scores))| {
+            query_rules_rank(right_scores)
+                .cmp(&query_rules_rank(left_scores))
+                .then(right.total_cmp(left))
+                .then(left_docid.cmp(right_docid))
+        },
+    );
+    fused.into_iter().map(|(docid, (_, _, scores))| (docid, scores)).collect()
+}
+
+/// The rank given by the query rules to a hit, `0` if no query rule reorders the hits.
+fn query_rules_rank(scores: &[ScoreDetails]) -> u32 {
+    scores
+        .iter()
+        .find_map(|score| match score {
+            ScoreDetails::QueryRules(rank) => Some(rank.rank),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
 impl<'a> Search<'a> {
-    pub fn execute_hybrid(&self, semantic_ratio: f32) -> Result<SearchResult> {
+    pub fn execute_hybrid(
+        &self,
+        semantic_ratio: f32,
+        fusion: FusionStrategy,
+    ) -> Result<SearchResult> {
         // TODO: find classier way to achieve that than to reset vector and query params
         // create separate keyword and semantic searches
         let mut search = Search {
             query: self.query.clone(),
+            structured_query: self.structured_query.clone(),
             vector: self.vector.clone(),
             filter: self.filter.clone(),
             offset: 0,
             limit: self.limit + self.offset,
             sort_criteria: self.sort_criteria.clone(),
             searchable_attributes: self.searchable_attributes,
+            attribute_boosts: self.attribute_boosts,
             geo_strategy: self.geo_strategy,
             terms_matching_strategy: self.terms_matching_strategy,
             scoring_strategy: ScoringStrategy::Detailed,
+            ranking_score_threshold: self.ranking_score_threshold,
+            time_budget: self.time_budget,
+            search_after: self.search_after.clone(),
+            group_by: self.group_by,
+            pinned_documents: self.pinned_documents,
+            hidden_documents: self.hidden_documents,
+            vocabulary: self.vocabulary,
+            phrase_slop: self.phrase_slop,
+            distinct: self.distinct,
             words_limit: self.words_limit,
             exhaustive_number_hits: self.exhaustive_number_hits,
+            early_termination: self.early_termination,
+            corrected_query: self.corrected_query,
             rtxn: self.rtxn,
             index: self.index,
             distribution_shift: self.distribution_shift,
             embedder_name: self.embedder_name.clone(),
+            vector_targets: Vec::new(),
+            candidate_selection: self.candidate_selection,
         };
 
         let vector_query = search.vector.take();
         let keyword_results = search.execute()?;
 
         // skip semantic search if we don't have a vector query (placeholder search)
-        let Some(vector_query) = vector_query else {
+        if vector_query.is_none() && self.vector_targets.is_empty() {
             return Ok(keyword_results);
-        };
+        }
 
         // completely skip semantic search if the results of the keyword search are good enough
         if self.results_good_enough(&keyword_results, semantic_ratio) {
             return Ok(keyword_results);
         }
 
-        search.vector = Some(vector_query);
-        search.query = None;
+        // the query is kept to exclude the documents containing its negative words
+        search.vector = vector_query;
+        search.vector_targets = self.vector_targets.clone();
 
         // TODO: would be better to have two distinct functions at this point
         let vector_results = search.execute()?;
@@ -155,12 +312,107 @@ impl<'a> Search<'a> {
         let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
         let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
 
-        let merge_results =
-            ScoreWithRatioResult::merge(vector_results, keyword_results, self.offset, self.limit);
+        let merge_results = ScoreWithRatioResult::merge(
+            vector_results,
+            keyword_results,
+            fusion,
+            self.offset,
+            self.limit,
+        );
         assert!(merge_results.documents_ids.len() <= self.limit);
         Ok(merge_results)
     }
 
+    /// Searches the vector space of each target and sums their similarities weighted by the
+    /// weight of the targets, once normalized between the worst and the best similarity of
+    /// each vector space.
+    pub(crate) fn execute_vector_targets(&self) -> Result<SearchResult> {
+        let mut searches = Vec::with_capacity(self.vector_targets.len());
+        let mut candidates = RoaringBitmap::new();
+        let mut degraded = false;
+        let mut applied_query_rules = BTreeMap::new();
+
+        for target in &self.vector_targets {
+            let search = Search {
+                query: self.query.clone(),
+                structured_query: self.structured_query.clone(),
+                vector: Some(target.vector.clone()),
+                filter: self.filter.clone(),
+                offset: 0,
+                limit: self.limit + self.offset,
+                sort_criteria: self.sort_criteria.clone(),
+                searchable_attributes: self.searchable_attributes,
+                attribute_boosts: self.attribute_boosts,
+                geo_strategy: self.geo_strategy,
+                terms_matching_strategy: self.terms_matching_strategy,
+                scoring_strategy: ScoringStrategy::Detailed,
+                ranking_score_threshold: self.ranking_score_threshold,
+                time_budget: self.time_budget,
+                search_after: None,
+                group_by: self.group_by,
+                pinned_documents: self.pinned_documents,
+                hidden_documents: self.hidden_documents,
+                vocabulary: self.vocabulary,
+                phrase_slop: self.phrase_slop,
+                distinct: self.distinct,
+                words_limit: self.words_limit,
+                exhaustive_number_hits: self.exhaustive_number_hits,
+                early_termination: self.early_termination,
+                corrected_query: self.corrected_query,
+                rtxn: self.rtxn,
+                index: self.index,
+                distribution_shift: None,
+                embedder_name: Some(target.embedder_name.clone()),
+                vector_targets: Vec::new(),
+                candidate_selection: self.candidate_selection,
+            };
+            let results = ScoreWithRatioResult::new(search.execute()?, target.weight);
+            candidates |= results.candidates;
+            degraded |= results.degraded;
+            applied_query_rules.extend(results.applied_query_rules);
+            searches.push(results.document_scores);
+        }
+
+        let mut documents_ids = Vec::new();
+        let mut document_scores = Vec::new();
+        for (docid, scores) in fuse(searches, FusionStrategy::Normalized)
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+        {
+            documents_ids.push(docid);
+            document_scores.push(scores);
+        }
+        applied_query_rules.retain(|docid, _| documents_ids.contains(docid));
+
+        // the groups are counted among the candidates of all the vector spaces
+        let group_by = match self.group_by {
+            Some((field, limit)) => GroupBy::new(self.index, self.rtxn, field, limit)?,
+            None => None,
+        };
+        let group_counts = match group_by {
+            Some(group_by) => group_counts(
+                self.index,
+                self.rtxn,
+                group_by.field_id(),
+                &documents_ids,
+                &candidates,
+            )?,
+            None => BTreeMap::new(),
+        };
+
+        Ok(SearchResult {
+            matching_words: MatchingWords::default(),
+            candidates,
+            documents_ids,
+            document_scores,
+            degraded,
+            group_counts,
+            applied_query_rules,
+            corrected_query: None,
+        })
+    }
+
     fn results_good_enough(&self, keyword_results: &SearchResult, semantic_ratio: f32) -> bool {
         // A result is good enough if its keyword score is > 0.9 with a semantic ratio of 0.5 => 0.9 * 0.5
         const GOOD_ENOUGH_SCORE: f64 = 0.45;
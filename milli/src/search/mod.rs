@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
 
 use charabia::normalizer::NormalizerOption;
 use charabia::Normalize;
@@ -10,9 +12,22 @@ use log::error;
 use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
-pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
-pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
-use self::new::{execute_vector_search, PartialSearchResult};
+pub use self::facet::{
+    aggregate_numbers, FacetDistribution, Filter, NumberAggregation, OrderBy,
+    DEFAULT_VALUES_PER_FACET,
+};
+pub use self::hybrid::{FusionStrategy, VectorTarget};
+pub use self::new::matches::{
+    FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OffsetUnit,
+};
+use self::new::{
+    corrected_query, execute_vector_search, group_counts, negative_query_docids, AppliedQueryRules,
+    GroupBy, PartialSearchResult,
+};
+pub use self::new::{
+    split_negative_query, BucketCoordinate, CandidateSelection, QueryClause, QueryVocabulary,
+    SearchCursor, StructuredQuery,
+};
 use crate::error::UserError;
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupValue};
 use crate::score_details::{ScoreDetails, ScoringStrategy};
@@ -37,6 +52,7 @@ pub mod new;
 
 pub struct Search<'a> {
     query: Option<String>,
+    structured_query: Option<StructuredQuery<'a>>,
     vector: Option<Vec<f32>>,
     // this should be linked to the String in the query
     filter: Option<Filter<'a>>,
@@ -44,37 +60,66 @@ pub struct Search<'a> {
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
     searchable_attributes: Option<&'a [String]>,
+    attribute_boosts: Option<&'a BTreeMap<String, f64>>,
     geo_strategy: new::GeoSortStrategy,
     terms_matching_strategy: TermsMatchingStrategy,
     scoring_strategy: ScoringStrategy,
+    ranking_score_threshold: Option<f64>,
+    time_budget: TimeBudget,
+    search_after: Option<SearchCursor>,
+    group_by: Option<(&'a str, usize)>,
+    pinned_documents: Option<&'a [String]>,
+    hidden_documents: Option<&'a [String]>,
+    vocabulary: Option<QueryVocabulary<'a>>,
+    phrase_slop: u8,
+    distinct: Option<&'a str>,
     words_limit: usize,
     exhaustive_number_hits: bool,
+    early_termination: bool,
+    corrected_query: bool,
     /// TODO: Add semantic ratio or pass it directly to execute_hybrid()
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
     distribution_shift: Option<DistributionShift>,
     embedder_name: Option<String>,
+    vector_targets: Vec<VectorTarget>,
+    candidate_selection: CandidateSelection,
 }
 
 impl<'a> Search<'a> {
     pub fn new(rtxn: &'a heed::RoTxn, index: &'a Index) -> Search<'a> {
         Search {
             query: None,
+            structured_query: None,
             vector: None,
             filter: None,
             offset: 0,
             limit: 20,
             sort_criteria: None,
             searchable_attributes: None,
+            attribute_boosts: None,
             geo_strategy: new::GeoSortStrategy::default(),
             terms_matching_strategy: TermsMatchingStrategy::default(),
             scoring_strategy: Default::default(),
+            ranking_score_threshold: None,
+            time_budget: TimeBudget::default(),
+            search_after: None,
+            group_by: None,
+            pinned_documents: None,
+            hidden_documents: None,
+            vocabulary: None,
+            phrase_slop: 0,
+            distinct: None,
             exhaustive_number_hits: false,
+            early_termination: false,
+            corrected_query: false,
             words_limit: 10,
             rtxn,
             index,
             distribution_shift: None,
             embedder_name: None,
+            vector_targets: Vec::new(),
+            candidate_selection: CandidateSelection::default(),
         }
     }
 
@@ -83,6 +128,12 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Searches the clauses of a structured query instead of a query string.
+    pub fn structured_query(&mut self, structured_query: StructuredQuery<'a>) -> &mut Search<'a> {
+        self.structured_query = Some(structured_query);
+        self
+    }
+
     pub fn vector(&mut self, vector: Vec<f32>) -> &mut Search<'a> {
         self.vector = Some(vector);
         self
@@ -108,6 +159,13 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Scales the weight of the attributes in the `attribute` ranking rule, the attributes
+    /// that are not boosted have a weight of `1.0`.
+    pub fn attribute_boosts(&mut self, boosts: &'a BTreeMap<String, f64>) -> &mut Search<'a> {
+        self.attribute_boosts = Some(boosts);
+        self
+    }
+
     pub fn terms_matching_strategy(&mut self, value: TermsMatchingStrategy) -> &mut Search<'a> {
         self.terms_matching_strategy = value;
         self
@@ -118,6 +176,66 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Drops the documents whose ranking score is below the threshold, between `0.0` and `1.0`.
+    ///
+    /// The candidates are then exhaustively ranked so that they only contain the documents
+    /// above the threshold.
+    pub fn ranking_score_threshold(&mut self, threshold: f64) -> &mut Search<'a> {
+        self.ranking_score_threshold = Some(threshold);
+        self
+    }
+
+    /// Stops ranking the documents once the time budget is exceeded, the results are then
+    /// flagged as degraded.
+    pub fn time_budget(&mut self, time_budget: TimeBudget) -> &mut Search<'a> {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Only returns the documents ranked after the document of the cursor.
+    pub fn search_after(&mut self, cursor: SearchCursor) -> &mut Search<'a> {
+        self.search_after = Some(cursor);
+        self
+    }
+
+    /// Only returns the `limit` best documents of each group of documents sharing
+    /// the same value for the given filterable field.
+    pub fn group_by(&mut self, field: &'a str, limit: usize) -> &mut Search<'a> {
+        self.group_by = Some((field, limit));
+        self
+    }
+
+    /// Ranks the documents with these external ids first, in this order,
+    /// when they match the search.
+    pub fn pinned_documents(&mut self, documents: &'a [String]) -> &mut Search<'a> {
+        self.pinned_documents = Some(documents);
+        self
+    }
+
+    /// Removes the documents with these external ids from the results.
+    pub fn hidden_documents(&mut self, documents: &'a [String]) -> &mut Search<'a> {
+        self.hidden_documents = Some(documents);
+        self
+    }
+
+    /// Uses these synonyms and stop words in addition to, or instead of, the ones of the index.
+    pub fn vocabulary(&mut self, vocabulary: QueryVocabulary<'a>) -> &mut Search<'a> {
+        self.vocabulary = Some(vocabulary);
+        self
+    }
+
+    /// Allows up to `slop` words between the words of the phrases of the query.
+    pub fn phrase_slop(&mut self, slop: u8) -> &mut Search<'a> {
+        self.phrase_slop = slop;
+        self
+    }
+
+    /// Deduplicates the documents on this filterable attribute instead of the distinct attribute of the index.
+    pub fn distinct(&mut self, distinct: &'a str) -> &mut Search<'a> {
+        self.distinct = Some(distinct);
+        self
+    }
+
     pub fn words_limit(&mut self, value: usize) -> &mut Search<'a> {
         self.words_limit = value;
         self
@@ -141,6 +259,22 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Allows the search to stop as soon as the requested documents are found among the first
+    /// buckets of the `words` ranking rule, e.g. the documents matching all the query words,
+    /// the documents matching fewer words being neither ranked nor resolved.
+    ///
+    /// The candidates are then only an estimation and must not be used to compute facets.
+    pub fn early_termination(&mut self, early_termination: bool) -> &mut Search<'a> {
+        self.early_termination = early_termination;
+        self
+    }
+
+    /// Computes the query with the typos of its words corrected, returned in the results.
+    pub fn corrected_query(&mut self, corrected_query: bool) -> &mut Search<'a> {
+        self.corrected_query = corrected_query;
+        self
+    }
+
     pub fn distribution_shift(
         &mut self,
         distribution_shift: Option<DistributionShift>,
@@ -154,16 +288,116 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Searches the vector space of each target instead of a single vector.
+    pub fn vector_targets(&mut self, vector_targets: Vec<VectorTarget>) -> &mut Search<'a> {
+        self.vector_targets = vector_targets;
+        self
+    }
+
+    /// Selects the candidates of the vector search exhaustively or approximately.
+    pub fn candidate_selection(&mut self, value: CandidateSelection) -> &mut Search<'a> {
+        self.candidate_selection = value;
+        self
+    }
+
     pub fn execute_for_candidates(&self, has_vector_search: bool) -> Result<RoaringBitmap> {
         if has_vector_search {
             let ctx = SearchContext::new(self.index, self.rtxn);
-            filtered_universe(&ctx, &self.filter)
+            Ok(filtered_universe(&ctx, &self.filter)? - self.hidden_documents_ids()?)
         } else {
             Ok(self.execute()?.candidates)
         }
     }
 
+    /// Returns the candidates on which the distribution of each of the disjunctive `facets`
+    /// must be computed: the candidates of the search when the conditions on the facet are
+    /// removed from the filter.
+    ///
+    /// The facets the filter doesn't constrain reuse the `candidates` of the search. For the
+    /// other ones, the search is only executed once without filter, then the filter without
+    /// the conditions on each facet is applied to these shared candidates.
+    pub fn execute_for_disjunctive_facets(
+        &self,
+        facets: &[String],
+        has_vector_search: bool,
+        candidates: &RoaringBitmap,
+    ) -> Result<BTreeMap<String, RoaringBitmap>> {
+        let mut facets_candidates = BTreeMap::new();
+        let mut constrained_facets = Vec::new();
+        for facet in facets {
+            match &self.filter {
+                Some(filter) => match filter.without_facet(facet) {
+                    Some(without_facet) if without_facet == *filter => {
+                        facets_candidates.insert(facet.clone(), candidates.clone());
+                    }
+                    without_facet => constrained_facets.push((facet, without_facet)),
+                },
+                None => {
+                    facets_candidates.insert(facet.clone(), candidates.clone());
+                }
+            }
+        }
+
+        if !constrained_facets.is_empty() {
+            let universe = self.execute_without_filter_for_candidates(has_vector_search)?;
+            for (facet, filter) in constrained_facets {
+                let facet_candidates = match filter {
+                    Some(filter) => filter.evaluate(self.rtxn, self.index)? & &universe,
+                    None => universe.clone(),
+                };
+                facets_candidates.insert(facet.clone(), facet_candidates);
+            }
+        }
+
+        Ok(facets_candidates)
+    }
+
+    fn execute_without_filter_for_candidates(
+        &self,
+        has_vector_search: bool,
+    ) -> Result<RoaringBitmap> {
+        let search = Search {
+            query: self.query.clone(),
+            structured_query: self.structured_query.clone(),
+            vector: self.vector.clone(),
+            filter: None,
+            offset: self.offset,
+            limit: self.limit,
+            sort_criteria: self.sort_criteria.clone(),
+            searchable_attributes: self.searchable_attributes,
+            attribute_boosts: self.attribute_boosts,
+            geo_strategy: self.geo_strategy,
+            terms_matching_strategy: self.terms_matching_strategy,
+            scoring_strategy: ScoringStrategy::Skip,
+            ranking_score_threshold: self.ranking_score_threshold,
+            time_budget: self.time_budget,
+            search_after: None,
+            group_by: None,
+            pinned_documents: None,
+            hidden_documents: self.hidden_documents,
+            vocabulary: self.vocabulary,
+            phrase_slop: self.phrase_slop,
+            distinct: self.distinct,
+            words_limit: self.words_limit,
+            exhaustive_number_hits: self.exhaustive_number_hits,
+            // the candidates are the point of this search
+            early_termination: false,
+            corrected_query: false,
+            rtxn: self.rtxn,
+            index: self.index,
+            distribution_shift: self.distribution_shift,
+            embedder_name: self.embedder_name.clone(),
+            vector_targets: Vec::new(),
+            candidate_selection: self.candidate_selection,
+        };
+        search.execute_for_candidates(has_vector_search)
+    }
+
     pub fn execute(&self) -> Result<SearchResult> {
+        if !self.vector_targets.is_empty() {
+            return self.execute_vector_targets();
+        }
+
         let embedder_name;
         let embedder_name = match &self.embedder_name {
             Some(embedder_name) => embedder_name,
@@ -179,13 +413,60 @@ impl<'a> Search<'a> {
             ctx.searchable_attributes(searchable_attributes)?;
         }
 
-        let universe = filtered_universe(&ctx, &self.filter)?;
-        let PartialSearchResult { located_query_terms, candidates, documents_ids, document_scores } =
-            match self.vector.as_ref() {
-                Some(vector) => execute_vector_search(
+        if let Some(attribute_boosts) = self.attribute_boosts {
+            ctx.attribute_boosts(attribute_boosts)?;
+        }
+
+        if let Some(vocabulary) = self.vocabulary {
+            ctx.vocabulary(vocabulary)?;
+        }
+
+        ctx.phrase_slop = self.phrase_slop;
+
+        if let Some(distinct) = self.distinct {
+            ctx.distinct(distinct)?;
+        }
+
+        let group_by = match self.group_by {
+            Some((field, limit)) => GroupBy::new(self.index, self.rtxn, field, limit)?,
+            None => None,
+        };
+        let group_field_id = group_by.as_ref().map(GroupBy::field_id);
+
+        let mut universe = filtered_universe(&ctx, &self.filter)? - self.hidden_documents_ids()?;
+        let mut query_rules =
+            AppliedQueryRules::new(self.index, self.rtxn, self.query.as_deref(), &self.filter)?;
+        if let Some(pinned_documents) = self.pinned_documents {
+            query_rules.pin_documents(self.index, self.rtxn, pinned_documents)?;
+        }
+        if let Some(candidates) = query_rules.candidates() {
+            universe &= candidates;
+        }
+        ctx.query_rules = Some(query_rules);
+
+        let PartialSearchResult {
+            located_query_terms,
+            candidates,
+            documents_ids,
+            document_scores,
+            degraded,
+        } = match self.vector.as_ref() {
+            Some(vector) => {
+                // the negative words and phrases of the query also exclude documents here
+                let negative_queries = match (&self.structured_query, self.query.as_deref()) {
+                    (Some(structured_query), _) => structured_query.negative_texts().collect(),
+                    (None, Some(query)) => split_negative_query(query).1,
+                    (None, None) => Vec::new(),
+                };
+                universe -= negative_query_docids(&mut ctx, &negative_queries)?;
+                execute_vector_search(
                     &mut ctx,
                     vector,
                     self.scoring_strategy,
+                    self.ranking_score_threshold,
+                    self.time_budget,
+                    self.search_after.as_ref(),
+                    group_by,
                     universe,
                     &self.sort_criteria,
                     self.geo_strategy,
@@ -193,23 +474,54 @@ impl<'a> Search<'a> {
                     self.limit,
                     self.distribution_shift,
                     embedder_name,
-                )?,
-                None => execute_search(
-                    &mut ctx,
-                    self.query.as_deref(),
-                    self.terms_matching_strategy,
-                    self.scoring_strategy,
-                    self.exhaustive_number_hits,
-                    universe,
-                    &self.sort_criteria,
-                    self.geo_strategy,
-                    self.offset,
-                    self.limit,
-                    Some(self.words_limit),
-                    &mut DefaultSearchLogger,
-                    &mut DefaultSearchLogger,
-                )?,
-            };
+                    self.candidate_selection,
+                )?
+            }
+            None => execute_search(
+                &mut ctx,
+                self.query.as_deref(),
+                self.structured_query.as_ref(),
+                self.terms_matching_strategy,
+                self.scoring_strategy,
+                self.ranking_score_threshold,
+                self.time_budget,
+                self.search_after.as_ref(),
+                group_by,
+                self.exhaustive_number_hits,
+                self.early_termination,
+                universe,
+                &self.sort_criteria,
+                self.geo_strategy,
+                self.offset,
+                self.limit,
+                Some(self.words_limit),
+                &mut DefaultSearchLogger,
+                &mut DefaultSearchLogger,
+            )?,
+        };
+
+        let group_counts = match group_field_id {
+            Some(field_id) => {
+                group_counts(self.index, self.rtxn, field_id, &documents_ids, &candidates)?
+            }
+            None => BTreeMap::new(),
+        };
+
+        let applied_query_rules = match ctx.query_rules.take() {
+            Some(query_rules) => documents_ids
+                .iter()
+                .map(|&docid| (docid, query_rules.rules_of(docid)))
+                .filter(|(_, rules)| !rules.is_empty())
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        let corrected_query = match located_query_terms.as_deref() {
+            Some(located_query_terms) if self.corrected_query => {
+                corrected_query(&mut ctx, located_query_terms)?
+            }
+            _ => None,
+        };
 
         // consume context and located_query_terms to build MatchingWords.
         let matching_words = match located_query_terms {
@@ -217,7 +529,28 @@ impl<'a> Search<'a> {
             None => MatchingWords::default(),
         };
 
-        Ok(SearchResult { matching_words, candidates, document_scores, documents_ids })
+        Ok(SearchResult {
+            matching_words,
+            candidates,
+            document_scores,
+            documents_ids,
+            degraded,
+            group_counts,
+            applied_query_rules,
+            corrected_query,
+        })
+    }
+
+    /// Returns the internal ids of the hidden documents, the unknown documents being ignored.
+    fn hidden_documents_ids(&self) -> Result<RoaringBitmap> {
+        let external_documents_ids = self.index.external_documents_ids();
+        let mut docids = RoaringBitmap::new();
+        for external_id in self.hidden_documents.unwrap_or_default() {
+            if let Some(docid) = external_documents_ids.get(self.rtxn, external_id)? {
+                docids.insert(docid);
+            }
+        }
+        Ok(docids)
     }
 }
 
@@ -225,36 +558,72 @@ impl fmt::Debug for Search<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let Search {
             query,
+            structured_query,
             vector: _,
             filter,
             offset,
             limit,
             sort_criteria,
             searchable_attributes,
+            attribute_boosts,
             geo_strategy: _,
             terms_matching_strategy,
             scoring_strategy,
+            ranking_score_threshold,
+            time_budget,
+            search_after,
+            group_by,
+            pinned_documents,
+            hidden_documents,
+            vocabulary,
+            phrase_slop,
+            distinct,
             words_limit,
             exhaustive_number_hits,
+            early_termination,
+            corrected_query,
             rtxn: _,
             index: _,
             distribution_shift,
             embedder_name,
+            vector_targets,
+            candidate_selection,
         } = self;
         f.debug_struct("Search")
             .field("query", query)
+            .field("structured_query", structured_query)
             .field("vector", &"[...]")
             .field("filter", filter)
             .field("offset", offset)
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
             .field("searchable_attributes", searchable_attributes)
+            .field("attribute_boosts", attribute_boosts)
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("scoring_strategy", scoring_strategy)
+            .field("ranking_score_threshold", ranking_score_threshold)
+            .field("time_budget", time_budget)
+            .field("search_after", search_after)
+            .field("group_by", group_by)
+            .field("pinned_documents", pinned_documents)
+            .field("hidden_documents", hidden_documents)
+            .field("vocabulary", vocabulary)
+            .field("phrase_slop", phrase_slop)
+            .field("distinct", distinct)
             .field("exhaustive_number_hits", exhaustive_number_hits)
+            .field("early_termination", early_termination)
+            .field("corrected_query", corrected_query)
             .field("words_limit", words_limit)
             .field("distribution_shift", distribution_shift)
             .field("embedder_name", embedder_name)
+            .field(
+                "vector_targets",
+                &vector_targets
+                    .iter()
+                    .map(|target| (&target.embedder_name, target.weight))
+                    .collect::<Vec<_>>(),
+            )
+            .field("candidate_selection", candidate_selection)
             .finish()
     }
 }
@@ -265,6 +634,43 @@ pub struct SearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// Whether the time budget was exceeded before the documents were entirely ranked.
+    pub degraded: bool,
+    /// The number of candidates in the group of each returned document, by group value.
+    pub group_counts: BTreeMap<String, u64>,
+    /// The ids of the query rules applied to each returned document.
+    pub applied_query_rules: BTreeMap<DocumentId, Vec<String>>,
+    /// The query where the words unknown to the index are replaced by their typo correction.
+    pub corrected_query: Option<String>,
+}
+
+/// The time a search is allowed to spend ranking the documents.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeBudget {
+    started_at: Instant,
+    budget: Duration,
+}
+
+impl TimeBudget {
+    /// Starts a time budget of the given duration from now.
+    pub fn new(budget: Duration) -> Self {
+        Self { started_at: Instant::now(), budget }
+    }
+
+    /// A time budget that is never exceeded.
+    pub fn max() -> Self {
+        Self::new(Duration::MAX)
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.started_at.elapsed() >= self.budget
+    }
+}
+
+impl Default for TimeBudget {
+    fn default() -> Self {
+        Self::max()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -273,6 +679,8 @@ pub enum TermsMatchingStrategy {
     Last,
     // all words are mandatory
     All,
+    // remove most frequent word first
+    Frequency,
 }
 
 impl Default for TermsMatchingStrategy {
@@ -1,19 +1,31 @@
+use std::cmp::Ordering;
+use std::iter;
+
+use rayon::prelude::*;
 use roaring::RoaringBitmap;
 
-use super::logger::SearchLogger;
-use super::ranking_rules::{BoxRankingRule, RankingRuleQueryTrait};
+use super::cursor::SearchCursor;
+use super::group_by::GroupBy;
+use super::logger::{DefaultSearchLogger, SearchLogger};
+use super::ranking_rules::{BoxRankingRule, PlaceholderQuery, RankingRuleQueryTrait};
 use super::SearchContext;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::{apply_distinct_rule, distinct_single_docid, DistinctOutput};
-use crate::Result;
+use crate::{Result, TimeBudget, UserError};
 
 pub struct BucketSortOutput {
     pub docids: Vec<u32>,
     pub scores: Vec<Vec<ScoreDetails>>,
     pub all_candidates: RoaringBitmap,
+    /// Whether the time budget was exceeded before the documents were entirely sorted.
+    pub degraded: bool,
 }
 
 // TODO: would probably be good to regroup some of these inside of a struct?
+/// Sorts the candidates by descending the ranking rules bucket by bucket.
+///
+/// The buckets are ranked sequentially: the ranking rules keep their state between
+/// buckets and share the search context, see [`parallel_bucket_sort`] otherwise.
 #[allow(clippy::too_many_arguments)]
 pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     ctx: &mut SearchContext<'ctx>,
@@ -23,30 +35,45 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     from: usize,
     length: usize,
     scoring_strategy: ScoringStrategy,
+    ranking_score_threshold: Option<f64>,
+    time_budget: TimeBudget,
+    search_after: Option<&SearchCursor>,
+    mut group_by: Option<GroupBy>,
     logger: &mut dyn SearchLogger<Q>,
 ) -> Result<BucketSortOutput> {
+    // the scores of the buckets are needed to compare them with the threshold or the cursor
+    let scoring_strategy = if ranking_score_threshold.is_some() || search_after.is_some() {
+        ScoringStrategy::Detailed
+    } else {
+        scoring_strategy
+    };
+
     logger.initial_query(query);
     logger.ranking_rules(&ranking_rules);
     logger.initial_universe(universe);
 
-    let distinct_fid = if let Some(field) = ctx.index.distinct_field(ctx.txn)? {
-        ctx.index.fields_ids_map(ctx.txn)?.id(field)
-    } else {
-        None
-    };
+    let distinct_fid = ctx.distinct_fid()?;
 
-    if universe.len() < from as u64 {
+    // No document is requested, e.g. to only count the candidates, there is nothing to sort.
+    if (universe.len() < from as u64 || length == 0) && ranking_score_threshold.is_none() {
         return Ok(BucketSortOutput {
             docids: vec![],
             scores: vec![],
             all_candidates: universe.clone(),
+            degraded: false,
         });
     }
     if ranking_rules.is_empty() {
+        // the documents are ranked by id, the ones up to the cursor were already returned
+        let mut ranked_universe = universe.clone();
+        if let Some(cursor) = search_after {
+            ranked_universe.remove_range(..=cursor.docid);
+        }
+
         if let Some(distinct_fid) = distinct_fid {
             let mut excluded = RoaringBitmap::new();
             let mut results = vec![];
-            for docid in universe.iter() {
+            for docid in ranked_universe.iter() {
                 if results.len() >= from + length {
                     break;
                 }
@@ -55,6 +82,11 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 }
 
                 distinct_single_docid(ctx.index, ctx.txn, distinct_fid, docid, &mut excluded)?;
+                if let Some(group_by) = &mut group_by {
+                    if !group_by.keep(ctx.index, ctx.txn, docid)? {
+                        continue;
+                    }
+                }
                 results.push(docid);
             }
 
@@ -73,13 +105,29 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 scores: vec![Default::default(); results.len()],
                 docids: results,
                 all_candidates,
+                degraded: false,
             });
         } else {
-            let docids: Vec<u32> = universe.iter().skip(from).take(length).collect();
+            let docids: Vec<u32> = match &mut group_by {
+                Some(group_by) => {
+                    let mut docids = Vec::new();
+                    for docid in ranked_universe.iter() {
+                        if docids.len() >= from + length {
+                            break;
+                        }
+                        if group_by.keep(ctx.index, ctx.txn, docid)? {
+                            docids.push(docid);
+                        }
+                    }
+                    docids.split_off(from.min(docids.len()))
+                }
+                None => ranked_universe.iter().skip(from).take(length).collect(),
+            };
             return Ok(BucketSortOutput {
                 scores: vec![Default::default(); docids.len()],
                 docids,
                 all_candidates: universe.clone(),
+                degraded: false,
             });
         };
     }
@@ -130,6 +178,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     let mut valid_docids = vec![];
     let mut valid_scores = vec![];
     let mut cur_offset = 0usize;
+    let mut degraded = false;
 
     macro_rules! maybe_add_to_results {
         ($candidates:expr) => {
@@ -146,13 +195,29 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 cur_ranking_rule_index,
                 &mut cur_offset,
                 distinct_fid,
+                &mut group_by,
                 &ranking_rule_scores,
                 $candidates,
             )?;
         };
     }
 
-    while valid_docids.len() < length {
+    // With a ranking score threshold, the iteration goes on once the results are found
+    // to remove all the documents below the threshold from the candidates.
+    while valid_docids.len() < length || ranking_score_threshold.is_some() {
+        if time_budget.exceeded() {
+            // Stop descending the ranking rules: the remaining candidates of each ranking rule,
+            // starting from the deepest one, are returned in the order of their ids with the
+            // scores of the ranking rules that were already applied to them.
+            loop {
+                let bucket = std::mem::take(&mut ranking_rule_universes[cur_ranking_rule_index]);
+                maybe_add_to_results!(bucket);
+                back!();
+            }
+            degraded = true;
+            break;
+        }
+
         // The universe for this bucket is zero, so we don't need to sort
         // anything, just go back to the parent ranking rule.
         if ranking_rule_universes[cur_ranking_rule_index].is_empty()
@@ -165,7 +230,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
             continue;
         }
 
-        let Some(next_bucket) = ranking_rules[cur_ranking_rule_index].next_bucket(
+        let Some(mut next_bucket) = ranking_rules[cur_ranking_rule_index].next_bucket(
             ctx,
             logger,
             &ranking_rule_universes[cur_ranking_rule_index],
@@ -189,9 +254,42 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         );
         ranking_rule_universes[cur_ranking_rule_index] -= &next_bucket.candidates;
 
+        // The score of a bucket is an upper bound of the scores of its documents,
+        // the following ranking rules can only lower it.
+        let is_below_threshold = ranking_score_threshold.map_or(false, |threshold| {
+            ScoreDetails::global_score(ranking_rule_scores.iter()) < threshold
+        });
+
+        if is_below_threshold {
+            // the next buckets of the ranking rule have an even lower score
+            all_candidates -= &next_bucket.candidates;
+            all_candidates -= &ranking_rule_universes[cur_ranking_rule_index];
+            ranking_rule_universes[cur_ranking_rule_index].clear();
+            ranking_rule_scores.pop();
+            continue;
+        }
+
+        // Only the buckets ranked after the cursor are returned, the bucket of the cursor
+        // is refined by the next ranking rules until its documents are ranked by id.
+        if let Some(cursor) = search_after {
+            match cursor.compare(&ranking_rule_scores) {
+                Some(Ordering::Greater) => {
+                    ranking_rule_scores.pop();
+                    continue;
+                }
+                Some(Ordering::Equal) if cur_ranking_rule_index == ranking_rules_len - 1 => {
+                    next_bucket.candidates.remove_range(..=cursor.docid);
+                }
+                Some(Ordering::Equal | Ordering::Less) => (),
+                None => return Err(UserError::InvalidSearchCursor.into()),
+            }
+        }
+
         if cur_ranking_rule_index == ranking_rules_len - 1
             || (scoring_strategy == ScoringStrategy::Skip && next_bucket.candidates.len() <= 1)
-            || cur_offset + (next_bucket.candidates.len() as usize) < from
+            || (ranking_score_threshold.is_none()
+                && search_after.is_none()
+                && cur_offset + (next_bucket.candidates.len() as usize) < from)
         {
             maybe_add_to_results!(next_bucket.candidates);
             ranking_rule_scores.pop();
@@ -214,7 +312,156 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         )?;
     }
 
-    Ok(BucketSortOutput { docids: valid_docids, scores: valid_scores, all_candidates })
+    Ok(BucketSortOutput { docids: valid_docids, scores: valid_scores, all_candidates, degraded })
+}
+
+/// The number of candidates from which the buckets of the first ranking rule
+/// of a placeholder search are ranked in parallel.
+pub const PARALLEL_BUCKET_SORT_THRESHOLD: u64 = 100_000;
+
+/// Sorts the candidates of a placeholder search like [`bucket_sort`], the buckets of the
+/// first ranking rule being ranked by the following ranking rules in parallel.
+///
+/// The first ranking rule is descended on the calling thread, up to the last bucket containing
+/// requested documents. Each of these buckets is then ranked by a rayon task with its own read
+/// transaction and search context, and the ranking rules built by `new_ranking_rules` without
+/// the first one. The ranked buckets are concatenated in the order of the first ranking rule,
+/// so the results don't depend on the order in which the tasks are run.
+///
+/// The documents of a bucket must not depend on the previous buckets: the distinct attribute,
+/// the grouping, the ranking score threshold and the search cursor are not supported.
+#[allow(clippy::too_many_arguments)]
+pub fn parallel_bucket_sort<'ctx, F>(
+    ctx: &mut SearchContext<'ctx>,
+    mut ranking_rules: Vec<BoxRankingRule<'ctx, PlaceholderQuery>>,
+    universe: &RoaringBitmap,
+    from: usize,
+    length: usize,
+    scoring_strategy: ScoringStrategy,
+    time_budget: TimeBudget,
+    new_ranking_rules: F,
+    logger: &mut dyn SearchLogger<PlaceholderQuery>,
+) -> Result<BucketSortOutput>
+where
+    F: for<'t> Fn(&SearchContext<'t>) -> Result<Vec<BoxRankingRule<'t, PlaceholderQuery>>> + Sync,
+{
+    if universe.len() < from as u64 || length == 0 || ranking_rules.len() < 2 {
+        return bucket_sort(
+            ctx,
+            ranking_rules,
+            &PlaceholderQuery,
+            universe,
+            from,
+            length,
+            scoring_strategy,
+            None,
+            time_budget,
+            None,
+            None,
+            logger,
+        );
+    }
+
+    logger.initial_query(&PlaceholderQuery);
+    logger.ranking_rules(&ranking_rules);
+    logger.initial_universe(universe);
+
+    let index = ctx.index;
+    let first_rule = &mut ranking_rules[0];
+    logger.start_iteration_ranking_rule(0, first_rule.as_ref(), &PlaceholderQuery, universe);
+    first_rule.start_iteration(ctx, logger, universe, &PlaceholderQuery)?;
+
+    // The buckets containing requested documents with their position in the results,
+    // and the score of the first ranking rule unless the time budget was exceeded.
+    let mut buckets = Vec::new();
+    let mut remaining = universe.clone();
+    let mut cur_offset = 0;
+    let mut degraded = false;
+    while cur_offset < from + length && !remaining.is_empty() {
+        if time_budget.exceeded() {
+            // the remaining candidates are returned in the order of their ids
+            buckets.push((cur_offset, std::mem::take(&mut remaining), None));
+            degraded = true;
+            break;
+        }
+
+        let Some(bucket) = first_rule.next_bucket(ctx, logger, &remaining)? else { break };
+        logger.next_bucket_ranking_rule(0, first_rule.as_ref(), &remaining, &bucket.candidates);
+        remaining -= &bucket.candidates;
+
+        let bucket_len = bucket.candidates.len() as usize;
+        if cur_offset + bucket_len > from {
+            buckets.push((cur_offset, bucket.candidates, Some(bucket.score)));
+        } else {
+            logger.skip_bucket_ranking_rule(0, first_rule.as_ref(), &bucket.candidates);
+        }
+        cur_offset += bucket_len;
+    }
+
+    logger.end_iteration_ranking_rule(0, first_rule.as_ref(), &remaining);
+    first_rule.end_iteration(ctx, logger);
+
+    let ranked_buckets = buckets
+        .into_par_iter()
+        .map(|(offset, candidates, score)| -> Result<_> {
+            // only the requested documents of the bucket are ranked
+            let bucket_from = from.saturating_sub(offset);
+            let bucket_length =
+                (from + length).min(offset + candidates.len() as usize) - offset.max(from);
+
+            let score = match score {
+                Some(score) => score,
+                None => {
+                    let docids: Vec<_> =
+                        candidates.iter().skip(bucket_from).take(bucket_length).collect();
+                    return Ok((vec![Vec::new(); docids.len()], docids, false));
+                }
+            };
+
+            if scoring_strategy == ScoringStrategy::Skip && candidates.len() <= 1 {
+                let docids: Vec<_> =
+                    candidates.iter().skip(bucket_from).take(bucket_length).collect();
+                return Ok((vec![vec![score]; docids.len()], docids, false));
+            }
+
+            let rtxn = index.read_txn()?;
+            let mut ctx = SearchContext::new(index, &rtxn);
+            let mut ranking_rules = new_ranking_rules(&ctx)?;
+            ranking_rules.remove(0);
+
+            let BucketSortOutput { docids, scores, all_candidates: _, degraded } = bucket_sort(
+                &mut ctx,
+                ranking_rules,
+                &PlaceholderQuery,
+                &candidates,
+                bucket_from,
+                bucket_length,
+                scoring_strategy,
+                None,
+                time_budget,
+                None,
+                None,
+                &mut DefaultSearchLogger,
+            )?;
+
+            let scores = scores
+                .into_iter()
+                .map(|scores| iter::once(score.clone()).chain(scores).collect())
+                .collect();
+            Ok((scores, docids, degraded))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut docids = Vec::new();
+    let mut scores = Vec::new();
+    for (bucket_scores, bucket_docids, bucket_degraded) in ranked_buckets {
+        scores.extend(bucket_scores);
+        docids.extend(bucket_docids);
+        degraded |= bucket_degraded;
+    }
+    logger.add_to_results(&docids);
+
+    Ok(BucketSortOutput { docids, scores, all_candidates: universe.clone(), degraded })
 }
 
 /// Add the candidates to the results. Take `distinct`, `from`, `length`, and `cur_offset`
@@ -238,6 +485,7 @@ fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
     cur_offset: &mut usize,
 
     distinct_fid: Option<u16>,
+    group_by: &mut Option<GroupBy>,
     ranking_rule_scores: &[ScoreDetails],
     candidates: RoaringBitmap,
 ) -> Result<()> {
@@ -255,6 +503,12 @@ fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
     };
     *all_candidates |= &candidates;
 
+    // Then only keep the top documents of each group, the other documents remain candidates
+    let candidates = match group_by {
+        Some(group_by) => group_by.apply(ctx, &candidates)?,
+        None => candidates,
+    };
+
     // if the candidates are empty, there is nothing to do;
     if candidates.is_empty() {
         return Ok(());
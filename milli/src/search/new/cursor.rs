@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::score_details::{self, ScoreDetails};
+use crate::DocumentId;
+
+/// The position of a document in the results of a search, used to return the documents
+/// ranked after it.
+///
+/// The position is made of the coordinates of the buckets of the document in each ranking rule,
+/// along with its document id which breaks the ties between the documents of the same buckets.
+/// Contrary to an offset, it doesn't move when documents are added or removed from the index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchCursor {
+    pub coordinates: Vec<BucketCoordinate>,
+    pub docid: DocumentId,
+}
+
+/// The coordinate of a bucket in a ranking rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BucketCoordinate {
+    /// The rank of the bucket, for the ranking rules ranking the documents on a scale.
+    Rank(u32),
+    /// The value of the sorted field or expression.
+    Value(serde_json::Value),
+    /// The geo point of the documents in a geo sort.
+    Point(Option<[f64; 2]>),
+    /// The similarity of the documents with the target vector.
+    Similarity(Option<f32>),
+}
+
+impl SearchCursor {
+    /// The cursor positioned on a document with the given scores.
+    pub fn new(scores: &[ScoreDetails], docid: DocumentId) -> Self {
+        let coordinates = scores.iter().map(BucketCoordinate::from_score).collect();
+        SearchCursor { coordinates, docid }
+    }
+
+    /// Compares the buckets with the given scores to the buckets of the cursor,
+    /// `Ordering::Greater` meaning that the buckets are ranked before the cursor.
+    ///
+    /// Returns `None` if the scores don't come from the same ranking rules as the cursor.
+    pub fn compare(&self, scores: &[ScoreDetails]) -> Option<Ordering> {
+        if scores.len() > self.coordinates.len() {
+            return None;
+        }
+        for (coordinate, score) in self.coordinates.iter().zip(scores) {
+            match coordinate.compare(score)? {
+                Ordering::Equal => continue,
+                ordering => return Some(ordering),
+            }
+        }
+        Some(Ordering::Equal)
+    }
+}
+
+impl BucketCoordinate {
+    pub fn from_score(score: &ScoreDetails) -> Self {
+        match score {
+            ScoreDetails::Sort(sort) => BucketCoordinate::Value(sort.value.clone()),
+            ScoreDetails::GeoSort(geo_sort) => BucketCoordinate::Point(geo_sort.value),
+            ScoreDetails::Vector(vector) => BucketCoordinate::Similarity(
+                vector.value_similarity.as_ref().map(|(_, similarity)| *similarity),
+            ),
+            score => BucketCoordinate::Rank(score.rank().map_or(0, |rank| rank.rank)),
+        }
+    }
+
+    fn compare(&self, score: &ScoreDetails) -> Option<Ordering> {
+        match (self, score) {
+            (BucketCoordinate::Value(value), ScoreDetails::Sort(sort)) => {
+                let cursor = score_details::Sort { value: value.clone(), ..sort.clone() };
+                sort.partial_cmp(&cursor)
+            }
+            (BucketCoordinate::Point(value), ScoreDetails::GeoSort(geo_sort)) => {
+                let cursor = score_details::GeoSort { value: *value, ..*geo_sort };
+                geo_sort.partial_cmp(&cursor)
+            }
+            (BucketCoordinate::Similarity(similarity), ScoreDetails::Vector(vector)) => {
+                let value_similarity =
+                    vector.value_similarity.as_ref().map(|(_, similarity)| *similarity);
+                match (value_similarity, similarity) {
+                    (None, None) => Some(Ordering::Equal),
+                    (None, Some(_)) => Some(Ordering::Less),
+                    (Some(_), None) => Some(Ordering::Greater),
+                    (Some(left), Some(right)) => left.partial_cmp(right),
+                }
+            }
+            (BucketCoordinate::Rank(rank), score) => Some(score.rank()?.rank.cmp(rank)),
+            _ => None,
+        }
+    }
+}
@@ -70,7 +70,7 @@ pub fn distinct_single_docid(
 }
 
 /// Return all the docids containing the given value in the given field
-fn facet_value_docids(
+pub fn facet_value_docids(
     database: Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
     txn: &RoTxn,
     field_id: u16,
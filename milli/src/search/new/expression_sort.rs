@@ -0,0 +1,158 @@
+use std::str::FromStr;
+
+use roaring::RoaringBitmap;
+
+use super::geo_sort::facet_number_values;
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::ranking_expression::RankingExpression;
+use crate::score_details::{self, ScoreDetails};
+use crate::{CriterionError, FieldId, Index, Result};
+
+/// A ranking rule sorting the documents by the value of an arithmetic expression
+/// over their numeric fields, such as `desc(log(popularity) * freshness_decay(published_at, 30d))`.
+///
+/// The expression is evaluated for each candidate document when the iteration starts,
+/// with the first number value of each field. The documents for which the expression
+/// has no value, e.g. because a field is missing, are returned in the last bucket.
+pub struct ExpressionSort<Query> {
+    source: String,
+    expression: RankingExpression,
+    /// The fields of the expression, with their id if they exist in the index.
+    fields: Vec<(String, Option<FieldId>)>,
+    is_ascending: bool,
+    must_redact: bool,
+    original_query: Option<Query>,
+    /// The candidates grouped by value, the best bucket being the last one.
+    buckets: Vec<(f64, RoaringBitmap)>,
+}
+
+impl<Query> ExpressionSort<Query> {
+    pub fn new(
+        index: &Index,
+        rtxn: &heed::RoTxn,
+        source: String,
+        is_ascending: bool,
+    ) -> Result<Self> {
+        let expression = RankingExpression::from_str(&source)
+            .map_err(|error| CriterionError::InvalidExpression { name: source.clone(), error })?;
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let fields: Vec<_> = expression
+            .fields()
+            .into_iter()
+            .map(|name| (name.to_string(), fields_ids_map.id(name)))
+            .collect();
+
+        let must_redact = match index.displayed_fields(rtxn)? {
+            Some(displayed_fields) => {
+                fields.iter().any(|(name, _)| !displayed_fields.iter().any(|&field| field == name))
+            }
+            None => false,
+        };
+
+        Ok(Self {
+            source,
+            expression,
+            fields,
+            is_ascending,
+            must_redact,
+            original_query: None,
+            buckets: Vec::new(),
+        })
+    }
+
+    fn score(&self, value: serde_json::Value) -> ScoreDetails {
+        ScoreDetails::Sort(score_details::Sort {
+            field_name: self.source.clone(),
+            ascending: self.is_ascending,
+            redacted: self.must_redact,
+            value,
+        })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for ExpressionSort<Query> {
+    fn id(&self) -> String {
+        let Self { source, is_ascending, .. } = self;
+        format!("{}({source})", if *is_ascending { "asc" } else { "desc" })
+    }
+
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp() as f64;
+
+        let mut values = Vec::new();
+        let mut field_values = Vec::with_capacity(self.fields.len());
+        for docid in parent_candidates {
+            field_values.clear();
+            for (name, field_id) in &self.fields {
+                let Some(field_id) = *field_id else { continue };
+                if let Some(result) =
+                    facet_number_values(docid, field_id, ctx.index, ctx.txn)?.next()
+                {
+                    let ((_, _, value), ()) = result?;
+                    field_values.push((name.as_str(), value));
+                }
+            }
+            let field_value = |name: &str| {
+                field_values.iter().find(|(field, _)| *field == name).map(|(_, value)| *value)
+            };
+            if let Some(value) = self.expression.evaluate(&field_value, now) {
+                values.push((value, docid));
+            }
+        }
+
+        // the best values are sorted last to be popped first
+        if self.is_ascending {
+            values.sort_unstable_by(|(left, _), (right, _)| right.total_cmp(left));
+        } else {
+            values.sort_unstable_by(|(left, _), (right, _)| left.total_cmp(right));
+        }
+
+        let mut buckets: Vec<(f64, RoaringBitmap)> = Vec::new();
+        for (value, docid) in values {
+            match buckets.last_mut() {
+                Some((last, docids)) if *last == value => {
+                    docids.insert(docid);
+                }
+                _ => buckets.push((value, RoaringBitmap::from_iter([docid]))),
+            }
+        }
+
+        self.original_query = Some(parent_query.clone());
+        self.buckets = buckets;
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        let (candidates, value) = match self.buckets.pop() {
+            Some((value, docids)) => {
+                (docids & universe, serde_json::Number::from_f64(value).into())
+            }
+            // the documents without a value for the expression come last
+            None => (universe.clone(), serde_json::Value::Null),
+        };
+
+        Ok(Some(RankingRuleOutput { query, candidates, score: self.score(value) }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.buckets.clear();
+    }
+}
@@ -24,7 +24,7 @@ fn facet_values_prefix_key(distinct: u16, id: u32) -> [u8; FID_SIZE + DOCID_SIZE
 }
 
 /// Return an iterator over each number value in the given field of the given document.
-fn facet_number_values<'a>(
+pub fn facet_number_values<'a>(
     docid: u32,
     field_id: u16,
     index: &Index,
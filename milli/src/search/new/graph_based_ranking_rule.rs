@@ -148,10 +148,17 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
                 // where only the phrase is matching, and none of the non-phrase words.
                 // With the `1` that `next_max_cost` is initialized with, this gets counted twice.
                 .saturating_sub(1) as u64;
-            match terms_matching_strategy {
+            let removal_order = match terms_matching_strategy {
                 TermsMatchingStrategy::Last => {
-                    let removal_order =
-                        query_graph.removal_order_for_terms_matching_strategy_last(ctx);
+                    Some(query_graph.removal_order_for_terms_matching_strategy_last(ctx))
+                }
+                TermsMatchingStrategy::Frequency => {
+                    Some(query_graph.removal_order_for_terms_matching_strategy_frequency(ctx)?)
+                }
+                TermsMatchingStrategy::All => None,
+            };
+            match removal_order {
+                Some(removal_order) => {
                     let mut forbidden_nodes =
                         SmallBitmap::for_interned_values_in(&query_graph.nodes);
                     let mut costs = query_graph.nodes.map(|_| None);
@@ -164,7 +171,7 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
                     }
                     costs
                 }
-                TermsMatchingStrategy::All => query_graph.nodes.map(|_| None),
+                None => query_graph.nodes.map(|_| None),
             }
         } else {
             query_graph.nodes.map(|_| None)
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use heed::RoTxn;
+use roaring::RoaringBitmap;
+
+use super::distinct::{facet_string_values, facet_value_docids};
+use super::geo_sort::facet_number_values;
+use super::SearchContext;
+use crate::heed_codec::facet::FacetGroupKey;
+use crate::{FieldId, Index, Result, UserError};
+
+/// Keeps the top documents of each group of documents sharing the same value for a field.
+///
+/// A document belongs to the group of its first value for the field, the documents without
+/// a value are each in their own group.
+pub struct GroupBy {
+    field_id: FieldId,
+    limit: usize,
+    /// The number of documents kept in each group, by the facet key of the group.
+    kept: HashMap<Group, usize>,
+}
+
+/// The normalized string value of a group, or the bits of its number value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Group {
+    String(Vec<u8>),
+    Number(u64),
+}
+
+impl GroupBy {
+    /// Returns `None` if no document has a value for the field.
+    pub fn new(index: &Index, txn: &RoTxn, field: &str, limit: usize) -> Result<Option<Self>> {
+        let filterable_fields = index.filterable_fields(txn)?;
+        if !crate::is_faceted(field, &filterable_fields) {
+            let (valid_fields, hidden_fields) =
+                index.remove_hidden_fields(txn, filterable_fields)?;
+            return Err(UserError::InvalidGroupByAttribute {
+                field: field.to_string(),
+                valid_fields,
+                hidden_fields,
+            }
+            .into());
+        }
+
+        Ok(index.fields_ids_map(txn)?.id(field).map(|field_id| GroupBy {
+            field_id,
+            limit,
+            kept: HashMap::new(),
+        }))
+    }
+
+    pub fn field_id(&self) -> FieldId {
+        self.field_id
+    }
+
+    /// Returns whether the document is one of the top documents of its group.
+    ///
+    /// The documents must be given by decreasing rank.
+    pub fn keep(&mut self, index: &Index, txn: &RoTxn, docid: u32) -> Result<bool> {
+        let Some((group, _)) = group_of(index, txn, self.field_id, docid)? else {
+            return Ok(true);
+        };
+        let kept = self.kept.entry(group).or_default();
+        if *kept < self.limit {
+            *kept += 1;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns the candidates that are among the top documents of their group,
+    /// the candidates being ranked by id.
+    pub fn apply(
+        &mut self,
+        ctx: &mut SearchContext,
+        candidates: &RoaringBitmap,
+    ) -> Result<RoaringBitmap> {
+        let mut remaining = RoaringBitmap::new();
+        for docid in candidates {
+            if self.keep(ctx.index, ctx.txn, docid)? {
+                remaining.push(docid);
+            }
+        }
+        Ok(remaining)
+    }
+}
+
+/// Returns the number of candidates in the group of each of the given documents,
+/// by the value of the group.
+pub fn group_counts(
+    index: &Index,
+    txn: &RoTxn,
+    field_id: FieldId,
+    docids: &[u32],
+    candidates: &RoaringBitmap,
+) -> Result<BTreeMap<String, u64>> {
+    let mut counts = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for &docid in docids {
+        let Some((group, value)) = group_of(index, txn, field_id, docid)? else { continue };
+        if !seen.insert(group.clone()) {
+            continue;
+        }
+
+        let group_docids = match group {
+            Group::String(normalized) => facet_value_docids(
+                index.facet_id_string_docids.remap_types(),
+                txn,
+                field_id,
+                &normalized,
+            )?,
+            Group::Number(bits) => index
+                .facet_id_f64_docids
+                .get(txn, &FacetGroupKey { field_id, level: 0, left_bound: f64::from_bits(bits) })?
+                .map(|group| group.bitmap),
+        };
+        let count =
+            group_docids.map_or(0, |group_docids| group_docids.intersection_len(candidates));
+        counts.insert(value, count);
+    }
+    Ok(counts)
+}
+
+/// Returns the group of the document along with its original value.
+fn group_of(
+    index: &Index,
+    txn: &RoTxn,
+    field_id: FieldId,
+    docid: u32,
+) -> Result<Option<(Group, String)>> {
+    if let Some(item) = facet_string_values(docid, field_id, index, txn)?.next() {
+        let ((_, _, normalized), original) = item?;
+        return Ok(Some((Group::String(normalized.to_vec()), original.to_string())));
+    }
+    if let Some(item) = facet_number_values(docid, field_id, index, txn)?.next() {
+        let ((_, _, number), ()) = item?;
+        return Ok(Some((Group::Number(number.to_bits()), number.to_string())));
+    }
+    Ok(None)
+}
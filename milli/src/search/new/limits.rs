@@ -8,6 +8,12 @@ pub const MAX_ONE_TYPO_COUNT: usize = 150;
 /// Maximum number of words that can be derived from a single word with a distance of two to that word.
 pub const MAX_TWO_TYPOS_COUNT: usize = 50;
 
+/// Maximum number of words sharing the stem of a single word that can be derived from it.
+pub const MAX_STEM_DERIVATIONS_COUNT: usize = 50;
+
+/// Maximum number of words written in another script that can be derived from a single latin word.
+pub const MAX_ROMANIZATION_DERIVATIONS_COUNT: usize = 50;
+
 /// Maximum amount of synonym phrases that can be derived from a single word.
 pub const MAX_SYNONYM_PHRASE_COUNT: usize = 50;
 
@@ -260,8 +260,9 @@ pub(crate) mod tests {
         let mut ctx = SearchContext::new(&temp_index, &rtxn);
         let mut builder = TokenizerBuilder::default();
         let tokenizer = builder.build();
-        let tokens = tokenizer.tokenize("split this world");
-        let query_terms = located_query_terms_from_tokens(&mut ctx, tokens, None).unwrap();
+        let text = "split this world";
+        let tokens = tokenizer.tokenize(text);
+        let query_terms = located_query_terms_from_tokens(&mut ctx, text, tokens, None).unwrap();
         let matching_words = MatchingWords::new(ctx, query_terms);
 
         assert_eq!(
@@ -4,6 +4,9 @@ use charabia::{SeparatorKind, Token, Tokenizer};
 pub use matching_words::MatchingWords;
 use matching_words::{MatchType, PartialMatch, WordId};
 use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::identifiers;
 
 pub mod matching_words;
 
@@ -18,6 +21,8 @@ pub struct MatcherBuilder<'m> {
     crop_marker: Option<String>,
     highlight_prefix: Option<String>,
     highlight_suffix: Option<String>,
+    split_identifiers: bool,
+    escape_html: bool,
 }
 
 impl<'m> MatcherBuilder<'m> {
@@ -28,6 +33,8 @@ impl<'m> MatcherBuilder<'m> {
             crop_marker: None,
             highlight_prefix: None,
             highlight_suffix: None,
+            split_identifiers: false,
+            escape_html: false,
         }
     }
 
@@ -46,6 +53,19 @@ impl<'m> MatcherBuilder<'m> {
         self
     }
 
+    /// Splits the words written in camelCase or in snake_case like the index does.
+    pub fn split_identifiers(&mut self, split_identifiers: bool) -> &Self {
+        self.split_identifiers = split_identifiers;
+        self
+    }
+
+    /// Escapes the HTML special characters of the formatted text, the highlight tags
+    /// and the crop marker being kept as is.
+    pub fn escape_html(&mut self, escape_html: bool) -> &Self {
+        self.escape_html = escape_html;
+        self
+    }
+
     pub fn build<'t>(&'m self, text: &'t str) -> Matcher<'t, 'm> {
         let crop_marker = match &self.crop_marker {
             Some(marker) => marker.as_str(),
@@ -67,6 +87,8 @@ impl<'m> MatcherBuilder<'m> {
             crop_marker,
             highlight_prefix,
             highlight_suffix,
+            split_identifiers: self.split_identifiers,
+            escape_html: self.escape_html,
             matches: None,
         }
     }
@@ -95,6 +117,17 @@ pub struct Match {
     token_position: usize,
 }
 
+/// The unit in which the bounds of the matches are expressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetUnit {
+    /// The bytes of the UTF-8 encoded text.
+    Bytes,
+    /// The unicode scalar values of the text.
+    Chars,
+    /// The extended grapheme clusters of the text, as displayed to the users.
+    Graphemes,
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct MatchBounds {
     pub start: usize,
@@ -110,10 +143,19 @@ pub struct Matcher<'t, 'm> {
     crop_marker: &'m str,
     highlight_prefix: &'m str,
     highlight_suffix: &'m str,
+    split_identifiers: bool,
+    escape_html: bool,
     matches: Option<(Vec<Token<'t>>, Vec<Match>)>,
 }
 
-impl<'t> Matcher<'t, '_> {
+impl<'t, 'm> Matcher<'t, 'm> {
+    /// Replaces the highlight tags of the builder, e.g. by the ones of the formatted attribute.
+    pub fn highlight_tags(&mut self, prefix: &'m str, suffix: &'m str) -> &mut Self {
+        self.highlight_prefix = prefix;
+        self.highlight_suffix = suffix;
+        self
+    }
+
     /// Iterates over tokens and save any of them that matches the query.
     fn compute_matches(&mut self) -> &mut Self {
         /// some words are counted as matches only if they are close together and in the good order,
@@ -169,7 +211,9 @@ impl<'t> Matcher<'t, '_> {
             false
         }
 
-        let tokens: Vec<_> = self.tokenizer.tokenize(self.text).collect();
+        let tokens = self.tokenizer.tokenize(self.text);
+        let tokens: Vec<_> =
+            identifiers::split_identifiers(self.text, tokens, self.split_identifiers).collect();
         let mut matches = Vec::new();
 
         let mut words_positions = tokens
@@ -239,6 +283,95 @@ impl<'t> Matcher<'t, '_> {
         }
     }
 
+    /// Returns boundaries of the words that match the query, expressed in the given unit.
+    pub fn matches_in(&mut self, unit: OffsetUnit) -> Vec<MatchBounds> {
+        match &self.matches {
+            None => self.compute_matches().matches_in(unit),
+            Some((tokens, matches)) => {
+                let count = |text: &str| match unit {
+                    OffsetUnit::Bytes => text.len(),
+                    OffsetUnit::Chars => text.chars().count(),
+                    OffsetUnit::Graphemes => text.graphemes(true).count(),
+                };
+
+                // the matches follow the order of the text, so the offset of a match is counted
+                // from the previous one instead of from the start of the text.
+                let mut previous = (0, 0);
+                matches
+                    .iter()
+                    .map(|m| {
+                        let token = &tokens[m.token_position];
+                        let (byte_offset, offset) =
+                            if token.byte_start >= previous.0 { previous } else { (0, 0) };
+                        let start = offset + count(&self.text[byte_offset..token.byte_start]);
+                        previous = (token.byte_start, start);
+
+                        let byte_end = self.match_byte_end(token, m);
+                        MatchBounds { start, length: count(&self.text[token.byte_start..byte_end]) }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the byte index of the end of the match, a prefix match ending in the token.
+    fn match_byte_end(&self, token: &Token, m: &Match) -> usize {
+        self.text[token.byte_start..]
+            .char_indices()
+            .enumerate()
+            .find(|(i, _)| *i == m.match_len)
+            .map_or(token.byte_end, |(_, (i, _))| i + token.byte_start)
+    }
+
+    /// Returns the words of the text matching the query, as normalized by the tokenizer.
+    ///
+    /// They are the words that matched after the typos, prefixes and synonyms of the
+    /// query were taken into account, each word being returned once.
+    pub fn matched_words(&mut self) -> Vec<String> {
+        match &self.matches {
+            None => self.compute_matches().matched_words(),
+            Some((tokens, matches)) => {
+                let mut words: Vec<_> =
+                    matches.iter().map(|m| tokens[m.token_position].lemma().to_string()).collect();
+                words.sort_unstable();
+                words.dedup();
+                words
+            }
+        }
+    }
+
+    /// Returns the largest crop size, in words, whose crop window holds at most `max_chars`
+    /// characters, along with the number of characters of this window.
+    ///
+    /// The crop marker and the highlight tags are not counted in the characters budget,
+    /// `None` being returned when not even a word fits in it.
+    pub fn crop_size_within(&mut self, max_chars: usize) -> Option<(usize, usize)> {
+        match &self.matches {
+            None => self.compute_matches().crop_size_within(max_chars),
+            Some((tokens, matches)) => {
+                let window_chars = |crop_size| {
+                    let matches = self.find_best_match_interval(matches, crop_size);
+                    let (byte_start, byte_end) = self.crop_bounds(tokens, matches, crop_size);
+                    self.text[byte_start..byte_end].chars().count()
+                };
+
+                // the crop window grows with the crop size, we search for the largest one fitting.
+                let words_count = tokens.iter().filter(|token| token.is_word()).count();
+                let (mut low, mut high) = (0, words_count.min(max_chars));
+                while low < high {
+                    let middle = (low + high + 1) / 2;
+                    if window_chars(middle) <= max_chars {
+                        low = middle;
+                    } else {
+                        high = middle - 1;
+                    }
+                }
+
+                (low > 0).then(|| (low, window_chars(low)))
+            }
+        }
+    }
+
     /// Returns the bounds in byte index of the crop window.
     fn crop_bounds(&self, tokens: &[Token], matches: &[Match], crop_size: usize) -> (usize, usize) {
         // if there is no match, we start from the beginning of the string by default.
@@ -414,7 +547,7 @@ impl<'t> Matcher<'t, '_> {
     pub fn format(&mut self, format_options: FormatOptions) -> Cow<'t, str> {
         if !format_options.highlight && format_options.crop.is_none() {
             // compute matches is not needed if no highlight nor crop is requested.
-            Cow::Borrowed(self.text)
+            self.escape(self.text)
         } else {
             match &self.matches {
                 Some((tokens, matches)) => {
@@ -432,7 +565,7 @@ impl<'t> Matcher<'t, '_> {
 
                     // push crop marker if it's not the start of the text.
                     if byte_start > 0 && !self.crop_marker.is_empty() {
-                        formatted.push(self.crop_marker);
+                        formatted.push(Cow::Borrowed(self.crop_marker));
                     }
 
                     let mut byte_index = byte_start;
@@ -448,20 +581,21 @@ impl<'t> Matcher<'t, '_> {
                             }
 
                             if byte_index < token.byte_start {
-                                formatted.push(&self.text[byte_index..token.byte_start]);
+                                formatted
+                                    .push(self.escape(&self.text[byte_index..token.byte_start]));
                             }
 
-                            let highlight_byte_index = self.text[token.byte_start..]
-                                .char_indices()
-                                .enumerate()
-                                .find(|(i, _)| *i == m.match_len)
-                                .map_or(token.byte_end, |(_, (i, _))| i + token.byte_start);
-                            formatted.push(self.highlight_prefix);
-                            formatted.push(&self.text[token.byte_start..highlight_byte_index]);
-                            formatted.push(self.highlight_suffix);
+                            let highlight_byte_index = self.match_byte_end(token, m);
+                            formatted.push(Cow::Borrowed(self.highlight_prefix));
+                            formatted.push(
+                                self.escape(&self.text[token.byte_start..highlight_byte_index]),
+                            );
+                            formatted.push(Cow::Borrowed(self.highlight_suffix));
                             // if it's a prefix highlight, we put the end of the word after the highlight marker.
                             if highlight_byte_index < token.byte_end {
-                                formatted.push(&self.text[highlight_byte_index..token.byte_end]);
+                                formatted.push(
+                                    self.escape(&self.text[highlight_byte_index..token.byte_end]),
+                                );
                             }
 
                             byte_index = token.byte_end;
@@ -470,17 +604,17 @@ impl<'t> Matcher<'t, '_> {
 
                     // push the rest of the text between last match and the end of crop.
                     if byte_index < byte_end {
-                        formatted.push(&self.text[byte_index..byte_end]);
+                        formatted.push(self.escape(&self.text[byte_index..byte_end]));
                     }
 
                     // push crop marker if it's not the end of the text.
                     if byte_end < self.text.len() && !self.crop_marker.is_empty() {
-                        formatted.push(self.crop_marker);
+                        formatted.push(Cow::Borrowed(self.crop_marker));
                     }
 
                     if formatted.len() == 1 {
                         // avoid concatenating if there is already 1 slice.
-                        Cow::Borrowed(&self.text[byte_start..byte_end])
+                        self.escape(&self.text[byte_start..byte_end])
                     } else {
                         Cow::Owned(formatted.concat())
                     }
@@ -489,6 +623,35 @@ impl<'t> Matcher<'t, '_> {
             }
         }
     }
+
+    /// Escapes the text when the HTML escaping is enabled.
+    fn escape<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.escape_html {
+            escape_html(text)
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+}
+
+/// Replaces the HTML special characters of the text by their entities.
+fn escape_html(text: &str) -> Cow<str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
 }
 
 #[cfg(test)]
@@ -507,8 +670,14 @@ mod tests {
             let crate::search::PartialSearchResult { located_query_terms, .. } = execute_search(
                 &mut ctx,
                 Some(query),
+                None,
                 crate::TermsMatchingStrategy::default(),
                 crate::score_details::ScoringStrategy::Skip,
+                None,
+                crate::TimeBudget::max(),
+                None,
+                None,
+                false,
                 false,
                 universe,
                 &None,
@@ -601,6 +770,130 @@ mod tests {
         );
     }
 
+    #[test]
+    fn matched_words() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let builder = MatcherBuilder::new_test(&rtxn, &temp_index, "split the world");
+
+        // Text without any match.
+        let text = "A quick brown fox can not jump 32 feet, right? Brr, it is cold!";
+        let mut matcher = builder.build(text);
+        assert!(matcher.matched_words().is_empty());
+
+        // Text containing all matches, each matched word is returned once.
+        let text = "Natalie risk her future to build a world with the boy she loves. Emily Henry: The Love That Split The World.";
+        let mut matcher = builder.build(text);
+        assert_eq!(matcher.matched_words(), vec!["split", "the", "world"]);
+
+        // Text containing some matches, the last word of the query is a prefix.
+        let text = "The worlds apart.";
+        let mut matcher = builder.build(text);
+        assert_eq!(matcher.matched_words(), vec!["the", "worlds"]);
+    }
+
+    #[test]
+    fn matches_in() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let builder = MatcherBuilder::new_test(&rtxn, &temp_index, "world");
+
+        // the family emoji is a single grapheme made of 5 chars and 18 bytes.
+        let text = "👨‍👩‍👦 Ŵôřlḑ";
+        let mut matcher = builder.build(text);
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Bytes),
+            vec![MatchBounds { start: 19, length: 10 }]
+        );
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Chars),
+            vec![MatchBounds { start: 6, length: 5 }]
+        );
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Graphemes),
+            vec![MatchBounds { start: 2, length: 5 }]
+        );
+
+        // the offsets of the next matches are counted from the previous ones.
+        let text = "👨‍👩‍👦 Ŵôřlḑ 👨‍👩‍👦 world";
+        let mut matcher = builder.build(text);
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Bytes),
+            vec![MatchBounds { start: 19, length: 10 }, MatchBounds { start: 49, length: 5 }]
+        );
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Chars),
+            vec![MatchBounds { start: 6, length: 5 }, MatchBounds { start: 18, length: 5 }]
+        );
+        assert_eq!(
+            matcher.matches_in(OffsetUnit::Graphemes),
+            vec![MatchBounds { start: 2, length: 5 }, MatchBounds { start: 10, length: 5 }]
+        );
+    }
+
+    #[test]
+    fn crop_size_within() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let builder = MatcherBuilder::new_test(&rtxn, &temp_index, "split the world");
+
+        // Text without any word.
+        let mut matcher = builder.build(":-)");
+        assert_eq!(matcher.crop_size_within(10), None);
+
+        // The whole text fits in the budget.
+        let text = "Split the world.";
+        let mut matcher = builder.build(text);
+        assert_eq!(matcher.crop_size_within(100), Some((3, 15)));
+
+        // Not even a word fits in the budget.
+        let mut matcher = builder.build(text);
+        assert_eq!(matcher.crop_size_within(2), None);
+
+        // The largest crop window fitting in the budget is kept.
+        let text = "Natalie risk her future to build a world with the boy she loves.";
+        let mut matcher = builder.build(text);
+        let (crop_size, chars) = matcher.crop_size_within(20).unwrap();
+        assert!(chars <= 20);
+        let format_options = FormatOptions { highlight: true, crop: Some(crop_size) };
+        insta::assert_snapshot!(
+            matcher.format(format_options),
+            @"…a <em>world</em> with <em>the</em> boy…"
+        );
+    }
+
+    #[test]
+    fn format_escape_html() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let mut builder = MatcherBuilder::new_test(&rtxn, &temp_index, "split the world");
+        builder.escape_html(true);
+
+        let text = r#"<b>The</b> world & "split" it"#;
+
+        // no crop and no highlight should return the escaped text.
+        let mut matcher = builder.build(text);
+        insta::assert_snapshot!(
+            matcher.format(FormatOptions { highlight: false, crop: None }),
+            @"&lt;b&gt;The&lt;/b&gt; world &amp; &quot;split&quot; it"
+        );
+
+        // the highlight tags are not escaped.
+        let mut matcher = builder.build(text);
+        insta::assert_snapshot!(
+            matcher.format(FormatOptions { highlight: true, crop: None }),
+            @"&lt;b&gt;<em>The</em>&lt;/b&gt; <em>world</em> &amp; &quot;<em>split</em>&quot; it"
+        );
+
+        // the highlight tags of the builder can be replaced.
+        let mut matcher = builder.build(text);
+        matcher.highlight_tags("<mark>", "</mark>");
+        insta::assert_snapshot!(
+            matcher.format(FormatOptions { highlight: true, crop: None }),
+            @"&lt;b&gt;<mark>The</mark>&lt;/b&gt; <mark>world</mark> &amp; &quot;<mark>split</mark>&quot; it"
+        );
+    }
+
     #[test]
     fn highlight_unicode() {
         let temp_index = temp_index_with_documents();
@@ -1,8 +1,10 @@
 mod bucket_sort;
+mod cursor;
 mod db_cache;
 mod distinct;
 mod geo_sort;
 mod graph_based_ranking_rule;
+mod group_by;
 mod interner;
 mod limits;
 mod logger;
@@ -15,44 +17,72 @@ mod resolve_query_graph;
 mod small_bitmap;
 
 mod exact_attribute;
+mod expression_sort;
+mod query_rules;
+mod random_sort;
 mod sort;
+mod static_boost;
+mod structured_query;
 mod vector_sort;
+mod vocabulary;
 
 #[cfg(test)]
 mod tests;
 
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use bucket_sort::{bucket_sort, BucketSortOutput};
-use charabia::TokenizerBuilder;
+use bucket_sort::{
+    bucket_sort, parallel_bucket_sort, BucketSortOutput, PARALLEL_BUCKET_SORT_THRESHOLD,
+};
+use charabia::{Tokenizer, TokenizerBuilder};
+pub use cursor::{BucketCoordinate, SearchCursor};
 use db_cache::DatabaseCache;
 use exact_attribute::ExactAttribute;
+use expression_sort::ExpressionSort;
+use fxhash::FxHashMap;
 use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, Typo};
+pub use group_by::{group_counts, GroupBy};
 use heed::RoTxn;
 use interner::{DedupInterner, Interner};
 pub use logger::visual::VisualSearchLogger;
 pub use logger::{DefaultSearchLogger, SearchLogger};
 use query_graph::{QueryGraph, QueryNode};
-use query_term::{located_query_terms_from_tokens, LocatedQueryTerm, Phrase, QueryTerm};
+pub use query_rules::AppliedQueryRules;
+use query_rules::QueryRules;
+pub use query_term::{corrected_query, split_negative_query};
+use query_term::{
+    located_query_terms_from_tokens, negative_phrase_from_tokens, LocatedQueryTerm, Phrase,
+    QueryTerm,
+};
+use random_sort::RandomSort;
 use ranking_rules::{
     BoxRankingRule, PlaceholderQuery, RankingRule, RankingRuleOutput, RankingRuleQueryTrait,
 };
 use resolve_query_graph::{compute_query_graph_docids, PhraseDocIdsCache};
 use roaring::RoaringBitmap;
 use sort::Sort;
+use static_boost::Boost;
 
 use self::distinct::facet_string_values;
 use self::geo_sort::GeoSort;
 pub use self::geo_sort::Strategy as GeoSortStrategy;
 use self::graph_based_ranking_rule::Words;
 use self::interner::Interned;
+use self::structured_query::ShouldClauses;
+pub use self::structured_query::{QueryClause, StructuredQuery};
+pub use self::vector_sort::CandidateSelection;
 use self::vector_sort::VectorSort;
+pub use self::vocabulary::QueryVocabulary;
+use self::vocabulary::Vocabulary;
 use crate::error::FieldIdMapMissingEntry;
+use crate::identifiers;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::search::new::distinct::apply_distinct_rule;
 use crate::vector::DistributionShift;
 use crate::{
-    AscDesc, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy, UserError,
+    is_faceted_by, match_attribute_pattern, AscDesc, DocumentId, FieldId, Filter, Index, Member,
+    Result, TermsMatchingStrategy, TimeBudget, UserError,
 };
 
 /// A structure used throughout the execution of a search query.
@@ -65,6 +95,14 @@ pub struct SearchContext<'ctx> {
     pub term_interner: Interner<QueryTerm>,
     pub phrase_docids: PhraseDocIdsCache,
     pub restricted_fids: Option<RestrictedFids>,
+    pub attribute_ranks: Option<FxHashMap<FieldId, u16>>,
+    pub query_rules: Option<AppliedQueryRules>,
+    pub vocabulary: Option<Vocabulary>,
+    /// The number of words allowed between the words of the phrases of the query
+    /// that don't have a slop of their own.
+    pub phrase_slop: u8,
+    /// The distinct attribute of the query, replacing the one of the index.
+    pub distinct: Option<String>,
 }
 
 impl<'ctx> SearchContext<'ctx> {
@@ -78,26 +116,69 @@ impl<'ctx> SearchContext<'ctx> {
             term_interner: <_>::default(),
             phrase_docids: <_>::default(),
             restricted_fids: None,
+            attribute_ranks: None,
+            query_rules: None,
+            vocabulary: None,
+            phrase_slop: 0,
+            distinct: None,
         }
     }
 
+    /// Restricts the search to the given attributes.
+    ///
+    /// An attribute also restricts the search to its nested fields, and a `*` at the start
+    /// or the end of an attribute matches all the searchable fields with this suffix or prefix.
     pub fn searchable_attributes(&mut self, searchable_attributes: &'ctx [String]) -> Result<()> {
         let fids_map = self.index.fields_ids_map(self.txn)?;
         let searchable_names = self.index.searchable_fields(self.txn)?;
         let exact_attributes_ids = self.index.exact_attributes_ids(self.txn)?;
+        let is_searchable =
+            |name: &str| searchable_names.as_ref().map_or(true, |sn| sn.contains(&name));
 
         let mut restricted_fids = RestrictedFids::default();
+        let push_fid = |restricted_fids: &mut RestrictedFids, fid: FieldId| {
+            if restricted_fids.contains(&fid) {
+                return;
+            }
+            if exact_attributes_ids.contains(&fid) {
+                restricted_fids.exact.push(fid);
+            } else {
+                restricted_fids.tolerant.push(fid);
+            };
+        };
+
         let mut contains_wildcard = false;
         for field_name in searchable_attributes {
             if field_name == "*" {
                 contains_wildcard = true;
                 continue;
             }
+
+            // The fields matching the pattern may not exist yet, the pattern then matches nothing.
+            if field_name.starts_with('*') || field_name.ends_with('*') {
+                for (fid, name) in fids_map.iter() {
+                    if is_searchable(name) && match_attribute_pattern(field_name, name) {
+                        push_fid(&mut restricted_fids, fid);
+                    }
+                }
+                continue;
+            }
+
+            let nested_fids: Vec<_> = fids_map
+                .iter()
+                .filter(|&(_, name)| {
+                    name != field_name.as_str()
+                        && is_searchable(name)
+                        && is_faceted_by(name, field_name)
+                })
+                .map(|(fid, _)| fid)
+                .collect();
+
             let searchable_contains_name =
                 searchable_names.as_ref().map(|sn| sn.iter().any(|name| name == field_name));
-            let fid = match (fids_map.id(field_name), searchable_contains_name) {
+            match (fids_map.id(field_name), searchable_contains_name) {
                 // The Field id exist and the field is searchable
-                (Some(fid), Some(true)) | (Some(fid), None) => fid,
+                (Some(fid), Some(true)) | (Some(fid), None) => push_fid(&mut restricted_fids, fid),
                 // The field is searchable but the Field id doesn't exist => Internal Error
                 (None, Some(true)) => {
                     return Err(FieldIdMapMissingEntry::FieldName {
@@ -107,7 +188,9 @@ impl<'ctx> SearchContext<'ctx> {
                     .into())
                 }
                 // The field is not searchable, but the searchableAttributes are set to * => ignore field
-                (None, None) => continue,
+                (None, None) => (),
+                // The field is not searchable but some of its nested fields are => only search them
+                (_fid, Some(false)) if !nested_fids.is_empty() => (),
                 // The field is not searchable => User error
                 (_fid, Some(false)) => {
                     let (valid_fields, hidden_fields) = match searchable_names {
@@ -123,19 +206,185 @@ impl<'ctx> SearchContext<'ctx> {
                     }
                     .into());
                 }
-            };
+            }
 
-            if exact_attributes_ids.contains(&fid) {
-                restricted_fids.exact.push(fid);
-            } else {
-                restricted_fids.tolerant.push(fid);
-            };
+            for fid in nested_fids {
+                push_fid(&mut restricted_fids, fid);
+            }
         }
 
         self.restricted_fids = (!contains_wildcard).then_some(restricted_fids);
 
         Ok(())
     }
+
+    /// Ranks the searchable attributes by their boosted weight for the `attribute` ranking rule.
+    ///
+    /// The weight of an attribute is its boost divided by its position in the searchable
+    /// attributes, the attributes of equal weight keeping their searchable order.
+    pub fn attribute_boosts(&mut self, attribute_boosts: &BTreeMap<String, f64>) -> Result<()> {
+        let fids_map = self.index.fields_ids_map(self.txn)?;
+        let mut searchable_fids = match self.index.searchable_fields_ids(self.txn)? {
+            Some(searchable_fids) => searchable_fids,
+            None => fids_map.ids().collect(),
+        };
+        // the attribute ranking rule ranks the attributes by their field id
+        searchable_fids.sort_unstable();
+
+        let mut weighted_fids: Vec<_> = searchable_fids
+            .into_iter()
+            .enumerate()
+            .map(|(position, fid)| {
+                let boost = fids_map
+                    .name(fid)
+                    .and_then(|name| {
+                        attribute_boosts
+                            .iter()
+                            .find(|(attribute, _)| crate::is_faceted_by(name, attribute))
+                    })
+                    .map_or(1.0, |(_, boost)| *boost);
+                (boost / (position as f64 + 1.0), fid)
+            })
+            .collect();
+        weighted_fids.sort_by(|(weight_a, fid_a), (weight_b, fid_b)| {
+            weight_b.total_cmp(weight_a).then(fid_a.cmp(fid_b))
+        });
+
+        let attribute_ranks = weighted_fids
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (_weight, fid))| (fid, rank as u16))
+            .collect();
+        self.attribute_ranks = Some(attribute_ranks);
+
+        Ok(())
+    }
+
+    /// Uses the synonyms and stop words of the query in addition to, or instead of,
+    /// the ones of the index.
+    pub fn vocabulary(&mut self, vocabulary: QueryVocabulary) -> Result<()> {
+        self.vocabulary = Some(Vocabulary::new(self.index, self.txn, vocabulary)?);
+        Ok(())
+    }
+
+    /// Deduplicates the documents on this filterable attribute instead of the distinct attribute of the index.
+    pub fn distinct(&mut self, distinct: &str) -> Result<()> {
+        let filterable_fields = self.index.filterable_fields(self.txn)?;
+        if !crate::is_faceted(distinct, &filterable_fields) {
+            let (valid_fields, hidden_fields) =
+                self.index.remove_hidden_fields(self.txn, filterable_fields)?;
+            return Err(UserError::InvalidDistinctAttribute {
+                field: distinct.to_string(),
+                valid_fields,
+                hidden_fields,
+            }
+            .into());
+        }
+
+        self.distinct = Some(distinct.to_string());
+        Ok(())
+    }
+
+    /// Returns the id of the field the documents are deduplicated on,
+    /// taking the distinct attribute of the query into account.
+    pub fn distinct_fid(&self) -> Result<Option<FieldId>> {
+        let field = match &self.distinct {
+            Some(field) => Some(field.as_str()),
+            None => self.index.distinct_field(self.txn)?,
+        };
+        match field {
+            Some(field) => Ok(self.index.fields_ids_map(self.txn)?.id(field)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the synonyms of the phrase, taking the vocabulary of the query into account.
+    pub fn words_synonyms<S: AsRef<str>>(&self, words: &[S]) -> Result<Option<Vec<Vec<String>>>> {
+        match &self.vocabulary {
+            Some(vocabulary) => vocabulary.words_synonyms(self.index, self.txn, words),
+            None => Ok(self.index.words_synonyms(self.txn, words)?),
+        }
+    }
+
+    /// Returns the greatest number of words of the phrases having synonyms,
+    /// taking the vocabulary of the query into account.
+    pub fn max_synonym_words(&self) -> Result<usize> {
+        match &self.vocabulary {
+            Some(vocabulary) => vocabulary.max_synonym_words(self.index, self.txn),
+            None => Ok(self.index.max_synonym_words(self.txn)?),
+        }
+    }
+
+    /// Returns the stop words ignored in the query, taking the vocabulary of the query into account.
+    pub fn stop_words(&self) -> Result<Option<fst::Set<Cow<'ctx, [u8]>>>> {
+        let mut index_stop_words = match self.index.stop_words(self.txn)? {
+            Some(stop_words) => Some(stop_words.map_data(Cow::Borrowed)?),
+            None => None,
+        };
+
+        // the stop words of a localized attributes rule are only ignored in the query
+        // when all the searched attributes match this rule
+        if let Some(localized_stop_words) = self.localized_stop_words()? {
+            let mut stop_words = localized_stop_words;
+            if let Some(index_stop_words) = &index_stop_words {
+                stop_words.extend(index_stop_words.stream().into_strs()?);
+            }
+            index_stop_words = Some(fst::Set::from_iter(stop_words)?.map_data(Cow::Owned)?);
+        }
+
+        let query_stop_words = match &self.vocabulary {
+            Some(vocabulary) => vocabulary.stop_words(index_stop_words.as_ref())?,
+            None => None,
+        };
+        Ok(match query_stop_words {
+            Some(stop_words) => Some(stop_words.map_data(Cow::Owned)?),
+            None => index_stop_words,
+        })
+    }
+
+    /// Returns the stop words of the localized attributes rule matched by all the searched
+    /// attributes, `None` if they don't all match the same rule or if it has no stop words.
+    fn localized_stop_words(&self) -> Result<Option<BTreeSet<String>>> {
+        let mut rules = self.index.localized_attributes_rules(self.txn)?;
+        if rules.iter().all(|rule| rule.stop_words.is_empty()) {
+            return Ok(None);
+        }
+
+        let fids_map = self.index.fields_ids_map(self.txn)?;
+        let searched_fids = match &self.restricted_fids {
+            Some(restricted_fids) => {
+                restricted_fids.tolerant.iter().chain(&restricted_fids.exact).copied().collect()
+            }
+            None => match self.index.searchable_fields_ids(self.txn)? {
+                Some(searchable_fids) => searchable_fids,
+                None => fids_map.ids().collect(),
+            },
+        };
+
+        let mut matched_rule = None;
+        for fid in searched_fids {
+            let rule = fids_map
+                .name(fid)
+                .and_then(|name| rules.iter().position(|rule| rule.matches(name)));
+            match (rule, matched_rule) {
+                (None, _) => return Ok(None),
+                (Some(rule), Some(matched)) if rule != matched => return Ok(None),
+                (rule, _) => matched_rule = rule,
+            }
+        }
+
+        Ok(matched_rule
+            .map(|rule| std::mem::take(&mut rules[rule].stop_words))
+            .filter(|stop_words| !stop_words.is_empty()))
+    }
+
+    /// Returns the rank of the attribute in the `attribute` ranking rule, the lower the better.
+    pub fn attribute_rank(&self, fid: FieldId) -> u16 {
+        match &self.attribute_ranks {
+            Some(attribute_ranks) => attribute_ranks.get(&fid).copied().unwrap_or(fid),
+            None => fid,
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
@@ -181,6 +430,11 @@ fn resolve_maximally_reduced_query_graph(
             .iter()
             .flat_map(|x| x.iter())
             .collect(),
+        TermsMatchingStrategy::Frequency => query_graph
+            .removal_order_for_terms_matching_strategy_frequency(ctx)?
+            .iter()
+            .flat_map(|x| x.iter())
+            .collect(),
         TermsMatchingStrategy::All => vec![],
     };
     graph.remove_nodes_keep_edges(&nodes_to_remove);
@@ -191,6 +445,46 @@ fn resolve_maximally_reduced_query_graph(
     Ok(docids)
 }
 
+/// Resolves the query graph reduced step by step, following the removal order of the matching
+/// strategy, until at least `min_len` documents match it.
+///
+/// The documents are the ones of the first buckets of the `words` ranking rule. Also returns
+/// whether the graph was maximally reduced, the documents then being the universe of the search.
+fn resolve_universe_until(
+    ctx: &mut SearchContext,
+    universe: &RoaringBitmap,
+    query_graph: &QueryGraph,
+    matching_strategy: TermsMatchingStrategy,
+    min_len: u64,
+    logger: &mut dyn SearchLogger<QueryGraph>,
+) -> Result<(RoaringBitmap, bool)> {
+    let removal_order = match matching_strategy {
+        TermsMatchingStrategy::Last => {
+            query_graph.removal_order_for_terms_matching_strategy_last(ctx)
+        }
+        TermsMatchingStrategy::Frequency => {
+            query_graph.removal_order_for_terms_matching_strategy_frequency(ctx)?
+        }
+        TermsMatchingStrategy::All => vec![],
+    };
+
+    let mut graph = query_graph.clone();
+    let mut removal_order = removal_order.into_iter();
+    loop {
+        let docids = compute_query_graph_docids(ctx, &graph, universe)?;
+        match removal_order.next() {
+            Some(nodes) if docids.len() < min_len => {
+                graph.remove_nodes_keep_edges(&nodes.iter().collect::<Vec<_>>())
+            }
+            Some(_) => return Ok((docids, false)),
+            None => {
+                logger.query_for_initial_universe(&graph);
+                return Ok((docids, true));
+            }
+        }
+    }
+}
+
 fn resolve_universe(
     ctx: &mut SearchContext,
     initial_universe: &RoaringBitmap,
@@ -212,11 +506,14 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
     ctx: &SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
+    should_clauses: &Option<Vec<(RoaringBitmap, f64)>>,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     let mut sort = false;
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
     let mut ranking_rules: Vec<BoxRankingRule<PlaceholderQuery>> = vec![];
+    push_query_rules(ctx, &mut ranking_rules);
+    let mut static_boost = ctx.index.static_boost(ctx.txn)?;
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
     for rr in settings_ranking_rules {
         match rr {
@@ -227,6 +524,7 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
             | crate::Criterion::Proximity
             | crate::Criterion::Exactness => continue,
             crate::Criterion::Sort => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sort {
                     continue;
                 }
@@ -241,6 +539,7 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 sort = true;
             }
             crate::Criterion::Asc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
@@ -248,17 +547,31 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
             crate::Criterion::Desc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::AscExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, true)?));
+            }
+            crate::Criterion::DescExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, false)?));
+            }
         }
     }
+    push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+    insert_should_clauses(&mut ranking_rules, should_clauses);
     Ok(ranking_rules)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_ranking_rules_for_vector<'ctx>(
     ctx: &SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
@@ -267,6 +580,7 @@ fn get_ranking_rules_for_vector<'ctx>(
     target: &[f32],
     distribution_shift: Option<DistributionShift>,
     embedder_name: &str,
+    candidate_selection: CandidateSelection,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
     // query graph search
 
@@ -276,7 +590,9 @@ fn get_ranking_rules_for_vector<'ctx>(
 
     let mut vector = false;
     let mut ranking_rules: Vec<BoxRankingRule<PlaceholderQuery>> = vec![];
+    push_query_rules(ctx, &mut ranking_rules);
 
+    let mut static_boost = ctx.index.static_boost(ctx.txn)?;
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
     for rr in settings_ranking_rules {
         match rr {
@@ -294,12 +610,14 @@ fn get_ranking_rules_for_vector<'ctx>(
                         limit_plus_offset,
                         distribution_shift,
                         embedder_name,
+                        candidate_selection,
                     )?;
                     ranking_rules.push(Box::new(vector_sort));
                     vector = true;
                 }
             }
             crate::Criterion::Sort => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sort {
                     continue;
                 }
@@ -314,6 +632,7 @@ fn get_ranking_rules_for_vector<'ctx>(
                 sort = true;
             }
             crate::Criterion::Asc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
@@ -321,14 +640,26 @@ fn get_ranking_rules_for_vector<'ctx>(
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
             crate::Criterion::Desc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::AscExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, true)?));
+            }
+            crate::Criterion::DescExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, false)?));
+            }
         }
     }
+    push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
 
     Ok(ranking_rules)
 }
@@ -356,6 +687,8 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     }
 
     let mut ranking_rules: Vec<BoxRankingRule<QueryGraph>> = vec![];
+    push_query_rules(ctx, &mut ranking_rules);
+    let mut static_boost = ctx.index.static_boost(ctx.txn)?;
     let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
     for rr in settings_ranking_rules {
         // Add Words before any of: typo, proximity, attribute
@@ -402,6 +735,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 ranking_rules.push(Box::new(Position::new(None)));
             }
             crate::Criterion::Sort => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sort {
                     continue;
                 }
@@ -424,6 +758,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 exactness = true;
             }
             crate::Criterion::Asc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
@@ -431,17 +766,63 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
             crate::Criterion::Desc(field_name) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::AscExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, true)?));
+            }
+            crate::Criterion::DescExpression(expression) => {
+                push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
+                ranking_rules
+                    .push(Box::new(ExpressionSort::new(ctx.index, ctx.txn, expression, false)?));
+            }
         }
     }
+    push_static_boost(ctx, &mut ranking_rules, &mut static_boost)?;
     Ok(ranking_rules)
 }
 
+/// Adds the query rules ranking rule when the triggered rules pin or boost documents.
+fn push_query_rules<'ctx, Query: RankingRuleQueryTrait>(
+    ctx: &SearchContext<'ctx>,
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+) {
+    if let Some(query_rules) = ctx.query_rules.as_ref().filter(|rules| rules.reorders()) {
+        ranking_rules.push(Box::new(QueryRules::new(query_rules)));
+    }
+}
+
+/// Adds the ranking rule of the `should` clauses of a structured query, if any,
+/// right after the query rules.
+fn insert_should_clauses<'ctx, Query: RankingRuleQueryTrait>(
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+    should_clauses: &Option<Vec<(RoaringBitmap, f64)>>,
+) {
+    if let Some(clauses) = should_clauses {
+        let position = ranking_rules.iter().take_while(|rule| rule.id() == "query_rules").count();
+        ranking_rules.insert(position, Box::new(ShouldClauses::new(clauses.clone())));
+    }
+}
+
+/// Adds the static boost ranking rule, if any, before the first custom sort rule.
+fn push_static_boost<'ctx, Query: RankingRuleQueryTrait>(
+    ctx: &SearchContext<'ctx>,
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+    static_boost: &mut Option<crate::static_boost::StaticBoost>,
+) -> Result<()> {
+    if let Some(static_boost) = static_boost.take() {
+        ranking_rules.push(Box::new(Boost::new(ctx.index, ctx.txn, static_boost)?));
+    }
+    Ok(())
+}
+
 fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
     sort_criteria: &Option<Vec<AscDesc>>,
     ctx: &SearchContext<'ctx>,
@@ -468,6 +849,24 @@ fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            AscDesc::Asc(Member::ModifiedField(field_name, modifiers)) => {
+                if sorted_fields.contains(&field_name) {
+                    continue;
+                }
+                sorted_fields.insert(field_name.clone());
+                ranking_rules.push(Box::new(Sort::with_modifiers(
+                    ctx.index, ctx.txn, field_name, true, modifiers,
+                )?));
+            }
+            AscDesc::Desc(Member::ModifiedField(field_name, modifiers)) => {
+                if sorted_fields.contains(&field_name) {
+                    continue;
+                }
+                sorted_fields.insert(field_name.clone());
+                ranking_rules.push(Box::new(Sort::with_modifiers(
+                    ctx.index, ctx.txn, field_name, false, modifiers,
+                )?));
+            }
             AscDesc::Asc(Member::Geo(point)) => {
                 if *geo_sorted {
                     continue;
@@ -492,6 +891,12 @@ fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
                     false,
                 )?));
             }
+            AscDesc::Asc(Member::Random(seed)) => {
+                ranking_rules.push(Box::new(RandomSort::new(seed, true)));
+            }
+            AscDesc::Desc(Member::Random(seed)) => {
+                ranking_rules.push(Box::new(RandomSort::new(seed, false)));
+            }
         };
     }
     Ok(())
@@ -510,6 +915,10 @@ pub fn execute_vector_search(
     ctx: &mut SearchContext,
     vector: &[f32],
     scoring_strategy: ScoringStrategy,
+    ranking_score_threshold: Option<f64>,
+    time_budget: TimeBudget,
+    search_after: Option<&SearchCursor>,
+    group_by: Option<GroupBy>,
     universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
@@ -517,6 +926,7 @@ pub fn execute_vector_search(
     length: usize,
     distribution_shift: Option<DistributionShift>,
     embedder_name: &str,
+    candidate_selection: CandidateSelection,
 ) -> Result<PartialSearchResult> {
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
@@ -530,13 +940,14 @@ pub fn execute_vector_search(
         vector,
         distribution_shift,
         embedder_name,
+        candidate_selection,
     )?;
 
     let mut placeholder_search_logger = logger::DefaultSearchLogger;
     let placeholder_search_logger: &mut dyn SearchLogger<PlaceholderQuery> =
         &mut placeholder_search_logger;
 
-    let BucketSortOutput { docids, scores, all_candidates } = bucket_sort(
+    let BucketSortOutput { docids, scores, all_candidates, degraded } = bucket_sort(
         ctx,
         ranking_rules,
         &PlaceholderQuery,
@@ -544,6 +955,10 @@ pub fn execute_vector_search(
         from,
         length,
         scoring_strategy,
+        ranking_score_threshold,
+        time_budget,
+        search_after,
+        group_by,
         placeholder_search_logger,
     )?;
 
@@ -552,6 +967,76 @@ pub fn execute_vector_search(
         document_scores: scores,
         documents_ids: docids,
         located_query_terms: None,
+        degraded,
+    })
+}
+
+/// Builds the tokenizer of the queries, which is aware of the stop words, separators,
+/// dictionary and languages of the index, and calls `f` with it.
+fn with_query_tokenizer<'ctx, T>(
+    ctx: &mut SearchContext<'ctx>,
+    f: impl FnOnce(&mut SearchContext<'ctx>, &Tokenizer) -> Result<T>,
+) -> Result<T> {
+    // We make sure that the analyzer is aware of the stop words
+    // this ensures that the query builder is able to properly remove them.
+    let mut tokbuilder = TokenizerBuilder::new();
+    let stop_words = ctx.stop_words()?;
+    if let Some(ref stop_words) = stop_words {
+        tokbuilder.stop_words(stop_words);
+    }
+
+    let separators = ctx.index.allowed_separators(ctx.txn)?;
+    let separators: Option<Vec<_>> =
+        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref separators) = separators {
+        tokbuilder.separators(separators);
+    }
+
+    let dictionary = ctx.index.dictionary(ctx.txn)?;
+    let dictionary: Option<Vec<_>> =
+        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref dictionary) = dictionary {
+        tokbuilder.words_dict(dictionary);
+    }
+
+    let script_lang_map = ctx.index.script_language(ctx.txn)?;
+    if !script_lang_map.is_empty() {
+        tokbuilder.allow_list(&script_lang_map);
+    }
+
+    let tokenizer = tokbuilder.build();
+    f(ctx, &tokenizer)
+}
+
+/// Returns the documents containing the negative words and phrases of a query.
+fn negative_queries_docids(
+    ctx: &mut SearchContext,
+    tokenizer: &Tokenizer,
+    negative_queries: &[&str],
+) -> Result<RoaringBitmap> {
+    let split_identifiers = ctx.index.split_identifiers(ctx.txn)?;
+    let mut docids = RoaringBitmap::new();
+    for negative_query in negative_queries {
+        let tokens = tokenizer.tokenize(negative_query);
+        let tokens = identifiers::split_identifiers(negative_query, tokens, split_identifiers);
+        if let Some(phrase) = negative_phrase_from_tokens(ctx, tokens) {
+            docids |= ctx.get_phrase_docids(phrase)?;
+        }
+    }
+    Ok(docids)
+}
+
+/// Returns the documents containing the negative words and phrases of the query of a
+/// semantic search, they are excluded from its results like from the keyword ones.
+pub fn negative_query_docids(
+    ctx: &mut SearchContext,
+    negative_queries: &[&str],
+) -> Result<RoaringBitmap> {
+    if negative_queries.is_empty() {
+        return Ok(RoaringBitmap::new());
+    }
+    with_query_tokenizer(ctx, |ctx, tokenizer| {
+        negative_queries_docids(ctx, tokenizer, negative_queries)
     })
 }
 
@@ -559,9 +1044,15 @@ pub fn execute_vector_search(
 pub fn execute_search(
     ctx: &mut SearchContext,
     query: Option<&str>,
+    structured_query: Option<&StructuredQuery>,
     terms_matching_strategy: TermsMatchingStrategy,
     scoring_strategy: ScoringStrategy,
+    ranking_score_threshold: Option<f64>,
+    time_budget: TimeBudget,
+    search_after: Option<&SearchCursor>,
+    group_by: Option<GroupBy>,
     exhaustive_number_hits: bool,
+    early_termination: bool,
     mut universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
@@ -573,45 +1064,75 @@ pub fn execute_search(
 ) -> Result<PartialSearchResult> {
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
-    let mut located_query_terms = None;
-    let query_terms = if let Some(query) = query {
-        // We make sure that the analyzer is aware of the stop words
-        // this ensures that the query builder is able to properly remove them.
-        let mut tokbuilder = TokenizerBuilder::new();
-        let stop_words = ctx.index.stop_words(ctx.txn)?;
-        if let Some(ref stop_words) = stop_words {
-            tokbuilder.stop_words(stop_words);
-        }
-
-        let separators = ctx.index.allowed_separators(ctx.txn)?;
-        let separators: Option<Vec<_>> =
-            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
-        if let Some(ref separators) = separators {
-            tokbuilder.separators(separators);
-        }
+    // The documents must match all the words of the `must` clauses of a structured query,
+    // the `should` clauses ranking them by a ranking rule of their own.
+    let terms_matching_strategy = match structured_query {
+        Some(_) => TermsMatchingStrategy::All,
+        None => terms_matching_strategy,
+    };
+    let mut should_clauses = None;
 
-        let dictionary = ctx.index.dictionary(ctx.txn)?;
-        let dictionary: Option<Vec<_>> =
-            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
-        if let Some(ref dictionary) = dictionary {
-            tokbuilder.words_dict(dictionary);
-        }
+    let mut located_query_terms = None;
+    let query_terms = if query.is_some() || structured_query.is_some() {
+        with_query_tokenizer(ctx, |ctx, tokenizer| {
+            let split_identifiers = ctx.index.split_identifiers(ctx.txn)?;
 
-        let script_lang_map = ctx.index.script_language(ctx.txn)?;
-        if !script_lang_map.is_empty() {
-            tokbuilder.allow_list(&script_lang_map);
-        }
+            // The documents containing the negative words and phrases are excluded from the universe.
+            let (query, negative_queries) = match structured_query {
+                Some(structured_query) => {
+                    structured_query.filter_universe(ctx, &mut universe)?;
+                    (
+                        structured_query.must_query_text(),
+                        structured_query.negative_texts().collect(),
+                    )
+                }
+                None => split_negative_query(query.unwrap_or_default()),
+            };
+            universe -= negative_queries_docids(ctx, tokenizer, &negative_queries)?;
 
-        let tokenizer = tokbuilder.build();
-        let tokens = tokenizer.tokenize(query);
+            // The documents matching each `should` clause of a structured query are resolved
+            // for its ranking rule, the documents having to match one of them without other clauses.
+            if let Some(structured_query) =
+                structured_query.filter(|query| !query.should.is_empty())
+            {
+                let mut clauses = Vec::new();
+                let mut any_clause = RoaringBitmap::new();
+                for (should_query, boost) in structured_query.should_query_texts() {
+                    let tokens = tokenizer.tokenize(&should_query);
+                    let tokens =
+                        identifiers::split_identifiers(&should_query, tokens, split_identifiers);
+                    let should_terms =
+                        located_query_terms_from_tokens(ctx, &should_query, tokens, words_limit)?;
+                    if should_terms.is_empty() {
+                        continue;
+                    }
+                    let (graph, _) = QueryGraph::from_query(ctx, &should_terms)?;
+                    let docids = resolve_universe(
+                        ctx,
+                        &universe,
+                        &graph,
+                        TermsMatchingStrategy::All,
+                        query_graph_logger,
+                    )?;
+                    any_clause |= &docids;
+                    clauses.push((docids, boost));
+                }
+                if structured_query.must.is_empty() {
+                    universe &= any_clause;
+                }
+                should_clauses = Some(clauses);
+            }
 
-        let query_terms = located_query_terms_from_tokens(ctx, tokens, words_limit)?;
-        if query_terms.is_empty() {
-            // Do a placeholder search instead
-            None
-        } else {
-            Some(query_terms)
-        }
+            let tokens = tokenizer.tokenize(&query);
+            let tokens = identifiers::split_identifiers(&query, tokens, split_identifiers);
+            let query_terms = located_query_terms_from_tokens(ctx, &query, tokens, words_limit)?;
+            if query_terms.is_empty() {
+                // Do a placeholder search instead
+                Ok(None)
+            } else {
+                Ok(Some(query_terms))
+            }
+        })?
     } else {
         None
     };
@@ -619,51 +1140,173 @@ pub fn execute_search(
         let (graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
         located_query_terms = Some(new_located_query_terms);
 
-        let ranking_rules = get_ranking_rules_for_query_graph_search(
+        let mut ranking_rules = get_ranking_rules_for_query_graph_search(
             ctx,
             sort_criteria,
             geo_strategy,
             terms_matching_strategy,
         )?;
+        insert_should_clauses(&mut ranking_rules, &should_clauses);
+        let mut ranking_rules = Some(ranking_rules);
 
-        universe &=
-            resolve_universe(ctx, &universe, &graph, terms_matching_strategy, query_graph_logger)?;
+        // The `words` ranking rule ranks the documents matching all the query words first, then the
+        // documents matching fewer and fewer words, following the matching strategy. When it's the
+        // first ranking rule, the documents of its first buckets outrank all the other documents:
+        // once they fill the page, the documents matching fewer words are neither resolved nor ranked.
+        let mut early_output = None;
+        let mut universe_resolved = false;
+        if early_termination
+            && !exhaustive_number_hits
+            && length > 0
+            && ranking_score_threshold.is_none()
+            && search_after.is_none()
+            && group_by.is_none()
+            && ranking_rules
+                .as_ref()
+                .and_then(|ranking_rules| ranking_rules.first())
+                .map_or(false, |ranking_rule| ranking_rule.id() == "words")
+        {
+            let (words_universe, maximally_reduced) = resolve_universe_until(
+                ctx,
+                &universe,
+                &graph,
+                terms_matching_strategy,
+                (from + length) as u64,
+                query_graph_logger,
+            )?;
+            if maximally_reduced {
+                // all the buckets are needed, they are the universe of the search
+                universe &= words_universe;
+                universe_resolved = true;
+            } else {
+                let output = bucket_sort(
+                    ctx,
+                    ranking_rules.take().unwrap(),
+                    &graph,
+                    &words_universe,
+                    from,
+                    length,
+                    scoring_strategy,
+                    None,
+                    time_budget,
+                    None,
+                    None,
+                    query_graph_logger,
+                )?;
+                // the distinct attribute can leave too few documents to fill the page
+                if output.docids.len() == length {
+                    early_output = Some(output);
+                }
+            }
+        }
 
-        bucket_sort(
-            ctx,
-            ranking_rules,
-            &graph,
-            &universe,
-            from,
-            length,
-            scoring_strategy,
-            query_graph_logger,
-        )?
+        match early_output {
+            Some(output) => output,
+            None => {
+                let ranking_rules = match ranking_rules {
+                    Some(ranking_rules) => ranking_rules,
+                    None => {
+                        let mut ranking_rules = get_ranking_rules_for_query_graph_search(
+                            ctx,
+                            sort_criteria,
+                            geo_strategy,
+                            terms_matching_strategy,
+                        )?;
+                        insert_should_clauses(&mut ranking_rules, &should_clauses);
+                        ranking_rules
+                    }
+                };
+
+                if !universe_resolved {
+                    universe &= resolve_universe(
+                        ctx,
+                        &universe,
+                        &graph,
+                        terms_matching_strategy,
+                        query_graph_logger,
+                    )?;
+                }
+
+                bucket_sort(
+                    ctx,
+                    ranking_rules,
+                    &graph,
+                    &universe,
+                    from,
+                    length,
+                    scoring_strategy,
+                    ranking_score_threshold,
+                    time_budget,
+                    search_after,
+                    group_by,
+                    query_graph_logger,
+                )?
+            }
+        }
     } else {
-        let ranking_rules =
-            get_ranking_rules_for_placeholder_search(ctx, sort_criteria, geo_strategy)?;
-        bucket_sort(
+        let ranking_rules = get_ranking_rules_for_placeholder_search(
             ctx,
-            ranking_rules,
-            &PlaceholderQuery,
-            &universe,
-            from,
-            length,
-            scoring_strategy,
-            placeholder_search_logger,
-        )?
+            sort_criteria,
+            geo_strategy,
+            &should_clauses,
+        )?;
+
+        // The buckets of the first ranking rule are ranked in parallel when there are many
+        // candidates, unless the documents of a bucket depend on the previous buckets or the
+        // ranking rules can't be built again for another search context.
+        let rank_in_parallel = universe.len() >= PARALLEL_BUCKET_SORT_THRESHOLD
+            && ranking_rules.len() > 1
+            && rayon::current_num_threads() > 1
+            && ranking_score_threshold.is_none()
+            && search_after.is_none()
+            && group_by.is_none()
+            && ctx.distinct_fid()?.is_none()
+            && ctx.query_rules.as_ref().map_or(true, |query_rules| !query_rules.reorders());
+
+        if rank_in_parallel {
+            parallel_bucket_sort(
+                ctx,
+                ranking_rules,
+                &universe,
+                from,
+                length,
+                scoring_strategy,
+                time_budget,
+                |ctx| {
+                    get_ranking_rules_for_placeholder_search(
+                        ctx,
+                        sort_criteria,
+                        geo_strategy,
+                        &should_clauses,
+                    )
+                },
+                placeholder_search_logger,
+            )?
+        } else {
+            bucket_sort(
+                ctx,
+                ranking_rules,
+                &PlaceholderQuery,
+                &universe,
+                from,
+                length,
+                scoring_strategy,
+                ranking_score_threshold,
+                time_budget,
+                search_after,
+                group_by,
+                placeholder_search_logger,
+            )?
+        }
     };
 
-    let BucketSortOutput { docids, scores, mut all_candidates } = bucket_sort_output;
-    let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
+    let BucketSortOutput { docids, scores, mut all_candidates, degraded } = bucket_sort_output;
 
     // The candidates is the universe unless the exhaustive number of hits
     // is requested and a distinct attribute is set.
     if exhaustive_number_hits {
-        if let Some(f) = ctx.index.distinct_field(ctx.txn)? {
-            if let Some(distinct_fid) = fields_ids_map.id(f) {
-                all_candidates = apply_distinct_rule(ctx, distinct_fid, &all_candidates)?.remaining;
-            }
+        if let Some(distinct_fid) = ctx.distinct_fid()? {
+            all_candidates = apply_distinct_rule(ctx, distinct_fid, &all_candidates)?.remaining;
         }
     }
 
@@ -672,6 +1315,7 @@ pub fn execute_search(
         document_scores: scores,
         documents_ids: docids,
         located_query_terms,
+        degraded,
     })
 }
 
@@ -698,7 +1342,9 @@ fn check_sort_criteria(ctx: &SearchContext, sort_criteria: Option<&Vec<AscDesc>>
     let sortable_fields = ctx.index.sortable_fields(ctx.txn)?;
     for asc_desc in sort_criteria {
         match asc_desc.member() {
-            Member::Field(ref field) if !crate::is_faceted(field, &sortable_fields) => {
+            Member::Field(ref field) | Member::ModifiedField(ref field, _)
+                if !crate::is_faceted(field, &sortable_fields) =>
+            {
                 let (valid_fields, hidden_fields) =
                     ctx.index.remove_hidden_fields(ctx.txn, sortable_fields)?;
 
@@ -732,4 +1378,5 @@ pub struct PartialSearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    pub degraded: bool,
 }
@@ -1,13 +1,15 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 
 use fxhash::{FxHashMap, FxHasher};
+use roaring::RoaringBitmap;
 
 use super::interner::{FixedSizeInterner, Interned};
 use super::query_term::{
     self, number_of_typos_allowed, LocatedQueryTerm, LocatedQueryTermSubset, QueryTermSubset,
 };
+use super::resolve_query_graph::compute_query_term_subset_docids;
 use super::small_bitmap::SmallBitmap;
 use super::SearchContext;
 use crate::search::new::interner::Interner;
@@ -98,6 +100,7 @@ impl QueryGraph {
         let mut new_located_query_terms = terms.to_vec();
 
         let nbr_typos = number_of_typos_allowed(ctx)?;
+        let max_synonym_words = ctx.max_synonym_words()?;
 
         let mut nodes_data: Vec<QueryNodeData> = vec![QueryNodeData::Start, QueryNodeData::End];
         let root_node = 0;
@@ -121,10 +124,12 @@ impl QueryGraph {
             );
             new_nodes.push(new_node_idx);
 
+            let mut ngram_lengths = vec![];
             if !prev1.is_empty() {
                 if let Some(ngram) =
                     query_term::make_ngram(ctx, &terms[term_idx - 1..=term_idx], &nbr_typos)?
                 {
+                    ngram_lengths.push(2);
                     new_located_query_terms.push(ngram.clone());
                     let ngram_idx = add_node(
                         &mut nodes_data,
@@ -141,6 +146,7 @@ impl QueryGraph {
                 if let Some(ngram) =
                     query_term::make_ngram(ctx, &terms[term_idx - 2..=term_idx], &nbr_typos)?
                 {
+                    ngram_lengths.push(3);
                     new_located_query_terms.push(ngram.clone());
                     let ngram_idx = add_node(
                         &mut nodes_data,
@@ -153,6 +159,27 @@ impl QueryGraph {
                     new_nodes.push(ngram_idx);
                 }
             }
+            // The synonyms of the phrases that the ngrams can't hold, ending with this term
+            for len in 2..=max_synonym_words.min(term_idx + 1) {
+                if ngram_lengths.contains(&len) {
+                    continue;
+                }
+                let start_idx = term_idx + 1 - len;
+                if let Some(synonyms) =
+                    query_term::make_synonyms_term(ctx, &terms[start_idx..=term_idx])?
+                {
+                    new_located_query_terms.push(synonyms.clone());
+                    let synonyms_idx = add_node(
+                        &mut nodes_data,
+                        QueryNodeData::Term(LocatedQueryTermSubset {
+                            term_subset: QueryTermSubset::full(synonyms.value),
+                            positions: synonyms.positions,
+                            term_ids: start_idx as u8..=term_idx as u8,
+                        }),
+                    );
+                    new_nodes.push(synonyms_idx);
+                }
+            }
             (prev0, prev1, prev2) = (new_nodes, prev0, prev1);
         }
 
@@ -319,6 +346,58 @@ impl QueryGraph {
             let rank = 1 + last_term_idx - term_idx;
             rank as u16
         };
+        self.removal_order_for_terms_matching_strategy(ctx, cost_of_term_idx)
+    }
+
+    /// Returns the nodes to remove from the most frequent terms of the query to the least
+    /// frequent ones, the terms matching no document being removed first.
+    pub fn removal_order_for_terms_matching_strategy_frequency(
+        &self,
+        ctx: &mut SearchContext,
+    ) -> Result<Vec<SmallBitmap<QueryNode>>> {
+        let mut term_docids = BTreeMap::<u8, RoaringBitmap>::new();
+        for (_, node) in self.nodes.iter() {
+            let QueryNodeData::Term(t) = &node.data else { continue };
+            let docids = compute_query_term_subset_docids(ctx, &t.term_subset)?;
+            for id in t.term_ids.clone() {
+                *term_docids.entry(id).or_default() |= &docids;
+            }
+        }
+        if term_docids.len() <= 1 {
+            return Ok(vec![]);
+        }
+
+        let mut term_frequencies: Vec<_> = term_docids
+            .into_iter()
+            .map(|(id, docids)| match docids.len() {
+                0 => (id, u64::MAX),
+                frequency => (id, frequency),
+            })
+            .collect();
+        term_frequencies.sort_by_key(|&(_, frequency)| Reverse(frequency));
+
+        // The terms sharing the same frequency are removed together.
+        let mut term_costs = BTreeMap::new();
+        let mut cost = 0;
+        let mut previous_frequency = None;
+        for (id, frequency) in term_frequencies {
+            if previous_frequency != Some(frequency) {
+                cost += 1;
+                previous_frequency = Some(frequency);
+            }
+            term_costs.insert(id, cost);
+        }
+
+        Ok(self.removal_order_for_terms_matching_strategy(ctx, |term_idx| term_costs[&term_idx]))
+    }
+
+    /// Groups the nodes to remove by the cost of their terms, the nodes with the lowest cost
+    /// being removed first, while keeping the costliest ones when all the terms are optional.
+    fn removal_order_for_terms_matching_strategy(
+        &self,
+        ctx: &SearchContext,
+        cost_of_term_idx: impl Fn(u8) -> u16,
+    ) -> Vec<SmallBitmap<QueryNode>> {
         let mut nodes_to_remove = BTreeMap::<u16, SmallBitmap<QueryNode>>::new();
         let mut at_least_one_mandatory_term = false;
         for (node_id, node) in self.nodes.iter() {
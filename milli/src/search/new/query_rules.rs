@@ -0,0 +1,214 @@
+use roaring::{MultiOps, RoaringBitmap};
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::query_rules::QueryRule;
+use crate::score_details::{Rank, ScoreDetails};
+use crate::{DocumentId, Filter, Index, Result};
+
+/// The consequences of the query rules of an index triggered by a search.
+#[derive(Debug, Default)]
+pub struct AppliedQueryRules {
+    /// The ids of the rules restricting the documents of the search.
+    filtering: Vec<String>,
+    /// The pinned documents, in order, with the id of the rule pinning them,
+    /// `None` for the documents pinned by the search itself.
+    pinned: Vec<(DocumentId, Option<String>)>,
+    /// The documents boosted by each rule, with the id of the rule.
+    boosted: Vec<(String, RoaringBitmap)>,
+    /// The documents the search is restricted to, `None` if no rule filters the documents.
+    candidates: Option<RoaringBitmap>,
+}
+
+impl AppliedQueryRules {
+    /// Evaluates the query rules of the index triggered by a search with this query and filter.
+    pub fn new(
+        index: &Index,
+        rtxn: &heed::RoTxn,
+        query: Option<&str>,
+        filter: &Option<Filter>,
+    ) -> Result<Self> {
+        let mut applied = AppliedQueryRules::default();
+        let external_documents_ids = index.external_documents_ids();
+
+        for rule in index.query_rules(rtxn)? {
+            if !Self::is_triggered(&rule, query, filter)? {
+                continue;
+            }
+
+            let QueryRule { id, conditions: _, consequences } = rule;
+            for external_id in &consequences.pin {
+                // the unknown documents are ignored, a document is only pinned once
+                if let Some(docid) = external_documents_ids.get(rtxn, external_id)? {
+                    if applied.pinned.iter().all(|(pinned, _)| *pinned != docid) {
+                        applied.pinned.push((docid, Some(id.clone())));
+                    }
+                }
+            }
+            if let Some(rule_filter) = consequences.filter.as_deref().map(Filter::from_str) {
+                if let Some(rule_filter) = rule_filter? {
+                    let docids = rule_filter.evaluate(rtxn, index)?;
+                    match &mut applied.candidates {
+                        Some(candidates) => *candidates &= docids,
+                        None => applied.candidates = Some(docids),
+                    }
+                    applied.filtering.push(id.clone());
+                }
+            }
+            if let Some(boost) = consequences.boost.as_deref().map(Filter::from_str) {
+                if let Some(boost) = boost? {
+                    applied.boosted.push((id, boost.evaluate(rtxn, index)?));
+                }
+            }
+        }
+
+        Ok(applied)
+    }
+
+    fn is_triggered(
+        rule: &QueryRule,
+        query: Option<&str>,
+        filter: &Option<Filter>,
+    ) -> Result<bool> {
+        let conditions = &rule.conditions;
+        if let Some(pattern) = &conditions.query {
+            if !conditions.anchoring.matches(query.unwrap_or_default(), pattern) {
+                return Ok(false);
+            }
+        }
+        if let Some(condition) = conditions.filter.as_deref().map(Filter::from_str) {
+            match (condition?, filter) {
+                (Some(condition), Some(filter)) => return Ok(filter.contains_condition(&condition)),
+                (Some(_), None) => return Ok(false),
+                (None, _) => (),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Pins the documents with these external ids before the documents pinned by the rules,
+    /// the unknown documents being ignored.
+    pub fn pin_documents(
+        &mut self,
+        index: &Index,
+        rtxn: &heed::RoTxn,
+        external_ids: &[String],
+    ) -> Result<()> {
+        let external_documents_ids = index.external_documents_ids();
+        let mut pinned = Vec::new();
+        for external_id in external_ids {
+            if let Some(docid) = external_documents_ids.get(rtxn, external_id)? {
+                if pinned.iter().all(|(pinned, _)| *pinned != docid) {
+                    pinned.push((docid, None));
+                }
+            }
+        }
+        self.pinned.retain(|(docid, _)| pinned.iter().all(|(pinned, _)| pinned != docid));
+        pinned.append(&mut self.pinned);
+        self.pinned = pinned;
+        Ok(())
+    }
+
+    /// The documents the search must be restricted to, `None` if no rule filters the documents.
+    pub fn candidates(&self) -> Option<&RoaringBitmap> {
+        self.candidates.as_ref()
+    }
+
+    /// Returns `true` if some documents must be ranked before the other ones.
+    pub fn reorders(&self) -> bool {
+        !self.pinned.is_empty() || !self.boosted.is_empty()
+    }
+
+    /// The ids of the rules applied to the document.
+    pub fn rules_of(&self, docid: DocumentId) -> Vec<String> {
+        let mut rules = self.filtering.clone();
+        let pinning = self.pinned.iter().filter(|(pinned, _)| *pinned == docid);
+        let boosting = self.boosted.iter().filter(|(_, boosted)| boosted.contains(docid));
+        for id in pinning.filter_map(|(_, id)| id.as_ref()).chain(boosting.map(|(id, _)| id)) {
+            if !rules.contains(id) {
+                rules.push(id.clone());
+            }
+        }
+        rules
+    }
+}
+
+/// A ranking rule returning the documents pinned by the query rules one by one, in order,
+/// then the documents they boost and finally the other documents.
+///
+/// The pinned documents that don't match the search are ignored. Like a sort, the rule only
+/// orders the documents, its rank doesn't lower their ranking score.
+pub struct QueryRules<Query> {
+    pinned: Vec<DocumentId>,
+    boosted: RoaringBitmap,
+    original_query: Option<Query>,
+    /// The candidates and rank of each bucket, the first bucket being the last one.
+    buckets: Vec<(RoaringBitmap, u32)>,
+}
+
+impl<Query> QueryRules<Query> {
+    pub fn new(applied: &AppliedQueryRules) -> Self {
+        let pinned = applied.pinned.iter().map(|(docid, _)| *docid).collect();
+        let boosted = MultiOps::union(applied.boosted.iter().map(|(_, docids)| docids));
+        Self { pinned, boosted, original_query: None, buckets: Vec::new() }
+    }
+
+    fn max_rank(&self) -> u32 {
+        self.pinned.len() as u32 + 2
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for QueryRules<Query> {
+    fn id(&self) -> String {
+        "query_rules".to_owned()
+    }
+
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        let max_rank = self.max_rank();
+        let mut pinned = RoaringBitmap::new();
+        let mut buckets = Vec::new();
+        for (i, &docid) in self.pinned.iter().enumerate() {
+            if parent_candidates.contains(docid) {
+                pinned.insert(docid);
+                buckets.push((RoaringBitmap::from_iter([docid]), max_rank - i as u32));
+            }
+        }
+        buckets.push(((&self.boosted & parent_candidates) - pinned, 2));
+        buckets.reverse();
+
+        self.original_query = Some(parent_query.clone());
+        self.buckets = buckets;
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        let (candidates, rank) = match self.buckets.pop() {
+            Some((bucket, rank)) => (bucket & universe, rank),
+            None => (universe.clone(), 1),
+        };
+        let rank = Rank { rank, max_rank: self.max_rank() };
+
+        Ok(Some(RankingRuleOutput { query, candidates, score: ScoreDetails::QueryRules(rank) }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.buckets.clear();
+    }
+}
@@ -7,10 +7,12 @@ use fst::{Automaton, IntoStreamer, Streamer};
 use heed::types::DecodeIgnore;
 
 use super::*;
+use crate::romanization::{is_romanized, word_romanization_prefix};
 use crate::search::fst_utils::{Complement, Intersection, StartsWith, Union};
 use crate::search::new::query_term::TwoTypoTerm;
 use crate::search::new::{limits, SearchContext};
 use crate::search::{build_dfa, get_first};
+use crate::stemming::{stemmer, word_stem_prefix};
 use crate::{Result, MAX_WORD_LENGTH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -69,6 +71,59 @@ fn find_zero_typo_prefix_derivations(
     Ok(())
 }
 
+/// Returns the words of the index sharing the stem of the word in one of the stemmed languages.
+fn find_stem_derivations(ctx: &mut SearchContext, word: &str) -> Result<BTreeSet<String>> {
+    let mut derived_words = BTreeSet::new();
+    let locales = ctx.index.stemming(ctx.txn)?;
+    if locales.is_empty() {
+        return Ok(derived_words);
+    }
+
+    let words_fst = ctx.index.words_fst(ctx.txn)?;
+    let word_stems_fst = ctx.index.word_stems_fst(ctx.txn)?;
+    for locale in locales {
+        let Some(stemmer) = stemmer(&locale) else { continue };
+        let stem = stemmer.stem(word);
+        if stem != word && words_fst.contains(&*stem) {
+            derived_words.insert(stem.clone().into_owned());
+        }
+
+        let prefix = word_stem_prefix(&locale, &stem);
+        let mut stream = word_stems_fst.search(Str::new(&prefix).starts_with()).into_stream();
+        while let Some(key) = stream.next() {
+            if derived_words.len() >= limits::MAX_STEM_DERIVATIONS_COUNT {
+                return Ok(derived_words);
+            }
+            let derived_word = std::str::from_utf8(&key[prefix.len()..])?;
+            if derived_word != word {
+                derived_words.insert(derived_word.to_owned());
+            }
+        }
+    }
+
+    Ok(derived_words)
+}
+
+/// Returns the words of the index having the latin word as their transcription.
+fn find_romanization_derivations(ctx: &mut SearchContext, word: &str) -> Result<BTreeSet<String>> {
+    let mut derived_words = BTreeSet::new();
+    if !is_romanized(word) {
+        return Ok(derived_words);
+    }
+
+    let word_romanizations_fst = ctx.index.word_romanizations_fst(ctx.txn)?;
+    let prefix = word_romanization_prefix(word);
+    let mut stream = word_romanizations_fst.search(Str::new(&prefix).starts_with()).into_stream();
+    while let Some(key) = stream.next() {
+        if derived_words.len() >= limits::MAX_ROMANIZATION_DERIVATIONS_COUNT {
+            break;
+        }
+        derived_words.insert(std::str::from_utf8(&key[prefix.len()..])?.to_owned());
+    }
+
+    Ok(derived_words)
+}
+
 fn find_zero_one_typo_derivations(
     ctx: &mut SearchContext,
     word_interned: Interned<String>,
@@ -110,9 +165,12 @@ fn find_zero_one_typo_derivations(
     Ok(())
 }
 
+/// When `typo_on_first_character` is `false`, the derivations with a different first character
+/// are skipped, so that "mall" doesn't match "ball" even if two typos are allowed.
 fn find_zero_one_two_typo_derivations(
     word_interned: Interned<String>,
     is_prefix: bool,
+    typo_on_first_character: bool,
     fst: fst::Set<Cow<[u8]>>,
     word_interner: &mut DedupInterner<String>,
     mut visit: impl FnMut(Interned<String>, NumberOfTypos) -> Result<ControlFlow<()>>,
@@ -134,6 +192,9 @@ fn find_zero_one_two_typo_derivations(
         // in the case the typo is on the first letter, we know the number of typo
         // is two
         if get_first(derived_word) != get_first(word) {
+            if !typo_on_first_character {
+                continue;
+            }
             let cf = visit(derived_word_interned, NumberOfTypos::Two)?;
             if cf.is_break() {
                 break;
@@ -170,6 +231,90 @@ fn find_zero_one_two_typo_derivations(
     Ok(())
 }
 
+/// Finds the derivations of a word containing digits, like a model number.
+///
+/// The digits must be matched exactly: a typo is only the omission, the addition, or the
+/// transposition of letters, so that `xps9370` matches `xp9370` but never `xps9371`.
+fn find_numeric_typo_derivations(
+    word_interned: Interned<String>,
+    max_typos: u8,
+    fst: fst::Set<Cow<[u8]>>,
+    word_interner: &mut DedupInterner<String>,
+    mut visit: impl FnMut(Interned<String>, NumberOfTypos) -> Result<ControlFlow<()>>,
+) -> Result<()> {
+    let word = word_interner.get(word_interned).to_owned();
+    let word = word.as_str();
+
+    // the levenshtein automaton finds the candidates, among which the restricted
+    // distance keeps the derivations that only differ by their letters
+    let dfa = build_dfa(word, max_typos, false);
+    let mut stream = fst.search(&dfa).into_stream();
+
+    while let Some(derived_word) = stream.next() {
+        let derived_word = std::str::from_utf8(derived_word)?;
+        let nbr_typos = match numeric_typo_distance(word, derived_word) {
+            Some(1) => NumberOfTypos::One,
+            Some(2) if max_typos > 1 => NumberOfTypos::Two,
+            _ => continue,
+        };
+        let derived_word_interned = word_interner.insert(derived_word.to_owned());
+        let cf = visit(derived_word_interned, nbr_typos)?;
+        if cf.is_break() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub fn contains_digit(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Computes the number of letters to omit, add or transpose to go from the word to the
+/// derived word, or `None` if their digits differ.
+fn numeric_typo_distance(word: &str, derived_word: &str) -> Option<u8> {
+    let left: Vec<char> = word.chars().collect();
+    let right: Vec<char> = derived_word.chars().collect();
+    let is_letter = |c: char| !c.is_ascii_digit();
+
+    // an optimal string alignment distance without substitutions, the digits
+    // can't be edited
+    let mut distances = vec![vec![None; right.len() + 1]; left.len() + 1];
+    distances[0][0] = Some(0u8);
+    for i in 0..=left.len() {
+        for j in 0..=right.len() {
+            let mut distance: Option<u8> = distances[i][j];
+            let mut candidate = |d: Option<u8>| {
+                if let Some(d) = d {
+                    distance = Some(distance.map_or(d, |distance| distance.min(d)));
+                }
+            };
+            if i > 0 && j > 0 && left[i - 1] == right[j - 1] {
+                candidate(distances[i - 1][j - 1]);
+            }
+            if i > 0 && is_letter(left[i - 1]) {
+                candidate(distances[i - 1][j].map(|d| d.saturating_add(1)));
+            }
+            if j > 0 && is_letter(right[j - 1]) {
+                candidate(distances[i][j - 1].map(|d| d.saturating_add(1)));
+            }
+            if i > 1
+                && j > 1
+                && left[i - 1] == right[j - 2]
+                && left[i - 2] == right[j - 1]
+                && left[i - 1] != left[i - 2]
+                && is_letter(left[i - 1])
+                && is_letter(left[i - 2])
+            {
+                candidate(distances[i - 2][j - 2].map(|d| d.saturating_add(1)));
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[left.len()][right.len()]
+}
+
 pub fn partially_initialized_term_from_word(
     ctx: &mut SearchContext,
     word: &str,
@@ -233,11 +378,9 @@ pub fn partially_initialized_term_from_word(
             },
         )?;
     }
-    let synonyms = ctx.index.synonyms(ctx.txn)?;
+    let synonyms = ctx.words_synonyms(&[word])?;
     let mut synonym_word_count = 0;
-    let synonyms = synonyms
-        .get(&vec![word.to_owned()])
-        .cloned()
+    let mut synonyms: BTreeSet<_> = synonyms
         .unwrap_or_default()
         .into_iter()
         .take(limits::MAX_SYNONYM_PHRASE_COUNT)
@@ -247,9 +390,18 @@ pub fn partially_initialized_term_from_word(
             }
             synonym_word_count += words.len();
             let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            Some(ctx.phrase_interner.insert(Phrase { words }))
+            Some(ctx.phrase_interner.insert(Phrase { words, slop: 0 }))
         })
         .collect();
+
+    // The words sharing the stem of the word and the ones it is the transcription of
+    // match it like synonyms do, only the original word being an exact match.
+    let mut derived_words = find_stem_derivations(ctx, word)?;
+    derived_words.extend(find_romanization_derivations(ctx, word)?);
+    for derived_word in derived_words {
+        let words = vec![Some(ctx.word_interner.insert(derived_word))];
+        synonyms.insert(ctx.phrase_interner.insert(Phrase { words, slop: 0 }));
+    }
     let zero_typo =
         ZeroTypoTerm { phrase: None, exact: zero_typo, prefix_of, synonyms, use_prefix_db };
 
@@ -266,7 +418,7 @@ pub fn partially_initialized_term_from_word(
 
 fn find_split_words(ctx: &mut SearchContext, word: &str) -> Result<Option<Interned<Phrase>>> {
     if let Some((l, r)) = split_best_frequency(ctx, word)? {
-        Ok(Some(ctx.phrase_interner.insert(Phrase { words: vec![Some(l), Some(r)] })))
+        Ok(Some(ctx.phrase_interner.insert(Phrase { words: vec![Some(l), Some(r)], slop: 0 })))
     } else {
         Ok(None)
     }
@@ -293,7 +445,22 @@ impl Interned<QueryTerm> {
         }
         let mut one_typo_words = BTreeSet::new();
 
-        if *max_nbr_typos > 0 {
+        if *max_nbr_typos > 0 && contains_digit(ctx.word_interner.get(original)) {
+            find_numeric_typo_derivations(
+                original,
+                1,
+                ctx.index.words_fst(ctx.txn)?,
+                &mut ctx.word_interner,
+                |derived_word, _| {
+                    if one_typo_words.len() < limits::MAX_ONE_TYPO_COUNT {
+                        one_typo_words.insert(derived_word);
+                        Ok(ControlFlow::Continue(()))
+                    } else {
+                        Ok(ControlFlow::Break(()))
+                    }
+                },
+            )?;
+        } else if *max_nbr_typos > 0 {
             find_zero_one_typo_derivations(ctx, original, is_prefix, |derived_word, nbr_typos| {
                 match nbr_typos {
                     ZeroOrOneTypo::Zero => {}
@@ -324,7 +491,7 @@ impl Interned<QueryTerm> {
         let split_words = if let Some((ngram_words, split_words)) =
             self_mut.ngram_words.as_ref().zip(split_words.as_ref())
         {
-            let Phrase { words } = ctx.phrase_interner.get(*split_words);
+            let Phrase { words, .. } = ctx.phrase_interner.get(*split_words);
             if ngram_words.iter().ne(words.iter().flatten()) {
                 Some(*split_words)
             } else {
@@ -355,34 +522,47 @@ impl Interned<QueryTerm> {
         let mut one_typo_words = BTreeSet::new();
         let mut two_typo_words = BTreeSet::new();
 
-        if *max_nbr_typos > 0 {
+        let mut visit =
+            |derived_word: Interned<String>, nbr_typos: NumberOfTypos| -> Result<ControlFlow<()>> {
+                if one_typo_words.len() >= limits::MAX_ONE_TYPO_COUNT
+                    && two_typo_words.len() >= limits::MAX_TWO_TYPOS_COUNT
+                {
+                    // No chance we will add either one- or two-typo derivations anymore, stop iterating.
+                    return Ok(ControlFlow::Break(()));
+                }
+                match nbr_typos {
+                    NumberOfTypos::Zero => {}
+                    NumberOfTypos::One => {
+                        if one_typo_words.len() < limits::MAX_ONE_TYPO_COUNT {
+                            one_typo_words.insert(derived_word);
+                        }
+                    }
+                    NumberOfTypos::Two => {
+                        if two_typo_words.len() < limits::MAX_TWO_TYPOS_COUNT {
+                            two_typo_words.insert(derived_word);
+                        }
+                    }
+                }
+                Ok(ControlFlow::Continue(()))
+            };
+
+        if *max_nbr_typos > 0 && contains_digit(&original_str) {
+            find_numeric_typo_derivations(
+                *original,
+                *max_nbr_typos,
+                ctx.index.words_fst(ctx.txn)?,
+                &mut ctx.word_interner,
+                &mut visit,
+            )?;
+        } else if *max_nbr_typos > 0 {
+            let typo_on_first_character = !ctx.index.disable_typo_on_first_character(ctx.txn)?;
             find_zero_one_two_typo_derivations(
                 *original,
                 *is_prefix,
+                typo_on_first_character,
                 ctx.index.words_fst(ctx.txn)?,
                 &mut ctx.word_interner,
-                |derived_word, nbr_typos| {
-                    if one_typo_words.len() >= limits::MAX_ONE_TYPO_COUNT
-                        && two_typo_words.len() >= limits::MAX_TWO_TYPOS_COUNT
-                    {
-                        // No chance we will add either one- or two-typo derivations anymore, stop iterating.
-                        return Ok(ControlFlow::Break(()));
-                    }
-                    match nbr_typos {
-                        NumberOfTypos::Zero => {}
-                        NumberOfTypos::One => {
-                            if one_typo_words.len() < limits::MAX_ONE_TYPO_COUNT {
-                                one_typo_words.insert(derived_word);
-                            }
-                        }
-                        NumberOfTypos::Two => {
-                            if two_typo_words.len() < limits::MAX_TWO_TYPOS_COUNT {
-                                two_typo_words.insert(derived_word);
-                            }
-                        }
-                    }
-                    Ok(ControlFlow::Continue(()))
-                },
+                &mut visit,
             )?;
         }
 
@@ -7,10 +7,13 @@ use std::collections::BTreeSet;
 use std::iter::FromIterator;
 use std::ops::RangeInclusive;
 
-use compute_derivations::partially_initialized_term_from_word;
+use compute_derivations::{contains_digit, partially_initialized_term_from_word};
 use either::Either;
 pub use ntypo_subset::NTypoTermSubset;
-pub use parse_query::{located_query_terms_from_tokens, make_ngram, number_of_typos_allowed};
+pub use parse_query::{
+    located_query_terms_from_tokens, make_ngram, make_synonyms_term, negative_phrase_from_tokens,
+    number_of_typos_allowed, split_negative_query,
+};
 pub use phrase::Phrase;
 
 use super::interner::{DedupInterner, Interned};
@@ -469,6 +472,77 @@ impl LocatedQueryTerm {
     }
 }
 
+/// Returns the query where the words that don't exist in the index are replaced by their
+/// typo derivation with the fewest typos, then with the most documents.
+///
+/// Returns `None` when none of the words of the query needed to be corrected.
+pub fn corrected_query(
+    ctx: &mut SearchContext,
+    located_query_terms: &[LocatedQueryTerm],
+) -> Result<Option<String>> {
+    // the ngrams are appended after the terms of the query, they are not written by the user
+    let mut terms: Vec<_> = located_query_terms
+        .iter()
+        .filter(|term| ctx.term_interner.get(term.value).ngram_words.is_none())
+        .collect();
+    terms.sort_by_key(|term| *term.positions.start());
+
+    let mut corrected = false;
+    let mut words = Vec::with_capacity(terms.len());
+    for term in terms {
+        let value = term.value;
+        let QueryTerm { original, max_levenshtein_distance, zero_typo, .. } =
+            ctx.term_interner.get(value);
+        let original = *original;
+        if let Some(phrase) = zero_typo.phrase {
+            words.push(format!("\"{}\"", phrase.description(ctx)));
+            continue;
+        }
+        let is_known = zero_typo.exact.is_some()
+            || zero_typo.use_prefix_db.is_some()
+            || !zero_typo.prefix_of.is_empty()
+            || !zero_typo.synonyms.is_empty();
+        if is_known || *max_levenshtein_distance == 0 {
+            words.push(ctx.word_interner.get(original).clone());
+            continue;
+        }
+
+        value.compute_fully_if_needed(ctx)?;
+        let term = ctx.term_interner.get(value);
+        let mut derivations = Vec::new();
+        if let Lazy::Init(OneTypoTerm { one_typo, .. }) = &term.one_typo {
+            derivations.extend(one_typo.iter().map(|word| (1, *word)));
+        }
+        if let Lazy::Init(TwoTypoTerm { two_typos }) = &term.two_typo {
+            derivations.extend(two_typos.iter().map(|word| (2, *word)));
+        }
+
+        let mut best: Option<(u8, u64, Interned<String>)> = None;
+        for (typos, word) in derivations {
+            let count = ctx.word_docids(Word::Derived(word))?.map_or(0, |docids| docids.len());
+            let is_better = match best {
+                Some((best_typos, best_count, _)) => {
+                    typos < best_typos || (typos == best_typos && count > best_count)
+                }
+                None => true,
+            };
+            if is_better {
+                best = Some((typos, count, word));
+            }
+        }
+
+        match best {
+            Some((_, _, word)) => {
+                corrected = true;
+                words.push(ctx.word_interner.get(word).clone());
+            }
+            None => words.push(ctx.word_interner.get(original).clone()),
+        }
+    }
+
+    Ok(corrected.then(|| words.join(" ")))
+}
+
 impl QueryTerm {
     pub fn is_cached_prefix(&self) -> bool {
         self.zero_typo.use_prefix_db.is_some()
@@ -1,13 +1,16 @@
-use charabia::normalizer::NormalizedTokenIter;
-use charabia::{SeparatorKind, TokenKind};
+use charabia::{SeparatorKind, Token, TokenKind};
 
 use super::*;
 use crate::{Result, SearchContext, MAX_WORD_LENGTH};
 
 /// Convert the tokenised search query into a list of located query terms.
-pub fn located_query_terms_from_tokens(
+///
+/// A phrase followed by `~N`, like `"quick fox"~2`, allows up to N words between its words,
+/// the `text` of the query being used to read this suffix.
+pub fn located_query_terms_from_tokens<'o>(
     ctx: &mut SearchContext,
-    query: NormalizedTokenIter,
+    text: &str,
+    query: impl Iterator<Item = Token<'o>>,
     words_limit: Option<usize>,
 ) -> Result<Vec<LocatedQueryTerm>> {
     let nbr_typos = number_of_typos_allowed(ctx)?;
@@ -15,15 +18,19 @@ pub fn located_query_terms_from_tokens(
     let mut located_terms = Vec::new();
 
     let mut phrase: Option<PhraseBuilder> = None;
+    let default_slop = ctx.phrase_slop;
 
     let parts_limit = words_limit.unwrap_or(usize::MAX);
 
     // start with the last position as we will wrap around to position 0 at the beginning of the loop below.
     let mut position = u16::MAX;
 
+    // the end of the `~N` slop suffix of the last phrase, its tokens being skipped.
+    let mut slop_end = 0;
+
     let mut peekable = query.take(super::limits::MAX_TOKEN_COUNT).peekable();
     while let Some(token) = peekable.next() {
-        if token.lemma().is_empty() {
+        if token.lemma().is_empty() || token.byte_start < slop_end {
             continue;
         }
         // early return if word limit is exceeded
@@ -88,7 +95,7 @@ pub fn located_query_terms_from_tokens(
                     // If we have a hard separator inside a phrase, we immediately start a new phrase
                     let phrase = if separator_kind == SeparatorKind::Hard {
                         if let Some(phrase) = phrase {
-                            if let Some(located_query_term) = phrase.build(ctx) {
+                            if let Some(located_query_term) = phrase.build(ctx, default_slop) {
                                 located_terms.push(located_query_term)
                             }
                             Some(PhraseBuilder::empty())
@@ -109,7 +116,14 @@ pub fn located_query_terms_from_tokens(
                     if let Some(phrase) = phrase {
                         // Per the check above, quote_count > 0
                         quote_count -= 1;
-                        if let Some(located_query_term) = phrase.build(ctx) {
+                        let slop = match phrase_slop_suffix(text, &token) {
+                            Some((slop, end)) => {
+                                slop_end = end;
+                                slop
+                            }
+                            None => default_slop,
+                        };
+                        if let Some(located_query_term) = phrase.build(ctx, slop) {
                             located_terms.push(located_query_term)
                         }
                     }
@@ -124,7 +138,7 @@ pub fn located_query_terms_from_tokens(
 
     // If a quote is never closed, we consider all of the end of the query as a phrase.
     if let Some(phrase) = phrase.take() {
-        if let Some(located_query_term) = phrase.build(ctx) {
+        if let Some(located_query_term) = phrase.build(ctx, default_slop) {
             located_terms.push(located_query_term);
         }
     }
@@ -132,10 +146,87 @@ pub fn located_query_terms_from_tokens(
     Ok(located_terms)
 }
 
+/// Returns the slop following the closing quote of the token, `"~N`, along with the byte
+/// offset of the end of this suffix in the text.
+fn phrase_slop_suffix(text: &str, closing_token: &Token) -> Option<(u8, usize)> {
+    let token_text = text.get(closing_token.byte_start..closing_token.byte_end)?;
+    let quote_end = closing_token.byte_start + token_text.find('"')? + 1;
+    let suffix = text[quote_end..].strip_prefix('~')?;
+    let digits_len = suffix.find(|c: char| !c.is_ascii_digit()).unwrap_or(suffix.len());
+    let slop = suffix[..digits_len].parse::<u32>().ok()?;
+    Some((slop.min(u8::MAX as u32) as u8, quote_end + 1 + digits_len))
+}
+
+/// Splits the negative words and phrases out of the search query.
+///
+/// A negative word, `-word`, or phrase, `-"exact phrase"`, is prefixed by a `-` at the
+/// beginning of the query or after a whitespace, outside of a quoted phrase.
+/// Returns the remaining positive query along with the negative words and phrases.
+pub fn split_negative_query(query: &str) -> (String, Vec<&str>) {
+    let mut positive = String::with_capacity(query.len());
+    let mut negatives = Vec::new();
+    let mut in_phrase = false;
+    let mut after_whitespace = true;
+
+    let mut rest = query;
+    while let Some(c) = rest.chars().next() {
+        if c == '-' && after_whitespace && !in_phrase {
+            let after_dash = &rest[1..];
+            let (negative, after_negative) = match after_dash.strip_prefix('"') {
+                Some(phrase) => match phrase.find('"') {
+                    Some(end) => (&phrase[..end], &phrase[end + 1..]),
+                    // If a quote is never closed, the end of the query is the phrase.
+                    None => (phrase, ""),
+                },
+                None => {
+                    let end = after_dash.find(char::is_whitespace).unwrap_or(after_dash.len());
+                    after_dash.split_at(end)
+                }
+            };
+            if !negative.trim().is_empty() {
+                negatives.push(negative);
+                // keep the surrounding words apart
+                positive.push(' ');
+                rest = after_negative;
+                continue;
+            }
+        }
+
+        if c == '"' {
+            in_phrase = !in_phrase;
+        }
+        after_whitespace = c.is_whitespace();
+        positive.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    (positive, negatives)
+}
+
+/// Convert a tokenised negative word or phrase into a phrase matching the documents to exclude.
+pub fn negative_phrase_from_tokens<'o>(
+    ctx: &mut SearchContext,
+    query: impl Iterator<Item = Token<'o>>,
+) -> Option<Interned<Phrase>> {
+    let mut phrase = PhraseBuilder::empty();
+    for token in query.take(super::limits::MAX_TOKEN_COUNT) {
+        if !token.lemma().is_empty() && matches!(token.kind, TokenKind::Word | TokenKind::StopWord)
+        {
+            phrase.push_word(ctx, &token, 0);
+        }
+    }
+    if phrase.is_empty() {
+        None
+    } else {
+        Some(ctx.phrase_interner.insert(Phrase { words: phrase.words, slop: 0 }))
+    }
+}
+
 pub fn number_of_typos_allowed<'ctx>(
     ctx: &SearchContext<'ctx>,
 ) -> Result<impl Fn(&str) -> u8 + 'ctx> {
     let authorize_typos = ctx.index.authorize_typos(ctx.txn)?;
+    let authorize_typos_on_numbers = ctx.index.authorize_typos_on_numbers(ctx.txn)?;
     let min_len_one_typo = ctx.index.min_word_len_one_typo(ctx.txn)?;
     let min_len_two_typos = ctx.index.min_word_len_two_typos(ctx.txn)?;
 
@@ -143,6 +234,7 @@ pub fn number_of_typos_allowed<'ctx>(
 
     Ok(Box::new(move |word: &str| {
         if !authorize_typos
+            || (!authorize_typos_on_numbers && contains_digit(word))
             || word.len() < min_len_one_typo as usize
             || exact_words.as_ref().map_or(false, |fst| fst.contains(word))
         {
@@ -199,14 +291,12 @@ pub fn make_ngram(
         partially_initialized_term_from_word(ctx, &ngram_str, max_nbr_typos, is_prefix, true)?;
 
     // Now add the synonyms
-    let index_synonyms = ctx.index.synonyms(ctx.txn)?;
+    let synonyms = ctx.words_synonyms(&words)?;
 
-    term.zero_typo.synonyms.extend(
-        index_synonyms.get(&words).cloned().unwrap_or_default().into_iter().map(|words| {
-            let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            ctx.phrase_interner.insert(Phrase { words })
-        }),
-    );
+    term.zero_typo.synonyms.extend(synonyms.unwrap_or_default().into_iter().map(|words| {
+        let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
+        ctx.phrase_interner.insert(Phrase { words, slop: 0 })
+    }));
 
     let term = QueryTerm {
         original: ngram_str_interned,
@@ -223,6 +313,71 @@ pub fn make_ngram(
     Ok(Some(term))
 }
 
+/// Builds the term matching the synonyms of a phrase made of consecutive words of the query.
+///
+/// Contrary to an ngram, the words are not concatenated and only the synonyms are matched,
+/// each synonym phrase as a whole so that the proximity between its words is kept. It allows
+/// the synonyms of phrases longer than the ngrams, or whose concatenated words would be too long.
+pub fn make_synonyms_term(
+    ctx: &mut SearchContext,
+    terms: &[LocatedQueryTerm],
+) -> Result<Option<LocatedQueryTerm>> {
+    assert!(terms.len() > 1);
+    for t in terms {
+        if ctx.term_interner.get(t.value).zero_typo.phrase.is_some() {
+            return Ok(None);
+        }
+    }
+    for ts in terms.windows(2) {
+        let [t1, t2] = ts else { panic!() };
+        if *t1.positions.end() != t2.positions.start() - 1 {
+            return Ok(None);
+        }
+    }
+    let mut words_interned = vec![];
+    for term in terms {
+        if let Some(original_term_word) = term.value.original_single_word(ctx) {
+            words_interned.push(original_term_word);
+        } else {
+            return Ok(None);
+        }
+    }
+    let words =
+        words_interned.iter().map(|&i| ctx.word_interner.get(i).to_owned()).collect::<Vec<_>>();
+
+    let Some(synonyms) = ctx.words_synonyms(&words)? else {
+        return Ok(None);
+    };
+    let synonyms = synonyms
+        .into_iter()
+        .take(super::limits::MAX_SYNONYM_PHRASE_COUNT)
+        .map(|words| {
+            let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
+            ctx.phrase_interner.insert(Phrase { words, slop: 0 })
+        })
+        .collect();
+
+    let start = *terms.first().as_ref().unwrap().positions.start();
+    let end = *terms.last().as_ref().unwrap().positions.end();
+    let term = QueryTerm {
+        original: ctx.word_interner.insert(words.join(" ")),
+        ngram_words: Some(words_interned),
+        is_prefix: false,
+        max_levenshtein_distance: 0,
+        zero_typo: ZeroTypoTerm {
+            phrase: None,
+            exact: None,
+            prefix_of: BTreeSet::default(),
+            synonyms,
+            use_prefix_db: None,
+        },
+        one_typo: Lazy::Init(OneTypoTerm::default()),
+        two_typo: Lazy::Init(TwoTypoTerm::default()),
+    };
+
+    Ok(Some(LocatedQueryTerm { value: ctx.term_interner.push(term), positions: start..=end }))
+}
+
 struct PhraseBuilder {
     words: Vec<Option<Interned<String>>>,
     start: u16,
@@ -253,13 +408,13 @@ impl PhraseBuilder {
         }
     }
 
-    fn build(self, ctx: &mut SearchContext) -> Option<LocatedQueryTerm> {
+    fn build(self, ctx: &mut SearchContext, slop: u8) -> Option<LocatedQueryTerm> {
         if self.is_empty() {
             return None;
         }
         Some(LocatedQueryTerm {
             value: ctx.term_interner.push({
-                let phrase = ctx.phrase_interner.insert(Phrase { words: self.words });
+                let phrase = ctx.phrase_interner.insert(Phrase { words: self.words, slop });
                 let phrase_desc = phrase.description(ctx);
                 QueryTerm {
                     original: ctx.word_interner.insert(phrase_desc),
@@ -310,8 +465,29 @@ mod tests {
         let rtxn = index.read_txn()?;
         let mut ctx = SearchContext::new(&index, &rtxn);
         // panics with `attempt to add with overflow` before <https://github.com/meilisearch/meilisearch/issues/3785>
-        let located_query_terms = located_query_terms_from_tokens(&mut ctx, tokens, None)?;
+        let located_query_terms = located_query_terms_from_tokens(&mut ctx, ".", tokens, None)?;
         assert!(located_query_terms.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn split_negative_words_and_phrases() {
+        let (positive, negatives) = split_negative_query("hello -world");
+        assert_eq!(positive, "hello  ");
+        assert_eq!(negatives, vec!["world"]);
+
+        let (positive, negatives) = split_negative_query(r#"-"the quick" fox -jumps over"#);
+        assert_eq!(positive, "  fox   over");
+        assert_eq!(negatives, vec!["the quick", "jumps"]);
+
+        // the dashes inside the words and the phrases are kept
+        let (positive, negatives) = split_negative_query(r#"e-mail "-not negative" - -"#);
+        assert_eq!(positive, r#"e-mail "-not negative" - -"#);
+        assert!(negatives.is_empty());
+
+        // an unclosed quote makes the end of the query a phrase
+        let (positive, negatives) = split_negative_query(r#"fox -"lazy dog"#);
+        assert_eq!(positive, "fox  ");
+        assert_eq!(negatives, vec!["lazy dog"]);
+    }
 }
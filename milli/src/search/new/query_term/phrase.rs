@@ -4,10 +4,13 @@ use crate::search::new::interner::Interned;
 use crate::SearchContext;
 
 /// A phrase in the user's search query, consisting of several words
-/// that must appear side-by-side in the search results.
+/// that must appear side-by-side in the search results, unless the
+/// phrase allows a slop.
 #[derive(Default, Clone, PartialEq, Eq, Hash)]
 pub struct Phrase {
     pub words: Vec<Option<Interned<String>>>,
+    /// The number of words allowed between the words of the phrase.
+    pub slop: u8,
 }
 impl Interned<Phrase> {
     pub fn description(self, ctx: &SearchContext) -> String {
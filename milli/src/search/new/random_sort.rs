@@ -0,0 +1,108 @@
+use roaring::RoaringBitmap;
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::score_details::{self, ScoreDetails};
+use crate::Result;
+
+/// A ranking rule returning the candidates one by one in a pseudo-random order
+/// derived from a seed, written `_random(seed)` in the sort of a search.
+///
+/// The key of a document only depends on the seed and on the document id, so
+/// two documents are always returned in the same relative order for a given seed,
+/// whatever the other candidates are.
+pub struct RandomSort<Query> {
+    seed: u64,
+    is_ascending: bool,
+    original_query: Option<Query>,
+    /// The candidates with their key, the next one to return being the last.
+    candidates: Vec<(u64, u32)>,
+}
+
+impl<Query> RandomSort<Query> {
+    pub fn new(seed: u64, is_ascending: bool) -> Self {
+        Self { seed, is_ascending, original_query: None, candidates: Vec::new() }
+    }
+
+    fn score(&self, value: serde_json::Value) -> ScoreDetails {
+        ScoreDetails::Sort(score_details::Sort {
+            field_name: format!("_random({})", self.seed),
+            ascending: self.is_ascending,
+            redacted: false,
+            value,
+        })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for RandomSort<Query> {
+    fn id(&self) -> String {
+        let Self { seed, is_ascending, .. } = self;
+        format!("_random({seed}):{}", if *is_ascending { "asc" } else { "desc" })
+    }
+
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        let mut candidates: Vec<_> =
+            parent_candidates.iter().map(|docid| (random_key(self.seed, docid), docid)).collect();
+
+        // the next candidates are sorted last to be popped first
+        if self.is_ascending {
+            candidates.sort_unstable_by(|left, right| right.cmp(left));
+        } else {
+            candidates.sort_unstable();
+        }
+
+        self.original_query = Some(parent_query.clone());
+        self.candidates = candidates;
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+
+        // the candidates removed from the universe, e.g. by the distinct rule, are skipped
+        while let Some((key, docid)) = self.candidates.pop() {
+            if universe.contains(docid) {
+                return Ok(Some(RankingRuleOutput {
+                    query,
+                    candidates: RoaringBitmap::from_iter([docid]),
+                    score: self.score(key.into()),
+                }));
+            }
+        }
+
+        Ok(Some(RankingRuleOutput {
+            query,
+            candidates: universe.clone(),
+            score: self.score(serde_json::Value::Null),
+        }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.candidates.clear();
+    }
+}
+
+/// Mixes the seed and the document id into a pseudo-random key,
+/// with the finalizer of the SplitMix64 generator.
+fn random_key(seed: u64, docid: u32) -> u64 {
+    let mut z = seed.wrapping_add((docid as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
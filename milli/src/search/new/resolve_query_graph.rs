@@ -10,8 +10,9 @@ use super::query_graph::QueryNodeData;
 use super::query_term::{Phrase, QueryTermSubset};
 use super::small_bitmap::SmallBitmap;
 use super::{QueryGraph, SearchContext, Word};
+use crate::proximity::MAX_DISTANCE;
 use crate::search::new::query_term::LocatedQueryTermSubset;
-use crate::Result;
+use crate::{bucketed_position, Result};
 
 #[derive(Default)]
 pub struct PhraseDocIdsCache {
@@ -181,7 +182,7 @@ pub fn compute_phrase_docids(
     ctx: &mut SearchContext,
     phrase: Interned<Phrase>,
 ) -> Result<RoaringBitmap> {
-    let Phrase { words } = ctx.phrase_interner.get(phrase).clone();
+    let Phrase { words, slop } = ctx.phrase_interner.get(phrase).clone();
 
     if words.is_empty() {
         return Ok(RoaringBitmap::new());
@@ -211,7 +212,9 @@ pub fn compute_phrase_docids(
                 .enumerate()
                 .filter_map(|(index, word)| word.as_ref().map(|word| (index, word)))
             {
-                if dist == 0 {
+                // The slop allows some more words between the words of the phrase.
+                let max_distance = dist + 1 + slop as usize;
+                if max_distance == 1 {
                     match ctx.get_db_word_pair_proximity_docids(s1, s2, 1)? {
                         Some(m) => bitmaps.push(m),
                         // If there are no documents for this pair, there will be no
@@ -220,13 +223,17 @@ pub fn compute_phrase_docids(
                     }
                 } else {
                     let mut bitmap = RoaringBitmap::new();
-                    for dist in 0..=dist {
+                    for proximity in 1..=max_distance.min(MAX_DISTANCE as usize - 1) {
                         if let Some(m) =
-                            ctx.get_db_word_pair_proximity_docids(s1, s2, dist as u8 + 1)?
+                            ctx.get_db_word_pair_proximity_docids(s1, s2, proximity as u8)?
                         {
                             bitmap |= m;
                         }
                     }
+                    // The word pair proximity database doesn't contain the words further apart.
+                    if max_distance >= MAX_DISTANCE as usize {
+                        bitmap |= compute_word_pair_positions_docids(ctx, s1, s2, max_distance)?;
+                    }
                     if bitmap.is_empty() {
                         return Ok(bitmap);
                     } else {
@@ -250,3 +257,51 @@ pub fn compute_phrase_docids(
     }
     Ok(candidates)
 }
+
+/// Returns the documents in which `word2` appears after `word1`, at most `max_distance`
+/// positions further, according to the word positions database.
+///
+/// The positions don't distinguish the attributes, so the words must also share an attribute
+/// of the documents. The positions being bucketed, documents in which the words are a bit
+/// further apart can also be returned.
+fn compute_word_pair_positions_docids(
+    ctx: &mut SearchContext,
+    word1: Interned<String>,
+    word2: Interned<String>,
+    max_distance: usize,
+) -> Result<RoaringBitmap> {
+    let mut word2_positions = Vec::new();
+    for position in ctx.get_db_word_positions(word2)? {
+        if let Some(docids) = ctx.get_db_word_position_docids(word2, position)? {
+            word2_positions.push((position, docids));
+        }
+    }
+
+    let max_distance = u16::try_from(max_distance).unwrap_or(u16::MAX);
+    let mut docids = RoaringBitmap::new();
+    for position1 in ctx.get_db_word_positions(word1)? {
+        let Some(word1_docids) = ctx.get_db_word_position_docids(word1, position1)? else {
+            continue;
+        };
+        let max_position2 = bucketed_position(position1.saturating_add(max_distance));
+        // Both words can be in the same bucket of positions.
+        for (_, word2_docids) in word2_positions
+            .iter()
+            .filter(|(position2, _)| (position1..=max_position2).contains(position2))
+        {
+            docids |= &word1_docids & word2_docids;
+        }
+    }
+
+    let mut same_attribute_docids = RoaringBitmap::new();
+    for fid in ctx.get_db_word_fids(word1)? {
+        let Some(word1_docids) = ctx.get_db_word_fid_docids(word1, fid)? else {
+            continue;
+        };
+        if let Some(word2_docids) = ctx.get_db_word_fid_docids(word2, fid)? {
+            same_attribute_docids |= word1_docids & word2_docids;
+        }
+    }
+
+    Ok(docids & same_attribute_docids)
+}
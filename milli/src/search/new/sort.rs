@@ -1,13 +1,18 @@
-use heed::BytesDecode;
+use std::collections::HashMap;
+
+use heed::types::Bytes;
+use heed::{BytesDecode, BytesEncode, RoTxn};
 use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
 use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
-use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec, OrderedI128Codec,
+};
 use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::score_details::{self, ScoreDetails};
-use crate::search::facet::{ascending_facet_sort, descending_facet_sort};
-use crate::{FieldId, Index, Result};
+use crate::search::facet::{ascending_facet_sort, descending_facet_sort, get_highest_level};
+use crate::{DocumentId, FieldId, Index, MultiValue, NullsOrder, Result, SortModifiers};
 
 pub trait RankingRuleOutputIter<'ctx, Query> {
     fn next_bucket(&mut self) -> Result<Option<RankingRuleOutput<Query>>>;
@@ -49,6 +54,7 @@ pub struct Sort<'ctx, Query> {
     field_name: String,
     field_id: Option<FieldId>,
     is_ascending: bool,
+    modifiers: SortModifiers,
     original_query: Option<Query>,
     iter: Option<RankingRuleOutputIterWrapper<'ctx, Query>>,
     must_redact: bool,
@@ -59,6 +65,16 @@ impl<'ctx, Query> Sort<'ctx, Query> {
         rtxn: &'ctx heed::RoTxn,
         field_name: String,
         is_ascending: bool,
+    ) -> Result<Self> {
+        Self::with_modifiers(index, rtxn, field_name, is_ascending, SortModifiers::default())
+    }
+
+    pub fn with_modifiers(
+        index: &Index,
+        rtxn: &'ctx heed::RoTxn,
+        field_name: String,
+        is_ascending: bool,
+        modifiers: SortModifiers,
     ) -> Result<Self> {
         let fields_ids_map = index.fields_ids_map(rtxn)?;
         let field_id = fields_ids_map.id(&field_name);
@@ -68,6 +84,7 @@ impl<'ctx, Query> Sort<'ctx, Query> {
             field_name,
             field_id,
             is_ascending,
+            modifiers,
             original_query: None,
             iter: None,
             must_redact,
@@ -139,16 +156,34 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                 };
                 let number_iter = number_iter.map(|r| -> Result<_> {
                     let (docids, bytes) = r?;
-                    Ok((
-                        docids,
-                        serde_json::Value::Number(
-                            serde_json::Number::from_f64(
-                                OrderedF64Codec::bytes_decode(bytes).expect("some number"),
-                            )
-                            .expect("too big float"),
-                        ),
-                    ))
+                    // the big integers are followed by their exact value
+                    let integer = (bytes.len() > 16)
+                        .then(|| OrderedI128Codec::bytes_decode(bytes).expect("some integer"));
+                    let number = match integer {
+                        Some(i) if i64::try_from(i).is_ok() => serde_json::Number::from(i as i64),
+                        Some(i) if u64::try_from(i).is_ok() => serde_json::Number::from(i as u64),
+                        _ => serde_json::Number::from_f64(
+                            OrderedF64Codec::bytes_decode(bytes).expect("some number"),
+                        )
+                        .expect("too big float"),
+                    };
+                    Ok((docids, serde_json::Value::Number(number)))
                 });
+                let number_iter = match self.modifiers.multi_value {
+                    Some(strategy) => itertools::Either::Left(
+                        multi_value_buckets(
+                            ctx.index,
+                            ctx.txn,
+                            field_id,
+                            strategy,
+                            self.is_ascending,
+                            parent_candidates,
+                        )?
+                        .into_iter()
+                        .map(Ok),
+                    ),
+                    None => itertools::Either::Right(number_iter),
+                };
                 let string_iter = string_iter.map(|r| -> Result<_> {
                     let (docids, bytes) = r?;
                     Ok((
@@ -159,25 +194,35 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                     ))
                 });
 
+                // the documents without a value for the field come before all the others
+                let nulls_bucket = match self.modifiers.nulls {
+                    NullsOrder::First => {
+                        let nulls = parent_candidates
+                            - sortable_documents_ids(ctx.txn, number_db, field_id)?
+                            - sortable_documents_ids(ctx.txn, string_db, field_id)?;
+                        (!nulls.is_empty()).then(|| Ok((nulls, serde_json::Value::Null)))
+                    }
+                    NullsOrder::Last => None,
+                };
+
                 let query_graph = parent_query.clone();
                 let ascending = self.is_ascending;
                 let field_name = self.field_name.clone();
                 let must_redact = self.must_redact;
-                RankingRuleOutputIterWrapper::new(Box::new(number_iter.chain(string_iter).map(
-                    move |r| {
-                        let (docids, value) = r?;
-                        Ok(RankingRuleOutput {
-                            query: query_graph.clone(),
-                            candidates: docids,
-                            score: ScoreDetails::Sort(score_details::Sort {
-                                field_name: field_name.clone(),
-                                ascending,
-                                redacted: must_redact,
-                                value,
-                            }),
-                        })
-                    },
-                )))
+                let buckets = nulls_bucket.into_iter().chain(number_iter).chain(string_iter);
+                RankingRuleOutputIterWrapper::new(Box::new(buckets.map(move |r| {
+                    let (docids, value) = r?;
+                    Ok(RankingRuleOutput {
+                        query: query_graph.clone(),
+                        candidates: docids,
+                        score: ScoreDetails::Sort(score_details::Sort {
+                            field_name: field_name.clone(),
+                            ascending,
+                            redacted: must_redact,
+                            value,
+                        }),
+                    })
+                })))
             }
             None => RankingRuleOutputIterWrapper::new(Box::new(std::iter::empty())),
         };
@@ -220,3 +265,119 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
         self.iter = None;
     }
 }
+
+/// Returns the documents having a value for the field in this facet database,
+/// by merging the groups of the highest level of its tree.
+fn sortable_documents_ids(
+    txn: &RoTxn,
+    db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    field_id: FieldId,
+) -> Result<RoaringBitmap> {
+    let level = get_highest_level(txn, db, field_id)?;
+    let mut prefix = field_id.to_be_bytes().to_vec();
+    prefix.push(level);
+
+    let mut docids = RoaringBitmap::new();
+    for result in db.remap_key_type::<Bytes>().prefix_iter(txn, &prefix)? {
+        let (_, group) = result?;
+        docids |= group.bitmap;
+    }
+    Ok(docids)
+}
+
+/// Groups the candidates having number values for the field by the value the strategy
+/// computes out of them, in the order of the sort.
+fn multi_value_buckets(
+    index: &Index,
+    txn: &RoTxn,
+    field_id: FieldId,
+    strategy: MultiValue,
+    is_ascending: bool,
+    candidates: &RoaringBitmap,
+) -> Result<Vec<(RoaringBitmap, serde_json::Value)>> {
+    let db = index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+
+    let mut buckets: Vec<(f64, RoaringBitmap)> = Vec::new();
+    match strategy {
+        // the facet sort places a document at the first of its values it meets,
+        // i.e. its minimum in ascending order and its maximum in descending order
+        MultiValue::Min | MultiValue::Max => {
+            let values = if strategy == MultiValue::Min {
+                itertools::Either::Left(ascending_facet_sort(
+                    txn,
+                    db,
+                    field_id,
+                    candidates.clone(),
+                )?)
+            } else {
+                itertools::Either::Right(descending_facet_sort(
+                    txn,
+                    db,
+                    field_id,
+                    candidates.clone(),
+                )?)
+            };
+            for result in values {
+                let (docids, bytes) = result?;
+                push_bucket(&mut buckets, facet_number_to_f64(bytes)?, docids);
+            }
+            if (strategy == MultiValue::Min) != is_ascending {
+                buckets.reverse();
+            }
+        }
+        // the values of the documents are summed value by value, using the level 0 of the facets
+        MultiValue::Avg => {
+            let mut sums: HashMap<DocumentId, (f64, u32)> = HashMap::new();
+            let prefix = FacetGroupKey { field_id, level: 0, left_bound: &[][..] };
+            let prefix = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&prefix)
+                .map_err(heed::Error::Encoding)?;
+            for result in db.remap_key_type::<Bytes>().prefix_iter(txn, &prefix)? {
+                let (key, group) = result?;
+                let docids = group.bitmap & candidates;
+                if docids.is_empty() {
+                    continue;
+                }
+                let value = facet_number_to_f64(&key[prefix.len()..])?;
+                for docid in docids {
+                    let (sum, count) = sums.entry(docid).or_default();
+                    *sum += value;
+                    *count += 1;
+                }
+            }
+
+            let mut values: Vec<_> =
+                sums.into_iter().map(|(docid, (sum, count))| (sum / count as f64, docid)).collect();
+            if is_ascending {
+                values.sort_unstable_by(|(left, _), (right, _)| left.total_cmp(right));
+            } else {
+                values.sort_unstable_by(|(left, _), (right, _)| right.total_cmp(left));
+            }
+            for (value, docid) in values {
+                push_bucket(&mut buckets, value, RoaringBitmap::from_iter([docid]));
+            }
+        }
+    }
+
+    Ok(buckets
+        .into_iter()
+        .map(|(value, docids)| (docids, serde_json::Number::from_f64(value).into()))
+        .collect())
+}
+
+/// Appends the documents to the last bucket if it has the same value, to a new bucket otherwise.
+fn push_bucket(buckets: &mut Vec<(f64, RoaringBitmap)>, value: f64, docids: RoaringBitmap) {
+    match buckets.last_mut() {
+        Some((last, last_docids)) if *last == value => *last_docids |= docids,
+        _ => buckets.push((value, docids)),
+    }
+}
+
+/// Decodes a number of the facet database, the big integers being rounded to a float.
+fn facet_number_to_f64(bytes: &[u8]) -> Result<f64> {
+    let number = if bytes.len() > 16 {
+        OrderedI128Codec::bytes_decode(bytes).map(|integer| integer as f64)
+    } else {
+        OrderedF64Codec::bytes_decode(bytes)
+    };
+    Ok(number.map_err(heed::Error::Decoding)?)
+}
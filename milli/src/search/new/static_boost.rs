@@ -0,0 +1,104 @@
+use heed::BytesDecode;
+use roaring::RoaringBitmap;
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::BytesRefCodec;
+use crate::score_details::{Rank, ScoreDetails};
+use crate::search::facet::{descending_facet_sort, facet_max_value, facet_min_value};
+use crate::static_boost::{BoostCurve, StaticBoost, BOOST_BUCKETS};
+use crate::{FieldId, Index, Result};
+
+/// A ranking rule grouping the documents by the boost of the numeric values of
+/// the static boost attribute, from the most boosted to the least boosted ones.
+///
+/// The boost curve is applied over the range of the values of all the documents of the
+/// index, so that the boost of a document doesn't depend on the query. The documents
+/// without a numeric value are part of the least boosted bucket.
+pub struct Boost<Query> {
+    field_id: Option<FieldId>,
+    curve: BoostCurve,
+    /// The minimum and maximum values of the attribute in the index.
+    range: Option<(f64, f64)>,
+    original_query: Option<Query>,
+    /// The candidates of each boost bucket, the most boosted bucket being the last one.
+    buckets: Vec<RoaringBitmap>,
+}
+
+impl<Query> Boost<Query> {
+    pub fn new(index: &Index, rtxn: &heed::RoTxn, static_boost: StaticBoost) -> Result<Self> {
+        let StaticBoost { attribute, curve } = static_boost;
+        let field_id = index.fields_ids_map(rtxn)?.id(&attribute);
+        let range = match field_id {
+            Some(field_id) => {
+                let documents_ids = index.documents_ids(rtxn)?;
+                let min = facet_min_value(index, rtxn, field_id, documents_ids.clone())?;
+                let max = facet_max_value(index, rtxn, field_id, documents_ids)?;
+                min.zip(max)
+            }
+            None => None,
+        };
+
+        Ok(Self { field_id, curve, range, original_query: None, buckets: Vec::new() })
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Boost<Query> {
+    fn id(&self) -> String {
+        "static_boost".to_owned()
+    }
+
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        let mut buckets = vec![RoaringBitmap::new(); BOOST_BUCKETS as usize + 1];
+        if let (Some(field_id), Some((min, max))) = (self.field_id, self.range) {
+            let number_db =
+                ctx.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+            // the documents with several values are only yielded with their highest one
+            let iter =
+                descending_facet_sort(ctx.txn, number_db, field_id, parent_candidates.clone())?;
+            for result in iter {
+                let (docids, bytes) = result?;
+                let value = OrderedF64Codec::bytes_decode(bytes).map_err(heed::Error::Decoding)?;
+                buckets[self.curve.bucket(value, min, max) as usize] |= docids;
+            }
+        }
+
+        self.original_query = Some(parent_query.clone());
+        self.buckets = buckets;
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        let candidates = match self.buckets.pop() {
+            // the least boosted bucket also contains the documents without a numeric value
+            Some(_) if self.buckets.is_empty() => universe.clone(),
+            Some(bucket) => bucket & universe,
+            None => universe.clone(),
+        };
+        let rank = Rank { rank: self.buckets.len() as u32 + 1, max_rank: BOOST_BUCKETS as u32 + 1 };
+
+        Ok(Some(RankingRuleOutput { query, candidates, score: ScoreDetails::StaticBoost(rank) }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.buckets.clear();
+    }
+}
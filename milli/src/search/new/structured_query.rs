@@ -0,0 +1,181 @@
+use ordered_float::OrderedFloat;
+use roaring::RoaringBitmap;
+
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::score_details::{Rank, ScoreDetails};
+use crate::{Filter, Result};
+
+/// A query made of clauses instead of a query string, for the applications needing
+/// a precise control over the matched words without escaping them in a query string.
+#[derive(Debug, Clone, Default)]
+pub struct StructuredQuery<'a> {
+    /// The clauses all the documents must match.
+    pub must: Vec<QueryClause<'a>>,
+    /// The clauses ranking higher the documents matching them, by the sum of their boosts.
+    ///
+    /// The documents must match at least one of them when there are no other clauses.
+    pub should: Vec<QueryClause<'a>>,
+    /// The clauses the documents must not match.
+    pub must_not: Vec<QueryClause<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum QueryClause<'a> {
+    /// Words matched like the words of a query string, with typos and prefixes.
+    ///
+    /// The boost of a clause only ranks the documents matching the `should` clauses.
+    Term { text: &'a str, boost: f64 },
+    /// Words matched exactly and consecutively, like a quoted phrase of a query string.
+    Phrase { text: &'a str, boost: f64 },
+    /// The documents matching a filter expression, ignored in the `should` clauses.
+    Filter(Filter<'a>),
+}
+
+impl QueryClause<'_> {
+    fn boost(&self) -> f64 {
+        match self {
+            QueryClause::Term { boost, .. } | QueryClause::Phrase { boost, .. } => *boost,
+            QueryClause::Filter(_) => 0.0,
+        }
+    }
+
+    /// Returns the clause written as in a query string, the double quotes
+    /// of the text being replaced by spaces since they are separators anyway.
+    fn query_text(&self) -> Option<String> {
+        match self {
+            QueryClause::Term { text, .. } => Some(text.replace('"', " ")),
+            QueryClause::Phrase { text, .. } => Some(format!("\"{}\"", text.replace('"', " "))),
+            QueryClause::Filter(_) => None,
+        }
+    }
+}
+
+impl<'a> StructuredQuery<'a> {
+    /// Returns the query string made of the `must` clauses, whose words the documents
+    /// must all match.
+    pub(crate) fn must_query_text(&self) -> String {
+        let texts: Vec<_> = self.must.iter().filter_map(QueryClause::query_text).collect();
+        texts.join(" ")
+    }
+
+    /// Returns the query string and the boost of each `should` clause.
+    pub(crate) fn should_query_texts(&self) -> impl Iterator<Item = (String, f64)> + '_ {
+        self.should.iter().filter_map(|clause| Some((clause.query_text()?, clause.boost())))
+    }
+
+    /// Returns the texts of the `must_not` clauses, each of them
+    /// being a word or a phrase the documents must not contain.
+    pub(crate) fn negative_texts(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.must_not.iter().flat_map(|clause| match clause {
+            QueryClause::Term { text, .. } => text.split_whitespace().collect(),
+            QueryClause::Phrase { text, .. } => vec![*text],
+            QueryClause::Filter(_) => Vec::new(),
+        })
+    }
+
+    /// Keeps the documents matching the filters of the `must` clauses
+    /// and none of the filters of the `must_not` clauses.
+    pub(crate) fn filter_universe(
+        &self,
+        ctx: &SearchContext,
+        universe: &mut RoaringBitmap,
+    ) -> Result<()> {
+        for clause in &self.must {
+            if let QueryClause::Filter(filter) = clause {
+                *universe &= filter.evaluate(ctx.txn, ctx.index)?;
+            }
+        }
+        for clause in &self.must_not {
+            if let QueryClause::Filter(filter) = clause {
+                *universe -= filter.evaluate(ctx.txn, ctx.index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A ranking rule grouping the documents by the sum of the boosts of the `should` clauses
+/// of a structured query they match, from the most boosted to the least boosted ones.
+pub struct ShouldClauses<Query> {
+    /// The documents matching each `should` clause, with its boost.
+    clauses: Vec<(RoaringBitmap, f64)>,
+    original_query: Option<Query>,
+    /// The candidates of each bucket, the most boosted bucket being the last one.
+    buckets: Vec<RoaringBitmap>,
+    max_rank: u32,
+}
+
+impl<Query> ShouldClauses<Query> {
+    pub fn new(clauses: Vec<(RoaringBitmap, f64)>) -> Self {
+        Self { clauses, original_query: None, buckets: Vec::new(), max_rank: 1 }
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for ShouldClauses<Query> {
+    fn id(&self) -> String {
+        "should_clauses".to_owned()
+    }
+
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        parent_candidates: &RoaringBitmap,
+        parent_query: &Query,
+    ) -> Result<()> {
+        // the candidates are split by each clause into the documents matching it or not
+        let mut buckets = vec![(0.0, parent_candidates.clone())];
+        for (docids, boost) in &self.clauses {
+            let mut split = Vec::with_capacity(buckets.len() * 2);
+            for (sum, candidates) in buckets {
+                let matching = &candidates & docids;
+                let not_matching = candidates - &matching;
+                split.extend([(sum + boost, matching), (sum, not_matching)]);
+            }
+            split.retain(|(_, candidates)| !candidates.is_empty());
+            buckets = split;
+        }
+
+        buckets.sort_unstable_by_key(|(sum, _)| OrderedFloat(*sum));
+        let mut merged: Vec<(f64, RoaringBitmap)> = Vec::with_capacity(buckets.len());
+        for (sum, candidates) in buckets {
+            match merged.last_mut() {
+                Some((last_sum, last_candidates)) if *last_sum == sum => {
+                    *last_candidates |= candidates
+                }
+                _ => merged.push((sum, candidates)),
+            }
+        }
+
+        self.original_query = Some(parent_query.clone());
+        self.max_rank = merged.len().max(1) as u32;
+        self.buckets = merged.into_iter().map(|(_, candidates)| candidates).collect();
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        let query = self.original_query.as_ref().unwrap().clone();
+        let candidates = match self.buckets.pop() {
+            Some(bucket) => bucket & universe,
+            None => universe.clone(),
+        };
+        let rank = Rank { rank: self.buckets.len() as u32 + 1, max_rank: self.max_rank };
+
+        Ok(Some(RankingRuleOutput { query, candidates, score: ScoreDetails::ShouldClauses(rank) }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.original_query = None;
+        self.buckets.clear();
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::index::tests::TempIndex;
 use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
 
@@ -144,3 +146,50 @@ fn test_attribute_fid_ngrams() {
     let document_ids_scores: Vec<_> = documents_ids.iter().zip(document_scores).collect();
     insta::assert_snapshot!(format!("{document_ids_scores:#?}"));
 }
+
+#[test]
+fn test_attribute_fid_boosts() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![
+                "title".to_owned(),
+                "description".to_owned(),
+                "plot".to_owned(),
+            ]);
+            s.set_criteria(vec![Criterion::Attribute]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "", "description": "", "plot": "the fox" },
+            { "id": 1, "title": "the fox", "description": "", "plot": "" },
+            { "id": 2, "title": "", "description": "the fox", "plot": "" },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("fox");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 0]");
+
+    // the plot is the third attribute, it must be boosted more than three times to come first
+    let boosts = BTreeMap::from([("plot".to_owned(), 4.0)]);
+    let mut s = Search::new(&txn, &index);
+    s.query("fox");
+    s.attribute_boosts(&boosts);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2]");
+
+    let boosts = BTreeMap::from([("plot".to_owned(), 2.0), ("title".to_owned(), 0.5)]);
+    let mut s = Search::new(&txn, &index);
+    s.query("fox");
+    s.attribute_boosts(&boosts);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2]");
+}
@@ -0,0 +1,114 @@
+/*!
+This module tests the corrected query of the search results:
+
+1. the words unknown to the index are replaced by their typo derivation with the fewest typos
+2. between derivations with the same number of typos, the one with the most documents is chosen
+3. the words existing in the index and the phrases are kept as written
+4. there is no corrected query when all the words exist in the index
+5. the corrected query is only computed when requested
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Typo]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            {
+                "id": 0,
+                "text": "the quick brown fox jumps over the lazy dog"
+            },
+            {
+                "id": 1,
+                "text": "the quick brown foxes jump over the lazy dog"
+            },
+            {
+                "id": 2,
+                "text": "the quack of the duck"
+            },
+            {
+                "id": 3,
+                "text": "the sunflower and the sunflowers"
+            },
+            {
+                "id": 4,
+                "text": "a sunflowar"
+            },
+            {
+                "id": 5,
+                "text": "the sunflower"
+            },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_corrected_query_fewest_typos() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.corrected_query(true);
+    s.query("quixk brown fox");
+    let result = s.execute().unwrap();
+    assert_eq!(result.corrected_query.as_deref(), Some("quick brown fox"));
+}
+
+#[test]
+fn test_corrected_query_most_documents() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    // both `sunflower` and `sunflowar` are one typo away, `sunflower` has more documents
+    let mut s = Search::new(&txn, &index);
+    s.corrected_query(true);
+    s.query("sunflowor dog");
+    let result = s.execute().unwrap();
+    assert_eq!(result.corrected_query.as_deref(), Some("sunflower dog"));
+}
+
+#[test]
+fn test_corrected_query_keeps_phrases() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.corrected_query(true);
+    s.query("\"the lazy\" quixk dog");
+    let result = s.execute().unwrap();
+    assert_eq!(result.corrected_query.as_deref(), Some("\"the lazy\" quick dog"));
+}
+
+#[test]
+fn test_no_corrected_query() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.corrected_query(true);
+    s.query("quick brown fox");
+    let result = s.execute().unwrap();
+    assert_eq!(result.corrected_query, None);
+}
+
+#[test]
+fn test_corrected_query_not_requested() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("quixk brown fox");
+    let result = s.execute().unwrap();
+    assert_eq!(result.corrected_query, None);
+}
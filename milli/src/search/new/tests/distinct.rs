@@ -585,3 +585,43 @@ fn test_distinct_typo() {
     ]
     "###);
 }
+
+#[test]
+fn test_distinct_of_the_query() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_filterable_fields(hashset! { S("color") });
+            s.set_distinct_field("letter".to_owned());
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "letter": "A", "color": "red" },
+            { "id": 1, "letter": "A", "color": "blue" },
+            { "id": 2, "letter": "B", "color": "red" },
+            { "id": 3, "letter": "C", "color": "green" },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 3]");
+
+    // the distinct attribute of the query replaces the one of the index
+    let mut s = Search::new(&txn, &index);
+    s.distinct("color");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3]");
+
+    // the distinct attribute of the query must be filterable
+    let mut s = Search::new(&txn, &index);
+    s.distinct("letter");
+    let error = s.execute().unwrap_err();
+    insta::assert_snapshot!(error, @"Attribute `letter` is not filterable and cannot be used as a distinct attribute. Available filterable attributes are: `color`.");
+}
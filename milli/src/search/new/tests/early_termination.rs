@@ -0,0 +1,80 @@
+/*!
+This module tests the early termination of the searches:
+- the documents are the same as the ones of an exhaustive search
+- the candidates are the documents matching all the query words when they fill the page
+- the candidates are the documents of the first buckets of the `words` ranking rule filling the page
+- the search is exhaustive when the documents matching all the query words don't fill the page
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "red running shoes" },
+            { "id": 1, "title": "red shoes" },
+            { "id": 2, "title": "blue shoes" },
+            { "id": 3, "title": "red socks" },
+            { "id": 4, "title": "red shoes for kids" },
+            { "id": 5, "title": "shoes" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, query: &str, limit: usize, early_termination: bool) -> SearchResult {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.limit(limit);
+    s.early_termination(early_termination);
+    s.execute().unwrap()
+}
+
+#[test]
+fn early_termination_fills_the_page() {
+    let index = create_index();
+
+    let exhaustive = search(&index, "red shoes", 2, false);
+    let early = search(&index, "red shoes", 2, true);
+    assert_eq!(early.documents_ids, exhaustive.documents_ids);
+
+    // the documents only matching `red` are not resolved
+    assert_eq!(exhaustive.candidates.iter().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+    assert_eq!(early.candidates.iter().collect::<Vec<_>>(), vec![0, 1, 4]);
+}
+
+#[test]
+fn early_termination_stops_at_the_bucket_filling_the_page() {
+    let index = create_index();
+
+    let exhaustive = search(&index, "red shoes kids", 3, false);
+    let early = search(&index, "red shoes kids", 3, true);
+    assert_eq!(early.documents_ids, exhaustive.documents_ids);
+
+    // only `kids` is removed from the query, the documents only matching `red` are not resolved
+    assert_eq!(exhaustive.candidates.iter().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+    assert_eq!(early.candidates.iter().collect::<Vec<_>>(), vec![0, 1, 4]);
+}
+
+#[test]
+fn early_termination_falls_back_to_an_exhaustive_search() {
+    let index = create_index();
+
+    let exhaustive = search(&index, "red shoes", 4, false);
+    let early = search(&index, "red shoes", 4, true);
+    assert_eq!(early.documents_ids, exhaustive.documents_ids);
+    assert_eq!(early.candidates, exhaustive.candidates);
+    assert_eq!(early.candidates.iter().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+}
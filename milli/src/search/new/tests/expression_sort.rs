@@ -0,0 +1,65 @@
+/*!
+This module tests the custom ranking rules written as expressions:
+
+1. the documents are sorted by the value of the expression, in the given order
+2. the documents for which the expression has no value are returned last
+3. the documents with the same value are in the same bucket
+4. the expression is also applied to the placeholder searches
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index(criterion: Criterion) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, criterion]);
+        })
+        .unwrap();
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp() as f64;
+    let day = 24.0 * 60.0 * 60.0;
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick fox", "popularity": 100, "published_at": now - day },
+            { "id": 1, "text": "the quick fox", "popularity": 10000, "published_at": now - 90.0 * day },
+            { "id": 2, "text": "the quick fox", "popularity": 1000, "published_at": now - 2.0 * day },
+            { "id": 3, "text": "the quick fox", "popularity": 1000 },
+            { "id": 4, "text": "the quick fox", "popularity": 1, "published_at": now },
+            { "id": 5, "text": "the quick", "popularity": 100000, "published_at": now },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_desc_expression() {
+    let index = create_index(Criterion::DescExpression(
+        "log(popularity) * freshness_decay(published_at, 30d)".to_owned(),
+    ));
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![2, 0, 1, 4, 3, 5]);
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 2, 0, 1, 4, 3]);
+}
+
+#[test]
+fn test_asc_expression() {
+    let index = create_index(Criterion::AscExpression("popularity / 10".to_owned()));
+    let txn = index.read_txn().unwrap();
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![4, 0, 2, 3, 1, 5]);
+}
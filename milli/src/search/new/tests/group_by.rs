@@ -0,0 +1,85 @@
+/*!
+This module tests the grouping of the hits by the value of a field:
+
+1. only the best documents of each group are returned, in the order of their ranking
+2. the documents without a value for the field are each in their own group
+3. the string values are grouped regardless of their case, the number values are grouped too
+4. the number of candidates of each returned group is given
+5. an error is returned if the field is not filterable
+*/
+
+use big_s::S;
+use maplit::{btreemap, hashset};
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Error, Search, SearchResult, UserError};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_filterable_fields(hashset! { S("brand") });
+            s.set_criteria(vec![Criterion::Desc("rank".to_owned())]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "brand": "Apple", "rank": 10 },
+            { "id": 1, "brand": "Apple", "rank": 9 },
+            { "id": 2, "brand": "Banana", "rank": 8 },
+            { "id": 3, "brand": "apple", "rank": 7 },
+            { "id": 4, "brand": "Banana", "rank": 6 },
+            { "id": 5, "rank": 5 },
+            { "id": 6, "brand": "Cherry", "rank": 4 },
+            { "id": 7, "brand": "Banana", "rank": 3 },
+            { "id": 8, "brand": 12, "rank": 2 },
+            { "id": 9, "brand": 12, "rank": 1 },
+            { "id": 10, "rank": 0 },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_group_by() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.group_by("brand", 2);
+    let SearchResult { documents_ids, candidates, group_counts, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0, 1, 2, 4, 5, 6, 8, 9, 10]);
+    assert_eq!(candidates.len(), 11);
+    assert_eq!(
+        group_counts,
+        btreemap! { S("12") => 2, S("Apple") => 3, S("Banana") => 3, S("Cherry") => 1 }
+    );
+
+    let mut s = Search::new(&txn, &index);
+    s.group_by("brand", 1);
+    s.limit(3);
+    let SearchResult { documents_ids, group_counts, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0, 2, 5]);
+    assert_eq!(group_counts, btreemap! { S("Apple") => 3, S("Banana") => 3 });
+
+    let mut s = Search::new(&txn, &index);
+    s.group_by("brand", 1);
+    s.offset(2);
+    s.limit(3);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 6, 8]);
+}
+
+#[test]
+fn test_group_by_not_filterable() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.group_by("rank", 2);
+    let result = s.execute();
+    assert!(matches!(result, Err(Error::UserError(UserError::InvalidGroupByAttribute { .. }))));
+}
@@ -1,18 +1,30 @@
 pub mod attribute_fid;
 pub mod attribute_position;
+pub mod corrected_query;
 pub mod distinct;
+pub mod early_termination;
 pub mod exactness;
+pub mod expression_sort;
 pub mod geo_sort;
+pub mod group_by;
 pub mod integration;
 #[cfg(feature = "all-tokenizations")]
 pub mod language;
+pub mod negative;
 pub mod ngram_split_words;
+pub mod parallel_bucket_sort;
+pub mod phrase_slop;
 pub mod proximity;
 pub mod proximity_typo;
+pub mod query_rules;
+pub mod search_after;
 pub mod sort;
+pub mod static_boost;
 pub mod stop_words;
+pub mod structured_query;
 pub mod typo;
 pub mod typo_proximity;
+pub mod vocabulary;
 pub mod words_tms;
 
 fn collect_field_values(
@@ -0,0 +1,77 @@
+/*!
+This module tests the following properties about negative query terms:
+- the documents containing a negative word, `-word`, are excluded from the results
+- the documents containing a negative phrase, `-"exact phrase"`, are excluded from the results
+- the negative words are exact: they are not tolerant to typos and are not prefixes
+- a query consisting only of negative terms is a placeholder query over the remaining documents
+- a dash inside a word or a quoted phrase doesn't make a negative term
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "the quick brown fox" },
+            { "id": 1, "title": "the quick red fox" },
+            { "id": 2, "title": "the lazy brown dog" },
+            { "id": 3, "title": "the brown fox is quick" },
+            { "id": 4, "title": "foxes are quicker" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    documents_ids
+}
+
+#[test]
+fn negative_words() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "fox"), vec![0, 1, 3, 4]);
+    assert_eq!(search(&index, "-red fox"), vec![0, 3, 4]);
+    // as in any query, the words followed by other terms are not prefixes
+    assert_eq!(search(&index, "fox -brown"), vec![1]);
+    assert_eq!(search(&index, "-red fox -brown"), vec![]);
+    // negative words are neither prefixes nor typo tolerant
+    assert_eq!(search(&index, "-quick"), vec![2, 4]);
+    assert_eq!(search(&index, "fox -qick"), vec![0, 1, 3]);
+}
+
+#[test]
+fn negative_phrases() {
+    let index = create_index();
+
+    assert_eq!(search(&index, r#"fox -"brown fox""#), vec![1]);
+    assert_eq!(search(&index, r#"-"quick brown""#), vec![1, 2, 3, 4]);
+    // the words of the phrase must be side by side
+    assert_eq!(search(&index, r#"brown -"brown quick""#), vec![0, 2, 3]);
+}
+
+#[test]
+fn dashes_without_negation() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "quick-fox"), vec![0, 1, 3]);
+    assert_eq!(search(&index, r#""-brown fox""#), vec![0, 3]);
+    assert_eq!(search(&index, "fox - brown"), vec![0, 3]);
+}
@@ -0,0 +1,118 @@
+/*!
+This module tests the parallel bucket sort of the placeholder searches:
+
+1. the buckets of the first ranking rule ranked in parallel give the same documents
+   and scores as the sequential bucket sort, whatever the requested page
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::documents::documents_batch_reader_from_objects;
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::search::new::bucket_sort::{bucket_sort, parallel_bucket_sort};
+use crate::search::new::ranking_rules::PlaceholderQuery;
+use crate::search::new::{
+    get_ranking_rules_for_placeholder_search, DefaultSearchLogger, GeoSortStrategy, SearchContext,
+};
+use crate::{Criterion, TimeBudget};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("size"), S("age"), S("name") });
+            s.set_criteria(vec![
+                Criterion::Asc(S("size")),
+                Criterion::Desc(S("age")),
+                Criterion::Asc(S("name")),
+            ]);
+        })
+        .unwrap();
+
+    let mut documents = vec![];
+    for id in 0..300u32 {
+        let document = serde_json::json!({
+            "id": id,
+            "size": id % 7,
+            "age": (id * 13) % 11,
+            "name": format!("{}", (id * 31) % 17),
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        documents.push(document);
+    }
+    index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+    index
+}
+
+#[test]
+fn test_parallel_bucket_sort_is_deterministic() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+
+    for (from, length) in [(0, 20), (0, 300), (13, 50), (150, 100), (290, 20), (400, 10)] {
+        let mut ctx = SearchContext::new(&index, &txn);
+        let ranking_rules = get_ranking_rules_for_placeholder_search(
+            &ctx,
+            &None,
+            GeoSortStrategy::default(),
+            &None,
+        )
+        .unwrap();
+        let sequential = bucket_sort(
+            &mut ctx,
+            ranking_rules,
+            &PlaceholderQuery,
+            &universe,
+            from,
+            length,
+            ScoringStrategy::Detailed,
+            None,
+            TimeBudget::max(),
+            None,
+            None,
+            &mut DefaultSearchLogger,
+        )
+        .unwrap();
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let ranking_rules = get_ranking_rules_for_placeholder_search(
+            &ctx,
+            &None,
+            GeoSortStrategy::default(),
+            &None,
+        )
+        .unwrap();
+        let parallel = parallel_bucket_sort(
+            &mut ctx,
+            ranking_rules,
+            &universe,
+            from,
+            length,
+            ScoringStrategy::Detailed,
+            TimeBudget::max(),
+            |ctx| {
+                get_ranking_rules_for_placeholder_search(
+                    ctx,
+                    &None,
+                    GeoSortStrategy::default(),
+                    &None,
+                )
+            },
+            &mut DefaultSearchLogger,
+        )
+        .unwrap();
+
+        assert_eq!(parallel.docids, sequential.docids, "from: {from}, length: {length}");
+        assert_eq!(parallel.scores, sequential.scores, "from: {from}, length: {length}");
+        assert_eq!(parallel.all_candidates, sequential.all_candidates);
+        assert!(!parallel.degraded);
+    }
+}
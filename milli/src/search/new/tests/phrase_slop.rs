@@ -0,0 +1,98 @@
+/*!
+This module tests the phrase slop of a search:
+
+1. without slop, only the documents containing the exact phrase are returned
+2. the slop allows some words between the words of the phrase
+3. the words further apart than the word pair proximity database are found in the positions
+4. a phrase followed by `~N` has a slop of its own
+5. the words of the phrase must be in the same attribute
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick fox" },
+            { "id": 1, "text": "the quick brown fox" },
+            { "id": 2, "text": "the quick and very agile brown fox" },
+            { "id": 3, "text": "the fox is not so quick" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, query: &str, slop: u8) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.phrase_slop(slop);
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    documents_ids
+}
+
+#[test]
+fn test_phrase_without_slop() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "\"quick fox\"", 0), vec![0]);
+}
+
+#[test]
+fn test_phrase_slop() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "\"quick fox\"", 1), vec![0, 1]);
+    assert_eq!(search(&index, "\"quick fox\"", 2), vec![0, 1]);
+}
+
+#[test]
+fn test_phrase_slop_in_positions() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "\"quick fox\"", 5), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_phrase_slop_syntax() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "\"quick fox\"~1", 0), vec![0, 1]);
+    assert_eq!(search(&index, "\"quick fox\"~5", 0), vec![0, 1, 2]);
+    // the slop of the phrase replaces the one of the search
+    assert_eq!(search(&index, "\"quick fox\"~0", 5), vec![0]);
+    assert_eq!(search(&index, "\"quick fox\"~1 \"the quick\"", 0), vec![0, 1]);
+}
+
+#[test]
+fn test_phrase_slop_in_one_attribute() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned(), "text".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "the quick", "text": "a sly fox" },
+            { "id": 1, "title": "fable", "text": "the quick and very agile brown fox" },
+        ]))
+        .unwrap();
+
+    assert_eq!(search(&index, "\"quick fox\"", 5), vec![1]);
+}
@@ -0,0 +1,175 @@
+/*!
+This module tests the query rules:
+
+1. the documents pinned by a triggered rule are ranked first, in order, when they match the search
+2. the documents boosted by a triggered rule are ranked right after the pinned documents
+3. the filter of a triggered rule restricts the documents of the search
+4. a rule is triggered by the query according to its anchoring, or by the filter of the search
+5. the ids of the rules applied to each returned document are given
+6. the documents pinned by the search are ranked before the ones pinned by the rules
+7. the documents hidden by the search are never returned
+8. the query rules reorder the documents without lowering their ranking score
+*/
+
+use big_s::S;
+use maplit::{btreemap, hashset};
+
+use crate::index::tests::TempIndex;
+use crate::query_rules::{Anchoring, QueryRule, QueryRuleConditions, QueryRuleConsequences};
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{Criterion, Filter, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(hashset! { S("color"), S("on_sale") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Desc("rank".to_owned())]);
+            s.set_query_rules(vec![
+                QueryRule {
+                    id: S("pin"),
+                    conditions: QueryRuleConditions {
+                        query: Some(S("shoes")),
+                        anchoring: Anchoring::EndsWith,
+                        filter: None,
+                    },
+                    consequences: QueryRuleConsequences {
+                        pin: vec![S("4"), S("unknown"), S("1")],
+                        ..Default::default()
+                    },
+                },
+                QueryRule {
+                    id: S("sale"),
+                    conditions: QueryRuleConditions {
+                        filter: Some(S("color = red")),
+                        ..Default::default()
+                    },
+                    consequences: QueryRuleConsequences {
+                        boost: Some(S("on_sale = true")),
+                        ..Default::default()
+                    },
+                },
+                QueryRule {
+                    id: S("blue"),
+                    conditions: QueryRuleConditions {
+                        query: Some(S("blue")),
+                        anchoring: Anchoring::StartsWith,
+                        filter: None,
+                    },
+                    consequences: QueryRuleConsequences {
+                        filter: Some(S("color = blue")),
+                        ..Default::default()
+                    },
+                },
+            ]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "red shoes", "color": "red", "rank": 5, "on_sale": false },
+            { "id": 1, "text": "red shoes", "color": "red", "rank": 4, "on_sale": true },
+            { "id": 2, "text": "blue shoes", "color": "blue", "rank": 3, "on_sale": false },
+            { "id": 3, "text": "blue hat", "color": "red", "rank": 2, "on_sale": true },
+            { "id": 4, "text": "green hat", "color": "green", "rank": 1, "on_sale": false },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_query_rules_pin() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    // the pinned document 4 doesn't match the query
+    let mut s = Search::new(&txn, &index);
+    s.query("cheap shoes");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![1, 0, 2]);
+    assert_eq!(applied_query_rules, btreemap! { 1 => vec![S("pin")] });
+
+    // the rule is not triggered
+    let mut s = Search::new(&txn, &index);
+    s.query("shoes cheap");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0, 1, 2]);
+    assert!(applied_query_rules.is_empty());
+}
+
+#[test]
+fn test_query_rules_boost() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.filter(Filter::from_str("color = red AND NOT color = blue").unwrap().unwrap());
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![1, 3, 0]);
+    assert_eq!(applied_query_rules, btreemap! { 1 => vec![S("sale")], 3 => vec![S("sale")] });
+
+    // the filter of the search doesn't contain the condition of the rule
+    let mut s = Search::new(&txn, &index);
+    s.filter(Filter::from_str("color = red OR color = green").unwrap().unwrap());
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0, 1, 3, 4]);
+    assert!(applied_query_rules.is_empty());
+}
+
+#[test]
+fn test_query_rules_ranking_score() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.filter(Filter::from_str("color = red AND NOT color = blue").unwrap().unwrap());
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![1, 3, 0]);
+    // the document 0 isn't boosted but it still matches the search perfectly
+    let scores: Vec<_> =
+        document_scores.iter().map(|scores| ScoreDetails::global_score(scores.iter())).collect();
+    assert_eq!(scores, vec![1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn test_query_rules_filter() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("blue hat");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![2]);
+    assert_eq!(applied_query_rules, btreemap! { 2 => vec![S("blue")] });
+}
+
+#[test]
+fn test_pinned_and_hidden_documents() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let pinned = vec![S("2"), S("1")];
+    let mut s = Search::new(&txn, &index);
+    s.query("shoes");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.pinned_documents(&pinned);
+    let SearchResult { documents_ids, applied_query_rules, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![2, 1, 0]);
+    assert!(applied_query_rules.is_empty());
+
+    let hidden = vec![S("1"), S("2")];
+    let mut s = Search::new(&txn, &index);
+    s.query("shoes");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.hidden_documents(&hidden);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0]);
+    assert_eq!(candidates.len(), 1);
+}
@@ -0,0 +1,107 @@
+/*!
+This module tests the search cursors:
+
+1. the documents ranked after the cursor are returned, page by page
+2. the documents of the same buckets are ranked by id after the cursor
+3. the pages don't move when documents ranked before the cursor are added
+4. a cursor built with other ranking rules is rejected
+*/
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{
+    BucketCoordinate, Criterion, Error, Search, SearchCursor, SearchResult, TermsMatchingStrategy,
+    UserError,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Desc("rank".to_owned())]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick fox", "rank": 1 },
+            { "id": 1, "text": "the quick fox", "rank": 3 },
+            { "id": 2, "text": "the quick fox", "rank": 3 },
+            { "id": 3, "text": "the quick", "rank": 5 },
+            { "id": 4, "text": "the", "rank": 2 },
+            { "id": 5, "text": "the", "rank": 10 },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search_page(index: &TempIndex, cursor: Option<&SearchCursor>) -> (Vec<u32>, SearchCursor) {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.query("the quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.limit(2);
+    if let Some(cursor) = cursor {
+        s.search_after(cursor.clone());
+    }
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+
+    let cursor = match (documents_ids.last(), document_scores.last()) {
+        (Some(docid), Some(scores)) => SearchCursor::new(scores, *docid),
+        _ => cursor.cloned().unwrap(),
+    };
+    (documents_ids, cursor)
+}
+
+#[test]
+fn test_search_after() {
+    let index = create_index();
+
+    let (documents_ids, cursor) = search_page(&index, None);
+    assert_eq!(documents_ids, vec![1, 2]);
+    let (documents_ids, cursor) = search_page(&index, Some(&cursor));
+    assert_eq!(documents_ids, vec![0, 3]);
+    let (documents_ids, cursor) = search_page(&index, Some(&cursor));
+    assert_eq!(documents_ids, vec![5, 4]);
+    let (documents_ids, _) = search_page(&index, Some(&cursor));
+    assert!(documents_ids.is_empty());
+}
+
+#[test]
+fn test_search_after_stable_pages() {
+    let index = create_index();
+
+    let (documents_ids, cursor) = search_page(&index, None);
+    assert_eq!(documents_ids, vec![1, 2]);
+
+    // the first document is ranked before the cursor and the second one after it, in its bucket
+    index
+        .add_documents(documents!([
+            { "id": 6, "text": "the quick fox", "rank": 4 },
+            { "id": 7, "text": "the quick fox", "rank": 3 },
+        ]))
+        .unwrap();
+
+    let (documents_ids, _) = search_page(&index, Some(&cursor));
+    assert_eq!(documents_ids, vec![7, 0]);
+}
+
+#[test]
+fn test_search_after_invalid_cursor() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick fox");
+    s.search_after(SearchCursor {
+        coordinates: vec![BucketCoordinate::Value(serde_json::json!("fox"))],
+        docid: 0,
+    });
+    let result = s.execute();
+    assert!(matches!(result, Err(Error::UserError(UserError::InvalidSearchCursor))));
+}
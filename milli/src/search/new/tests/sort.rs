@@ -9,6 +9,8 @@ This module tests the `sort` ranking rule:
 6. documents with either: (1) no value, (2) null, or (3) an object for the field-to-sort appear at the end of the bucket
 7. boolean values are translated to strings
 8. if a field contains an array, it is sorted by the best value in the array according to the sort rule
+9. the sort modifiers place the documents without a value first and choose the value of the arrays
+10. the random sort returns the documents in an order depending on its seed only
 */
 
 use big_s::S;
@@ -18,7 +20,7 @@ use meili_snap::insta;
 use crate::index::tests::TempIndex;
 use crate::search::new::tests::collect_field_values;
 use crate::{
-    score_details, AscDesc, Criterion, Member, Search, SearchResult, TermsMatchingStrategy,
+    score_details, AscDesc, Criterion, Filter, Member, Search, SearchResult, TermsMatchingStrategy,
 };
 
 fn create_index() -> TempIndex {
@@ -354,3 +356,91 @@ fn test_redacted() {
     insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4, 5, 22, 23, 13, 1, 3, 12, 21, 11, 20, 6, 7, 8, 9, 10, 14, 15]");
     insta::assert_json_snapshot!(document_scores_json);
 }
+
+#[test]
+fn test_sort_modifiers() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("price"), S("ratings") });
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "price": 10, "ratings": [1, 9] },
+            { "id": 1, "price": null, "ratings": [4] },
+            { "id": 2, "price": 5, "ratings": [2, 3, 7] },
+            { "id": 3 },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let sort = |criterion: &str| {
+        let mut s = Search::new(&txn, &index);
+        s.sort_criteria(vec![criterion.parse().unwrap()]);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+        format!("{documents_ids:?}")
+    };
+
+    // the documents without a value come last by default
+    insta::assert_snapshot!(sort("price:asc"), @"[2, 0, 1, 3]");
+    insta::assert_snapshot!(sort("price:asc:nullsFirst"), @"[1, 3, 2, 0]");
+    insta::assert_snapshot!(sort("price:desc:nullsFirst"), @"[1, 3, 0, 2]");
+    insta::assert_snapshot!(sort("price:desc:nullsLast"), @"[0, 2, 1, 3]");
+
+    // the arrays are sorted by their best value by default
+    insta::assert_snapshot!(sort("ratings:asc"), @"[0, 2, 1, 3]");
+    insta::assert_snapshot!(sort("ratings:asc:max"), @"[1, 2, 0, 3]");
+    insta::assert_snapshot!(sort("ratings:desc:min"), @"[1, 2, 0, 3]");
+    insta::assert_snapshot!(sort("ratings:desc:avg"), @"[0, 1, 2, 3]");
+    insta::assert_snapshot!(sort("ratings:asc:avg:nullsFirst"), @"[3, 1, 2, 0]");
+}
+
+#[test]
+fn test_random_sort() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_filterable_fields(hashset! { S("even") });
+        })
+        .unwrap();
+
+    let documents: Vec<_> =
+        (0..10).map(|id| serde_json::json!({ "id": id, "even": id % 2 == 0 })).collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let sort = |criterion: &str, filter: Option<&str>| {
+        let mut s = Search::new(&txn, &index);
+        s.sort_criteria(vec![criterion.parse().unwrap()]);
+        if let Some(filter) = filter {
+            s.filter(Filter::from_str(filter).unwrap().unwrap());
+        }
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+        documents_ids
+    };
+
+    let documents_ids = sort("_random(42)", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[4, 1, 6, 2, 8, 3, 9, 0, 7, 5]");
+
+    // the same seed always gives the same order, the direction reverses it
+    assert_eq!(sort("_random(42):asc", None), documents_ids);
+    let mut reversed = sort("_random(42):desc", None);
+    reversed.reverse();
+    assert_eq!(reversed, documents_ids);
+
+    let documents_ids = sort("_random(7)", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 8, 5, 7, 0, 9, 4, 6, 3, 2]");
+
+    // the relative order of the documents doesn't depend on the other candidates
+    let documents_ids = sort("_random(42)", Some("even = true"));
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[4, 6, 2, 8, 0]");
+}
@@ -0,0 +1,94 @@
+/*!
+This module tests the static boost ranking rule:
+
+1. it is applied after the relevancy ranking rules and before the custom sort rules
+2. the boost curve defines how the values of the boost attribute are grouped
+3. the documents without a value for the boost attribute are the least boosted ones
+4. it is also applied to the placeholder searches
+*/
+
+use crate::index::tests::TempIndex;
+use crate::static_boost::{BoostCurve, StaticBoost};
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index(curve: BoostCurve) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Desc("id".to_owned())]);
+            s.set_static_boost(StaticBoost { attribute: "popularity".to_owned(), curve });
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick fox", "popularity": 10 },
+            { "id": 1, "text": "the quick fox", "popularity": 1000 },
+            { "id": 2, "text": "the quick fox", "popularity": 100 },
+            { "id": 3, "text": "the quick", "popularity": 100000 },
+            { "id": 4, "text": "the quick fox" },
+            { "id": 5, "text": "the quick fox", "popularity": 900 },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_static_boost() {
+    let index = create_index(BoostCurve::Log);
+    let txn = index.read_txn().unwrap();
+
+    // the most popular document doesn't contain all the query words
+    let mut s = Search::new(&txn, &index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 1, 2, 4, 0, 3]);
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![3, 5, 1, 2, 4, 0]);
+}
+
+#[test]
+fn test_static_boost_curve() {
+    let index = create_index(BoostCurve::Linear);
+    let txn = index.read_txn().unwrap();
+
+    // the values are too small compared to the most popular document to be boosted
+    let mut s = Search::new(&txn, &index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 4, 2, 1, 0, 3]);
+    drop(txn);
+
+    index
+        .update_settings(|s| {
+            s.set_static_boost(StaticBoost {
+                attribute: "popularity".to_owned(),
+                curve: BoostCurve::Log,
+            });
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 1, 2, 4, 0, 3]);
+    drop(txn);
+
+    index.update_settings(|s| s.reset_static_boost()).unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![5, 4, 2, 1, 0, 3]);
+}
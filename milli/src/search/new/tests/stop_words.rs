@@ -7,12 +7,14 @@ This module tests the following properties about stop words:
 - If a query consists only of stop words, a placeholder query is used instead
 - A prefix word is never ignored, even if the prefix is a stop word
 - Phrases consisting only of stop words are ignored
+- The stop words of a localized attributes rule are only ignored when searching the attributes of the rule
 */
 
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 use crate::index::tests::TempIndex;
+use crate::localized_attributes_rules::LocalizedAttributesRule;
 use crate::{db_snap, Search, SearchResult, TermsMatchingStrategy};
 
 fn create_index() -> TempIndex {
@@ -458,3 +460,41 @@ fn test_stop_words_in_phrase() {
     // As a result the scores are empty lists
     insta::assert_snapshot!(format!("{document_scores:#?}"));
 }
+
+#[test]
+fn test_localized_stop_words_scoped_to_their_rule() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_localized_attributes_rules(vec![LocalizedAttributesRule {
+                attribute_patterns: vec!["*_fr".to_owned()],
+                locales: vec!["fra".to_owned()],
+                stop_words: BTreeSet::from_iter(["le".to_owned()]),
+            }]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "title_en": "Le Mans", "title_fr": "Le chat" },
+            { "id": 1, "title_en": "A dog", "title_fr": "Un chien" },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    // `le` is not a stop word of `title_en`, it isn't ignored when searching all the attributes
+    let mut s = Search::new(&txn, &index);
+    s.query("le ");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0]");
+
+    // the search only applies to the attributes of the rule, `le` is ignored
+    let searchable_attributes = ["title_fr".to_owned()];
+    let mut s = Search::new(&txn, &index);
+    s.query("le ");
+    s.searchable_attributes(&searchable_attributes);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1]");
+}
@@ -0,0 +1,128 @@
+/*!
+This module tests the following properties about structured queries:
+- the documents must match all the `must` clauses and none of the `must_not` clauses
+- the `should` clauses rank the documents by the sum of the boosts of the ones they match
+- without other clauses, the documents must match at least one of the `should` clauses
+- the filter clauses restrict the documents like the filter of the search
+- the double quotes of the texts of the clauses don't need to be escaped
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{
+    Criterion, Filter, QueryClause, Search, SearchResult, StructuredQuery, TermsMatchingStrategy,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned()]);
+            s.set_filterable_fields(maplit::hashset! { "id".to_owned() });
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "the red running shoes" },
+            { "id": 1, "title": "blue running shoes for kids" },
+            { "id": 2, "title": "red shoes" },
+            { "id": 3, "title": "running socks" },
+            { "id": 4, "title": "shoes with a red sole" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, structured_query: StructuredQuery) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.structured_query(structured_query);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    documents_ids
+}
+
+fn term(text: &str, boost: f64) -> QueryClause {
+    QueryClause::Term { text, boost }
+}
+
+#[test]
+fn must_and_should_clauses() {
+    let index = create_index();
+
+    let query = StructuredQuery {
+        must: vec![term("shoes", 1.0)],
+        should: vec![term("running", 1.0)],
+        must_not: vec![],
+    };
+    assert_eq!(search(&index, query.clone()), vec![0, 1, 2, 4]);
+
+    let query = StructuredQuery { must_not: vec![term("kids", 1.0)], ..query };
+    assert_eq!(search(&index, query), vec![0, 2, 4]);
+
+    let query = StructuredQuery {
+        must: vec![QueryClause::Phrase { text: "red shoes", boost: 1.0 }],
+        ..Default::default()
+    };
+    assert_eq!(search(&index, query), vec![2]);
+
+    // the double quotes are separators like in any text
+    let query = StructuredQuery { must: vec![term("\"red\" sole", 1.0)], ..Default::default() };
+    assert_eq!(search(&index, query), vec![4]);
+}
+
+#[test]
+fn should_clauses_boosts() {
+    let index = create_index();
+
+    let query = StructuredQuery {
+        should: vec![term("socks", 1.0), term("kids", 2.0)],
+        ..Default::default()
+    };
+    assert_eq!(search(&index, query), vec![1, 3]);
+
+    let query = StructuredQuery {
+        should: vec![term("socks", 2.0), term("kids", 1.0)],
+        ..Default::default()
+    };
+    assert_eq!(search(&index, query), vec![3, 1]);
+
+    // two clauses outrank a more boosted one
+    let query = StructuredQuery {
+        must: vec![term("shoes", 1.0)],
+        should: vec![term("red", 1.0), term("sole", 1.0), term("kids", 1.5)],
+        must_not: vec![],
+    };
+    assert_eq!(search(&index, query), vec![4, 1, 0, 2]);
+
+    // the matching strategy doesn't make the `should` clauses mandatory
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.structured_query(StructuredQuery {
+        should: vec![term("socks", 1.0), term("kids", 2.0)],
+        ..Default::default()
+    });
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![1, 3]);
+}
+
+#[test]
+fn filter_clauses() {
+    let index = create_index();
+
+    let query = StructuredQuery {
+        must: vec![term("shoes", 1.0)],
+        must_not: vec![QueryClause::Filter(Filter::from_str("id = 0").unwrap().unwrap())],
+        ..Default::default()
+    };
+    assert_eq!(search(&index, query), vec![1, 2, 4]);
+
+    let query = StructuredQuery {
+        must: vec![QueryClause::Filter(Filter::from_str("id > 2").unwrap().unwrap())],
+        ..Default::default()
+    };
+    assert_eq!(search(&index, query), vec![3, 4]);
+}
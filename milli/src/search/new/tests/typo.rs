@@ -6,7 +6,8 @@ This module tests the following properties:
 3. Words which are < `min_word_len_one_typo` are not typo tolerant
 4. Words which are >= `min_word_len_one_typo` but < `min_word_len_two_typos` can have one typo
 5. Words which are >= `min_word_len_two_typos` can have two typos
-6. A typo on the first letter of a word counts as two typos
+6. A typo on the first letter of a word counts as two typos, and is forbidden when
+`disable_typo_on_first_character` is set
 7. Phrases are not typo tolerant
 8. 2grams can have 1 typo if they are larger than `min_word_len_two_typos`
 9. 3grams are not typo tolerant (but they can be split into two words)
@@ -16,6 +17,8 @@ if `words` doesn't exist before it.
 12. Prefix tolerance costs nothing according to the typo ranking rule
 13. Split words cost 1 typo according to the typo ranking rule
 14. Synonyms cost nothing according to the typo ranking rule
+15. Words containing digits are not typo tolerant when `authorize_typos_on_numbers` is disabled,
+otherwise only their letters can be omitted, added, or swapped
 */
 
 use std::collections::BTreeMap;
@@ -230,6 +233,105 @@ fn test_default_typo() {
     "###);
 }
 
+#[test]
+fn test_typo_on_first_character() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+            s.set_min_word_len_one_typo(3);
+            s.set_min_word_len_two_typos(4);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "ball" },
+            { "id": 1, "text": "mall" },
+            { "id": 2, "text": "malt" },
+        ]))
+        .unwrap();
+
+    // the typo on the first letter counts as two typos
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("mall");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2]");
+    drop(txn);
+
+    index
+        .update_settings(|s| {
+            s.set_disable_typo_on_first_character(true);
+        })
+        .unwrap();
+
+    // but is forbidden once disabled, the other typos being still allowed
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("mall");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2]");
+}
+
+#[test]
+fn test_typo_on_numbers() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "xps9370" },
+            { "id": 1, "text": "xps9371" },
+            { "id": 2, "text": "xp9370" },
+            { "id": 3, "text": "pxs9370" },
+            { "id": 4, "text": "xpsd9370" },
+        ]))
+        .unwrap();
+
+    // the letters of the words containing digits can be omitted, added or swapped by default,
+    // but their digits can't change
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("xps9370");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 3, 4]");
+    drop(txn);
+
+    index
+        .update_settings(|s| {
+            s.set_authorize_typos_on_numbers(false);
+        })
+        .unwrap();
+
+    // they are then matched exactly
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("xps9370");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0]");
+    drop(txn);
+
+    index
+        .update_settings(|s| {
+            s.reset_authorize_typos_on_numbers();
+        })
+        .unwrap();
+
+    // until the setting is reset
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("xps9370");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 3, 4]");
+}
+
 #[test]
 fn test_phrase_no_typo_allowed() {
     let index = create_index();
@@ -0,0 +1,109 @@
+/*!
+This module tests the vocabulary given along with a search:
+
+1. its synonyms are used in addition to the ones of the index
+2. its synonyms of several words are used like the ones of the index
+3. the synonyms of the index can be disabled
+4. its stop words are ignored in addition to the ones of the index
+5. the stop words of the index can be disabled
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use big_s::S;
+use maplit::{btreemap, btreeset, hashmap};
+
+use crate::index::tests::TempIndex;
+use crate::{QueryVocabulary, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_synonyms(btreemap! { S("tv") => vec![S("television")] });
+            s.set_stop_words(btreeset! { S("the") });
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the television set" },
+            { "id": 1, "text": "a new smartphone" },
+            { "id": 2, "text": "the cell phone" },
+            { "id": 3, "text": "laptop computer" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, query: &str, vocabulary: Option<QueryVocabulary>) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    if let Some(vocabulary) = vocabulary {
+        s.vocabulary(vocabulary);
+    }
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    documents_ids
+}
+
+#[test]
+fn test_vocabulary_synonyms() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "mobile", None), Vec::<u32>::new());
+
+    let synonyms = btreemap! {
+        S("Mobile") => vec![S("smartphone"), S("Cell Phone")],
+        S("pocket computer") => vec![S("smartphone")],
+    };
+    let stop_words = BTreeSet::new();
+    let mut vocabulary = QueryVocabulary {
+        synonyms: &synonyms,
+        stop_words: &stop_words,
+        disable_index_synonyms: false,
+        disable_index_stop_words: false,
+    };
+    assert_eq!(search(&index, "mobile", Some(vocabulary)), vec![1, 2]);
+    assert_eq!(search(&index, "pocket computer", Some(vocabulary)), vec![1]);
+    // the synonyms of the index are kept
+    assert_eq!(search(&index, "tv", Some(vocabulary)), vec![0]);
+
+    vocabulary.disable_index_synonyms = true;
+    assert_eq!(search(&index, "mobile", Some(vocabulary)), vec![1, 2]);
+    assert_eq!(search(&index, "tv", Some(vocabulary)), Vec::<u32>::new());
+
+    // the vocabulary of a search doesn't change the index
+    let txn = index.read_txn().unwrap();
+    let synonyms = index.synonyms(&txn).unwrap();
+    assert_eq!(synonyms, hashmap! { vec![S("tv")] => vec![vec![S("television")]] });
+}
+
+#[test]
+fn test_vocabulary_stop_words() {
+    let index = create_index();
+
+    assert_eq!(search(&index, "new laptop", None), Vec::<u32>::new());
+    assert_eq!(search(&index, "the television", None), vec![0]);
+
+    let synonyms = BTreeMap::new();
+    let stop_words = btreeset! { S("new") };
+    let mut vocabulary = QueryVocabulary {
+        synonyms: &synonyms,
+        stop_words: &stop_words,
+        disable_index_synonyms: false,
+        disable_index_stop_words: false,
+    };
+    assert_eq!(search(&index, "new laptop", Some(vocabulary)), vec![3]);
+    assert_eq!(search(&index, "the television", Some(vocabulary)), vec![0]);
+
+    // the stop words of the index are not indexed, they can't be found once disabled
+    vocabulary.disable_index_stop_words = true;
+    assert_eq!(search(&index, "new laptop", Some(vocabulary)), vec![3]);
+    assert_eq!(search(&index, "the television", Some(vocabulary)), Vec::<u32>::new());
+}
@@ -10,11 +10,15 @@ account by the proximity ranking rule.
 5. Unclosed double quotes still make a phrase
 6. The `all` term matching strategy does not remove any term from the query
 7. The search is capable of returning no results if no documents match the query
+8. The documents whose ranking score is below the threshold are not returned nor counted
+9. The documents are returned unranked and flagged as degraded once the time budget is exceeded
+10. The `frequency` term matching strategy starts removing the most frequent terms of the query,
+the terms matching no document being removed first
 */
 
 use crate::index::tests::TempIndex;
 use crate::search::new::tests::collect_field_values;
-use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy, TimeBudget};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -175,6 +179,55 @@ fn test_words_tms_last_simple() {
     insta::assert_snapshot!(format!("{document_scores:?}"), @"[]");
 }
 
+#[test]
+fn test_words_tms_ranking_score_threshold() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.ranking_score_threshold(0.75);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+
+    // the documents matching less than 7 of the 9 words have a score below 0.75
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[9, 10, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 8, 6, 7]");
+    assert_eq!(candidates.len(), 15);
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.ranking_score_threshold(0.75);
+    s.offset(10);
+    s.limit(10);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[21, 22, 8, 6, 7]");
+    assert_eq!(candidates.len(), 15);
+}
+
+#[test]
+fn test_words_tms_time_budget() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, degraded, .. } = s.execute().unwrap();
+    assert!(!degraded);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[9, 10, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 8, 6, 7, 5, 4, 11, 12, 3]");
+
+    // the budget is exceeded before the first bucket, the candidates are returned by id
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.time_budget(TimeBudget::new(std::time::Duration::ZERO));
+    let SearchResult { documents_ids, candidates, degraded, .. } = s.execute().unwrap();
+    assert!(degraded);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]");
+    assert_eq!(candidates.len(), 22);
+}
+
 #[test]
 fn test_words_tms_last_phrase() {
     let index = create_index();
@@ -458,3 +511,44 @@ fn test_words_tms_all() {
     let texts = collect_field_values(&index, &txn, "text", &documents_ids);
     insta::assert_debug_snapshot!(texts, @"[]");
 }
+
+#[test]
+fn test_words_tms_frequency() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the cat" },
+            { "id": 1, "text": "the dog" },
+            { "id": 2, "text": "the bird" },
+            { "id": 3, "text": "a cat sleeps" },
+            { "id": 4, "text": "the sleeping cat" },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the cat");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4, 1, 2]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the cat");
+    s.terms_matching_strategy(TermsMatchingStrategy::Frequency);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4, 3]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("the cat unicorn");
+    s.terms_matching_strategy(TermsMatchingStrategy::Frequency);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4, 3]");
+}
@@ -5,9 +5,32 @@ use roaring::RoaringBitmap;
 
 use super::ranking_rules::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use crate::score_details::{self, ScoreDetails};
-use crate::vector::DistributionShift;
+use crate::vector::quantized::{hamming_distance, quantize, OVERSAMPLING};
+use crate::vector::{Distance, DistributionShift};
 use crate::{DocumentId, Result, SearchContext, SearchLogger};
 
+/// Below this ratio of the embedded documents, the filtered candidates are scored
+/// exhaustively rather than by traversing the vector store.
+const EXHAUSTIVE_SELECTIVITY_RATIO: f64 = 0.05;
+
+/// Below this number of filtered candidates, scoring them exhaustively is always cheaper
+/// than traversing the vector store.
+const EXHAUSTIVE_CANDIDATES_THRESHOLD: u64 = 2_000;
+
+/// How the candidates of a vector search are selected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateSelection {
+    /// Compares the target with the vectors of the candidates when the filter is selective,
+    /// retrieves the approximate nearest neighbors from the vector store otherwise.
+    Auto,
+    /// Retrieves the approximate nearest neighbors from the vector store.
+    #[default]
+    Approximate,
+    /// Compares the target with the vectors of all the candidates,
+    /// retrieving the exact nearest neighbors.
+    Exhaustive,
+}
+
 pub struct VectorSort<Q: RankingRuleQueryTrait> {
     query: Option<Q>,
     target: Vec<f32>,
@@ -16,6 +39,9 @@ pub struct VectorSort<Q: RankingRuleQueryTrait> {
     limit: usize,
     distribution_shift: Option<DistributionShift>,
     embedder_index: u8,
+    binary_quantized: bool,
+    distance: Distance,
+    candidate_selection: CandidateSelection,
 }
 
 impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
@@ -26,12 +52,19 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         limit: usize,
         distribution_shift: Option<DistributionShift>,
         embedder_name: &str,
+        candidate_selection: CandidateSelection,
     ) -> Result<Self> {
         let embedder_index = ctx
             .index
             .embedder_category_id
             .get(ctx.txn, embedder_name)?
             .ok_or_else(|| crate::UserError::InvalidEmbedder(embedder_name.to_owned()))?;
+        let config = ctx
+            .index
+            .embedding_configs(ctx.txn)?
+            .into_iter()
+            .find_map(|(name, config)| (name == embedder_name).then_some(config))
+            .unwrap_or_default();
 
         Ok(Self {
             query: None,
@@ -41,6 +74,9 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
             limit,
             distribution_shift,
             embedder_index,
+            binary_quantized: config.binary_quantized,
+            distance: config.distance,
+            candidate_selection,
         })
     }
 
@@ -49,10 +85,33 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
         ctx: &mut SearchContext<'_>,
         vector_candidates: &RoaringBitmap,
     ) -> Result<()> {
+        let index = ctx.index;
+        let mut results = match self.distance {
+            Distance::Cosine => self.nns(ctx, index.vector_arroy, vector_candidates)?,
+            Distance::DotProduct => {
+                self.nns(ctx, index.vector_arroy_dot_product, vector_candidates)?
+            }
+            Distance::Euclidean => {
+                self.nns(ctx, index.vector_arroy_euclidean, vector_candidates)?
+            }
+        };
+        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
+        self.cached_sorted_docids = results.into_iter();
+
+        Ok(())
+    }
+
+    /// Retrieves the nearest neighbors of the target in the arroy database of the distance.
+    fn nns<D: arroy::Distance>(
+        &self,
+        ctx: &SearchContext<'_>,
+        database: arroy::Database<D>,
+        vector_candidates: &RoaringBitmap,
+    ) -> Result<Vec<(DocumentId, f32, Vec<f32>)>> {
         let writer_index = (self.embedder_index as u16) << 8;
         let readers: std::result::Result<Vec<_>, _> = (0..=u8::MAX)
             .map_while(|k| {
-                arroy::Reader::open(ctx.txn, writer_index | (k as u16), ctx.index.vector_arroy)
+                arroy::Reader::open(ctx.txn, writer_index | (k as u16), database)
                     .map(Some)
                     .or_else(|e| match e {
                         arroy::Error::MissingMetadata => Ok(None),
@@ -64,23 +123,117 @@ impl<Q: RankingRuleQueryTrait> VectorSort<Q> {
 
         let readers = readers?;
 
+        if self.is_exhaustive(&readers, vector_candidates) {
+            return self.exhaustive_nns(ctx, &readers, vector_candidates);
+        }
+
+        if self.binary_quantized {
+            return self.quantized_nns(ctx, &readers, vector_candidates);
+        }
+
         let target = &self.target;
         let mut results = Vec::new();
 
         for reader in readers.iter() {
             let nns_by_vector =
                 reader.nns_by_vector(ctx.txn, target, self.limit, None, Some(vector_candidates))?;
-            let vectors: std::result::Result<Vec<_>, _> = nns_by_vector
-                .iter()
-                .map(|(docid, _)| reader.item_vector(ctx.txn, *docid).transpose().unwrap())
-                .collect();
-            let vectors = vectors?;
-            results.extend(nns_by_vector.into_iter().zip(vectors).map(|((x, y), z)| (x, y, z)));
+            // the distances are computed again, the ones of arroy aren't normalized like ours
+            for (docid, _) in nns_by_vector {
+                if let Some(vector) = reader.item_vector(ctx.txn, docid)? {
+                    let distance = self.distance.distance(target, &vector);
+                    results.push((docid, distance, vector));
+                }
+            }
         }
-        results.sort_unstable_by_key(|(_, distance, _)| OrderedFloat(*distance));
-        self.cached_sorted_docids = results.into_iter();
 
-        Ok(())
+        Ok(results)
+    }
+
+    /// Whether the candidates must be scored exhaustively rather than retrieved from the
+    /// vector store, depending on how selective the filters are when in the `Auto` mode.
+    fn is_exhaustive<D: arroy::Distance>(
+        &self,
+        readers: &[arroy::Reader<D>],
+        vector_candidates: &RoaringBitmap,
+    ) -> bool {
+        match self.candidate_selection {
+            CandidateSelection::Approximate => false,
+            CandidateSelection::Exhaustive => true,
+            CandidateSelection::Auto => {
+                let candidates = vector_candidates.len();
+                // the vectors are packed in the first readers, the first one holds
+                // a vector for each document having embeddings
+                let embedded = readers.first().map_or(0.0, |reader| reader.n_items() as f64);
+                candidates <= EXHAUSTIVE_CANDIDATES_THRESHOLD
+                    || (candidates as f64) < embedded * EXHAUSTIVE_SELECTIVITY_RATIO
+            }
+        }
+    }
+
+    /// Compares the target with every vector of the candidates, keeping the nearest ones.
+    fn exhaustive_nns<D: arroy::Distance>(
+        &self,
+        ctx: &SearchContext<'_>,
+        readers: &[arroy::Reader<D>],
+        vector_candidates: &RoaringBitmap,
+    ) -> Result<Vec<(DocumentId, f32, Vec<f32>)>> {
+        let mut results = Vec::new();
+        for docid in vector_candidates {
+            for reader in readers {
+                if let Some(vector) = reader.item_vector(ctx.txn, docid)? {
+                    let distance = self.distance.distance(&self.target, &vector);
+                    results.push((docid, distance, vector));
+                }
+            }
+        }
+
+        if results.len() > self.limit {
+            results
+                .select_nth_unstable_by_key(self.limit, |(_, distance, _)| OrderedFloat(*distance));
+            results.truncate(self.limit);
+        }
+        Ok(results)
+    }
+
+    /// Retrieves candidates by comparing the quantized vectors with the quantized target,
+    /// then re-scores them with their full-precision vectors.
+    fn quantized_nns<D: arroy::Distance>(
+        &self,
+        ctx: &SearchContext<'_>,
+        readers: &[arroy::Reader<D>],
+        vector_candidates: &RoaringBitmap,
+    ) -> Result<Vec<(DocumentId, f32, Vec<f32>)>> {
+        let quantized_target = quantize(&self.target);
+        let mut candidates = Vec::new();
+        let iter = ctx
+            .index
+            .vector_binary_quantized
+            .remap_key_type::<heed::types::Bytes>()
+            .prefix_iter(ctx.txn, &[self.embedder_index])?
+            .remap_key_type::<crate::heed_codec::BEU16BEU32Codec>();
+        for result in iter {
+            let ((writer_index, docid), quantized) = result?;
+            if vector_candidates.contains(docid) {
+                let distance = hamming_distance(&quantized_target, quantized);
+                candidates.push((distance, docid, (writer_index & 0xff) as usize));
+            }
+        }
+
+        let oversampled = self.limit.saturating_mul(OVERSAMPLING);
+        if candidates.len() > oversampled {
+            candidates.select_nth_unstable(oversampled);
+            candidates.truncate(oversampled);
+        }
+
+        let mut results = Vec::with_capacity(candidates.len());
+        for (_, docid, k) in candidates {
+            let Some(reader) = readers.get(k) else { continue };
+            if let Some(vector) = reader.item_vector(ctx.txn, docid)? {
+                let distance = self.distance.distance(&self.target, &vector);
+                results.push((docid, distance, vector));
+            }
+        }
+        Ok(results)
     }
 }
 
@@ -127,7 +280,7 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for VectorSort<Q> {
 
         for (docid, distance, vector) in self.cached_sorted_docids.by_ref() {
             if vector_candidates.contains(docid) {
-                let score = 1.0 - distance;
+                let score = self.distance.similarity(distance);
                 let score = self
                     .distribution_shift
                     .map(|distribution| distribution.shift(score))
@@ -0,0 +1,161 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use charabia::{Normalize, Tokenizer, TokenizerBuilder};
+use heed::RoTxn;
+
+use crate::{Index, Result};
+
+/// The synonyms and stop words given along with a search request, they are used
+/// in addition to the ones of the index, or instead of them when the index ones are disabled.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryVocabulary<'a> {
+    pub synonyms: &'a BTreeMap<String, Vec<String>>,
+    pub stop_words: &'a BTreeSet<String>,
+    pub disable_index_synonyms: bool,
+    pub disable_index_stop_words: bool,
+}
+
+/// The normalized vocabulary of a search.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
+    stop_words: BTreeSet<String>,
+    disable_index_synonyms: bool,
+    disable_index_stop_words: bool,
+}
+
+impl Vocabulary {
+    /// Normalizes the synonyms and stop words the same way the settings of the index are.
+    pub(crate) fn new(index: &Index, rtxn: &RoTxn, vocabulary: QueryVocabulary) -> Result<Self> {
+        let QueryVocabulary {
+            synonyms,
+            stop_words,
+            disable_index_synonyms,
+            disable_index_stop_words,
+        } = vocabulary;
+
+        let mut stop_words: BTreeSet<String> = stop_words
+            .iter()
+            .map(|w| w.as_str().normalize(&Default::default()).into_owned())
+            .collect();
+        stop_words.retain(|w| !w.is_empty());
+
+        // the synonyms are normalized knowing the stop words of the search
+        let mut all_stop_words = stop_words.clone();
+        if !disable_index_stop_words {
+            if let Some(index_stop_words) = index.stop_words(rtxn)? {
+                all_stop_words.extend(index_stop_words.stream().into_strs()?);
+            }
+        }
+        let all_stop_words = fst::Set::from_iter(all_stop_words)?;
+
+        let mut builder = TokenizerBuilder::new();
+        builder.stop_words(&all_stop_words);
+
+        let separators = index.allowed_separators(rtxn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
+
+        let dictionary = index.dictionary(rtxn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+
+        let tokenizer = builder.build();
+
+        let mut normalized_synonyms: HashMap<_, Vec<_>> = HashMap::new();
+        for (word, synonyms) in synonyms {
+            let normalized_word = normalize(&tokenizer, word);
+            let synonyms = synonyms
+                .iter()
+                .map(|synonym| normalize(&tokenizer, synonym))
+                .filter(|synonym| !synonym.is_empty());
+            if !normalized_word.is_empty() {
+                normalized_synonyms.entry(normalized_word).or_default().extend(synonyms);
+            }
+        }
+        normalized_synonyms.retain(|_, synonyms| {
+            synonyms.sort_unstable();
+            synonyms.dedup();
+            !synonyms.is_empty()
+        });
+
+        Ok(Vocabulary {
+            synonyms: normalized_synonyms,
+            stop_words,
+            disable_index_synonyms,
+            disable_index_stop_words,
+        })
+    }
+
+    /// Returns the synonyms of the phrase, the ones of the index are kept unless disabled.
+    pub(crate) fn words_synonyms<S: AsRef<str>>(
+        &self,
+        index: &Index,
+        rtxn: &RoTxn,
+        words: &[S],
+    ) -> Result<Option<Vec<Vec<String>>>> {
+        let mut synonyms = match self.disable_index_synonyms {
+            true => None,
+            false => index.words_synonyms(rtxn, words)?,
+        };
+
+        let words: Vec<_> = words.iter().map(|s| s.as_ref().to_owned()).collect();
+        if let Some(query_synonyms) = self.synonyms.get(&words) {
+            let synonyms = synonyms.get_or_insert_with(Vec::new);
+            for synonym in query_synonyms {
+                if !synonyms.contains(synonym) {
+                    synonyms.push(synonym.clone());
+                }
+            }
+        }
+
+        Ok(synonyms)
+    }
+
+    /// Returns the greatest number of words of the phrases having synonyms.
+    pub(crate) fn max_synonym_words(&self, index: &Index, rtxn: &RoTxn) -> Result<usize> {
+        let index_max = match self.disable_index_synonyms {
+            true => 0,
+            false => index.max_synonym_words(rtxn)?,
+        };
+        let query_max = self.synonyms.keys().map(Vec::len).max().unwrap_or_default();
+        Ok(index_max.max(query_max))
+    }
+
+    /// Returns the stop words to ignore in the query, `None` when the ones of the index
+    /// can be used unchanged.
+    pub(crate) fn stop_words<D: AsRef<[u8]>>(
+        &self,
+        index_stop_words: Option<&fst::Set<D>>,
+    ) -> Result<Option<fst::Set<Vec<u8>>>> {
+        let index_stop_words = index_stop_words.filter(|_| !self.disable_index_stop_words);
+        if self.stop_words.is_empty() && !self.disable_index_stop_words {
+            return Ok(None);
+        }
+
+        let mut stop_words = self.stop_words.clone();
+        if let Some(index_stop_words) = index_stop_words {
+            stop_words.extend(index_stop_words.stream().into_strs()?);
+        }
+        Ok(Some(fst::Set::from_iter(stop_words)?))
+    }
+}
+
+fn normalize(tokenizer: &Tokenizer, text: &str) -> Vec<String> {
+    tokenizer
+        .tokenize(text)
+        .filter_map(|token| {
+            if token.is_word() && !token.lemma().is_empty() {
+                Some(token.lemma().to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// The number of buckets in which the documents are grouped by the static boost
+/// ranking rule, in addition to the bucket of the lowest boost values.
+pub const BOOST_BUCKETS: u16 = 10;
+
+/// A numeric field of the documents blended into the relevancy of the search results,
+/// right before the custom sort rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticBoost {
+    pub attribute: String,
+    #[serde(default)]
+    pub curve: BoostCurve,
+}
+
+/// The curve applied to the values of the boost attribute before they are bucketed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BoostCurve {
+    /// The boost grows proportionally to the value.
+    Linear,
+    /// The boost grows quickly for the lowest values and flattens for the highest ones,
+    /// which suits long tailed distributions such as popularity counts.
+    #[default]
+    Log,
+    /// The boost mostly separates the values below and above the middle of the range.
+    Sigmoid,
+}
+
+impl BoostCurve {
+    /// The steepness of the sigmoid curve around the middle of the range.
+    const SIGMOID_STEEPNESS: f64 = 10.0;
+
+    /// Returns the boost of the value, between 0 and 1, given the range of the values.
+    pub fn apply(&self, value: f64, min: f64, max: f64) -> f64 {
+        if max <= min {
+            return 1.0;
+        }
+        let value = value.clamp(min, max);
+        let boost = match self {
+            BoostCurve::Linear => (value - min) / (max - min),
+            BoostCurve::Log => (value - min).ln_1p() / (max - min).ln_1p(),
+            BoostCurve::Sigmoid => {
+                let sigmoid = |x: f64| 1.0 / (1.0 + (-Self::SIGMOID_STEEPNESS * (x - 0.5)).exp());
+                let (low, high) = (sigmoid(0.0), sigmoid(1.0));
+                (sigmoid((value - min) / (max - min)) - low) / (high - low)
+            }
+        };
+        boost.clamp(0.0, 1.0)
+    }
+
+    /// Returns the bucket of the value, between 0 and [`BOOST_BUCKETS`], the higher the better.
+    pub fn bucket(&self, value: f64, min: f64, max: f64) -> u16 {
+        (self.apply(value, min, max) * BOOST_BUCKETS as f64).floor() as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost_curves() {
+        for curve in [BoostCurve::Linear, BoostCurve::Log, BoostCurve::Sigmoid] {
+            assert_eq!(curve.bucket(0.0, 0.0, 1000.0), 0);
+            assert_eq!(curve.bucket(1000.0, 0.0, 1000.0), BOOST_BUCKETS);
+            // out of range and empty ranges
+            assert_eq!(curve.bucket(-5.0, 0.0, 1000.0), 0);
+            assert_eq!(curve.bucket(2000.0, 0.0, 1000.0), BOOST_BUCKETS);
+            assert_eq!(curve.bucket(42.0, 42.0, 42.0), BOOST_BUCKETS);
+        }
+
+        assert_eq!(BoostCurve::Linear.bucket(100.0, 0.0, 1000.0), 1);
+        assert_eq!(BoostCurve::Log.bucket(100.0, 0.0, 1000.0), 6);
+        assert_eq!(BoostCurve::Sigmoid.bucket(100.0, 0.0, 1000.0), 0);
+
+        assert_eq!(BoostCurve::Linear.bucket(600.0, 0.0, 1000.0), 6);
+        assert_eq!(BoostCurve::Log.bucket(600.0, 0.0, 1000.0), 9);
+        assert_eq!(BoostCurve::Sigmoid.bucket(600.0, 0.0, 1000.0), 7);
+
+        assert_eq!(BoostCurve::Linear.bucket(900.0, 0.0, 1000.0), 9);
+        assert_eq!(BoostCurve::Log.bucket(900.0, 0.0, 1000.0), 9);
+        assert_eq!(BoostCurve::Sigmoid.bucket(900.0, 0.0, 1000.0), 9);
+    }
+}
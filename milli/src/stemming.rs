@@ -0,0 +1,62 @@
+use rust_stemmers::{Algorithm, Stemmer};
+
+/// Returns the snowball algorithm stemming the language of the ISO 639-3 locale, if any.
+fn algorithm(locale: &str) -> Option<Algorithm> {
+    let algorithm = match locale {
+        "ara" => Algorithm::Arabic,
+        "dan" => Algorithm::Danish,
+        "nld" => Algorithm::Dutch,
+        "eng" => Algorithm::English,
+        "fin" => Algorithm::Finnish,
+        "fra" => Algorithm::French,
+        "deu" => Algorithm::German,
+        "ell" => Algorithm::Greek,
+        "hun" => Algorithm::Hungarian,
+        "ita" => Algorithm::Italian,
+        "nob" => Algorithm::Norwegian,
+        "por" => Algorithm::Portuguese,
+        "ron" => Algorithm::Romanian,
+        "rus" => Algorithm::Russian,
+        "spa" => Algorithm::Spanish,
+        "swe" => Algorithm::Swedish,
+        "tam" => Algorithm::Tamil,
+        "tur" => Algorithm::Turkish,
+        _ => return None,
+    };
+    Some(algorithm)
+}
+
+/// Returns the stemmer of the language of the ISO 639-3 locale, if any.
+pub fn stemmer(locale: &str) -> Option<Stemmer> {
+    algorithm(locale).map(Stemmer::create)
+}
+
+/// Returns `true` if the language of the ISO 639-3 locale can be stemmed.
+pub fn is_stemmable_locale(locale: &str) -> bool {
+    algorithm(locale).is_some()
+}
+
+/// Returns the prefix of the keys of the word stems FST of the words having
+/// this stem in the language of the locale.
+///
+/// The keys of the FST are made of the locale, the stem and the word, separated by a `\0`.
+pub(crate) fn word_stem_prefix(locale: &str, stem: &str) -> String {
+    format!("{locale}\0{stem}\0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stemmer;
+
+    #[test]
+    fn stemmers() {
+        let english = stemmer("eng").unwrap();
+        assert_eq!(english.stem("running"), english.stem("runs"));
+        assert_eq!(english.stem("fruitlessly"), "fruitless");
+
+        let french = stemmer("fra").unwrap();
+        assert_eq!(french.stem("chevaux"), french.stem("cheval"));
+
+        assert!(stemmer("jpn").is_none());
+    }
+}
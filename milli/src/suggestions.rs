@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io;
+
+use fst::automaton::{Automaton, Str};
+use fst::{IntoStreamer, Streamer};
+use heed::{RoTxn, RwTxn};
+use obkv::KvReader;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::update::del_add::{DelAdd, KvReaderDelAdd};
+use crate::{normalize_facet, FieldId, FieldsIdsMap, Index, InternalError, Result};
+
+/// The number of suggestions returned when no limit is given.
+pub const DEFAULT_SUGGESTIONS_LIMIT: usize = 10;
+
+/// Separates the normalized value from the original one in the keys of the suggestions FST,
+/// the prefix searches being done on the normalized part.
+const KEY_SEPARATOR: u8 = 0;
+
+/// A completion of the query, along with the number of documents containing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Completes a query with the values of the suggestable fields, only reading the
+/// suggestions FST of the index.
+pub struct Suggest<'a> {
+    index: &'a Index,
+    rtxn: &'a RoTxn<'a>,
+    query: String,
+    limit: usize,
+}
+
+impl<'a> Suggest<'a> {
+    pub fn new(index: &'a Index, rtxn: &'a RoTxn<'a>, query: impl Into<String>) -> Self {
+        Suggest { index, rtxn, query: query.into(), limit: DEFAULT_SUGGESTIONS_LIMIT }
+    }
+
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the values starting with the query, the most frequent ones first.
+    ///
+    /// The values only differing by their case or their accents are suggested once,
+    /// with the spelling found in the most documents.
+    pub fn execute(&self) -> Result<Vec<Suggestion>> {
+        let fst = self.index.suggestions_fst(self.rtxn)?;
+        let prefix = normalize_facet(&self.query);
+
+        // the original spelling with the most documents, and the total number of documents
+        let mut suggestions: HashMap<Vec<u8>, (Vec<u8>, u64, u64)> = HashMap::new();
+        let mut stream = fst.search(Str::new(&prefix).starts_with()).into_stream();
+        while let Some((key, count)) = stream.next() {
+            let Some(position) = key.iter().position(|&byte| byte == KEY_SEPARATOR) else {
+                continue;
+            };
+            let (normalized, original) = (&key[..position], &key[position + 1..]);
+            let (spelling, spelling_count, total) =
+                suggestions.entry(normalized.to_vec()).or_insert_with(|| (Vec::new(), 0, 0));
+            if count > *spelling_count {
+                *spelling = original.to_vec();
+                *spelling_count = count;
+            }
+            *total += count;
+        }
+
+        let mut suggestions: Vec<_> = suggestions
+            .into_iter()
+            .map(|(normalized, (spelling, _, total))| (normalized, spelling, total))
+            .collect();
+        suggestions.sort_unstable_by(|(left, _, left_count), (right, _, right_count)| {
+            right_count.cmp(left_count).then_with(|| left.cmp(right))
+        });
+
+        Ok(suggestions
+            .into_iter()
+            .take(self.limit)
+            .map(|(_, spelling, count)| Suggestion {
+                value: String::from_utf8_lossy(&spelling).into_owned(),
+                count,
+            })
+            .collect())
+    }
+}
+
+/// Rebuilds the suggestions FST from the values of the suggestable fields of the documents.
+///
+/// A value is counted once per document, however many times the document contains it.
+pub(crate) fn build_suggestions(wtxn: &mut RwTxn, index: &Index) -> Result<()> {
+    let fields = index.suggestable_fields(wtxn)?;
+    if fields.is_empty() {
+        index.delete_suggestions_fst(wtxn)?;
+        return Ok(());
+    }
+
+    let fields_ids_map = index.fields_ids_map(wtxn)?;
+    let mut counts: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+    for result in index.all_documents(wtxn)? {
+        let (_docid, obkv) = result?;
+
+        let mut values = BTreeSet::new();
+        for (field_id, bytes) in obkv.iter() {
+            let Some(name) = fields_ids_map.name(field_id) else { continue };
+            collect_field_values(&fields, name, bytes, &mut values)?;
+        }
+
+        for key in values.iter().filter_map(|value| suggestion_key(value)) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+
+    let fst = fst::Map::from_iter(counts)?;
+    index.put_suggestions_fst(wtxn, &fst)?;
+    Ok(())
+}
+
+/// Updates the suggestions FST with the values of the suggestable fields of the documents
+/// changed by a document operation, without reading the other documents.
+///
+/// The original documents hold the deleted and added versions of the fields of each changed
+/// document, the values only found in one of the versions are uncounted or counted.
+pub(crate) fn update_suggestions(
+    wtxn: &mut RwTxn,
+    index: &Index,
+    original_documents: &File,
+    fields_ids_map: &FieldsIdsMap,
+) -> Result<()> {
+    let fields = index.suggestable_fields(wtxn)?;
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    // Safety: the file of the original documents isn't modified during the indexing
+    let original_documents = unsafe { memmap2::Mmap::map(original_documents)? };
+    let mut cursor =
+        grenad::Reader::new(io::Cursor::new(&original_documents[..]))?.into_cursor()?;
+    let mut deltas: BTreeMap<Vec<u8>, i64> = BTreeMap::new();
+    while let Some((_docid, document)) = cursor.move_on_next()? {
+        let mut deleted = BTreeSet::new();
+        let mut added = BTreeSet::new();
+        for (field_id, value) in KvReader::<FieldId>::new(document).iter() {
+            let Some(name) = fields_ids_map.name(field_id) else { continue };
+            let value = KvReaderDelAdd::new(value);
+            if let Some(bytes) = value.get(DelAdd::Deletion) {
+                collect_field_values(&fields, name, bytes, &mut deleted)?;
+            }
+            if let Some(bytes) = value.get(DelAdd::Addition) {
+                collect_field_values(&fields, name, bytes, &mut added)?;
+            }
+        }
+
+        for key in deleted.difference(&added).filter_map(|value| suggestion_key(value)) {
+            *deltas.entry(key).or_default() -= 1;
+        }
+        for key in added.difference(&deleted).filter_map(|value| suggestion_key(value)) {
+            *deltas.entry(key).or_default() += 1;
+        }
+    }
+
+    if deltas.values().all(|&delta| delta == 0) {
+        return Ok(());
+    }
+
+    let mut counts: BTreeMap<Vec<u8>, u64> = {
+        let fst = index.suggestions_fst(wtxn)?;
+        let mut stream = fst.stream();
+        let mut counts = BTreeMap::new();
+        while let Some((key, count)) = stream.next() {
+            counts.insert(key.to_vec(), count);
+        }
+        counts
+    };
+    for (key, delta) in deltas {
+        match counts.get(&key).copied().unwrap_or_default() as i64 + delta {
+            count if count > 0 => counts.insert(key, count as u64),
+            _ => counts.remove(&key),
+        };
+    }
+
+    let fst = fst::Map::from_iter(counts)?;
+    index.put_suggestions_fst(wtxn, &fst)?;
+    Ok(())
+}
+
+/// Collects the values of the suggestable fields within the top-level field.
+fn collect_field_values(
+    fields: &HashSet<String>,
+    name: &str,
+    bytes: &[u8],
+    output: &mut BTreeSet<String>,
+) -> Result<()> {
+    // the paths of the suggestable fields within this top-level field
+    let paths: Vec<_> = fields
+        .iter()
+        .filter_map(|field| match field.strip_prefix(name)? {
+            "" => Some(""),
+            path => path.strip_prefix('.'),
+        })
+        .collect();
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let value: Value = serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)?;
+    for path in paths {
+        collect_strings(&value, path, output);
+    }
+    Ok(())
+}
+
+/// Returns the key of the value in the suggestions FST, made of its normalized and original forms.
+fn suggestion_key(value: &str) -> Option<Vec<u8>> {
+    let normalized = normalize_facet(value);
+    if normalized.is_empty() {
+        return None;
+    }
+    let mut key = normalized.into_bytes();
+    key.push(KEY_SEPARATOR);
+    key.extend_from_slice(value.as_bytes());
+    Some(key)
+}
+
+/// Collects the strings found at the path of the value, looking into the arrays.
+fn collect_strings(value: &Value, path: &str, output: &mut BTreeSet<String>) {
+    match value {
+        Value::String(string) if path.is_empty() => {
+            let string = string.trim();
+            if !string.is_empty() {
+                output.insert(string.to_string());
+            }
+        }
+        Value::Array(values) => {
+            values.iter().for_each(|value| collect_strings(value, path, output));
+        }
+        Value::Object(object) if !path.is_empty() => {
+            for (key, value) in object {
+                match path.strip_prefix(key.as_str()) {
+                    Some("") => collect_strings(value, "", output),
+                    Some(rest) => {
+                        if let Some(rest) = rest.strip_prefix('.') {
+                            collect_strings(value, rest, output);
+                        }
+                    }
+                    None => (),
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+
+    #[test]
+    fn suggest_values_of_suggestable_fields() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_suggestable_fields(hashset! { S("title"), S("brand.name") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "title": "Iphone 15", "brand": { "name": "Apple" } },
+                { "id": 1, "title": "iPhone 15", "brand": { "name": "Apple" } },
+                { "id": 2, "title": "iPhone 14", "brand": { "name": "Apple" } },
+                { "id": 3, "title": "Ipad", "brand": [{ "name": "Apple" }, { "name": "Ipsum" }] },
+                { "id": 4, "title": "Galaxy", "description": "iphone killer" },
+                { "id": 5, "title": "iPhone 15" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let suggestions = Suggest::new(&index, &rtxn, "ip").execute().unwrap();
+        let suggestions: Vec<_> = suggestions.iter().map(|s| (s.value.as_str(), s.count)).collect();
+        assert_eq!(
+            suggestions,
+            vec![("iPhone 15", 3), ("Ipad", 1), ("iPhone 14", 1), ("Ipsum", 1)]
+        );
+
+        let suggestions = Suggest::new(&index, &rtxn, "APP").limit(1).execute().unwrap();
+        assert_eq!(suggestions, vec![Suggestion { value: S("Apple"), count: 4 }]);
+        drop(rtxn);
+
+        index.delete_documents(vec![S("3")]);
+        let rtxn = index.read_txn().unwrap();
+        let suggestions = Suggest::new(&index, &rtxn, "ap").execute().unwrap();
+        assert_eq!(suggestions, vec![Suggestion { value: S("Apple"), count: 3 }]);
+        drop(rtxn);
+
+        // only the values of the replaced documents are counted again
+        index
+            .add_documents(documents!([
+                { "id": 2, "title": "iPhone 15" },
+                { "id": 6, "title": "Ipod", "brand": { "name": "Apple" } },
+            ]))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let suggestions = Suggest::new(&index, &rtxn, "ip").execute().unwrap();
+        let suggestions: Vec<_> = suggestions.iter().map(|s| (s.value.as_str(), s.count)).collect();
+        assert_eq!(suggestions, vec![("iPhone 15", 4), ("Ipod", 1)]);
+        let suggestions = Suggest::new(&index, &rtxn, "ap").execute().unwrap();
+        assert_eq!(suggestions, vec![Suggestion { value: S("Apple"), count: 3 }]);
+
+        // the suggestions are the same as the ones rebuilt from all the documents
+        let incremental = index.suggestions_fst(&rtxn).unwrap().as_fst().as_bytes().to_vec();
+        drop(rtxn);
+        let mut wtxn = index.write_txn().unwrap();
+        build_suggestions(&mut wtxn, &index).unwrap();
+        let rebuilt = index.suggestions_fst(&wtxn).unwrap().as_fst().as_bytes().to_vec();
+        assert_eq!(incremental, rebuilt);
+        drop(wtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_suggestable_fields();
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert!(Suggest::new(&index, &rtxn, "ap").execute().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use heed::RwTxn;
+use time::OffsetDateTime;
+
+use crate::documents::{DocumentIdExtractionError, PrimaryKey};
+use crate::error::{FieldIdMapMissingEntry, InternalError};
+use crate::external_documents_ids::{DocumentOperation, DocumentOperationKind};
+use crate::{all_obkv_to_json, DocumentId, Index, Result, UserError};
+
+/// Re-keys all the documents of the index to a new primary key.
+///
+/// The external documents ids are rebuilt from the stored documents, the internal
+/// documents ids do not change and nothing needs to be reindexed.
+pub struct ChangePrimaryKey<'t, 'i> {
+    wtxn: &'t mut RwTxn<'i>,
+    index: &'i Index,
+    primary_key: String,
+}
+
+impl<'t, 'i> ChangePrimaryKey<'t, 'i> {
+    pub fn new(
+        wtxn: &'t mut RwTxn<'i>,
+        index: &'i Index,
+        primary_key: String,
+    ) -> ChangePrimaryKey<'t, 'i> {
+        ChangePrimaryKey { wtxn, index, primary_key }
+    }
+
+    /// Returns the number of documents that have been re-keyed.
+    ///
+    /// Fails if a document is missing the new primary key, has an invalid document id
+    /// or shares its document id with another document.
+    pub fn execute(self) -> Result<u64> {
+        puffin::profile_function!();
+
+        let mut fields_ids_map = self.index.fields_ids_map(self.wtxn)?;
+        fields_ids_map.insert(&self.primary_key).ok_or(UserError::AttributeLimitReached)?;
+        let primary_key = PrimaryKey::new(&self.primary_key, &fields_ids_map).ok_or_else(|| {
+            InternalError::FieldIdMapMissingEntry(FieldIdMapMissingEntry::FieldName {
+                field_name: self.primary_key.clone(),
+                process: "ChangePrimaryKey::execute",
+            })
+        })?;
+
+        let mut external_ids = HashMap::<String, DocumentId>::new();
+        for result in self.index.all_documents(self.wtxn)? {
+            let (docid, obkv) = result?;
+            let external_id = match primary_key.document_id(&obkv, &fields_ids_map)? {
+                Ok(external_id) => external_id,
+                Err(DocumentIdExtractionError::InvalidDocumentId(user_error)) => {
+                    return Err(user_error.into())
+                }
+                Err(DocumentIdExtractionError::MissingDocumentId) => {
+                    return Err(UserError::MissingDocumentId {
+                        primary_key: primary_key.name().to_string(),
+                        document: all_obkv_to_json(obkv, &fields_ids_map)?,
+                    }
+                    .into())
+                }
+                Err(DocumentIdExtractionError::TooManyDocumentIds(_)) => {
+                    return Err(UserError::TooManyDocumentIds {
+                        primary_key: primary_key.name().to_string(),
+                        document: all_obkv_to_json(obkv, &fields_ids_map)?,
+                    }
+                    .into())
+                }
+            };
+
+            match external_ids.entry(external_id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(docid);
+                }
+                Entry::Occupied(entry) => {
+                    return Err(UserError::DuplicateDocumentId {
+                        primary_key: primary_key.name().to_string(),
+                        document_id: entry.key().clone(),
+                    }
+                    .into())
+                }
+            }
+        }
+
+        let number_of_documents = external_ids.len() as u64;
+        let operations = external_ids
+            .into_iter()
+            .map(|(external_id, internal_id)| DocumentOperation {
+                external_id,
+                internal_id,
+                kind: DocumentOperationKind::Create,
+            })
+            .collect();
+
+        self.index.external_documents_ids.clear(self.wtxn)?;
+        self.index.external_documents_ids().apply(self.wtxn, operations)?;
+        self.index.put_fields_ids_map(self.wtxn, &fields_ids_map)?;
+        self.index.put_primary_key(self.wtxn, &self.primary_key)?;
+        self.index.set_updated_at(self.wtxn, &OffsetDateTime::now_utc())?;
+
+        Ok(number_of_documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::Error;
+
+    #[test]
+    fn change_primary_key_of_a_non_empty_index() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 1, "isbn": "978-0", "name": "kevin" },
+                { "id": 2, "isbn": "978-1", "name": "kevina" },
+                { "id": 3, "isbn": "978-2", "name": "benoit" },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let count = ChangePrimaryKey::new(&mut wtxn, &index, S("isbn")).execute().unwrap();
+        wtxn.commit().unwrap();
+        assert_eq!(count, 3);
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.primary_key(&rtxn).unwrap(), Some("isbn"));
+        let external_documents_ids = index.external_documents_ids();
+        assert_eq!(external_documents_ids.get(&rtxn, "978-1").unwrap(), Some(1));
+        assert_eq!(external_documents_ids.get(&rtxn, "2").unwrap(), None);
+        drop(rtxn);
+
+        // documents are now replaced using the new primary key
+        index.add_documents(documents!([{ "id": 1, "isbn": "978-2", "name": "bob" }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 3);
+    }
+
+    #[test]
+    fn change_primary_key_with_duplicate_values() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevin" },
+                { "id": 2, "name": "kevin" },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let error = ChangePrimaryKey::new(&mut wtxn, &index, S("name")).execute().unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::DuplicateDocumentId { .. })));
+
+        let error = ChangePrimaryKey::new(&mut wtxn, &index, S("age")).execute().unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::MissingDocumentId { .. })));
+    }
+}
@@ -2,6 +2,7 @@ use heed::RwTxn;
 use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 
+use crate::update::ReindexScope;
 use crate::{FieldDistribution, Index, Result};
 
 pub struct ClearDocuments<'t, 'i> {
@@ -37,14 +38,22 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             facet_id_string_docids,
             facet_id_normalized_string_strings,
             facet_id_string_fst,
+            facet_id_distribution,
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
+            vector_arroy_dot_product,
+            vector_arroy_euclidean,
+            vector_binary_quantized,
+            vector_prompt_hashes,
             embedder_category_id: _,
             documents,
+            phrase_synonyms: _,
+            // the clears are themselves recorded in the change feed
+            change_feed: _,
         } = self.index;
 
         let empty_roaring = RoaringBitmap::default();
@@ -55,10 +64,13 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         // We clean some of the main engine datastructures.
         self.index.put_words_fst(self.wtxn, &fst::Set::default())?;
         self.index.put_words_prefixes_fst(self.wtxn, &fst::Set::default())?;
+        self.index.put_word_stems_fst(self.wtxn, &fst::Set::default())?;
+        self.index.put_word_romanizations_fst(self.wtxn, &fst::Set::default())?;
         self.index.put_documents_ids(self.wtxn, &empty_roaring)?;
         self.index.put_field_distribution(self.wtxn, &FieldDistribution::default())?;
         self.index.delete_geo_rtree(self.wtxn)?;
         self.index.delete_geo_faceted_documents_ids(self.wtxn)?;
+        self.index.delete_suggestions_fst(self.wtxn)?;
 
         // Clear the other databases.
         external_documents_ids.clear(self.wtxn)?;
@@ -76,6 +88,7 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         facet_id_f64_docids.clear(self.wtxn)?;
         facet_id_normalized_string_strings.clear(self.wtxn)?;
         facet_id_string_fst.clear(self.wtxn)?;
+        facet_id_distribution.clear(self.wtxn)?;
         facet_id_exists_docids.clear(self.wtxn)?;
         facet_id_is_null_docids.clear(self.wtxn)?;
         facet_id_is_empty_docids.clear(self.wtxn)?;
@@ -84,6 +97,10 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         field_id_docid_facet_strings.clear(self.wtxn)?;
         // vector
         vector_arroy.clear(self.wtxn)?;
+        vector_arroy_dot_product.clear(self.wtxn)?;
+        vector_arroy_euclidean.clear(self.wtxn)?;
+        vector_binary_quantized.clear(self.wtxn)?;
+        vector_prompt_hashes.clear(self.wtxn)?;
 
         documents.clear(self.wtxn)?;
 
@@ -91,6 +108,102 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
     }
 }
 
+/// Clears the databases that are rebuilt by a reindexing of the given scope.
+///
+/// The documents and their ids are kept when the scope doesn't include the documents.
+pub(crate) fn clear_reindexed_databases(
+    wtxn: &mut RwTxn,
+    index: &Index,
+    scope: ReindexScope,
+) -> Result<()> {
+    if scope.documents {
+        ClearDocuments::new(wtxn, index).execute()?;
+        return Ok(());
+    }
+
+    let Index {
+        env: _env,
+        main: _main,
+        external_documents_ids: _,
+        word_docids,
+        exact_word_docids,
+        word_prefix_docids,
+        exact_word_prefix_docids,
+        word_pair_proximity_docids,
+        word_position_docids,
+        word_fid_docids,
+        field_id_word_count_docids,
+        word_prefix_position_docids,
+        word_prefix_fid_docids,
+        script_language_docids,
+        facet_id_f64_docids,
+        facet_id_string_docids,
+        facet_id_normalized_string_strings,
+        facet_id_string_fst,
+        facet_id_distribution,
+        facet_id_exists_docids,
+        facet_id_is_null_docids,
+        facet_id_is_empty_docids,
+        field_id_docid_facet_f64s,
+        field_id_docid_facet_strings,
+        vector_arroy,
+        vector_arroy_dot_product,
+        vector_arroy_euclidean,
+        vector_binary_quantized,
+        // the hashes describe the committed embeddings that can be reused by the reindexing
+        vector_prompt_hashes: _,
+        embedder_category_id: _,
+        documents: _,
+        phrase_synonyms: _,
+        change_feed: _,
+    } = index;
+
+    if scope.words {
+        index.put_words_fst(wtxn, &fst::Set::default())?;
+        index.put_words_prefixes_fst(wtxn, &fst::Set::default())?;
+        index.put_word_stems_fst(wtxn, &fst::Set::default())?;
+        index.put_word_romanizations_fst(wtxn, &fst::Set::default())?;
+        word_docids.clear(wtxn)?;
+        exact_word_docids.clear(wtxn)?;
+        word_prefix_docids.clear(wtxn)?;
+        exact_word_prefix_docids.clear(wtxn)?;
+        word_position_docids.clear(wtxn)?;
+        word_fid_docids.clear(wtxn)?;
+        field_id_word_count_docids.clear(wtxn)?;
+        word_prefix_position_docids.clear(wtxn)?;
+        word_prefix_fid_docids.clear(wtxn)?;
+        script_language_docids.clear(wtxn)?;
+    }
+
+    if scope.words || scope.proximities {
+        word_pair_proximity_docids.clear(wtxn)?;
+    }
+
+    if scope.facets {
+        index.delete_geo_rtree(wtxn)?;
+        index.delete_geo_faceted_documents_ids(wtxn)?;
+        facet_id_f64_docids.clear(wtxn)?;
+        facet_id_normalized_string_strings.clear(wtxn)?;
+        facet_id_string_fst.clear(wtxn)?;
+        facet_id_distribution.clear(wtxn)?;
+        facet_id_exists_docids.clear(wtxn)?;
+        facet_id_is_null_docids.clear(wtxn)?;
+        facet_id_is_empty_docids.clear(wtxn)?;
+        facet_id_string_docids.clear(wtxn)?;
+        field_id_docid_facet_f64s.clear(wtxn)?;
+        field_id_docid_facet_strings.clear(wtxn)?;
+    }
+
+    if scope.vectors {
+        vector_arroy.clear(wtxn)?;
+        vector_arroy_dot_product.clear(wtxn)?;
+        vector_arroy_euclidean.clear(wtxn)?;
+        vector_binary_quantized.clear(wtxn)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,6 +234,8 @@ mod tests {
 
         assert!(index.words_fst(&rtxn).unwrap().is_empty());
         assert!(index.words_prefixes_fst(&rtxn).unwrap().is_empty());
+        assert!(index.word_stems_fst(&rtxn).unwrap().is_empty());
+        assert!(index.word_romanizations_fst(&rtxn).unwrap().is_empty());
         assert!(index.external_documents_ids().is_empty(&rtxn).unwrap());
         assert!(index.documents_ids(&rtxn).unwrap().is_empty());
         assert!(index.field_distribution(&rtxn).unwrap().is_empty());
@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::str;
+
+use fst::Streamer;
+use heed::RoTxn;
+
+use crate::{Index, Result};
+
+/// Computes an FST whose keys are derived from the words of the index, like their stems.
+///
+/// When the words added to the index are given, only their keys are derived and merged with
+/// the ones of the current FST, which are kept like the words FST keeps the words that are
+/// not in the documents anymore. All the words of the index are derived otherwise.
+pub(crate) fn derive_words_fst(
+    rtxn: &RoTxn,
+    index: &Index,
+    current_fst: fst::Set<Cow<[u8]>>,
+    new_words: Option<&fst::Set<Vec<u8>>>,
+    mut derive: impl FnMut(&str, &mut BTreeSet<String>) -> Result<()>,
+) -> Result<fst::Set<Vec<u8>>> {
+    let mut keys = BTreeSet::new();
+    match new_words {
+        Some(new_words) => {
+            derive_keys(new_words, &mut keys, &mut derive)?;
+            let keys = fst::Set::from_iter(keys)?;
+            let mut builder = fst::SetBuilder::memory();
+            builder.extend_stream(current_fst.op().add(&keys).union())?;
+            Ok(builder.into_set())
+        }
+        None => {
+            derive_keys(&index.words_fst(rtxn)?, &mut keys, &mut derive)?;
+            Ok(fst::Set::from_iter(keys)?)
+        }
+    }
+}
+
+fn derive_keys<A: AsRef<[u8]>>(
+    words: &fst::Set<A>,
+    keys: &mut BTreeSet<String>,
+    derive: &mut impl FnMut(&str, &mut BTreeSet<String>) -> Result<()>,
+) -> Result<()> {
+    let mut stream = words.stream();
+    while let Some(bytes) = stream.next() {
+        derive(str::from_utf8(bytes)?, keys)?;
+    }
+    Ok(())
+}
+
+/// Returns the words of the current words FST that are not in the previous one.
+pub(crate) fn new_words<A: AsRef<[u8]>>(
+    previous_words_fst: &fst::Set<A>,
+    index: &Index,
+    rtxn: &RoTxn,
+) -> Result<fst::Set<Vec<u8>>> {
+    let words_fst = index.words_fst(rtxn)?;
+    let mut builder = fst::SetBuilder::memory();
+    builder.extend_stream(words_fst.op().add(previous_words_fst).difference())?;
+    Ok(builder.into_set())
+}
@@ -76,7 +76,7 @@ pub const FACET_MAX_GROUP_SIZE: u8 = 8;
 pub const FACET_GROUP_SIZE: u8 = 4;
 pub const FACET_MIN_LEVEL_SIZE: u8 = 5;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::iter::FromIterator;
@@ -84,7 +84,7 @@ use std::iter::FromIterator;
 use charabia::normalizer::{Normalize, NormalizerOption};
 use grenad::{CompressionType, SortAlgorithm};
 use heed::types::{Bytes, DecodeIgnore, SerdeJson};
-use heed::BytesEncode;
+use heed::{BytesDecode, BytesEncode};
 use log::debug;
 use time::OffsetDateTime;
 
@@ -93,9 +93,10 @@ use super::FacetsUpdateBulk;
 use crate::facet::FacetType;
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
 use crate::heed_codec::BytesRefCodec;
-use crate::update::index_documents::create_sorter;
+use crate::search::facet::CachedFacetValues;
+use crate::update::index_documents::{as_cloneable_grenad, create_sorter, valid_lmdb_key};
 use crate::update::merge_btreeset_string;
-use crate::{BEU16StrCodec, Index, Result, MAX_FACET_VALUE_LENGTH};
+use crate::{BEU16StrCodec, FieldId, Index, Result, MAX_FACET_VALUE_LENGTH};
 
 pub mod bulk;
 pub mod incremental;
@@ -145,6 +146,18 @@ impl<'i> FacetsUpdate<'i> {
         debug!("Computing and writing the facet values levels docids into LMDB on disk...");
         self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
 
+        // The values whose documents change, only their cached number of documents is updated
+        let mut touched_values: BTreeMap<FieldId, Vec<Vec<u8>>> = BTreeMap::new();
+        let mut cursor = unsafe { as_cloneable_grenad(&self.delta_data)? }.into_cursor()?;
+        while let Some((key, _)) = cursor.move_on_next()? {
+            if !valid_lmdb_key(key) {
+                continue;
+            }
+            let key = FacetGroupKeyCodec::<BytesRefCodec>::bytes_decode(key)
+                .map_err(heed::Error::Encoding)?;
+            touched_values.entry(key.field_id).or_default().push(key.left_bound.to_vec());
+        }
+
         // See self::comparison_bench::benchmark_facet_indexing
         if self.delta_data.len() >= (self.database.len(wtxn)? / 50) {
             let field_ids =
@@ -170,6 +183,30 @@ impl<'i> FacetsUpdate<'i> {
             incremental_update.execute(wtxn)?;
         }
 
+        // We update the touched values of the distributions over all the documents, the values
+        // of the types not cached yet, or having too many values, being computed from scratch
+        for (field_id, touched_values) in touched_values {
+            let mut distribution =
+                self.index.facet_id_distribution.get(wtxn, &field_id)?.unwrap_or_default();
+            for facet_type in [FacetType::Number, FacetType::String] {
+                let cached_values = match facet_type {
+                    FacetType::Number => &mut distribution.numbers,
+                    FacetType::String => &mut distribution.strings,
+                };
+                *cached_values = match cached_values.take() {
+                    Some(mut values) if facet_type == self.facet_type => {
+                        let touched_values = touched_values.iter().map(Vec::as_slice);
+                        values
+                            .update(wtxn, self.index, field_id, facet_type, touched_values)?
+                            .then_some(values)
+                    }
+                    Some(values) => Some(values),
+                    None => CachedFacetValues::compute(wtxn, self.index, field_id, facet_type)?,
+                };
+            }
+            self.index.facet_id_distribution.put(wtxn, &field_id, &distribution)?;
+        }
+
         // We clear the list of normalized-for-search facets
         // and the previous FSTs to compute everything from scratch
         self.index.facet_id_normalized_string_strings.clear(wtxn)?;
@@ -1,23 +1,30 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io::{BufWriter, Read, Seek};
+use std::ops::ControlFlow;
 use std::result::Result as StdResult;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::documents::{
-    DocumentIdExtractionError, DocumentsBatchIndex, DocumentsBatchReader,
-    EnrichedDocumentsBatchReader, PrimaryKey, DEFAULT_PRIMARY_KEY,
+    DocumentIdExtractionError, DocumentsBatchCursor, DocumentsBatchIndex, DocumentsBatchReader,
+    EnrichedDocumentsBatchReader, FieldIdMapper, PrimaryKey, DEFAULT_PRIMARY_KEY,
 };
 use crate::error::{GeoError, InternalError, UserError};
+use crate::geojson::GeoShape;
 use crate::update::index_documents::{obkv_to_object, writer_into_reader};
+use crate::update::ingestion_mapping::renamed_field;
 use crate::{FieldId, Index, Result};
 
-/// This function validates and enrich the documents by checking that:
+/// This function validates and enrich the documents, once their fields are renamed by the
+/// ingestion mapping of the index, by checking that:
 ///  - we can infer a primary key,
 ///  - all the documents id exist and are extracted,
 ///  - the validity of them but also,
-///  - the validity of the `_geo` field depending on the settings.
+///  - the validity of the `_geo` field depending on the settings,
+///  - the validity of the `_geojson` field depending on the settings,
+///  - the validity of the reserved `_version` field.
 ///
 /// # Panics
 ///
@@ -33,32 +40,183 @@ pub fn enrich_documents_batch<R: Read + Seek>(
     let (mut cursor, mut documents_batch_index) = reader.into_cursor_and_fields_index();
 
     let mut external_ids = tempfile::tempfile().map(BufWriter::new).map(grenad::Writer::new)?;
+
+    let primary_key_name = match validate_documents(
+        rtxn,
+        index,
+        autogenerate_docids,
+        &mut cursor,
+        &mut documents_batch_index,
+        |position, _document, _documents_batch_index, document_id| {
+            let document_id = match document_id {
+                Ok(document_id) => document_id,
+                Err(user_error) => return Ok(ControlFlow::Break(user_error)),
+            };
+            let document_id = serde_json::to_vec(&document_id).map_err(InternalError::SerdeJson)?;
+            external_ids.insert(position.to_be_bytes(), document_id)?;
+            Ok(ControlFlow::Continue(()))
+        },
+    )? {
+        Ok(primary_key_name) => primary_key_name,
+        Err(user_error) => return Ok(Err(user_error)),
+    };
+
+    let external_ids = writer_into_reader(external_ids)?;
+    let reader = EnrichedDocumentsBatchReader::new(
+        DocumentsBatchReader::new(cursor, documents_batch_index),
+        primary_key_name,
+        external_ids,
+    )?;
+
+    Ok(Ok(reader))
+}
+
+/// Validates and enriches the documents like [`enrich_documents_batch`], but skips the documents
+/// that can't be indexed, e.g. because of an invalid id or `_geo` field, instead of rejecting
+/// the whole batch.
+///
+/// Returns the valid documents and the errors of the skipped ones with their position in the
+/// batch, or the error rejecting the whole batch, e.g. when no primary key can be inferred.
+pub fn enrich_valid_documents<R: Read + Seek>(
+    rtxn: &heed::RoTxn,
+    index: &Index,
+    autogenerate_docids: bool,
+    reader: DocumentsBatchReader<R>,
+) -> Result<StdResult<(EnrichedDocumentsBatchReader<R>, Vec<(u32, UserError)>), UserError>> {
+    puffin::profile_function!();
+
+    let (mut cursor, mut documents_batch_index) = reader.into_cursor_and_fields_index();
+
+    let mut external_ids = tempfile::tempfile().map(BufWriter::new).map(grenad::Writer::new)?;
+    let mut invalid_documents = Vec::new();
+
+    let primary_key_name = match validate_documents(
+        rtxn,
+        index,
+        autogenerate_docids,
+        &mut cursor,
+        &mut documents_batch_index,
+        |position, _document, _documents_batch_index, document_id| {
+            match document_id {
+                Ok(document_id) => {
+                    let document_id =
+                        serde_json::to_vec(&document_id).map_err(InternalError::SerdeJson)?;
+                    external_ids.insert(position.to_be_bytes(), document_id)?;
+                }
+                Err(user_error) => invalid_documents.push((position, user_error)),
+            }
+            Ok(ControlFlow::Continue(()))
+        },
+    )? {
+        Ok(primary_key_name) => primary_key_name,
+        Err(user_error) => return Ok(Err(user_error)),
+    };
+
+    // the documents without an external id are skipped by the reader
+    let external_ids = writer_into_reader(external_ids)?;
+    let reader = EnrichedDocumentsBatchReader::new(
+        DocumentsBatchReader::new(cursor, documents_batch_index),
+        primary_key_name,
+        external_ids,
+    )?;
+
+    Ok(Ok((reader, invalid_documents)))
+}
+
+/// The ids of the reserved fields of a batch that must be validated.
+#[derive(Clone, Copy)]
+struct ReservedFieldIds {
+    geo: Option<FieldId>,
+    geojson: Option<FieldId>,
+    version: Option<FieldId>,
+}
+
+/// The fields of a batch named after their renaming by the ingestion mapping of the index.
+#[derive(Clone, Copy)]
+struct RenamedFields<'a> {
+    documents_batch_index: &'a DocumentsBatchIndex,
+    ingestion_mapping: &'a BTreeMap<String, String>,
+}
+
+impl FieldIdMapper for RenamedFields<'_> {
+    /// Returns the field of the batch renamed into `name`, or else the field named `name`
+    /// when it isn't renamed itself.
+    fn id(&self, name: &str) -> Option<FieldId> {
+        if self.ingestion_mapping.is_empty() {
+            return self.documents_batch_index.id(name);
+        }
+
+        let mut kept = None;
+        for (&field_id, field) in self.documents_batch_index.iter() {
+            match self.ingestion_mapping.get(field) {
+                Some(target) if target == name => return Some(field_id),
+                Some(_) => (),
+                None if field == name => kept = Some(field_id),
+                None => (),
+            }
+        }
+        kept
+    }
+}
+
+/// Validates the documents of the batch one by one, calling `on_document` with the position of
+/// each document in the batch and either its id or the reason why it can't be indexed.
+///
+/// Returns the name of the primary key, or the error rejecting the whole batch, either because
+/// no primary key can be inferred or because `on_document` stopped the validation.
+fn validate_documents<R: Read + Seek>(
+    rtxn: &heed::RoTxn,
+    index: &Index,
+    autogenerate_docids: bool,
+    cursor: &mut DocumentsBatchCursor<R>,
+    documents_batch_index: &mut DocumentsBatchIndex,
+    mut on_document: impl FnMut(
+        u32,
+        &obkv::KvReader<FieldId>,
+        &DocumentsBatchIndex,
+        StdResult<DocumentId, UserError>,
+    ) -> Result<ControlFlow<UserError>>,
+) -> Result<StdResult<String, UserError>> {
     let mut uuid_buffer = [0; uuid::fmt::Hyphenated::LENGTH];
+    let ingestion_mapping = index.ingestion_mapping(rtxn)?;
 
     // The primary key *field id* that has already been set for this index or the one
     // we will guess by searching for the first key that contains "id" as a substring.
     let primary_key = match index.primary_key(rtxn)? {
-        Some(primary_key) => match PrimaryKey::new(primary_key, &documents_batch_index) {
+        Some(primary_key) => match PrimaryKey::new(
+            primary_key,
+            &RenamedFields { documents_batch_index, ingestion_mapping: &ingestion_mapping },
+        ) {
             Some(primary_key) => primary_key,
             None if autogenerate_docids => PrimaryKey::Flat {
                 name: primary_key,
                 field_id: documents_batch_index.insert(primary_key),
             },
             None => {
-                return match cursor.next_document()? {
-                    Some(first_document) => Ok(Err(UserError::MissingDocumentId {
+                // none of the documents of the batch contain the primary key
+                let mut position = 0;
+                while let Some(document) = cursor.next_document()? {
+                    let user_error = UserError::MissingDocumentId {
                         primary_key: primary_key.to_string(),
-                        document: obkv_to_object(&first_document, &documents_batch_index)?,
-                    })),
-                    None => unreachable!("Called with reader.is_empty()"),
-                };
+                        document: obkv_to_object(&document, documents_batch_index)?,
+                    };
+                    if let ControlFlow::Break(user_error) =
+                        on_document(position, &document, documents_batch_index, Err(user_error))?
+                    {
+                        return Ok(Err(user_error));
+                    }
+                    position += 1;
+                }
+                return Ok(Ok(primary_key.to_string()));
             }
         },
         None => {
+            let renamed_fields =
+                RenamedFields { documents_batch_index, ingestion_mapping: &ingestion_mapping };
             let mut guesses: Vec<(u16, &str)> = documents_batch_index
                 .iter()
+                .map(|(field_id, name)| (*field_id, renamed_field(&ingestion_mapping, name)))
                 .filter(|(_, name)| name.to_lowercase().ends_with(DEFAULT_PRIMARY_KEY))
-                .map(|(field_id, name)| (*field_id, name.as_str()))
                 .collect();
 
             // sort the keys in a deterministic, obvious way, so that fields are always in the same order.
@@ -69,6 +227,8 @@ pub fn enrich_documents_batch<R: Read + Seek>(
                     || left_name.cmp(right_name),
                 )
             });
+            // a field renamed into the name of another field of the batch replaces it
+            guesses.dedup_by_key(|(_, name)| *name);
 
             match guesses.as_slice() {
                 [] if autogenerate_docids => PrimaryKey::Flat {
@@ -78,7 +238,10 @@ pub fn enrich_documents_batch<R: Read + Seek>(
                 [] => return Ok(Err(UserError::NoPrimaryKeyCandidateFound)),
                 [(field_id, name)] => {
                     log::info!("Primary key was not specified in index. Inferred to '{name}'");
-                    PrimaryKey::Flat { name, field_id: *field_id }
+                    PrimaryKey::Flat {
+                        name,
+                        field_id: renamed_fields.id(name).unwrap_or(*field_id),
+                    }
                 }
                 multiple => {
                     return Ok(Err(UserError::MultiplePrimaryKeyCandidatesFound {
@@ -92,66 +255,106 @@ pub fn enrich_documents_batch<R: Read + Seek>(
         }
     };
 
-    // If the settings specifies that a _geo field must be used therefore we must check the
-    // validity of it in all the documents of this batch and this is when we return `Some`.
-    let geo_field_id = match documents_batch_index.id("_geo") {
-        Some(geo_field_id)
-            if index.sortable_fields(rtxn)?.contains("_geo")
-                || index.filterable_fields(rtxn)?.contains("_geo") =>
-        {
+    let renamed_fields =
+        RenamedFields { documents_batch_index, ingestion_mapping: &ingestion_mapping };
+    let reserved_field_ids = ReservedFieldIds {
+        // If the settings specifies that a _geo field must be used therefore we must check the
+        // validity of it in all the documents of this batch and this is when we return `Some`.
+        geo: match renamed_fields.id("_geo") {
             Some(geo_field_id)
-        }
-        _otherwise => None,
+                if index.sortable_fields(rtxn)?.contains("_geo")
+                    || index.filterable_fields(rtxn)?.contains("_geo") =>
+            {
+                Some(geo_field_id)
+            }
+            _otherwise => None,
+        },
+        // Same for the _geojson field which is only used when it is filterable.
+        geojson: match renamed_fields.id("_geojson") {
+            Some(geojson_field_id) if index.filterable_fields(rtxn)?.contains("_geojson") => {
+                Some(geojson_field_id)
+            }
+            _otherwise => None,
+        },
+        version: renamed_fields.id("_version"),
     };
 
-    let mut count = 0;
+    let mut position = 0;
     while let Some(document) = cursor.next_document()? {
-        let document_id = match fetch_or_generate_document_id(
+        let document_id = validate_document(
             &document,
-            &documents_batch_index,
+            renamed_fields,
             primary_key,
+            reserved_field_ids,
             autogenerate_docids,
             &mut uuid_buffer,
-            count,
-        )? {
-            Ok(document_id) => document_id,
-            Err(user_error) => return Ok(Err(user_error)),
-        };
-
-        if let Some(geo_value) = geo_field_id.and_then(|fid| document.get(fid)) {
-            if let Err(user_error) = validate_geo_from_json(&document_id, geo_value)? {
-                return Ok(Err(UserError::from(user_error)));
-            }
+            position,
+        )?;
+        if let ControlFlow::Break(user_error) =
+            on_document(position, &document, documents_batch_index, document_id)?
+        {
+            return Ok(Err(user_error));
         }
+        position += 1;
+    }
 
-        let document_id = serde_json::to_vec(&document_id).map_err(InternalError::SerdeJson)?;
-        external_ids.insert(count.to_be_bytes(), document_id)?;
+    Ok(Ok(primary_key.name().to_string()))
+}
 
-        count += 1;
+/// Returns the id of the document after validating it and its reserved fields.
+fn validate_document(
+    document: &obkv::KvReader<FieldId>,
+    fields: RenamedFields,
+    primary_key: PrimaryKey,
+    reserved_field_ids: ReservedFieldIds,
+    autogenerate_docids: bool,
+    uuid_buffer: &mut [u8; uuid::fmt::Hyphenated::LENGTH],
+    count: u32,
+) -> Result<StdResult<DocumentId, UserError>> {
+    let document_id = match fetch_or_generate_document_id(
+        document,
+        fields,
+        primary_key,
+        autogenerate_docids,
+        uuid_buffer,
+        count,
+    )? {
+        Ok(document_id) => document_id,
+        Err(user_error) => return Ok(Err(user_error)),
+    };
+
+    if let Some(geo_value) = reserved_field_ids.geo.and_then(|fid| document.get(fid)) {
+        if let Err(user_error) = validate_geo_from_json(&document_id, geo_value)? {
+            return Ok(Err(UserError::from(user_error)));
+        }
     }
 
-    let external_ids = writer_into_reader(external_ids)?;
-    let primary_key_name = primary_key.name().to_string();
-    let reader = EnrichedDocumentsBatchReader::new(
-        DocumentsBatchReader::new(cursor, documents_batch_index),
-        primary_key_name,
-        external_ids,
-    )?;
+    if let Some(geojson_value) = reserved_field_ids.geojson.and_then(|fid| document.get(fid)) {
+        if let Err(user_error) = validate_geojson_from_json(&document_id, geojson_value)? {
+            return Ok(Err(user_error));
+        }
+    }
 
-    Ok(Ok(reader))
+    if let Some(version_value) = reserved_field_ids.version.and_then(|fid| document.get(fid)) {
+        if let Err(user_error) = validate_version_from_json(&document_id, version_value)? {
+            return Ok(Err(user_error));
+        }
+    }
+
+    Ok(Ok(document_id))
 }
 
 /// Retrieve the document id after validating it, returning a `UserError`
 /// if the id is invalid or can't be guessed.
 fn fetch_or_generate_document_id(
     document: &obkv::KvReader<FieldId>,
-    documents_batch_index: &DocumentsBatchIndex,
+    fields: RenamedFields,
     primary_key: PrimaryKey,
     autogenerate_docids: bool,
     uuid_buffer: &mut [u8; uuid::fmt::Hyphenated::LENGTH],
     count: u32,
 ) -> Result<StdResult<DocumentId, UserError>> {
-    Ok(match primary_key.document_id(document, documents_batch_index)? {
+    Ok(match primary_key.document_id(document, &fields)? {
         Ok(document_id) => Ok(DocumentId::Retrieved { value: document_id }),
         Err(DocumentIdExtractionError::InvalidDocumentId(user_error)) => Err(user_error),
         Err(DocumentIdExtractionError::MissingDocumentId) if autogenerate_docids => {
@@ -160,12 +363,12 @@ fn fetch_or_generate_document_id(
         }
         Err(DocumentIdExtractionError::MissingDocumentId) => Err(UserError::MissingDocumentId {
             primary_key: primary_key.name().to_string(),
-            document: obkv_to_object(document, documents_batch_index)?,
+            document: obkv_to_object(document, fields.documents_batch_index)?,
         }),
         Err(DocumentIdExtractionError::TooManyDocumentIds(_)) => {
             Err(UserError::TooManyDocumentIds {
                 primary_key: primary_key.name().to_string(),
-                document: obkv_to_object(document, documents_batch_index)?,
+                document: obkv_to_object(document, fields.documents_batch_index)?,
             })
         }
     })
@@ -260,3 +463,33 @@ pub fn validate_geo_from_json(id: &DocumentId, bytes: &[u8]) -> Result<StdResult
         value => Ok(Err(NotAnObject { document_id: debug_id(), value })),
     }
 }
+
+/// Checks that the reserved `_geojson` field of a document is a supported GeoJSON geometry.
+pub fn validate_geojson_from_json(
+    id: &DocumentId,
+    bytes: &[u8],
+) -> Result<StdResult<(), UserError>> {
+    let value: Value = serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)?;
+    if value.is_null() || GeoShape::from_geojson(&value).is_some() {
+        Ok(Ok(()))
+    } else {
+        let document_id = serde_json::from_slice(id.value().as_bytes())
+            .unwrap_or_else(|_| Value::from(id.debug()));
+        Ok(Err(UserError::InvalidGeoJsonField { document_id, value }))
+    }
+}
+
+/// Checks that the reserved `_version` field of a document is a positive integer.
+pub fn validate_version_from_json(
+    id: &DocumentId,
+    bytes: &[u8],
+) -> Result<StdResult<(), UserError>> {
+    let version: Value = serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)?;
+    if version.as_u64().map_or(false, |version| version > 0) {
+        Ok(Ok(()))
+    } else {
+        let document_id = serde_json::from_slice(id.value().as_bytes())
+            .unwrap_or_else(|_| Value::from(id.debug()));
+        Ok(Err(UserError::InvalidDocumentVersion { document_id, version }))
+    }
+}
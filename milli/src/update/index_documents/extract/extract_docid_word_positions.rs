@@ -11,6 +11,8 @@ use serde_json::Value;
 
 use super::helpers::{create_sorter, keep_latest_obkv, sorter_into_reader, GrenadParameters};
 use crate::error::{InternalError, SerializationError};
+use crate::identifiers;
+use crate::localized_attributes_rules::LocalizedFieldIds;
 use crate::update::del_add::{del_add_from_two_obkvs, DelAdd, KvReaderDelAdd};
 use crate::{FieldId, Result, MAX_POSITION_PER_ATTRIBUTE, MAX_WORD_LENGTH};
 
@@ -29,6 +31,8 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     stop_words: Option<&fst::Set<&[u8]>>,
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
+    localized_fields: &LocalizedFieldIds,
+    split_identifiers: bool,
     max_positions_per_attributes: Option<u32>,
 ) -> Result<(grenad::Reader<BufReader<File>>, ScriptLanguageDocidsMap)> {
     puffin::profile_function!();
@@ -59,6 +63,28 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     let mut builder = tokenizer_builder(stop_words, allowed_separators, dictionary, None);
     let tokenizer = builder.build();
 
+    // initialize the tokenizers of the localized attributes rules.
+    let localized_stop_words = localized_fields
+        .rules()
+        .map(|(_, rule_stop_words)| {
+            rule_stop_words.map(|set| fst::Set::new(set.as_fst().as_bytes())).transpose()
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut localized_builders: Vec<_> = localized_fields
+        .rules()
+        .zip(&localized_stop_words)
+        .map(|((script_language, _), rule_stop_words)| {
+            tokenizer_builder(
+                rule_stop_words.as_ref().or(stop_words),
+                allowed_separators,
+                dictionary,
+                Some(script_language),
+            )
+        })
+        .collect();
+    let localized_tokenizers: Vec<_> =
+        localized_builders.iter_mut().map(|builder| builder.build()).collect();
+
     // iterate over documents.
     let mut cursor = obkv_documents.into_cursor()?;
     while let Some((key, value)) = cursor.move_on_next()? {
@@ -90,6 +116,9 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
                     stop_words,
                     allowed_separators,
                     dictionary,
+                    localized_fields,
+                    &localized_tokenizers,
+                    split_identifiers,
                     max_positions_per_attributes,
                     DelAdd::Deletion,
                     &mut del_buffers,
@@ -104,6 +133,9 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
                     stop_words,
                     allowed_separators,
                     dictionary,
+                    localized_fields,
+                    &localized_tokenizers,
+                    split_identifiers,
                     max_positions_per_attributes,
                     DelAdd::Addition,
                     &mut add_buffers,
@@ -214,6 +246,9 @@ fn lang_safe_tokens_from_document<'a>(
     stop_words: Option<&fst::Set<&[u8]>>,
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
+    localized_fields: &LocalizedFieldIds,
+    localized_tokenizers: &[Tokenizer],
+    split_identifiers: bool,
     max_positions_per_attributes: u32,
     del_add: DelAdd,
     buffers: &'a mut Buffers,
@@ -224,6 +259,9 @@ fn lang_safe_tokens_from_document<'a>(
         obkv,
         searchable_fields,
         tokenizer,
+        localized_fields,
+        localized_tokenizers,
+        split_identifiers,
         max_positions_per_attributes,
         del_add,
         buffers,
@@ -261,6 +299,9 @@ fn lang_safe_tokens_from_document<'a>(
                 obkv,
                 searchable_fields,
                 &tokenizer,
+                localized_fields,
+                localized_tokenizers,
+                split_identifiers,
                 max_positions_per_attributes,
                 del_add,
                 buffers,
@@ -274,10 +315,14 @@ fn lang_safe_tokens_from_document<'a>(
 }
 
 /// Extract words mapped with their positions of a document.
+#[allow(clippy::too_many_arguments)]
 fn tokens_from_document<'a>(
     obkv: &KvReader<FieldId>,
     searchable_fields: &Option<HashSet<FieldId>>,
     tokenizer: &Tokenizer,
+    localized_fields: &LocalizedFieldIds,
+    localized_tokenizers: &[Tokenizer],
+    split_identifiers: bool,
     max_positions_per_attributes: u32,
     del_add: DelAdd,
     buffers: &'a mut Buffers,
@@ -301,8 +346,21 @@ fn tokens_from_document<'a>(
                 // convert json into a unique string.
                 buffers.field_buffer.clear();
                 if let Some(field) = json_to_string(&value, &mut buffers.field_buffer) {
+                    // the languages of a localized field are only detected among the ones of
+                    // its rule, and the stop words of its rule are ignored.
+                    let tokenizer = match localized_fields.rule(field_id) {
+                        Some(rule) => &localized_tokenizers[rule],
+                        None => tokenizer,
+                    };
+
                     // create an iterator of token with their positions.
-                    let tokens = process_tokens(tokenizer.tokenize(field))
+                    let tokens = tokenizer.tokenize(field);
+                    let tokens = identifiers::split_identifiers_keeping_words(
+                        field,
+                        tokens,
+                        split_identifiers,
+                    );
+                    let tokens = process_tokens(tokens)
                         .take_while(|(p, _)| (*p as u32) < max_positions_per_attributes);
 
                     for (index, token) in tokens {
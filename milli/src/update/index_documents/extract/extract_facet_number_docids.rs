@@ -6,9 +6,8 @@ use heed::{BytesDecode, BytesEncode};
 use super::helpers::{
     create_sorter, merge_deladd_cbo_roaring_bitmaps, sorter_into_reader, GrenadParameters,
 };
-use crate::heed_codec::facet::{
-    FacetGroupKey, FacetGroupKeyCodec, FieldDocIdFacetF64Codec, OrderedF64Codec,
-};
+use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FieldDocIdFacetCodec};
+use crate::heed_codec::BytesRefCodec;
 use crate::update::del_add::{KvReaderDelAdd, KvWriterDelAdd};
 use crate::Result;
 
@@ -37,11 +36,12 @@ pub fn extract_facet_number_docids<R: io::Read + io::Seek>(
     let mut buffer = Vec::new();
     let mut cursor = fid_docid_facet_number.into_cursor()?;
     while let Some((key_bytes, deladd_obkv_bytes)) = cursor.move_on_next()? {
+        // the encoded number is kept as is to not lose the exact value of the big integers
         let (field_id, document_id, number) =
-            FieldDocIdFacetF64Codec::bytes_decode(key_bytes).unwrap();
+            FieldDocIdFacetCodec::<BytesRefCodec>::bytes_decode(key_bytes).unwrap();
 
         let key = FacetGroupKey { field_id, level: 0, left_bound: number };
-        let key_bytes = FacetGroupKeyCodec::<OrderedF64Codec>::bytes_encode(&key).unwrap();
+        let key_bytes = FacetGroupKeyCodec::<BytesRefCodec>::bytes_encode(&key).unwrap();
 
         buffer.clear();
         let mut obkv = KvWriterDelAdd::new(&mut buffer);
@@ -8,16 +8,15 @@ use std::result::Result as StdResult;
 
 use bytemuck::bytes_of;
 use grenad::Sorter;
-use heed::BytesEncode;
+use heed::{BytesDecode, BytesEncode};
 use itertools::EitherOrBoth;
-use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 use serde_json::{from_slice, Value};
 use FilterableValues::{Empty, Null, Values};
 
 use super::helpers::{create_sorter, keep_first, sorter_into_reader, GrenadParameters};
 use crate::error::InternalError;
-use crate::facet::value_encoding::f64_into_bytes;
+use crate::heed_codec::facet::{OrderedF64Codec, OrderedI128Codec};
 use crate::update::del_add::{DelAdd, KvWriterDelAdd};
 use crate::update::index_documents::{create_writer, writer_into_reader};
 use crate::{CboRoaringBitmapCodec, DocumentId, Error, FieldId, Result, MAX_FACET_VALUE_LENGTH};
@@ -39,11 +38,13 @@ pub struct ExtractedFacetValues {
 /// Returns the generated grenad reader containing the docid the fid and the orginal value as key
 /// and the normalized value as value extracted from the given chunk of documents.
 /// We need the fid of the geofields to correctly parse them as numbers if they were sent as strings initially.
+/// The RFC 3339 dates of the date fields are extracted as timestamps, in place of their strings.
 #[logging_timer::time]
 pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
     faceted_fields: &HashSet<FieldId>,
+    date_fields: &HashSet<FieldId>,
     geo_fields_ids: Option<(FieldId, FieldId)>,
 ) -> Result<ExtractedFacetValues> {
     puffin::profile_function!();
@@ -125,10 +126,11 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
 
                 let geo_support =
                     geo_fields_ids.map_or(false, |(lat, lng)| field_id == lat || field_id == lng);
+                let date_field = date_fields.contains(&field_id);
                 let del_filterable_values =
-                    del_value.map(|value| extract_facet_values(&value, geo_support));
+                    del_value.map(|value| extract_facet_values(&value, geo_support, date_field));
                 let add_filterable_values =
-                    add_value.map(|value| extract_facet_values(&value, geo_support));
+                    add_value.map(|value| extract_facet_values(&value, geo_support, date_field));
 
                 // Those closures are just here to simplify things a bit.
                 let mut insert_numbers_diff = |del_numbers, add_numbers| {
@@ -290,54 +292,55 @@ fn truncate_string(s: String) -> String {
 fn insert_numbers_diff<MF>(
     fid_docid_facet_numbers_sorter: &mut Sorter<MF>,
     key_buffer: &mut Vec<u8>,
-    mut del_numbers: Vec<f64>,
-    mut add_numbers: Vec<f64>,
+    mut del_numbers: Vec<Vec<u8>>,
+    mut add_numbers: Vec<Vec<u8>>,
 ) -> Result<()>
 where
     MF: for<'a> Fn(&[u8], &[Cow<'a, [u8]>]) -> StdResult<Cow<'a, [u8]>, Error>,
 {
-    // We sort and dedup the float numbers
-    del_numbers.sort_unstable_by_key(|f| OrderedFloat(*f));
-    add_numbers.sort_unstable_by_key(|f| OrderedFloat(*f));
-    del_numbers.dedup_by_key(|f| OrderedFloat(*f));
-    add_numbers.dedup_by_key(|f| OrderedFloat(*f));
-
-    let merged_numbers_iter = itertools::merge_join_by(
-        del_numbers.into_iter().map(OrderedFloat),
-        add_numbers.into_iter().map(OrderedFloat),
-        |del, add| del.cmp(add),
-    );
+    // The integers that an f64 can't represent exactly used to be stored as their closest
+    // float, we also delete this legacy encoding to not leave it behind in the databases.
+    let legacy_numbers: Vec<_> = del_numbers
+        .iter()
+        .filter(|bytes| bytes.len() > 16)
+        .filter_map(|bytes| OrderedI128Codec::bytes_decode(bytes).ok())
+        .filter_map(|integer| encode_float(integer as f64))
+        .collect();
+    del_numbers.extend(legacy_numbers);
+
+    // We sort and dedup the encoded numbers, the encoding preserves their order
+    del_numbers.sort_unstable();
+    add_numbers.sort_unstable();
+    del_numbers.dedup();
+    add_numbers.dedup();
+
+    let merged_numbers_iter =
+        itertools::merge_join_by(del_numbers, add_numbers, |del, add| del.cmp(add));
 
     // insert facet numbers in sorter
     for eob in merged_numbers_iter {
         key_buffer.truncate(TRUNCATE_SIZE);
         match eob {
             EitherOrBoth::Both(_, _) => (), // no need to touch anything
-            EitherOrBoth::Left(OrderedFloat(number)) => {
-                if let Some(value_bytes) = f64_into_bytes(number) {
-                    key_buffer.extend_from_slice(&value_bytes);
-                    key_buffer.extend_from_slice(&number.to_be_bytes());
-
-                    // We insert only the Del part of the Obkv to inform
-                    // that we only want to remove all those numbers.
-                    let mut obkv = KvWriterDelAdd::memory();
-                    obkv.insert(DelAdd::Deletion, bytes_of(&()))?;
-                    let bytes = obkv.into_inner()?;
-                    fid_docid_facet_numbers_sorter.insert(&key_buffer, bytes)?;
-                }
+            EitherOrBoth::Left(value_bytes) => {
+                key_buffer.extend_from_slice(&value_bytes);
+
+                // We insert only the Del part of the Obkv to inform
+                // that we only want to remove all those numbers.
+                let mut obkv = KvWriterDelAdd::memory();
+                obkv.insert(DelAdd::Deletion, bytes_of(&()))?;
+                let bytes = obkv.into_inner()?;
+                fid_docid_facet_numbers_sorter.insert(&key_buffer, bytes)?;
             }
-            EitherOrBoth::Right(OrderedFloat(number)) => {
-                if let Some(value_bytes) = f64_into_bytes(number) {
-                    key_buffer.extend_from_slice(&value_bytes);
-                    key_buffer.extend_from_slice(&number.to_be_bytes());
-
-                    // We insert only the Add part of the Obkv to inform
-                    // that we only want to remove all those numbers.
-                    let mut obkv = KvWriterDelAdd::memory();
-                    obkv.insert(DelAdd::Addition, bytes_of(&()))?;
-                    let bytes = obkv.into_inner()?;
-                    fid_docid_facet_numbers_sorter.insert(&key_buffer, bytes)?;
-                }
+            EitherOrBoth::Right(value_bytes) => {
+                key_buffer.extend_from_slice(&value_bytes);
+
+                // We insert only the Add part of the Obkv to inform
+                // that we only want to remove all those numbers.
+                let mut obkv = KvWriterDelAdd::memory();
+                obkv.insert(DelAdd::Addition, bytes_of(&()))?;
+                let bytes = obkv.into_inner()?;
+                fid_docid_facet_numbers_sorter.insert(&key_buffer, bytes)?;
             }
         }
     }
@@ -404,38 +407,58 @@ enum FilterableValues {
     /// Corresponds to either, an empty string `""`, an empty array `[]`, or an empty object `{}`.
     Empty,
     /// Represents all the numbers and strings values found in this document field.
-    Values { numbers: Vec<f64>, strings: Vec<(String, String)> },
+    /// The numbers are encoded like in the facet numbers database, keeping the big integers exact.
+    Values { numbers: Vec<Vec<u8>>, strings: Vec<(String, String)> },
+}
+
+/// Encodes a float like in the facet numbers database, `None` if it isn't finite.
+fn encode_float(float: f64) -> Option<Vec<u8>> {
+    OrderedF64Codec::bytes_encode(&float).map(|bytes| bytes.into_owned()).ok()
 }
 
 /// Extracts the facet values of a JSON field.
-fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
+fn extract_facet_values(value: &Value, geo_field: bool, date_field: bool) -> FilterableValues {
     fn inner_extract_facet_values(
         value: &Value,
         can_recurse: bool,
-        output_numbers: &mut Vec<f64>,
+        output_numbers: &mut Vec<Vec<u8>>,
         output_strings: &mut Vec<(String, String)>,
         geo_field: bool,
+        date_field: bool,
     ) {
         match value {
             Value::Null => (),
             Value::Bool(b) => output_strings.push((b.to_string(), b.to_string())),
             Value::Number(number) => {
-                if let Some(float) = number.as_f64() {
-                    output_numbers.push(float);
-                }
+                let integer = number.as_i64().map(i128::from).or(number.as_u64().map(i128::from));
+                let bytes = match (integer, number.as_f64()) {
+                    (Some(integer), _) => OrderedI128Codec::bytes_encode(&integer)
+                        .map(|bytes| bytes.into_owned())
+                        .ok(),
+                    (None, Some(float)) => encode_float(float),
+                    (None, None) => None,
+                };
+                output_numbers.extend(bytes);
             }
             Value::String(original) => {
                 // if we're working on a geofield it MUST be something we can parse or else there was an internal error
                 // in the enrich pipeline. But since the enrich pipeline worked, we want to avoid crashing at all costs.
                 if geo_field {
-                    if let Ok(float) = original.parse() {
-                        output_numbers.push(float);
+                    if let Some(bytes) = original.parse().ok().and_then(encode_float) {
+                        output_numbers.push(bytes);
                     } else {
                         log::warn!(
                             "Internal error, could not parse a geofield that has been validated. Please open an issue."
                         )
                     }
                 }
+                // the dates are only stored as timestamps to be sorted chronologically
+                if date_field {
+                    if let Some(bytes) = crate::date_to_timestamp(original).and_then(encode_float) {
+                        output_numbers.push(bytes);
+                        return;
+                    }
+                }
                 let normalized = crate::normalize_facet(original);
                 output_strings.push((normalized, original.clone()));
             }
@@ -448,6 +471,7 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                             output_numbers,
                             output_strings,
                             geo_field,
+                            date_field,
                         );
                     }
                 }
@@ -464,7 +488,14 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         otherwise => {
             let mut numbers = Vec::new();
             let mut strings = Vec::new();
-            inner_extract_facet_values(otherwise, true, &mut numbers, &mut strings, geo_field);
+            inner_extract_facet_values(
+                otherwise,
+                true,
+                &mut numbers,
+                &mut strings,
+                geo_field,
+                date_field,
+            );
             FilterableValues::Values { numbers, strings }
         }
     }
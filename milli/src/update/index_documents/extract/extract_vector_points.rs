@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::convert::{TryFrom, TryInto};
 use std::fs::File;
+use std::future::Future;
 use std::io::{self, BufReader, BufWriter};
 use std::mem::size_of;
 use std::str::from_utf8;
@@ -10,19 +11,31 @@ use bytemuck::cast_slice;
 use grenad::Writer;
 use itertools::EitherOrBoth;
 use ordered_float::OrderedFloat;
+use roaring::RoaringBitmap;
 use serde_json::{from_slice, Value};
 
 use super::helpers::{create_writer, writer_into_reader, GrenadParameters};
-use crate::error::UserError;
+use crate::error::{FaultSource, UserError};
 use crate::prompt::Prompt;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::helpers::try_split_at;
-use crate::vector::Embedder;
+use crate::vector::cache::EmbeddingCache;
+use crate::vector::error::EmbedError;
+use crate::vector::policy::RequestLimiter;
+use crate::vector::{Embedder, Embeddings};
 use crate::{DocumentId, FieldsIdsMap, InternalError, Result, VectorOrArrayOfVectors};
 
 /// The length of the elements that are always in the buffer when inserting new values.
 const TRUNCATE_SIZE: usize = size_of::<DocumentId>();
 
+pub struct ExtractedEmbeddings {
+    // docid -> embeddings
+    pub embeddings: grenad::Reader<BufReader<File>>,
+    // the documents whose embedding failed within the tolerance of the request policy
+    pub failed_docids: RoaringBitmap,
+    pub last_error: Option<EmbedError>,
+}
+
 pub struct ExtractedVectorPoints {
     // docid, _index -> KvWriterDelAdd -> Vector
     pub manual_vectors: grenad::Reader<BufReader<File>>,
@@ -30,6 +43,8 @@ pub struct ExtractedVectorPoints {
     pub remove_vectors: grenad::Reader<BufReader<File>>,
     // docid -> prompt
     pub prompts: grenad::Reader<BufReader<File>>,
+    // docid -> prompt hash
+    pub prompt_hashes: grenad::Reader<BufReader<File>>,
 }
 
 enum VectorStateDelta {
@@ -47,21 +62,48 @@ enum VectorStateDelta {
     // Add the vector computed from the specified prompt
     // Remove any previous vector
     // Note: changing the value of the prompt **does require** recording this delta
-    NowGenerated(String),
+    NowGenerated(String, u64),
+
+    // Add back the vectors previously generated from a prompt with the same hash
+    // Remove any previous vector
+    NowCached(Vec<Vec<f32>>, u64),
 }
 
 impl VectorStateDelta {
-    fn into_values(self) -> (bool, String, (Vec<Vec<f32>>, Vec<Vec<f32>>)) {
+    fn into_values(self) -> (bool, String, Option<u64>, (Vec<Vec<f32>>, Vec<Vec<f32>>)) {
         match self {
             VectorStateDelta::NoChange => Default::default(),
-            VectorStateDelta::NowRemoved => (true, Default::default(), Default::default()),
+            VectorStateDelta::NowRemoved => (true, Default::default(), None, Default::default()),
             VectorStateDelta::WasGeneratedNowManual(add) => {
-                (true, Default::default(), (Default::default(), add))
+                (true, Default::default(), None, (Default::default(), add))
+            }
+            VectorStateDelta::ManualDelta(del, add) => {
+                (false, Default::default(), None, (del, add))
+            }
+            VectorStateDelta::NowGenerated(prompt, hash) => {
+                (true, prompt, Some(hash), Default::default())
+            }
+            VectorStateDelta::NowCached(add, hash) => {
+                (true, Default::default(), Some(hash), (Default::default(), add))
             }
-            VectorStateDelta::ManualDelta(del, add) => (false, Default::default(), (del, add)),
-            VectorStateDelta::NowGenerated(prompt) => (true, prompt, Default::default()),
         }
     }
+
+    /// Reuses the embeddings committed for the document when its prompt didn't change.
+    fn generated(
+        prompt: String,
+        docid: DocumentId,
+        embedding_cache: &EmbeddingCache,
+    ) -> Result<Self> {
+        let hash = embedding_cache.hash(&prompt);
+        Ok(match embedding_cache.get(docid, hash)? {
+            Some(embeddings) => {
+                log::trace!("♻️ Prompt already embedded, reusing the embeddings");
+                VectorStateDelta::NowCached(embeddings, hash)
+            }
+            None => VectorStateDelta::NowGenerated(prompt, hash),
+        })
+    }
 }
 
 /// Extracts the embedding vector contained in each document under the `_vectors` field.
@@ -74,6 +116,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
     field_id_map: &FieldsIdsMap,
     prompt: &Prompt,
     embedder_name: &str,
+    embedding_cache: &EmbeddingCache,
 ) -> Result<ExtractedVectorPoints> {
     puffin::profile_function!();
 
@@ -91,6 +134,13 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         tempfile::tempfile()?,
     );
 
+    // (docid) -> (prompt hash)
+    let mut prompt_hashes_writer = create_writer(
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        tempfile::tempfile()?,
+    );
+
     // (docid) -> ()
     let mut remove_vectors_writer = create_writer(
         indexer.chunk_compression_type,
@@ -107,6 +157,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         let (docid_bytes, external_id_bytes) =
             try_split_at(key, std::mem::size_of::<DocumentId>()).unwrap();
         debug_assert!(from_utf8(external_id_bytes).is_ok());
+        let docid = docid_bytes.try_into().map(DocumentId::from_be_bytes).unwrap();
 
         let obkv = obkv::KvReader::new(value);
         key_buffer.clear();
@@ -152,11 +203,11 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                     .any(|deladd| deladd.get(DelAdd::Addition).is_some());
                 if document_is_kept {
                     // becomes autogenerated
-                    VectorStateDelta::NowGenerated(prompt.render(
-                        obkv,
-                        DelAdd::Addition,
-                        field_id_map,
-                    )?)
+                    VectorStateDelta::generated(
+                        prompt.render(obkv, DelAdd::Addition, field_id_map)?,
+                        docid,
+                        embedding_cache,
+                    )?
                 } else {
                     VectorStateDelta::NowRemoved
                 }
@@ -189,7 +240,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
                         log::trace!(
                             "🚀 Changing prompt from\n{old_prompt}\n===to===\n{new_prompt}"
                         );
-                        VectorStateDelta::NowGenerated(new_prompt)
+                        VectorStateDelta::generated(new_prompt, docid, embedding_cache)?
                     } else {
                         log::trace!("⏭️ Prompt unmodified, skipping");
                         VectorStateDelta::NoChange
@@ -204,6 +255,7 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         push_vectors_diff(
             &mut remove_vectors_writer,
             &mut prompts_writer,
+            &mut prompt_hashes_writer,
             &mut manual_vectors_writer,
             &mut key_buffer,
             delta,
@@ -217,6 +269,8 @@ pub fn extract_vector_points<R: io::Read + io::Seek>(
         remove_vectors: writer_into_reader(remove_vectors_writer)?,
         // docid -> prompt
         prompts: writer_into_reader(prompts_writer)?,
+        // docid -> prompt hash
+        prompt_hashes: writer_into_reader(prompt_hashes_writer)?,
     })
 }
 
@@ -253,11 +307,13 @@ fn to_vector_map(
 fn push_vectors_diff(
     remove_vectors_writer: &mut Writer<BufWriter<File>>,
     prompts_writer: &mut Writer<BufWriter<File>>,
+    prompt_hashes_writer: &mut Writer<BufWriter<File>>,
     manual_vectors_writer: &mut Writer<BufWriter<File>>,
     key_buffer: &mut Vec<u8>,
     delta: VectorStateDelta,
 ) -> Result<()> {
-    let (must_remove, prompt, (mut del_vectors, mut add_vectors)) = delta.into_values();
+    let (must_remove, prompt, prompt_hash, (mut del_vectors, mut add_vectors)) =
+        delta.into_values();
     if must_remove {
         key_buffer.truncate(TRUNCATE_SIZE);
         remove_vectors_writer.insert(&key_buffer, [])?;
@@ -266,6 +322,10 @@ fn push_vectors_diff(
         key_buffer.truncate(TRUNCATE_SIZE);
         prompts_writer.insert(&key_buffer, prompt.as_bytes())?;
     }
+    if let Some(prompt_hash) = prompt_hash {
+        key_buffer.truncate(TRUNCATE_SIZE);
+        prompt_hashes_writer.insert(&key_buffer, prompt_hash.to_be_bytes())?;
+    }
 
     // We sort and dedup the vectors
     del_vectors.sort_unstable_by(|a, b| compare_vectors(a, b));
@@ -338,10 +398,12 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
     prompt_reader: grenad::Reader<R>,
     indexer: GrenadParameters,
     embedder: Arc<Embedder>,
-) -> Result<grenad::Reader<BufReader<File>>> {
+    limiter: &RequestLimiter,
+) -> Result<ExtractedEmbeddings> {
     let rt = tokio::runtime::Builder::new_current_thread().enable_io().enable_time().build()?;
 
-    let n_chunks = embedder.chunk_count_hint(); // chunk level parellelism
+    // chunk level parellelism, bounded by the request policy
+    let n_chunks = limiter.policy().concurrency(embedder.chunk_count_hint());
     let n_vectors_per_chunk = embedder.prompt_count_in_chunk_hint(); // number of vectors in a single chunk
 
     // docid, state with embedding
@@ -350,6 +412,8 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
         indexer.chunk_compression_level,
         tempfile::tempfile()?,
     );
+    let mut failed_docids = RoaringBitmap::new();
+    let mut last_error = None;
 
     let mut chunks = Vec::with_capacity(n_chunks);
     let mut current_chunk = Vec::with_capacity(n_vectors_per_chunk);
@@ -375,50 +439,142 @@ pub fn extract_embeddings<R: io::Read + io::Seek>(
         current_chunk_ids.push(docid);
 
         if chunks.len() == chunks.capacity() {
-            let chunked_embeds = rt
-                .block_on(
-                    embedder
-                        .embed_chunks(std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks))),
-                )
-                .map_err(crate::vector::Error::from)
-                .map_err(crate::Error::from)?;
-
-            for (docid, embeddings) in chunks_ids
-                .iter()
-                .flat_map(|docids| docids.iter())
-                .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-            {
-                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
-            }
+            let chunks = std::mem::replace(&mut chunks, Vec::with_capacity(n_chunks));
+            let requests = chunks.len();
+            let result = request_embeddings(&rt, limiter, requests, || {
+                embedder.embed_chunks(chunks.clone())
+            });
+            write_embeddings(
+                &rt,
+                limiter,
+                &embedder,
+                &mut state_writer,
+                chunks_ids.iter().flatten().zip(chunks.iter().flatten()),
+                result.map(|chunked_embeds| chunked_embeds.into_iter().flatten()),
+                &mut failed_docids,
+                &mut last_error,
+            )?;
             chunks_ids.clear();
         }
     }
 
     // send last chunk
     if !chunks.is_empty() {
-        let chunked_embeds = rt
-            .block_on(embedder.embed_chunks(std::mem::take(&mut chunks)))
-            .map_err(crate::vector::Error::from)
-            .map_err(crate::Error::from)?;
-        for (docid, embeddings) in chunks_ids
-            .iter()
-            .flat_map(|docids| docids.iter())
-            .zip(chunked_embeds.iter().flat_map(|embeds| embeds.iter()))
-        {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
-        }
+        let chunks = std::mem::take(&mut chunks);
+        let requests = chunks.len();
+        let result =
+            request_embeddings(&rt, limiter, requests, || embedder.embed_chunks(chunks.clone()));
+        write_embeddings(
+            &rt,
+            limiter,
+            &embedder,
+            &mut state_writer,
+            chunks_ids.iter().flatten().zip(chunks.iter().flatten()),
+            result.map(|chunked_embeds| chunked_embeds.into_iter().flatten()),
+            &mut failed_docids,
+            &mut last_error,
+        )?;
     }
 
     if !current_chunk.is_empty() {
-        let embeds = rt
-            .block_on(embedder.embed(std::mem::take(&mut current_chunk)))
-            .map_err(crate::vector::Error::from)
-            .map_err(crate::Error::from)?;
+        let current_chunk = std::mem::take(&mut current_chunk);
+        let result = request_embeddings(&rt, limiter, 1, || embedder.embed(current_chunk.clone()));
+        write_embeddings(
+            &rt,
+            limiter,
+            &embedder,
+            &mut state_writer,
+            current_chunk_ids.iter().zip(current_chunk.iter()),
+            result,
+            &mut failed_docids,
+            &mut last_error,
+        )?;
+    }
+
+    Ok(ExtractedEmbeddings {
+        embeddings: writer_into_reader(state_writer)?,
+        failed_docids,
+        last_error,
+    })
+}
 
-        for (docid, embeddings) in current_chunk_ids.iter().zip(embeds.iter()) {
-            state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+/// Sends the requests to the embedder within the limits of its request policy,
+/// retrying them with an exponential backoff as long as they fail at runtime.
+fn request_embeddings<T, F>(
+    rt: &tokio::runtime::Runtime,
+    limiter: &RequestLimiter,
+    requests: usize,
+    mut request: impl FnMut() -> F,
+) -> std::result::Result<T, EmbedError>
+where
+    F: Future<Output = std::result::Result<T, EmbedError>>,
+{
+    let mut attempt = 0;
+    loop {
+        let permit = limiter.acquire(requests);
+        let result = rt.block_on(request());
+        drop(permit);
+
+        match result {
+            Err(error)
+                if error.fault == FaultSource::Runtime
+                    && attempt < limiter.policy().max_retries =>
+            {
+                let backoff = limiter.policy().backoff(attempt);
+                log::warn!("Retrying a request to the embedder in {backoff:?} after: {error}");
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            result => return result,
         }
     }
+}
 
-    writer_into_reader(state_writer)
+/// Writes the embeddings of the documents.
+///
+/// When the request failed and the request policy tolerates failures, the documents are sent
+/// again one by one, so that only the documents the embedder can't embed are recorded as failed.
+#[allow(clippy::too_many_arguments)]
+fn write_embeddings<'a>(
+    rt: &tokio::runtime::Runtime,
+    limiter: &RequestLimiter,
+    embedder: &Embedder,
+    state_writer: &mut Writer<BufWriter<File>>,
+    documents: impl Iterator<Item = (&'a DocumentId, &'a String)>,
+    result: std::result::Result<impl IntoIterator<Item = Embeddings<f32>>, EmbedError>,
+    failed_docids: &mut RoaringBitmap,
+    last_error: &mut Option<EmbedError>,
+) -> Result<()> {
+    let policy = limiter.policy();
+    match result {
+        Ok(embeds) => {
+            for ((docid, _), embeddings) in documents.zip(embeds) {
+                state_writer.insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+            }
+        }
+        Err(error) if policy.tolerates_failures() => {
+            log::warn!("Sending the documents to the embedder one by one after: {error}");
+            for (docid, prompt) in documents {
+                // past the tolerated failures the indexing fails anyway
+                if failed_docids.len() > policy.max_failed_documents {
+                    failed_docids.insert(*docid);
+                    continue;
+                }
+                match request_embeddings(rt, limiter, 1, || embedder.embed(vec![prompt.clone()])) {
+                    Ok(embeds) => {
+                        if let Some(embeddings) = embeds.into_iter().next() {
+                            state_writer
+                                .insert(docid.to_be_bytes(), cast_slice(embeddings.as_inner()))?;
+                        }
+                    }
+                    Err(error) => {
+                        failed_docids.insert(*docid);
+                        *last_error = Some(error);
+                    }
+                }
+            }
+        }
+        Err(error) => return Err(crate::vector::Error::from(error).into()),
+    }
+    Ok(())
 }
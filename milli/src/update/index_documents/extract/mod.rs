@@ -9,13 +9,15 @@ mod extract_word_docids;
 mod extract_word_pair_proximity_docids;
 mod extract_word_position_docids;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
+use std::sync::Arc;
 
 use crossbeam_channel::Sender;
 use log::debug;
 use rayon::prelude::*;
+use roaring::RoaringBitmap;
 
 use self::extract_docid_word_positions::extract_docid_word_positions;
 use self::extract_facet_number_docids::extract_facet_number_docids;
@@ -24,7 +26,7 @@ use self::extract_fid_docid_facet_values::{extract_fid_docid_facet_values, Extra
 use self::extract_fid_word_count_docids::extract_fid_word_count_docids;
 use self::extract_geo_points::extract_geo_points;
 use self::extract_vector_points::{
-    extract_embeddings, extract_vector_points, ExtractedVectorPoints,
+    extract_embeddings, extract_vector_points, ExtractedEmbeddings, ExtractedVectorPoints,
 };
 use self::extract_word_docids::extract_word_docids;
 use self::extract_word_pair_proximity_docids::extract_word_pair_proximity_docids;
@@ -33,13 +35,18 @@ use super::helpers::{
     as_cloneable_grenad, merge_deladd_cbo_roaring_bitmaps, CursorClonableMmap, GrenadParameters,
     MergeFn, MergeableReader,
 };
-use super::{helpers, TypedChunk};
+use super::{helpers, ReindexScope, TypedChunk};
+use crate::localized_attributes_rules::LocalizedFieldIds;
 use crate::proximity::ProximityPrecision;
+use crate::vector::cache::EmbeddingCache;
+use crate::vector::policy::RequestLimiter;
 use crate::vector::EmbeddingConfigs;
-use crate::{FieldId, FieldsIdsMap, Result};
+use crate::{FieldId, FieldsIdsMap, Index, Result};
 
 /// Extract data for each databases from obkv documents in parallel.
 /// Send data in grenad file over provided Sender.
+///
+/// Nothing is extracted for the databases that are not part of the `reindex_scope`.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn data_from_obkv_documents(
     original_obkv_chunks: impl Iterator<Item = Result<grenad::Reader<BufReader<File>>>> + Send,
@@ -48,19 +55,36 @@ pub(crate) fn data_from_obkv_documents(
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     searchable_fields: Option<HashSet<FieldId>>,
     faceted_fields: HashSet<FieldId>,
+    date_fields: HashSet<FieldId>,
     primary_key_id: FieldId,
     geo_fields_ids: Option<(FieldId, FieldId)>,
     field_id_map: FieldsIdsMap,
     stop_words: Option<fst::Set<&[u8]>>,
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
+    localized_fields: &LocalizedFieldIds,
+    split_identifiers: bool,
     max_positions_per_attributes: Option<u32>,
     exact_attributes: HashSet<FieldId>,
     proximity_precision: ProximityPrecision,
     embedders: EmbeddingConfigs,
+    embedders_options_hashes: HashMap<String, u64>,
+    request_limiters: HashMap<String, Arc<RequestLimiter>>,
+    index: &Index,
+    reindex_scope: ReindexScope,
 ) -> Result<()> {
     puffin::profile_function!();
 
+    // The extractors produce empty chunks when they have no field to extract.
+    let searchable_fields = if reindex_scope.words || reindex_scope.proximities {
+        searchable_fields
+    } else {
+        Some(HashSet::new())
+    };
+    let faceted_fields = if reindex_scope.facets { faceted_fields } else { HashSet::new() };
+    let geo_fields_ids = geo_fields_ids.filter(|_| reindex_scope.facets);
+    let embedders = if reindex_scope.vectors { embedders } else { EmbeddingConfigs::default() };
+
     original_obkv_chunks
         .par_bridge()
         .map(|original_documents_chunk| {
@@ -70,6 +94,10 @@ pub(crate) fn data_from_obkv_documents(
                 lmdb_writer_sx.clone(),
                 field_id_map.clone(),
                 embedders.clone(),
+                embedders_options_hashes.clone(),
+                request_limiters.clone(),
+                index.clone(),
+                reindex_scope.documents,
             )
         })
         .collect::<Result<()>>()?;
@@ -85,12 +113,16 @@ pub(crate) fn data_from_obkv_documents(
                     lmdb_writer_sx.clone(),
                     &searchable_fields,
                     &faceted_fields,
+                    &date_fields,
                     primary_key_id,
                     geo_fields_ids,
                     &stop_words,
                     &allowed_separators,
                     &dictionary,
+                    localized_fields,
+                    split_identifiers,
                     max_positions_per_attributes,
+                    reindex_scope,
                 )
             })
             .collect();
@@ -169,49 +201,53 @@ pub(crate) fn data_from_obkv_documents(
         );
     }
 
-    spawn_extraction_task::<_, _, Vec<grenad::Reader<BufReader<File>>>>(
-        docid_word_positions_chunks.clone(),
-        indexer,
-        lmdb_writer_sx.clone(),
-        extract_fid_word_count_docids,
-        merge_deladd_cbo_roaring_bitmaps,
-        TypedChunk::FieldIdWordCountDocids,
-        "field-id-wordcount-docids",
-    );
+    if reindex_scope.words {
+        spawn_extraction_task::<_, _, Vec<grenad::Reader<BufReader<File>>>>(
+            docid_word_positions_chunks.clone(),
+            indexer,
+            lmdb_writer_sx.clone(),
+            extract_fid_word_count_docids,
+            merge_deladd_cbo_roaring_bitmaps,
+            TypedChunk::FieldIdWordCountDocids,
+            "field-id-wordcount-docids",
+        );
 
-    spawn_extraction_task::<
-        _,
-        _,
-        Vec<(
-            grenad::Reader<BufReader<File>>,
-            grenad::Reader<BufReader<File>>,
-            grenad::Reader<BufReader<File>>,
-        )>,
-    >(
-        docid_word_positions_chunks.clone(),
-        indexer,
-        lmdb_writer_sx.clone(),
-        move |doc_word_pos, indexer| extract_word_docids(doc_word_pos, indexer, &exact_attributes),
-        merge_deladd_cbo_roaring_bitmaps,
-        |(word_docids_reader, exact_word_docids_reader, word_fid_docids_reader)| {
-            TypedChunk::WordDocids {
-                word_docids_reader,
-                exact_word_docids_reader,
-                word_fid_docids_reader,
-            }
-        },
-        "word-docids",
-    );
+        spawn_extraction_task::<
+            _,
+            _,
+            Vec<(
+                grenad::Reader<BufReader<File>>,
+                grenad::Reader<BufReader<File>>,
+                grenad::Reader<BufReader<File>>,
+            )>,
+        >(
+            docid_word_positions_chunks.clone(),
+            indexer,
+            lmdb_writer_sx.clone(),
+            move |doc_word_pos, indexer| {
+                extract_word_docids(doc_word_pos, indexer, &exact_attributes)
+            },
+            merge_deladd_cbo_roaring_bitmaps,
+            |(word_docids_reader, exact_word_docids_reader, word_fid_docids_reader)| {
+                TypedChunk::WordDocids {
+                    word_docids_reader,
+                    exact_word_docids_reader,
+                    word_fid_docids_reader,
+                }
+            },
+            "word-docids",
+        );
 
-    spawn_extraction_task::<_, _, Vec<grenad::Reader<BufReader<File>>>>(
-        docid_word_positions_chunks.clone(),
-        indexer,
-        lmdb_writer_sx.clone(),
-        extract_word_position_docids,
-        merge_deladd_cbo_roaring_bitmaps,
-        TypedChunk::WordPositionDocids,
-        "word-position-docids",
-    );
+        spawn_extraction_task::<_, _, Vec<grenad::Reader<BufReader<File>>>>(
+            docid_word_positions_chunks.clone(),
+            indexer,
+            lmdb_writer_sx.clone(),
+            extract_word_position_docids,
+            merge_deladd_cbo_roaring_bitmaps,
+            TypedChunk::WordPositionDocids,
+            "word-position-docids",
+        );
+    }
 
     spawn_extraction_task::<_, _, Vec<grenad::Reader<BufReader<File>>>>(
         fid_docid_facet_strings_chunks,
@@ -276,13 +312,18 @@ fn spawn_extraction_task<FE, FS, M>(
 }
 
 /// Extract chunked data and send it into lmdb_writer_sx sender:
-/// - documents
+/// - documents, when `send_documents` is `true`
+#[allow(clippy::too_many_arguments)]
 fn send_original_documents_data(
     original_documents_chunk: Result<grenad::Reader<BufReader<File>>>,
     indexer: GrenadParameters,
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     field_id_map: FieldsIdsMap,
     embedders: EmbeddingConfigs,
+    embedders_options_hashes: HashMap<String, u64>,
+    request_limiters: HashMap<String, Arc<RequestLimiter>>,
+    index: Index,
+    send_documents: bool,
 ) -> Result<()> {
     let original_documents_chunk =
         original_documents_chunk.and_then(|c| unsafe { as_cloneable_grenad(&c) })?;
@@ -291,25 +332,40 @@ fn send_original_documents_data(
     let lmdb_writer_sx_cloned = lmdb_writer_sx.clone();
     rayon::spawn(move || {
         for (name, (embedder, prompt)) in embedders {
-            let result = extract_vector_points(
-                documents_chunk_cloned.clone(),
-                indexer,
-                &field_id_map,
-                &prompt,
-                &name,
-            );
+            let options_hash = embedders_options_hashes.get(&name).copied().unwrap_or_default();
+            let result = EmbeddingCache::new(&index, &name, options_hash).and_then(|cache| {
+                extract_vector_points(
+                    documents_chunk_cloned.clone(),
+                    indexer,
+                    &field_id_map,
+                    &prompt,
+                    &name,
+                    &cache,
+                )
+            });
             match result {
-                Ok(ExtractedVectorPoints { manual_vectors, remove_vectors, prompts }) => {
-                    let embeddings = match extract_embeddings(prompts, indexer, embedder.clone()) {
-                        Ok(results) => Some(results),
-                        Err(error) => {
-                            let _ = lmdb_writer_sx_cloned.send(Err(error));
-                            None
-                        }
-                    };
+                Ok(ExtractedVectorPoints {
+                    manual_vectors,
+                    remove_vectors,
+                    prompts,
+                    prompt_hashes,
+                }) => {
+                    let limiter = request_limiters.get(&name).cloned().unwrap_or_default();
+                    let (embeddings, failed_docids, embedding_error) =
+                        match extract_embeddings(prompts, indexer, embedder.clone(), &limiter) {
+                            Ok(ExtractedEmbeddings { embeddings, failed_docids, last_error }) => {
+                                (Some(embeddings), failed_docids, last_error)
+                            }
+                            Err(error) => {
+                                let _ = lmdb_writer_sx_cloned.send(Err(error));
+                                (None, RoaringBitmap::new(), None)
+                            }
+                        };
 
                     if !(remove_vectors.is_empty()
                         && manual_vectors.is_empty()
+                        && prompt_hashes.is_empty()
+                        && failed_docids.is_empty()
                         && embeddings.as_ref().map_or(true, |e| e.is_empty()))
                     {
                         let _ = lmdb_writer_sx_cloned.send(Ok(TypedChunk::VectorPoints {
@@ -317,6 +373,9 @@ fn send_original_documents_data(
                             embeddings,
                             expected_dimension: embedder.dimensions(),
                             manual_vectors,
+                            prompt_hashes,
+                            failed_docids,
+                            embedding_error,
                             embedder_name: name,
                         }));
                     }
@@ -329,8 +388,10 @@ fn send_original_documents_data(
         }
     });
 
-    // TODO: create a custom internal error
-    lmdb_writer_sx.send(Ok(TypedChunk::Documents(original_documents_chunk))).unwrap();
+    if send_documents {
+        // TODO: create a custom internal error
+        lmdb_writer_sx.send(Ok(TypedChunk::Documents(original_documents_chunk))).unwrap();
+    }
     Ok(())
 }
 
@@ -348,12 +409,16 @@ fn send_and_extract_flattened_documents_data(
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     searchable_fields: &Option<HashSet<FieldId>>,
     faceted_fields: &HashSet<FieldId>,
+    date_fields: &HashSet<FieldId>,
     primary_key_id: FieldId,
     geo_fields_ids: Option<(FieldId, FieldId)>,
     stop_words: &Option<fst::Set<&[u8]>>,
     allowed_separators: &Option<&[&str]>,
     dictionary: &Option<&[&str]>,
+    localized_fields: &LocalizedFieldIds,
+    split_identifiers: bool,
     max_positions_per_attributes: Option<u32>,
+    reindex_scope: ReindexScope,
 ) -> Result<(
     grenad::Reader<CursorClonableMmap>,
     (
@@ -394,6 +459,8 @@ fn send_and_extract_flattened_documents_data(
                         stop_words.as_ref(),
                         *allowed_separators,
                         *dictionary,
+                        localized_fields,
+                        split_identifiers,
                         max_positions_per_attributes,
                     )?;
 
@@ -401,8 +468,11 @@ fn send_and_extract_flattened_documents_data(
                 let docid_word_positions_chunk =
                     unsafe { as_cloneable_grenad(&docid_word_positions_chunk)? };
 
-                let _ =
-                    lmdb_writer_sx.send(Ok(TypedChunk::ScriptLanguageDocids(script_language_pair)));
+                // the proximities are reindexed alone, the other word databases are kept
+                if reindex_scope.words {
+                    let _ = lmdb_writer_sx
+                        .send(Ok(TypedChunk::ScriptLanguageDocids(script_language_pair)));
+                }
 
                 Ok(docid_word_positions_chunk)
             },
@@ -417,6 +487,7 @@ fn send_and_extract_flattened_documents_data(
                     flattened_documents_chunk.clone(),
                     indexer,
                     faceted_fields,
+                    date_fields,
                     geo_fields_ids,
                 )?;
 
@@ -32,6 +32,25 @@ pub fn create_sorter(
     max_nb_chunks: Option<usize>,
     max_memory: Option<usize>,
 ) -> grenad::Sorter<MergeFn> {
+    create_sorter_with_merge(
+        sort_algorithm,
+        merge,
+        chunk_compression_type,
+        chunk_compression_level,
+        max_nb_chunks,
+        max_memory,
+    )
+}
+
+/// Creates a sorter that uses any kind of merge function, e.g. a `BoxedMergeFn`.
+pub fn create_sorter_with_merge<MF>(
+    sort_algorithm: grenad::SortAlgorithm,
+    merge: MF,
+    chunk_compression_type: grenad::CompressionType,
+    chunk_compression_level: Option<u32>,
+    max_nb_chunks: Option<usize>,
+    max_memory: Option<usize>,
+) -> grenad::Sorter<MF> {
     let mut builder = grenad::Sorter::builder(merge);
     builder.chunk_compression_type(chunk_compression_type);
     if let Some(level) = chunk_compression_level {
@@ -1,17 +1,32 @@
 use std::borrow::Cow;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::io;
 use std::result::Result as StdResult;
 
 use roaring::RoaringBitmap;
+use serde_json::{Map, Value};
 
+use crate::error::InternalError;
 use crate::heed_codec::CboRoaringBitmapCodec;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::index_documents::transform::Operation;
-use crate::Result;
+use crate::update::MergeStrategy;
+use crate::{FieldId, Result};
 
 pub type MergeFn = for<'a> fn(&[u8], &[Cow<'a, [u8]>]) -> Result<Cow<'a, [u8]>>;
 
+/// A merge function that can capture its environment, like the merge strategies of the fields.
+pub type BoxedMergeFn =
+    Box<dyn for<'a> Fn(&[u8], &[Cow<'a, [u8]>]) -> Result<Cow<'a, [u8]>> + Send + Sync>;
+
+/// Boxes a merge function, the closures passed to this function get their signature inferred.
+pub fn boxed_merge_fn<F>(merge: F) -> BoxedMergeFn
+where
+    F: for<'a> Fn(&[u8], &[Cow<'a, [u8]>]) -> Result<Cow<'a, [u8]>> + Send + Sync + 'static,
+{
+    Box::new(merge)
+}
+
 pub fn serialize_roaring_bitmap(bitmap: &RoaringBitmap, buffer: &mut Vec<u8>) -> io::Result<()> {
     buffer.clear();
     buffer.reserve(bitmap.serialized_size());
@@ -65,23 +80,43 @@ pub fn keep_latest_obkv<'a>(_key: &[u8], obkvs: &[Cow<'a, [u8]>]) -> Result<Cow<
     Ok(obkvs.last().unwrap().clone())
 }
 
+/// Describes how the additions of two DelAdd obkvs must be combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeAdditions {
+    /// Only keep the newest additions, the fields that are not part of it are removed.
+    KeepNewest,
+    /// Keep the fields of both obkvs, the newest value replacing the oldest one
+    /// unless the field has a merge strategy.
+    Replace,
+    /// Keep the fields of both obkvs, deep merging the values following the
+    /// JSON Merge Patch semantics (RFC 7396), a `null` value removes the field.
+    MergePatch,
+}
+
 pub fn merge_two_del_add_obkvs(
     base: obkv::KvReaderU16,
     update: obkv::KvReaderU16,
-    merge_additions: bool,
+    merge_additions: MergeAdditions,
+    merge_strategies: &HashMap<FieldId, MergeStrategy>,
     buffer: &mut Vec<u8>,
-) {
+) -> Result<()> {
     use itertools::merge_join_by;
     use itertools::EitherOrBoth::{Both, Left, Right};
 
     buffer.clear();
 
+    // When merge patching, a base without any addition is the deletion of the stored document
+    // and the update is its complete new version. Otherwise the update is a patch of a document
+    // of the same batch and the fields missing from the base are patched onto empty values.
+    let patch_missing_fields = merge_additions == MergeAdditions::MergePatch
+        && base.iter().any(|(_, v)| KvReaderDelAdd::new(v).get(DelAdd::Addition).is_some());
+
     let mut writer = obkv::KvWriter::new(buffer);
     let mut value_buffer = Vec::new();
     for eob in merge_join_by(base.iter(), update.iter(), |(b, _), (u, _)| b.cmp(u)) {
         match eob {
             Left((k, v)) => {
-                if merge_additions {
+                if merge_additions != MergeAdditions::KeepNewest {
                     writer.insert(k, v).unwrap()
                 } else {
                     // If merge_additions is false, recreate an obkv keeping the deletions only.
@@ -96,6 +131,27 @@ pub fn merge_two_del_add_obkvs(
                     }
                 }
             }
+            Right((k, v)) if patch_missing_fields => {
+                let update_reader = KvReaderDelAdd::new(v);
+                let deletion = update_reader.get(DelAdd::Deletion);
+                let addition = match update_reader.get(DelAdd::Addition) {
+                    Some(patch) => patch_empty_value(patch)?,
+                    None => None,
+                };
+                // a null patch of a field that was never added removes it entirely.
+                if deletion.is_some() || addition.is_some() {
+                    value_buffer.clear();
+                    let mut value_writer = KvWriterDelAdd::new(&mut value_buffer);
+                    if let Some(deletion) = deletion {
+                        value_writer.insert(DelAdd::Deletion, deletion).unwrap();
+                    }
+                    if let Some(addition) = addition {
+                        value_writer.insert(DelAdd::Addition, addition).unwrap();
+                    }
+                    value_writer.finish().unwrap();
+                    writer.insert(k, &value_buffer).unwrap()
+                }
+            }
             Right((k, v)) => writer.insert(k, v).unwrap(),
             Both((k, base), (_, update)) => {
                 // merge deletions and additions.
@@ -112,13 +168,47 @@ pub fn merge_two_del_add_obkvs(
                     value_writer.insert(DelAdd::Deletion, deletion).unwrap();
                 }
 
-                // keep base addition only if merge_additions is true.
-                let base_addition =
-                    merge_additions.then(|| base_reader.get(DelAdd::Addition)).flatten();
-                // keep newest addition.
-                // TODO use or_else
-                if let Some(addition) = update_reader.get(DelAdd::Addition).or(base_addition) {
-                    value_writer.insert(DelAdd::Addition, addition).unwrap();
+                // keep base addition only if merge_additions is not `KeepNewest`.
+                let base_addition = (merge_additions != MergeAdditions::KeepNewest)
+                    .then(|| base_reader.get(DelAdd::Addition))
+                    .flatten();
+                match (merge_additions, base_addition, update_reader.get(DelAdd::Addition)) {
+                    (MergeAdditions::MergePatch, Some(base), Some(patch)) => {
+                        let patch: Value =
+                            serde_json::from_slice(patch).map_err(InternalError::SerdeJson)?;
+                        // a null patch removes the field entirely.
+                        if !patch.is_null() {
+                            let mut target: Value =
+                                serde_json::from_slice(base).map_err(InternalError::SerdeJson)?;
+                            json_merge_patch(&mut target, patch);
+                            let target =
+                                serde_json::to_vec(&target).map_err(InternalError::SerdeJson)?;
+                            value_writer.insert(DelAdd::Addition, target).unwrap();
+                        }
+                    }
+                    (MergeAdditions::MergePatch, None, Some(patch)) if patch_missing_fields => {
+                        if let Some(addition) = patch_empty_value(patch)? {
+                            value_writer.insert(DelAdd::Addition, addition).unwrap();
+                        }
+                    }
+                    (MergeAdditions::Replace, Some(base), Some(update))
+                        if merge_strategies.contains_key(&k) =>
+                    {
+                        let base: Value =
+                            serde_json::from_slice(base).map_err(InternalError::SerdeJson)?;
+                        let update: Value =
+                            serde_json::from_slice(update).map_err(InternalError::SerdeJson)?;
+                        let merged = merge_strategies[&k].merge(base, update);
+                        let merged =
+                            serde_json::to_vec(&merged).map_err(InternalError::SerdeJson)?;
+                        value_writer.insert(DelAdd::Addition, merged).unwrap();
+                    }
+                    // keep newest addition.
+                    (_, base_addition, update_addition) => {
+                        if let Some(addition) = update_addition.or(base_addition) {
+                            value_writer.insert(DelAdd::Addition, addition).unwrap();
+                        }
+                    }
                 }
 
                 value_writer.finish().unwrap();
@@ -128,12 +218,48 @@ pub fn merge_two_del_add_obkvs(
     }
 
     writer.finish().unwrap();
+    Ok(())
+}
+
+/// Applies the serialized `patch` onto an empty value, returns `None` when the field is removed.
+fn patch_empty_value(patch: &[u8]) -> Result<Option<Vec<u8>>> {
+    let patch: Value = serde_json::from_slice(patch).map_err(InternalError::SerdeJson)?;
+    let mut target = Value::Null;
+    json_merge_patch(&mut target, patch);
+    match target {
+        Value::Null => Ok(None),
+        target => Ok(Some(serde_json::to_vec(&target).map_err(InternalError::SerdeJson)?)),
+    }
+}
+
+/// Applies the `patch` onto the `target` following the JSON Merge Patch semantics (RFC 7396).
+///
+/// Objects are recursively merged, a `null` in the patch removes the corresponding
+/// entry from the target and any other value replaces the target one.
+pub fn json_merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let target = target.as_object_mut().unwrap();
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    json_merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        patch => *target = patch,
+    }
 }
 
 /// Merge all the obkvs from the newest to the oldest.
 fn inner_merge_del_add_obkvs<'a>(
     obkvs: &[Cow<'a, [u8]>],
-    merge_additions: bool,
+    merge_additions: MergeAdditions,
+    merge_strategies: &HashMap<FieldId, MergeStrategy>,
 ) -> Result<Cow<'a, [u8]>> {
     // pop the newest operation from the list.
     let (newest, obkvs) = obkvs.split_last().unwrap();
@@ -154,7 +280,7 @@ fn inner_merge_del_add_obkvs<'a>(
 
         let newest = obkv::KvReader::new(&acc);
         let oldest = obkv::KvReader::new(&current[1..]);
-        merge_two_del_add_obkvs(oldest, newest, merge_additions, &mut buffer);
+        merge_two_del_add_obkvs(oldest, newest, merge_additions, merge_strategies, &mut buffer)?;
 
         // we want the result of the merge into our accumulator.
         std::mem::swap(&mut acc, &mut buffer);
@@ -170,7 +296,26 @@ pub fn obkvs_merge_additions_and_deletions<'a>(
     _key: &[u8],
     obkvs: &[Cow<'a, [u8]>],
 ) -> Result<Cow<'a, [u8]>> {
-    inner_merge_del_add_obkvs(obkvs, true)
+    inner_merge_del_add_obkvs(obkvs, MergeAdditions::Replace, &HashMap::new())
+}
+
+/// Merge all the obkvs from the newest to the oldest, combining the additions of the
+/// fields that have a merge strategy instead of replacing them.
+pub fn obkvs_merge_additions_with_strategies<'a>(
+    _key: &[u8],
+    obkvs: &[Cow<'a, [u8]>],
+    merge_strategies: &HashMap<FieldId, MergeStrategy>,
+) -> Result<Cow<'a, [u8]>> {
+    inner_merge_del_add_obkvs(obkvs, MergeAdditions::Replace, merge_strategies)
+}
+
+/// Merge all the obkvs from the newest to the oldest, deep merging the additions
+/// following the JSON Merge Patch semantics.
+pub fn obkvs_merge_patch_additions_and_deletions<'a>(
+    _key: &[u8],
+    obkvs: &[Cow<'a, [u8]>],
+) -> Result<Cow<'a, [u8]>> {
+    inner_merge_del_add_obkvs(obkvs, MergeAdditions::MergePatch, &HashMap::new())
 }
 
 /// Merge all the obkvs deletions from the newest to the oldest and keep only the newest additions.
@@ -178,7 +323,7 @@ pub fn obkvs_keep_last_addition_merge_deletions<'a>(
     _key: &[u8],
     obkvs: &[Cow<'a, [u8]>],
 ) -> Result<Cow<'a, [u8]>> {
-    inner_merge_del_add_obkvs(obkvs, false)
+    inner_merge_del_add_obkvs(obkvs, MergeAdditions::KeepNewest, &HashMap::new())
 }
 
 /// Do a union of all the CboRoaringBitmaps in the values.
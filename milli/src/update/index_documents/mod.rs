@@ -4,11 +4,12 @@ mod helpers;
 mod transform;
 mod typed_chunk;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{Cursor, Read, Seek};
 use std::iter::FromIterator;
 use std::num::NonZeroU32;
 use std::result::Result as StdResult;
+use std::sync::Arc;
 
 use crossbeam_channel::{Receiver, Sender};
 use heed::types::Str;
@@ -20,7 +21,7 @@ use serde::{Deserialize, Serialize};
 use slice_group_by::GroupBy;
 use typed_chunk::{write_typed_chunk_into_index, TypedChunk};
 
-use self::enrich::enrich_documents_batch;
+use self::enrich::{enrich_documents_batch, enrich_valid_documents};
 pub use self::enrich::{extract_finite_float_from_value, validate_geo_from_json, DocumentId};
 pub use self::helpers::{
     as_cloneable_grenad, create_sorter, create_writer, fst_stream_into_hashset,
@@ -31,14 +32,22 @@ pub use self::helpers::{
 };
 use self::helpers::{grenad_obkv_into_chunks, GrenadParameters};
 pub use self::transform::{Transform, TransformOutput};
+use crate::change_feed::ChangeOperation;
 use crate::documents::{obkv_to_object, DocumentsBatchReader};
 use crate::error::{Error, InternalError, UserError};
+use crate::geojson::is_geojson_field;
+use crate::localized_attributes_rules::LocalizedFieldIds;
+use crate::suggestions::update_suggestions;
+use crate::update::derived_words_fst::new_words;
 pub use crate::update::index_documents::helpers::CursorClonableMmap;
 use crate::update::{
-    IndexerConfig, UpdateIndexingStep, WordPrefixDocids, WordPrefixIntegerDocids, WordsPrefixesFst,
+    IndexerConfig, UpdateIndexingStep, WordPrefixDocids, WordPrefixIntegerDocids,
+    WordRomanizationsFst, WordStemsFst, WordsPrefixesFst,
 };
-use crate::vector::EmbeddingConfigs;
-use crate::{CboRoaringBitmapCodec, Index, Result};
+use crate::vector::cache::options_hash;
+use crate::vector::policy::{EmbeddingFailures, RequestLimiter};
+use crate::vector::{Distance, EmbeddingConfigs};
+use crate::{CboRoaringBitmapCodec, Filter, Index, Result};
 
 static MERGED_DATABASE_COUNT: usize = 7;
 static PREFIX_DATABASE_COUNT: usize = 4;
@@ -50,6 +59,8 @@ pub struct DocumentAdditionResult {
     pub indexed_documents: u64,
     /// The total number of documents in the index after the update
     pub number_of_documents: u64,
+    /// The documents indexed without the embeddings of an embedder, by embedder name
+    pub embedding_failures: BTreeMap<String, EmbeddingFailures>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -62,6 +73,10 @@ pub enum IndexDocumentsMethod {
     /// Merge the previous version of the document with the new version,
     /// replacing old attributes values with the new ones and add the new attributes.
     UpdateDocuments,
+
+    /// Apply the new version of the document as a JSON Merge Patch (RFC 7396) onto the
+    /// previous one, deep merging the nested objects and removing the `null` attributes.
+    MergePatchDocuments,
 }
 
 impl Default for IndexDocumentsMethod {
@@ -70,6 +85,37 @@ impl Default for IndexDocumentsMethod {
     }
 }
 
+/// The parts of the index that are rebuilt from the stored documents by a reindexing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReindexScope {
+    /// The documents are remapped to the new fields ids map and written back,
+    /// every database of the index must then be rebuilt.
+    pub documents: bool,
+    /// The facet databases, including the geo ones.
+    pub facets: bool,
+    /// The word databases along with their prefix databases.
+    pub words: bool,
+    /// The word pair proximity database, rebuilt alone when only the proximity precision changes.
+    pub proximities: bool,
+    /// The vector databases.
+    pub vectors: bool,
+}
+
+impl ReindexScope {
+    pub const FULL: ReindexScope = ReindexScope {
+        documents: true,
+        facets: true,
+        words: true,
+        proximities: true,
+        vectors: true,
+    };
+
+    /// Returns `true` if nothing has to be reindexed.
+    pub fn is_empty(&self) -> bool {
+        !(self.documents || self.facets || self.words || self.proximities || self.vectors)
+    }
+}
+
 pub struct IndexDocuments<'t, 'i, 'a, FP, FA> {
     wtxn: &'t mut heed::RwTxn<'i>,
     index: &'i Index,
@@ -81,6 +127,7 @@ pub struct IndexDocuments<'t, 'i, 'a, FP, FA> {
     added_documents: u64,
     deleted_documents: u64,
     embedders: EmbeddingConfigs,
+    reindex_scope: ReindexScope,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -125,6 +172,7 @@ where
             added_documents: 0,
             deleted_documents: 0,
             embedders: Default::default(),
+            reindex_scope: ReindexScope::FULL,
         })
     }
 
@@ -133,6 +181,10 @@ where
     /// Since the documents are progressively added to the writer, a failure will cause only
     /// return an error and not the `IndexDocuments` struct as it is invalid to use it afterward.
     ///
+    /// The documents with a reserved `_version` field lower than the one of the previous document
+    /// with the same id, stored or added to the builder, are outdated: they are skipped and not
+    /// counted as added, see [`Self::take_outdated_documents`].
+    ///
     /// Returns the number of documents added to the builder.
     pub fn add_documents<R: Read + Seek>(
         mut self,
@@ -164,6 +216,7 @@ where
                 self.wtxn,
                 &self.progress,
                 &self.should_abort,
+                None,
             )? as u64;
 
         self.added_documents += indexed_documents;
@@ -171,11 +224,94 @@ where
         Ok((self, Ok(indexed_documents)))
     }
 
+    /// Adds the valid documents of the batch to the current builder, the documents that can't
+    /// be indexed, e.g. because of an invalid id or `_geo` field or because they would exceed
+    /// the maximum number of fields, being skipped.
+    ///
+    /// Returns the number of added documents and the errors of the skipped documents with their
+    /// position in the batch, or the error rejecting the whole batch. When none of the documents
+    /// can be indexed, the error of the first one rejects the batch.
+    pub fn add_valid_documents<R: Read + Seek>(
+        mut self,
+        reader: DocumentsBatchReader<R>,
+    ) -> Result<(Self, StdResult<(u64, Vec<(u32, UserError)>), UserError>)> {
+        puffin::profile_function!();
+
+        if reader.is_empty() {
+            return Ok((self, Ok((0, Vec::new()))));
+        }
+
+        let documents_count = reader.documents_count() as usize;
+        let (enriched_documents_reader, mut invalid_documents) = match enrich_valid_documents(
+            self.wtxn,
+            self.index,
+            self.config.autogenerate_docids,
+            reader,
+        )? {
+            Ok(split) => split,
+            Err(user_error) => return Ok((self, Err(user_error))),
+        };
+
+        let indexed_documents = if invalid_documents.len() < documents_count {
+            self.transform.as_mut().expect("Invalid document addition state").read_documents(
+                enriched_documents_reader,
+                self.wtxn,
+                &self.progress,
+                &self.should_abort,
+                Some(&mut invalid_documents),
+            )? as u64
+        } else {
+            0
+        };
+
+        invalid_documents.sort_unstable_by_key(|(position, _)| *position);
+        if invalid_documents.len() == documents_count {
+            let (_, user_error) = invalid_documents.swap_remove(0);
+            return Ok((self, Err(user_error)));
+        }
+
+        self.added_documents += indexed_documents;
+
+        Ok((self, Ok((indexed_documents, invalid_documents))))
+    }
+
     pub fn with_embedders(mut self, embedders: EmbeddingConfigs) -> Self {
         self.embedders = embedders;
         self
     }
 
+    /// Records the changes made to the documents by the next operations of the builder,
+    /// to be retrieved with [`Self::take_document_changes`].
+    pub fn with_document_changes(mut self) -> Self {
+        self.transform.as_mut().expect("Invalid document addition state").record_document_changes();
+        self
+    }
+
+    /// Returns the changes made to the documents since the last call, along with the external
+    /// ids of the changed documents, so that they can be attributed to the operations that
+    /// made them.
+    pub fn take_document_changes(&mut self) -> Vec<(String, ChangeOperation)> {
+        match self.transform.as_mut() {
+            Some(transform) => transform.take_document_changes(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the number of documents skipped since the last call because a previous document
+    /// with the same id, stored or added to the builder, has a higher `_version`.
+    pub fn take_outdated_documents(&mut self) -> u64 {
+        match self.transform.as_mut() {
+            Some(transform) => transform.take_outdated_documents(),
+            None => 0,
+        }
+    }
+
+    /// Only extracts and writes the databases of the given scope, the other ones are kept as is.
+    pub(crate) fn with_reindex_scope(mut self, reindex_scope: ReindexScope) -> Self {
+        self.reindex_scope = reindex_scope;
+        self
+    }
+
     /// Remove a batch of documents from the current builder.
     ///
     /// Returns the number of documents deleted from the builder.
@@ -204,6 +340,32 @@ where
         Ok((self, Ok(deleted_documents)))
     }
 
+    /// Remove the documents matching the filter from the current builder.
+    ///
+    /// Returns the number of documents deleted from the builder, or the user error
+    /// raised while evaluating the filter, in which case the builder can still be used.
+    pub fn remove_documents_from_filter(
+        mut self,
+        filter: &Filter,
+    ) -> Result<(Self, StdResult<u64, UserError>)> {
+        puffin::profile_function!();
+
+        let deleted_documents = match self
+            .transform
+            .as_mut()
+            .expect("Invalid document deletion state")
+            .remove_documents_from_filter(filter, self.wtxn, &self.should_abort)
+        {
+            Ok(deleted_documents) => deleted_documents as u64,
+            Err(Error::UserError(user_error)) => return Ok((self, Err(user_error))),
+            Err(error) => return Err(error),
+        };
+
+        self.deleted_documents += deleted_documents;
+
+        Ok((self, Ok(deleted_documents)))
+    }
+
     /// Removes documents from db using their internal document ids.
     ///
     /// # Warning
@@ -243,7 +405,11 @@ where
 
         if self.added_documents == 0 && self.deleted_documents == 0 {
             let number_of_documents = self.index.number_of_documents(self.wtxn)?;
-            return Ok(DocumentAdditionResult { indexed_documents: 0, number_of_documents });
+            return Ok(DocumentAdditionResult {
+                indexed_documents: 0,
+                number_of_documents,
+                embedding_failures: BTreeMap::new(),
+            });
         }
         let output = self
             .transform
@@ -266,15 +432,27 @@ where
             )?;
         }
 
+        // the suggestions only need the changed documents
+        update_suggestions(
+            self.wtxn,
+            self.index,
+            &output.original_documents,
+            &output.fields_ids_map,
+        )?;
+
         let indexed_documents = output.documents_count as u64;
-        let number_of_documents = self.execute_raw(output)?;
+        let (number_of_documents, embedding_failures) = self.execute_raw(output)?;
 
-        Ok(DocumentAdditionResult { indexed_documents, number_of_documents })
+        Ok(DocumentAdditionResult { indexed_documents, number_of_documents, embedding_failures })
     }
 
-    /// Returns the total number of documents in the index after the update.
+    /// Returns the total number of documents in the index after the update,
+    /// along with the documents indexed without the embeddings of an embedder.
     #[logging_timer::time("IndexDocuments::{}")]
-    pub fn execute_raw(self, output: TransformOutput) -> Result<u64>
+    pub fn execute_raw(
+        self,
+        output: TransformOutput,
+    ) -> Result<(u64, BTreeMap<String, EmbeddingFailures>)>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
         FA: Fn() -> bool + Sync,
@@ -326,13 +504,29 @@ where
         let primary_key_id = fields_ids_map.id(&primary_key).unwrap();
 
         // get searchable fields for word databases
-        let searchable_fields =
+        let mut searchable_fields: Option<HashSet<_>> =
             self.index.searchable_fields_ids(self.wtxn)?.map(HashSet::from_iter);
         // get filterable fields for facet databases
         let faceted_fields = self.index.faceted_fields_ids(self.wtxn)?;
+        // get the date fields whose values are indexed as timestamps
+        let date_fields = self.index.date_fields_ids(self.wtxn)?;
         // get the fid of the `_geo.lat` and `_geo.lng` fields.
         let mut field_id_map = self.index.fields_ids_map(self.wtxn)?;
 
+        // the coordinates of the `_geojson` field and its bounding box are never searchable
+        let geojson_fields_ids: Vec<_> = field_id_map
+            .iter()
+            .filter(|(_, name)| is_geojson_field(name))
+            .map(|(field_id, _)| field_id)
+            .collect();
+        if !geojson_fields_ids.is_empty() {
+            let searchable_fields =
+                searchable_fields.get_or_insert_with(|| field_id_map.ids().collect());
+            for field_id in geojson_fields_ids {
+                searchable_fields.remove(&field_id);
+            }
+        }
+
         // self.index.fields_ids_map($a)? ==>> field_id_map
         let geo_fields_ids = match field_id_map.id("_geo") {
             Some(gfid) => {
@@ -359,6 +553,13 @@ where
         let dictionary = self.index.dictionary(self.wtxn)?;
         let dictionary: Option<Vec<_>> =
             dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        let localized_attributes_rules = self.index.localized_attributes_rules(self.wtxn)?;
+        let localized_fields = LocalizedFieldIds::new(
+            &localized_attributes_rules,
+            &field_id_map,
+            stop_words.as_ref(),
+        )?;
+        let split_identifiers = self.index.split_identifiers(self.wtxn)?;
         let exact_attributes = self.index.exact_attributes_ids(self.wtxn)?;
         let proximity_precision = self.index.proximity_precision(self.wtxn)?.unwrap_or_default();
 
@@ -373,6 +574,22 @@ where
         let max_positions_per_attributes = self.indexer_config.max_positions_per_attributes;
 
         let cloned_embedder = self.embedders.clone();
+        let embedding_configs = self.index.embedding_configs(self.wtxn)?;
+        // the embeddings generated with other options can't be reused
+        let embedders_options_hashes: HashMap<_, _> = embedding_configs
+            .iter()
+            .map(|(name, config)| (name.clone(), options_hash(&config.embedder_options)))
+            .collect();
+        // the request policies are enforced across all the extraction threads
+        let request_limiters: HashMap<_, _> = embedding_configs
+            .iter()
+            .map(|(name, config)| {
+                (name.clone(), Arc::new(RequestLimiter::new(config.request_policy)))
+            })
+            .collect();
+        let cloned_request_limiters = request_limiters.clone();
+        let index = self.index;
+        let reindex_scope = self.reindex_scope;
 
         // Run extraction pipeline in parallel.
         pool.install(|| {
@@ -395,16 +612,23 @@ where
                     lmdb_writer_sx.clone(),
                     searchable_fields,
                     faceted_fields,
+                    date_fields,
                     primary_key_id,
                     geo_fields_ids,
                     field_id_map,
                     stop_words,
                     separators.as_deref(),
                     dictionary.as_deref(),
+                    &localized_fields,
+                    split_identifiers,
                     max_positions_per_attributes,
                     exact_attributes,
                     proximity_precision,
                     cloned_embedder,
+                    embedders_options_hashes,
+                    cloned_request_limiters,
+                    index,
+                    reindex_scope,
                 )
             });
 
@@ -431,6 +655,11 @@ where
         let mut exact_word_docids = None;
 
         let mut dimension = HashMap::new();
+        let mut embedding_failures: BTreeMap<String, EmbeddingFailures> = BTreeMap::new();
+
+        // The stems and romanizations are only derived from the words added by the batch.
+        let previous_words_fst =
+            self.index.words_fst(self.wtxn)?.map_data(|cow| cow.into_owned())?;
 
         for result in lmdb_writer_rx {
             if (self.should_abort)() {
@@ -466,14 +695,36 @@ where
                     remove_vectors,
                     embeddings,
                     manual_vectors,
+                    prompt_hashes,
+                    failed_docids,
+                    embedding_error,
                     embedder_name,
                 } => {
                     dimension.insert(embedder_name.clone(), expected_dimension);
+                    if let Some(error) = embedding_error {
+                        let failures = embedding_failures.entry(embedder_name.clone()).or_default();
+                        failures.failed_documents += failed_docids.len();
+                        let max_failed_documents = request_limiters
+                            .get(&embedder_name)
+                            .map_or(0, |limiter| limiter.policy().max_failed_documents);
+                        // past the tolerance of the policy, the whole indexing fails
+                        if failures.failed_documents > max_failed_documents {
+                            return Err(crate::vector::Error::from(error).into());
+                        }
+                        log::warn!(
+                            "Indexing {} documents without the embeddings of `{embedder_name}`: {error}",
+                            failed_docids.len()
+                        );
+                        failures.last_error = Some(error.to_string());
+                    }
                     TypedChunk::VectorPoints {
                         remove_vectors,
                         embeddings,
                         expected_dimension,
                         manual_vectors,
+                        prompt_hashes,
+                        failed_docids,
+                        embedding_error: None,
                         embedder_name,
                     }
                 }
@@ -513,38 +764,68 @@ where
 
         for (embedder_name, dimension) in dimension {
             let wtxn = &mut *self.wtxn;
-            let vector_arroy = self.index.vector_arroy;
+            let index = self.index;
 
             let embedder_index = self.index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
-
-            pool.install(|| {
-                let writer_index = (embedder_index as u16) << 8;
-                for k in 0..=u8::MAX {
-                    let writer = arroy::Writer::prepare(
-                        wtxn,
-                        vector_arroy,
-                        writer_index | (k as u16),
-                        dimension,
-                    )?;
-                    if writer.is_empty(wtxn)? {
-                        break;
-                    }
-                    writer.build(wtxn, &mut rng, None)?;
-                }
-                Result::Ok(())
+            let (distance, binary_quantized) = embedding_configs
+                .iter()
+                .find_map(|(name, config)| {
+                    (*name == embedder_name).then_some((config.distance, config.binary_quantized))
+                })
+                .unwrap_or_default();
+            // The quantized vectors are compared without the trees of arroy, which only keeps
+            // the full-precision vectors the candidates are re-scored with.
+            let n_trees = binary_quantized.then_some(0);
+
+            pool.install(|| match distance {
+                Distance::Cosine => build_vectors(
+                    wtxn,
+                    index.vector_arroy,
+                    embedder_index,
+                    dimension,
+                    n_trees,
+                    &mut rng,
+                ),
+                Distance::DotProduct => build_vectors(
+                    wtxn,
+                    index.vector_arroy_dot_product,
+                    embedder_index,
+                    dimension,
+                    n_trees,
+                    &mut rng,
+                ),
+                Distance::Euclidean => build_vectors(
+                    wtxn,
+                    index.vector_arroy_euclidean,
+                    embedder_index,
+                    dimension,
+                    n_trees,
+                    &mut rng,
+                ),
             })?;
         }
 
-        self.execute_prefix_databases(
-            word_docids,
-            exact_word_docids,
-            word_position_docids,
-            word_fid_docids,
-        )?;
+        // The word stems, romanizations and the prefix databases are only computed
+        // from the word databases.
+        if reindex_scope.words {
+            let new_words = new_words(&previous_words_fst, self.index, self.wtxn)?;
+            let mut builder = WordStemsFst::new(self.wtxn, self.index);
+            builder.new_words(new_words.clone());
+            builder.execute()?;
+            let mut builder = WordRomanizationsFst::new(self.wtxn, self.index);
+            builder.new_words(new_words);
+            builder.execute()?;
+            self.execute_prefix_databases(
+                word_docids,
+                exact_word_docids,
+                word_position_docids,
+                word_fid_docids,
+            )?;
+        }
 
-        Ok(number_of_documents)
+        Ok((number_of_documents, embedding_failures))
     }
 
     #[logging_timer::time("IndexDocuments::{}")]
@@ -748,6 +1029,28 @@ fn execute_word_prefix_docids(
     Ok(())
 }
 
+/// Builds the arroy trees of the vectors of an embedder.
+/// Builds the trees of the arroy writers of the embedder, as many as arroy decides when
+/// `n_trees` is `None`. Without any tree, only the metadata needed to read the vectors is written.
+fn build_vectors<D: arroy::Distance>(
+    wtxn: &mut heed::RwTxn,
+    database: arroy::Database<D>,
+    embedder_index: u8,
+    dimension: usize,
+    n_trees: Option<usize>,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<()> {
+    let writer_index = (embedder_index as u16) << 8;
+    for k in 0..=u8::MAX {
+        let writer = arroy::Writer::prepare(wtxn, database, writer_index | (k as u16), dimension)?;
+        if writer.is_empty(wtxn)? {
+            break;
+        }
+        writer.build(wtxn, rng, n_trees)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -761,8 +1064,8 @@ mod tests {
     use crate::documents::documents_batch_reader_from_objects;
     use crate::index::tests::TempIndex;
     use crate::search::TermsMatchingStrategy;
-    use crate::update::Setting;
-    use crate::{db_snap, Filter, Search};
+    use crate::update::{AttributeOverflowPolicy, Setting, TypeCoercion};
+    use crate::{db_snap, FieldsIdsMap, Filter, Search};
 
     #[test]
     fn simple_document_replacement() {
@@ -867,6 +1170,95 @@ mod tests {
         drop(rtxn);
     }
 
+    #[test]
+    fn simple_document_merge_patch() {
+        let mut index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                {
+                    "id": 1,
+                    "name": "kevin",
+                    "address": { "city": "Paris", "zip": 75000 },
+                    "tags": ["a", "b"],
+                },
+                { "id": 2, "name": "kevina" },
+            ]))
+            .unwrap();
+
+        index.index_documents_config.update_method = IndexDocumentsMethod::MergePatchDocuments;
+
+        // We send two patches for the same document in the same batch and a patch for a
+        // document that doesn't exist yet.
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": null, "address": { "zip": null, "street": "rue" } },
+                { "id": 1, "address": { "city": "Lyon" }, "tags": ["c"] },
+                { "id": 3, "name": "benoit", "address": { "city": "Nantes" } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let count = index.number_of_documents(&rtxn).unwrap();
+        assert_eq!(count, 3);
+
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let external_documents_ids = index.external_documents_ids();
+        let mut documents = Vec::new();
+        for external_id in ["1", "2", "3"] {
+            let docid = external_documents_ids.get(&rtxn, external_id).unwrap().unwrap();
+            let (_, obkv) = index.documents(&rtxn, Some(docid)).unwrap()[0];
+            documents.push(crate::all_obkv_to_json(obkv, &fields_ids_map).unwrap());
+        }
+
+        assert_eq!(
+            serde_json::Value::Array(documents.into_iter().map(Into::into).collect()),
+            serde_json::json!([
+                { "id": 1, "address": { "city": "Lyon", "street": "rue" }, "tags": ["c"] },
+                { "id": 2, "name": "kevina" },
+                { "id": 3, "name": "benoit", "address": { "city": "Nantes" } },
+            ])
+        );
+
+        // The nested fields are flattened from the merged document.
+        assert!(fields_ids_map.id("address.street").is_some());
+        drop(rtxn);
+    }
+
+    #[test]
+    fn merge_patch_null_fields_of_new_documents() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.update_method = IndexDocumentsMethod::MergePatchDocuments;
+
+        // The first document doesn't exist, the second one is patched in the same batch.
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": null, "address": { "city": "Paris", "zip": null } },
+                { "id": 2, "name": "kevina" },
+                { "id": 2, "name": null, "age": null, "address": { "zip": null } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let external_documents_ids = index.external_documents_ids();
+        let mut documents = Vec::new();
+        for external_id in ["1", "2"] {
+            let docid = external_documents_ids.get(&rtxn, external_id).unwrap().unwrap();
+            let (_, obkv) = index.documents(&rtxn, Some(docid)).unwrap()[0];
+            documents.push(crate::all_obkv_to_json(obkv, &fields_ids_map).unwrap());
+        }
+
+        assert_eq!(
+            serde_json::Value::Array(documents.into_iter().map(Into::into).collect()),
+            serde_json::json!([
+                { "id": 1, "address": { "city": "Paris" } },
+                { "id": 2, "address": {} },
+            ])
+        );
+        drop(rtxn);
+    }
+
     #[test]
     fn not_auto_generated_documents_ids() {
         let index = TempIndex::new();
@@ -1208,6 +1600,32 @@ mod tests {
         index.add_documents(documents).unwrap();
     }
 
+    #[test]
+    fn ignore_fields_over_the_attribute_limit() {
+        let mut index = TempIndex::new();
+
+        // we fill the fields ids map up to two fields before its limit
+        let mut wtxn = index.write_txn().unwrap();
+        let mut fields_ids_map = FieldsIdsMap::new();
+        for i in 0..u16::MAX - 1 {
+            fields_ids_map.insert(&format!("field{i}")).unwrap();
+        }
+        index.put_fields_ids_map(&mut wtxn, &fields_ids_map).unwrap();
+        wtxn.commit().unwrap();
+
+        let error = index
+            .add_documents(documents!([{ "id": 0, "title": "kevin", "extra": "bob" }]))
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::AttributeLimitReached)));
+
+        index.indexer_config.attribute_overflow_policy = AttributeOverflowPolicy::Ignore;
+        index.add_documents(documents!([{ "id": 0, "title": "kevin", "extra": "bob" }])).unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"title":"kevin"}
+        "###);
+    }
+
     #[test]
     fn index_more_than_1000_positions_in_a_field() {
         let index = TempIndex::new_with_map_size(4096 * 100_000); // 400 MB
@@ -1388,6 +1806,102 @@ mod tests {
         assert!(matches!(error, crate::Error::UserError(crate::UserError::InvalidFilter(_))));
     }
 
+    #[test]
+    fn geojson_is_neither_flattened_nor_searchable() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset!(S("_geojson")));
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "name": "park", "_geojson": { "type": "Point", "coordinates": [12, 42] } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        assert!(fields_ids_map.id("_geojson").is_some());
+        assert!(fields_ids_map.id("_geojson._minLat").is_some());
+        assert!(fields_ids_map.id("_geojson.type").is_none());
+        assert!(fields_ids_map.id("_geojson.coordinates").is_none());
+
+        let mut search = crate::Search::new(&rtxn, &index);
+        search.query("park");
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(documents_ids, vec![0]);
+        search.query("42");
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert!(documents_ids.is_empty());
+        search.query("point");
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert!(documents_ids.is_empty());
+    }
+
+    #[test]
+    fn flatten_documents_in_parallel() {
+        let mut index = TempIndex::new();
+        index.indexer_config.thread_pool =
+            Some(rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap());
+        index.index_documents_config.update_method = IndexDocumentsMethod::UpdateDocuments;
+
+        let documents = (0..1000).map(|id| {
+            serde_json::json!({ "id": id, "nested": { "id": id, format!("field{}", id % 10): id } })
+                .as_object()
+                .unwrap()
+                .clone()
+        });
+        index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+        // the stored documents are flattened again when they are updated
+        let documents = (0..1000).step_by(2).map(|id| {
+            serde_json::json!({ "id": id, "nested": { "even": true } }).as_object().unwrap().clone()
+        });
+        index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+        // the new fields are registered in the order of the documents
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let fields: Vec<_> = fields_ids_map.iter().map(|(_, name)| name).collect();
+        insta::assert_debug_snapshot!(fields, @r###"
+        [
+            "id",
+            "nested",
+            "nested.id",
+            "nested.field0",
+            "nested.field1",
+            "nested.field2",
+            "nested.field3",
+            "nested.field4",
+            "nested.field5",
+            "nested.field6",
+            "nested.field7",
+            "nested.field8",
+            "nested.field9",
+            "nested.even",
+        ]
+        "###);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset!(S("nested.even"), S("nested.id")));
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let mut search = crate::Search::new(&rtxn, &index);
+        search.filter(crate::Filter::from_str("nested.even = true").unwrap().unwrap());
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(documents_ids.len(), 500);
+
+        search.filter(crate::Filter::from_str("nested.id = 1").unwrap().unwrap());
+        let crate::SearchResult { documents_ids, .. } = search.execute().unwrap();
+        assert_eq!(documents_ids, vec![1]);
+    }
+
     #[test]
     fn index_documents_with_nested_primary_key() {
         let index = TempIndex::new();
@@ -2210,6 +2724,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 2,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2220,6 +2735,369 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn record_the_document_changes_of_the_transform() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevin" },
+                { "id": 2, "name": "bob" },
+                { "id": 3, "name": "jean" },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let mut builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap()
+        .with_document_changes();
+
+        // the unchanged document 1 isn't recorded
+        let documents = documents!([
+            { "id": 1, "name": "kevin" },
+            { "id": 2, "name": "bobby" },
+            { "id": 4, "name": "lucas" },
+            { "id": 4, "name": "lucas" },
+        ]);
+        (builder, _) = builder.add_documents(documents).unwrap();
+        insta::assert_debug_snapshot!(builder.take_document_changes(), @r###"
+        [
+            (
+                "2",
+                Update,
+            ),
+            (
+                "4",
+                Addition,
+            ),
+            (
+                "4",
+                Update,
+            ),
+        ]
+        "###);
+
+        // the documents are only recorded as deleted once, the unknown ones aren't recorded
+        (builder, _) = builder.remove_documents(vec![S("3"), S("4"), S("5")]).unwrap();
+        (builder, _) = builder.remove_documents(vec![S("3")]).unwrap();
+        let documents = documents!([{ "id": 3, "name": "jean" }]);
+        (builder, _) = builder.add_documents(documents).unwrap();
+        insta::assert_debug_snapshot!(builder.take_document_changes(), @r###"
+        [
+            (
+                "3",
+                Deletion,
+            ),
+            (
+                "4",
+                Deletion,
+            ),
+            (
+                "3",
+                Addition,
+            ),
+        ]
+        "###);
+
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn add_valid_documents_skips_the_invalid_ones() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 1, "_geo": { "lat": 12, "lng": 13 } },
+            { "id": "bad id!" },
+            { "id": 3, "_geo": { "lat": 12 } },
+            { "doggo": "kevin" },
+            { "id": 5 },
+        ]);
+        let (builder, added) = builder.add_valid_documents(documents).unwrap();
+        let (added, invalid_documents) = added.unwrap();
+        assert_eq!(added, 2);
+        let positions: Vec<_> = invalid_documents.iter().map(|(position, _)| *position).collect();
+        assert_eq!(positions, vec![1, 2, 3]);
+        assert!(matches!(invalid_documents[0].1, UserError::InvalidDocumentId { .. }));
+        assert!(matches!(invalid_documents[1].1, UserError::InvalidGeoField(_)));
+        assert!(matches!(invalid_documents[2].1, UserError::MissingDocumentId { .. }));
+
+        // the batch is rejected when none of its documents can be indexed
+        let documents = documents!([{ "id": "bad id!" }, { "id": 7, "_geo": { "lat": 12 } }]);
+        let (builder, added) = builder.add_valid_documents(documents).unwrap();
+        assert!(matches!(added, Err(UserError::InvalidDocumentId { .. })));
+
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":1,"_geo":{"lat":12,"lng":13}}
+        {"id":5}
+        "###);
+    }
+
+    #[test]
+    fn add_and_delete_documents_by_filter_in_single_transform() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("doggo") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 1, "doggo": "kevin" },
+                { "id": 2, "doggo": "bob" },
+                { "id": 3, "doggo": "kevin" },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 3, "doggo": "kevin", "age": 2 },
+            { "id": 4, "doggo": "kevin" },
+        ]);
+        let (builder, added) = builder.add_documents(documents).unwrap();
+        insta::assert_display_snapshot!(added.unwrap(), @"2");
+
+        // The filter can only be applied on the filterable attributes.
+        let filter = Filter::from_str("age = 2").unwrap().unwrap();
+        let (builder, removed) = builder.remove_documents_from_filter(&filter).unwrap();
+        assert!(matches!(removed, Err(UserError::InvalidFilter(_))));
+
+        // Only the documents stored in the database are matched by the filter.
+        let filter = Filter::from_str("doggo = kevin").unwrap().unwrap();
+        let (builder, removed) = builder.remove_documents_from_filter(&filter).unwrap();
+        insta::assert_display_snapshot!(removed.unwrap(), @"2");
+
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":2,"doggo":"bob"}
+        {"id":4,"doggo":"kevin"}
+        "###);
+    }
+
+    #[test]
+    fn skip_outdated_document_versions() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 1, "doggo": "kevin", "_version": 2 },
+                { "id": 2, "doggo": "bob", "_version": 2 },
+                { "id": 3, "doggo": "jean" },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 1, "doggo": "bernard", "_version": 1 },
+            { "id": 2, "doggo": "michel", "_version": 3 },
+            { "id": 3, "doggo": "jacques", "_version": 1 },
+        ]);
+        let (mut builder, added) = builder.add_documents(documents).unwrap();
+        insta::assert_display_snapshot!(added.unwrap(), @"2");
+        insta::assert_display_snapshot!(builder.take_outdated_documents(), @"1");
+
+        // the documents of a batch are compared with the previous ones of the same batch
+        let documents = documents!([
+            { "id": 4, "doggo": "paul", "_version": 3 },
+            { "id": 4, "doggo": "pierre", "_version": 2 },
+            { "id": 2, "doggo": "jules", "_version": 4 },
+            { "id": 2, "doggo": "jim", "_version": 3 },
+        ]);
+        let (mut builder, added) = builder.add_documents(documents).unwrap();
+        insta::assert_display_snapshot!(added.unwrap(), @"2");
+        insta::assert_display_snapshot!(builder.take_outdated_documents(), @"2");
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":1,"doggo":"kevin","_version":2}
+        {"id":2,"doggo":"jules","_version":4}
+        {"id":3,"doggo":"jacques","_version":1}
+        {"id":4,"doggo":"paul","_version":3}
+        "###);
+
+        let error = index
+            .add_documents(documents!([{ "id": 1, "doggo": "bernard", "_version": "a" }]))
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::InvalidDocumentVersion { .. })));
+
+        let error = index
+            .add_documents(documents!([{ "id": 1, "doggo": "bernard", "_version": 0 }]))
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::InvalidDocumentVersion { .. })));
+    }
+
+    #[test]
+    fn reject_documents_not_matching_the_document_schema() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_document_schema(serde_json::json!({
+                    "type": "object",
+                    "properties": { "price": { "type": "number" } }
+                }));
+            })
+            .unwrap();
+
+        index.add_documents(documents!([{ "id": 1, "price": 12 }, { "id": 2 }])).unwrap();
+
+        let error = index.add_documents(documents!([{ "id": 3, "price": "12" }])).unwrap_err();
+        insta::assert_display_snapshot!(error, @"The document with the id: `3` does not match the document schema of the index: `price` must be of type `number`, but found a string: `\"12\"`.");
+
+        db_snap!(index, documents, @r###"
+        {"id":1,"price":12}
+        {"id":2}
+        "###);
+
+        let error = index
+            .update_settings(|settings| {
+                settings.set_document_schema(serde_json::json!({ "type": "float" }));
+            })
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::InvalidDocumentSchemaSetting(_))));
+    }
+
+    #[test]
+    fn validate_the_converted_and_updated_documents_against_the_document_schema() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.update_method = IndexDocumentsMethod::UpdateDocuments;
+        index
+            .update_settings(|settings| {
+                settings.set_type_coercions(BTreeMap::from([(S("price"), TypeCoercion::Number)]));
+                settings.set_document_schema(serde_json::json!({
+                    "type": "object",
+                    "required": ["title"],
+                    "properties": { "title": { "type": "string" }, "price": { "type": "number" } }
+                }));
+            })
+            .unwrap();
+
+        // the price is validated once converted into a number
+        index.add_documents(documents!([{ "id": 1, "title": "Carol", "price": "12" }])).unwrap();
+        // the title is required but already stored
+        index.add_documents(documents!([{ "id": 1, "price": 10 }])).unwrap();
+
+        let error = index.add_documents(documents!([{ "id": 2, "price": 3 }])).unwrap_err();
+        insta::assert_display_snapshot!(error, @"The document with the id: `2` does not match the document schema of the index: `title` is required.");
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+        let documents = documents!([
+            { "id": 3, "title": "Gravity" },
+            { "id": 4, "title": "Wall-E", "price": "free" },
+            { "id": 1, "title": null },
+        ]);
+        let (builder, added) = builder.add_valid_documents(documents).unwrap();
+        let (added, invalid_documents) = added.unwrap();
+        assert_eq!(added, 1);
+        let positions: Vec<_> = invalid_documents.iter().map(|(position, _)| *position).collect();
+        assert_eq!(positions, vec![1, 2]);
+        assert!(invalid_documents
+            .iter()
+            .all(|(_, error)| matches!(error, UserError::InvalidDocumentSchema { .. })));
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":1,"title":"Carol","price":10}
+        {"id":3,"title":"Gravity"}
+        "###);
+    }
+
+    #[test]
+    fn skip_documents_where_only_ignored_fields_changed() {
+        let mut index = TempIndex::new();
+        index.indexer_config.unchanged_document_ignored_fields = vec![S("updated_at")];
+
+        index
+            .add_documents(documents!([
+                { "id": 1, "doggo": "kevin", "updated_at": 1 },
+                { "id": 2, "doggo": "bob", "updated_at": 1 },
+            ]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 1, "doggo": "kevin", "updated_at": 2 },
+            { "id": 2, "doggo": "michel", "updated_at": 2 },
+        ]);
+        let (builder, added) = builder.add_documents(documents).unwrap();
+        insta::assert_display_snapshot!(added.unwrap(), @"2");
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":1,"doggo":"kevin","updated_at":1}
+        {"id":2,"doggo":"michel","updated_at":2}
+        "###);
+    }
+
     #[test]
     fn add_update_and_delete_documents_in_single_transform() {
         let mut index = TempIndex::new();
@@ -2259,6 +3137,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 5,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2297,6 +3176,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 3,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2335,6 +3215,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2375,6 +3256,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 2,
             number_of_documents: 2,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2422,6 +3304,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 3,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2458,6 +3341,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2493,6 +3377,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2566,6 +3451,9 @@ mod tests {
                             crate::vector::settings::UserProvidedSettings { dimensions: 3 },
                         )),
                         document_template: Setting::NotSet,
+                        binary_quantized: Setting::NotSet,
+                        distance: Setting::NotSet,
+                        request_policy: Setting::NotSet,
                     }),
                 );
                 settings.set_embedder_settings(embedders);
@@ -2589,6 +3477,158 @@ mod tests {
         assert_eq!(res.documents_ids.len(), 3);
     }
 
+    #[test]
+    fn vector_distances() {
+        use crate::vector::settings::{EmbedderSettings, EmbeddingSettings};
+        use crate::vector::Distance;
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                let mut embedders = BTreeMap::default();
+                for (name, distance) in
+                    [("euclidean", Distance::Euclidean), ("dotProduct", Distance::DotProduct)]
+                {
+                    embedders.insert(
+                        name.to_string(),
+                        Setting::Set(EmbeddingSettings {
+                            embedder_options: Setting::Set(EmbedderSettings::UserProvided(
+                                crate::vector::settings::UserProvidedSettings { dimensions: 2 },
+                            )),
+                            document_template: Setting::NotSet,
+                            binary_quantized: Setting::NotSet,
+                            distance: Setting::Set(distance),
+                            request_policy: Setting::NotSet,
+                        }),
+                    );
+                }
+                settings.set_embedder_settings(embedders);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "euclidean": [10, 0], "dotProduct": [10, 0] } },
+                { "id": 1, "_vectors": { "euclidean": [0.9, 0.1], "dotProduct": [0.9, 0.1] } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let mut search = index.search(&rtxn);
+        search.vector([1.0, 0.0].to_vec()).embedder_name("euclidean");
+        assert_eq!(search.execute().unwrap().documents_ids, vec![1, 0]);
+
+        let mut search = index.search(&rtxn);
+        search.vector([1.0, 0.0].to_vec()).embedder_name("dotProduct");
+        assert_eq!(search.execute().unwrap().documents_ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn multiple_vector_targets() {
+        use crate::vector::settings::{EmbedderSettings, EmbeddingSettings};
+        use crate::VectorTarget;
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                let mut embedders = BTreeMap::default();
+                for name in ["title", "image"] {
+                    embedders.insert(
+                        name.to_string(),
+                        Setting::Set(EmbeddingSettings {
+                            embedder_options: Setting::Set(EmbedderSettings::UserProvided(
+                                crate::vector::settings::UserProvidedSettings { dimensions: 2 },
+                            )),
+                            document_template: Setting::NotSet,
+                            binary_quantized: Setting::NotSet,
+                            distance: Setting::NotSet,
+                            request_policy: Setting::NotSet,
+                        }),
+                    );
+                }
+                settings.set_embedder_settings(embedders);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "title": [1, 0], "image": [0, 1] } },
+                { "id": 1, "_vectors": { "title": [0, 1], "image": [1, 0] } },
+                { "id": 2, "_vectors": { "title": [0.7, 0.7], "image": [0.7, 0.7] } },
+            ]))
+            .unwrap();
+
+        let targets = |title_weight, image_weight| {
+            vec![
+                VectorTarget {
+                    embedder_name: "title".to_string(),
+                    vector: vec![1.0, 0.0],
+                    weight: title_weight,
+                },
+                VectorTarget {
+                    embedder_name: "image".to_string(),
+                    vector: vec![1.0, 0.0],
+                    weight: image_weight,
+                },
+            ]
+        };
+
+        let rtxn = index.read_txn().unwrap();
+        let mut search = index.search(&rtxn);
+        search.vector_targets(targets(1.0, 0.1));
+        assert_eq!(search.execute().unwrap().documents_ids, vec![0, 2, 1]);
+
+        let mut search = index.search(&rtxn);
+        search.vector_targets(targets(0.1, 1.0));
+        assert_eq!(search.execute().unwrap().documents_ids, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn binary_quantized_vectors() {
+        use crate::vector::settings::{EmbedderSettings, EmbeddingSettings};
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                let mut embedders = BTreeMap::default();
+                embedders.insert(
+                    "manual".to_string(),
+                    Setting::Set(EmbeddingSettings {
+                        embedder_options: Setting::Set(EmbedderSettings::UserProvided(
+                            crate::vector::settings::UserProvidedSettings { dimensions: 3 },
+                        )),
+                        document_template: Setting::NotSet,
+                        binary_quantized: Setting::Set(true),
+                        distance: Setting::NotSet,
+                        request_policy: Setting::NotSet,
+                    }),
+                );
+                settings.set_embedder_settings(embedders);
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "_vectors": { "manual": [[1, 1, 1], [-1, -1, 1]] } },
+                { "id": 1, "_vectors": { "manual": [-1, -1, -1] } },
+                { "id": 2, "_vectors": { "manual": [1, 0.8, 1.2] } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.vector_binary_quantized.len(&rtxn).unwrap(), 4);
+        let res = index.search(&rtxn).vector([1.0, 1.0, 1.0].to_vec()).execute().unwrap();
+        assert_eq!(res.documents_ids, vec![0, 2, 1]);
+        drop(rtxn);
+
+        index.delete_documents(vec!["0".to_string()]);
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.vector_binary_quantized.len(&rtxn).unwrap(), 2);
+        let res = index.search(&rtxn).vector([1.0, 1.0, 1.0].to_vec()).execute().unwrap();
+        assert_eq!(res.documents_ids, vec![2, 1]);
+    }
+
     #[test]
     fn reproduce_the_bug() {
         /*
@@ -2651,6 +3691,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2695,6 +3736,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 1,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -2735,6 +3777,7 @@ mod tests {
         DocumentAdditionResult {
             indexed_documents: 1,
             number_of_documents: 2,
+            embedding_failures: {},
         }
         "###);
         wtxn.commit().unwrap();
@@ -1,30 +1,47 @@
 use std::borrow::Cow;
 use std::collections::btree_map::Entry as BEntry;
 use std::collections::hash_map::Entry as HEntry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::io::{Read, Seek};
+use std::sync::{Arc, RwLock};
 
 use fxhash::FxHashMap;
 use heed::RoTxn;
 use itertools::Itertools;
 use obkv::{KvReader, KvReaderU16, KvWriter};
+use rayon::prelude::*;
 use roaring::RoaringBitmap;
 use serde_json::Value;
 use smartstring::SmartString;
 
 use super::helpers::{
-    create_sorter, create_writer, keep_first, obkvs_keep_last_addition_merge_deletions,
-    obkvs_merge_additions_and_deletions, sorter_into_reader, MergeFn,
+    boxed_merge_fn, create_sorter, create_sorter_with_merge, create_writer, json_merge_patch,
+    keep_first, obkvs_keep_last_addition_merge_deletions, obkvs_merge_additions_and_deletions,
+    obkvs_merge_additions_with_strategies, obkvs_merge_patch_additions_and_deletions,
+    sorter_into_reader, BoxedMergeFn,
 };
-use super::{IndexDocumentsMethod, IndexerConfig};
+use super::{IndexDocumentsMethod, IndexerConfig, ReindexScope};
+use crate::change_feed::ChangeOperation;
+use crate::document_schema::{DocumentSchema, SchemaViolation};
 use crate::documents::{DocumentsBatchIndex, EnrichedDocument, EnrichedDocumentsBatchReader};
 use crate::error::{Error, InternalError, UserError};
+use crate::geojson::{GeoShape, GEOJSON_BOUNDING_BOX_FIELDS, GEOJSON_FIELD};
 use crate::index::{db_name, main_key};
+use crate::update::clear_documents::clear_reindexed_databases;
 use crate::update::del_add::{into_del_add_obkv, DelAdd, DelAddOperation, KvReaderDelAdd};
 use crate::update::index_documents::GrenadParameters;
-use crate::update::{AvailableDocumentsIds, ClearDocuments, UpdateIndexingStep};
-use crate::{FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldsIdsMap, Index, Result};
+use crate::update::ingestion_mapping::{nested_sources, renamed_field, take_at_path};
+use crate::update::{
+    AttributeOverflowPolicy, AvailableDocumentsIds, MergeStrategy, TypeCoercion, UpdateIndexingStep,
+};
+use crate::{
+    all_obkv_to_json, DocumentId, FieldDistribution, FieldId, FieldIdMapMissingEntry, FieldsIdsMap,
+    Filter, Index, Result,
+};
+
+/// The number of documents flattened in parallel at once by the transform.
+const FLATTENING_CHUNK_SIZE: usize = 10_000;
 
 pub struct TransformOutput {
     pub primary_key: String,
@@ -44,6 +61,21 @@ pub struct TransformOutput {
 pub struct Transform<'a, 'i> {
     pub index: &'i Index,
     fields_ids_map: FieldsIdsMap,
+    /// The fields whose nested objects are kept verbatim instead of being flattened.
+    non_flattened_fields: HashSet<String>,
+    /// The separators of the hierarchical facets whose levels are indexed as separate fields.
+    facet_hierarchy_separators: BTreeMap<String, String>,
+    /// The strategies used to combine the stored and new values of the fields on update.
+    merge_strategies: BTreeMap<String, MergeStrategy>,
+    /// The merge strategies by field id, shared with the merge function of the sorters.
+    fields_merge_strategies: Arc<RwLock<HashMap<FieldId, MergeStrategy>>>,
+    /// The types the values of the fields are converted to when the documents are read.
+    type_coercions: BTreeMap<String, TypeCoercion>,
+    /// The JSON Schema the documents must conform to once converted and merged with their
+    /// stored version.
+    document_schema: Option<DocumentSchema>,
+    /// The new names of the fields of the documents, indexed by their path in the documents.
+    ingestion_mapping: BTreeMap<String, String>,
 
     indexer_settings: &'a IndexerConfig,
     pub autogenerate_docids: bool,
@@ -53,19 +85,31 @@ pub struct Transform<'a, 'i> {
     // Both grenad follows the same format:
     // key | value
     // u32 | 1 byte for the Operation byte, the rest is the obkv of the document stored
-    original_sorter: grenad::Sorter<MergeFn>,
-    flattened_sorter: grenad::Sorter<MergeFn>,
+    original_sorter: grenad::Sorter<BoxedMergeFn>,
+    flattened_sorter: grenad::Sorter<BoxedMergeFn>,
 
     replaced_documents_ids: RoaringBitmap,
     new_documents_ids: RoaringBitmap,
+    /// The documents replaced by a version with the same fields as the stored one, they
+    /// don't change the field distribution and are skipped when computing it.
+    same_fields_documents_ids: RoaringBitmap,
     // To increase the cache locality and decrease the heap usage we use compact smartstring.
     new_external_documents_ids_builder: FxHashMap<SmartString<smartstring::Compact>, u64>,
     documents_count: usize,
+    /// The `_version` of the documents inserted by the transform, by external id, the following
+    /// documents with the same id being compared with them rather than with the stored ones.
+    inserted_versions: FxHashMap<SmartString<smartstring::Compact>, Option<u64>>,
+    /// The number of documents skipped since the last call to `take_outdated_documents`
+    /// because they have a lower `_version` than the previous document with the same id.
+    outdated_documents: u64,
+    /// The changes made to the documents, when they are recorded.
+    document_changes: Option<Vec<(String, ChangeOperation)>>,
 }
 
 /// This enum is specific to the grenad sorter stored in the transform.
 /// It's used as the first byte of the grenads and tells you if the document id was an addition or a deletion.
 #[repr(u8)]
+#[derive(Clone, Copy)]
 pub enum Operation {
     Addition,
     Deletion,
@@ -75,25 +119,67 @@ pub enum Operation {
 /// already present in the index.
 ///
 /// If new fields are present in the addition, they are added to the index field ids map.
+///
+/// The fields renamed by the ingestion mapping of the index are mapped to the field
+/// of their new name.
+///
+/// When the fields limit is reached and the overflow policy is to ignore the exceeding
+/// fields, those fields are not part of the returned mapping.
 fn create_fields_mapping(
     index_field_map: &mut FieldsIdsMap,
     batch_field_map: &DocumentsBatchIndex,
+    ingestion_mapping: &BTreeMap<String, String>,
+    overflow_policy: AttributeOverflowPolicy,
 ) -> Result<HashMap<FieldId, FieldId>> {
     batch_field_map
         .iter()
         // we sort by id here to ensure a deterministic mapping of the fields, that preserves
         // the original ordering.
         .sorted_by_key(|(&id, _)| id)
-        .map(|(field, name)| match index_field_map.id(name) {
-            Some(id) => Ok((*field, id)),
-            None => index_field_map
-                .insert(name)
-                .ok_or(Error::UserError(UserError::AttributeLimitReached))
-                .map(|id| (*field, id)),
+        .filter_map(|(field, name)| {
+            match index_field_map.insert(renamed_field(ingestion_mapping, name)) {
+                Some(id) => Some(Ok((*field, id))),
+                None => match overflow_policy {
+                    AttributeOverflowPolicy::Error => {
+                        Some(Err(Error::UserError(UserError::AttributeLimitReached)))
+                    }
+                    AttributeOverflowPolicy::Ignore => None,
+                },
+            }
         })
         .collect()
 }
 
+/// Create a mapping between the field ids found in the document batch and the nested values
+/// the ingestion mapping moves out of those fields, with their path in the field and the id
+/// of the field they are moved into.
+///
+/// When the fields limit is reached and the overflow policy is to ignore the exceeding
+/// fields, the nested values are kept in place.
+fn create_nested_fields_mapping(
+    index_field_map: &mut FieldsIdsMap,
+    batch_field_map: &DocumentsBatchIndex,
+    ingestion_mapping: &BTreeMap<String, String>,
+    overflow_policy: AttributeOverflowPolicy,
+) -> Result<HashMap<FieldId, Vec<(String, FieldId)>>> {
+    let mut nested_mapping: HashMap<FieldId, Vec<_>> = HashMap::new();
+    for (&field, name) in batch_field_map.iter().sorted_by_key(|(&id, _)| id) {
+        for (path, target) in nested_sources(ingestion_mapping, name) {
+            match index_field_map.insert(target) {
+                Some(id) => nested_mapping.entry(field).or_default().push((path.to_string(), id)),
+                None => match overflow_policy {
+                    AttributeOverflowPolicy::Error => {
+                        return Err(UserError::AttributeLimitReached.into())
+                    }
+                    AttributeOverflowPolicy::Ignore => (),
+                },
+            }
+        }
+    }
+
+    Ok(nested_mapping)
+}
+
 impl<'a, 'i> Transform<'a, 'i> {
     pub fn new(
         wtxn: &mut heed::RwTxn,
@@ -102,17 +188,30 @@ impl<'a, 'i> Transform<'a, 'i> {
         index_documents_method: IndexDocumentsMethod,
         autogenerate_docids: bool,
     ) -> Result<Self> {
-        // We must choose the appropriate merge function for when two or more documents
-        // with the same user id must be merged or fully replaced in the same batch.
-        let merge_function = match index_documents_method {
-            IndexDocumentsMethod::ReplaceDocuments => obkvs_keep_last_addition_merge_deletions,
-            IndexDocumentsMethod::UpdateDocuments => obkvs_merge_additions_and_deletions,
+        // The merge strategies only apply when updating the documents.
+        let merge_strategies = match index_documents_method {
+            IndexDocumentsMethod::UpdateDocuments => index.merge_strategies(wtxn)?,
+            IndexDocumentsMethod::ReplaceDocuments | IndexDocumentsMethod::MergePatchDocuments => {
+                BTreeMap::new()
+            }
         };
+        let fields_ids_map = index.fields_ids_map(wtxn)?;
+        let fields_merge_strategies = fields_ids_map
+            .iter()
+            .filter_map(|(fid, name)| {
+                merge_strategy_of(&merge_strategies, name).map(|strategy| (fid, strategy))
+            })
+            .collect();
+        let fields_merge_strategies = Arc::new(RwLock::new(fields_merge_strategies));
 
         // We initialize the sorter with the user indexing settings.
-        let original_sorter = create_sorter(
+        let original_sorter = create_sorter_with_merge(
             grenad::SortAlgorithm::Stable,
-            merge_function,
+            documents_merge_function(
+                index_documents_method,
+                &merge_strategies,
+                &fields_merge_strategies,
+            ),
             indexer_settings.chunk_compression_type,
             indexer_settings.chunk_compression_level,
             indexer_settings.max_nb_chunks,
@@ -120,9 +219,13 @@ impl<'a, 'i> Transform<'a, 'i> {
         );
 
         // We initialize the sorter with the user indexing settings.
-        let flattened_sorter = create_sorter(
+        let flattened_sorter = create_sorter_with_merge(
             grenad::SortAlgorithm::Stable,
-            merge_function,
+            documents_merge_function(
+                index_documents_method,
+                &merge_strategies,
+                &fields_merge_strategies,
+            ),
             indexer_settings.chunk_compression_type,
             indexer_settings.chunk_compression_level,
             indexer_settings.max_nb_chunks,
@@ -132,7 +235,14 @@ impl<'a, 'i> Transform<'a, 'i> {
 
         Ok(Transform {
             index,
-            fields_ids_map: index.fields_ids_map(wtxn)?,
+            fields_ids_map,
+            non_flattened_fields: index.non_flattened_fields(wtxn)?,
+            facet_hierarchy_separators: index.facet_hierarchy_separators(wtxn)?,
+            merge_strategies,
+            fields_merge_strategies,
+            type_coercions: index.type_coercions(wtxn)?,
+            document_schema: index.document_schema(wtxn)?,
+            ingestion_mapping: index.ingestion_mapping(wtxn)?,
             indexer_settings,
             autogenerate_docids,
             available_documents_ids: AvailableDocumentsIds::from_documents_ids(&documents_ids),
@@ -141,11 +251,43 @@ impl<'a, 'i> Transform<'a, 'i> {
             index_documents_method,
             replaced_documents_ids: RoaringBitmap::new(),
             new_documents_ids: RoaringBitmap::new(),
+            same_fields_documents_ids: RoaringBitmap::new(),
             new_external_documents_ids_builder: FxHashMap::default(),
             documents_count: 0,
+            inserted_versions: FxHashMap::default(),
+            outdated_documents: 0,
+            document_changes: None,
         })
     }
 
+    /// Starts recording the changes made to the documents by the next operations.
+    pub fn record_document_changes(&mut self) {
+        self.document_changes.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the changes made to the documents since the last call, along with the external
+    /// ids of the changed documents.
+    pub fn take_document_changes(&mut self) -> Vec<(String, ChangeOperation)> {
+        self.document_changes.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Returns the number of documents skipped since the last call because they were outdated.
+    pub fn take_outdated_documents(&mut self) -> u64 {
+        std::mem::take(&mut self.outdated_documents)
+    }
+
+    fn record_document_change(&mut self, external_id: &str, operation: ChangeOperation) {
+        if let Some(changes) = &mut self.document_changes {
+            changes.push((external_id.to_string(), operation));
+        }
+    }
+
+    /// Reads the documents of the batch into the sorters and returns the number of read documents.
+    ///
+    /// When `invalid_documents` is given, the documents that can't be indexed, e.g. because they
+    /// would exceed the maximum number of fields or don't conform to the document schema, are
+    /// skipped and their errors are pushed along with their position in the batch, instead of
+    /// rejecting the whole batch.
     #[logging_timer::time]
     pub fn read_documents<R, FP, FA>(
         &mut self,
@@ -153,6 +295,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         wtxn: &mut heed::RwTxn,
         progress_callback: FP,
         should_abort: FA,
+        mut invalid_documents: Option<&mut Vec<(u32, UserError)>>,
     ) -> Result<usize>
     where
         R: Read + Seek,
@@ -163,28 +306,107 @@ impl<'a, 'i> Transform<'a, 'i> {
 
         let (mut cursor, fields_index) = reader.into_cursor_and_fields_index();
         let external_documents_ids = self.index.external_documents_ids();
-        let mapping = create_fields_mapping(&mut self.fields_ids_map, &fields_index)?;
+        let overflow_policy = self.indexer_settings.attribute_overflow_policy;
+        // When skipping the invalid documents, the fields that can't be registered only
+        // reject the documents containing them.
+        let skip_overflowing_documents =
+            invalid_documents.is_some() && overflow_policy == AttributeOverflowPolicy::Error;
+        let mapping_policy = if skip_overflowing_documents {
+            AttributeOverflowPolicy::Ignore
+        } else {
+            overflow_policy
+        };
+        let mapping = create_fields_mapping(
+            &mut self.fields_ids_map,
+            &fields_index,
+            &self.ingestion_mapping,
+            mapping_policy,
+        )?;
+        let nested_mapping = create_nested_fields_mapping(
+            &mut self.fields_ids_map,
+            &fields_index,
+            &self.ingestion_mapping,
+            mapping_policy,
+        )?;
+        let overflowing_fields_ids: HashSet<FieldId> = fields_index
+            .iter()
+            .filter(|_| skip_overflowing_documents)
+            .filter(|(field_id, name)| {
+                let nested_count = nested_mapping.get(*field_id).map_or(0, Vec::len);
+                !mapping.contains_key(*field_id)
+                    || nested_sources(&self.ingestion_mapping, name).count() != nested_count
+            })
+            .map(|(&field_id, _)| field_id)
+            .collect();
+        let fields_ids: HashSet<FieldId> = mapping
+            .values()
+            .chain(nested_mapping.values().flatten().map(|(_, field_id)| field_id))
+            .copied()
+            .collect();
+        for &field_id in &fields_ids {
+            if let Some(name) = self.fields_ids_map.name(field_id) {
+                self.register_merge_strategy(field_id, name);
+            }
+        }
+        // The fields of the batch whose values take precedence over the fields of the
+        // documents already having their new name.
+        let renamed_fields_ids: HashSet<FieldId> = fields_index
+            .iter()
+            .filter(|(_, name)| self.ingestion_mapping.contains_key(name.as_str()))
+            .map(|(&field_id, _)| field_id)
+            .collect();
 
         let primary_key = cursor.primary_key().to_string();
         let primary_key_id =
             self.fields_ids_map.insert(&primary_key).ok_or(UserError::AttributeLimitReached)?;
+        let version_field_id = self.fields_ids_map.id("_version");
+        let fields_coercions = fields_type_coercions(
+            &self.type_coercions,
+            fields_ids
+                .iter()
+                .filter_map(|&field_id| Some((field_id, self.fields_ids_map.name(field_id)?))),
+            &primary_key,
+        );
+        let unchanged_ignored_fields_ids: Vec<FieldId> = self
+            .indexer_settings
+            .unchanged_document_ignored_fields
+            .iter()
+            .filter_map(|name| self.fields_ids_map.id(name))
+            .collect();
 
         let mut obkv_buffer = Vec::new();
+        let mut patched_obkv_buffer = Vec::new();
         let mut document_sorter_value_buffer = Vec::new();
         let mut document_sorter_key_buffer = Vec::new();
         let mut documents_count = 0;
         let mut docid_buffer: Vec<u8> = Vec::new();
         let mut field_buffer: Vec<(u16, Cow<[u8]>)> = Vec::new();
+        let mut renamed_fields_buffer: Vec<(u16, Cow<[u8]>)> = Vec::new();
+        let mut pending_flattening = Vec::new();
+        // An upper bound of the number of fields the pending documents generate when flattened.
+        let mut pending_flattened_fields = 0;
         while let Some(enriched_document) = cursor.next_enriched_document()? {
-            let EnrichedDocument { document, document_id } = enriched_document;
+            let EnrichedDocument { document, document_id, position } = enriched_document;
 
             if should_abort() {
                 return Err(Error::InternalError(InternalError::AbortedIndexation));
             }
 
+            if let Some(invalid_documents) = invalid_documents.as_deref_mut() {
+                if !overflowing_fields_ids.is_empty()
+                    && document
+                        .iter()
+                        .any(|(field_id, _)| overflowing_fields_ids.contains(&field_id))
+                {
+                    invalid_documents.push((position, UserError::AttributeLimitReached));
+                    continue;
+                }
+            }
+
             // drop_and_reuse is called instead of .clear() to communicate to the compiler that field_buffer
             // does not keep references from the cursor between loop iterations
             let mut field_buffer_cache = drop_and_reuse(field_buffer);
+            let mut renamed_fields = drop_and_reuse(renamed_fields_buffer);
             if self.indexer_settings.log_every_n.map_or(false, |len| documents_count % len == 0) {
                 progress_callback(UpdateIndexingStep::RemapDocumentAddition {
                     documents_seen: documents_count,
@@ -201,9 +423,49 @@ impl<'a, 'i> Transform<'a, 'i> {
             }
 
             for (k, v) in document.iter() {
-                let mapped_id =
-                    *mapping.get(&k).ok_or(InternalError::FieldIdMappingMissingEntry { key: k })?;
-                field_buffer_cache.push((mapped_id, Cow::from(v)));
+                let mapped_id = match mapping.get(&k) {
+                    Some(mapped_id) => *mapped_id,
+                    // the field did not fit in the fields ids map
+                    None if overflow_policy == AttributeOverflowPolicy::Ignore => continue,
+                    None => return Err(InternalError::FieldIdMappingMissingEntry { key: k }.into()),
+                };
+                let mut value = Cow::from(v);
+                if let Some(nested_fields) = nested_mapping.get(&k) {
+                    let mut object: Value =
+                        serde_json::from_slice(v).map_err(InternalError::SerdeJson)?;
+                    let mut moved = false;
+                    for (path, field_id) in nested_fields {
+                        if let Some(nested) = take_at_path(&mut object, path) {
+                            let nested =
+                                serde_json::to_vec(&nested).map_err(InternalError::SerdeJson)?;
+                            let nested = coerce_field_value(
+                                &fields_coercions,
+                                *field_id,
+                                Cow::Owned(nested),
+                            )?;
+                            renamed_fields.push((*field_id, nested));
+                            moved = true;
+                        }
+                    }
+                    if moved {
+                        value = Cow::Owned(
+                            serde_json::to_vec(&object).map_err(InternalError::SerdeJson)?,
+                        );
+                    }
+                }
+
+                let value = coerce_field_value(&fields_coercions, mapped_id, value)?;
+                if renamed_fields_ids.contains(&k) {
+                    renamed_fields.push((mapped_id, value));
+                } else {
+                    field_buffer_cache.push((mapped_id, value));
+                }
+            }
+
+            // The renamed fields replace the fields of the document having the same name.
+            for (field_id, value) in renamed_fields.drain(..) {
+                field_buffer_cache.retain(|(id, _)| *id != field_id);
+                field_buffer_cache.push((field_id, value));
             }
 
             // Insertion in a obkv need to be done with keys ordered. For now they are ordered
@@ -217,9 +479,49 @@ impl<'a, 'i> Transform<'a, 'i> {
                 writer.insert(*k, v)?;
             }
 
+            if skip_overflowing_documents
+                && !self.has_room_for_flattened_fields(
+                    &obkv_buffer,
+                    &mut pending_flattening,
+                    &mut pending_flattened_fields,
+                )?
+            {
+                if let Some(invalid_documents) = invalid_documents.as_deref_mut() {
+                    invalid_documents.push((position, UserError::AttributeLimitReached));
+                }
+                field_buffer = drop_and_reuse(field_buffer_cache);
+                renamed_fields_buffer = drop_and_reuse(renamed_fields);
+                docid_buffer.clear();
+                obkv_buffer.clear();
+                continue;
+            }
+
+            if let Some(violation) =
+                self.document_schema_violation(wtxn, external_id, &obkv_buffer)?
+            {
+                let document_id = serde_json::from_slice(external_id.as_bytes())
+                    .unwrap_or_else(|_| Value::from(format!("{document_id:?}")));
+                let user_error = UserError::InvalidDocumentSchema { document_id, violation };
+                match invalid_documents.as_deref_mut() {
+                    Some(invalid_documents) => invalid_documents.push((position, user_error)),
+                    None => return Err(user_error.into()),
+                }
+                field_buffer = drop_and_reuse(field_buffer_cache);
+                renamed_fields_buffer = drop_and_reuse(renamed_fields);
+                docid_buffer.clear();
+                obkv_buffer.clear();
+                continue;
+            }
+
             let mut original_docid = None;
+            let mut added_by_transform = false;
             let docid = match self.new_external_documents_ids_builder.entry((*external_id).into()) {
-                HEntry::Occupied(entry) => *entry.get() as u32,
+                HEntry::Occupied(entry) => {
+                    added_by_transform = true;
+                    let docid = *entry.get() as u32;
+                    self.same_fields_documents_ids.remove(docid);
+                    docid
+                }
                 HEntry::Vacant(entry) => {
                     let docid = match external_documents_ids.get(wtxn, entry.key())? {
                         Some(docid) => {
@@ -241,7 +543,30 @@ impl<'a, 'i> Transform<'a, 'i> {
                 }
             };
 
-            let mut skip_insertion = false;
+            // The new document is compared with the previous document with the same id,
+            // inserted by the transform or stored in the database.
+            let new_version = document_version(version_field_id, KvReaderU16::new(&obkv_buffer))?;
+            let mut previous_version = match added_by_transform {
+                true => self.inserted_versions.get(external_id).copied().flatten(),
+                false => None,
+            };
+            let mut outdated_document = is_outdated_version(previous_version, new_version);
+            let mut skip_insertion = outdated_document;
+
+            // A patch for a new document is applied onto an empty one, its `null` values are
+            // removed instead of being stored.
+            if original_docid.is_none()
+                && !added_by_transform
+                && self.index_documents_method == IndexDocumentsMethod::MergePatchDocuments
+            {
+                merge_patch_obkvs(
+                    KvReaderU16::new(&[]),
+                    KvReaderU16::new(&obkv_buffer),
+                    &mut patched_obkv_buffer,
+                )?;
+                std::mem::swap(&mut obkv_buffer, &mut patched_obkv_buffer);
+            }
+
             if let Some(original_docid) = original_docid {
                 let original_key = original_docid;
                 let base_obkv = self
@@ -254,51 +579,106 @@ impl<'a, 'i> Transform<'a, 'i> {
                         key: None,
                     })?;
 
-                // we check if the two documents are exactly equal. If it's the case we can skip this document entirely
-                if base_obkv == obkv_buffer {
-                    // we're not replacing anything
+                // When the stored document has a higher `_version` than the new one, the new one
+                // is outdated: we keep the stored document untouched and skip the new one.
+                previous_version = document_version(version_field_id, KvReaderU16::new(base_obkv))?;
+                if is_outdated_version(previous_version, new_version) {
                     self.replaced_documents_ids.remove(original_docid);
-                    // and we need to put back the original id as it was before
                     self.new_external_documents_ids_builder.remove(external_id);
                     skip_insertion = true;
+                    outdated_document = true;
                 } else {
-                    // we associate the base document with the new key, everything will get merged later.
-                    let deladd_operation = match self.index_documents_method {
-                        IndexDocumentsMethod::UpdateDocuments => {
-                            DelAddOperation::DeletionAndAddition
-                        }
-                        IndexDocumentsMethod::ReplaceDocuments => DelAddOperation::Deletion,
+                    // When merge patching we compute the full new version of the document right
+                    // away so that nested objects are deep merged before the flattening.
+                    if self.index_documents_method == IndexDocumentsMethod::MergePatchDocuments {
+                        merge_patch_obkvs(
+                            KvReaderU16::new(base_obkv),
+                            KvReaderU16::new(&obkv_buffer),
+                            &mut patched_obkv_buffer,
+                        )?;
+                        std::mem::swap(&mut obkv_buffer, &mut patched_obkv_buffer);
+                    }
+
+                    // the fields with a merge strategy are combined with the stored ones, the
+                    // document can change even when it's equal to the stored one.
+                    let has_merge_strategy = {
+                        let strategies = self.fields_merge_strategies.read().unwrap();
+                        !strategies.is_empty()
+                            && KvReaderU16::new(&obkv_buffer)
+                                .iter()
+                                .any(|(fid, _)| strategies.contains_key(&fid))
                     };
-                    document_sorter_key_buffer.clear();
-                    document_sorter_key_buffer.extend_from_slice(&docid.to_be_bytes());
-                    document_sorter_key_buffer.extend_from_slice(external_id.as_bytes());
-                    document_sorter_value_buffer.clear();
-                    document_sorter_value_buffer.push(Operation::Addition as u8);
-                    into_del_add_obkv(
-                        KvReaderU16::new(base_obkv),
-                        deladd_operation,
-                        &mut document_sorter_value_buffer,
-                    )?;
-                    self.original_sorter
-                        .insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
-                    let base_obkv = KvReader::new(base_obkv);
-                    if let Some(flattened_obkv) = self.flatten_from_fields_ids_map(base_obkv)? {
-                        // we recreate our buffer with the flattened documents
+
+                    // we check if the two documents are equal, without considering the fields the user
+                    // asked us to ignore. If it's the case we can skip this document entirely
+                    if !has_merge_strategy
+                        && obkvs_equal_ignoring_fields(
+                            base_obkv,
+                            &obkv_buffer,
+                            &unchanged_ignored_fields_ids,
+                        )
+                    {
+                        // we're not replacing anything
+                        self.replaced_documents_ids.remove(original_docid);
+                        // and we need to put back the original id as it was before
+                        self.new_external_documents_ids_builder.remove(external_id);
+                        skip_insertion = true;
+                    } else {
+                        // we associate the base document with the new key, everything will get merged later.
+                        let deladd_operation = match self.index_documents_method {
+                            IndexDocumentsMethod::UpdateDocuments => {
+                                DelAddOperation::DeletionAndAddition
+                            }
+                            // the merge patched document already contains all the fields.
+                            IndexDocumentsMethod::ReplaceDocuments
+                            | IndexDocumentsMethod::MergePatchDocuments => {
+                                DelAddOperation::Deletion
+                            }
+                        };
+                        document_sorter_key_buffer.clear();
+                        document_sorter_key_buffer.extend_from_slice(&docid.to_be_bytes());
+                        document_sorter_key_buffer.extend_from_slice(external_id.as_bytes());
                         document_sorter_value_buffer.clear();
                         document_sorter_value_buffer.push(Operation::Addition as u8);
                         into_del_add_obkv(
-                            KvReaderU16::new(&flattened_obkv),
+                            KvReaderU16::new(base_obkv),
                             deladd_operation,
                             &mut document_sorter_value_buffer,
                         )?;
+                        self.original_sorter
+                            .insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
+                        if keeps_the_same_fields(
+                            KvReaderU16::new(base_obkv),
+                            KvReaderU16::new(&obkv_buffer),
+                            self.index_documents_method,
+                        ) {
+                            self.same_fields_documents_ids.insert(docid);
+                        }
+                        pending_flattening.push(PendingFlattening {
+                            docid,
+                            operation: Operation::Addition,
+                            deladd_operation,
+                            obkv: base_obkv.to_vec(),
+                        });
                     }
-                    self.flattened_sorter
-                        .insert(docid.to_be_bytes(), &document_sorter_value_buffer)?;
                 }
             }
 
             if !skip_insertion {
+                // the updated documents keep the previous version when the new one has none
+                let version = match (new_version, self.index_documents_method) {
+                    (Some(version), _) => Some(version),
+                    (None, IndexDocumentsMethod::ReplaceDocuments) => None,
+                    (None, _) => previous_version,
+                };
+                self.inserted_versions.insert((*external_id).into(), version);
                 self.new_documents_ids.insert(docid);
+                let operation = if original_docid.is_some() || added_by_transform {
+                    ChangeOperation::Update
+                } else {
+                    ChangeOperation::Addition
+                };
+                self.record_document_change(external_id, operation);
 
                 document_sorter_key_buffer.clear();
                 document_sorter_key_buffer.extend_from_slice(&docid.to_be_bytes());
@@ -314,29 +694,38 @@ impl<'a, 'i> Transform<'a, 'i> {
                 self.original_sorter
                     .insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
 
-                let flattened_obkv = KvReader::new(&obkv_buffer);
-                if let Some(obkv) = self.flatten_from_fields_ids_map(flattened_obkv)? {
-                    document_sorter_value_buffer.clear();
-                    document_sorter_value_buffer.push(Operation::Addition as u8);
-                    into_del_add_obkv(
-                        KvReaderU16::new(&obkv),
-                        DelAddOperation::Addition,
-                        &mut document_sorter_value_buffer,
-                    )?
-                }
-                self.flattened_sorter.insert(docid.to_be_bytes(), &document_sorter_value_buffer)?;
+                pending_flattening.push(PendingFlattening {
+                    docid,
+                    operation: Operation::Addition,
+                    deladd_operation: DelAddOperation::Addition,
+                    obkv: std::mem::take(&mut obkv_buffer),
+                });
+            }
+
+            if pending_flattening.len() >= FLATTENING_CHUNK_SIZE {
+                self.flatten_pending_documents(&mut pending_flattening)?;
+                pending_flattened_fields = 0;
+            }
+
+            // the outdated documents are not counted as indexed
+            if outdated_document {
+                self.outdated_documents += 1;
+            } else {
+                documents_count += 1;
             }
-            documents_count += 1;
 
             progress_callback(UpdateIndexingStep::RemapDocumentAddition {
                 documents_seen: documents_count,
             });
 
             field_buffer = drop_and_reuse(field_buffer_cache);
+            renamed_fields_buffer = drop_and_reuse(renamed_fields);
             docid_buffer.clear();
             obkv_buffer.clear();
         }
 
+        self.flatten_pending_documents(&mut pending_flattening)?;
+
         progress_callback(UpdateIndexingStep::RemapDocumentAddition {
             documents_seen: documents_count,
         });
@@ -409,15 +798,24 @@ impl<'a, 'i> Transform<'a, 'i> {
                         // 2. It wasn't in it because the document was created by a previous batch and since
                         //    we're removing it there is nothing to do.
                         self.new_documents_ids.remove(docid);
+                        self.same_fields_documents_ids.remove(docid);
                         entry.remove_entry();
                         true
                     }
                     HEntry::Vacant(_) => false,
                 };
 
+            // The documents of the db removed by a previous call are only recorded once.
+            let db_docid = external_documents_ids.get(wtxn, &to_remove)?;
+            let removed_before =
+                db_docid.map_or(false, |docid| self.replaced_documents_ids.contains(docid));
+            if deleted_from_current || (db_docid.is_some() && !removed_before) {
+                self.record_document_change(&to_remove, ChangeOperation::Deletion);
+            }
+
             // If the document was already in the db we mark it as a `to_delete` document.
             // Then we push the document in sorters in deletion mode.
-            let deleted_from_db = match external_documents_ids.get(wtxn, &to_remove)? {
+            let deleted_from_db = match db_docid {
                 Some(docid) => {
                     self.remove_document_from_db(
                         docid,
@@ -440,6 +838,34 @@ impl<'a, 'i> Transform<'a, 'i> {
         Ok(documents_deleted)
     }
 
+    /// Removes the documents of the database matching the given filter, marking them
+    /// as deletions in the sorters exactly like the `remove_documents` method does.
+    ///
+    /// The filter is evaluated against the documents stored in the database, the documents
+    /// inserted by the `read_documents` method of this transform are only removed if they
+    /// replace a matching document of the database.
+    #[logging_timer::time]
+    pub fn remove_documents_from_filter<FA>(
+        &mut self,
+        filter: &Filter,
+        wtxn: &mut heed::RwTxn,
+        should_abort: FA,
+    ) -> Result<usize>
+    where
+        FA: Fn() -> bool + Sync,
+    {
+        puffin::profile_function!();
+
+        let candidates = filter.evaluate(wtxn, self.index)?;
+        let to_remove = self
+            .index
+            .external_id_of(wtxn, candidates.iter())?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        self.remove_documents(to_remove, wtxn, should_abort)
+    }
+
     /// Removes documents from db using their internal document ids.
     ///
     /// # Warning
@@ -472,6 +898,7 @@ impl<'a, 'i> Transform<'a, 'i> {
             if should_abort() {
                 return Err(Error::InternalError(InternalError::AbortedIndexation));
             }
+            self.record_document_change(&external_docid, ChangeOperation::Deletion);
             self.remove_document_from_db(
                 internal_docid,
                 external_docid,
@@ -495,6 +922,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         document_sorter_value_buffer: &mut Vec<u8>,
     ) -> Result<()> {
         self.replaced_documents_ids.insert(internal_docid);
+        self.same_fields_documents_ids.remove(internal_docid);
 
         // fetch the obkv document
         let original_key = internal_docid;
@@ -539,49 +967,199 @@ impl<'a, 'i> Transform<'a, 'i> {
         Ok(())
     }
 
+    /// Validates the document against the document schema of the index, once merged with the
+    /// stored version of the document it updates, so that the required fields don't have to be
+    /// sent again. The versions of the document added earlier in the same batch are ignored.
+    fn document_schema_violation(
+        &self,
+        rtxn: &RoTxn,
+        external_id: &str,
+        obkv: &[u8],
+    ) -> Result<Option<SchemaViolation>> {
+        let Some(document_schema) = &self.document_schema else { return Ok(None) };
+
+        let document = all_obkv_to_json(KvReaderU16::new(obkv), &self.fields_ids_map)?;
+        let stored_docid = match self.index_documents_method {
+            IndexDocumentsMethod::ReplaceDocuments => None,
+            _ if self.new_external_documents_ids_builder.contains_key(external_id) => None,
+            IndexDocumentsMethod::UpdateDocuments | IndexDocumentsMethod::MergePatchDocuments => {
+                // the documents deleted by the transform are not merged with the update
+                self.index
+                    .external_documents_ids()
+                    .get(rtxn, external_id)?
+                    .filter(|docid| !self.replaced_documents_ids.contains(*docid))
+            }
+        };
+        let stored = match stored_docid {
+            Some(docid) => {
+                let obkv = self.index.documents.get(rtxn, &docid)?.ok_or(
+                    InternalError::DatabaseMissingEntry { db_name: db_name::DOCUMENTS, key: None },
+                )?;
+                Some(all_obkv_to_json(obkv, &self.fields_ids_map)?)
+            }
+            None => None,
+        };
+
+        let document = match stored {
+            Some(mut stored) => match self.index_documents_method {
+                IndexDocumentsMethod::MergePatchDocuments => {
+                    let mut stored = Value::Object(stored);
+                    json_merge_patch(&mut stored, Value::Object(document));
+                    stored
+                }
+                _ => {
+                    stored.extend(document);
+                    Value::Object(stored)
+                }
+            },
+            None => Value::Object(document),
+        };
+
+        Ok(document_schema.validate(&document).err())
+    }
+
+    /// Associates the field id to the merge strategy of the field, if any, so that
+    /// the merge function of the sorters can use it.
+    fn register_merge_strategy(&self, field_id: FieldId, name: &str) {
+        if let Some(strategy) = merge_strategy_of(&self.merge_strategies, name) {
+            self.fields_merge_strategies.write().unwrap().insert(field_id, strategy);
+        }
+    }
+
     // Flatten a document from the fields ids map contained in self and insert the new
     // created fields. Returns `None` if the document doesn't need to be flattened.
     fn flatten_from_fields_ids_map(&mut self, obkv: KvReader<FieldId>) -> Result<Option<Vec<u8>>> {
-        if obkv
-            .iter()
-            .all(|(_, value)| !json_depth_checker::should_flatten_from_unchecked_slice(value))
-        {
-            return Ok(None);
+        match flatten_obkv(
+            &self.fields_ids_map,
+            &self.non_flattened_fields,
+            &self.facet_hierarchy_separators,
+            obkv,
+        )? {
+            Some(document) => {
+                let mut buffer = Vec::new();
+                self.insert_flattened_document(document, &mut buffer)?;
+                Ok(Some(buffer))
+            }
+            None => Ok(None),
         }
+    }
 
-        // store the keys and values the original obkv + the flattened json
-        // We first extract all the key+value out of the obkv. If a value is not nested
-        // we keep a reference on its value. If the value is nested we'll get its value
-        // as an owned `Vec<u8>` after flattening it.
-        let mut key_value: Vec<(FieldId, Cow<[u8]>)> = Vec::new();
-
-        // the object we're going to use to store the fields that need to be flattened.
-        let mut doc = serde_json::Map::new();
-
-        // we recreate a json containing only the fields that needs to be flattened.
-        // all the raw values get inserted directly in the `key_value` vec.
-        for (key, value) in obkv.iter() {
-            if json_depth_checker::should_flatten_from_unchecked_slice(value) {
-                let key = self.fields_ids_map.name(key).ok_or(FieldIdMapMissingEntry::FieldId {
-                    field_id: key,
-                    process: "Flatten from fields ids map.",
-                })?;
+    /// Flattens the pending documents in parallel on the indexing thread pool and
+    /// inserts them in the flattened sorter, in the order they were pushed.
+    ///
+    /// The new fields generated by the flattening are registered sequentially in the
+    /// fields ids map once all the documents of the chunk are flattened.
+    fn flatten_pending_documents(&mut self, pending: &mut Vec<PendingFlattening>) -> Result<()> {
+        puffin::profile_function!();
 
-                let value = serde_json::from_slice::<Value>(value)
-                    .map_err(crate::error::InternalError::SerdeJson)?;
-                doc.insert(key.to_string(), value);
-            } else {
-                key_value.push((key, value.into()));
-            }
+        let fields_ids_map = &self.fields_ids_map;
+        let non_flattened_fields = &self.non_flattened_fields;
+        let facet_hierarchy_separators = &self.facet_hierarchy_separators;
+        let flatten = || {
+            pending
+                .par_iter()
+                .map(|document| {
+                    flatten_obkv(
+                        fields_ids_map,
+                        non_flattened_fields,
+                        facet_hierarchy_separators,
+                        KvReader::new(&document.obkv),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let flattened_documents = match self.indexer_settings.thread_pool {
+            Some(ref pool) => pool.install(flatten)?,
+            None => flatten()?,
+        };
+
+        let mut obkv_buffer = Vec::new();
+        let mut value_buffer = Vec::new();
+        for (document, flattened) in pending.iter().zip(flattened_documents) {
+            let obkv = match flattened {
+                Some(flattened) => {
+                    self.insert_flattened_document(flattened, &mut obkv_buffer)?;
+                    &obkv_buffer[..]
+                }
+                None => &document.obkv[..],
+            };
+
+            value_buffer.clear();
+            value_buffer.push(document.operation as u8);
+            into_del_add_obkv(
+                KvReaderU16::new(obkv),
+                document.deladd_operation,
+                &mut value_buffer,
+            )?;
+            self.flattened_sorter.insert(document.docid.to_be_bytes(), &value_buffer)?;
         }
 
-        let flattened = flatten_serde_json::flatten(&doc);
+        pending.clear();
+        Ok(())
+    }
+
+    /// Returns whether the fields generated by the flattening of the document can be registered
+    /// in the fields ids map, along with the ones of the pending documents.
+    ///
+    /// A document can't generate more fields than the bytes of its values, the sum of these
+    /// bounds for the pending documents being `pending_fields`. Only when the fields ids map is
+    /// about to be full, the pending documents are flattened to count the new fields exactly.
+    fn has_room_for_flattened_fields(
+        &mut self,
+        obkv: &[u8],
+        pending: &mut Vec<PendingFlattening>,
+        pending_fields: &mut usize,
+    ) -> Result<bool> {
+        let document_fields: usize = KvReaderU16::new(obkv).iter().map(|(_, v)| v.len()).sum();
+        if *pending_fields + document_fields <= self.fields_ids_map.remaining_capacity() {
+            *pending_fields += document_fields;
+            return Ok(true);
+        }
+
+        self.flatten_pending_documents(pending)?;
+        let new_fields = match flatten_obkv(
+            &self.fields_ids_map,
+            &self.non_flattened_fields,
+            &self.facet_hierarchy_separators,
+            KvReader::new(obkv),
+        )? {
+            Some(document) => document
+                .flattened
+                .iter()
+                .filter(|(key, _)| self.fields_ids_map.id(key).is_none())
+                .map(|(key, _)| key)
+                .collect::<HashSet<_>>()
+                .len(),
+            None => 0,
+        };
+
+        let has_room = new_fields <= self.fields_ids_map.remaining_capacity();
+        *pending_fields = if has_room { new_fields } else { 0 };
+        Ok(has_room)
+    }
+
+    /// Registers the fields generated by the flattening of a document in the fields ids map
+    /// and writes the obkv of the flattened document into the buffer.
+    fn insert_flattened_document(
+        &mut self,
+        document: FlattenedDocument,
+        buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        let FlattenedDocument { mut key_value, flattened } = document;
 
         // Once we have the flattened version we insert all the new generated fields_ids
-        // (if any) in the fields ids map and serialize the value.
-        for (key, value) in flattened.into_iter() {
-            let fid = self.fields_ids_map.insert(&key).ok_or(UserError::AttributeLimitReached)?;
-            let value = serde_json::to_vec(&value).map_err(InternalError::SerdeJson)?;
+        // (if any) in the fields ids map.
+        for (key, value) in flattened {
+            let fid = match self.fields_ids_map.insert(&key) {
+                Some(fid) => fid,
+                None => match self.indexer_settings.attribute_overflow_policy {
+                    AttributeOverflowPolicy::Error => {
+                        return Err(UserError::AttributeLimitReached.into())
+                    }
+                    AttributeOverflowPolicy::Ignore => continue,
+                },
+            };
+            self.register_merge_strategy(fid, &key);
             key_value.push((fid, value.into()));
         }
 
@@ -589,9 +1167,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         // keys will be consecutive.
         key_value.sort_unstable_by_key(|(key, _)| *key);
 
-        let mut buffer = Vec::new();
-        Self::create_obkv_from_key_value(&mut key_value, &mut buffer)?;
-        Ok(Some(buffer))
+        Self::create_obkv_from_key_value(&mut key_value, buffer)
     }
 
     /// Generate an obkv from a slice of key / value sorted by key.
@@ -691,7 +1267,12 @@ impl<'a, 'i> Transform<'a, 'i> {
         // To compute the field distribution we need to;
         // 1. Remove all the deleted documents from the field distribution
         // 2. Add all the new documents to the field distribution
+        //
+        // The changes are accumulated by field id while iterating over the documents and are
+        // applied once on the field distribution, which is indexed by field name, at the end.
+        // The documents replaced by a version with the same fields don't change it.
         let mut field_distribution = self.index.field_distribution(wtxn)?;
+        let mut field_distribution_deltas: FxHashMap<FieldId, i64> = FxHashMap::default();
 
         // Here we are going to do the document count + field distribution + `write_into_stream_writer`
         let mut iter = self.original_sorter.into_stream_merger_iter()?;
@@ -709,55 +1290,50 @@ impl<'a, 'i> Transform<'a, 'i> {
                 total_documents: self.documents_count,
             });
 
-            for (key, value) in KvReader::new(val) {
-                let reader = KvReaderDelAdd::new(value);
-                match (reader.get(DelAdd::Deletion), reader.get(DelAdd::Addition)) {
-                    (None, None) => {}
-                    (None, Some(_)) => {
+            let docid = DocumentId::from_be_bytes(key[..4].try_into().unwrap());
+            if !self.same_fields_documents_ids.contains(docid) {
+                for (key, value) in KvReader::new(val) {
+                    let reader = KvReaderDelAdd::new(value);
+                    match (reader.get(DelAdd::Deletion), reader.get(DelAdd::Addition)) {
+                        (None, None) => {}
                         // New field
-                        let name = self.fields_ids_map.name(key).ok_or(
-                            FieldIdMapMissingEntry::FieldId {
-                                field_id: key,
-                                process: "Computing field distribution in transform.",
-                            },
-                        )?;
-                        *field_distribution.entry(name.to_string()).or_insert(0) += 1;
-                    }
-                    (Some(_), None) => {
+                        (None, Some(_)) => *field_distribution_deltas.entry(key).or_default() += 1,
                         // Field removed
-                        let name = self.fields_ids_map.name(key).ok_or(
-                            FieldIdMapMissingEntry::FieldId {
-                                field_id: key,
-                                process: "Computing field distribution in transform.",
-                            },
-                        )?;
-                        match field_distribution.entry(name.to_string()) {
-                            BEntry::Vacant(_) => { /* Bug? trying to remove a non-existing field */
-                            }
-                            BEntry::Occupied(mut entry) => {
-                                // attempt to remove one
-                                match entry.get_mut().checked_sub(1) {
-                                    Some(0) => {
-                                        entry.remove();
-                                    }
-                                    Some(new_val) => {
-                                        *entry.get_mut() = new_val;
-                                    }
-                                    None => {
-                                        unreachable!("Attempting to remove a field that wasn't in the field distribution")
-                                    }
-                                }
-                            }
+                        (Some(_), None) => *field_distribution_deltas.entry(key).or_default() -= 1,
+                        (Some(_), Some(_)) => {
+                            // Value change, no field distribution change
                         }
                     }
-                    (Some(_), Some(_)) => {
-                        // Value change, no field distribution change
-                    }
                 }
             }
             writer.insert(key, val)?;
         }
 
+        for (field_id, delta) in field_distribution_deltas {
+            let name =
+                self.fields_ids_map.name(field_id).ok_or(FieldIdMapMissingEntry::FieldId {
+                    field_id,
+                    process: "Computing field distribution in transform.",
+                })?;
+            match field_distribution.entry(name.to_string()) {
+                BEntry::Vacant(entry) => {
+                    // A negative delta is a bug? trying to remove a non-existing field
+                    if delta > 0 {
+                        entry.insert(delta as u64);
+                    }
+                }
+                BEntry::Occupied(mut entry) => {
+                    // the field is removed when more documents lose it than it was counted in
+                    match (*entry.get() as i64).saturating_add(delta) {
+                        new_val if new_val > 0 => *entry.get_mut() = new_val as u64,
+                        _ => {
+                            entry.remove();
+                        }
+                    }
+                }
+            }
+        }
+
         let mut original_documents = writer.into_inner()?;
         // We then extract the file and reset the seek to be able to read it again.
         original_documents.rewind()?;
@@ -803,8 +1379,12 @@ impl<'a, 'i> Transform<'a, 'i> {
         })
     }
 
-    /// Clear all databases. Returns a `TransformOutput` with a file that contains the documents
-    /// of the index with the attributes reordered accordingly to the `FieldsIdsMap` given as argument.
+    /// Clear the databases of the `scope`. Returns a `TransformOutput` with a file that contains
+    /// the documents of the index with the attributes reordered accordingly to the `FieldsIdsMap`
+    /// given as argument.
+    ///
+    /// The original documents are only part of the output when they must be written back or
+    /// when the vectors are reindexed, the flattened ones when another database is reindexed.
     ///
     // TODO this can be done in parallel by using the rayon `ThreadPool`.
     pub fn prepare_for_documents_reindexing(
@@ -812,6 +1392,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         wtxn: &mut heed::RwTxn<'i>,
         old_fields_ids_map: FieldsIdsMap,
         mut new_fields_ids_map: FieldsIdsMap,
+        scope: ReindexScope,
     ) -> Result<TransformOutput> {
         // There already has been a document addition, the primary key should be set by now.
         let primary_key = self
@@ -847,6 +1428,9 @@ impl<'a, 'i> Transform<'a, 'i> {
             self.indexer_settings.max_memory.map(|mem| mem / 2),
         );
 
+        let send_original = scope.documents || scope.vectors;
+        let send_flattened = scope.documents || scope.facets || scope.words || scope.proximities;
+
         let mut obkv_buffer = Vec::new();
         let mut document_sorter_key_buffer = Vec::new();
         let mut document_sorter_value_buffer = Vec::new();
@@ -868,16 +1452,23 @@ impl<'a, 'i> Transform<'a, 'i> {
 
             let buffer = obkv_writer.into_inner()?;
 
-            document_sorter_key_buffer.clear();
-            document_sorter_key_buffer.extend_from_slice(&docid.to_be_bytes());
-            document_sorter_key_buffer.extend_from_slice(external_id.as_bytes());
-            document_sorter_value_buffer.clear();
-            into_del_add_obkv(
-                KvReaderU16::new(buffer),
-                DelAddOperation::Addition,
-                &mut document_sorter_value_buffer,
-            )?;
-            original_sorter.insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
+            if send_original {
+                document_sorter_key_buffer.clear();
+                document_sorter_key_buffer.extend_from_slice(&docid.to_be_bytes());
+                document_sorter_key_buffer.extend_from_slice(external_id.as_bytes());
+                document_sorter_value_buffer.clear();
+                into_del_add_obkv(
+                    KvReaderU16::new(buffer),
+                    DelAddOperation::Addition,
+                    &mut document_sorter_value_buffer,
+                )?;
+                original_sorter
+                    .insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
+            }
+
+            if !send_flattened {
+                continue;
+            }
 
             // Once we have the document. We're going to flatten it
             // and insert it in the flattened sorter.
@@ -894,7 +1485,11 @@ impl<'a, 'i> Transform<'a, 'i> {
                 doc.insert(key.to_string(), value);
             }
 
-            let flattened = flatten_serde_json::flatten(&doc);
+            let mut flattened = flatten_serde_json::flatten_except(&doc, |key| {
+                key == GEOJSON_FIELD || self.non_flattened_fields.contains(key)
+            });
+            insert_facet_hierarchy_levels(&self.facet_hierarchy_separators, &mut flattened);
+            insert_geojson_bounding_box(&doc, &mut flattened);
 
             // Once we have the flattened version we can convert it back to obkv and
             // insert all the new generated fields_ids (if any) in the fields ids map.
@@ -907,8 +1502,15 @@ impl<'a, 'i> Transform<'a, 'i> {
             });
 
             for (key, value) in flattened {
-                let fid =
-                    new_fields_ids_map.insert(&key).ok_or(UserError::AttributeLimitReached)?;
+                let fid = match new_fields_ids_map.insert(&key) {
+                    Some(fid) => fid,
+                    None => match self.indexer_settings.attribute_overflow_policy {
+                        AttributeOverflowPolicy::Error => {
+                            return Err(UserError::AttributeLimitReached.into())
+                        }
+                        AttributeOverflowPolicy::Ignore => continue,
+                    },
+                };
                 let value = serde_json::to_vec(&value).map_err(InternalError::SerdeJson)?;
                 writer.insert(fid, &value)?;
             }
@@ -945,13 +1547,335 @@ impl<'a, 'i> Transform<'a, 'i> {
         let new_facets = output.compute_real_facets(wtxn, self.index)?;
         self.index.put_faceted_fields(wtxn, &new_facets)?;
 
-        // We clear the full database (words-fst, documents ids and documents content).
-        ClearDocuments::new(wtxn, self.index).execute()?;
+        // We clear the databases that will be rebuilt, the full database when the
+        // documents are written back (words-fst, documents ids and documents content).
+        clear_reindexed_databases(wtxn, self.index, scope)?;
 
         Ok(output)
     }
 }
 
+/// Applies the `patch` obkv onto the `base` obkv following the JSON Merge Patch semantics (RFC 7396)
+/// and writes the resulting obkv into the `output` buffer.
+///
+/// Nested objects are deep merged and a `null` value removes the field from the document.
+fn merge_patch_obkvs(base: KvReaderU16, patch: KvReaderU16, output: &mut Vec<u8>) -> Result<()> {
+    use itertools::EitherOrBoth::{Both, Left, Right};
+
+    output.clear();
+    let mut writer = KvWriter::new(output);
+    for eob in itertools::merge_join_by(base.iter(), patch.iter(), |(b, _), (p, _)| b.cmp(p)) {
+        let (key, base, patch) = match eob {
+            Left((key, value)) => {
+                writer.insert(key, value)?;
+                continue;
+            }
+            Right((key, patch)) => (key, None, patch),
+            Both((key, base), (_, patch)) => (key, Some(base), patch),
+        };
+
+        let patch: Value = serde_json::from_slice(patch).map_err(InternalError::SerdeJson)?;
+        // a null patch removes the field entirely.
+        if patch.is_null() {
+            continue;
+        }
+        let mut target = match base {
+            Some(base) => serde_json::from_slice(base).map_err(InternalError::SerdeJson)?,
+            None => Value::Null,
+        };
+        json_merge_patch(&mut target, patch);
+        let target = serde_json::to_vec(&target).map_err(InternalError::SerdeJson)?;
+        writer.insert(key, target)?;
+    }
+
+    writer.finish()?;
+    Ok(())
+}
+
+/// A document whose flattening must be done before being inserted in the flattened sorter.
+struct PendingFlattening {
+    docid: u32,
+    operation: Operation,
+    deladd_operation: DelAddOperation,
+    obkv: Vec<u8>,
+}
+
+/// A flattened document whose generated fields are not yet part of the fields ids map.
+struct FlattenedDocument<'a> {
+    /// The fields that didn't need to be flattened.
+    key_value: Vec<(FieldId, Cow<'a, [u8]>)>,
+    /// The fields generated by the flattening along with their serialized value.
+    flattened: Vec<(String, Vec<u8>)>,
+}
+
+/// Flattens a document without modifying the fields ids map, so that it can be done in parallel.
+/// Returns `None` if the document doesn't need to be flattened.
+///
+/// The values of the hierarchical facets are also split into their `{field}.lvl{n}` fields.
+fn flatten_obkv<'a>(
+    fields_ids_map: &FieldsIdsMap,
+    non_flattened_fields: &HashSet<String>,
+    facet_hierarchy_separators: &BTreeMap<String, String>,
+    obkv: KvReader<'a, FieldId>,
+) -> Result<Option<FlattenedDocument<'a>>> {
+    let is_hierarchical = |field_id: FieldId| {
+        !facet_hierarchy_separators.is_empty()
+            && fields_ids_map
+                .name(field_id)
+                .map_or(false, |name| facet_hierarchy_separators.contains_key(name))
+    };
+    let should_flatten = |field_id: FieldId, value: &[u8]| {
+        json_depth_checker::should_flatten_from_unchecked_slice(value) || is_hierarchical(field_id)
+    };
+
+    if obkv.iter().all(|(key, value)| !should_flatten(key, value)) {
+        return Ok(None);
+    }
+
+    // store the keys and values the original obkv + the flattened json
+    // We first extract all the key+value out of the obkv. If a value is not nested
+    // we keep a reference on its value. If the value is nested we'll get its value
+    // as an owned `Vec<u8>` after flattening it.
+    let mut key_value: Vec<(FieldId, Cow<[u8]>)> = Vec::new();
+
+    // the object we're going to use to store the fields that need to be flattened.
+    let mut doc = serde_json::Map::new();
+
+    // we recreate a json containing only the fields that needs to be flattened.
+    // all the raw values get inserted directly in the `key_value` vec.
+    for (key, value) in obkv.iter() {
+        if should_flatten(key, value) {
+            let key = fields_ids_map.name(key).ok_or(FieldIdMapMissingEntry::FieldId {
+                field_id: key,
+                process: "Flatten from fields ids map.",
+            })?;
+
+            let value = serde_json::from_slice::<Value>(value)
+                .map_err(crate::error::InternalError::SerdeJson)?;
+            doc.insert(key.to_string(), value);
+        } else {
+            key_value.push((key, value.into()));
+        }
+    }
+
+    let mut flattened = flatten_serde_json::flatten_except(&doc, |key| {
+        key == GEOJSON_FIELD || non_flattened_fields.contains(key)
+    });
+    insert_facet_hierarchy_levels(facet_hierarchy_separators, &mut flattened);
+    insert_geojson_bounding_box(&doc, &mut flattened);
+    let flattened = flattened
+        .into_iter()
+        .map(|(key, value)| {
+            let value = serde_json::to_vec(&value).map_err(InternalError::SerdeJson)?;
+            Ok((key, value))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Some(FlattenedDocument { key_value, flattened }))
+}
+
+/// Inserts the bounding box of the shape stored in the `_geojson` field of a document
+/// in the `_geojson._minLat`, `_geojson._minLng`, `_geojson._maxLat` and `_geojson._maxLng` fields.
+fn insert_geojson_bounding_box(
+    document: &serde_json::Map<String, Value>,
+    flattened: &mut serde_json::Map<String, Value>,
+) {
+    if let Some(shape) = document.get(GEOJSON_FIELD).and_then(GeoShape::from_geojson) {
+        for (field, value) in GEOJSON_BOUNDING_BOX_FIELDS.iter().zip(shape.bounding_box()) {
+            flattened.insert(field.to_string(), Value::from(value));
+        }
+    }
+}
+
+/// Splits the values of the hierarchical facets of a flattened document on their separator
+/// and inserts the `n`th level of the values in the `{field}.lvl{n}` field.
+///
+/// `"Books > Scifi > Space opera"` is indexed as `"Books"` in the `.lvl0` field,
+/// `"Scifi"` in the `.lvl1` field and `"Space opera"` in the `.lvl2` field.
+fn insert_facet_hierarchy_levels(
+    facet_hierarchy_separators: &BTreeMap<String, String>,
+    document: &mut serde_json::Map<String, Value>,
+) {
+    for (field, separator) in facet_hierarchy_separators {
+        let values = match document.get(field) {
+            Some(Value::String(value)) => vec![value.as_str()],
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+            _ => continue,
+        };
+
+        let mut levels: Vec<Vec<Value>> = Vec::new();
+        for value in values {
+            let components = value.split(separator.as_str()).map(str::trim);
+            for (level, component) in components.filter(|c| !c.is_empty()).enumerate() {
+                if levels.len() <= level {
+                    levels.push(Vec::new());
+                }
+                let component = Value::from(component);
+                if !levels[level].contains(&component) {
+                    levels[level].push(component);
+                }
+            }
+        }
+
+        for (level, mut values) in levels.into_iter().enumerate() {
+            let value =
+                if values.len() == 1 { values.pop().unwrap() } else { Value::Array(values) };
+            document.insert(format!("{field}.lvl{level}"), value);
+        }
+    }
+}
+
+/// Returns the merge function used by the sorters of the transform, the one for when
+/// two or more documents with the same user id must be merged or fully replaced.
+fn documents_merge_function(
+    index_documents_method: IndexDocumentsMethod,
+    merge_strategies: &BTreeMap<String, MergeStrategy>,
+    fields_merge_strategies: &Arc<RwLock<HashMap<FieldId, MergeStrategy>>>,
+) -> BoxedMergeFn {
+    match index_documents_method {
+        IndexDocumentsMethod::ReplaceDocuments => {
+            boxed_merge_fn(obkvs_keep_last_addition_merge_deletions)
+        }
+        IndexDocumentsMethod::UpdateDocuments if merge_strategies.is_empty() => {
+            boxed_merge_fn(obkvs_merge_additions_and_deletions)
+        }
+        IndexDocumentsMethod::UpdateDocuments => {
+            let fields_merge_strategies = fields_merge_strategies.clone();
+            boxed_merge_fn(move |key, obkvs| {
+                let fields_merge_strategies = fields_merge_strategies.read().unwrap();
+                obkvs_merge_additions_with_strategies(key, obkvs, &fields_merge_strategies)
+            })
+        }
+        IndexDocumentsMethod::MergePatchDocuments => {
+            boxed_merge_fn(obkvs_merge_patch_additions_and_deletions)
+        }
+    }
+}
+
+/// Returns the type coercions applying to the fields of the index receiving the values of
+/// the batch, along with the path of the nested values they convert, e.g. `price` for the
+/// `variants.price` rule and the `variants` field. The primary key is never converted.
+fn fields_type_coercions<'a>(
+    type_coercions: &BTreeMap<String, TypeCoercion>,
+    fields: impl IntoIterator<Item = (FieldId, &'a str)>,
+    primary_key: &str,
+) -> HashMap<FieldId, Vec<(String, TypeCoercion)>> {
+    if type_coercions.is_empty() {
+        return HashMap::new();
+    }
+
+    fields
+        .into_iter()
+        .filter(|(_, name)| *name != primary_key)
+        .filter_map(|(field_id, name)| {
+            let coercions: Vec<_> = type_coercions
+                .iter()
+                .filter_map(|(rule, &coercion)| {
+                    if rule == name {
+                        Some((String::new(), coercion))
+                    } else {
+                        let path = rule.strip_prefix(name)?.strip_prefix('.')?;
+                        Some((path.to_string(), coercion))
+                    }
+                })
+                .collect();
+            (!coercions.is_empty()).then_some((field_id, coercions))
+        })
+        .collect()
+}
+
+/// Converts the value of the field with its type coercions, if it has any.
+fn coerce_field_value<'a>(
+    fields_coercions: &HashMap<FieldId, Vec<(String, TypeCoercion)>>,
+    field_id: FieldId,
+    value: Cow<'a, [u8]>,
+) -> Result<Cow<'a, [u8]>> {
+    let Some(coercions) = fields_coercions.get(&field_id) else {
+        return Ok(value);
+    };
+
+    let mut json: Value = serde_json::from_slice(&value).map_err(InternalError::SerdeJson)?;
+    let changed = coercions.iter().fold(false, |changed, (path, coercion)| {
+        coercion.coerce_at_path(&mut json, path) || changed
+    });
+    if changed {
+        Ok(Cow::Owned(serde_json::to_vec(&json).map_err(InternalError::SerdeJson)?))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Returns the merge strategy of a field. The nested fields generated by the flattening
+/// inherit the strategy of their closest parent that uses an array strategy.
+fn merge_strategy_of(
+    merge_strategies: &BTreeMap<String, MergeStrategy>,
+    name: &str,
+) -> Option<MergeStrategy> {
+    if merge_strategies.is_empty() {
+        return None;
+    }
+
+    if let Some(strategy) = merge_strategies.get(name) {
+        return Some(*strategy);
+    }
+
+    name.rmatch_indices('.')
+        .filter_map(|(index, _)| merge_strategies.get(&name[..index]))
+        .find(|strategy| strategy.applies_to_nested_fields())
+        .copied()
+}
+
+/// Returns `true` when the `new` version of a document, once merged with the `base` stored one
+/// following the indexing method, has exactly the fields of the stored one.
+fn keeps_the_same_fields(
+    base: KvReaderU16,
+    new: KvReaderU16,
+    index_documents_method: IndexDocumentsMethod,
+) -> bool {
+    let base = base.iter().map(|(fid, _)| fid);
+    let new = new.iter().map(|(fid, _)| fid);
+    match index_documents_method {
+        // the fields of the stored document are kept, the new ones must be a subset of them.
+        IndexDocumentsMethod::UpdateDocuments => itertools::merge_join_by(base, new, Ord::cmp)
+            .all(|eob| !matches!(eob, itertools::EitherOrBoth::Right(_))),
+        // the merge patched document already contains all the fields.
+        IndexDocumentsMethod::ReplaceDocuments | IndexDocumentsMethod::MergePatchDocuments => {
+            base.eq(new)
+        }
+    }
+}
+
+/// Returns `true` if both obkvs contain the same fields with the same values,
+/// without considering the fields listed in `ignored_fields_ids`.
+fn obkvs_equal_ignoring_fields(lhs: &[u8], rhs: &[u8], ignored_fields_ids: &[FieldId]) -> bool {
+    if ignored_fields_ids.is_empty() {
+        return lhs == rhs;
+    }
+
+    let lhs = KvReaderU16::new(lhs).iter().filter(|(fid, _)| !ignored_fields_ids.contains(fid));
+    let rhs = KvReaderU16::new(rhs).iter().filter(|(fid, _)| !ignored_fields_ids.contains(fid));
+    lhs.eq(rhs)
+}
+
+/// Returns the `_version` of a document, if any.
+fn document_version(version_field_id: Option<FieldId>, obkv: KvReaderU16) -> Result<Option<u64>> {
+    match version_field_id.and_then(|field_id| obkv.get(field_id)) {
+        Some(version) => {
+            let version: Value =
+                serde_json::from_slice(version).map_err(InternalError::SerdeJson)?;
+            Ok(version.as_u64())
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns `true` if the previous document has a higher `_version` than the new document.
+///
+/// The documents that don't have a `_version` field are never considered outdated.
+fn is_outdated_version(previous_version: Option<u64>, new_version: Option<u64>) -> bool {
+    matches!((previous_version, new_version), (Some(previous), Some(new)) if previous > new)
+}
+
 /// Drops all the value of type `U` in vec, and reuses the allocation to create a `Vec<T>`.
 ///
 /// The size and alignment of T and U must match.
@@ -1090,4 +2014,33 @@ mod test {
         .unwrap();
         assert_eq!(*ret, del_add_doc_0);
     }
+
+    #[test]
+    fn facet_hierarchy_levels() {
+        let separators = maplit::btreemap! { "category".to_string() => " > ".to_string() };
+        let serde_json::Value::Object(mut document) = serde_json::json!({
+            "id": 0,
+            "category": ["Books > Scifi > Space opera", "Books > Scifi", " > Movies"],
+        }) else {
+            unreachable!()
+        };
+
+        insert_facet_hierarchy_levels(&separators, &mut document);
+        insta::assert_json_snapshot!(document, @r###"
+        {
+          "id": 0,
+          "category": [
+            "Books > Scifi > Space opera",
+            "Books > Scifi",
+            " > Movies"
+          ],
+          "category.lvl0": [
+            "Books",
+            "Movies"
+          ],
+          "category.lvl1": "Scifi",
+          "category.lvl2": "Space opera"
+        }
+        "###);
+    }
 }
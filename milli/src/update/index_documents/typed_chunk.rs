@@ -22,6 +22,8 @@ use crate::index::db_name::DOCUMENTS;
 use crate::update::del_add::{deladd_serialize_add_side, DelAdd, KvReaderDelAdd};
 use crate::update::facet::FacetsUpdate;
 use crate::update::index_documents::helpers::{as_cloneable_grenad, try_split_array_at};
+use crate::vector::error::EmbedError;
+use crate::vector::Distance;
 use crate::{
     lat_lng_to_xyz, DocumentId, FieldId, GeoPoint, Index, InternalError, Result, SerializationError,
 };
@@ -49,6 +51,10 @@ pub(crate) enum TypedChunk {
         embeddings: Option<grenad::Reader<BufReader<File>>>,
         expected_dimension: usize,
         manual_vectors: grenad::Reader<BufReader<File>>,
+        prompt_hashes: grenad::Reader<BufReader<File>>,
+        // the documents whose embedding failed within the tolerance of the request policy
+        failed_docids: RoaringBitmap,
+        embedding_error: Option<EmbedError>,
         embedder_name: String,
     },
     ScriptLanguageDocids(HashMap<(Script, Language), (RoaringBitmap, RoaringBitmap)>),
@@ -103,8 +109,8 @@ impl TypedChunk {
             TypedChunk::GeoPoints(grenad) => {
                 format!("GeoPoints {{ number_of_entries: {} }}", grenad.len())
             }
-            TypedChunk::VectorPoints{ remove_vectors, manual_vectors, embeddings, expected_dimension, embedder_name } => {
-                format!("VectorPoints {{ remove_vectors: {}, manual_vectors: {}, embeddings: {}, dimension: {}, embedder_name: {} }}", remove_vectors.len(), manual_vectors.len(), embeddings.as_ref().map(|e| e.len()).unwrap_or_default(), expected_dimension, embedder_name)
+            TypedChunk::VectorPoints{ remove_vectors, manual_vectors, embeddings, expected_dimension, prompt_hashes, failed_docids, embedder_name, .. } => {
+                format!("VectorPoints {{ remove_vectors: {}, manual_vectors: {}, embeddings: {}, dimension: {}, prompt_hashes: {}, failed_documents: {}, embedder_name: {} }}", remove_vectors.len(), manual_vectors.len(), embeddings.as_ref().map(|e| e.len()).unwrap_or_default(), expected_dimension, prompt_hashes.len(), failed_docids.len(), embedder_name)
             }
             TypedChunk::ScriptLanguageDocids(sl_map) => {
                 format!("ScriptLanguageDocids {{ number_of_entries: {} }}", sl_map.len())
@@ -363,122 +369,60 @@ pub(crate) fn write_typed_chunk_into_index(
             manual_vectors,
             embeddings,
             expected_dimension,
+            prompt_hashes,
+            failed_docids,
+            embedding_error: _,
             embedder_name,
         } => {
             let embedder_index = index.embedder_category_id.get(wtxn, &embedder_name)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: "embedder_category_id", key: None },
             )?;
-            let writer_index = (embedder_index as u16) << 8;
-            // FIXME: allow customizing distance
-            let writers: std::result::Result<Vec<_>, _> = (0..=u8::MAX)
-                .map(|k| {
-                    arroy::Writer::prepare(
-                        wtxn,
-                        index.vector_arroy,
-                        writer_index | (k as u16),
-                        expected_dimension,
-                    )
-                })
-                .collect();
-            let writers = writers?;
-
-            // remove vectors for docids we want them removed
-            let mut cursor = remove_vectors.into_cursor()?;
-            while let Some((key, _)) = cursor.move_on_next()? {
-                let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
-
-                for writer in &writers {
-                    // Uses invariant: vectors are packed in the first writers.
-                    if !writer.del_item(wtxn, docid)? {
-                        break;
-                    }
-                }
-            }
-
-            // add generated embeddings
-            if let Some(embeddings) = embeddings {
-                let mut cursor = embeddings.into_cursor()?;
-                while let Some((key, value)) = cursor.move_on_next()? {
-                    let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
-                    let data = pod_collect_to_vec(value);
-                    // it is a code error to have embeddings and not expected_dimension
-                    let embeddings =
-                        crate::vector::Embeddings::from_inner(data, expected_dimension)
-                            // code error if we somehow got the wrong dimension
-                            .unwrap();
-
-                    if embeddings.embedding_count() > u8::MAX.into() {
-                        let external_docid = if let Ok(Some(Ok(index))) = index
-                            .external_id_of(wtxn, std::iter::once(docid))
-                            .map(|it| it.into_iter().next())
-                        {
-                            index
-                        } else {
-                            format!("internal docid={docid}")
-                        };
-                        return Err(crate::Error::UserError(crate::UserError::TooManyVectors(
-                            external_docid,
-                            embeddings.embedding_count(),
-                        )));
-                    }
-                    for (embedding, writer) in embeddings.iter().zip(&writers) {
-                        writer.add_item(wtxn, docid, embedding)?;
-                    }
-                }
-            }
-
-            // perform the manual diff
-            let mut cursor = manual_vectors.into_cursor()?;
-            while let Some((key, value)) = cursor.move_on_next()? {
-                // convert the key back to a u32 (4 bytes)
-                let (left, _index) = try_split_array_at(key).unwrap();
-                let docid = DocumentId::from_be_bytes(left);
-
-                let vector_deladd_obkv = KvReaderDelAdd::new(value);
-                if let Some(value) = vector_deladd_obkv.get(DelAdd::Deletion) {
-                    let vector: Vec<f32> = pod_collect_to_vec(value);
-
-                    let mut deleted_index = None;
-                    for (index, writer) in writers.iter().enumerate() {
-                        let Some(candidate) = writer.item_vector(wtxn, docid)? else {
-                            // uses invariant: vectors are packed in the first writers.
-                            break;
-                        };
-                        if candidate == vector {
-                            writer.del_item(wtxn, docid)?;
-                            deleted_index = Some(index);
-                        }
-                    }
-
-                    // 🥲 enforce invariant: vectors are packed in the first writers.
-                    if let Some(deleted_index) = deleted_index {
-                        let mut last_index_with_a_vector = None;
-                        for (index, writer) in writers.iter().enumerate().skip(deleted_index) {
-                            let Some(candidate) = writer.item_vector(wtxn, docid)? else {
-                                break;
-                            };
-                            last_index_with_a_vector = Some((index, candidate));
-                        }
-                        if let Some((last_index, vector)) = last_index_with_a_vector {
-                            // unwrap: computed the index from the list of writers
-                            let writer = writers.get(last_index).unwrap();
-                            writer.del_item(wtxn, docid)?;
-                            writers.get(deleted_index).unwrap().add_item(wtxn, docid, &vector)?;
-                        }
-                    }
-                }
-
-                if let Some(value) = vector_deladd_obkv.get(DelAdd::Addition) {
-                    let vector = pod_collect_to_vec(value);
-
-                    // overflow was detected during vector extraction.
-                    for writer in &writers {
-                        if !writer.contains_item(wtxn, docid)? {
-                            writer.add_item(wtxn, docid, &vector)?;
-                            break;
-                        }
-                    }
-                }
+            let config = index
+                .embedding_configs(wtxn)?
+                .into_iter()
+                .find_map(|(name, config)| (name == embedder_name).then_some(config))
+                .unwrap_or_default();
+            let binary_quantized = config.binary_quantized;
+            match config.distance {
+                Distance::Cosine => write_vector_points(
+                    wtxn,
+                    index,
+                    index.vector_arroy,
+                    embedder_index,
+                    expected_dimension,
+                    binary_quantized,
+                    remove_vectors,
+                    embeddings,
+                    manual_vectors,
+                    prompt_hashes,
+                    &failed_docids,
+                )?,
+                Distance::DotProduct => write_vector_points(
+                    wtxn,
+                    index,
+                    index.vector_arroy_dot_product,
+                    embedder_index,
+                    expected_dimension,
+                    binary_quantized,
+                    remove_vectors,
+                    embeddings,
+                    manual_vectors,
+                    prompt_hashes,
+                    &failed_docids,
+                )?,
+                Distance::Euclidean => write_vector_points(
+                    wtxn,
+                    index,
+                    index.vector_arroy_euclidean,
+                    embedder_index,
+                    expected_dimension,
+                    binary_quantized,
+                    remove_vectors,
+                    embeddings,
+                    manual_vectors,
+                    prompt_hashes,
+                    &failed_docids,
+                )?,
             }
 
             log::debug!("Finished vector chunk for {}", embedder_name);
@@ -509,6 +453,156 @@ pub(crate) fn write_typed_chunk_into_index(
     Ok((RoaringBitmap::new(), is_merged_database))
 }
 
+/// Writes the vectors of an embedder into the arroy database of its distance.
+#[allow(clippy::too_many_arguments)]
+fn write_vector_points<D: arroy::Distance>(
+    wtxn: &mut RwTxn,
+    index: &Index,
+    database: arroy::Database<D>,
+    embedder_index: u8,
+    expected_dimension: usize,
+    binary_quantized: bool,
+    remove_vectors: grenad::Reader<BufReader<File>>,
+    embeddings: Option<grenad::Reader<BufReader<File>>>,
+    manual_vectors: grenad::Reader<BufReader<File>>,
+    prompt_hashes: grenad::Reader<BufReader<File>>,
+    failed_docids: &RoaringBitmap,
+) -> Result<()> {
+    let writer_index = (embedder_index as u16) << 8;
+    let writers: std::result::Result<Vec<_>, _> = (0..=u8::MAX)
+        .map(|k| {
+            arroy::Writer::prepare(wtxn, database, writer_index | (k as u16), expected_dimension)
+        })
+        .collect();
+    let writers = writers?;
+    let mut updated_docids = RoaringBitmap::new();
+
+    // remove vectors for docids we want them removed
+    let mut cursor = remove_vectors.into_cursor()?;
+    while let Some((key, _)) = cursor.move_on_next()? {
+        let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
+        updated_docids.insert(docid);
+        index.vector_prompt_hashes.delete(wtxn, &(embedder_index as u16, docid))?;
+
+        for writer in &writers {
+            // Uses invariant: vectors are packed in the first writers.
+            if !writer.del_item(wtxn, docid)? {
+                break;
+            }
+        }
+    }
+
+    // add generated embeddings
+    if let Some(embeddings) = embeddings {
+        let mut cursor = embeddings.into_cursor()?;
+        while let Some((key, value)) = cursor.move_on_next()? {
+            let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
+            updated_docids.insert(docid);
+            let data = pod_collect_to_vec(value);
+            // it is a code error to have embeddings and not expected_dimension
+            let embeddings = crate::vector::Embeddings::from_inner(data, expected_dimension)
+                // code error if we somehow got the wrong dimension
+                .unwrap();
+
+            if embeddings.embedding_count() > u8::MAX.into() {
+                let external_docid = if let Ok(Some(Ok(index))) = index
+                    .external_id_of(wtxn, std::iter::once(docid))
+                    .map(|it| it.into_iter().next())
+                {
+                    index
+                } else {
+                    format!("internal docid={docid}")
+                };
+                return Err(crate::Error::UserError(crate::UserError::TooManyVectors(
+                    external_docid,
+                    embeddings.embedding_count(),
+                )));
+            }
+            for (embedding, writer) in embeddings.iter().zip(&writers) {
+                writer.add_item(wtxn, docid, embedding)?;
+            }
+        }
+    }
+
+    // perform the manual diff
+    let mut cursor = manual_vectors.into_cursor()?;
+    while let Some((key, value)) = cursor.move_on_next()? {
+        // convert the key back to a u32 (4 bytes)
+        let (left, _index) = try_split_array_at(key).unwrap();
+        let docid = DocumentId::from_be_bytes(left);
+        updated_docids.insert(docid);
+
+        let vector_deladd_obkv = KvReaderDelAdd::new(value);
+        if let Some(value) = vector_deladd_obkv.get(DelAdd::Deletion) {
+            let vector: Vec<f32> = pod_collect_to_vec(value);
+
+            let mut deleted_index = None;
+            for (index, writer) in writers.iter().enumerate() {
+                let Some(candidate) = writer.item_vector(wtxn, docid)? else {
+                    // uses invariant: vectors are packed in the first writers.
+                    break;
+                };
+                if candidate == vector {
+                    writer.del_item(wtxn, docid)?;
+                    deleted_index = Some(index);
+                }
+            }
+
+            // 🥲 enforce invariant: vectors are packed in the first writers.
+            if let Some(deleted_index) = deleted_index {
+                let mut last_index_with_a_vector = None;
+                for (index, writer) in writers.iter().enumerate().skip(deleted_index) {
+                    let Some(candidate) = writer.item_vector(wtxn, docid)? else {
+                        break;
+                    };
+                    last_index_with_a_vector = Some((index, candidate));
+                }
+                if let Some((last_index, vector)) = last_index_with_a_vector {
+                    // unwrap: computed the index from the list of writers
+                    let writer = writers.get(last_index).unwrap();
+                    writer.del_item(wtxn, docid)?;
+                    writers.get(deleted_index).unwrap().add_item(wtxn, docid, &vector)?;
+                }
+            }
+        }
+
+        if let Some(value) = vector_deladd_obkv.get(DelAdd::Addition) {
+            let vector = pod_collect_to_vec(value);
+
+            // overflow was detected during vector extraction.
+            for writer in &writers {
+                if !writer.contains_item(wtxn, docid)? {
+                    writer.add_item(wtxn, docid, &vector)?;
+                    break;
+                }
+            }
+        }
+    }
+
+    // remember the prompts the embeddings were generated from
+    let mut cursor = prompt_hashes.into_cursor()?;
+    while let Some((key, value)) = cursor.move_on_next()? {
+        let docid = key.try_into().map(DocumentId::from_be_bytes).unwrap();
+        if failed_docids.contains(docid) {
+            continue;
+        }
+        let hash = value.try_into().map(u64::from_be_bytes).unwrap();
+        index.vector_prompt_hashes.put(wtxn, &(embedder_index as u16, docid), &hash)?;
+    }
+
+    if binary_quantized {
+        crate::vector::quantized::requantize_documents(
+            wtxn,
+            index,
+            &writers,
+            embedder_index,
+            &updated_docids,
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Converts the latitude and longitude back to an xyz GeoPoint.
 fn extract_geo_point(value: &[u8], docid: DocumentId) -> GeoPoint {
     let (lat, tail) = helpers::try_split_array_at::<u8, 8>(value).unwrap();
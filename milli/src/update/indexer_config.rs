@@ -1,5 +1,9 @@
+use std::fmt;
+use std::str::FromStr;
+
 use grenad::CompressionType;
 use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct IndexerConfig {
@@ -12,6 +16,11 @@ pub struct IndexerConfig {
     pub thread_pool: Option<ThreadPool>,
     pub max_positions_per_attributes: Option<u32>,
     pub skip_index_budget: bool,
+    pub attribute_overflow_policy: AttributeOverflowPolicy,
+    /// The top-level fields that are not considered when checking if a
+    /// document changed, e.g. `updated_at`. A document that only differs
+    /// from its stored version by these fields is not written again.
+    pub unchanged_document_ignored_fields: Vec<String>,
 }
 
 impl Default for IndexerConfig {
@@ -26,6 +35,62 @@ impl Default for IndexerConfig {
             thread_pool: None,
             max_positions_per_attributes: None,
             skip_index_budget: false,
+            attribute_overflow_policy: AttributeOverflowPolicy::default(),
+            unchanged_document_ignored_fields: Vec::new(),
+        }
+    }
+}
+
+/// Defines what the indexer does with the new fields of a document
+/// once the index reached its limit of 65,536 fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttributeOverflowPolicy {
+    /// Fails the indexation with an `AttributeLimitReached` error.
+    #[default]
+    Error,
+    /// Skips the fields that cannot be registered in the fields ids map.
+    ///
+    /// The nested fields generated by the flattening are only skipped from the
+    /// indexation, their values are still stored in their parent object.
+    /// The top-level fields are neither stored nor indexed.
+    Ignore,
+}
+
+#[derive(Debug)]
+pub struct AttributeOverflowPolicyError {
+    pub given_policy: String,
+}
+
+impl fmt::Display for AttributeOverflowPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Attribute overflow policy '{}' is invalid. Accepted values are 'error' and 'ignore'.",
+            self.given_policy
+        )
+    }
+}
+
+impl std::error::Error for AttributeOverflowPolicyError {}
+
+impl fmt::Display for AttributeOverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeOverflowPolicy::Error => f.write_str("error"),
+            AttributeOverflowPolicy::Ignore => f.write_str("ignore"),
+        }
+    }
+}
+
+impl FromStr for AttributeOverflowPolicy {
+    type Err = AttributeOverflowPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Ok(AttributeOverflowPolicy::Error),
+            "ignore" => Ok(AttributeOverflowPolicy::Ignore),
+            _ => Err(AttributeOverflowPolicyError { given_policy: s.to_owned() }),
         }
     }
 }
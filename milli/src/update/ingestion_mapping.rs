@@ -0,0 +1,61 @@
+//! The ingestion mapping of an index renames the fields of the documents when they are added,
+//! e.g. `{ "legacy_id": "id", "properties.title": "title" }` indexes the `legacy_id` field as
+//! `id` and moves the `title` nested in the `properties` object to a top-level `title` field.
+//!
+//! A renamed field takes precedence over the field of the document already having its new name.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Returns the name of the top-level field once renamed by the ingestion mapping.
+pub(crate) fn renamed_field<'a>(
+    ingestion_mapping: &'a BTreeMap<String, String>,
+    name: &'a str,
+) -> &'a str {
+    ingestion_mapping.get(name).map_or(name, String::as_str)
+}
+
+/// Returns the sources of the mapping that are nested in the `name` top-level field,
+/// along with their path in this field and their new name.
+pub(crate) fn nested_sources<'a>(
+    ingestion_mapping: &'a BTreeMap<String, String>,
+    name: &'a str,
+) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    ingestion_mapping.iter().filter_map(move |(source, target)| {
+        let path = source.strip_prefix(name)?.strip_prefix('.')?;
+        Some((path, target.as_str()))
+    })
+}
+
+/// Removes and returns the value found at the dotted `path` of the object, e.g. `title` in
+/// `{ "properties": { "title": "Carol" } }` for the `properties.title` path.
+///
+/// The arrays are not traversed as the values they contain can't be moved into a single field.
+pub(crate) fn take_at_path(value: &mut Value, path: &str) -> Option<Value> {
+    let object = value.as_object_mut()?;
+    if let Some(value) = object.shift_remove(path) {
+        return Some(value);
+    }
+
+    object.iter_mut().find_map(|(key, value)| {
+        let rest = path.strip_prefix(key.as_str())?.strip_prefix('.')?;
+        take_at_path(value, rest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn take_nested_values() {
+        let mut value = json!({ "a": { "b.c": 1, "b": { "d": 2 } } });
+        assert_eq!(take_at_path(&mut value, "a.b.c"), Some(json!(1)));
+        assert_eq!(take_at_path(&mut value, "a.b.d"), Some(json!(2)));
+        assert_eq!(take_at_path(&mut value, "a.e"), None);
+        assert_eq!(value, json!({ "a": { "b": {} } }));
+    }
+}
@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+/// Describes how the stored value of a field is combined with a new one
+/// when documents are updated with `IndexDocumentsMethod::UpdateDocuments`.
+///
+/// The fields without a merge strategy are replaced by the new value.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// Appends the new values at the end of the stored array.
+    Append,
+    /// Adds the new number to the stored one.
+    Increment,
+    /// Appends the new values that are not already part of the stored array.
+    Union,
+    /// Keeps the lowest of the stored and new numbers.
+    KeepMin,
+    /// Keeps the highest of the stored and new numbers.
+    KeepMax,
+}
+
+impl MergeStrategy {
+    /// Returns `true` if the strategy also applies to the nested fields generated by the
+    /// flattening of the field, i.e. `tags.name` when the strategy is declared on `tags`.
+    pub fn applies_to_nested_fields(&self) -> bool {
+        matches!(self, MergeStrategy::Append | MergeStrategy::Union)
+    }
+
+    /// Combines the `stored` value with the `new` one.
+    ///
+    /// The scalar values are considered as single-element arrays by the array strategies.
+    /// When the numeric strategies are used on values that are not numbers the new value
+    /// replaces the stored one.
+    pub fn merge(&self, stored: Value, new: Value) -> Value {
+        match self {
+            MergeStrategy::Append => {
+                let mut values = into_array(stored);
+                values.extend(into_array(new));
+                Value::Array(values)
+            }
+            MergeStrategy::Union => {
+                let mut values = into_array(stored);
+                for value in into_array(new) {
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+                Value::Array(values)
+            }
+            MergeStrategy::Increment => match (&stored, &new) {
+                (Value::Number(lhs), Value::Number(rhs)) => add_numbers(lhs, rhs).unwrap_or(new),
+                _ => new,
+            },
+            MergeStrategy::KeepMin => match compare_numbers(&stored, &new) {
+                Some(Ordering::Greater) => new,
+                Some(_) => stored,
+                None => new,
+            },
+            MergeStrategy::KeepMax => match compare_numbers(&stored, &new) {
+                Some(Ordering::Less) => new,
+                Some(_) => stored,
+                None => new,
+            },
+        }
+    }
+}
+
+fn into_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(values) => values,
+        Value::Null => Vec::new(),
+        value => vec![value],
+    }
+}
+
+fn add_numbers(lhs: &Number, rhs: &Number) -> Option<Value> {
+    if let (Some(lhs), Some(rhs)) = (lhs.as_i64(), rhs.as_i64()) {
+        if let Some(sum) = lhs.checked_add(rhs) {
+            return Some(Value::from(sum));
+        }
+    }
+    if let (Some(lhs), Some(rhs)) = (lhs.as_u64(), rhs.as_u64()) {
+        if let Some(sum) = lhs.checked_add(rhs) {
+            return Some(Value::from(sum));
+        }
+    }
+    Number::from_f64(lhs.as_f64()? + rhs.as_f64()?).map(Value::Number)
+}
+
+fn compare_numbers(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    lhs.as_f64()?.partial_cmp(&rhs.as_f64()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::MergeStrategy;
+
+    #[test]
+    fn merge_values() {
+        let merge = |strategy: MergeStrategy, stored, new| strategy.merge(stored, new);
+
+        assert_eq!(merge(MergeStrategy::Append, json!([1, 2]), json!([2, 3])), json!([1, 2, 2, 3]));
+        assert_eq!(merge(MergeStrategy::Append, json!("a"), json!("b")), json!(["a", "b"]));
+        assert_eq!(merge(MergeStrategy::Union, json!([1, 2]), json!([2, 3])), json!([1, 2, 3]));
+        assert_eq!(merge(MergeStrategy::Union, json!(null), json!(1)), json!([1]));
+        assert_eq!(merge(MergeStrategy::Increment, json!(1), json!(2)), json!(3));
+        assert_eq!(merge(MergeStrategy::Increment, json!(1.5), json!(-2)), json!(-0.5));
+        assert_eq!(merge(MergeStrategy::Increment, json!("a"), json!(2)), json!(2));
+        assert_eq!(merge(MergeStrategy::KeepMin, json!(1), json!(2)), json!(1));
+        assert_eq!(merge(MergeStrategy::KeepMin, json!(3), json!(2)), json!(2));
+        assert_eq!(merge(MergeStrategy::KeepMax, json!(1), json!(2.5)), json!(2.5));
+        assert_eq!(merge(MergeStrategy::KeepMax, json!(3), json!(2)), json!(3));
+        assert_eq!(merge(MergeStrategy::KeepMax, json!(3), json!("b")), json!("b"));
+    }
+}
@@ -1,27 +1,41 @@
 pub use self::available_documents_ids::AvailableDocumentsIds;
+pub use self::change_primary_key::ChangePrimaryKey;
 pub use self::clear_documents::ClearDocuments;
 pub use self::facet::bulk::FacetsUpdateBulk;
 pub use self::facet::incremental::FacetsUpdateIncrementalInner;
 pub use self::index_documents::{
     merge_btreeset_string, merge_cbo_roaring_bitmaps, merge_roaring_bitmaps,
     DocumentAdditionResult, DocumentId, IndexDocuments, IndexDocumentsConfig, IndexDocumentsMethod,
-    MergeFn,
+    MergeFn, ReindexScope,
 };
-pub use self::indexer_config::IndexerConfig;
+pub use self::indexer_config::{
+    AttributeOverflowPolicy, AttributeOverflowPolicyError, IndexerConfig,
+};
+pub use self::merge_strategy::MergeStrategy;
 pub use self::settings::{Setting, Settings};
+pub use self::type_coercion::TypeCoercion;
 pub use self::update_step::UpdateIndexingStep;
 pub use self::word_prefix_docids::WordPrefixDocids;
+pub use self::word_romanizations_fst::WordRomanizationsFst;
+pub use self::word_stems_fst::WordStemsFst;
 pub use self::words_prefix_integer_docids::WordPrefixIntegerDocids;
 pub use self::words_prefixes_fst::WordsPrefixesFst;
 
 mod available_documents_ids;
+mod change_primary_key;
 mod clear_documents;
 pub(crate) mod del_add;
+mod derived_words_fst;
 pub(crate) mod facet;
 mod index_documents;
 mod indexer_config;
+pub(crate) mod ingestion_mapping;
+mod merge_strategy;
 mod settings;
+mod type_coercion;
 mod update_step;
 mod word_prefix_docids;
+mod word_romanizations_fst;
+mod word_stems_fst;
 mod words_prefix_integer_docids;
 mod words_prefixes_fst;
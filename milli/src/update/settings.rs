@@ -12,11 +12,22 @@ use time::OffsetDateTime;
 use super::index_documents::{IndexDocumentsConfig, Transform};
 use super::IndexerConfig;
 use crate::criterion::Criterion;
+use crate::document_schema::DocumentSchema;
 use crate::error::UserError;
+use crate::foreign_key::ForeignKey;
 use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
+use crate::localized_attributes_rules::LocalizedAttributesRule;
 use crate::proximity::ProximityPrecision;
+use crate::query_rules::QueryRule;
+use crate::static_boost::StaticBoost;
+use crate::stemming::is_stemmable_locale;
+use crate::suggestions::build_suggestions;
 use crate::update::index_documents::IndexDocumentsMethod;
-use crate::update::{IndexDocuments, UpdateIndexingStep};
+use crate::update::{
+    ChangePrimaryKey, IndexDocuments, MergeStrategy, ReindexScope, TypeCoercion,
+    UpdateIndexingStep, WordRomanizationsFst, WordStemsFst,
+};
+use crate::vector::rerank::RerankerOptions;
 use crate::vector::settings::{EmbeddingSettings, PromptSettings};
 use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
 use crate::{FieldsIdsMap, Index, OrderBy, Result};
@@ -122,15 +133,33 @@ pub struct Settings<'a, 't, 'i> {
     displayed_fields: Setting<Vec<String>>,
     filterable_fields: Setting<HashSet<String>>,
     sortable_fields: Setting<HashSet<String>>,
+    non_flattened_fields: Setting<HashSet<String>>,
+    merge_strategies: Setting<BTreeMap<String, MergeStrategy>>,
+    type_coercions: Setting<BTreeMap<String, TypeCoercion>>,
+    ingestion_mapping: Setting<BTreeMap<String, String>>,
+    facet_hierarchy_separators: Setting<BTreeMap<String, String>>,
+    date_fields: Setting<HashSet<String>>,
+    suggestable_fields: Setting<HashSet<String>>,
     criteria: Setting<Vec<Criterion>>,
     stop_words: Setting<BTreeSet<String>>,
     non_separator_tokens: Setting<BTreeSet<String>>,
     separator_tokens: Setting<BTreeSet<String>>,
     dictionary: Setting<BTreeSet<String>>,
     distinct_field: Setting<String>,
+    static_boost: Setting<StaticBoost>,
+    foreign_keys: Setting<Vec<ForeignKey>>,
+    query_rules: Setting<Vec<QueryRule>>,
+    localized_attributes_rules: Setting<Vec<LocalizedAttributesRule>>,
+    stemming: Setting<BTreeSet<String>>,
+    romanization: Setting<bool>,
+    split_identifiers: Setting<bool>,
+    document_schema: Setting<serde_json::Value>,
+    reranker: Setting<RerankerOptions>,
     synonyms: Setting<BTreeMap<String, Vec<String>>>,
     primary_key: Setting<String>,
     authorize_typos: Setting<bool>,
+    disable_typo_on_first_character: Setting<bool>,
+    authorize_typos_on_numbers: Setting<bool>,
     min_word_len_two_typos: Setting<u8>,
     min_word_len_one_typo: Setting<u8>,
     exact_words: Setting<BTreeSet<String>>,
@@ -156,15 +185,33 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             displayed_fields: Setting::NotSet,
             filterable_fields: Setting::NotSet,
             sortable_fields: Setting::NotSet,
+            non_flattened_fields: Setting::NotSet,
+            merge_strategies: Setting::NotSet,
+            type_coercions: Setting::NotSet,
+            ingestion_mapping: Setting::NotSet,
+            facet_hierarchy_separators: Setting::NotSet,
+            date_fields: Setting::NotSet,
+            suggestable_fields: Setting::NotSet,
             criteria: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
             separator_tokens: Setting::NotSet,
             dictionary: Setting::NotSet,
             distinct_field: Setting::NotSet,
+            static_boost: Setting::NotSet,
+            foreign_keys: Setting::NotSet,
+            query_rules: Setting::NotSet,
+            localized_attributes_rules: Setting::NotSet,
+            stemming: Setting::NotSet,
+            romanization: Setting::NotSet,
+            split_identifiers: Setting::NotSet,
+            document_schema: Setting::NotSet,
+            reranker: Setting::NotSet,
             synonyms: Setting::NotSet,
             primary_key: Setting::NotSet,
             authorize_typos: Setting::NotSet,
+            disable_typo_on_first_character: Setting::NotSet,
+            authorize_typos_on_numbers: Setting::NotSet,
             exact_words: Setting::NotSet,
             min_word_len_two_typos: Setting::NotSet,
             min_word_len_one_typo: Setting::NotSet,
@@ -210,6 +257,68 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.sortable_fields = Setting::Reset;
     }
 
+    pub fn set_non_flattened_fields(&mut self, names: HashSet<String>) {
+        self.non_flattened_fields =
+            if names.is_empty() { Setting::Reset } else { Setting::Set(names) }
+    }
+
+    pub fn reset_non_flattened_fields(&mut self) {
+        self.non_flattened_fields = Setting::Reset;
+    }
+
+    pub fn set_merge_strategies(&mut self, strategies: BTreeMap<String, MergeStrategy>) {
+        self.merge_strategies =
+            if strategies.is_empty() { Setting::Reset } else { Setting::Set(strategies) }
+    }
+
+    pub fn reset_merge_strategies(&mut self) {
+        self.merge_strategies = Setting::Reset;
+    }
+
+    pub fn set_type_coercions(&mut self, coercions: BTreeMap<String, TypeCoercion>) {
+        self.type_coercions =
+            if coercions.is_empty() { Setting::Reset } else { Setting::Set(coercions) }
+    }
+
+    pub fn reset_type_coercions(&mut self) {
+        self.type_coercions = Setting::Reset;
+    }
+
+    pub fn set_ingestion_mapping(&mut self, mapping: BTreeMap<String, String>) {
+        self.ingestion_mapping =
+            if mapping.is_empty() { Setting::Reset } else { Setting::Set(mapping) }
+    }
+
+    pub fn reset_ingestion_mapping(&mut self) {
+        self.ingestion_mapping = Setting::Reset;
+    }
+
+    pub fn set_facet_hierarchy_separators(&mut self, separators: BTreeMap<String, String>) {
+        self.facet_hierarchy_separators =
+            if separators.is_empty() { Setting::Reset } else { Setting::Set(separators) }
+    }
+
+    pub fn reset_facet_hierarchy_separators(&mut self) {
+        self.facet_hierarchy_separators = Setting::Reset;
+    }
+
+    pub fn set_date_fields(&mut self, names: HashSet<String>) {
+        self.date_fields = if names.is_empty() { Setting::Reset } else { Setting::Set(names) }
+    }
+
+    pub fn reset_date_fields(&mut self) {
+        self.date_fields = Setting::Reset;
+    }
+
+    pub fn set_suggestable_fields(&mut self, names: HashSet<String>) {
+        self.suggestable_fields =
+            if names.is_empty() { Setting::Reset } else { Setting::Set(names) }
+    }
+
+    pub fn reset_suggestable_fields(&mut self) {
+        self.suggestable_fields = Setting::Reset;
+    }
+
     pub fn reset_criteria(&mut self) {
         self.criteria = Setting::Reset;
     }
@@ -268,6 +377,78 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.distinct_field = Setting::Set(distinct_field);
     }
 
+    pub fn reset_static_boost(&mut self) {
+        self.static_boost = Setting::Reset;
+    }
+
+    pub fn set_static_boost(&mut self, static_boost: StaticBoost) {
+        self.static_boost = Setting::Set(static_boost);
+    }
+
+    pub fn reset_foreign_keys(&mut self) {
+        self.foreign_keys = Setting::Reset;
+    }
+
+    pub fn set_foreign_keys(&mut self, foreign_keys: Vec<ForeignKey>) {
+        self.foreign_keys = Setting::Set(foreign_keys);
+    }
+
+    pub fn reset_query_rules(&mut self) {
+        self.query_rules = Setting::Reset;
+    }
+
+    pub fn set_query_rules(&mut self, query_rules: Vec<QueryRule>) {
+        self.query_rules = Setting::Set(query_rules);
+    }
+
+    pub fn reset_localized_attributes_rules(&mut self) {
+        self.localized_attributes_rules = Setting::Reset;
+    }
+
+    pub fn set_localized_attributes_rules(&mut self, rules: Vec<LocalizedAttributesRule>) {
+        self.localized_attributes_rules = Setting::Set(rules);
+    }
+
+    pub fn reset_stemming(&mut self) {
+        self.stemming = Setting::Reset;
+    }
+
+    pub fn set_stemming(&mut self, locales: BTreeSet<String>) {
+        self.stemming = Setting::Set(locales);
+    }
+
+    pub fn reset_romanization(&mut self) {
+        self.romanization = Setting::Reset;
+    }
+
+    pub fn set_romanization(&mut self, val: bool) {
+        self.romanization = Setting::Set(val);
+    }
+
+    pub fn reset_split_identifiers(&mut self) {
+        self.split_identifiers = Setting::Reset;
+    }
+
+    pub fn set_split_identifiers(&mut self, val: bool) {
+        self.split_identifiers = Setting::Set(val);
+    }
+
+    pub fn reset_document_schema(&mut self) {
+        self.document_schema = Setting::Reset;
+    }
+
+    pub fn set_document_schema(&mut self, schema: serde_json::Value) {
+        self.document_schema = Setting::Set(schema);
+    }
+
+    pub fn reset_reranker(&mut self) {
+        self.reranker = Setting::Reset;
+    }
+
+    pub fn set_reranker(&mut self, reranker: RerankerOptions) {
+        self.reranker = Setting::Set(reranker);
+    }
+
     pub fn reset_synonyms(&mut self) {
         self.synonyms = Setting::Reset;
     }
@@ -292,6 +473,22 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.authorize_typos = Setting::Reset;
     }
 
+    pub fn set_disable_typo_on_first_character(&mut self, val: bool) {
+        self.disable_typo_on_first_character = Setting::Set(val);
+    }
+
+    pub fn reset_disable_typo_on_first_character(&mut self) {
+        self.disable_typo_on_first_character = Setting::Reset;
+    }
+
+    pub fn set_authorize_typos_on_numbers(&mut self, val: bool) {
+        self.authorize_typos_on_numbers = Setting::Set(val);
+    }
+
+    pub fn reset_authorize_typos_on_numbers(&mut self) {
+        self.authorize_typos_on_numbers = Setting::Reset;
+    }
+
     pub fn set_min_word_len_two_typos(&mut self, val: u8) {
         self.min_word_len_two_typos = Setting::Set(val);
     }
@@ -369,6 +566,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         progress_callback: &FP,
         should_abort: &FA,
         old_fields_ids_map: FieldsIdsMap,
+        scope: ReindexScope,
     ) -> Result<()>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
@@ -396,6 +594,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             self.wtxn,
             old_fields_ids_map,
             fields_ids_map,
+            scope,
         )?;
 
         let embedder_configs = self.index.embedding_configs(self.wtxn)?;
@@ -412,7 +611,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             &should_abort,
         )?;
 
-        let indexing_builder = indexing_builder.with_embedders(embedders);
+        let indexing_builder = indexing_builder.with_embedders(embedders).with_reindex_scope(scope);
         indexing_builder.execute_raw(output)?;
 
         Ok(())
@@ -424,7 +623,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     ) -> Result<EmbeddingConfigs> {
         let res: Result<_> = embedding_configs
             .into_iter()
-            .map(|(name, EmbeddingConfig { embedder_options, prompt })| {
+            .map(|(name, EmbeddingConfig { embedder_options, prompt, .. })| {
                 let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
 
                 let embedder = Arc::new(
@@ -466,6 +665,73 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(true)
     }
 
+    fn update_static_boost(&mut self) -> Result<()> {
+        match self.static_boost {
+            Setting::Set(ref static_boost) => {
+                self.index.put_static_boost(self.wtxn, static_boost)?;
+            }
+            Setting::Reset => {
+                self.index.delete_static_boost(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_foreign_keys(&mut self) -> Result<()> {
+        match self.foreign_keys {
+            Setting::Set(ref foreign_keys) => {
+                self.index.put_foreign_keys(self.wtxn, foreign_keys)?;
+            }
+            Setting::Reset => {
+                self.index.delete_foreign_keys(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_query_rules(&mut self) -> Result<()> {
+        match self.query_rules {
+            Setting::Set(ref query_rules) => {
+                self.index.put_query_rules(self.wtxn, query_rules)?;
+            }
+            Setting::Reset => {
+                self.index.delete_query_rules(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_document_schema(&mut self) -> Result<()> {
+        match self.document_schema {
+            Setting::Set(ref schema) => {
+                // the documents already in the index are not validated against the new schema
+                let schema = DocumentSchema::new(schema.clone()).map_err(UserError::from)?;
+                self.index.put_document_schema(self.wtxn, &schema)?;
+            }
+            Setting::Reset => {
+                self.index.delete_document_schema(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
+    fn update_reranker(&mut self) -> Result<()> {
+        match self.reranker {
+            Setting::Set(ref reranker) => {
+                self.index.put_reranker(self.wtxn, reranker)?;
+            }
+            Setting::Reset => {
+                self.index.delete_reranker(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+        Ok(())
+    }
+
     /// Updates the index's searchable attributes. This causes the field map to be recomputed to
     /// reflect the order of the searchable attributes.
     fn update_searchable(&mut self) -> Result<bool> {
@@ -548,14 +814,101 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         }
     }
 
+    fn update_localized_attributes_rules(&mut self) -> Result<bool> {
+        match self.localized_attributes_rules {
+            Setting::Set(ref rules) => {
+                let current = self.index.localized_attributes_rules(self.wtxn)?;
+
+                // the stop words of the rules are normalized like the ones of the index
+                let rules: Vec<_> = rules
+                    .iter()
+                    .cloned()
+                    .map(|mut rule| {
+                        rule.stop_words = rule
+                            .stop_words
+                            .iter()
+                            .map(|w| w.as_str().normalize(&Default::default()).into_owned())
+                            .collect();
+                        rule
+                    })
+                    .collect();
+
+                if current != rules {
+                    self.index.put_localized_attributes_rules(self.wtxn, &rules)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_localized_attributes_rules(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
+    fn update_stemming(&mut self) -> Result<bool> {
+        match self.stemming {
+            Setting::Set(ref locales) => {
+                if let Some(locale) = locales.iter().find(|locale| !is_stemmable_locale(locale)) {
+                    return Err(UserError::UnknownStemmingLocale(locale.clone()).into());
+                }
+
+                let current = self.index.stemming(self.wtxn)?;
+                if current != *locales {
+                    self.index.put_stemming(self.wtxn, locales)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_stemming(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
+    fn update_romanization(&mut self) -> Result<bool> {
+        let flag = match self.romanization {
+            Setting::Set(flag) => flag,
+            Setting::Reset => false,
+            Setting::NotSet => return Ok(false),
+        };
+
+        if self.index.romanization(self.wtxn)? != flag {
+            self.index.put_romanization(self.wtxn, flag)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn update_split_identifiers(&mut self) -> Result<bool> {
+        let flag = match self.split_identifiers {
+            Setting::Set(flag) => flag,
+            Setting::Reset => false,
+            Setting::NotSet => return Ok(false),
+        };
+
+        if self.index.split_identifiers(self.wtxn)? != flag {
+            self.index.put_split_identifiers(self.wtxn, flag)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     fn update_non_separator_tokens(&mut self) -> Result<bool> {
         let changes = match self.non_separator_tokens {
             Setting::Set(ref non_separator_tokens) => {
+                // an empty token would match everywhere in the text
+                let non_separator_tokens: BTreeSet<String> = non_separator_tokens
+                    .iter()
+                    .filter(|token| !token.is_empty())
+                    .cloned()
+                    .collect();
                 let current = self.index.non_separator_tokens(self.wtxn)?;
 
                 // Does the new list differ from the previous one?
-                if current.map_or(true, |current| &current != non_separator_tokens) {
-                    self.index.put_non_separator_tokens(self.wtxn, non_separator_tokens)?;
+                if current.map_or(true, |current| current != non_separator_tokens) {
+                    self.index.put_non_separator_tokens(self.wtxn, &non_separator_tokens)?;
                     true
                 } else {
                     false
@@ -576,11 +929,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     fn update_separator_tokens(&mut self) -> Result<bool> {
         let changes = match self.separator_tokens {
             Setting::Set(ref separator_tokens) => {
+                // an empty token would match everywhere in the text
+                let separator_tokens: BTreeSet<String> =
+                    separator_tokens.iter().filter(|token| !token.is_empty()).cloned().collect();
                 let current = self.index.separator_tokens(self.wtxn)?;
 
                 // Does the new list differ from the previous one?
-                if current.map_or(true, |current| &current != separator_tokens) {
-                    self.index.put_separator_tokens(self.wtxn, separator_tokens)?;
+                if current.map_or(true, |current| current != separator_tokens) {
+                    self.index.put_separator_tokens(self.wtxn, &separator_tokens)?;
                     true
                 } else {
                     false
@@ -598,14 +954,33 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changes)
     }
 
+    /// Ensures that no token is both a separator and a non separator token.
+    fn check_separator_tokens(&self) -> Result<()> {
+        let separator_tokens = self.index.separator_tokens(self.wtxn)?.unwrap_or_default();
+        let non_separator_tokens = self.index.non_separator_tokens(self.wtxn)?.unwrap_or_default();
+        match separator_tokens.intersection(&non_separator_tokens).next() {
+            Some(token) => Err(UserError::ConflictingSeparatorToken(token.clone()).into()),
+            None => Ok(()),
+        }
+    }
+
     fn update_dictionary(&mut self) -> Result<bool> {
         let changes = match self.dictionary {
             Setting::Set(ref dictionary) => {
+                // the words are matched as is in the text, surrounding spaces would never match
+                let dictionary: BTreeSet<String> = dictionary
+                    .iter()
+                    .map(|word| word.trim())
+                    .filter(|word| !word.is_empty())
+                    .map(String::from)
+                    .collect();
                 let current = self.index.dictionary(self.wtxn)?;
 
                 // Does the new list differ from the previous one?
-                if current.map_or(true, |current| &current != dictionary) {
-                    self.index.put_dictionary(self.wtxn, dictionary)?;
+                if dictionary.is_empty() {
+                    self.index.delete_dictionary(self.wtxn)?
+                } else if current.map_or(true, |current| current != dictionary) {
+                    self.index.put_dictionary(self.wtxn, &dictionary)?;
                     true
                 } else {
                     false
@@ -752,6 +1127,81 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
+    fn update_non_flattened_fields(&mut self) -> Result<bool> {
+        let changes = match self.non_flattened_fields {
+            Setting::Set(ref fields) => {
+                let current = self.index.non_flattened_fields(self.wtxn)?;
+                if &current != fields {
+                    self.index.put_non_flattened_fields(self.wtxn, fields)?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Setting::Reset => self.index.delete_non_flattened_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changes)
+    }
+
+    fn update_facet_hierarchy_separators(&mut self) -> Result<bool> {
+        let changes = match self.facet_hierarchy_separators {
+            Setting::Set(ref separators) => {
+                if separators.values().any(|separator| separator.trim().is_empty()) {
+                    return Err(UserError::InvalidFacetHierarchySeparator.into());
+                }
+                let current = self.index.facet_hierarchy_separators(self.wtxn)?;
+                if &current != separators {
+                    self.index.put_facet_hierarchy_separators(self.wtxn, separators)?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Setting::Reset => self.index.delete_facet_hierarchy_separators(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changes)
+    }
+
+    fn update_date_fields(&mut self) -> Result<bool> {
+        let changes = match self.date_fields {
+            Setting::Set(ref fields) => {
+                let current = self.index.date_fields(self.wtxn)?;
+                if &current != fields {
+                    self.index.put_date_fields(self.wtxn, fields)?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Setting::Reset => self.index.delete_date_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changes)
+    }
+
+    fn update_suggestable_fields(&mut self) -> Result<bool> {
+        let changes = match self.suggestable_fields {
+            Setting::Set(ref fields) => {
+                let current = self.index.suggestable_fields(self.wtxn)?;
+                if &current != fields {
+                    self.index.put_suggestable_fields(self.wtxn, fields)?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Setting::Reset => self.index.delete_suggestable_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changes)
+    }
+
     fn update_criteria(&mut self) -> Result<()> {
         match &self.criteria {
             Setting::Set(criteria) => {
@@ -776,11 +1226,11 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                     Ok(())
                 } else {
                     let curr_primary_key = self.index.primary_key(self.wtxn)?.unwrap().to_string();
-                    if primary_key == &curr_primary_key {
-                        Ok(())
-                    } else {
-                        Err(UserError::PrimaryKeyCannotBeChanged(curr_primary_key).into())
+                    if primary_key != &curr_primary_key {
+                        ChangePrimaryKey::new(self.wtxn, self.index, primary_key.clone())
+                            .execute()?;
                     }
+                    Ok(())
                 }
             }
             Setting::Reset => {
@@ -810,6 +1260,34 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         }
     }
 
+    fn update_disable_typo_on_first_character(&mut self) -> Result<()> {
+        match self.disable_typo_on_first_character {
+            Setting::Set(flag) => {
+                self.index.put_disable_typo_on_first_character(self.wtxn, flag)?;
+                Ok(())
+            }
+            Setting::Reset => {
+                self.index.put_disable_typo_on_first_character(self.wtxn, false)?;
+                Ok(())
+            }
+            Setting::NotSet => Ok(()),
+        }
+    }
+
+    fn update_authorize_typos_on_numbers(&mut self) -> Result<()> {
+        match self.authorize_typos_on_numbers {
+            Setting::Set(flag) => {
+                self.index.put_authorize_typos_on_numbers(self.wtxn, flag)?;
+                Ok(())
+            }
+            Setting::Reset => {
+                self.index.put_authorize_typos_on_numbers(self.wtxn, true)?;
+                Ok(())
+            }
+            Setting::NotSet => Ok(()),
+        }
+    }
+
     fn update_min_typo_word_len(&mut self) -> Result<()> {
         let one = self.min_word_len_one_typo.or_reset(DEFAULT_MIN_WORD_LEN_ONE_TYPO);
         let two = self.min_word_len_two_typos.or_reset(DEFAULT_MIN_WORD_LEN_TWO_TYPOS);
@@ -918,34 +1396,87 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(())
     }
 
-    fn update_proximity_precision(&mut self) -> Result<bool> {
-        let changed = match self.proximity_precision {
-            Setting::Set(new) => {
-                let old = self.index.proximity_precision(self.wtxn)?;
-                if old == Some(new) {
-                    false
-                } else {
-                    self.index.put_proximity_precision(self.wtxn, new)?;
-                    true
-                }
+    fn update_merge_strategies(&mut self) -> Result<()> {
+        match self.merge_strategies.as_ref() {
+            Setting::Set(strategies) => {
+                self.index.put_merge_strategies(self.wtxn, strategies)?;
             }
-            Setting::Reset => self.index.delete_proximity_precision(self.wtxn)?,
-            Setting::NotSet => false,
-        };
+            Setting::Reset => {
+                self.index.delete_merge_strategies(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
 
-        Ok(changed)
+        Ok(())
     }
 
-    fn update_embedding_configs(&mut self) -> Result<bool> {
-        let update = match std::mem::take(&mut self.embedder_settings) {
-            Setting::Set(configs) => {
-                let mut changed = false;
-                let old_configs = self.index.embedding_configs(self.wtxn)?;
-                let old_configs: BTreeMap<String, Setting<EmbeddingSettings>> =
-                    old_configs.into_iter().map(|(k, v)| (k, Setting::Set(v.into()))).collect();
-
-                let mut new_configs = BTreeMap::new();
-                for joined in old_configs
+    fn update_type_coercions(&mut self) -> Result<()> {
+        match self.type_coercions.as_ref() {
+            Setting::Set(coercions) => {
+                self.index.put_type_coercions(self.wtxn, coercions)?;
+            }
+            Setting::Reset => {
+                self.index.delete_type_coercions(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
+    fn update_ingestion_mapping(&mut self) -> Result<()> {
+        match self.ingestion_mapping.as_ref() {
+            Setting::Set(mapping) => {
+                let mut sources_by_target: BTreeMap<&str, &str> = BTreeMap::new();
+                for (source, target) in mapping {
+                    if let Some(first) = sources_by_target.insert(target, source) {
+                        return Err(UserError::ConflictingIngestionMapping {
+                            first: first.to_string(),
+                            second: source.clone(),
+                            target: target.clone(),
+                        }
+                        .into());
+                    }
+                }
+                self.index.put_ingestion_mapping(self.wtxn, mapping)?;
+            }
+            Setting::Reset => {
+                self.index.delete_ingestion_mapping(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
+
+        Ok(())
+    }
+
+    fn update_proximity_precision(&mut self) -> Result<bool> {
+        let changed = match self.proximity_precision {
+            Setting::Set(new) => {
+                let old = self.index.proximity_precision(self.wtxn)?;
+                if old == Some(new) {
+                    false
+                } else {
+                    self.index.put_proximity_precision(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_proximity_precision(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_embedding_configs(&mut self) -> Result<bool> {
+        let update = match std::mem::take(&mut self.embedder_settings) {
+            Setting::Set(configs) => {
+                let mut changed = false;
+                let old_configs = self.index.embedding_configs(self.wtxn)?;
+                let old_configs: BTreeMap<String, Setting<EmbeddingSettings>> =
+                    old_configs.into_iter().map(|(k, v)| (k, Setting::Set(v.into()))).collect();
+
+                let mut new_configs = BTreeMap::new();
+                for joined in old_configs
                     .into_iter()
                     .merge_join_by(configs.into_iter(), |(left, _), (right, _)| left.cmp(right))
                 {
@@ -1017,28 +1548,50 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.update_filterable()?;
         self.update_sortable()?;
         self.update_distinct_field()?;
+        self.update_static_boost()?;
+        self.update_foreign_keys()?;
+        self.update_query_rules()?;
+        self.update_document_schema()?;
+        self.update_reranker()?;
         self.update_criteria()?;
         self.update_primary_key()?;
         self.update_authorize_typos()?;
+        self.update_disable_typo_on_first_character()?;
+        self.update_authorize_typos_on_numbers()?;
         self.update_min_typo_word_len()?;
         self.update_exact_words()?;
         self.update_max_values_per_facet()?;
         self.update_sort_facet_values_by()?;
         self.update_pagination_max_total_hits()?;
+        self.update_merge_strategies()?;
+        self.update_type_coercions()?;
+        self.update_ingestion_mapping()?;
 
         // If there is new faceted fields we indicate that we must reindex as we must
-        // index new fields as facets. It means that the distinct attribute,
+        // index new fields as facets. It means that the distinct attribute, the static boost,
         // an Asc/Desc criterion or a filtered attribute as be added or removed.
         let new_faceted_fields = self.index.user_defined_faceted_fields(self.wtxn)?;
         let faceted_updated = old_faceted_fields != new_faceted_fields;
+        // The date fields change the values stored in the facet databases.
+        let date_fields_updated = self.update_date_fields()?;
 
         let stop_words_updated = self.update_stop_words()?;
+        let localized_attributes_updated = self.update_localized_attributes_rules()?;
         let non_separator_tokens_updated = self.update_non_separator_tokens()?;
         let separator_tokens_updated = self.update_separator_tokens()?;
+        if non_separator_tokens_updated || separator_tokens_updated {
+            self.check_separator_tokens()?;
+        }
         let dictionary_updated = self.update_dictionary()?;
+        let split_identifiers_updated = self.update_split_identifiers()?;
         let synonyms_updated = self.update_synonyms()?;
         let searchable_updated = self.update_searchable()?;
+        let non_flattened_updated = self.update_non_flattened_fields()?;
+        let facet_hierarchy_updated = self.update_facet_hierarchy_separators()?;
         let exact_attributes_updated = self.update_exact_attributes()?;
+        let suggestable_fields_updated = self.update_suggestable_fields()?;
+        let stemming_updated = self.update_stemming()?;
+        let romanization_updated = self.update_romanization()?;
         let proximity_precision = self.update_proximity_precision()?;
         // TODO: very rough approximation of the needs for reindexing where any change will result in
         // a full reindexing.
@@ -1048,18 +1601,46 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         // 3. Keep the old vectors but reattempt indexing on a prompt change: only actually changed prompt will need embedding + storage
         let embedding_configs_updated = self.update_embedding_configs()?;
 
-        if stop_words_updated
-            || non_separator_tokens_updated
-            || separator_tokens_updated
-            || dictionary_updated
-            || faceted_updated
-            || synonyms_updated
-            || searchable_updated
-            || exact_attributes_updated
-            || proximity_precision
-            || embedding_configs_updated
-        {
-            self.reindex(&progress_callback, &should_abort, old_fields_ids_map)?;
+        // The searchable fields define the order of the fields ids map, the non flattened
+        // fields and the facet hierarchies define the fields generated by the flattening:
+        // the documents must be rewritten.
+        let scope = if searchable_updated || non_flattened_updated || facet_hierarchy_updated {
+            ReindexScope::FULL
+        } else {
+            ReindexScope {
+                documents: false,
+                facets: faceted_updated || date_fields_updated,
+                words: stop_words_updated
+                    || localized_attributes_updated
+                    || non_separator_tokens_updated
+                    || separator_tokens_updated
+                    || dictionary_updated
+                    || split_identifiers_updated
+                    || synonyms_updated
+                    || exact_attributes_updated,
+                // the other word databases don't depend on the proximity precision
+                proximities: proximity_precision,
+                vectors: embedding_configs_updated,
+            }
+        };
+
+        if !scope.is_empty() {
+            self.reindex(&progress_callback, &should_abort, old_fields_ids_map, scope)?;
+        }
+
+        // The suggestions don't depend on the other databases, they are rebuilt on their own.
+        if suggestable_fields_updated {
+            build_suggestions(self.wtxn, self.index)?;
+        }
+
+        // The word stems only depend on the words of the index, they are rebuilt on their own.
+        if stemming_updated && !scope.words {
+            WordStemsFst::new(self.wtxn, self.index).execute()?;
+        }
+
+        // So are the word romanizations.
+        if romanization_updated && !scope.words {
+            WordRomanizationsFst::new(self.wtxn, self.index).execute()?;
         }
 
         Ok(())
@@ -1074,6 +1655,9 @@ fn validate_prompt(
         Setting::Set(EmbeddingSettings {
             embedder_options,
             document_template: Setting::Set(PromptSettings { template: Setting::Set(template) }),
+            binary_quantized,
+            distance,
+            request_policy,
         }) => {
             // validate
             let template = crate::prompt::Prompt::new(template)
@@ -1085,6 +1669,9 @@ fn validate_prompt(
                 document_template: Setting::Set(PromptSettings {
                     template: Setting::Set(template),
                 }),
+                binary_quantized,
+                distance,
+                request_policy,
             }))
         }
         new => Ok(new),
@@ -1101,7 +1688,7 @@ mod tests {
     use crate::error::Error;
     use crate::index::tests::TempIndex;
     use crate::update::ClearDocuments;
-    use crate::{Criterion, Filter, SearchResult};
+    use crate::{db_snap, Criterion, Filter, SearchResult};
 
     #[test]
     fn set_and_reset_searchable_fields() {
@@ -1335,6 +1922,42 @@ mod tests {
         assert_eq!(count, 4);
     }
 
+    #[test]
+    fn set_filterable_fields_only_reindexes_facets() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "name": "kevin", "age": 23 },
+                { "id": 1, "name": "kevina", "age": 21 },
+                { "id": 2, "name": "benoit", "age": 34 }
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let word_docids = index.word_docids.len(&rtxn).unwrap();
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("age") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        // The documents and the word databases are kept untouched.
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 3);
+        assert_eq!(index.word_docids.len(&rtxn).unwrap(), word_docids);
+        let SearchResult { documents_ids, .. } =
+            index.search(&rtxn).query("kevin").execute().unwrap();
+        assert_eq!(documents_ids.len(), 2);
+
+        // While the facet databases are filled.
+        let filter = Filter::from_str("age < 30").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+    }
+
     #[test]
     fn set_asc_desc_field() {
         let mut index = TempIndex::new();
@@ -1540,6 +2163,307 @@ mod tests {
         assert_eq!(result.documents_ids.len(), 1); // there is one benoit in our data
     }
 
+    #[test]
+    fn set_and_reset_localized_attributes_rules() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "title_fr": "Le chat noir", "title_en": "The black cat" },
+                { "id": 1, "title_fr": "Un chien", "title_en": "Le Mans" },
+            ]))
+            .unwrap();
+
+        let rule = LocalizedAttributesRule {
+            attribute_patterns: vec!["*_fr".to_string()],
+            locales: vec!["fra".to_string()],
+            stop_words: btreeset! { "Le".to_string(), "un".to_string() },
+        };
+        index
+            .update_settings(|settings| {
+                settings.set_localized_attributes_rules(vec![rule.clone()]);
+            })
+            .unwrap();
+
+        // the stop words of the rule are normalized and stored along with it
+        let rtxn = index.read_txn().unwrap();
+        let rules = index.localized_attributes_rules(&rtxn).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].stop_words, btreeset! { "le".to_string(), "un".to_string() });
+
+        // the stop words are only ignored in the attributes matching the rule
+        let docids = |word: &str| -> Option<Vec<u32>> {
+            index.word_docids.get(&rtxn, word).unwrap().map(|docids| docids.iter().collect())
+        };
+        assert_eq!(docids("le"), Some(vec![1]));
+        assert_eq!(docids("un"), None);
+        assert_eq!(docids("chat"), Some(vec![0]));
+
+        // and in the query
+        let result = index.search(&rtxn).query("le ").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 2);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_localized_attributes_rules();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.localized_attributes_rules(&rtxn).unwrap().is_empty());
+        let docids = |word: &str| -> Option<Vec<u32>> {
+            index.word_docids.get(&rtxn, word).unwrap().map(|docids| docids.iter().collect())
+        };
+        assert_eq!(docids("le"), Some(vec![0, 1]));
+        assert_eq!(docids("un"), Some(vec![1]));
+    }
+
+    #[test]
+    fn set_and_reset_stemming() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "running" },
+                { "id": 1, "text": "runs" },
+                { "id": 2, "text": "run" },
+                { "id": 3, "text": "runner" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let result = index.search(&rtxn).query("runs").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![1]);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_stemming(btreeset! { S("eng") });
+            })
+            .unwrap();
+
+        // the words sharing a stem match each other, the original form ranking first
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.stemming(&rtxn).unwrap(), btreeset! { S("eng") });
+        let result = index.search(&rtxn).query("runs").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 3);
+        assert_eq!(result.documents_ids[0], 1);
+        let result = index.search(&rtxn).query("run").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 3);
+        assert_eq!(result.documents_ids[0], 2);
+        drop(rtxn);
+
+        // the stems are computed for the new documents
+        index.add_documents(documents!([{ "id": 4, "text": "ran and runs" }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let result = index.search(&rtxn).query("running").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 4);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_stemming();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.stemming(&rtxn).unwrap().is_empty());
+        assert!(index.word_stems_fst(&rtxn).unwrap().is_empty());
+        let result = index.search(&rtxn).query("runs").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 2);
+    }
+
+    #[test]
+    fn set_and_reset_romanization() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "中国" },
+                { "id": 1, "text": "すし" },
+                { "id": 2, "text": "sushi" },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_romanization(true);
+            })
+            .unwrap();
+
+        // the words are matched by their latin transcription, the native ones ranking first
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.romanization(&rtxn).unwrap());
+        let result = index.search(&rtxn).query("zhongguo ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0]);
+        let result = index.search(&rtxn).query("sushi ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![2, 1]);
+        let result = index.search(&rtxn).query("すし ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![1]);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_romanization();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(!index.romanization(&rtxn).unwrap());
+        assert!(index.word_romanizations_fst(&rtxn).unwrap().is_empty());
+        let result = index.search(&rtxn).query("sushi ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![2]);
+    }
+
+    #[test]
+    fn romanization_of_the_added_words() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_romanization(true);
+            })
+            .unwrap();
+
+        index.add_documents(documents!([{ "id": 0, "text": "中国" }])).unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 1, "text": "東京に行きました" },
+                { "id": 2, "text": "北京" },
+            ]))
+            .unwrap();
+
+        // the transcriptions of the previous batches are kept, the kanji of the japanese
+        // documents not being transcribed into pinyin
+        let rtxn = index.read_txn().unwrap();
+        let result = index.search(&rtxn).query("zhongguo ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0]);
+        let result = index.search(&rtxn).query("beijing ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![2]);
+        let result = index.search(&rtxn).query("dongjing ").execute().unwrap();
+        assert!(result.documents_ids.is_empty());
+    }
+
+    #[test]
+    fn set_and_reset_split_identifiers() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "code": "fn getUserName()" },
+                { "id": 1, "code": "def get_user_name():" },
+                { "id": 2, "code": "user name" },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_split_identifiers(true);
+            })
+            .unwrap();
+
+        // the identifiers are indexed as their sub-words
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.split_identifiers(&rtxn).unwrap());
+        let docids = |word: &str| -> Option<Vec<u32>> {
+            index.word_docids.get(&rtxn, word).unwrap().map(|docids| docids.iter().collect())
+        };
+        assert_eq!(docids("user"), Some(vec![0, 1, 2]));
+        assert_eq!(docids("getusername"), None);
+
+        // and so are the ones of the query
+        let result = index.search(&rtxn).query("getUserName ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_split_identifiers();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(!index.split_identifiers(&rtxn).unwrap());
+        let result = index.search(&rtxn).query("getUserName ").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![0]);
+    }
+
+    #[test]
+    fn toggle_proximity_precision() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "text": "the quick brown fox" },
+                { "id": 1, "text": "a quick fox" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let word_docids_len = index.word_docids.len(&rtxn).unwrap();
+        assert!(!index.word_pair_proximity_docids.is_empty(&rtxn).unwrap());
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_proximity_precision(ProximityPrecision::ByAttribute);
+            })
+            .unwrap();
+
+        // only the word pair proximities are removed, the other word databases are kept
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.word_pair_proximity_docids.is_empty(&rtxn).unwrap());
+        assert_eq!(index.word_docids.len(&rtxn).unwrap(), word_docids_len);
+        let result = index.search(&rtxn).query("quick fox").execute().unwrap();
+        assert_eq!(result.documents_ids.len(), 2);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.reset_proximity_precision();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(!index.word_pair_proximity_docids.is_empty(&rtxn).unwrap());
+        assert_eq!(index.word_docids.len(&rtxn).unwrap(), word_docids_len);
+        let result = index.search(&rtxn).query("quick fox").execute().unwrap();
+        assert_eq!(result.documents_ids, vec![1, 0]);
+    }
+
+    #[test]
+    fn conflicting_separator_tokens() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_separator_tokens(btreeset! { S("&sep"), S("") });
+                settings.set_non_separator_tokens(btreeset! { S("#"), S("@") });
+            })
+            .unwrap();
+
+        // the empty tokens are ignored
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.separator_tokens(&rtxn).unwrap(), Some(btreeset! { S("&sep") }));
+        drop(rtxn);
+
+        // a token can't be both a separator and a non separator token,
+        // even when the two settings are updated separately
+        let error = index
+            .update_settings(|settings| {
+                settings.set_separator_tokens(btreeset! { S("&sep"), S("@") });
+            })
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UserError(UserError::ConflictingSeparatorToken(token)) if token == "@"
+        ));
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.separator_tokens(&rtxn).unwrap(), Some(btreeset! { S("&sep") }));
+    }
+
     #[test]
     fn set_and_reset_synonyms() {
         let mut index = TempIndex::new();
@@ -1604,6 +2528,59 @@ mod tests {
         assert!(result.documents_ids.is_empty());
     }
 
+    #[test]
+    fn directional_phrase_synonyms() {
+        let index = TempIndex::new();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "name": "new york" },
+                { "id": 1, "name": "york is new" },
+                { "id": 2, "name": "nyc" },
+            ]))
+            .unwrap();
+
+        index
+            .update_settings(|settings| {
+                settings.set_synonyms(btreemap! {
+                    S("nyc") => vec![S("new york")],
+                    S("the city that never sleeps") => vec![S("new york")],
+                });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.max_synonym_words(&rtxn).unwrap(), 5);
+        assert_eq!(
+            index.words_synonyms(&rtxn, &["nyc"]).unwrap(),
+            Some(vec![vec![S("new"), S("york")]])
+        );
+
+        // the synonym phrase is matched as a whole, its words next to each other
+        let mut result = index.search(&rtxn).query("nyc").execute().unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 2]);
+
+        // but the synonyms only apply in the direction they were defined for
+        let mut result = index
+            .search(&rtxn)
+            .query("new york")
+            .terms_matching_strategy(crate::TermsMatchingStrategy::All)
+            .execute()
+            .unwrap();
+        result.documents_ids.sort_unstable();
+        assert_eq!(result.documents_ids, vec![0, 1]);
+
+        // the phrases with more words than an ngram also have their synonyms
+        let result = index
+            .search(&rtxn)
+            .query("the city that never sleeps")
+            .terms_matching_strategy(crate::TermsMatchingStrategy::All)
+            .execute()
+            .unwrap();
+        assert_eq!(result.documents_ids, vec![0]);
+    }
+
     #[test]
     fn thai_synonyms() {
         let mut index = TempIndex::new();
@@ -1734,7 +2711,18 @@ mod tests {
         assert_eq!(index.primary_key(&wtxn).unwrap(), Some("mykey"));
         wtxn.commit().unwrap();
 
-        // Updating the settings with a different (or no) primary key causes an error
+        // Updating the settings with a different primary key re-keys the documents
+        let mut wtxn = index.write_txn().unwrap();
+        index
+            .update_settings_using_wtxn(&mut wtxn, |settings| {
+                settings.set_primary_key(S("name"));
+            })
+            .unwrap();
+        assert_eq!(index.primary_key(&wtxn).unwrap(), Some("name"));
+        assert_eq!(index.external_documents_ids().get(&wtxn, "benoit").unwrap(), Some(2));
+        wtxn.abort();
+
+        // Updating the settings with no primary key causes an error
         let mut wtxn = index.write_txn().unwrap();
         let error = index
             .update_settings_using_wtxn(&mut wtxn, |settings| {
@@ -1758,6 +2746,305 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn set_non_flattened_fields() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_non_flattened_fields(hashset! { S("metadata") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "title": "kevin", "metadata": { "a": { "b": 1 }, "c": "d" } },
+                { "id": 1, "title": "bob", "tags": { "color": "blue" } },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let mut fields: Vec<_> = fields_ids_map.iter().map(|(_, name)| name).collect();
+        fields.sort_unstable();
+        assert_eq!(fields, ["id", "metadata", "tags", "tags.color", "title"]);
+        drop(rtxn);
+
+        // Removing the setting reindexes the documents and flattens the metadata
+        index
+            .update_settings(|settings| {
+                settings.reset_non_flattened_fields();
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        assert!(fields_ids_map.id("metadata.a.b").is_some());
+        assert!(fields_ids_map.id("metadata.c").is_some());
+    }
+
+    #[test]
+    fn set_facet_hierarchy_separators() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("category") });
+                settings.set_facet_hierarchy_separators(btreemap! { S("category") => S(">") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "category": "Books > Scifi > Space opera" },
+                { "id": 1, "category": ["Books > Scifi > Cyberpunk", "Movies > Scifi"] },
+                { "id": 2, "category": "Books > Fantasy" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("category LEVEL 1 = Scifi").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        let filter = Filter::from_str("category LEVEL 0 = Movies").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![1]);
+        let filter = Filter::from_str("category LEVEL 2 = 'Space opera'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        let filter = Filter::from_str("category LEVEL 1 IN [Fantasy, Cyberpunk]").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![2]);
+        let filter = Filter::from_str("category LEVEL 2 EXISTS").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        drop(rtxn);
+
+        // Updating the separator reindexes the levels of the documents
+        index
+            .update_settings(|settings| {
+                settings.set_facet_hierarchy_separators(btreemap! { S("category") => S("/") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("category LEVEL 1 = Scifi").unwrap().unwrap();
+        assert!(filter.evaluate(&rtxn, &index).unwrap().is_empty());
+        let filter = Filter::from_str("category LEVEL 0 = 'Books > Fantasy'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![2]);
+        drop(rtxn);
+
+        // Empty separators are rejected
+        let error = index
+            .update_settings(|settings| {
+                settings.set_facet_hierarchy_separators(btreemap! { S("category") => S(" ") });
+            })
+            .unwrap_err();
+        assert!(matches!(error, Error::UserError(UserError::InvalidFacetHierarchySeparator)));
+    }
+
+    #[test]
+    fn set_date_fields() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("published_at") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "published_at": "2023-06-15T12:00:00Z" },
+                { "id": 1, "published_at": "2024-03-01T08:30:00+02:00" },
+                { "id": 2, "published_at": "not a date" },
+                { "id": 3, "published_at": 1704067200 },
+            ]))
+            .unwrap();
+
+        // Without the setting the dates are plain strings
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("published_at > '2024-01-01T00:00:00Z'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert!(docids.is_empty());
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_date_fields(hashset! { S("published_at") });
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("published_at > '2024-01-01T00:00:00Z'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![1]);
+        let filter = Filter::from_str("published_at >= '2024-01-01T00:00:00Z'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![1, 3]);
+        let filter =
+            Filter::from_str("published_at = '2023-06-15T14:00:00+02:00'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        let filter = Filter::from_str("published_at = 'not a date'").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn set_merge_strategies() {
+        let mut index = TempIndex::new();
+        index.index_documents_config.update_method = IndexDocumentsMethod::UpdateDocuments;
+
+        index
+            .update_settings(|settings| {
+                settings.set_merge_strategies(btreemap! {
+                    S("tags") => MergeStrategy::Union,
+                    S("comments") => MergeStrategy::Append,
+                    S("views") => MergeStrategy::Increment,
+                    S("lowest") => MergeStrategy::KeepMin,
+                    S("highest") => MergeStrategy::KeepMax,
+                });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "tags": ["a"], "comments": [{ "by": "kevin" }], "views": 1, "lowest": 5, "highest": 5, "title": "hello" },
+            ]))
+            .unwrap();
+
+        // the documents updated in the same batch are merged with the strategies too
+        index
+            .add_documents(documents!([
+                { "id": 0, "tags": ["a", "b"], "comments": [{ "by": "bob" }], "views": 2, "lowest": 7, "highest": 7, "title": "world" },
+                { "id": 0, "tags": ["c"], "views": 3, "lowest": 1, "highest": 1 },
+            ]))
+            .unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"tags":["a","b","c"],"comments":[{"by":"kevin"},{"by":"bob"}],"views":6,"lowest":1,"highest":7,"title":"world"}
+        "###);
+
+        // the flattened nested fields follow the strategy of their parent
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.fields_ids_map(&rtxn).unwrap().id("comments.by").is_some());
+        assert!(index.word_docids.get(&rtxn, "kevin").unwrap().is_some());
+        assert!(index.word_docids.get(&rtxn, "bob").unwrap().is_some());
+        drop(rtxn);
+
+        // replacing the documents ignores the strategies
+        index.index_documents_config.update_method = IndexDocumentsMethod::ReplaceDocuments;
+        index.add_documents(documents!([{ "id": 0, "views": 1 }])).unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"views":1}
+        "###);
+    }
+
+    #[test]
+    fn set_type_coercions() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings
+                    .set_filterable_fields(hashset! { S("price"), S("tags"), S("variants.size") });
+                settings.set_type_coercions(btreemap! {
+                    S("price") => TypeCoercion::Number,
+                    S("tags") => TypeCoercion::ArrayOfStrings,
+                    S("variants.size") => TypeCoercion::String,
+                });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": "42", "tags": "red", "variants": [{ "size": 38 }, { "size": "M" }] },
+                { "id": 1, "price": 12.5, "tags": ["blue", 3] },
+                { "id": 2, "price": "free", "tags": null },
+            ]))
+            .unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"price":42,"tags":["red"],"variants":[{"size":"38"},{"size":"M"}]}
+        {"id":1,"price":12.5,"tags":["blue","3"]}
+        {"id":2,"price":"free","tags":null}
+        "###);
+
+        let rtxn = index.read_txn().unwrap();
+        let filter = Filter::from_str("price > 40").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        let filter = Filter::from_str("variants.size = 38 AND tags = red").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        drop(rtxn);
+
+        // the documents already indexed are kept as is when the coercions are reset
+        index.update_settings(|settings| settings.reset_type_coercions()).unwrap();
+        index.add_documents(documents!([{ "id": 3, "price": "7" }])).unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"price":42,"tags":["red"],"variants":[{"size":"38"},{"size":"M"}]}
+        {"id":1,"price":12.5,"tags":["blue","3"]}
+        {"id":2,"price":"free","tags":null}
+        {"id":3,"price":"7"}
+        "###);
+    }
+
+    #[test]
+    fn set_ingestion_mapping() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_filterable_fields(hashset! { S("title"), S("year") });
+                settings.set_type_coercions(btreemap! { S("year") => TypeCoercion::Number });
+                settings.set_ingestion_mapping(btreemap! {
+                    S("legacy_id") => S("id"),
+                    S("properties.title") => S("title"),
+                    S("properties.meta.year") => S("year"),
+                });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "legacy_id": 0, "properties": { "title": "Carol", "meta": { "year": "2015" } } },
+                { "legacy_id": 1, "id": 10, "title": "ignored", "properties": { "title": "Gravity" } },
+            ]))
+            .unwrap();
+
+        db_snap!(index, documents, @r###"
+        {"id":0,"properties":{"meta":{}},"title":"Carol","year":2015}
+        {"id":1,"properties":{},"title":"Gravity"}
+        "###);
+
+        let rtxn = index.read_txn().unwrap();
+        assert!(index.fields_ids_map(&rtxn).unwrap().id("legacy_id").is_none());
+        let filter = Filter::from_str("year = 2015 AND title = Carol").unwrap().unwrap();
+        let docids = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(docids.into_iter().collect::<Vec<_>>(), vec![0]);
+        drop(rtxn);
+
+        // several fields can't be renamed into the same field
+        let error = index
+            .update_settings(|settings| {
+                settings.set_ingestion_mapping(btreemap! {
+                    S("name") => S("title"),
+                    S("properties.title") => S("title"),
+                });
+            })
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UserError(UserError::ConflictingIngestionMapping { ref target, .. }) if target == "title"
+        ));
+    }
+
     #[test]
     fn setting_impact_relevancy() {
         let mut index = TempIndex::new();
@@ -1892,15 +3179,33 @@ mod tests {
                     displayed_fields,
                     filterable_fields,
                     sortable_fields,
+                    non_flattened_fields,
+                    merge_strategies,
+                    type_coercions,
+                    ingestion_mapping,
+                    facet_hierarchy_separators,
+                    date_fields,
+                    suggestable_fields,
                     criteria,
                     stop_words,
                     non_separator_tokens,
                     separator_tokens,
                     dictionary,
                     distinct_field,
+                    static_boost,
+                    foreign_keys,
+                    query_rules,
+                    localized_attributes_rules,
+                    stemming,
+                    romanization,
+                    split_identifiers,
+                    document_schema,
+                    reranker,
                     synonyms,
                     primary_key,
                     authorize_typos,
+                    disable_typo_on_first_character,
+                    authorize_typos_on_numbers,
                     min_word_len_two_typos,
                     min_word_len_one_typo,
                     exact_words,
@@ -1915,15 +3220,33 @@ mod tests {
                 assert!(matches!(displayed_fields, Setting::NotSet));
                 assert!(matches!(filterable_fields, Setting::NotSet));
                 assert!(matches!(sortable_fields, Setting::NotSet));
+                assert!(matches!(non_flattened_fields, Setting::NotSet));
+                assert!(matches!(merge_strategies, Setting::NotSet));
+                assert!(matches!(type_coercions, Setting::NotSet));
+                assert!(matches!(ingestion_mapping, Setting::NotSet));
+                assert!(matches!(facet_hierarchy_separators, Setting::NotSet));
+                assert!(matches!(date_fields, Setting::NotSet));
+                assert!(matches!(suggestable_fields, Setting::NotSet));
                 assert!(matches!(criteria, Setting::NotSet));
                 assert!(matches!(stop_words, Setting::NotSet));
                 assert!(matches!(non_separator_tokens, Setting::NotSet));
                 assert!(matches!(separator_tokens, Setting::NotSet));
                 assert!(matches!(dictionary, Setting::NotSet));
                 assert!(matches!(distinct_field, Setting::NotSet));
+                assert!(matches!(static_boost, Setting::NotSet));
+                assert!(matches!(foreign_keys, Setting::NotSet));
+                assert!(matches!(query_rules, Setting::NotSet));
+                assert!(matches!(localized_attributes_rules, Setting::NotSet));
+                assert!(matches!(stemming, Setting::NotSet));
+                assert!(matches!(romanization, Setting::NotSet));
+                assert!(matches!(split_identifiers, Setting::NotSet));
+                assert!(matches!(document_schema, Setting::NotSet));
+                assert!(matches!(reranker, Setting::NotSet));
                 assert!(matches!(synonyms, Setting::NotSet));
                 assert!(matches!(primary_key, Setting::NotSet));
                 assert!(matches!(authorize_typos, Setting::NotSet));
+                assert!(matches!(disable_typo_on_first_character, Setting::NotSet));
+                assert!(matches!(authorize_typos_on_numbers, Setting::NotSet));
                 assert!(matches!(min_word_len_two_typos, Setting::NotSet));
                 assert!(matches!(min_word_len_one_typo, Setting::NotSet));
                 assert!(matches!(exact_words, Setting::NotSet));
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+/// Describes the type the values of a field are converted to when documents are added,
+/// so that the documents coming from heterogeneous sources share the same facet values.
+///
+/// The values that can't be converted, as well as `null`, are kept as is.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TypeCoercion {
+    /// Converts the strings representing a number, e.g. `"42"`, into numbers.
+    Number,
+    /// Converts the numbers and the booleans into strings.
+    String,
+    /// Converts the `"true"` and `"false"` strings into booleans.
+    Boolean,
+    /// Wraps the values that are not arrays into single-element arrays.
+    Array,
+    /// Wraps the values into arrays and converts their elements into numbers.
+    ArrayOfNumbers,
+    /// Wraps the values into arrays and converts their elements into strings.
+    ArrayOfStrings,
+}
+
+impl TypeCoercion {
+    /// Converts the value in place, returning `true` if it changed.
+    ///
+    /// The scalar conversions apply to each element of the arrays.
+    pub fn coerce(&self, value: &mut Value) -> bool {
+        match self {
+            TypeCoercion::Number => coerce_scalars(value, into_number),
+            TypeCoercion::String => coerce_scalars(value, into_string),
+            TypeCoercion::Boolean => coerce_scalars(value, into_boolean),
+            TypeCoercion::Array => into_array(value),
+            TypeCoercion::ArrayOfNumbers => {
+                let wrapped = into_array(value);
+                coerce_scalars(value, into_number) || wrapped
+            }
+            TypeCoercion::ArrayOfStrings => {
+                let wrapped = into_array(value);
+                coerce_scalars(value, into_string) || wrapped
+            }
+        }
+    }
+
+    /// Converts the values found at the dotted `path` of the value, e.g. `price` for
+    /// `{ "variants": [{ "price": "12" }] }` and the `variants.price` field, returning
+    /// `true` if any of them changed. The value itself is converted when the path is empty.
+    pub fn coerce_at_path(&self, value: &mut Value, path: &str) -> bool {
+        if path.is_empty() {
+            return self.coerce(value);
+        }
+
+        match value {
+            Value::Object(object) => {
+                let mut changed = false;
+                for (key, value) in object.iter_mut() {
+                    if key == path {
+                        changed |= self.coerce(value);
+                    } else if let Some(rest) =
+                        path.strip_prefix(key.as_str()).and_then(|rest| rest.strip_prefix('.'))
+                    {
+                        changed |= self.coerce_at_path(value, rest);
+                    }
+                }
+                changed
+            }
+            Value::Array(values) => values
+                .iter_mut()
+                .fold(false, |changed, value| self.coerce_at_path(value, path) || changed),
+            _ => false,
+        }
+    }
+}
+
+fn coerce_scalars(value: &mut Value, convert: fn(&Value) -> Option<Value>) -> bool {
+    match value {
+        Value::Array(values) => values
+            .iter_mut()
+            .fold(false, |changed, value| coerce_scalars(value, convert) || changed),
+        Value::Object(_) => false,
+        scalar => match convert(scalar) {
+            Some(converted) => {
+                *scalar = converted;
+                true
+            }
+            None => false,
+        },
+    }
+}
+
+fn into_number(value: &Value) -> Option<Value> {
+    let string = value.as_str()?.trim();
+    if let Ok(number) = string.parse::<i64>() {
+        Some(Value::from(number))
+    } else if let Ok(number) = string.parse::<u64>() {
+        Some(Value::from(number))
+    } else {
+        string.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number)
+    }
+}
+
+fn into_string(value: &Value) -> Option<Value> {
+    match value {
+        Value::Number(number) => Some(Value::String(number.to_string())),
+        Value::Bool(boolean) => Some(Value::String(boolean.to_string())),
+        _ => None,
+    }
+}
+
+fn into_boolean(value: &Value) -> Option<Value> {
+    let string = value.as_str()?.trim();
+    if string.eq_ignore_ascii_case("true") {
+        Some(Value::Bool(true))
+    } else if string.eq_ignore_ascii_case("false") {
+        Some(Value::Bool(false))
+    } else {
+        None
+    }
+}
+
+fn into_array(value: &mut Value) -> bool {
+    match value {
+        Value::Array(_) | Value::Null => false,
+        value => {
+            *value = Value::Array(vec![value.take()]);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::TypeCoercion;
+
+    #[test]
+    fn coerce_values() {
+        let coerce = |coercion: TypeCoercion, mut value| {
+            coercion.coerce(&mut value);
+            value
+        };
+
+        assert_eq!(coerce(TypeCoercion::Number, json!("42")), json!(42));
+        assert_eq!(coerce(TypeCoercion::Number, json!(" -4.5 ")), json!(-4.5));
+        assert_eq!(coerce(TypeCoercion::Number, json!(["1", 2, "three"])), json!([1, 2, "three"]));
+        assert_eq!(coerce(TypeCoercion::Number, json!("NaN")), json!("NaN"));
+        assert_eq!(coerce(TypeCoercion::String, json!(42)), json!("42"));
+        assert_eq!(coerce(TypeCoercion::String, json!([true, null])), json!(["true", null]));
+        assert_eq!(coerce(TypeCoercion::Boolean, json!("TRUE")), json!(true));
+        assert_eq!(coerce(TypeCoercion::Boolean, json!("yes")), json!("yes"));
+        assert_eq!(coerce(TypeCoercion::Array, json!("drama")), json!(["drama"]));
+        assert_eq!(coerce(TypeCoercion::Array, json!(null)), json!(null));
+        assert_eq!(coerce(TypeCoercion::ArrayOfNumbers, json!("42")), json!([42]));
+        assert_eq!(coerce(TypeCoercion::ArrayOfStrings, json!([1, "a"])), json!(["1", "a"]));
+        assert_eq!(
+            coerce(TypeCoercion::Number, json!({ "price": "12" })),
+            json!({ "price": "12" })
+        );
+    }
+
+    #[test]
+    fn coerce_nested_values() {
+        let mut value = json!([{ "price": "12" }, { "price": 13, "name": "a" }, "14"]);
+        assert!(TypeCoercion::Number.coerce_at_path(&mut value, "price"));
+        assert_eq!(value, json!([{ "price": 12 }, { "price": 13, "name": "a" }, "14"]));
+
+        let mut value = json!({ "a": { "b": "1" }, "a.b": "2", "c": "3" });
+        assert!(TypeCoercion::Number.coerce_at_path(&mut value, "a.b"));
+        assert_eq!(value, json!({ "a": { "b": 1 }, "a.b": 2, "c": "3" }));
+
+        let mut value = json!({ "a": 1 });
+        assert!(!TypeCoercion::Number.coerce_at_path(&mut value, "a.b"));
+    }
+}
@@ -0,0 +1,69 @@
+use charabia::{Language, Script};
+use heed::RwTxn;
+
+use super::derived_words_fst::derive_words_fst;
+use crate::romanization::{
+    romanizable_script, romanize, word_romanization_prefix, RomanizableScript,
+};
+use crate::{Index, Result};
+
+/// Computes the FST associating the latin transcriptions of the words of the index
+/// written in kana or in chinese characters to these words.
+pub struct WordRomanizationsFst<'t, 'i> {
+    wtxn: &'t mut RwTxn<'i>,
+    index: &'i Index,
+    new_words: Option<fst::Set<Vec<u8>>>,
+}
+
+impl<'t, 'i> WordRomanizationsFst<'t, 'i> {
+    pub fn new(wtxn: &'t mut RwTxn<'i>, index: &'i Index) -> WordRomanizationsFst<'t, 'i> {
+        WordRomanizationsFst { wtxn, index, new_words: None }
+    }
+
+    /// Only transcribes the words added to the index, the transcriptions of the other words
+    /// being up to date.
+    pub fn new_words(&mut self, new_words: fst::Set<Vec<u8>>) {
+        self.new_words = Some(new_words);
+    }
+
+    #[logging_timer::time("WordRomanizationsFst::{}")]
+    pub fn execute(self) -> Result<()> {
+        puffin::profile_function!();
+
+        let rtxn = &*self.wtxn;
+        let index = self.index;
+        let romanization = index.romanization(rtxn)?;
+        let japanese_docids = index
+            .script_language_documents_ids(rtxn, &(Script::Cj, Language::Jpn))?
+            .unwrap_or_default();
+
+        let word_romanizations_fst = derive_words_fst(
+            rtxn,
+            index,
+            index.word_romanizations_fst(rtxn)?,
+            self.new_words.as_ref(),
+            |word, keys| {
+                let script = match romanizable_script(word) {
+                    Some(script) if romanization => script,
+                    _ => return Ok(()),
+                };
+                // The words in chinese characters are only transcribed into pinyin when they
+                // appear in documents that are not japanese, as they are kanji otherwise.
+                if script == RomanizableScript::Han {
+                    let mut docids = index.word_docids.get(rtxn, word)?.unwrap_or_default();
+                    docids |= index.exact_word_docids.get(rtxn, word)?.unwrap_or_default();
+                    if (docids - &japanese_docids).is_empty() {
+                        return Ok(());
+                    }
+                }
+                if let Some(romanized) = romanize(word, script) {
+                    keys.insert(word_romanization_prefix(&romanized) + word);
+                }
+                Ok(())
+            },
+        )?;
+        self.index.put_word_romanizations_fst(self.wtxn, &word_romanizations_fst)?;
+
+        Ok(())
+    }
+}
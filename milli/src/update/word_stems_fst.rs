@@ -0,0 +1,56 @@
+use heed::RwTxn;
+
+use super::derived_words_fst::derive_words_fst;
+use crate::stemming::{stemmer, word_stem_prefix};
+use crate::{Index, Result};
+
+/// Computes the FST associating the stems of the words of the index to these words,
+/// in each of the languages the index is configured to stem.
+pub struct WordStemsFst<'t, 'i> {
+    wtxn: &'t mut RwTxn<'i>,
+    index: &'i Index,
+    new_words: Option<fst::Set<Vec<u8>>>,
+}
+
+impl<'t, 'i> WordStemsFst<'t, 'i> {
+    pub fn new(wtxn: &'t mut RwTxn<'i>, index: &'i Index) -> WordStemsFst<'t, 'i> {
+        WordStemsFst { wtxn, index, new_words: None }
+    }
+
+    /// Only stems the words added to the index, the stems of the other words being up to date.
+    pub fn new_words(&mut self, new_words: fst::Set<Vec<u8>>) {
+        self.new_words = Some(new_words);
+    }
+
+    #[logging_timer::time("WordStemsFst::{}")]
+    pub fn execute(self) -> Result<()> {
+        puffin::profile_function!();
+
+        let stemmers: Vec<_> = self
+            .index
+            .stemming(self.wtxn)?
+            .into_iter()
+            .filter_map(|locale| stemmer(&locale).map(|stemmer| (locale, stemmer)))
+            .collect();
+
+        let word_stems_fst = derive_words_fst(
+            self.wtxn,
+            self.index,
+            self.index.word_stems_fst(self.wtxn)?,
+            self.new_words.as_ref(),
+            |word, keys| {
+                for (locale, stemmer) in &stemmers {
+                    // The words that are their own stem are already matched as is.
+                    let stem = stemmer.stem(word);
+                    if stem != word {
+                        keys.insert(word_stem_prefix(locale, &stem) + word);
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        self.index.put_word_stems_fst(self.wtxn, &word_stems_fst)?;
+
+        Ok(())
+    }
+}
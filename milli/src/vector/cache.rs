@@ -0,0 +1,129 @@
+//! Reuse of the embeddings generated by a previous indexing.
+//!
+//! The hash of the embedder options and of the rendered prompt is stored next to the generated
+//! embeddings of each document. When a document must be embedded again from the same hash,
+//! typically when the vector store is rebuilt after a settings update, its committed embeddings
+//! are reused instead of calling the embedder again.
+
+use heed::RoTxn;
+
+use super::{Distance, EmbedderOptions, Embedding};
+use crate::index::stored_embeddings;
+use crate::{DocumentId, Index, Result};
+
+/// Hashes the options of an embedder, a change of model invalidates all its cached embeddings.
+pub fn options_hash(options: &EmbedderOptions) -> u64 {
+    fxhash::hash64(options)
+}
+
+/// Hashes the prompt rendered for a document with the hash of the options of the embedder.
+pub fn prompt_hash(options_hash: u64, prompt: &str) -> u64 {
+    fxhash::hash64(&(options_hash, prompt))
+}
+
+/// Reads the embeddings committed for an embedder, the ones being written by the current
+/// indexing are not visible.
+pub(crate) struct EmbeddingCache<'a> {
+    index: &'a Index,
+    rtxn: RoTxn<'a>,
+    /// The id and the distance of the embedder in the committed index.
+    embedder: Option<(u8, Distance)>,
+    options_hash: u64,
+}
+
+impl<'a> EmbeddingCache<'a> {
+    pub fn new(index: &'a Index, embedder_name: &str, options_hash: u64) -> Result<Self> {
+        let rtxn = index.read_txn()?;
+        let embedder_index = index.embedder_category_id.get(&rtxn, embedder_name)?;
+        let distance = index
+            .embedding_configs(&rtxn)?
+            .into_iter()
+            .find_map(|(name, config)| (name == embedder_name).then_some(config.distance));
+        Ok(EmbeddingCache { index, rtxn, embedder: embedder_index.zip(distance), options_hash })
+    }
+
+    /// Hashes the prompt rendered for a document.
+    pub fn hash(&self, prompt: &str) -> u64 {
+        prompt_hash(self.options_hash, prompt)
+    }
+
+    /// Returns the committed embeddings of the document when they were generated from the same hash.
+    pub fn get(&self, docid: DocumentId, hash: u64) -> Result<Option<Vec<Embedding>>> {
+        let Some((embedder_index, distance)) = self.embedder else { return Ok(None) };
+        let key = (embedder_index as u16, docid);
+        if self.index.vector_prompt_hashes.get(&self.rtxn, &key)? != Some(hash) {
+            return Ok(None);
+        }
+
+        let (rtxn, index) = (&self.rtxn, self.index);
+        let embeddings = match distance {
+            Distance::Cosine => stored_embeddings(rtxn, index.vector_arroy, embedder_index, docid)?,
+            Distance::DotProduct => {
+                stored_embeddings(rtxn, index.vector_arroy_dot_product, embedder_index, docid)?
+            }
+            Distance::Euclidean => {
+                stored_embeddings(rtxn, index.vector_arroy_euclidean, embedder_index, docid)?
+            }
+        };
+        Ok(Some(embeddings).filter(|embeddings| !embeddings.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use big_s::S;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::update::Setting;
+    use crate::vector::settings::{EmbedderSettings, EmbeddingSettings};
+
+    #[test]
+    fn hashes_are_stable() {
+        let options = EmbedderOptions::default();
+        assert_eq!(options_hash(&options), options_hash(&options.clone()));
+        let hash = options_hash(&options);
+        assert_eq!(prompt_hash(hash, "a prompt"), prompt_hash(hash, "a prompt"));
+        assert_ne!(prompt_hash(hash, "a prompt"), prompt_hash(hash, "another prompt"));
+        assert_ne!(prompt_hash(hash, "a prompt"), prompt_hash(hash + 1, "a prompt"));
+    }
+
+    #[test]
+    fn reuse_committed_embeddings_with_the_same_hash() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                let mut embedders = std::collections::BTreeMap::default();
+                embedders.insert(
+                    S("manual"),
+                    Setting::Set(EmbeddingSettings {
+                        embedder_options: Setting::Set(EmbedderSettings::UserProvided(
+                            crate::vector::settings::UserProvidedSettings { dimensions: 2 },
+                        )),
+                        document_template: Setting::NotSet,
+                        binary_quantized: Setting::NotSet,
+                        distance: Setting::NotSet,
+                        request_policy: Setting::NotSet,
+                    }),
+                );
+                settings.set_embedder_settings(embedders);
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([{ "id": 0, "_vectors": { "manual": [1.0, 2.0] } }]))
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let embedder_index = index.embedder_category_id.get(&wtxn, "manual").unwrap().unwrap();
+        index.vector_prompt_hashes.put(&mut wtxn, &(embedder_index as u16, 0), &42).unwrap();
+        wtxn.commit().unwrap();
+
+        let cache = EmbeddingCache::new(&index, "manual", 0).unwrap();
+        assert_eq!(cache.get(0, 42).unwrap(), Some(vec![vec![1.0, 2.0]]));
+        assert_eq!(cache.get(0, 43).unwrap(), None);
+        assert_eq!(cache.get(1, 42).unwrap(), None);
+
+        let cache = EmbeddingCache::new(&index, "unknown", 0).unwrap();
+        assert_eq!(cache.get(0, 42).unwrap(), None);
+    }
+}
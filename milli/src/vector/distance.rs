@@ -0,0 +1,102 @@
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+/// The distance used to compare the vectors of an embedder.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum Distance {
+    /// The angle between the vectors, regardless of their norms.
+    #[default]
+    Cosine,
+    /// The opposite of the dot product, for models trained on unnormalized vectors.
+    DotProduct,
+    /// The euclidean distance between the vectors.
+    Euclidean,
+}
+
+impl Distance {
+    /// Computes the distance between two full-precision vectors, the lower the closer.
+    pub fn distance(self, left: &[f32], right: &[f32]) -> f32 {
+        match self {
+            Distance::Cosine => {
+                let left_norm = dot_product(left, left).sqrt();
+                let right_norm = dot_product(right, right).sqrt();
+                let norms = left_norm * right_norm;
+                if norms == 0.0 {
+                    return 1.0;
+                }
+                1.0 - dot_product(left, right) / norms
+            }
+            Distance::DotProduct => -dot_product(left, right),
+            Distance::Euclidean => left
+                .iter()
+                .zip(right)
+                .map(|(left, right)| (left - right) * (left - right))
+                .sum::<f32>()
+                .sqrt(),
+        }
+    }
+
+    /// Maps a distance computed by [`Self::distance`] to a similarity between `0.0` and `1.0`,
+    /// the greater the closer.
+    pub fn similarity(self, distance: f32) -> f32 {
+        match self {
+            // the cosine distance is between 0 (same direction) and 2 (opposite directions)
+            Distance::Cosine => (1.0 - distance / 2.0).clamp(0.0, 1.0),
+            // the dot product is unbounded, the logistic function maps it into ]0, 1[
+            Distance::DotProduct => 1.0 / (1.0 + distance.exp()),
+            Distance::Euclidean => 1.0 / (1.0 + distance),
+        }
+    }
+}
+
+fn dot_product(left: &[f32], right: &[f32]) -> f32 {
+    left.iter().zip(right).map(|(left, right)| left * right).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cosine_ignores_the_norms() {
+        assert!(Distance::Cosine.distance(&[1.0, 2.0], &[2.0, 4.0]).abs() < 1e-6);
+        assert!((Distance::Cosine.distance(&[1.0, 0.0], &[-3.0, 0.0]) - 2.0).abs() < 1e-6);
+        assert_eq!(Distance::Cosine.distance(&[0.0, 0.0], &[1.0, 0.0]), 1.0);
+    }
+
+    #[test]
+    fn dot_product_favors_the_longest_vectors() {
+        let target = [1.0, 1.0];
+        assert!(
+            Distance::DotProduct.distance(&target, &[2.0, 2.0])
+                < Distance::DotProduct.distance(&target, &[1.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn euclidean_distance() {
+        assert_eq!(Distance::Euclidean.distance(&[0.0, 0.0], &[3.0, 4.0]), 5.0);
+    }
+
+    #[test]
+    fn similarities_are_between_zero_and_one() {
+        let target = [1.0, 2.0];
+        let vectors = [[1.0, 2.0], [2.0, 4.0], [-1.0, -2.0], [100.0, -3.0], [0.0, 0.0]];
+        for distance in [Distance::Cosine, Distance::DotProduct, Distance::Euclidean] {
+            let similarities: Vec<_> = vectors
+                .iter()
+                .map(|vector| distance.similarity(distance.distance(&target, vector)))
+                .collect();
+            assert!(similarities.iter().all(|s| (0.0..=1.0).contains(s)), "{similarities:?}");
+        }
+
+        assert_eq!(Distance::Cosine.similarity(0.0), 1.0);
+        assert_eq!(Distance::Cosine.similarity(2.0), 0.0);
+        assert_eq!(Distance::DotProduct.similarity(0.0), 0.5);
+        assert!(Distance::DotProduct.similarity(-10.0) > Distance::DotProduct.similarity(-1.0));
+        assert_eq!(Distance::Euclidean.similarity(0.0), 1.0);
+        assert_eq!(Distance::Euclidean.similarity(1.0), 0.5);
+    }
+}
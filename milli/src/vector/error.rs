@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use hf_hub::api::sync::ApiError;
 
 use crate::error::FaultSource;
+use crate::vector::ollama::OllamaError;
 use crate::vector::openai::OpenAiError;
 
 #[derive(Debug, thiserror::Error)]
@@ -65,6 +66,26 @@ pub enum EmbedErrorKind {
     OpenAiTooManyTokens(OpenAiError),
     #[error("received unhandled HTTP status code {0} from OpenAI")]
     OpenAiUnhandledStatusCode(u16),
+    #[error("could not reach Ollama: {0}")]
+    OllamaNetwork(reqwest::Error),
+    #[error("unexpected response from Ollama: {0}")]
+    OllamaUnexpected(reqwest::Error),
+    #[error("model not found, it must be pulled on the Ollama server first: {0}")]
+    OllamaModelNotFound(OllamaError),
+    #[error("received internal error from Ollama: {0}")]
+    OllamaInternalServerError(OllamaError),
+    #[error("received unhandled HTTP status code {0} from Ollama")]
+    OllamaUnhandledStatusCode(u16),
+    #[error("could not reach the REST embedder: {0}")]
+    RestNetwork(reqwest::Error),
+    #[error("unexpected response from the REST embedder: {0}")]
+    RestUnexpected(reqwest::Error),
+    #[error("received internal error {0} from the REST embedder: {1}")]
+    RestServerError(u16, String),
+    #[error("received unhandled HTTP status code {0} from the REST embedder: {1}")]
+    RestUnhandledStatusCode(u16, String),
+    #[error("could not extract the embeddings from the response of the REST embedder: {0}")]
+    RestResponseEmbeddings(String),
     #[error("attempt to embed the following text in a configuration where embeddings must be user provided: {0:?}")]
     ManualEmbed(String),
 }
@@ -114,6 +135,46 @@ impl EmbedError {
         Self { kind: EmbedErrorKind::OpenAiUnhandledStatusCode(code), fault: FaultSource::Bug }
     }
 
+    pub fn ollama_network(inner: reqwest::Error) -> Self {
+        Self { kind: EmbedErrorKind::OllamaNetwork(inner), fault: FaultSource::Runtime }
+    }
+
+    pub fn ollama_unexpected(inner: reqwest::Error) -> EmbedError {
+        Self { kind: EmbedErrorKind::OllamaUnexpected(inner), fault: FaultSource::Bug }
+    }
+
+    pub(crate) fn ollama_model_not_found(inner: OllamaError) -> EmbedError {
+        Self { kind: EmbedErrorKind::OllamaModelNotFound(inner), fault: FaultSource::User }
+    }
+
+    pub(crate) fn ollama_internal_server_error(inner: OllamaError) -> EmbedError {
+        Self { kind: EmbedErrorKind::OllamaInternalServerError(inner), fault: FaultSource::Runtime }
+    }
+
+    pub(crate) fn ollama_unhandled_status_code(code: u16) -> EmbedError {
+        Self { kind: EmbedErrorKind::OllamaUnhandledStatusCode(code), fault: FaultSource::Bug }
+    }
+
+    pub fn rest_network(inner: reqwest::Error) -> Self {
+        Self { kind: EmbedErrorKind::RestNetwork(inner), fault: FaultSource::Runtime }
+    }
+
+    pub fn rest_unexpected(inner: reqwest::Error) -> EmbedError {
+        Self { kind: EmbedErrorKind::RestUnexpected(inner), fault: FaultSource::User }
+    }
+
+    pub(crate) fn rest_server_error(code: u16, body: String) -> EmbedError {
+        Self { kind: EmbedErrorKind::RestServerError(code, body), fault: FaultSource::Runtime }
+    }
+
+    pub(crate) fn rest_unhandled_status_code(code: u16, body: String) -> EmbedError {
+        Self { kind: EmbedErrorKind::RestUnhandledStatusCode(code, body), fault: FaultSource::User }
+    }
+
+    pub(crate) fn rest_response_embeddings(inner: String) -> EmbedError {
+        Self { kind: EmbedErrorKind::RestResponseEmbeddings(inner), fault: FaultSource::User }
+    }
+
     pub(crate) fn embed_on_manual_embedder(texts: String) -> EmbedError {
         Self { kind: EmbedErrorKind::ManualEmbed(texts), fault: FaultSource::User }
     }
@@ -190,6 +251,37 @@ impl NewEmbedderError {
     pub fn openai_invalid_api_key_format(inner: reqwest::header::InvalidHeaderValue) -> Self {
         Self { kind: NewEmbedderErrorKind::InvalidApiKeyFormat(inner), fault: FaultSource::User }
     }
+
+    pub fn ollama_initialize_web_client(inner: reqwest::Error) -> Self {
+        Self { kind: NewEmbedderErrorKind::InitWebClient(inner), fault: FaultSource::Runtime }
+    }
+
+    pub fn rest_initialize_web_client(inner: reqwest::Error) -> Self {
+        Self { kind: NewEmbedderErrorKind::InitWebClient(inner), fault: FaultSource::Runtime }
+    }
+
+    pub fn rest_invalid_api_key_format(inner: reqwest::header::InvalidHeaderValue) -> Self {
+        Self { kind: NewEmbedderErrorKind::InvalidApiKeyFormat(inner), fault: FaultSource::User }
+    }
+
+    pub fn rest_invalid_request_template(inner: String) -> Self {
+        Self { kind: NewEmbedderErrorKind::InvalidRequestTemplate(inner), fault: FaultSource::User }
+    }
+
+    pub fn rest_invalid_response_path(inner: String) -> Self {
+        Self { kind: NewEmbedderErrorKind::InvalidResponsePath(inner), fault: FaultSource::User }
+    }
+
+    pub fn initialize_runtime(inner: std::io::Error) -> Self {
+        Self { kind: NewEmbedderErrorKind::InitRuntime(inner), fault: FaultSource::Runtime }
+    }
+
+    pub fn could_not_determine_dimension(inner: EmbedError) -> NewEmbedderError {
+        Self {
+            kind: NewEmbedderErrorKind::CouldNotDetermineDimension(inner),
+            fault: FaultSource::Runtime,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -241,4 +333,12 @@ pub enum NewEmbedderErrorKind {
     InitWebClient(reqwest::Error),
     #[error("The API key passed to Authorization error was in an invalid format: {0}")]
     InvalidApiKeyFormat(reqwest::header::InvalidHeaderValue),
+    // ollama
+    #[error("initializing runtime for sending embedding requests failed: {0}")]
+    InitRuntime(std::io::Error),
+    // rest
+    #[error("invalid request template: {0}")]
+    InvalidRequestTemplate(String),
+    #[error("invalid path to the embeddings in the response: {0}")]
+    InvalidResponsePath(String),
 }
@@ -4,13 +4,22 @@ use std::sync::Arc;
 use self::error::{EmbedError, NewEmbedderError};
 use crate::prompt::{Prompt, PromptData};
 
+pub mod cache;
+pub mod distance;
 pub mod error;
 pub mod hf;
 pub mod manual;
+pub mod ollama;
 pub mod openai;
+pub mod policy;
+pub mod quantized;
+pub mod rerank;
+pub mod rest;
 pub mod settings;
 
+pub use self::distance::Distance;
 pub use self::error::Error;
+pub use self::policy::RequestPolicy;
 
 pub type Embedding = Vec<f32>;
 
@@ -75,6 +84,8 @@ impl<F> Embeddings<F> {
 pub enum Embedder {
     HuggingFace(hf::Embedder),
     OpenAi(openai::Embedder),
+    Ollama(ollama::Embedder),
+    Rest(rest::Embedder),
     UserProvided(manual::Embedder),
 }
 
@@ -82,6 +93,16 @@ pub enum Embedder {
 pub struct EmbeddingConfig {
     pub embedder_options: EmbedderOptions,
     pub prompt: PromptData,
+    /// Whether the search retrieves its candidates with the 1-bit quantized vectors,
+    /// only re-scoring the best of them with the full-precision vectors.
+    #[serde(default)]
+    pub binary_quantized: bool,
+    /// The distance used to compare the vectors of this embedder.
+    #[serde(default)]
+    pub distance: Distance,
+    /// How the documents are sent to the embedder during the indexing.
+    #[serde(default)]
+    pub request_policy: RequestPolicy,
     // TODO: add metrics and anything needed
 }
 
@@ -127,6 +148,8 @@ impl IntoIterator for EmbeddingConfigs {
 pub enum EmbedderOptions {
     HuggingFace(hf::EmbedderOptions),
     OpenAi(openai::EmbedderOptions),
+    Ollama(ollama::EmbedderOptions),
+    Rest(rest::EmbedderOptions),
     UserProvided(manual::EmbedderOptions),
 }
 
@@ -144,6 +167,10 @@ impl EmbedderOptions {
     pub fn openai(api_key: Option<String>) -> Self {
         Self::OpenAi(openai::EmbedderOptions::with_default_model(api_key))
     }
+
+    pub fn ollama(url: Option<String>) -> Self {
+        Self::Ollama(ollama::EmbedderOptions::with_default_model(url))
+    }
 }
 
 impl Embedder {
@@ -151,6 +178,8 @@ impl Embedder {
         Ok(match options {
             EmbedderOptions::HuggingFace(options) => Self::HuggingFace(hf::Embedder::new(options)?),
             EmbedderOptions::OpenAi(options) => Self::OpenAi(openai::Embedder::new(options)?),
+            EmbedderOptions::Ollama(options) => Self::Ollama(ollama::Embedder::new(options)?),
+            EmbedderOptions::Rest(options) => Self::Rest(rest::Embedder::new(options)?),
             EmbedderOptions::UserProvided(options) => {
                 Self::UserProvided(manual::Embedder::new(options))
             }
@@ -164,6 +193,8 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed(texts),
             Embedder::OpenAi(embedder) => embedder.embed(texts).await,
+            Embedder::Ollama(embedder) => embedder.embed(texts).await,
+            Embedder::Rest(embedder) => embedder.embed(texts).await,
             Embedder::UserProvided(embedder) => embedder.embed(texts),
         }
     }
@@ -175,6 +206,8 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.embed_chunks(text_chunks),
             Embedder::OpenAi(embedder) => embedder.embed_chunks(text_chunks).await,
+            Embedder::Ollama(embedder) => embedder.embed_chunks(text_chunks).await,
+            Embedder::Rest(embedder) => embedder.embed_chunks(text_chunks).await,
             Embedder::UserProvided(embedder) => embedder.embed_chunks(text_chunks),
         }
     }
@@ -183,6 +216,8 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.chunk_count_hint(),
             Embedder::OpenAi(embedder) => embedder.chunk_count_hint(),
+            Embedder::Ollama(embedder) => embedder.chunk_count_hint(),
+            Embedder::Rest(embedder) => embedder.chunk_count_hint(),
             Embedder::UserProvided(_) => 1,
         }
     }
@@ -191,6 +226,8 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::OpenAi(embedder) => embedder.prompt_count_in_chunk_hint(),
+            Embedder::Ollama(embedder) => embedder.prompt_count_in_chunk_hint(),
+            Embedder::Rest(embedder) => embedder.prompt_count_in_chunk_hint(),
             Embedder::UserProvided(_) => 1,
         }
     }
@@ -199,6 +236,8 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.dimensions(),
             Embedder::OpenAi(embedder) => embedder.dimensions(),
+            Embedder::Ollama(embedder) => embedder.dimensions(),
+            Embedder::Rest(embedder) => embedder.dimensions(),
             Embedder::UserProvided(embedder) => embedder.dimensions(),
         }
     }
@@ -207,11 +246,36 @@ impl Embedder {
         match self {
             Embedder::HuggingFace(embedder) => embedder.distribution(),
             Embedder::OpenAi(embedder) => embedder.distribution(),
+            Embedder::Ollama(embedder) => embedder.distribution(),
+            Embedder::Rest(embedder) => embedder.distribution(),
             Embedder::UserProvided(_embedder) => None,
         }
     }
 }
 
+/// Runs the future to completion on a dedicated thread with its own runtime.
+///
+/// The embedders are built from both sync and async contexts, where blocking on
+/// the runtime of the caller is not possible.
+fn block_on_dedicated_runtime<F>(future: F) -> std::io::Result<F::Output>
+where
+    F: std::future::Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_io()
+                    .enable_time()
+                    .build()?;
+                Ok(rt.block_on(future))
+            })
+            .join()
+            .expect("the thread running the future panicked")
+    })
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DistributionShift {
     pub current_mean: f32,
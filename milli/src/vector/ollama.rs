@@ -0,0 +1,274 @@
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::error::{EmbedError, NewEmbedderError};
+use super::{block_on_dedicated_runtime, DistributionShift, Embedding, Embeddings};
+
+pub const OLLAMA_EMBEDDINGS_URL: &str = "http://localhost:11434/api/embeddings";
+pub const OLLAMA_DEFAULT_MODEL: &str = "nomic-embed-text";
+
+#[derive(Debug)]
+pub struct Embedder {
+    client: reqwest::Client,
+    options: EmbedderOptions,
+    dimensions: usize,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct EmbedderOptions {
+    /// The URL of the embeddings route of the Ollama server.
+    pub url: Option<String>,
+    /// The name of the model pulled on the Ollama server.
+    pub embedding_model: String,
+}
+
+impl EmbedderOptions {
+    pub fn with_default_model(url: Option<String>) -> Self {
+        Self { url, embedding_model: OLLAMA_DEFAULT_MODEL.to_string() }
+    }
+
+    pub fn with_embedding_model(url: Option<String>, embedding_model: String) -> Self {
+        Self { url, embedding_model }
+    }
+}
+
+impl Embedder {
+    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            reqwest::header::HeaderValue::from_static("application/json"),
+        );
+        let client = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .build()
+            .map_err(NewEmbedderError::ollama_initialize_web_client)?;
+
+        let mut embedder = Self { client, options, dimensions: 0 };
+        embedder.dimensions = embedder.infer_dimensions()?;
+        Ok(embedder)
+    }
+
+    /// Embeds a test text to find the dimensions of the model.
+    fn infer_dimensions(&self) -> Result<usize, NewEmbedderError> {
+        let embedding = block_on_dedicated_runtime(self.try_embed("test"))
+            .map_err(NewEmbedderError::initialize_runtime)?
+            .map_err(NewEmbedderError::could_not_determine_dimension)?;
+        Ok(embedding.len())
+    }
+
+    fn url(&self) -> &str {
+        self.options.url.as_deref().unwrap_or(OLLAMA_EMBEDDINGS_URL)
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Embeddings<f32>>, EmbedError> {
+        // Ollama embeds a single prompt per request
+        futures::future::try_join_all(texts.iter().map(|text| self.embed_one(text))).await
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Embeddings<f32>, EmbedError> {
+        for attempt in 0..3 {
+            match self.try_embed(text).await {
+                Ok(embedding) => return Ok(Embeddings::from_single_embedding(embedding)),
+                // the server is probably still loading the model
+                Err(error) if error.fault == crate::error::FaultSource::Runtime => {
+                    log::warn!("Failed: {}", error);
+                    let duration = tokio::time::Duration::from_millis(10u64.pow(attempt + 1));
+                    log::warn!("Attempt #{}, retrying after {}ms.", attempt, duration.as_millis());
+                    tokio::time::sleep(duration).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.try_embed(text).await.map(Embeddings::from_single_embedding)
+    }
+
+    async fn try_embed(&self, text: &str) -> Result<Embedding, EmbedError> {
+        log::trace!("Received prompt: {}", text);
+        let request = OllamaRequest { model: &self.options.embedding_model, prompt: text };
+        let response = self
+            .client
+            .post(self.url())
+            .json(&request)
+            .send()
+            .await
+            .map_err(EmbedError::ollama_network)?;
+
+        match response.status() {
+            status if status.is_success() => (),
+            StatusCode::NOT_FOUND => {
+                let error_response: OllamaError =
+                    response.json().await.map_err(EmbedError::ollama_unexpected)?;
+                return Err(EmbedError::ollama_model_not_found(error_response));
+            }
+            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+                let error_response: OllamaError =
+                    response.json().await.map_err(EmbedError::ollama_unexpected)?;
+                return Err(EmbedError::ollama_internal_server_error(error_response));
+            }
+            code => return Err(EmbedError::ollama_unhandled_status_code(code.as_u16())),
+        }
+
+        let response: OllamaResponse =
+            response.json().await.map_err(EmbedError::ollama_unexpected)?;
+        Ok(response.embedding)
+    }
+
+    pub async fn embed_chunks(
+        &self,
+        text_chunks: Vec<Vec<String>>,
+    ) -> Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        futures::future::try_join_all(text_chunks.into_iter().map(|prompts| self.embed(prompts)))
+            .await
+    }
+
+    pub fn chunk_count_hint(&self) -> usize {
+        10
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        10
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        None
+    }
+}
+
+// ollama api structs
+
+#[derive(Debug, Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    embedding: Embedding,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaError {
+    error: String,
+}
+
+impl Display for OllamaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// Answers each request with the next of the given status codes and bodies, returning the
+    /// bodies of the requests once all the responses are sent.
+    fn mock_ollama(responses: Vec<(u16, Value)>) -> (String, JoinHandle<Vec<Value>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/embeddings", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut request = vec![0; content_length];
+                reader.read_exact(&mut request).unwrap();
+                requests.push(serde_json::from_slice(&request).unwrap());
+
+                // the connection is closed to receive each request on a new one
+                let body = body.to_string();
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn embed_with_the_model() {
+        let (url, server) = mock_ollama(vec![
+            (200, json!({ "embedding": [0.0, 0.0, 0.0] })),
+            (200, json!({ "embedding": [0.1, 0.2, 0.3] })),
+        ]);
+        let options = EmbedderOptions::with_embedding_model(Some(url), "all-minilm".to_string());
+        let embedder = Embedder::new(options).unwrap();
+        assert_eq!(embedder.dimensions(), 3);
+
+        let embeddings =
+            block_on_dedicated_runtime(embedder.embed(vec!["hello".to_string()])).unwrap().unwrap();
+        assert_eq!(embeddings.len(), 1);
+        assert_eq!(embeddings[0].as_inner(), &[0.1, 0.2, 0.3]);
+
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests,
+            vec![
+                json!({ "model": "all-minilm", "prompt": "test" }),
+                json!({ "model": "all-minilm", "prompt": "hello" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn retry_while_the_model_loads() {
+        let (url, server) = mock_ollama(vec![
+            (200, json!({ "embedding": [0.0, 0.0] })),
+            (503, json!({ "error": "the model is loading" })),
+            (200, json!({ "embedding": [0.4, 0.5] })),
+        ]);
+        let embedder = Embedder::new(EmbedderOptions::with_default_model(Some(url))).unwrap();
+
+        let embeddings =
+            block_on_dedicated_runtime(embedder.embed(vec!["hello".to_string()])).unwrap().unwrap();
+        assert_eq!(embeddings[0].as_inner(), &[0.4, 0.5]);
+        assert_eq!(server.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn model_not_found() {
+        let (url, server) = mock_ollama(vec![(
+            404,
+            json!({ "error": "model 'missing' not found, try pulling it first" }),
+        )]);
+        let options = EmbedderOptions::with_embedding_model(Some(url), "missing".to_string());
+        let error = Embedder::new(options).unwrap_err();
+        assert!(error.to_string().contains(
+            "model not found, it must be pulled on the Ollama server first: model 'missing' not found, try pulling it first"
+        ), "{error}");
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+}
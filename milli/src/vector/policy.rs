@@ -0,0 +1,184 @@
+//! Limits the pressure put on the embedders by the indexing.
+//!
+//! The request policy of an embedder bounds the number of requests sent concurrently and per
+//! second, retries the requests failing at runtime with an exponential backoff, and lets a
+//! number of documents fail to be embedded without failing the whole indexing.
+
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How the documents are sent to an embedder during the indexing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestPolicy {
+    /// The maximum number of requests sent concurrently, defaults to the hint of the embedder.
+    pub max_concurrent_requests: Option<usize>,
+    /// The maximum number of requests sent per second, unlimited by default.
+    pub max_requests_per_second: Option<u32>,
+    /// The number of times a request failing at runtime is sent again.
+    pub max_retries: u32,
+    /// The delay before the first retry, doubled at each subsequent retry.
+    pub initial_backoff_ms: u64,
+    /// The number of documents that can fail to be embedded without failing the indexing.
+    pub max_failed_documents: u64,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: None,
+            max_requests_per_second: None,
+            max_retries: 0,
+            initial_backoff_ms: 100,
+            max_failed_documents: 0,
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// The number of requests sent concurrently for an embedder suggesting `hint` of them.
+    pub fn concurrency(&self, hint: usize) -> usize {
+        self.max_concurrent_requests.unwrap_or(hint).max(1)
+    }
+
+    /// The delay to wait before sending the request again after its `attempt`-th failure.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt);
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor))
+    }
+
+    /// Whether some documents can fail to be embedded without failing the indexing.
+    pub fn tolerates_failures(&self) -> bool {
+        self.max_failed_documents > 0
+    }
+}
+
+/// Enforces the request policy of an embedder across all the extraction threads.
+pub struct RequestLimiter {
+    policy: RequestPolicy,
+    in_flight: Mutex<usize>,
+    released: Condvar,
+    next_request: Mutex<Option<Instant>>,
+}
+
+impl RequestLimiter {
+    pub fn new(policy: RequestPolicy) -> Self {
+        Self {
+            policy,
+            in_flight: Mutex::new(0),
+            released: Condvar::new(),
+            next_request: Mutex::new(None),
+        }
+    }
+
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    /// Blocks until `requests` requests can be sent without exceeding the policy.
+    ///
+    /// The requests are counted as in flight until the returned permit is dropped.
+    pub fn acquire(&self, requests: usize) -> RequestPermit<'_> {
+        let requests = match self.policy.max_concurrent_requests {
+            Some(max) => requests.min(max.max(1)),
+            None => requests,
+        };
+
+        if let Some(max) = self.policy.max_concurrent_requests {
+            let max = max.max(1);
+            let mut in_flight = self.in_flight.lock().unwrap();
+            while *in_flight + requests > max {
+                in_flight = self.released.wait(in_flight).unwrap();
+            }
+            *in_flight += requests;
+        }
+
+        if let Some(per_second) = self.policy.max_requests_per_second {
+            let interval = Duration::from_secs(1) / per_second.max(1);
+            let now = Instant::now();
+            // the slot is reserved under the lock, but waited for once the lock is released
+            let start = {
+                let mut next_request = self.next_request.lock().unwrap();
+                let start = match *next_request {
+                    Some(next) if next > now => next,
+                    _ => now,
+                };
+                *next_request = Some(start + interval * requests as u32);
+                start
+            };
+            if start > now {
+                std::thread::sleep(start - now);
+            }
+        }
+
+        RequestPermit { limiter: self, requests }
+    }
+}
+
+impl Default for RequestLimiter {
+    fn default() -> Self {
+        Self::new(RequestPolicy::default())
+    }
+}
+
+pub struct RequestPermit<'a> {
+    limiter: &'a RequestLimiter,
+    requests: usize,
+}
+
+impl Drop for RequestPermit<'_> {
+    fn drop(&mut self) {
+        if self.limiter.policy.max_concurrent_requests.is_some() {
+            *self.limiter.in_flight.lock().unwrap() -= self.requests;
+            self.limiter.released.notify_all();
+        }
+    }
+}
+
+/// The documents an embedder failed to embed during an indexing, within its request policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingFailures {
+    /// The number of documents indexed without the embeddings of this embedder.
+    pub failed_documents: u64,
+    /// The last error returned by the embedder.
+    pub last_error: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RequestPolicy { initial_backoff_ms: 100, ..Default::default() };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(u64::MAX));
+    }
+
+    #[test]
+    fn concurrency_defaults_to_the_hint() {
+        let policy = RequestPolicy::default();
+        assert_eq!(policy.concurrency(10), 10);
+        let policy = RequestPolicy { max_concurrent_requests: Some(2), ..Default::default() };
+        assert_eq!(policy.concurrency(10), 2);
+        let policy = RequestPolicy { max_concurrent_requests: Some(0), ..Default::default() };
+        assert_eq!(policy.concurrency(10), 1);
+    }
+
+    #[test]
+    fn requests_are_spaced_by_the_rate() {
+        let policy = RequestPolicy { max_requests_per_second: Some(20), ..Default::default() };
+        let limiter = RequestLimiter::new(policy);
+        let start = Instant::now();
+        drop(limiter.acquire(2));
+        drop(limiter.acquire(2));
+        drop(limiter.acquire(1));
+        // the 4 first requests must be spread over at least 200ms
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
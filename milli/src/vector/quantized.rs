@@ -0,0 +1,92 @@
+//! Binary quantization of the embeddings.
+//!
+//! Each dimension of a vector is reduced to its sign and packed in a single bit, dividing
+//! the size of the vectors by 32. The quantized vectors are compared with the Hamming distance
+//! to generate candidates, that are then re-scored with their full-precision vectors.
+
+use heed::RwTxn;
+use roaring::RoaringBitmap;
+
+use crate::{DocumentId, Index, Result};
+
+/// The number of candidates retrieved with the quantized vectors for each requested result,
+/// before re-scoring them with the full-precision vectors.
+pub const OVERSAMPLING: usize = 4;
+
+/// Reduces each dimension of the vector to its sign, packed in a bit.
+pub fn quantize(vector: &[f32]) -> Vec<u8> {
+    let mut quantized = vec![0u8; (vector.len() + 7) / 8];
+    for (i, value) in vector.iter().enumerate() {
+        if value.is_sign_positive() && *value != 0.0 {
+            quantized[i / 8] |= 1 << (i % 8);
+        }
+    }
+    quantized
+}
+
+/// The number of dimensions on which two quantized vectors disagree.
+pub fn hamming_distance(left: &[u8], right: &[u8]) -> u32 {
+    left.iter().zip(right).map(|(left, right)| (left ^ right).count_ones()).sum()
+}
+
+/// Rewrites the quantized vectors of the given documents from the vectors stored in arroy.
+pub(crate) fn requantize_documents<D: arroy::Distance>(
+    wtxn: &mut RwTxn,
+    index: &Index,
+    writers: &[arroy::Writer<D>],
+    embedder_index: u8,
+    docids: &RoaringBitmap,
+) -> Result<()> {
+    let writer_index = (embedder_index as u16) << 8;
+    for docid in docids {
+        for (k, writer) in writers.iter().enumerate() {
+            let key = (writer_index | (k as u16), docid);
+            match writer.item_vector(wtxn, docid)? {
+                Some(vector) => {
+                    index.vector_binary_quantized.put(wtxn, &key, &quantize(&vector))?
+                }
+                None => {
+                    // uses invariant: vectors are packed in the first writers.
+                    delete_from(wtxn, index, writer_index | (k as u16), docid)?;
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deletes the quantized vectors of the document starting at the given arroy store index.
+fn delete_from(
+    wtxn: &mut RwTxn,
+    index: &Index,
+    from_writer_index: u16,
+    docid: DocumentId,
+) -> Result<()> {
+    let last_writer_index = from_writer_index | u8::MAX as u16;
+    for writer_index in from_writer_index..=last_writer_index {
+        if !index.vector_binary_quantized.delete(wtxn, &(writer_index, docid))? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quantize_keeps_the_signs() {
+        let quantized = quantize(&[0.5, -0.2, 0.0, 3.0, -1.0, 0.1, 0.1, -0.1, 0.7]);
+        assert_eq!(quantized, vec![0b0110_1001, 0b0000_0001]);
+    }
+
+    #[test]
+    fn hamming_distance_counts_different_signs() {
+        let left = quantize(&[1.0, 1.0, -1.0, -1.0]);
+        let right = quantize(&[1.0, -1.0, 1.0, -1.0]);
+        assert_eq!(hamming_distance(&left, &left), 0);
+        assert_eq!(hamming_distance(&left, &right), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, InvalidHeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+
+use crate::prompt::error::{NewPromptError, RenderPromptError};
+use crate::prompt::Prompt;
+use crate::update::del_add::{into_del_add_obkv, DelAdd, DelAddOperation};
+use crate::FieldsIdsMap;
+
+/// The number of best hits sent to the reranker by default.
+pub const DEFAULT_RERANKER_TOP_N: usize = 50;
+/// The time after which the reranker is considered down by default.
+pub const DEFAULT_RERANKER_TIMEOUT_MS: u64 = 1000;
+
+/// A second-stage reranker reordering the best hits of a search, such as a cross-encoder model
+/// served by a [rerank endpoint](https://docs.cohere.com/reference/rerank).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerankerOptions {
+    /// The URL of the endpoint receiving the query and the documents to rerank.
+    pub url: String,
+    pub api_key: Option<String>,
+    /// The model sent to the endpoint, if it serves several models.
+    pub model: Option<String>,
+    /// The template rendering the documents sent to the endpoint, all the fields by default.
+    pub document_template: Option<String>,
+    /// The number of best hits reranked, the other hits keep their order.
+    #[serde(default = "default_top_n")]
+    pub top_n: usize,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_top_n() -> usize {
+    DEFAULT_RERANKER_TOP_N
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_RERANKER_TIMEOUT_MS
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    #[error("invalid document template: {0}")]
+    InvalidTemplate(NewPromptError),
+    #[error("invalid API key: {0}")]
+    InvalidApiKey(InvalidHeaderValue),
+    #[error("could not initialize the web client: {0}")]
+    Client(reqwest::Error),
+    #[error("could not render a document: {0}")]
+    Render(RenderPromptError),
+    #[error("could not reach the reranker: {0}")]
+    Network(reqwest::Error),
+    #[error("received unhandled HTTP status code {0} from the reranker")]
+    UnhandledStatusCode(u16),
+    #[error("unexpected response from the reranker: {0}")]
+    Unexpected(reqwest::Error),
+    #[error("the reranker returned the index {0} which doesn't match any of the documents")]
+    UnknownDocument(usize),
+}
+
+pub struct Reranker {
+    client: reqwest::Client,
+    prompt: Prompt,
+    options: RerankerOptions,
+}
+
+impl Reranker {
+    pub fn new(options: RerankerOptions) -> Result<Self, RerankError> {
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &options.api_key {
+            let bearer = HeaderValue::from_str(&format!("Bearer {api_key}"))
+                .map_err(RerankError::InvalidApiKey)?;
+            headers.insert(AUTHORIZATION, bearer);
+        }
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let client = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .timeout(Duration::from_millis(options.timeout_ms))
+            .build()
+            .map_err(RerankError::Client)?;
+
+        let prompt = match &options.document_template {
+            Some(template) => {
+                Prompt::new(template.clone()).map_err(RerankError::InvalidTemplate)?
+            }
+            None => Prompt::default(),
+        };
+
+        Ok(Self { client, prompt, options })
+    }
+
+    pub fn top_n(&self) -> usize {
+        self.options.top_n
+    }
+
+    /// Renders a document of the index with the document template of the reranker.
+    pub fn render(
+        &self,
+        document: obkv::KvReaderU16<'_>,
+        fields_ids_map: &FieldsIdsMap,
+    ) -> Result<String, RerankError> {
+        let mut buffer = Vec::new();
+        // the prompts render the del/add version of the documents being indexed
+        into_del_add_obkv(document, DelAddOperation::Addition, &mut buffer)
+            .expect("writing in memory can't fail");
+        self.prompt
+            .render(obkv::KvReaderU16::new(&buffer), DelAdd::Addition, fields_ids_map)
+            .map_err(RerankError::Render)
+    }
+
+    /// Returns the indexes of the documents, from the most relevant to the query to the least
+    /// relevant one, with their relevance score.
+    pub async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+    ) -> Result<Vec<(usize, f32)>, RerankError> {
+        let request = RerankRequest {
+            model: self.options.model.as_deref(),
+            query,
+            documents,
+            top_n: documents.len(),
+        };
+        let response = self
+            .client
+            .post(&self.options.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(RerankError::Network)?;
+
+        if !response.status().is_success() {
+            return Err(RerankError::UnhandledStatusCode(response.status().as_u16()));
+        }
+
+        let response: RerankResponse = response.json().await.map_err(RerankError::Unexpected)?;
+        let mut results = Vec::with_capacity(response.results.len());
+        for RerankResult { index, relevance_score } in response.results {
+            if index >= documents.len() {
+                return Err(RerankError::UnknownDocument(index));
+            }
+            results.push((index, relevance_score));
+        }
+        results.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+        Ok(results)
+    }
+}
+
+// rerank api structs
+
+#[derive(Debug, Serialize)]
+struct RerankRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    query: &'a str,
+    documents: &'a [String],
+    top_n: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RerankResponse {
+    results: Vec<RerankResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RerankResult {
+    index: usize,
+    #[serde(alias = "score")]
+    relevance_score: f32,
+}
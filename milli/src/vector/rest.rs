@@ -0,0 +1,388 @@
+use std::hash::{Hash, Hasher};
+
+use serde_json::Value;
+
+use super::error::{EmbedError, NewEmbedderError};
+use super::{block_on_dedicated_runtime, DistributionShift, Embedding, Embeddings};
+
+/// The placeholder replaced by the text to embed in the request template.
+pub const TEXT_PLACEHOLDER: &str = "{{text}}";
+/// The placeholder replaced by the array of the texts to embed in the request template.
+pub const TEXTS_PLACEHOLDER: &str = "{{texts}}";
+pub const DEFAULT_RESPONSE_PATH: &str = "data[*].embedding";
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+pub fn default_request() -> Value {
+    serde_json::json!({ "input": TEXTS_PLACEHOLDER })
+}
+
+#[derive(Debug)]
+pub struct Embedder {
+    client: reqwest::Client,
+    options: EmbedderOptions,
+    response_path: Vec<PathSegment>,
+    dimensions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct EmbedderOptions {
+    pub url: String,
+    pub api_key: Option<String>,
+    /// The dimensions of the embeddings, inferred from a test embedding when `None`.
+    pub dimensions: Option<usize>,
+    /// The body of the requests, containing either the `{{text}}` or the `{{texts}}` placeholder.
+    pub request: Value,
+    /// The path to the embeddings in the body of the responses, e.g. `data[*].embedding`.
+    pub response: String,
+    /// The maximum number of texts sent in a request using the `{{texts}}` placeholder.
+    pub batch_size: usize,
+}
+
+// `serde_json::Value` doesn't implement `Hash`, the request is hashed as its JSON representation
+impl Hash for EmbedderOptions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self { url, api_key, dimensions, request, response, batch_size } = self;
+        url.hash(state);
+        api_key.hash(state);
+        dimensions.hash(state);
+        request.to_string().hash(state);
+        response.hash(state);
+        batch_size.hash(state);
+    }
+}
+
+impl EmbedderOptions {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            api_key: None,
+            dimensions: None,
+            request: default_request(),
+            response: DEFAULT_RESPONSE_PATH.to_string(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+impl Embedder {
+    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(api_key) = &options.api_key {
+            headers.insert(
+                reqwest::header::AUTHORIZATION,
+                reqwest::header::HeaderValue::from_str(&format!("Bearer {}", api_key))
+                    .map_err(NewEmbedderError::rest_invalid_api_key_format)?,
+            );
+        }
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            reqwest::header::HeaderValue::from_static("application/json"),
+        );
+        let client = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .build()
+            .map_err(NewEmbedderError::rest_initialize_web_client)?;
+
+        let placeholders = count_placeholders(&options.request);
+        if placeholders != 1 {
+            return Err(NewEmbedderError::rest_invalid_request_template(format!(
+                "expected a single `{TEXT_PLACEHOLDER}` or `{TEXTS_PLACEHOLDER}` placeholder, found {placeholders}"
+            )));
+        }
+        if options.batch_size == 0 {
+            return Err(NewEmbedderError::rest_invalid_request_template(
+                "the batch size must be greater than 0".to_string(),
+            ));
+        }
+        let response_path =
+            parse_path(&options.response).map_err(NewEmbedderError::rest_invalid_response_path)?;
+
+        let mut embedder = Self { client, options, response_path, dimensions: 0 };
+        embedder.dimensions = match embedder.options.dimensions {
+            Some(dimensions) => dimensions,
+            None => embedder.infer_dimensions()?,
+        };
+        Ok(embedder)
+    }
+
+    /// Embeds a test text to find the dimensions of the model.
+    fn infer_dimensions(&self) -> Result<usize, NewEmbedderError> {
+        let mut embeddings = block_on_dedicated_runtime(self.try_embed(&["test".to_string()]))
+            .map_err(NewEmbedderError::initialize_runtime)?
+            .map_err(NewEmbedderError::could_not_determine_dimension)?;
+        Ok(embeddings.pop().map_or(0, |embedding| embedding.len()))
+    }
+
+    /// Whether the request template embeds several texts at once.
+    fn is_batched(&self) -> bool {
+        contains_placeholder(&self.options.request, TEXTS_PLACEHOLDER)
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Embeddings<f32>>, EmbedError> {
+        let batch_size = self.prompt_count_in_chunk_hint();
+        let batches = futures::future::try_join_all(
+            texts.chunks(batch_size).map(|batch| self.embed_batch(batch)),
+        )
+        .await?;
+        Ok(batches.into_iter().flatten().map(Embeddings::from_single_embedding).collect())
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Embedding>, EmbedError> {
+        for attempt in 0..3 {
+            match self.try_embed(texts).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(error) if error.fault == crate::error::FaultSource::Runtime => {
+                    log::warn!("Failed: {}", error);
+                    let duration = tokio::time::Duration::from_millis(10u64.pow(attempt + 1));
+                    log::warn!("Attempt #{}, retrying after {}ms.", attempt, duration.as_millis());
+                    tokio::time::sleep(duration).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.try_embed(texts).await
+    }
+
+    async fn try_embed(&self, texts: &[String]) -> Result<Vec<Embedding>, EmbedError> {
+        for text in texts {
+            log::trace!("Received prompt: {}", text)
+        }
+        let request = render_request(&self.options.request, texts);
+        let response = self
+            .client
+            .post(&self.options.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(EmbedError::rest_network)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(if status.is_server_error() || status.as_u16() == 429 {
+                EmbedError::rest_server_error(status.as_u16(), body)
+            } else {
+                EmbedError::rest_unhandled_status_code(status.as_u16(), body)
+            });
+        }
+
+        let response: Value = response.json().await.map_err(EmbedError::rest_unexpected)?;
+        let embeddings = extract_embeddings(&response, &self.response_path)
+            .map_err(EmbedError::rest_response_embeddings)?;
+        if embeddings.len() != texts.len() {
+            return Err(EmbedError::rest_response_embeddings(format!(
+                "expected {} embeddings, found {}",
+                texts.len(),
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
+    pub async fn embed_chunks(
+        &self,
+        text_chunks: Vec<Vec<String>>,
+    ) -> Result<Vec<Vec<Embeddings<f32>>>, EmbedError> {
+        futures::future::try_join_all(text_chunks.into_iter().map(|prompts| self.embed(prompts)))
+            .await
+    }
+
+    pub fn chunk_count_hint(&self) -> usize {
+        10
+    }
+
+    pub fn prompt_count_in_chunk_hint(&self) -> usize {
+        if self.is_batched() {
+            self.options.batch_size
+        } else {
+            1
+        }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn distribution(&self) -> Option<DistributionShift> {
+        None
+    }
+}
+
+// request templating
+
+fn count_placeholders(template: &Value) -> usize {
+    match template {
+        Value::String(s) if s == TEXT_PLACEHOLDER || s == TEXTS_PLACEHOLDER => 1,
+        Value::Array(values) => values.iter().map(count_placeholders).sum(),
+        Value::Object(map) => map.values().map(count_placeholders).sum(),
+        _ => 0,
+    }
+}
+
+fn contains_placeholder(template: &Value, placeholder: &str) -> bool {
+    match template {
+        Value::String(s) => s == placeholder,
+        Value::Array(values) => values.iter().any(|value| contains_placeholder(value, placeholder)),
+        Value::Object(map) => map.values().any(|value| contains_placeholder(value, placeholder)),
+        _ => false,
+    }
+}
+
+/// Replaces the placeholder of the template by the texts, the `{{text}}` placeholder
+/// being only used with a single text.
+fn render_request(template: &Value, texts: &[String]) -> Value {
+    match template {
+        Value::String(s) if s == TEXT_PLACEHOLDER => {
+            Value::String(texts.first().cloned().unwrap_or_default())
+        }
+        Value::String(s) if s == TEXTS_PLACEHOLDER => {
+            Value::Array(texts.iter().cloned().map(Value::String).collect())
+        }
+        Value::Array(values) => {
+            Value::Array(values.iter().map(|value| render_request(value, texts)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter().map(|(key, value)| (key.clone(), render_request(value, texts))).collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+// response extraction
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    /// All the values of an array, written `[*]`.
+    All,
+}
+
+/// Parses a path such as `data[*].embedding` or `embeddings`, the empty path
+/// designating the whole response.
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        let (key, mut indexes) = match part.find('[') {
+            Some(position) => part.split_at(position),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        }
+        while !indexes.is_empty() {
+            let end = indexes
+                .find(']')
+                .filter(|_| indexes.starts_with('['))
+                .ok_or_else(|| format!("unclosed bracket in `{part}`"))?;
+            segments.push(match &indexes[1..end] {
+                "*" => PathSegment::All,
+                index => PathSegment::Index(
+                    index.parse().map_err(|_| format!("invalid index `{index}` in `{part}`"))?,
+                ),
+            });
+            indexes = &indexes[end + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+/// Returns the embeddings found at the end of the path, each value being either
+/// an embedding or an array of embeddings.
+fn extract_embeddings(response: &Value, path: &[PathSegment]) -> Result<Vec<Embedding>, String> {
+    let mut values = vec![response];
+    for segment in path {
+        let mut next = Vec::with_capacity(values.len());
+        for value in values {
+            match (segment, value) {
+                (PathSegment::Key(key), Value::Object(map)) => match map.get(key) {
+                    Some(value) => next.push(value),
+                    None => return Err(format!("missing key `{key}`")),
+                },
+                (PathSegment::Index(index), Value::Array(array)) => match array.get(*index) {
+                    Some(value) => next.push(value),
+                    None => return Err(format!("missing index `{index}`")),
+                },
+                (PathSegment::All, Value::Array(array)) => next.extend(array),
+                (segment, value) => {
+                    return Err(format!("cannot follow `{segment:?}` in `{value}`"));
+                }
+            }
+        }
+        values = next;
+    }
+
+    let mut embeddings = Vec::with_capacity(values.len());
+    for value in values {
+        match value {
+            Value::Array(array) if array.iter().all(Value::is_array) => {
+                for embedding in array {
+                    embeddings.push(parse_embedding(embedding)?);
+                }
+            }
+            value => embeddings.push(parse_embedding(value)?),
+        }
+    }
+    Ok(embeddings)
+}
+
+fn parse_embedding(value: &Value) -> Result<Embedding, String> {
+    let not_an_embedding = || format!("expected an array of numbers, found `{value}`");
+    let array = value.as_array().ok_or_else(not_an_embedding)?;
+    array.iter().map(|x| x.as_f64().map(|x| x as f32).ok_or_else(not_an_embedding)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_templates() {
+        let texts = ["a".to_string(), "b".to_string()];
+        let template = json!({ "model": "embed", "input": { "texts": "{{texts}}" } });
+        assert_eq!(
+            render_request(&template, &texts),
+            json!({ "model": "embed", "input": { "texts": ["a", "b"] } })
+        );
+        let template = json!({ "prompt": "{{text}}", "options": ["{{texts}}"] });
+        assert_eq!(count_placeholders(&template), 2);
+        let template = json!({ "prompt": "{{text}}" });
+        assert_eq!(render_request(&template, &texts[..1]), json!({ "prompt": "a" }));
+    }
+
+    #[test]
+    fn extract_with_paths() {
+        let openai = json!({ "data": [{ "embedding": [0.1, 0.2] }, { "embedding": [0.3, 0.4] }] });
+        let path = parse_path("data[*].embedding").unwrap();
+        assert_eq!(
+            extract_embeddings(&openai, &path).unwrap(),
+            vec![vec![0.1, 0.2], vec![0.3, 0.4]]
+        );
+
+        let cohere = json!({ "embeddings": [[0.1, 0.2], [0.3, 0.4]] });
+        let path = parse_path("embeddings").unwrap();
+        assert_eq!(
+            extract_embeddings(&cohere, &path).unwrap(),
+            vec![vec![0.1, 0.2], vec![0.3, 0.4]]
+        );
+
+        let nested = json!({ "result": { "vectors": [[[0.1, 0.2]]] } });
+        let path = parse_path("result.vectors[0]").unwrap();
+        assert_eq!(extract_embeddings(&nested, &path).unwrap(), vec![vec![0.1, 0.2]]);
+
+        assert_eq!(
+            parse_path("data[*].embedding").unwrap(),
+            vec![
+                PathSegment::Key("data".to_string()),
+                PathSegment::All,
+                PathSegment::Key("embedding".to_string())
+            ]
+        );
+        assert!(parse_path("data[*.embedding").is_err());
+        assert!(parse_path("data[first]").is_err());
+        assert!(extract_embeddings(&cohere, &parse_path("data").unwrap()).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::prompt::PromptData;
 use crate::update::Setting;
-use crate::vector::EmbeddingConfig;
+use crate::vector::{Distance, EmbeddingConfig, RequestPolicy};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
@@ -15,13 +15,31 @@ pub struct EmbeddingSettings {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
     pub document_template: Setting<PromptSettings>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub binary_quantized: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub distance: Setting<Distance>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub request_policy: Setting<RequestPolicySettings>,
 }
 
 impl EmbeddingSettings {
     pub fn apply(&mut self, new: Self) {
-        let EmbeddingSettings { embedder_options, document_template: prompt } = new;
+        let EmbeddingSettings {
+            embedder_options,
+            document_template: prompt,
+            binary_quantized,
+            distance,
+            request_policy,
+        } = new;
         self.embedder_options.apply(embedder_options);
         self.document_template.apply(prompt);
+        self.binary_quantized.apply(binary_quantized);
+        self.distance.apply(distance);
+        self.request_policy.apply(request_policy);
     }
 }
 
@@ -30,6 +48,9 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
         Self {
             embedder_options: Setting::Set(value.embedder_options.into()),
             document_template: Setting::Set(value.prompt.into()),
+            binary_quantized: Setting::Set(value.binary_quantized),
+            distance: Setting::Set(value.distance),
+            request_policy: Setting::Set(value.request_policy.into()),
         }
     }
 }
@@ -37,13 +58,28 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
 impl From<EmbeddingSettings> for EmbeddingConfig {
     fn from(value: EmbeddingSettings) -> Self {
         let mut this = Self::default();
-        let EmbeddingSettings { embedder_options, document_template: prompt } = value;
+        let EmbeddingSettings {
+            embedder_options,
+            document_template: prompt,
+            binary_quantized,
+            distance,
+            request_policy,
+        } = value;
         if let Some(embedder_options) = embedder_options.set() {
             this.embedder_options = embedder_options.into();
         }
         if let Some(prompt) = prompt.set() {
             this.prompt = prompt.into();
         }
+        if let Some(binary_quantized) = binary_quantized.set() {
+            this.binary_quantized = binary_quantized;
+        }
+        if let Some(distance) = distance.set() {
+            this.distance = distance;
+        }
+        if let Some(request_policy) = request_policy.set() {
+            this.request_policy = request_policy.into();
+        }
         this
     }
 }
@@ -81,11 +117,96 @@ impl From<PromptSettings> for PromptData {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RequestPolicySettings {
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_concurrent_requests: Setting<usize>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_requests_per_second: Setting<u32>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_retries: Setting<u32>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub initial_backoff_ms: Setting<u64>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub max_failed_documents: Setting<u64>,
+}
+
+impl RequestPolicySettings {
+    pub fn apply(&mut self, new: Self) {
+        let RequestPolicySettings {
+            max_concurrent_requests,
+            max_requests_per_second,
+            max_retries,
+            initial_backoff_ms,
+            max_failed_documents,
+        } = new;
+        self.max_concurrent_requests.apply(max_concurrent_requests);
+        self.max_requests_per_second.apply(max_requests_per_second);
+        self.max_retries.apply(max_retries);
+        self.initial_backoff_ms.apply(initial_backoff_ms);
+        self.max_failed_documents.apply(max_failed_documents);
+    }
+}
+
+impl From<RequestPolicy> for RequestPolicySettings {
+    fn from(value: RequestPolicy) -> Self {
+        let RequestPolicy {
+            max_concurrent_requests,
+            max_requests_per_second,
+            max_retries,
+            initial_backoff_ms,
+            max_failed_documents,
+        } = value;
+        Self {
+            max_concurrent_requests: max_concurrent_requests
+                .map(Setting::Set)
+                .unwrap_or(Setting::Reset),
+            max_requests_per_second: max_requests_per_second
+                .map(Setting::Set)
+                .unwrap_or(Setting::Reset),
+            max_retries: Setting::Set(max_retries),
+            initial_backoff_ms: Setting::Set(initial_backoff_ms),
+            max_failed_documents: Setting::Set(max_failed_documents),
+        }
+    }
+}
+
+impl From<RequestPolicySettings> for RequestPolicy {
+    fn from(value: RequestPolicySettings) -> Self {
+        let RequestPolicySettings {
+            max_concurrent_requests,
+            max_requests_per_second,
+            max_retries,
+            initial_backoff_ms,
+            max_failed_documents,
+        } = value;
+        let default = Self::default();
+        Self {
+            max_concurrent_requests: max_concurrent_requests.set(),
+            max_requests_per_second: max_requests_per_second.set(),
+            max_retries: max_retries.set().unwrap_or(default.max_retries),
+            initial_backoff_ms: initial_backoff_ms.set().unwrap_or(default.initial_backoff_ms),
+            max_failed_documents: max_failed_documents
+                .set()
+                .unwrap_or(default.max_failed_documents),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub enum EmbedderSettings {
     HuggingFace(Setting<HfEmbedderSettings>),
     OpenAi(Setting<OpenAiEmbedderSettings>),
+    Ollama(Setting<OllamaEmbedderSettings>),
+    Rest(RestEmbedderSettings),
     UserProvided(UserProvidedSettings),
 }
 
@@ -127,6 +248,18 @@ where
                             location.push_key(&k),
                         )?,
                     ))),
+                    "ollama" => Ok(EmbedderSettings::Ollama(Setting::Set(
+                        OllamaEmbedderSettings::deserialize_from_value(
+                            v.into_value(),
+                            location.push_key(&k),
+                        )?,
+                    ))),
+                    "rest" => {
+                        Ok(EmbedderSettings::Rest(RestEmbedderSettings::deserialize_from_value(
+                            v.into_value(),
+                            location.push_key(&k),
+                        )?))
+                    }
                     "userProvided" => Ok(EmbedderSettings::UserProvided(
                         UserProvidedSettings::deserialize_from_value(
                             v.into_value(),
@@ -137,7 +270,7 @@ where
                         None,
                         deserr::ErrorKind::UnknownKey {
                             key: other,
-                            accepted: &["huggingFace", "openAi", "userProvided"],
+                            accepted: &["huggingFace", "openAi", "ollama", "rest", "userProvided"],
                         },
                         location,
                     ))),
@@ -170,6 +303,10 @@ impl From<crate::vector::EmbedderOptions> for EmbedderSettings {
             crate::vector::EmbedderOptions::OpenAi(openai) => {
                 Self::OpenAi(Setting::Set(openai.into()))
             }
+            crate::vector::EmbedderOptions::Ollama(ollama) => {
+                Self::Ollama(Setting::Set(ollama.into()))
+            }
+            crate::vector::EmbedderOptions::Rest(rest) => Self::Rest(rest.into()),
             crate::vector::EmbedderOptions::UserProvided(user_provided) => {
                 Self::UserProvided(user_provided.into())
             }
@@ -186,6 +323,11 @@ impl From<EmbedderSettings> for crate::vector::EmbedderOptions {
             EmbedderSettings::OpenAi(_setting) => {
                 Self::OpenAi(crate::vector::openai::EmbedderOptions::with_default_model(None))
             }
+            EmbedderSettings::Ollama(Setting::Set(ollama)) => Self::Ollama(ollama.into()),
+            EmbedderSettings::Ollama(_setting) => {
+                Self::Ollama(crate::vector::ollama::EmbedderOptions::with_default_model(None))
+            }
+            EmbedderSettings::Rest(rest) => Self::Rest(rest.into()),
             EmbedderSettings::UserProvided(user_provided) => {
                 Self::UserProvided(user_provided.into())
             }
@@ -272,6 +414,108 @@ impl From<OpenAiEmbedderSettings> for crate::vector::openai::EmbedderOptions {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct OllamaEmbedderSettings {
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub url: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set", rename = "model")]
+    #[deserr(default, rename = "model")]
+    pub embedding_model: Setting<String>,
+}
+
+impl OllamaEmbedderSettings {
+    pub fn apply(&mut self, new: Self) {
+        let Self { url, embedding_model } = new;
+        self.url.apply(url);
+        self.embedding_model.apply(embedding_model);
+    }
+}
+
+impl From<crate::vector::ollama::EmbedderOptions> for OllamaEmbedderSettings {
+    fn from(value: crate::vector::ollama::EmbedderOptions) -> Self {
+        Self {
+            url: value.url.map(Setting::Set).unwrap_or(Setting::Reset),
+            embedding_model: Setting::Set(value.embedding_model),
+        }
+    }
+}
+
+impl From<OllamaEmbedderSettings> for crate::vector::ollama::EmbedderOptions {
+    fn from(value: OllamaEmbedderSettings) -> Self {
+        let OllamaEmbedderSettings { url, embedding_model } = value;
+        match embedding_model.set() {
+            Some(embedding_model) => Self::with_embedding_model(url.set(), embedding_model),
+            None => Self::with_default_model(url.set()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct RestEmbedderSettings {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub api_key: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub dimensions: Setting<usize>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub request: Setting<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub response: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub batch_size: Setting<usize>,
+}
+
+impl From<crate::vector::rest::EmbedderOptions> for RestEmbedderSettings {
+    fn from(value: crate::vector::rest::EmbedderOptions) -> Self {
+        let crate::vector::rest::EmbedderOptions {
+            url,
+            api_key,
+            dimensions,
+            request,
+            response,
+            batch_size,
+        } = value;
+        Self {
+            url,
+            api_key: api_key.map(Setting::Set).unwrap_or(Setting::Reset),
+            dimensions: dimensions.map(Setting::Set).unwrap_or(Setting::Reset),
+            request: Setting::Set(request),
+            response: Setting::Set(response),
+            batch_size: Setting::Set(batch_size),
+        }
+    }
+}
+
+impl From<RestEmbedderSettings> for crate::vector::rest::EmbedderOptions {
+    fn from(value: RestEmbedderSettings) -> Self {
+        let RestEmbedderSettings { url, api_key, dimensions, request, response, batch_size } =
+            value;
+        let mut this = Self::new(url);
+        this.api_key = api_key.set();
+        this.dimensions = dimensions.set();
+        if let Some(request) = request.set() {
+            this.request = request;
+        }
+        if let Some(response) = response.set() {
+            this.response = response;
+        }
+        if let Some(batch_size) = batch_size.set() {
+            this.batch_size = batch_size;
+        }
+        this
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 #[deserr(rename_all = camelCase, deny_unknown_fields)]